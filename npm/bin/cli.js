@@ -1,11 +1,10 @@
 #!/usr/bin/env node
 
-const { spawn, exec } = require('child_process');
+const { spawn } = require('child_process');
 const path = require('path');
 const fs = require('fs');
 
 const PORT = 3008;
-const URL = `http://localhost:${PORT}`;
 
 // Determine binary name based on platform
 function getBinaryName() {
@@ -29,34 +28,13 @@ function findBinary() {
   return binaryPath;
 }
 
-// Open browser using platform-specific command
-function openBrowser(url) {
-  const platform = process.platform;
-  let command;
-
-  if (platform === 'darwin') {
-    command = `open "${url}"`;
-  } else if (platform === 'win32') {
-    command = `start "" "${url}"`;
-  } else {
-    // Linux and others
-    command = `xdg-open "${url}"`;
-  }
-
-  exec(command, (error) => {
-    if (error) {
-      console.log(`Could not open browser automatically. Please navigate to: ${url}`);
-    }
-  });
-}
-
 function main() {
   const binaryPath = findBinary();
 
   console.log('Starting Beads Kanban UI server...');
 
   // Spawn the server process
-  const serverProcess = spawn(binaryPath, [], {
+  const serverProcess = spawn(binaryPath, process.argv.slice(2), {
     stdio: ['ignore', 'pipe', 'pipe'],
     env: { ...process.env, PORT: PORT.toString() }
   });
@@ -85,11 +63,7 @@ function main() {
     process.exit(code || 0);
   });
 
-  // Auto-open browser after 1 second delay
-  setTimeout(() => {
-    console.log(`Opening browser at ${URL}`);
-    openBrowser(URL);
-  }, 1000);
+  // The server opens the browser itself, with the access token in the URL
 
   // Handle SIGINT (Ctrl+C) to gracefully shutdown
   process.on('SIGINT', () => {
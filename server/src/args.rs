@@ -0,0 +1,519 @@
+//! Command-line options.
+//!
+//! Every option can also be set in a YAML (or JSON) file passed with
+//! `--config`:
+//!
+//! ```yaml
+//! port: 3008
+//! host: 127.0.0.1
+//! open: false
+//! project: ~/code/my-app     # or several with `projects: [...]`
+//! all_projects: false
+//! log_level: debug
+//! log_format: json
+//! watch_mode: poll           # auto, native, or poll
+//! api_only: false
+//! assets_dir: ~/code/beads-kanban-ui/out
+//! tls_cert: /etc/beads/cert.pem
+//! tls_key: /etc/beads/key.pem
+//! token: a-long-random-string
+//! oidc:
+//!   issuer: https://accounts.example.com
+//!   client_id: beads-kanban
+//!   redirect_url: https://beads.example.com/auth/callback
+//! ```
+//!
+//! Flags on the command line (and `PORT` in the environment) take
+//! precedence over the file.
+
+use clap::Parser;
+use directories::UserDirs;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tracing::Level;
+
+use crate::auth;
+use crate::db::{CreateProjectInput, Database, Project, UpdateProjectInput};
+use crate::logging::LogFormat;
+use crate::oidc::OidcSettings;
+use crate::routes::watch::WatchMode;
+use crate::tls::TlsSettings;
+
+/// Port used when none is configured.
+const DEFAULT_PORT: u16 = 3008;
+
+/// Address bound when none is configured. The API can run git and `bd`, so
+/// only this machine can reach it unless another address is chosen.
+const DEFAULT_HOST: &str = "127.0.0.1";
+
+/// Beads Kanban UI server.
+#[derive(Debug, Parser)]
+#[command(name = "beads-server", version = crate::update::VERSION, about)]
+pub struct Cli {
+    /// Port to listen on [default: 3008]
+    #[arg(long, env = "PORT")]
+    pub port: Option<u16>,
+    /// Address to bind to, e.g. 0.0.0.0 to serve the network (requires
+    /// authentication) [default: 127.0.0.1]
+    #[arg(long)]
+    pub host: Option<String>,
+    /// Don't open the browser on launch
+    #[arg(long)]
+    pub no_open: bool,
+    /// Project to register (if it isn't already) and open on launch;
+    /// repeat to keep several active at once
+    #[arg(long, value_name = "PATH")]
+    pub project: Vec<PathBuf>,
+    /// Keep every registered project active, along with any --project
+    #[arg(long)]
+    pub all_projects: bool,
+    /// Log level: error, warn, info, debug, or trace [default: RUST_LOG,
+    /// else info]
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<Level>,
+    /// Log format: text, or json for log shippers [default: text]
+    #[arg(long, value_name = "FORMAT")]
+    pub log_format: Option<LogFormat>,
+    /// How file changes are detected: auto (native events, polling a
+    /// project once they miss a change), native, or poll for network
+    /// filesystems [default: auto]
+    #[arg(long, value_name = "MODE")]
+    pub watch_mode: Option<WatchMode>,
+    /// Serve only the API, not the embedded frontend (implies --no-open)
+    #[arg(long)]
+    pub api_only: bool,
+    /// Serve the frontend from this directory (e.g. ../out) ahead of the
+    /// embedded build, picking up rebuilds without restarting
+    #[arg(long, value_name = "PATH", conflicts_with = "api_only")]
+    pub assets_dir: Option<PathBuf>,
+    /// Serve HTTPS with this PEM certificate (chain); requires --tls-key
+    #[arg(long, value_name = "PATH", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+    /// Private key (PEM) for --tls-cert
+    #[arg(long, value_name = "PATH", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+    /// Serve HTTPS with a generated self-signed certificate
+    #[arg(long, conflicts_with = "tls_cert")]
+    pub tls_self_signed: bool,
+    /// Access token for the API [default: generated on first start and kept
+    /// across restarts, unless OIDC login is configured]
+    #[arg(long, env = "BEADS_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+    /// Serve the API without an access token
+    #[arg(long, conflicts_with = "token")]
+    pub no_auth: bool,
+    /// YAML file with defaults for the options above
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Replace this binary with the latest GitHub release, then exit
+    #[arg(long)]
+    pub update: bool,
+}
+
+/// Options read from the `--config` file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FileConfig {
+    port: Option<u16>,
+    host: Option<String>,
+    open: Option<bool>,
+    project: Option<String>,
+    projects: Option<Vec<String>>,
+    all_projects: Option<bool>,
+    log_level: Option<String>,
+    log_format: Option<LogFormat>,
+    watch_mode: Option<WatchMode>,
+    api_only: Option<bool>,
+    assets_dir: Option<String>,
+    tls_cert: Option<String>,
+    tls_key: Option<String>,
+    tls_self_signed: Option<bool>,
+    token: Option<String>,
+    auth: Option<bool>,
+    oidc: Option<OidcSettings>,
+}
+
+/// Server settings after merging the command line, the config file, and the
+/// defaults.
+#[derive(Debug, PartialEq)]
+pub struct Settings {
+    pub port: u16,
+    pub host: String,
+    /// Whether to open the browser on launch
+    pub open: bool,
+    /// Projects to register and keep active; the board opens on the
+    /// project if there is just one
+    pub projects: Vec<PathBuf>,
+    /// Keep every registered project active
+    pub all_projects: bool,
+    /// Log level; `None` leaves it to `RUST_LOG`
+    pub log_level: Option<Level>,
+    pub log_format: LogFormat,
+    pub watch_mode: WatchMode,
+    /// Whether to serve the embedded frontend
+    pub frontend: bool,
+    /// Directory to serve the frontend from ahead of the embedded build
+    pub assets_dir: Option<PathBuf>,
+    /// Serve HTTPS instead of HTTP
+    pub tls: Option<TlsSettings>,
+    /// Token accepted on `/api` requests
+    pub token: Option<String>,
+    /// Whether `token` was generated rather than configured, so the one
+    /// kept from an earlier start should be used instead
+    pub token_generated: bool,
+    /// OpenID Connect login; without it or a token, authentication is off
+    pub oidc: Option<OidcSettings>,
+}
+
+impl Cli {
+    /// Merges the command line with the config file, if any.
+    pub fn settings(&self) -> Result<Settings, String> {
+        let file = match &self.config {
+            Some(path) => read_config(path)?,
+            None => FileConfig::default(),
+        };
+
+        let log_level = match (self.log_level, &file.log_level) {
+            (Some(level), _) => Some(level),
+            (None, Some(level)) => Some(
+                level
+                    .parse()
+                    .map_err(|_| format!("Invalid log level in config: '{}'", level))?,
+            ),
+            (None, None) => None,
+        };
+
+        // Certificate files on the command line replace the file's TLS
+        // settings as a whole
+        let tls = if let Some((cert, key)) = self.tls_cert.clone().zip(self.tls_key.clone()) {
+            Some(TlsSettings::Files { cert, key })
+        } else if self.tls_self_signed {
+            Some(TlsSettings::SelfSigned)
+        } else {
+            match (&file.tls_cert, &file.tls_key, file.tls_self_signed) {
+                (Some(_), Some(_), Some(true)) => {
+                    return Err(
+                        "tls_self_signed cannot be combined with tls_cert and tls_key".to_string(),
+                    )
+                }
+                (Some(cert), Some(key), _) => Some(TlsSettings::Files {
+                    cert: expand_home(cert),
+                    key: expand_home(key),
+                }),
+                (Some(_), None, _) | (None, Some(_), _) => {
+                    return Err("tls_cert and tls_key must be set together".to_string())
+                }
+                (None, None, Some(true)) => Some(TlsSettings::SelfSigned),
+                (None, None, _) => None,
+            }
+        };
+
+        if let Some(oidc) = &file.oidc {
+            oidc.validate()?;
+        }
+
+        // Signing in replaces the generated token; a configured one still
+        // works alongside it, e.g. for scripts
+        let (token, token_generated) =
+            if self.no_auth || (self.token.is_none() && file.auth == Some(false)) {
+                (None, false)
+            } else {
+                let token = self.token.clone().or(file.token);
+                if let Some(token) = &token {
+                    auth::validate_token(token)?;
+                }
+                match token {
+                    Some(token) => (Some(token), false),
+                    None if file.oidc.is_some() => (None, false),
+                    None => (Some(auth::generate_token()), true),
+                }
+            };
+
+        let host = self
+            .host
+            .clone()
+            .or(file.host)
+            .unwrap_or_else(|| DEFAULT_HOST.to_string());
+        if !is_loopback(&host) && token.is_none() && file.oidc.is_none() {
+            return Err(format!(
+                "Serving on {} exposes the API to the network, so authentication can't be turned off",
+                host
+            ));
+        }
+
+        // Builds without the `frontend` feature have nothing to serve
+        let frontend =
+            cfg!(feature = "frontend") && !self.api_only && !file.api_only.unwrap_or(false);
+        let assets_dir = self
+            .assets_dir
+            .clone()
+            .or_else(|| file.assets_dir.as_deref().map(expand_home));
+        if let Some(dir) = &assets_dir {
+            if !cfg!(feature = "frontend") {
+                return Err("--assets-dir needs a build with the frontend feature".to_string());
+            }
+            if !dir.is_dir() {
+                return Err(format!("Assets directory not found: {}", dir.display()));
+            }
+        }
+
+        // Projects on the command line replace the file's
+        let projects = if self.project.is_empty() {
+            file.project
+                .iter()
+                .chain(file.projects.iter().flatten())
+                .map(|path| expand_home(path))
+                .collect()
+        } else {
+            self.project.clone()
+        };
+
+        Ok(Settings {
+            port: self.port.or(file.port).unwrap_or(DEFAULT_PORT),
+            host,
+            open: frontend && !self.no_open && file.open.unwrap_or(true),
+            projects,
+            all_projects: self.all_projects || file.all_projects.unwrap_or(false),
+            log_level,
+            log_format: self.log_format.or(file.log_format).unwrap_or_default(),
+            watch_mode: self.watch_mode.or(file.watch_mode).unwrap_or_default(),
+            frontend,
+            assets_dir: assets_dir.filter(|_| frontend),
+            tls,
+            token,
+            token_generated,
+            oidc: file.oidc,
+        })
+    }
+}
+
+/// Whether `host` only accepts connections from this machine.
+fn is_loopback(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .trim_matches(|c| c == '[' || c == ']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Reads the `--config` file.
+fn read_config(path: &Path) -> Result<FileConfig, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_yaml::from_str(&contents).map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+/// Expands a leading `~/` to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), UserDirs::new()) {
+        (Some(rest), Some(dirs)) => dirs.home_dir().join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Returns the registered project at `path`, registering it (named after
+/// its directory) if needed. Either way it becomes the most recently
+/// opened project.
+pub fn register_project(db: &Database, path: &Path) -> Result<Project, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("Invalid project path {}: {}", path.display(), e))?;
+    if !path.is_dir() {
+        return Err(format!("Project path is not a directory: {}", path.display()));
+    }
+
+    let projects = db.get_projects().map_err(|e| e.to_string())?;
+    let existing = projects
+        .into_iter()
+        .find(|p| Path::new(&p.path).canonicalize().ok().as_deref() == Some(path.as_path()));
+    match existing {
+        Some(project) => db
+            .update_project(
+                &project.id,
+                UpdateProjectInput {
+                    name: None,
+                    path: None,
+                },
+            )
+            .map_err(|e| e.to_string()),
+        None => {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string());
+            db.create_project(CreateProjectInput {
+                name,
+                path: path.to_string_lossy().to_string(),
+            })
+            .map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "port: 4000\nhost: 127.0.0.1\nopen: false\nlog_level: debug\nlog_format: json\nwatch_mode: poll\n",
+        )
+        .unwrap();
+
+        let cli = Cli::try_parse_from(["beads-server", "--no-open"]).unwrap();
+        let settings = cli.settings().unwrap();
+        assert_eq!(settings.host, DEFAULT_HOST);
+        assert!(!settings.open);
+        assert_eq!(settings.log_level, None);
+        assert_eq!(settings.watch_mode, WatchMode::Auto);
+        assert!(settings.projects.is_empty() && !settings.all_projects);
+
+        let cli =
+            Cli::try_parse_from(["beads-server", "--project", "a", "--project", "b"]).unwrap();
+        assert_eq!(
+            cli.settings().unwrap().projects,
+            [PathBuf::from("a"), PathBuf::from("b")]
+        );
+
+        let cli = Cli::try_parse_from(["beads-server", "--api-only"]).unwrap();
+        let settings = cli.settings().unwrap();
+        assert!(!settings.frontend);
+        assert!(!settings.open);
+
+        let dir_arg = dir.path().to_string_lossy().to_string();
+        let cli = Cli::try_parse_from(["beads-server", "--assets-dir", &dir_arg]).unwrap();
+        if cfg!(feature = "frontend") {
+            assert_eq!(cli.settings().unwrap().assets_dir.as_deref(), Some(dir.path()));
+        }
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+        let cli = Cli::try_parse_from(["beads-server", "--assets-dir", &missing]).unwrap();
+        assert!(cli.settings().is_err());
+
+        let config_arg = config.to_string_lossy().to_string();
+        let cli = Cli::try_parse_from([
+            "beads-server",
+            "--config",
+            &config_arg,
+            "--port",
+            "5000",
+            "--log-level",
+            "warn",
+        ])
+        .unwrap();
+        let settings = cli.settings().unwrap();
+        assert_eq!(settings.port, 5000);
+        assert_eq!(settings.host, "127.0.0.1");
+        assert!(!settings.open);
+        assert_eq!(settings.log_level, Some(Level::WARN));
+        assert_eq!(settings.log_format, LogFormat::Json);
+        assert_eq!(settings.watch_mode, WatchMode::Poll);
+
+        assert_eq!(settings.tls, None);
+
+        std::fs::write(&config, "tls_cert: cert.pem\ntls_key: key.pem\n").unwrap();
+        assert_eq!(
+            cli.settings().unwrap().tls,
+            Some(TlsSettings::Files {
+                cert: PathBuf::from("cert.pem"),
+                key: PathBuf::from("key.pem"),
+            })
+        );
+        let cli = Cli::try_parse_from(["beads-server", "--config", &config_arg, "--tls-self-signed"])
+            .unwrap();
+        assert_eq!(cli.settings().unwrap().tls, Some(TlsSettings::SelfSigned));
+        std::fs::write(&config, "tls_cert: cert.pem\n").unwrap();
+        assert!(cli.settings().is_ok());
+        let cli = Cli::try_parse_from(["beads-server", "--config", &config_arg]).unwrap();
+        assert!(cli.settings().is_err());
+        assert!(Cli::try_parse_from(["beads-server", "--tls-cert", "cert.pem"]).is_err());
+
+        std::fs::write(&config, "auth: false\n").unwrap();
+        assert_eq!(cli.settings().unwrap().token, None);
+
+        std::fs::write(&config, "project: a\nprojects: [b, c]\nall_projects: true\n").unwrap();
+        let settings = cli.settings().unwrap();
+        assert_eq!(settings.projects.len(), 3);
+        assert!(settings.all_projects);
+
+        std::fs::write(&config, "prot: 4000\n").unwrap();
+        assert!(cli.settings().is_err());
+        assert!(Cli::try_parse_from(["beads-server", "--log-level", "loud"]).is_err());
+    }
+
+    #[test]
+    fn test_token_settings() {
+        let token = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["beads-server"], args].concat()).unwrap();
+            cli.settings().map(|s| s.token)
+        };
+        assert_eq!(token(&[]).unwrap().map(|t| t.len()), Some(32));
+        let generated = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["beads-server"], args].concat()).unwrap();
+            cli.settings().unwrap().token_generated
+        };
+        assert!(generated(&[]));
+        assert!(!generated(&["--token", "0123456789abcdef"]));
+        assert!(!generated(&["--no-auth"]));
+        assert_eq!(
+            token(&["--token", "0123456789abcdef"]).unwrap().as_deref(),
+            Some("0123456789abcdef")
+        );
+        assert!(token(&["--token", "short"]).is_err());
+        assert_eq!(token(&["--no-auth"]).unwrap(), None);
+        assert!(token(&["--no-auth", "--host", "0.0.0.0"]).is_err());
+        assert!(token(&["--no-auth", "--host", "::1"]).is_ok());
+        assert!(token(&["--host", "0.0.0.0"]).unwrap().is_some());
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.yaml");
+        std::fs::write(
+            &config,
+            "oidc:\n  issuer: https://id.example.com\n  client_id: beads\n  redirect_url: https://beads.example.com/auth/callback\n",
+        )
+        .unwrap();
+        let config_arg = config.to_string_lossy().to_string();
+        let settings = Cli::try_parse_from(["beads-server", "--config", &config_arg])
+            .unwrap()
+            .settings()
+            .unwrap();
+        assert_eq!(settings.token, None);
+        assert_eq!(settings.oidc.unwrap().scopes, ["openid", "profile", "email"]);
+        assert_eq!(
+            token(&["--config", &config_arg, "--token", "0123456789abcdef"])
+                .unwrap()
+                .as_deref(),
+            Some("0123456789abcdef")
+        );
+    }
+
+    #[test]
+    fn test_is_loopback() {
+        assert!(is_loopback("localhost"));
+        assert!(is_loopback("127.0.0.2"));
+        assert!(is_loopback("[::1]"));
+        assert!(!is_loopback("0.0.0.0"));
+        assert!(!is_loopback("192.168.1.5"));
+        assert!(!is_loopback("beads.example.com"));
+    }
+
+    #[test]
+    fn test_register_project() {
+        let db = Database::new_in_memory().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("my-app");
+        std::fs::create_dir(&project_dir).unwrap();
+
+        let project = register_project(&db, &project_dir).unwrap();
+        assert_eq!(project.name, "my-app");
+        // Registering the same directory again reuses the project
+        let again = register_project(&db, &project_dir.join("..").join("my-app")).unwrap();
+        assert_eq!(again.id, project.id);
+        assert_eq!(db.get_projects().unwrap().len(), 1);
+
+        assert!(register_project(&db, &dir.path().join("missing")).is_err());
+    }
+}
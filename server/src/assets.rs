@@ -0,0 +1,241 @@
+//! The embedded frontend.
+//!
+//! Files from the Next.js build are served with an `ETag` (from a hash of
+//! their contents, taken once at startup) and revalidated with
+//! `If-None-Match`. Files under `_next/static/` have content hashes in
+//! their names, so browsers may cache them for good; everything else (the
+//! HTML pages in particular) is revalidated on each load.
+//!
+//! With `--assets-dir`, files are read from that directory on each request
+//! instead, so a rebuilt frontend shows up without rebuilding the server.
+//! Files missing there still come from the embedded build.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use rust_embed::Embed;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Embedded static files from the Next.js build output.
+#[derive(Embed)]
+#[folder = "../out/"]
+struct Assets;
+
+/// Cache policy for files whose names change with their contents.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+/// Cache policy for everything else: keep, but check the ETag first.
+const REVALIDATE: &str = "no-cache";
+
+/// Directory to serve files from ahead of the embedded build.
+static ASSETS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Quoted ETag for a SHA-256 hash of a file's contents.
+fn etag_for(hash: &[u8]) -> String {
+    // Half the hash is plenty to tell versions apart
+    let mut etag = String::from("\"");
+    for byte in &hash[..16] {
+        let _ = write!(etag, "{:02x}", byte);
+    }
+    etag.push('"');
+    etag
+}
+
+/// ETags of the embedded files by path.
+fn etags() -> &'static HashMap<String, String> {
+    static ETAGS: OnceLock<HashMap<String, String>> = OnceLock::new();
+    ETAGS.get_or_init(|| {
+        Assets::iter()
+            .filter_map(|path| {
+                let file = Assets::get(&path)?;
+                Some((path.to_string(), etag_for(&file.metadata.sha256_hash())))
+            })
+            .collect()
+    })
+}
+
+/// Hashes the embedded files up front rather than on the first request,
+/// and serves files from `assets_dir` ahead of them if given.
+pub fn init(assets_dir: Option<PathBuf>) {
+    if let Some(dir) = assets_dir {
+        let _ = ASSETS_DIR.set(dir);
+    }
+    etags();
+}
+
+/// Reads `path` from `dir`, refusing paths that would leave it.
+fn read_from(dir: &Path, path: &str) -> Option<Vec<u8>> {
+    let relative = Path::new(path);
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    let file = dir.join(relative);
+    if !file.is_file() {
+        return None;
+    }
+    std::fs::read(file).ok()
+}
+
+fn cache_control(path: &str) -> &'static str {
+    if path.starts_with("_next/static/") {
+        IMMUTABLE
+    } else {
+        REVALIDATE
+    }
+}
+
+/// Whether an `If-None-Match` header matches `etag` (weak comparison).
+fn matches_etag(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+/// Responds with the file at `path`, or `None` if there isn't one.
+fn asset_response(headers: &HeaderMap, path: &str, content_type: &str) -> Option<Response<Body>> {
+    let from_disk = ASSETS_DIR.get().and_then(|dir| read_from(dir, path));
+    let (data, etag, cache_control): (Cow<'static, [u8]>, String, _) = match from_disk {
+        // Files on disk can change under the same name, so always revalidate
+        Some(data) => {
+            let etag = etag_for(&Sha256::digest(&data));
+            (Cow::Owned(data), etag, REVALIDATE)
+        }
+        None => {
+            let content = Assets::get(path)?;
+            let etag = etags()
+                .get(path)
+                .cloned()
+                .unwrap_or_else(|| etag_for(&content.metadata.sha256_hash()));
+            (content.data, etag, cache_control(path))
+        }
+    };
+    let builder = Response::builder()
+        .header(header::CACHE_CONTROL, cache_control)
+        .header(header::ETAG, &etag);
+
+    if matches_etag(headers, &etag) {
+        return Some(
+            builder
+                .status(StatusCode::NOT_MODIFIED)
+                .body(Body::empty())
+                .unwrap(),
+        );
+    }
+    Some(
+        builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, content_type)
+            .body(Body::from(data.into_owned()))
+            .unwrap(),
+    )
+}
+
+/// Serves the frontend's static files, with fallback to index.html for SPA
+/// routing.
+pub async fn serve_static(req: Request<Body>) -> impl IntoResponse {
+    let headers = req.headers();
+    let path = req.uri().path().trim_start_matches('/');
+
+    // Try the exact path first
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    if let Some(response) = asset_response(headers, path, mime.as_ref()) {
+        return response;
+    }
+
+    // Try with .html extension (for Next.js static export)
+    let html_path = format!("{}.html", path);
+    if let Some(response) = asset_response(headers, &html_path, "text/html") {
+        return response;
+    }
+
+    // Try index.html in subdirectory
+    let index_path = if path.is_empty() {
+        "index.html".to_string()
+    } else {
+        format!("{}/index.html", path)
+    };
+    if let Some(response) = asset_response(headers, &index_path, "text/html") {
+        return response;
+    }
+
+    // Fallback to root index.html for SPA client-side routing
+    if let Some(response) = asset_response(headers, "index.html", "text/html") {
+        return response;
+    }
+
+    // 404 if nothing found
+    Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Body::from("Not Found"))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_etag() {
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::IF_NONE_MATCH, value.parse().unwrap());
+            headers
+        };
+        assert!(!matches_etag(&HeaderMap::new(), "\"abc\""));
+        assert!(matches_etag(&headers("\"abc\""), "\"abc\""));
+        assert!(matches_etag(&headers("\"xyz\", W/\"abc\""), "\"abc\""));
+        assert!(matches_etag(&headers("*"), "\"abc\""));
+        assert!(!matches_etag(&headers("\"abcd\""), "\"abc\""));
+    }
+
+    #[test]
+    fn test_read_from() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("_next")).unwrap();
+        std::fs::write(dir.path().join("_next/app.js"), "app").unwrap();
+
+        assert_eq!(read_from(dir.path(), "_next/app.js").as_deref(), Some(&b"app"[..]));
+        assert_eq!(read_from(dir.path(), "missing.js"), None);
+        assert_eq!(read_from(dir.path(), "_next"), None);
+        assert_eq!(read_from(dir.path(), ""), None);
+        assert_eq!(read_from(&dir.path().join("_next"), "../_next/app.js"), None);
+    }
+
+    #[test]
+    fn test_cache_control() {
+        assert_eq!(cache_control("_next/static/chunks/main-1a2b3c.js"), IMMUTABLE);
+        assert_eq!(cache_control("index.html"), REVALIDATE);
+        assert_eq!(cache_control("favicon.ico"), REVALIDATE);
+    }
+
+    #[tokio::test]
+    async fn test_serve_static_revalidates() {
+        let request = |etag: Option<&str>| {
+            let mut builder = Request::builder().uri("/");
+            if let Some(etag) = etag {
+                builder = builder.header(header::IF_NONE_MATCH, etag);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let response = serve_static(request(None)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], REVALIDATE);
+        let etag = response.headers()[header::ETAG].to_str().unwrap().to_string();
+
+        let response = serve_static(request(Some(&etag))).await.into_response();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], etag.as_str());
+    }
+}
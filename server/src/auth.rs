@@ -0,0 +1,176 @@
+//! Access-token authentication for the API.
+//!
+//! Every `/api` request must carry the server's token, either as an
+//! `Authorization: Bearer <token>` header or, for clients that cannot set
+//! headers (`EventSource`, `WebSocket`, the first page load), a `token`
+//! query parameter.
+//! The token is set with `--token` or in the config file, or generated on
+//! first start and kept in the settings database so restarts don't sign
+//! boards out; the URL the server opens in the browser carries it.
+//!
+//! With OIDC login configured (see [`crate::oidc`]), a session cookie is
+//! accepted instead and the signed-in [`Identity`] is handed to handlers as
+//! a request extension. Changes made through the API are logged under the
+//! `audit` target with the user who made them.
+
+use axum::{
+    extract::{Extension, Query, Request, State},
+    http::{header, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::error::AppError;
+use crate::oidc::{Identity, Oidc};
+
+/// Generates a random access token.
+pub fn generate_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Checks that a configured token can be passed in a URL unescaped.
+pub fn validate_token(token: &str) -> Result<(), String> {
+    let allowed = |c: char| c.is_ascii_alphanumeric() || "-._~".contains(c);
+    if token.len() < 16 || !token.chars().all(allowed) {
+        return Err(
+            "Token must be at least 16 characters of letters, digits, '-', '.', '_', or '~'"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Ways a request may authenticate; both `None` disables authentication.
+#[derive(Clone)]
+pub struct AuthState {
+    pub token: Option<Arc<str>>,
+    pub oidc: Option<Arc<Oidc>>,
+}
+
+/// Rejects `/api` requests that carry neither the token nor a signed-in
+/// session. Other paths (the frontend, `/auth` login) and CORS preflight
+/// requests pass through.
+pub async fn require_auth(
+    State(state): State<AuthState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if !path.starts_with("/api/") || request.method() == Method::OPTIONS {
+        return next.run(request).await;
+    }
+
+    let identity = state
+        .oidc
+        .as_ref()
+        .and_then(|oidc| oidc.identity(request.headers()));
+    let user = match (&identity, &state.token) {
+        (Some(identity), _) => identity.display_name().to_string(),
+        (None, Some(token)) if provided_token(&request).is_some_and(|t| tokens_match(&t, token)) => {
+            "token".to_string()
+        }
+        (None, None) if state.oidc.is_none() => "anonymous".to_string(),
+        _ => return unauthorized(state.oidc.is_some()),
+    };
+
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        info!(target: "audit", user = %user, "{} {}", request.method(), path);
+    }
+    if let Some(identity) = identity {
+        request.extensions_mut().insert(identity);
+    }
+    next.run(request).await
+}
+
+fn unauthorized(login: bool) -> Response {
+    let error = if login {
+        AppError::Unauthorized("Sign in to use the board".to_string())
+            .with_details(serde_json::json!({ "login_url": "/auth/login" }))
+    } else {
+        AppError::Unauthorized("Missing or invalid access token".to_string())
+    };
+    ([(header::WWW_AUTHENTICATE, "Bearer")], error).into_response()
+}
+
+/// Returns the signed-in user, if any.
+///
+/// # Endpoint
+///
+/// `GET /api/auth/me`
+pub async fn me(identity: Option<Extension<Identity>>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "user": identity.map(|Extension(identity)| identity),
+    }))
+}
+
+/// Returns the token from the `Authorization` header or the `token` query
+/// parameter.
+fn provided_token(request: &Request) -> Option<String> {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string());
+    bearer.or_else(|| {
+        Query::<HashMap<String, String>>::try_from_uri(request.uri())
+            .ok()
+            .and_then(|Query(mut params)| params.remove("token"))
+    })
+}
+
+/// Compares tokens in time independent of where they first differ.
+fn tokens_match(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_provided_token() {
+        let request = |uri: &str, authorization: Option<&str>| {
+            let mut builder = Request::builder().uri(uri);
+            if let Some(value) = authorization {
+                builder = builder.header(header::AUTHORIZATION, value);
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        assert_eq!(provided_token(&request("/api/health", None)), None);
+        assert_eq!(
+            provided_token(&request("/api/watch/beads?path=x&token=abc", None)),
+            Some("abc".to_string())
+        );
+        assert_eq!(
+            provided_token(&request("/api/health", Some("Bearer abc"))),
+            Some("abc".to_string())
+        );
+        assert_eq!(provided_token(&request("/api/health", Some("Basic abc"))), None);
+    }
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("0123456789abcdef", "0123456789abcdef"));
+        assert!(!tokens_match("0123456789abcdeX", "0123456789abcdef"));
+        assert!(!tokens_match("0123456789abcde", "0123456789abcdef"));
+    }
+
+    #[test]
+    fn test_validate_token() {
+        assert!(validate_token(&generate_token()).is_ok());
+        assert!(validate_token("short").is_err());
+        assert!(validate_token("has spaces in the token").is_err());
+    }
+}
@@ -1,10 +1,12 @@
 //! Database module for beads-server
 //!
-//! Provides SQLite storage for projects, tags, and their relationships.
+//! Provides SQLite storage for projects, tags, and their relationships, for
+//! per-device UI preferences, for approved worktree hooks, and for
+//! server-wide settings.
 //! Uses rusqlite with Arc<Mutex<>> for thread-safe access from Axum handlers.
 
 use chrono::Utc;
-use rusqlite::{params, Connection, Result as SqliteResult};
+use rusqlite::{params, Connection, OptionalExtension, Result as SqliteResult};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Mutex;
@@ -20,6 +22,14 @@ pub enum DbError {
     ProjectNotFound(String),
     #[error("Tag not found: {0}")]
     TagNotFound(String),
+    #[error("View not found: {0}")]
+    ViewNotFound(String),
+    #[error("A view named '{0}' already exists for this project")]
+    ViewNameTaken(String),
+    #[error("Session not found: {0}")]
+    SessionNotFound(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
     #[error("Database path error")]
     PathError,
 }
@@ -93,6 +103,147 @@ pub struct ProjectTagInput {
     pub tag_id: String,
 }
 
+/// A saved board view (named filters, grouping, and sort) for a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedView {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    /// Filter query string as accepted by `GET /api/beads` (e.g., "status=open&owner=ann")
+    pub query: String,
+    pub group_by: Option<String>,
+    pub sort: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Input for creating a saved view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateViewInput {
+    pub project_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub query: String,
+    pub group_by: Option<String>,
+    pub sort: Option<String>,
+}
+
+/// Input for updating a saved view
+///
+/// `groupBy` and `sort` are cleared by sending an empty string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateViewInput {
+    pub name: Option<String>,
+    pub query: Option<String>,
+    pub group_by: Option<String>,
+    pub sort: Option<String>,
+}
+
+/// Reviewers, labels, and assignees applied to PRs created for a project's
+/// beads unless the create request sets its own
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PrDefaults {
+    #[serde(default)]
+    pub reviewers: Vec<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub assignees: Vec<String>,
+}
+
+/// Where a project's pull requests are hosted
+///
+/// Kept in the user's database rather than the repository: `.beads/` is
+/// usually committed, and a cloned repository must not be able to send the
+/// user's tokens to a server of its choosing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrSettings {
+    /// "github", "bitbucket", "gitea", or "forgejo"; detected from the
+    /// `origin` remote when unset
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Web URL of a Gitea or GitHub Enterprise server (e.g.
+    /// "https://git.example.com"); unset uses the remote's host for Gitea
+    /// and github.com for GitHub
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// GitHub token, used when `GITHUB_TOKEN` and `GH_TOKEN` are unset.
+    /// Never returned; left unchanged when omitted and cleared by sending an
+    /// empty string.
+    #[serde(default, skip_serializing)]
+    pub token: Option<String>,
+    /// Whether a token is stored
+    #[serde(default, skip_deserializing)]
+    pub has_token: bool,
+}
+
+/// UI preferences kept for one browser or device
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionPrefs {
+    /// "light", "dark", or "system"; unset follows the system
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub last_project_id: Option<String>,
+    /// Status columns collapsed on the board
+    #[serde(default)]
+    pub collapsed_columns: Vec<String>,
+}
+
+/// A browser or device with its own preferences, identified by a cookie
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    /// Kept out of responses; the cookie carries it
+    #[serde(skip_serializing)]
+    pub id: String,
+    /// Name given to the device (e.g., "Work laptop")
+    pub name: Option<String>,
+    pub prefs: SessionPrefs,
+    pub created_at: String,
+    pub last_seen: String,
+}
+
+/// Input for updating a session
+///
+/// `name` is cleared by sending an empty string.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSessionInput {
+    pub name: Option<String>,
+    pub prefs: Option<SessionPrefs>,
+}
+
+/// Server-wide settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+    /// Command that opens a terminal, split on whitespace; `{path}` is
+    /// replaced with the directory. Unset uses the platform default.
+    #[serde(default)]
+    pub terminal_command: Option<String>,
+}
+
+/// Key of the terminal command in the `settings` table
+const TERMINAL_COMMAND_KEY: &str = "terminal_command";
+
+/// Key of the generated access token in the `settings` table
+const AUTH_TOKEN_KEY: &str = "auth_token";
+
+/// Themes a session may choose
+const THEMES: &[&str] = &["light", "dark", "system"];
+
+/// PR providers a project may choose
+const PR_PROVIDERS: &[&str] = &["github", "bitbucket", "gitea", "forgejo"];
+
+/// Sessions not seen for this many days are removed
+const SESSION_RETENTION_DAYS: i64 = 180;
+
 /// Thread-safe database wrapper
 pub struct Database {
     conn: Mutex<Connection>,
@@ -131,6 +282,13 @@ impl Database {
         Ok(db)
     }
 
+    /// Closes the connection, reporting any error SQLite hits finishing up
+    /// (dropping the database closes it silently)
+    pub fn close(self) -> Result<(), DbError> {
+        let conn = self.conn.into_inner().unwrap_or_else(|e| e.into_inner());
+        conn.close().map_err(|(_, e)| DbError::Sqlite(e))
+    }
+
     /// Gets the database file path in the app data directory
     fn get_db_path() -> Result<PathBuf, DbError> {
         let proj_dirs =
@@ -166,9 +324,56 @@ impl Database {
                 FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
             );
 
+            CREATE TABLE IF NOT EXISTS saved_views (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                query TEXT NOT NULL,
+                group_by TEXT,
+                sort TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE (project_id, name),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS pr_defaults (
+                project_id TEXT PRIMARY KEY,
+                reviewers TEXT NOT NULL,
+                labels TEXT NOT NULL,
+                assignees TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS pr_settings (
+                project_id TEXT PRIMARY KEY,
+                settings TEXT NOT NULL,
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS trusted_hooks (
+                repo_path TEXT PRIMARY KEY,
+                hash TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS sessions (
+                id TEXT PRIMARY KEY,
+                name TEXT,
+                prefs TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                last_seen TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+
             CREATE INDEX IF NOT EXISTS idx_projects_last_opened ON projects(last_opened DESC);
             CREATE INDEX IF NOT EXISTS idx_project_tags_project ON project_tags(project_id);
             CREATE INDEX IF NOT EXISTS idx_project_tags_tag ON project_tags(tag_id);
+            CREATE INDEX IF NOT EXISTS idx_saved_views_project ON saved_views(project_id);
+            CREATE INDEX IF NOT EXISTS idx_sessions_last_seen ON sessions(last_seen);
             ",
         )?;
 
@@ -427,6 +632,581 @@ impl Database {
 
         Ok(())
     }
+
+    // ===== Saved Views =====
+
+    /// Gets saved views ordered by name, optionally only those of one project
+    pub fn get_views(&self, project_id: Option<&str>) -> Result<Vec<SavedView>, DbError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, project_id, name, query, group_by, sort, created_at, updated_at
+             FROM saved_views
+             WHERE ?1 IS NULL OR project_id = ?1
+             ORDER BY name",
+        )?;
+
+        let views = stmt
+            .query_map(params![project_id], view_from_row)?
+            .collect::<SqliteResult<Vec<_>>>()?;
+
+        Ok(views)
+    }
+
+    /// Creates a new saved view for an existing project
+    pub fn create_view(&self, input: CreateViewInput) -> Result<SavedView, DbError> {
+        let name = validate_view_name(&input.name)?;
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+
+        let conn = self.conn.lock().unwrap();
+
+        // Verify project exists
+        let project_exists: bool = conn
+            .query_row(
+                "SELECT 1 FROM projects WHERE id = ?1",
+                params![input.project_id],
+                |_| Ok(true),
+            )
+            .unwrap_or(false);
+
+        if !project_exists {
+            return Err(DbError::ProjectNotFound(input.project_id));
+        }
+
+        let view = SavedView {
+            id,
+            project_id: input.project_id,
+            name,
+            query: normalize_view_query(&input.query),
+            group_by: non_empty(input.group_by),
+            sort: non_empty(input.sort),
+            created_at: now.clone(),
+            updated_at: now,
+        };
+
+        conn.execute(
+            "INSERT INTO saved_views (id, project_id, name, query, group_by, sort, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                view.id,
+                view.project_id,
+                view.name,
+                view.query,
+                view.group_by,
+                view.sort,
+                view.created_at,
+                view.updated_at
+            ],
+        )
+        .map_err(|e| view_write_error(e, &view.name))?;
+
+        Ok(view)
+    }
+
+    /// Updates an existing saved view
+    pub fn update_view(&self, id: &str, input: UpdateViewInput) -> Result<SavedView, DbError> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut view = conn
+            .query_row(
+                "SELECT id, project_id, name, query, group_by, sort, created_at, updated_at
+                 FROM saved_views WHERE id = ?1",
+                params![id],
+                view_from_row,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::ViewNotFound(id.to_string()),
+                e => DbError::Sqlite(e),
+            })?;
+
+        if let Some(ref name) = input.name {
+            view.name = validate_view_name(name)?;
+        }
+        if let Some(ref query) = input.query {
+            view.query = normalize_view_query(query);
+        }
+        if input.group_by.is_some() {
+            view.group_by = non_empty(input.group_by);
+        }
+        if input.sort.is_some() {
+            view.sort = non_empty(input.sort);
+        }
+        view.updated_at = Utc::now().to_rfc3339();
+
+        conn.execute(
+            "UPDATE saved_views SET name = ?1, query = ?2, group_by = ?3, sort = ?4, updated_at = ?5
+             WHERE id = ?6",
+            params![view.name, view.query, view.group_by, view.sort, view.updated_at, id],
+        )
+        .map_err(|e| view_write_error(e, &view.name))?;
+
+        Ok(view)
+    }
+
+    /// Deletes a saved view by ID
+    pub fn delete_view(&self, id: &str) -> Result<(), DbError> {
+        let conn = self.conn.lock().unwrap();
+        let rows = conn.execute("DELETE FROM saved_views WHERE id = ?1", params![id])?;
+
+        if rows == 0 {
+            return Err(DbError::ViewNotFound(id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    // ===== PR Defaults =====
+
+    /// Gets a project's PR defaults, empty if none were set
+    pub fn get_pr_defaults(&self, project_id: &str) -> Result<PrDefaults, DbError> {
+        let conn = self.conn.lock().unwrap();
+        ensure_project(&conn, project_id)?;
+        read_pr_defaults(&conn, project_id)
+    }
+
+    /// Gets the PR defaults of the project at a repository path, if the
+    /// path is a registered project
+    pub fn get_pr_defaults_for_path(&self, path: &str) -> Result<Option<PrDefaults>, DbError> {
+        let conn = self.conn.lock().unwrap();
+        project_id_for_path(&conn, path)?
+            .map(|id| read_pr_defaults(&conn, &id))
+            .transpose()
+    }
+
+    /// Replaces a project's PR defaults
+    pub fn set_pr_defaults(&self, project_id: &str, input: PrDefaults) -> Result<PrDefaults, DbError> {
+        let defaults = PrDefaults {
+            reviewers: normalize_names(input.reviewers),
+            labels: normalize_names(input.labels),
+            assignees: normalize_names(input.assignees),
+        };
+
+        let conn = self.conn.lock().unwrap();
+        ensure_project(&conn, project_id)?;
+        conn.execute(
+            "INSERT INTO pr_defaults (project_id, reviewers, labels, assignees)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(project_id) DO UPDATE SET
+                reviewers = excluded.reviewers,
+                labels = excluded.labels,
+                assignees = excluded.assignees",
+            params![
+                project_id,
+                encode_names(&defaults.reviewers),
+                encode_names(&defaults.labels),
+                encode_names(&defaults.assignees)
+            ],
+        )?;
+
+        Ok(defaults)
+    }
+
+    // ===== PR Settings =====
+
+    /// Gets where a project's PRs are hosted, empty if nothing was set
+    pub fn get_pr_settings(&self, project_id: &str) -> Result<PrSettings, DbError> {
+        let conn = self.conn.lock().unwrap();
+        ensure_project(&conn, project_id)?;
+        read_pr_settings(&conn, project_id)
+    }
+
+    /// Gets the PR settings of the project at a repository path, including
+    /// its token; empty if the path is not a registered project
+    pub fn get_pr_settings_for_path(&self, path: &str) -> Result<PrSettings, DbError> {
+        let conn = self.conn.lock().unwrap();
+        match project_id_for_path(&conn, path)? {
+            Some(id) => read_pr_settings(&conn, &id),
+            None => Ok(PrSettings::default()),
+        }
+    }
+
+    /// Replaces a project's PR settings, keeping the stored token unless
+    /// `input` sets one
+    pub fn set_pr_settings(&self, project_id: &str, input: PrSettings) -> Result<PrSettings, DbError> {
+        let provider = non_empty(input.provider).map(|p| p.to_lowercase());
+        if let Some(ref provider) = provider {
+            if !PR_PROVIDERS.contains(&provider.as_str()) {
+                return Err(DbError::InvalidInput(format!(
+                    "Unknown PR provider '{}'. Expected one of: {}",
+                    provider,
+                    PR_PROVIDERS.join(", ")
+                )));
+            }
+        }
+        let base_url = non_empty(input.base_url)
+            .map(|url| url.trim_end_matches('/').to_string());
+        if let Some(ref url) = base_url {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(DbError::InvalidInput(format!(
+                    "Server URL '{}' must start with https:// or http://",
+                    url
+                )));
+            }
+        }
+
+        let conn = self.conn.lock().unwrap();
+        ensure_project(&conn, project_id)?;
+        let token = match input.token {
+            Some(token) => non_empty(Some(token)),
+            None => read_pr_settings(&conn, project_id)?.token,
+        };
+        let settings = PrSettings {
+            provider,
+            base_url,
+            has_token: token.is_some(),
+            token,
+        };
+        conn.execute(
+            "INSERT INTO pr_settings (project_id, settings) VALUES (?1, ?2)
+             ON CONFLICT(project_id) DO UPDATE SET settings = excluded.settings",
+            params![project_id, encode_pr_settings(&settings)],
+        )?;
+
+        Ok(settings)
+    }
+
+    // ===== Trusted Hooks =====
+
+    /// Gets the hash of the worktree hook commands the user approved for a
+    /// repository, if any
+    pub fn get_trusted_hooks(&self, repo_path: &str) -> Result<Option<String>, DbError> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn
+            .query_row(
+                "SELECT hash FROM trusted_hooks WHERE repo_path = rtrim(?1, '/')",
+                params![repo_path],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Records the user's approval of a repository's worktree hook
+    /// commands, replacing any earlier approval
+    pub fn trust_hooks(&self, repo_path: &str, hash: &str) -> Result<(), DbError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO trusted_hooks (repo_path, hash) VALUES (rtrim(?1, '/'), ?2)
+             ON CONFLICT(repo_path) DO UPDATE SET hash = excluded.hash",
+            params![repo_path, hash],
+        )?;
+        Ok(())
+    }
+
+    // ===== Settings =====
+
+    /// Gets the server-wide settings
+    pub fn get_settings(&self) -> Result<Settings, DbError> {
+        let conn = self.conn.lock().unwrap();
+        Ok(Settings {
+            terminal_command: read_setting(&conn, TERMINAL_COMMAND_KEY)?,
+        })
+    }
+
+    /// Replaces the server-wide settings; blank values are cleared
+    pub fn set_settings(&self, input: Settings) -> Result<Settings, DbError> {
+        let settings = Settings {
+            terminal_command: non_empty(input.terminal_command),
+        };
+
+        let conn = self.conn.lock().unwrap();
+        write_setting(&conn, TERMINAL_COMMAND_KEY, settings.terminal_command.as_deref())?;
+
+        Ok(settings)
+    }
+
+    /// Gets the access token generated on an earlier start, storing
+    /// `generated` as that token if there isn't one yet
+    pub fn keep_auth_token(&self, generated: &str) -> Result<String, DbError> {
+        let conn = self.conn.lock().unwrap();
+        if let Some(token) = read_setting(&conn, AUTH_TOKEN_KEY)? {
+            return Ok(token);
+        }
+        write_setting(&conn, AUTH_TOKEN_KEY, Some(generated))?;
+        Ok(generated.to_string())
+    }
+
+    // ===== Sessions =====
+
+    /// Gets a session and marks it seen, or starts a new one when `id` is
+    /// missing or unknown (e.g., it expired)
+    pub fn touch_session(&self, id: Option<&str>) -> Result<Session, DbError> {
+        let now = Utc::now();
+        let conn = self.conn.lock().unwrap();
+
+        if let Some(id) = id {
+            let rows = conn.execute(
+                "UPDATE sessions SET last_seen = ?1 WHERE id = ?2",
+                params![now.to_rfc3339(), id],
+            )?;
+            if rows > 0 {
+                return read_session(&conn, id);
+            }
+        }
+
+        let cutoff = now - chrono::Duration::days(SESSION_RETENTION_DAYS);
+        conn.execute(
+            "DELETE FROM sessions WHERE last_seen < ?1",
+            params![cutoff.to_rfc3339()],
+        )?;
+
+        let session = Session {
+            id: Uuid::new_v4().to_string(),
+            name: None,
+            prefs: SessionPrefs::default(),
+            created_at: now.to_rfc3339(),
+            last_seen: now.to_rfc3339(),
+        };
+        conn.execute(
+            "INSERT INTO sessions (id, name, prefs, created_at, last_seen)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                session.id,
+                session.name,
+                encode_prefs(&session.prefs),
+                session.created_at,
+                session.last_seen
+            ],
+        )?;
+
+        Ok(session)
+    }
+
+    /// Renames a session or replaces its preferences
+    pub fn update_session(&self, id: &str, input: UpdateSessionInput) -> Result<Session, DbError> {
+        let conn = self.conn.lock().unwrap();
+        let mut session = read_session(&conn, id)?;
+
+        if input.name.is_some() {
+            session.name = non_empty(input.name);
+        }
+        if let Some(prefs) = input.prefs {
+            session.prefs = validate_prefs(prefs)?;
+        }
+
+        conn.execute(
+            "UPDATE sessions SET name = ?1, prefs = ?2 WHERE id = ?3",
+            params![session.name, encode_prefs(&session.prefs), id],
+        )?;
+
+        Ok(session)
+    }
+}
+
+/// Reads a setting, `None` if it isn't set
+fn read_setting(conn: &Connection, key: &str) -> Result<Option<String>, DbError> {
+    Ok(conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Stores a setting, removing it when `value` is `None`
+fn write_setting(conn: &Connection, key: &str, value: Option<&str>) -> Result<(), DbError> {
+    match value {
+        Some(value) => conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?,
+        None => conn.execute("DELETE FROM settings WHERE key = ?1", params![key])?,
+    };
+    Ok(())
+}
+
+/// Reads a session by ID
+fn read_session(conn: &Connection, id: &str) -> Result<Session, DbError> {
+    conn.query_row(
+        "SELECT id, name, prefs, created_at, last_seen FROM sessions WHERE id = ?1",
+        params![id],
+        |row| {
+            let prefs: String = row.get(2)?;
+            Ok(Session {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                prefs: serde_json::from_str(&prefs).unwrap_or_default(),
+                created_at: row.get(3)?,
+                last_seen: row.get(4)?,
+            })
+        },
+    )
+    .map_err(|e| match e {
+        rusqlite::Error::QueryReturnedNoRows => DbError::SessionNotFound(id.to_string()),
+        e => DbError::Sqlite(e),
+    })
+}
+
+/// Stores session preferences as a JSON object
+fn encode_prefs(prefs: &SessionPrefs) -> String {
+    serde_json::to_string(prefs).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Checks the theme and tidies the collapsed columns
+fn validate_prefs(prefs: SessionPrefs) -> Result<SessionPrefs, DbError> {
+    let theme = non_empty(prefs.theme);
+    if let Some(ref theme) = theme {
+        if !THEMES.contains(&theme.as_str()) {
+            return Err(DbError::InvalidInput(format!(
+                "Unknown theme '{}'. Expected one of: {}",
+                theme,
+                THEMES.join(", ")
+            )));
+        }
+    }
+    Ok(SessionPrefs {
+        theme,
+        last_project_id: non_empty(prefs.last_project_id),
+        collapsed_columns: normalize_names(prefs.collapsed_columns),
+    })
+}
+
+/// Fails with [`DbError::ProjectNotFound`] unless the project exists
+fn ensure_project(conn: &Connection, project_id: &str) -> Result<(), DbError> {
+    let exists = conn
+        .query_row(
+            "SELECT 1 FROM projects WHERE id = ?1",
+            params![project_id],
+            |_| Ok(()),
+        )
+        .optional()?
+        .is_some();
+
+    if exists {
+        Ok(())
+    } else {
+        Err(DbError::ProjectNotFound(project_id.to_string()))
+    }
+}
+
+/// Reads a project's PR defaults, empty if none were set
+fn read_pr_defaults(conn: &Connection, project_id: &str) -> Result<PrDefaults, DbError> {
+    let row: Option<(String, String, String)> = conn
+        .query_row(
+            "SELECT reviewers, labels, assignees FROM pr_defaults WHERE project_id = ?1",
+            params![project_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?;
+
+    Ok(row
+        .map(|(reviewers, labels, assignees)| PrDefaults {
+            reviewers: decode_names(&reviewers),
+            labels: decode_names(&labels),
+            assignees: decode_names(&assignees),
+        })
+        .unwrap_or_default())
+}
+
+/// Finds the registered project at a path, ignoring a trailing slash
+fn project_id_for_path(conn: &Connection, path: &str) -> Result<Option<String>, DbError> {
+    Ok(conn
+        .query_row(
+            "SELECT id FROM projects WHERE rtrim(path, '/') = rtrim(?1, '/')",
+            params![path],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Reads a project's PR settings, empty if none were set
+fn read_pr_settings(conn: &Connection, project_id: &str) -> Result<PrSettings, DbError> {
+    let settings: Option<String> = conn
+        .query_row(
+            "SELECT settings FROM pr_settings WHERE project_id = ?1",
+            params![project_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    let mut settings: PrSettings = settings
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    settings.has_token = settings.token.is_some();
+    Ok(settings)
+}
+
+/// Stores PR settings as a JSON object, token included
+fn encode_pr_settings(settings: &PrSettings) -> String {
+    serde_json::json!({
+        "provider": settings.provider,
+        "baseUrl": settings.base_url,
+        "token": settings.token,
+    })
+    .to_string()
+}
+
+/// Trims names, dropping blanks and duplicates while keeping their order
+fn normalize_names(names: Vec<String>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for name in names {
+        let name = name.trim();
+        if !name.is_empty() && !result.iter().any(|n| n == name) {
+            result.push(name.to_string());
+        }
+    }
+    result
+}
+
+/// Stores a list of names as a JSON array
+fn encode_names(names: &[String]) -> String {
+    serde_json::to_string(names).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Reads a JSON array of names, treating bad data as empty
+fn decode_names(json: &str) -> Vec<String> {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+/// Maps a `saved_views` row to a [`SavedView`]
+fn view_from_row(row: &rusqlite::Row) -> SqliteResult<SavedView> {
+    Ok(SavedView {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        query: row.get(3)?,
+        group_by: row.get(4)?,
+        sort: row.get(5)?,
+        created_at: row.get(6)?,
+        updated_at: row.get(7)?,
+    })
+}
+
+/// Trims a view name, rejecting empty names
+fn validate_view_name(name: &str) -> Result<String, DbError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(DbError::InvalidInput(
+            "View name cannot be empty".to_string(),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Strips whitespace and a leading `?` from a view's query string
+fn normalize_view_query(query: &str) -> String {
+    let query = query.trim();
+    query.strip_prefix('?').unwrap_or(query).to_string()
+}
+
+/// Treats empty or blank optional text as absent
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Reports a unique constraint failure on a view write as a name conflict
+fn view_write_error(err: rusqlite::Error, name: &str) -> DbError {
+    match err {
+        rusqlite::Error::SqliteFailure(ref e, _)
+            if e.code == rusqlite::ErrorCode::ConstraintViolation =>
+        {
+            DbError::ViewNameTaken(name.to_string())
+        }
+        e => DbError::Sqlite(e),
+    }
 }
 
 #[cfg(test)]
@@ -452,6 +1232,17 @@ mod tests {
         assert_eq!(projects[0].id, project.id);
     }
 
+    #[test]
+    fn test_close() {
+        let db = Database::new_in_memory().unwrap();
+        db.create_project(CreateProjectInput {
+            name: "Test Project".to_string(),
+            path: "/test/path".to_string(),
+        })
+        .unwrap();
+        assert!(db.close().is_ok());
+    }
+
     #[test]
     fn test_update_project() {
         let db = Database::new_in_memory().unwrap();
@@ -567,4 +1358,270 @@ mod tests {
         assert_eq!(projects[0].tags.len(), 1);
         assert_eq!(projects[0].tags[0].name, "Tag1");
     }
+
+    #[test]
+    fn test_saved_view_crud() {
+        let db = Database::new_in_memory().unwrap();
+
+        let project = db
+            .create_project(CreateProjectInput {
+                name: "Board".to_string(),
+                path: "/board".to_string(),
+            })
+            .unwrap();
+
+        let view = db
+            .create_view(CreateViewInput {
+                project_id: project.id.clone(),
+                name: " My bugs ".to_string(),
+                query: "?issue_type=bug&owner=ann".to_string(),
+                group_by: Some("priority".to_string()),
+                sort: None,
+            })
+            .unwrap();
+
+        assert_eq!(view.name, "My bugs");
+        assert_eq!(view.query, "issue_type=bug&owner=ann");
+
+        let duplicate = db.create_view(CreateViewInput {
+            project_id: project.id.clone(),
+            name: "My bugs".to_string(),
+            query: String::new(),
+            group_by: None,
+            sort: None,
+        });
+        assert!(matches!(duplicate, Err(DbError::ViewNameTaken(_))));
+
+        let updated = db
+            .update_view(
+                &view.id,
+                UpdateViewInput {
+                    name: None,
+                    query: None,
+                    group_by: Some(String::new()),
+                    sort: Some("-updated_at".to_string()),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(updated.group_by, None);
+        assert_eq!(updated.sort.as_deref(), Some("-updated_at"));
+
+        assert_eq!(db.get_views(Some(&project.id)).unwrap().len(), 1);
+        assert!(db.get_views(Some("other")).unwrap().is_empty());
+
+        db.delete_view(&view.id).unwrap();
+        assert!(matches!(
+            db.delete_view(&view.id),
+            Err(DbError::ViewNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_pr_defaults() {
+        let db = Database::new_in_memory().unwrap();
+
+        let project = db
+            .create_project(CreateProjectInput {
+                name: "Board".to_string(),
+                path: "/board".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(db.get_pr_defaults(&project.id).unwrap(), PrDefaults::default());
+        assert!(matches!(
+            db.get_pr_defaults("missing"),
+            Err(DbError::ProjectNotFound(_))
+        ));
+
+        let saved = db
+            .set_pr_defaults(
+                &project.id,
+                PrDefaults {
+                    reviewers: vec![" ann ".to_string(), "ann".to_string(), "org/team".to_string()],
+                    labels: vec!["beads".to_string(), " ".to_string()],
+                    assignees: Vec::new(),
+                },
+            )
+            .unwrap();
+        assert_eq!(saved.reviewers, vec!["ann", "org/team"]);
+        assert_eq!(saved.labels, vec!["beads"]);
+
+        assert_eq!(db.get_pr_defaults(&project.id).unwrap(), saved);
+        assert_eq!(db.get_pr_defaults_for_path("/board/").unwrap(), Some(saved));
+        assert_eq!(db.get_pr_defaults_for_path("/elsewhere").unwrap(), None);
+    }
+
+    #[test]
+    fn test_pr_settings() {
+        let db = Database::new_in_memory().unwrap();
+
+        let project = db
+            .create_project(CreateProjectInput {
+                name: "Board".to_string(),
+                path: "/board".to_string(),
+            })
+            .unwrap();
+
+        assert_eq!(db.get_pr_settings(&project.id).unwrap(), PrSettings::default());
+        assert_eq!(db.get_pr_settings_for_path("/board").unwrap(), PrSettings::default());
+
+        let saved = db
+            .set_pr_settings(
+                &project.id,
+                PrSettings {
+                    provider: Some("Gitea".to_string()),
+                    base_url: Some(" https://git.example.com/ ".to_string()),
+                    token: Some("secret".to_string()),
+                    has_token: false,
+                },
+            )
+            .unwrap();
+        assert_eq!(saved.provider.as_deref(), Some("gitea"));
+        assert_eq!(saved.base_url.as_deref(), Some("https://git.example.com"));
+        assert!(saved.has_token);
+        assert!(!serde_json::to_string(&saved).unwrap().contains("secret"));
+
+        // Omitting the token keeps it; an empty one clears it
+        db.set_pr_settings(&project.id, PrSettings::default()).unwrap();
+        let stored = db.get_pr_settings_for_path("/board/").unwrap();
+        assert_eq!(stored.token.as_deref(), Some("secret"));
+        assert_eq!(stored.base_url, None);
+        let cleared = db
+            .set_pr_settings(
+                &project.id,
+                PrSettings {
+                    token: Some(String::new()),
+                    ..PrSettings::default()
+                },
+            )
+            .unwrap();
+        assert!(!cleared.has_token);
+
+        assert!(matches!(
+            db.set_pr_settings(
+                &project.id,
+                PrSettings {
+                    base_url: Some("git.example.com".to_string()),
+                    ..PrSettings::default()
+                }
+            ),
+            Err(DbError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            db.set_pr_settings(
+                &project.id,
+                PrSettings {
+                    provider: Some("svn".to_string()),
+                    ..PrSettings::default()
+                }
+            ),
+            Err(DbError::InvalidInput(_))
+        ));
+        assert!(matches!(
+            db.get_pr_settings("missing"),
+            Err(DbError::ProjectNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_trusted_hooks() {
+        let db = Database::new_in_memory().unwrap();
+        assert_eq!(db.get_trusted_hooks("/repo").unwrap(), None);
+
+        db.trust_hooks("/repo/", "abc").unwrap();
+        assert_eq!(db.get_trusted_hooks("/repo").unwrap().as_deref(), Some("abc"));
+        db.trust_hooks("/repo", "def").unwrap();
+        assert_eq!(db.get_trusted_hooks("/repo/").unwrap().as_deref(), Some("def"));
+    }
+
+    #[test]
+    fn test_settings() {
+        let db = Database::new_in_memory().unwrap();
+        assert_eq!(db.get_settings().unwrap(), Settings::default());
+
+        let saved = db
+            .set_settings(Settings {
+                terminal_command: Some(" wezterm start --cwd {path} ".to_string()),
+            })
+            .unwrap();
+        assert_eq!(
+            saved.terminal_command.as_deref(),
+            Some("wezterm start --cwd {path}")
+        );
+        assert_eq!(db.get_settings().unwrap(), saved);
+
+        db.set_settings(Settings {
+            terminal_command: Some(" ".to_string()),
+        })
+        .unwrap();
+        assert_eq!(db.get_settings().unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn test_keep_auth_token() {
+        let db = Database::new_in_memory().unwrap();
+        assert_eq!(db.keep_auth_token("first-start-token").unwrap(), "first-start-token");
+        // A restart generates a new token but keeps the first
+        assert_eq!(db.keep_auth_token("second-start-token").unwrap(), "first-start-token");
+        // The server-wide settings don't expose it
+        assert_eq!(db.get_settings().unwrap(), Settings::default());
+    }
+
+    #[test]
+    fn test_sessions() {
+        let db = Database::new_in_memory().unwrap();
+
+        let session = db.touch_session(None).unwrap();
+        assert_eq!(session.prefs, SessionPrefs::default());
+        assert_eq!(db.touch_session(Some(&session.id)).unwrap().id, session.id);
+        assert_ne!(db.touch_session(Some("expired")).unwrap().id, session.id);
+
+        let updated = db
+            .update_session(
+                &session.id,
+                UpdateSessionInput {
+                    name: Some(" Work laptop ".to_string()),
+                    prefs: Some(SessionPrefs {
+                        theme: Some("dark".to_string()),
+                        last_project_id: Some("p1".to_string()),
+                        collapsed_columns: vec!["closed".to_string(), "closed".to_string()],
+                    }),
+                },
+            )
+            .unwrap();
+        assert_eq!(updated.name.as_deref(), Some("Work laptop"));
+        assert_eq!(updated.prefs.collapsed_columns, vec!["closed"]);
+
+        let renamed = db
+            .update_session(
+                &session.id,
+                UpdateSessionInput {
+                    name: Some(String::new()),
+                    prefs: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(renamed.name, None);
+        assert_eq!(
+            db.touch_session(Some(&session.id)).unwrap().prefs,
+            updated.prefs
+        );
+
+        let bad_theme = db.update_session(
+            &session.id,
+            UpdateSessionInput {
+                name: None,
+                prefs: Some(SessionPrefs {
+                    theme: Some("neon".to_string()),
+                    ..SessionPrefs::default()
+                }),
+            },
+        );
+        assert!(matches!(bad_theme, Err(DbError::InvalidInput(_))));
+        assert!(matches!(
+            db.update_session("missing", UpdateSessionInput::default()),
+            Err(DbError::SessionNotFound(_))
+        ));
+    }
 }
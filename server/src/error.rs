@@ -0,0 +1,273 @@
+//! API error responses.
+//!
+//! Handlers fail with an [`AppError`], which responds with its status and a
+//! JSON envelope:
+//!
+//! ```json
+//! {
+//!   "code": "not_found",
+//!   "message": "Bead not found: bd-42",
+//!   "details": { "bead_id": "bd-42" },
+//!   "error": "Bead not found: bd-42"
+//! }
+//! ```
+//!
+//! `code` is stable for clients to match on; `details` is present only when
+//! there is more to say. `error` repeats the message for clients written
+//! against the earlier `{"error": "..."}` bodies. Panics in handlers are
+//! caught and answered with the same envelope (see [`panic_response`]).
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::Value;
+use std::any::Any;
+use std::fmt;
+use tracing::error;
+
+use crate::db::DbError;
+use crate::lock::LockError;
+
+/// An error from an API handler.
+#[derive(Debug)]
+pub enum AppError {
+    /// The request is malformed or fails validation (400)
+    BadRequest(String),
+    /// Credentials are missing or were rejected (401)
+    Unauthorized(String),
+    /// The request may not touch what it asks for (403)
+    Forbidden(String),
+    /// What the request refers to doesn't exist (404)
+    NotFound(String),
+    /// The request conflicts with the current state, e.g. a held lock (409)
+    Conflict(String),
+    /// A precondition of the request doesn't hold, e.g. a failing check (412)
+    PreconditionFailed(String),
+    /// The request body is too large (413)
+    PayloadTooLarge(String),
+    /// The requested byte range lies outside the file (416)
+    RangeNotSatisfiable(String),
+    /// The request is well-formed but can't be carried out (422)
+    Unprocessable(String),
+    /// The operation isn't supported here, e.g. by a PR provider (501)
+    NotImplemented(String),
+    /// A service the server depends on failed, e.g. GitHub (502)
+    Upstream(String),
+    /// A subprocess or service took too long (504)
+    Timeout(String),
+    /// Anything else that went wrong on the server (500)
+    Internal(String),
+    /// An error with extra context for the client
+    Detailed {
+        error: Box<AppError>,
+        details: Value,
+    },
+}
+
+impl AppError {
+    /// The error for `status`, e.g. one reported by a lower layer. Statuses
+    /// without a variant of their own become `BadRequest` or `Internal`.
+    pub fn with_status(status: StatusCode, message: impl Into<String>) -> Self {
+        let message = message.into();
+        match status {
+            StatusCode::UNAUTHORIZED => AppError::Unauthorized(message),
+            StatusCode::FORBIDDEN => AppError::Forbidden(message),
+            StatusCode::NOT_FOUND => AppError::NotFound(message),
+            StatusCode::CONFLICT => AppError::Conflict(message),
+            StatusCode::PRECONDITION_FAILED => AppError::PreconditionFailed(message),
+            StatusCode::PAYLOAD_TOO_LARGE => AppError::PayloadTooLarge(message),
+            StatusCode::RANGE_NOT_SATISFIABLE => AppError::RangeNotSatisfiable(message),
+            StatusCode::UNPROCESSABLE_ENTITY => AppError::Unprocessable(message),
+            StatusCode::NOT_IMPLEMENTED => AppError::NotImplemented(message),
+            StatusCode::BAD_GATEWAY => AppError::Upstream(message),
+            StatusCode::GATEWAY_TIMEOUT => AppError::Timeout(message),
+            status if status.is_client_error() => AppError::BadRequest(message),
+            _ => AppError::Internal(message),
+        }
+    }
+
+    /// Attaches `details` to the error.
+    pub fn with_details(self, details: Value) -> Self {
+        match self {
+            AppError::Detailed { error, .. } => AppError::Detailed { error, details },
+            error => AppError::Detailed {
+                error: Box::new(error),
+                details,
+            },
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::RangeNotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
+            AppError::Unprocessable(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::NotImplemented(_) => StatusCode::NOT_IMPLEMENTED,
+            AppError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            AppError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Detailed { error, .. } => error.status(),
+        }
+    }
+
+    /// Machine-readable error code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::PreconditionFailed(_) => "precondition_failed",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::RangeNotSatisfiable(_) => "range_not_satisfiable",
+            AppError::Unprocessable(_) => "unprocessable",
+            AppError::NotImplemented(_) => "not_implemented",
+            AppError::Upstream(_) => "upstream_error",
+            AppError::Timeout(_) => "timeout",
+            AppError::Internal(_) => "internal_error",
+            AppError::Detailed { error, .. } => error.code(),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::BadRequest(message)
+            | AppError::Unauthorized(message)
+            | AppError::Forbidden(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::PreconditionFailed(message)
+            | AppError::PayloadTooLarge(message)
+            | AppError::RangeNotSatisfiable(message)
+            | AppError::Unprocessable(message)
+            | AppError::NotImplemented(message)
+            | AppError::Upstream(message)
+            | AppError::Timeout(message)
+            | AppError::Internal(message) => message,
+            AppError::Detailed { error, .. } => error.message(),
+        }
+    }
+
+    fn details(&self) -> Option<&Value> {
+        match self {
+            AppError::Detailed { details, .. } => Some(details),
+            _ => None,
+        }
+    }
+
+    /// The JSON envelope.
+    pub fn body(&self) -> Value {
+        let mut body = serde_json::json!({
+            "code": self.code(),
+            "message": self.message(),
+            "error": self.message(),
+        });
+        if let Some(details) = self.details() {
+            body["details"] = details.clone();
+        }
+        body
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = self.status();
+        if status.is_server_error() {
+            error!(code = self.code(), "{}", self.message());
+        }
+        (status, Json(self.body())).into_response()
+    }
+}
+
+impl From<DbError> for AppError {
+    fn from(err: DbError) -> Self {
+        let message = err.to_string();
+        match err {
+            DbError::ProjectNotFound(_)
+            | DbError::TagNotFound(_)
+            | DbError::ViewNotFound(_)
+            | DbError::SessionNotFound(_) => AppError::NotFound(message),
+            DbError::ViewNameTaken(_) => AppError::Conflict(message),
+            DbError::InvalidInput(_) => AppError::BadRequest(message),
+            DbError::Sqlite(_) | DbError::PathError => AppError::Internal(message),
+        }
+    }
+}
+
+impl From<LockError> for AppError {
+    fn from(err: LockError) -> Self {
+        AppError::with_status(err.status_code(), err.to_string())
+    }
+}
+
+/// Responds to a handler panic, for `CatchPanicLayer`.
+pub fn panic_response(panic: Box<dyn Any + Send + 'static>) -> Response {
+    let detail = panic
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| panic.downcast_ref::<&str>().copied())
+        .unwrap_or("unknown panic");
+    error!("Handler panicked: {}", detail);
+    AppError::Internal("Internal server error".to_string()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body() {
+        let error = AppError::NotFound("Bead not found: bd-42".to_string());
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            error.body(),
+            serde_json::json!({
+                "code": "not_found",
+                "message": "Bead not found: bd-42",
+                "error": "Bead not found: bd-42",
+            })
+        );
+
+        let error = error.with_details(serde_json::json!({ "bead_id": "bd-42" }));
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+        assert_eq!(error.body()["details"]["bead_id"], "bd-42");
+        assert_eq!(error.body()["code"], "not_found");
+    }
+
+    #[test]
+    fn test_with_status() {
+        let error = AppError::with_status(StatusCode::UNPROCESSABLE_ENTITY, "bad");
+        assert_eq!(error.code(), "unprocessable");
+        let error = AppError::with_status(StatusCode::METHOD_NOT_ALLOWED, "no");
+        assert_eq!(error.code(), "bad_request");
+        let error = AppError::with_status(StatusCode::SERVICE_UNAVAILABLE, "down");
+        assert_eq!(error.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            AppError::from(LockError::Busy("issues.jsonl".into())).status(),
+            StatusCode::CONFLICT
+        );
+    }
+
+    #[test]
+    fn test_panic_response() {
+        let response = panic_response(Box::new("boom"));
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
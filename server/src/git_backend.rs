@@ -0,0 +1,1153 @@
+//! In-process git queries backed by libgit2.
+//!
+//! The board polls branch and worktree state constantly, and spawning `git`
+//! for each query is slow and fails outright when git is not on `PATH`.
+//! Queries and local commits go through libgit2 here instead. Operations
+//! libgit2 does not cover well (fetch, push, rebase, and adding or removing
+//! worktrees) still shell out to `git`.
+//!
+//! These functions block; async callers should run them on the blocking
+//! thread pool.
+
+use git2::{
+    Delta, DiffFindOptions, IndexAddOption, Patch, Repository, RepositoryState, Sort,
+    StatusOptions, WorktreeLockStatus,
+};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::logging::LogCommand;
+
+/// A linked worktree registered with a repository.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitWorktree {
+    pub path: PathBuf,
+    /// Branch checked out in the worktree (`None` when HEAD is detached)
+    pub branch: Option<String>,
+    /// Whether the worktree is locked against pruning and removal
+    pub locked: bool,
+    /// Reason given when the worktree was locked, if any
+    pub lock_reason: Option<String>,
+    /// Whether the worktree directory is gone and git would prune it
+    pub prunable: bool,
+}
+
+/// How commits made by the server are signed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Signing {
+    /// Detached OpenPGP signature from `gpg`, with the given key ID or
+    /// gpg's default key
+    Gpg(Option<String>),
+    /// SSH signature from `ssh-keygen -Y sign` with the given key file
+    Ssh(PathBuf),
+}
+
+/// Identity and signing for commits made by the server.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitOptions {
+    /// Author and committer as (name, email); taken from the repository's
+    /// git config when `None`
+    pub identity: Option<(String, String)>,
+    /// Commits are unsigned when `None`
+    pub signing: Option<Signing>,
+}
+
+/// A commit on a branch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitSummary {
+    pub id: String,
+    /// First line of the commit message
+    pub summary: String,
+    pub author: String,
+    /// Commit time in seconds since the Unix epoch
+    pub time: i64,
+}
+
+/// A file changed on a branch, with line counts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileChange {
+    pub path: String,
+    /// "added", "removed", "modified", "renamed", "copied", or "changed",
+    /// matching GitHub's PR file statuses
+    pub status: &'static str,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// The commits and file changes a branch would bring into its base.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BranchChanges {
+    /// The base the branch was compared against
+    pub base: String,
+    /// Commits on the branch but not the base, newest first
+    pub commits: Vec<CommitSummary>,
+    /// Files changed between the merge base and the branch tip
+    pub files: Vec<FileChange>,
+}
+
+/// The three versions of a conflicted file. A side is `None` when the file
+/// does not exist there (e.g. deleted on one side).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConflictSides {
+    /// Common ancestor version
+    pub ancestor: Option<String>,
+    /// Version on the branch being merged into (during a rebase, the
+    /// upstream the commits are replayed onto)
+    pub ours: Option<String>,
+    /// Version being merged in (during a rebase, the commit being replayed)
+    pub theirs: Option<String>,
+}
+
+/// Returns whether `rev` (a branch name or other revision) resolves to an
+/// object, like `git rev-parse --verify`.
+pub fn revision_exists(repo_path: &Path, rev: &str) -> Result<bool, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let exists = repo.revparse_single(rev).is_ok();
+    Ok(exists)
+}
+
+/// Counts the commits `branch` is ahead of and behind the first of `bases`
+/// that exists, like `git rev-list --left-right --count base...branch`.
+///
+/// Returns `None` if neither the branch nor any base resolves.
+pub fn ahead_behind(
+    repo_path: &Path,
+    branch: &str,
+    bases: &[&str],
+) -> Result<Option<(usize, usize)>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let Ok(branch_commit) = repo.revparse_single(branch).and_then(|o| o.peel_to_commit()) else {
+        return Ok(None);
+    };
+
+    for base in bases {
+        if let Ok(base_commit) = repo.revparse_single(base).and_then(|o| o.peel_to_commit()) {
+            let counts = repo.graph_ahead_behind(branch_commit.id(), base_commit.id())?;
+            return Ok(Some(counts));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the commits on `branch` that are not on `upstream`, oldest
+/// first, like `git rev-list --reverse --topo-order upstream..branch`.
+pub fn commits_between(
+    repo_path: &Path,
+    upstream: &str,
+    branch: &str,
+) -> Result<Vec<String>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(repo.revparse_single(branch)?.peel_to_commit()?.id())?;
+    revwalk.hide(repo.revparse_single(upstream)?.peel_to_commit()?.id())?;
+    revwalk
+        .map(|id| id.map(|id| id.to_string()))
+        .collect()
+}
+
+/// Resolves a revision (e.g. an abbreviated commit ID) to a full commit
+/// ID, or `None` if it does not name a commit.
+pub fn resolve_commit(repo_path: &Path, rev: &str) -> Result<Option<String>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let id = repo
+        .revparse_single(rev)
+        .and_then(|o| o.peel_to_commit())
+        .ok()
+        .map(|c| c.id().to_string());
+    Ok(id)
+}
+
+/// Lists the commits reachable from HEAD that changed the file at `path`
+/// (relative to the repository root), newest first, like
+/// `git log -n <limit> -- <path>`. Renames are not followed.
+///
+/// Returns an empty list if the repository has no commits yet.
+pub fn file_history(
+    repo_path: &Path,
+    path: &Path,
+    limit: usize,
+) -> Result<Vec<CommitSummary>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let Ok(head) = repo.head().and_then(|h| h.peel_to_commit()) else {
+        return Ok(Vec::new());
+    };
+    let blob_at = |commit: &git2::Commit| -> Result<Option<git2::Oid>, git2::Error> {
+        Ok(commit.tree()?.get_path(path).ok().map(|entry| entry.id()))
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(head.id())?;
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        if commits.len() >= limit {
+            break;
+        }
+        let commit = repo.find_commit(oid?)?;
+        let blob = blob_at(&commit)?;
+        let parent_blob = match commit.parent(0) {
+            Ok(parent) => blob_at(&parent)?,
+            Err(_) => None,
+        };
+        if blob == parent_blob {
+            continue;
+        }
+        commits.push(CommitSummary {
+            id: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("").to_string(),
+            time: commit.time().seconds(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Reads the file at `path` (relative to the repository root) as of the
+/// commit `rev` names, like `git show <rev>:<path>`. Returns the full commit
+/// ID with the content.
+///
+/// Returns `None` if `rev` does not name a commit or the file does not
+/// exist in it.
+pub fn file_at_revision(
+    repo_path: &Path,
+    rev: &str,
+    path: &Path,
+) -> Result<Option<(String, String)>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let Ok(commit) = repo.revparse_single(rev).and_then(|o| o.peel_to_commit()) else {
+        return Ok(None);
+    };
+    let Ok(entry) = commit.tree()?.get_path(path) else {
+        return Ok(None);
+    };
+    let Ok(blob) = entry.to_object(&repo)?.into_blob() else {
+        return Ok(None);
+    };
+    Ok(Some((
+        commit.id().to_string(),
+        String::from_utf8_lossy(blob.content()).to_string(),
+    )))
+}
+
+/// Returns whether the working tree at `path` has uncommitted changes,
+/// including untracked files, like a non-empty `git status --porcelain`.
+pub fn is_dirty(path: &Path) -> Result<bool, git2::Error> {
+    let repo = Repository::open(path)?;
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    let dirty = !repo.statuses(Some(&mut options))?.is_empty();
+    Ok(dirty)
+}
+
+/// Returns whether the repository has any remotes configured.
+pub fn has_remote(repo_path: &Path) -> Result<bool, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let has_remote = !repo.remotes()?.is_empty();
+    Ok(has_remote)
+}
+
+/// Returns the URL of the named remote, if it exists.
+pub fn remote_url(repo_path: &Path, name: &str) -> Result<Option<String>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let url = match repo.find_remote(name) {
+        Ok(remote) => remote.url().map(String::from),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    Ok(url)
+}
+
+/// Returns the default branch of the named remote, like the target of
+/// `refs/remotes/<remote>/HEAD` with the remote prefix stripped.
+///
+/// Falls back to `main` or `master` when the remote HEAD was never
+/// recorded (e.g. the remote was added rather than cloned), and to `None`
+/// when neither branch exists on the remote.
+pub fn default_branch(repo_path: &Path, remote: &str) -> Result<Option<String>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let prefix = format!("refs/remotes/{}/", remote);
+
+    if let Ok(head) = repo.find_reference(&format!("{}HEAD", prefix)) {
+        if let Some(branch) = head.symbolic_target().and_then(|t| t.strip_prefix(&prefix)) {
+            return Ok(Some(branch.to_string()));
+        }
+    }
+
+    for branch in ["main", "master"] {
+        if repo.find_reference(&format!("{}{}", prefix, branch)).is_ok() {
+            return Ok(Some(branch.to_string()));
+        }
+    }
+    Ok(None)
+}
+
+/// Lists the linked worktrees of a repository (the main worktree is not
+/// included).
+pub fn list_worktrees(repo_path: &Path) -> Result<Vec<GitWorktree>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let names = repo.worktrees()?;
+
+    let mut worktrees = Vec::new();
+    for name in names.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        let (locked, lock_reason) = match worktree.is_locked()? {
+            WorktreeLockStatus::Unlocked => (false, None),
+            WorktreeLockStatus::Locked(reason) => (true, reason.filter(|r| !r.is_empty())),
+        };
+        // A worktree whose directory is gone can't be opened; its HEAD is
+        // still recorded in the main repository's administrative files
+        let branch = match Repository::open_from_worktree(&worktree) {
+            Ok(wt_repo) => wt_repo.head().ok().and_then(|head| {
+                if head.is_branch() {
+                    head.shorthand().map(String::from)
+                } else {
+                    None
+                }
+            }),
+            Err(_) => recorded_branch(&repo, name),
+        };
+        worktrees.push(GitWorktree {
+            path: worktree.path().to_path_buf(),
+            branch,
+            locked,
+            lock_reason,
+            prunable: worktree.is_prunable(None)?,
+        });
+    }
+    Ok(worktrees)
+}
+
+/// Reads the branch a linked worktree has checked out from
+/// `.git/worktrees/<name>/HEAD`.
+fn recorded_branch(repo: &Repository, name: &str) -> Option<String> {
+    let head = std::fs::read_to_string(repo.path().join("worktrees").join(name).join("HEAD")).ok()?;
+    head.trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(String::from)
+}
+
+/// Unlocks the linked worktree at `worktree_path`, like
+/// `git worktree unlock`.
+///
+/// Returns `false` if no worktree is registered at that path.
+pub fn unlock_worktree(repo_path: &Path, worktree_path: &Path) -> Result<bool, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let names = repo.worktrees()?;
+    let wanted = worktree_path.canonicalize().unwrap_or_else(|_| worktree_path.to_path_buf());
+
+    for name in names.iter().flatten() {
+        let worktree = repo.find_worktree(name)?;
+        let path = worktree.path().canonicalize().unwrap_or_else(|_| worktree.path().to_path_buf());
+        if path == wanted {
+            worktree.unlock()?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Collects what `branch` adds on top of the first of `bases` that exists,
+/// like `git log base..branch` plus `git diff --numstat base...branch`.
+///
+/// Returns `None` if neither the branch nor any base resolves.
+pub fn branch_changes(
+    repo_path: &Path,
+    branch: &str,
+    bases: &[&str],
+) -> Result<Option<BranchChanges>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let Ok(branch_commit) = repo.revparse_single(branch).and_then(|o| o.peel_to_commit()) else {
+        return Ok(None);
+    };
+    let Some((base, base_commit)) = bases.iter().find_map(|base| {
+        let commit = repo.revparse_single(base).and_then(|o| o.peel_to_commit()).ok()?;
+        Some((base.to_string(), commit))
+    }) else {
+        return Ok(None);
+    };
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    revwalk.push(branch_commit.id())?;
+    revwalk.hide(base_commit.id())?;
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        commits.push(CommitSummary {
+            id: commit.id().to_string(),
+            summary: commit.summary().unwrap_or("").to_string(),
+            author: commit.author().name().unwrap_or("").to_string(),
+            time: commit.time().seconds(),
+        });
+    }
+
+    let merge_base = repo.find_commit(repo.merge_base(branch_commit.id(), base_commit.id())?)?;
+    let mut diff = repo.diff_tree_to_tree(
+        Some(&merge_base.tree()?),
+        Some(&branch_commit.tree()?),
+        None,
+    )?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    let mut files = Vec::new();
+    for (idx, delta) in diff.deltas().enumerate() {
+        let file = match delta.status() {
+            Delta::Deleted => delta.old_file(),
+            _ => delta.new_file(),
+        };
+        let path = file
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        // Binary files have no patch and count as zero lines
+        let (additions, deletions) = match Patch::from_diff(&diff, idx)? {
+            Some(patch) => {
+                let (_, additions, deletions) = patch.line_stats()?;
+                (additions, deletions)
+            }
+            None => (0, 0),
+        };
+        let status = match delta.status() {
+            Delta::Added => "added",
+            Delta::Deleted => "removed",
+            Delta::Modified => "modified",
+            Delta::Renamed => "renamed",
+            Delta::Copied => "copied",
+            _ => "changed",
+        };
+        files.push(FileChange {
+            path,
+            status,
+            additions,
+            deletions,
+        });
+    }
+
+    Ok(Some(BranchChanges {
+        base,
+        commits,
+        files,
+    }))
+}
+
+/// Merges `branch` into the first of `bases` that exists in memory, like
+/// `git merge-tree --write-tree base branch`, and returns the base that was
+/// used with the paths that would conflict. Neither the index nor any
+/// working tree is touched.
+///
+/// Returns `None` if neither the branch nor any base resolves.
+pub fn merge_conflicts(
+    repo_path: &Path,
+    branch: &str,
+    bases: &[&str],
+) -> Result<Option<(String, Vec<String>)>, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let Ok(branch_commit) = repo.revparse_single(branch).and_then(|o| o.peel_to_commit()) else {
+        return Ok(None);
+    };
+    let Some((base, base_commit)) = bases.iter().find_map(|base| {
+        let commit = repo.revparse_single(base).and_then(|o| o.peel_to_commit()).ok()?;
+        Some((base.to_string(), commit))
+    }) else {
+        return Ok(None);
+    };
+
+    let index = repo.merge_commits(&base_commit, &branch_commit, None)?;
+    let mut conflicts = Vec::new();
+    if index.has_conflicts() {
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+            if let Some(entry) = entry {
+                let path = String::from_utf8_lossy(&entry.path).to_string();
+                if !conflicts.contains(&path) {
+                    conflicts.push(path);
+                }
+            }
+        }
+    }
+    Ok(Some((base, conflicts)))
+}
+
+/// Returns the multi-step operation in progress in the working tree at
+/// `path` ("rebase", "merge", "cherry-pick", or "revert"), if any.
+pub fn operation_in_progress(path: &Path) -> Result<Option<&'static str>, git2::Error> {
+    let repo = Repository::open(path)?;
+    let operation = match repo.state() {
+        RepositoryState::Clean => None,
+        RepositoryState::Rebase
+        | RepositoryState::RebaseInteractive
+        | RepositoryState::RebaseMerge => Some("rebase"),
+        RepositoryState::Merge => Some("merge"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("cherry-pick"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some("revert"),
+        _ => None,
+    };
+    Ok(operation)
+}
+
+/// Lists the conflicted paths in the index of the working tree at `path`.
+pub fn conflicted_files(path: &Path) -> Result<Vec<String>, git2::Error> {
+    let repo = Repository::open(path)?;
+    let index = repo.index()?;
+    let mut files = Vec::new();
+    if index.has_conflicts() {
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+            if let Some(entry) = entry {
+                let file = String::from_utf8_lossy(&entry.path).to_string();
+                if !files.contains(&file) {
+                    files.push(file);
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Finds the conflict entry for `file`, if it is conflicted.
+fn find_conflict(index: &git2::Index, file: &str) -> Result<Option<git2::IndexConflict>, git2::Error> {
+    if !index.has_conflicts() {
+        return Ok(None);
+    }
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let matches = [&conflict.ancestor, &conflict.our, &conflict.their]
+            .iter()
+            .any(|entry| entry.as_ref().is_some_and(|e| e.path == file.as_bytes()));
+        if matches {
+            return Ok(Some(conflict));
+        }
+    }
+    Ok(None)
+}
+
+/// Reads the ancestor, ours, and theirs versions of a conflicted file.
+///
+/// Returns `None` if `file` is not conflicted.
+pub fn conflict_sides(path: &Path, file: &str) -> Result<Option<ConflictSides>, git2::Error> {
+    let repo = Repository::open(path)?;
+    let index = repo.index()?;
+    let Some(conflict) = find_conflict(&index, file)? else {
+        return Ok(None);
+    };
+
+    let read = |entry: Option<git2::IndexEntry>| -> Result<Option<String>, git2::Error> {
+        match entry {
+            Some(entry) => {
+                let blob = repo.find_blob(entry.id)?;
+                Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+            }
+            None => Ok(None),
+        }
+    };
+    Ok(Some(ConflictSides {
+        ancestor: read(conflict.ancestor)?,
+        ours: read(conflict.our)?,
+        theirs: read(conflict.their)?,
+    }))
+}
+
+/// Resolves a conflicted file by taking one side wholesale, like
+/// `git checkout --ours/--theirs file` followed by `git add file`. Taking a
+/// side that deleted the file deletes it.
+///
+/// Returns `false` if `file` is not conflicted.
+pub fn resolve_conflict(path: &Path, file: &str, ours: bool) -> Result<bool, git2::Error> {
+    let repo = Repository::open(path)?;
+    let mut index = repo.index()?;
+    let Some(conflict) = find_conflict(&index, file)? else {
+        return Ok(false);
+    };
+
+    let side = if ours { conflict.our } else { conflict.their };
+    let target = path.join(file);
+    match side {
+        Some(entry) => {
+            let blob = repo.find_blob(entry.id)?;
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+            }
+            std::fs::write(&target, blob.content())
+                .map_err(|e| git2::Error::from_str(&e.to_string()))?;
+            index.add_path(Path::new(file))?;
+        }
+        None => {
+            if target.exists() {
+                std::fs::remove_file(&target).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+            }
+            index.remove_path(Path::new(file))?;
+        }
+    }
+    index.write()?;
+    Ok(true)
+}
+
+/// Stages changes in the working tree at `path` and commits them on HEAD,
+/// like `git add -A [paths]` followed by `git commit -m message`.
+///
+/// `paths` limits staging to the given repository-relative paths (deleted
+/// files are staged as removals); `None` stages everything. The author is
+/// the identity in `options`, or the repository's git config. Returns the
+/// new commit's ID, or `None` when nothing changed relative to HEAD.
+pub fn commit(
+    path: &Path,
+    paths: Option<&[String]>,
+    message: &str,
+    options: &CommitOptions,
+) -> Result<Option<String>, git2::Error> {
+    let repo = Repository::open(path)?;
+    let mut index = repo.index()?;
+    match paths {
+        Some(paths) => {
+            for file in paths {
+                let file = Path::new(file);
+                if path.join(file).is_dir() {
+                    index.add_all([file], IndexAddOption::DEFAULT, None)?;
+                    index.update_all([file], None)?;
+                } else if path.join(file).exists() {
+                    index.add_path(file)?;
+                } else {
+                    index.remove_path(file)?;
+                }
+            }
+        }
+        None => {
+            index.add_all(["*"], IndexAddOption::DEFAULT, None)?;
+            index.update_all(["*"], None)?;
+        }
+    }
+    index.write()?;
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit()?),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+        Err(e) => return Err(e),
+    };
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree.id()) {
+        return Ok(None);
+    }
+
+    let signature = match &options.identity {
+        Some((name, email)) => git2::Signature::now(name, email)?,
+        None => repo.signature()?,
+    };
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let Some(signing) = &options.signing else {
+        let oid = repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)?;
+        return Ok(Some(oid.to_string()));
+    };
+
+    let buffer = repo.commit_create_buffer(&signature, &signature, message, &tree, &parents)?;
+    let content = buffer
+        .as_str()
+        .ok_or_else(|| git2::Error::from_str("Commit is not valid UTF-8"))?;
+    let oid = repo.commit_signed(content, &sign_buffer(signing, content)?, None)?;
+
+    // commit_signed does not move HEAD
+    let log_message = format!("commit: {}", message.lines().next().unwrap_or(""));
+    match repo.find_reference("HEAD")?.symbolic_target() {
+        Some(branch) => {
+            repo.reference(branch, oid, true, &log_message)?;
+        }
+        None => repo.set_head_detached(oid)?,
+    }
+    Ok(Some(oid.to_string()))
+}
+
+/// Signs a commit buffer the way git does for `gpg.format` openpgp or ssh,
+/// returning the armored signature.
+fn sign_buffer(signing: &Signing, buffer: &str) -> Result<String, git2::Error> {
+    let mut command = match signing {
+        Signing::Gpg(key) => {
+            let mut command = Command::new("gpg");
+            command.args(["--detach-sign", "--armor"]);
+            if let Some(key) = key {
+                command.args(["--local-user", key]);
+            }
+            command
+        }
+        Signing::Ssh(key) => {
+            let mut command = Command::new("ssh-keygen");
+            command.args(["-Y", "sign", "-n", "git", "-f"]).arg(key);
+            command
+        }
+    };
+    let failed = |e: String| git2::Error::from_str(&format!("Failed to sign commit: {}", e));
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .logged()
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(buffer.as_bytes())
+            .map_err(|e| failed(e.to_string()))?;
+    }
+    let output = child.wait_with_output().map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| failed(e.to_string()))
+}
+
+/// Creates an annotated tag named `name` on `target`, like
+/// `git tag -a name target -m message`. The tagger is taken from the
+/// repository's git config. Fails if the tag already exists.
+///
+/// Returns the ID of the tagged commit.
+pub fn create_tag(
+    repo_path: &Path,
+    name: &str,
+    target: &str,
+    message: &str,
+) -> Result<String, git2::Error> {
+    let repo = Repository::open(repo_path)?;
+    let commit = repo.revparse_single(target)?.peel_to_commit()?;
+    let tagger = repo.signature()?;
+    repo.tag(name, commit.as_object(), &tagger, message, false)?;
+    Ok(commit.id().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, WorktreeAddOptions};
+
+    /// Commits the current state of `file` on HEAD.
+    fn commit(repo: &Repository, file: &str, message: &str) {
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_branch_queries() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+        commit(&repo, "a.txt", "first");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("bd-1", &head, false).unwrap();
+        repo.set_head("refs/heads/bd-1").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "two").unwrap();
+        commit(&repo, "a.txt", "second");
+
+        assert!(revision_exists(dir.path(), "bd-1").unwrap());
+        assert!(!revision_exists(dir.path(), "bd-2").unwrap());
+        assert_eq!(
+            ahead_behind(dir.path(), "bd-1", &["master", "main"]).unwrap(),
+            Some((1, 0))
+        );
+        assert_eq!(ahead_behind(dir.path(), "main", &["bd-1"]).unwrap(), Some((0, 1)));
+        assert_eq!(ahead_behind(dir.path(), "bd-2", &["main"]).unwrap(), None);
+
+        assert!(!is_dirty(dir.path()).unwrap());
+        std::fs::write(dir.path().join("b.txt"), "untracked").unwrap();
+        assert!(is_dirty(dir.path()).unwrap());
+
+        assert!(!has_remote(dir.path()).unwrap());
+        assert_eq!(remote_url(dir.path(), "origin").unwrap(), None);
+        repo.remote("origin", "https://example.com/repo.git").unwrap();
+        assert!(has_remote(dir.path()).unwrap());
+        assert_eq!(
+            remote_url(dir.path(), "origin").unwrap().as_deref(),
+            Some("https://example.com/repo.git")
+        );
+    }
+
+    #[test]
+    fn test_default_branch() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+        commit(&repo, "a.txt", "first");
+        let head = repo.head().unwrap().peel_to_commit().unwrap().id();
+
+        assert_eq!(default_branch(dir.path(), "origin").unwrap(), None);
+
+        repo.reference("refs/remotes/origin/master", head, false, "test").unwrap();
+        assert_eq!(
+            default_branch(dir.path(), "origin").unwrap().as_deref(),
+            Some("master")
+        );
+
+        repo.reference("refs/remotes/origin/develop", head, false, "test").unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/develop",
+            false,
+            "test",
+        )
+        .unwrap();
+        assert_eq!(
+            default_branch(dir.path(), "origin").unwrap().as_deref(),
+            Some("develop")
+        );
+    }
+
+    #[test]
+    fn test_commits_between() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+        commit(&repo, "a.txt", "first");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("bd-1", &head, false).unwrap();
+        repo.set_head("refs/heads/bd-1").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "two").unwrap();
+        commit(&repo, "a.txt", "second");
+        std::fs::write(dir.path().join("a.txt"), "three").unwrap();
+        commit(&repo, "a.txt", "third");
+
+        let commits = commits_between(dir.path(), "main", "bd-1").unwrap();
+        assert_eq!(commits.len(), 2);
+        let tip = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(commits[1], tip.id().to_string());
+        assert_eq!(commits[0], tip.parent_id(0).unwrap().to_string());
+        assert!(commits_between(dir.path(), "bd-1", "main").unwrap().is_empty());
+
+        assert_eq!(
+            resolve_commit(dir.path(), &commits[0][..8]).unwrap(),
+            Some(commits[0].clone())
+        );
+        assert_eq!(resolve_commit(dir.path(), "nope").unwrap(), None);
+    }
+
+    #[test]
+    fn test_file_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let doc = Path::new(".designs/epic.md");
+        assert!(file_history(dir.path(), doc, 10).unwrap().is_empty());
+
+        std::fs::create_dir(dir.path().join(".designs")).unwrap();
+        std::fs::write(dir.path().join(doc), "v1").unwrap();
+        commit(&repo, ".designs/epic.md", "add spec");
+        std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+        commit(&repo, "a.txt", "unrelated");
+        std::fs::write(dir.path().join(doc), "v2").unwrap();
+        commit(&repo, ".designs/epic.md", "revise spec");
+
+        let history = file_history(dir.path(), doc, 10).unwrap();
+        let summaries: Vec<&str> = history.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, vec!["revise spec", "add spec"]);
+        assert_eq!(history[0].author, "Test");
+        assert_eq!(file_history(dir.path(), doc, 1).unwrap().len(), 1);
+        assert!(file_history(dir.path(), Path::new("missing.md"), 10)
+            .unwrap()
+            .is_empty());
+
+        let (id, content) = file_at_revision(dir.path(), &history[1].id[..8], doc)
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, history[1].id);
+        assert_eq!(content, "v1");
+        assert_eq!(
+            file_at_revision(dir.path(), "HEAD", doc).unwrap().unwrap().1,
+            "v2"
+        );
+        assert!(file_at_revision(dir.path(), "nope", doc).unwrap().is_none());
+        assert!(file_at_revision(dir.path(), "HEAD", Path::new(".designs"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_branch_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\ntwo\n").unwrap();
+        commit(&repo, "a.txt", "first");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("bd-1", &head, false).unwrap();
+        repo.set_head("refs/heads/bd-1").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\n2\nthree\n").unwrap();
+        commit(&repo, "a.txt", "edit a");
+        std::fs::write(dir.path().join("b.txt"), "new\n").unwrap();
+        commit(&repo, "b.txt", "add b\n\nMore detail");
+
+        let changes = branch_changes(dir.path(), "bd-1", &["master", "main"])
+            .unwrap()
+            .unwrap();
+        assert_eq!(changes.base, "main");
+        let summaries: Vec<_> = changes.commits.iter().map(|c| c.summary.as_str()).collect();
+        assert_eq!(summaries, ["add b", "edit a"]);
+        assert_eq!(changes.commits[0].author, "Test");
+        assert_eq!(
+            changes.files,
+            vec![
+                FileChange {
+                    path: "a.txt".to_string(),
+                    status: "modified",
+                    additions: 2,
+                    deletions: 1,
+                },
+                FileChange {
+                    path: "b.txt".to_string(),
+                    status: "added",
+                    additions: 1,
+                    deletions: 0,
+                },
+            ]
+        );
+
+        assert_eq!(branch_changes(dir.path(), "bd-2", &["main"]).unwrap(), None);
+        assert_eq!(branch_changes(dir.path(), "bd-1", &["develop"]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "one\n").unwrap();
+        commit(&repo, "a.txt", "first");
+        commit(&repo, "b.txt", "second");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("bd-1", &head, false).unwrap();
+        repo.set_head("refs/heads/bd-1").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "branch\n").unwrap();
+        commit(&repo, "a.txt", "edit a on branch");
+
+        // Clean until main touches the same file
+        assert_eq!(
+            merge_conflicts(dir.path(), "bd-1", &["main"]).unwrap(),
+            Some(("main".to_string(), vec![]))
+        );
+
+        repo.set_head("refs/heads/main").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "main\n").unwrap();
+        commit(&repo, "a.txt", "edit a on main");
+        std::fs::write(dir.path().join("b.txt"), "main\n").unwrap();
+        commit(&repo, "b.txt", "edit b on main");
+
+        let head_before = repo.head().unwrap().target();
+        assert_eq!(
+            merge_conflicts(dir.path(), "bd-1", &["origin/main", "main"]).unwrap(),
+            Some(("main".to_string(), vec!["a.txt".to_string()]))
+        );
+        assert_eq!(repo.head().unwrap().target(), head_before);
+        assert_eq!(merge_conflicts(dir.path(), "bd-2", &["main"]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_conflict_resolution() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "base\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "base\n").unwrap();
+        commit(&repo, "a.txt", "first");
+        commit(&repo, "b.txt", "second");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("bd-1", &head, false).unwrap();
+        repo.set_head("refs/heads/bd-1").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "branch\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "branch\n").unwrap();
+        commit(&repo, "a.txt", "edit a on branch");
+        commit(&repo, "b.txt", "edit b on branch");
+
+        repo.set_head("refs/heads/main").unwrap();
+        repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+            .unwrap();
+        std::fs::write(dir.path().join("a.txt"), "main\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "main\n").unwrap();
+        commit(&repo, "a.txt", "edit a on main");
+        commit(&repo, "b.txt", "edit b on main");
+
+        assert_eq!(operation_in_progress(dir.path()).unwrap(), None);
+        assert!(conflicted_files(dir.path()).unwrap().is_empty());
+
+        let branch = repo.find_branch("bd-1", git2::BranchType::Local).unwrap();
+        let annotated = repo.reference_to_annotated_commit(branch.get()).unwrap();
+        repo.merge(&[&annotated], None, None).unwrap();
+
+        assert_eq!(operation_in_progress(dir.path()).unwrap(), Some("merge"));
+        assert_eq!(conflicted_files(dir.path()).unwrap(), ["a.txt", "b.txt"]);
+        assert_eq!(
+            conflict_sides(dir.path(), "a.txt").unwrap(),
+            Some(ConflictSides {
+                ancestor: Some("base\n".to_string()),
+                ours: Some("main\n".to_string()),
+                theirs: Some("branch\n".to_string()),
+            })
+        );
+        assert_eq!(conflict_sides(dir.path(), "c.txt").unwrap(), None);
+
+        assert!(resolve_conflict(dir.path(), "a.txt", true).unwrap());
+        assert!(resolve_conflict(dir.path(), "b.txt", false).unwrap());
+        assert!(!resolve_conflict(dir.path(), "b.txt", false).unwrap());
+        assert!(conflicted_files(dir.path()).unwrap().is_empty());
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "main\n");
+        assert_eq!(std::fs::read_to_string(dir.path().join("b.txt")).unwrap(), "branch\n");
+    }
+
+    #[test]
+    fn test_list_worktrees() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+        commit(&repo, "a.txt", "first");
+        assert!(list_worktrees(dir.path()).unwrap().is_empty());
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let branch = repo.branch("bd-1", &head, false).unwrap();
+        let worktree_path = dir.path().join(".worktrees").join("bd-1");
+        std::fs::create_dir_all(worktree_path.parent().unwrap()).unwrap();
+        let mut options = WorktreeAddOptions::new();
+        options.reference(Some(branch.get()));
+        repo.worktree("bd-1", &worktree_path, Some(&options)).unwrap();
+
+        let worktrees = list_worktrees(dir.path()).unwrap();
+        assert_eq!(worktrees.len(), 1);
+        assert_eq!(worktrees[0].branch.as_deref(), Some("bd-1"));
+        assert!(worktrees[0].path.ends_with(".worktrees/bd-1"));
+        assert!(!worktrees[0].locked);
+        assert!(!worktrees[0].prunable);
+        assert!(!is_dirty(&worktree_path).unwrap());
+
+        let worktree = repo.find_worktree("bd-1").unwrap();
+        worktree.lock(Some("crashed mid-create")).unwrap();
+        let worktrees = list_worktrees(dir.path()).unwrap();
+        assert!(worktrees[0].locked);
+        assert_eq!(worktrees[0].lock_reason.as_deref(), Some("crashed mid-create"));
+        // Locked worktrees are never prunable, even once their directory is gone
+        std::fs::remove_dir_all(&worktree_path).unwrap();
+        assert!(!list_worktrees(dir.path()).unwrap()[0].prunable);
+
+        assert!(unlock_worktree(dir.path(), &worktree_path).unwrap());
+        assert!(!unlock_worktree(dir.path(), &dir.path().join("elsewhere")).unwrap());
+        let worktrees = list_worktrees(dir.path()).unwrap();
+        assert!(!worktrees[0].locked);
+        assert!(worktrees[0].prunable);
+        // The branch is still known from the administrative files
+        assert_eq!(worktrees[0].branch.as_deref(), Some("bd-1"));
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let detached_path = dir.path().join(".worktrees").join("bd-2");
+        repo.worktree("bd-2", &detached_path, None).unwrap();
+        let detached = Repository::open(&detached_path).unwrap();
+        detached.set_head_detached(head.id()).unwrap();
+        let worktrees = list_worktrees(dir.path()).unwrap();
+        let detached = worktrees.iter().find(|w| w.path.ends_with("bd-2")).unwrap();
+        assert_eq!(detached.branch, None);
+        assert!(!detached.prunable);
+    }
+
+    #[test]
+    fn test_create_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+        commit(&repo, "a.txt", "first");
+
+        let id = create_tag(dir.path(), "v1.0.0", "HEAD", "First release").unwrap();
+        assert_eq!(id, repo.head().unwrap().peel_to_commit().unwrap().id().to_string());
+        let tag = repo
+            .revparse_single("refs/tags/v1.0.0")
+            .unwrap()
+            .peel_to_tag()
+            .unwrap();
+        assert_eq!(tag.message(), Some("First release"));
+
+        // Existing tags are not moved
+        assert!(create_tag(dir.path(), "v1.0.0", "HEAD", "Again").is_err());
+    }
+
+    #[test]
+    fn test_commit() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one").unwrap();
+        commit(&repo, "a.txt", "first");
+        let options = CommitOptions::default();
+
+        // Only the selected path is committed
+        std::fs::write(dir.path().join("a.txt"), "two").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "new").unwrap();
+        let paths = vec!["a.txt".to_string()];
+        let id = super::commit(dir.path(), Some(&paths), "update a", &options).unwrap();
+        assert!(id.is_some());
+        assert_eq!(
+            repo.head().unwrap().peel_to_commit().unwrap().message(),
+            Some("update a")
+        );
+        assert!(is_dirty(dir.path()).unwrap());
+
+        // Everything else, including deletions
+        std::fs::remove_file(dir.path().join("a.txt")).unwrap();
+        assert!(super::commit(dir.path(), None, "the rest", &options).unwrap().is_some());
+        assert!(!is_dirty(dir.path()).unwrap());
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        assert!(tree.get_name("a.txt").is_none());
+        assert!(tree.get_name("b.txt").is_some());
+
+        assert_eq!(super::commit(dir.path(), None, "empty", &options).unwrap(), None);
+    }
+
+    #[test]
+    fn test_commit_identity_and_signing() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        let key = dir.path().join("signing_key");
+        let generated = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key)
+            .status();
+        if !generated.is_ok_and(|s| s.success()) {
+            // ssh-keygen is not installed
+            return;
+        }
+        std::fs::write(dir.path().join(".gitignore"), "signing_key*\n").unwrap();
+
+        let options = CommitOptions {
+            identity: Some(("Board Bot".to_string(), "bot@example.com".to_string())),
+            signing: Some(Signing::Ssh(key)),
+        };
+        let id = super::commit(dir.path(), None, "signed", &options)
+            .unwrap()
+            .unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.id().to_string(), id);
+        assert_eq!(head.author().name(), Some("Board Bot"));
+        assert_eq!(head.committer().email(), Some("bot@example.com"));
+        let (signature, _) = repo.extract_signature(&head.id(), None).unwrap();
+        assert!(signature
+            .as_str()
+            .unwrap()
+            .starts_with("-----BEGIN SSH SIGNATURE-----"));
+    }
+}
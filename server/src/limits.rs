@@ -0,0 +1,117 @@
+//! Request timeouts and body size limits.
+//!
+//! API requests get [`REQUEST_TIMEOUT`] to respond and may send at most
+//! [`MAX_BODY_SIZE`], so a hung subprocess or a giant POST can't tie up the
+//! server. A request over the size limit gets `413 Payload Too Large`; one
+//! that runs out of time gets `408 Request Timeout`, and the handler is
+//! dropped along with any subprocess spawned with `kill_on_drop`.
+//!
+//! Routes that need more opt out of the defaults: uploads with [`upload`],
+//! and git operations that talk to the network or run hooks with
+//! [`long_running`]. Event streams (SSE and WebSocket) only have to start
+//! within the timeout; the stream itself can stay open.
+
+use axum::{extract::DefaultBodyLimit, routing::MethodRouter, Router};
+use std::time::Duration;
+use tower::{
+    layer::util::{Identity, Stack},
+    ServiceBuilder,
+};
+use tower_http::{limit::RequestBodyLimitLayer, timeout::TimeoutLayer};
+
+/// How long an API request may take to respond.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long git operations that fetch, push, or run hooks may take.
+pub const LONG_REQUEST_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Largest request body accepted, unless a route allows more. Extractors'
+/// own limit is disabled wherever this applies, so it is the only one.
+pub const MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+
+/// Headroom over an upload's file size limit for multipart framing.
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
+/// Applies the default timeout and body size limit to the routes in
+/// `router`. Routes added afterwards keep their own limits.
+pub fn with_defaults<S>(router: Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router.layer(layers(REQUEST_TIMEOUT, MAX_BODY_SIZE))
+}
+
+/// Limits for a route accepting files of up to `max_file_size` bytes.
+pub fn upload<S>(route: MethodRouter<S>, max_file_size: usize) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route.layer(layers(LONG_REQUEST_TIMEOUT, max_file_size + MULTIPART_OVERHEAD))
+}
+
+/// Limits for a route running slow git or network operations.
+pub fn long_running<S>(route: MethodRouter<S>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route.layer(layers(LONG_REQUEST_TIMEOUT, MAX_BODY_SIZE))
+}
+
+/// A body size limit around a timeout.
+type Limits =
+    ServiceBuilder<Stack<TimeoutLayer, Stack<DefaultBodyLimit, Stack<RequestBodyLimitLayer, Identity>>>>;
+
+fn layers(timeout: Duration, max_body_size: usize) -> Limits {
+    ServiceBuilder::new()
+        .layer(RequestBodyLimitLayer::new(max_body_size))
+        .layer(DefaultBodyLimit::disable())
+        .layer(TimeoutLayer::new(timeout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, http::StatusCode, routing::post};
+    use tower::ServiceExt;
+
+    fn post_body(uri: &str, size: usize) -> Request<Body> {
+        Request::builder()
+            .method("POST")
+            .uri(uri)
+            .body(Body::from(vec![b'x'; size]))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_body_limits() {
+        let echo_len = |body: String| async move { body.len().to_string() };
+        let app = with_defaults(Router::new().route("/small", post(echo_len)))
+            .route("/upload", upload(post(echo_len), 4 * 1024 * 1024));
+
+        let response = app.clone().oneshot(post_body("/small", 1024)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.clone().oneshot(post_body("/small", MAX_BODY_SIZE + 1)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Routes with their own limit aren't held to the default
+        let response = app.clone().oneshot(post_body("/upload", 3 * 1024 * 1024)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = app.oneshot(post_body("/upload", 5 * 1024 * 1024)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timeouts() {
+        let slow = || async {
+            tokio::time::sleep(REQUEST_TIMEOUT * 2).await;
+            "done"
+        };
+        let app = with_defaults(Router::new().route("/slow", post(slow)))
+            .route("/long", long_running(post(slow)));
+
+        let response = app.clone().oneshot(post_body("/slow", 0)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::REQUEST_TIMEOUT);
+        let response = app.oneshot(post_body("/long", 0)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
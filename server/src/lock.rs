@@ -0,0 +1,165 @@
+//! Advisory file locking for read-modify-write cycles.
+//!
+//! Mutations take an exclusive lock on a sibling `<file>.lock` before
+//! reading, and hold it until the rewritten file is in place. A sibling
+//! file is locked (rather than the data file itself) because writes replace
+//! the data file via rename, which would orphan a lock held on the old inode.
+//!
+//! The lock serializes the server's own writers: concurrent requests,
+//! background tasks, and other server processes on the same project. The
+//! `bd` CLI does not take it, so a `bd` command that rewrites a file while
+//! the server holds the lock can still lose one of the two changes.
+
+use axum::http::StatusCode;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Number of attempts made to acquire a lock before giving up.
+const LOCK_ATTEMPTS: u32 = 6;
+
+/// Delay before the first retry; doubled after each failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+
+/// File locking error types
+#[derive(Error, Debug)]
+pub enum LockError {
+    #[error("{0} is being changed by another request or process, try again shortly")]
+    Busy(String),
+    #[error("Failed to lock {0}: {1}")]
+    Io(String, std::io::Error),
+}
+
+impl LockError {
+    /// HTTP status code to report for this error.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            LockError::Busy(_) => StatusCode::CONFLICT,
+            LockError::Io(..) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// An exclusive advisory lock, released when dropped.
+#[derive(Debug)]
+pub struct FileLock {
+    _file: File,
+}
+
+/// Returns the path of the lock file guarding `path`.
+fn lock_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".lock");
+    path.with_file_name(name)
+}
+
+/// Opens (creating if needed) the lock file guarding `path`.
+fn open_lock_file(path: &Path) -> Result<File, LockError> {
+    OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path(path))
+        .map_err(|e| LockError::Io(path.display().to_string(), e))
+}
+
+/// Makes one attempt at the lock; `false` if it is held elsewhere.
+fn try_lock(file: &File, path: &Path) -> Result<bool, LockError> {
+    match file.try_lock() {
+        Ok(()) => Ok(true),
+        Err(TryLockError::WouldBlock) => Ok(false),
+        Err(TryLockError::Error(e)) => Err(LockError::Io(path.display().to_string(), e)),
+    }
+}
+
+/// Reports that the lock could not be acquired in time.
+fn busy(path: &Path) -> LockError {
+    tracing::warn!("Timed out waiting for lock on {}", path.display());
+    LockError::Busy(path.display().to_string())
+}
+
+/// Acquires an exclusive lock guarding `path`, retrying with backoff.
+///
+/// Waits between attempts without blocking the runtime. Returns
+/// [`LockError::Busy`] if the lock is still held after all attempts.
+pub async fn lock_exclusive(path: &Path) -> Result<FileLock, LockError> {
+    let file = open_lock_file(path)?;
+    let mut backoff = INITIAL_BACKOFF;
+    for attempt in 1..=LOCK_ATTEMPTS {
+        if try_lock(&file, path)? {
+            return Ok(FileLock { _file: file });
+        }
+        if attempt < LOCK_ATTEMPTS {
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+    Err(busy(path))
+}
+
+/// Like [`lock_exclusive`], but sleeps the thread between attempts. Only
+/// for code already running on a blocking thread (e.g. in `spawn_blocking`).
+pub fn lock_exclusive_blocking(path: &Path) -> Result<FileLock, LockError> {
+    let file = open_lock_file(path)?;
+    let mut backoff = INITIAL_BACKOFF;
+    for attempt in 1..=LOCK_ATTEMPTS {
+        if try_lock(&file, path)? {
+            return Ok(FileLock { _file: file });
+        }
+        if attempt < LOCK_ATTEMPTS {
+            std::thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+    Err(busy(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_path() {
+        let path = Path::new("/project/.beads/issues.jsonl");
+        assert_eq!(
+            lock_path(path),
+            PathBuf::from("/project/.beads/issues.jsonl.lock")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lock_is_exclusive_until_dropped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("issues.jsonl");
+
+        let lock = lock_exclusive(&path).await.unwrap();
+        let err = lock_exclusive(&path).await.unwrap_err();
+        assert!(matches!(err, LockError::Busy(_)));
+        assert_eq!(err.status_code(), StatusCode::CONFLICT);
+        assert!(matches!(
+            lock_exclusive_blocking(&path),
+            Err(LockError::Busy(_))
+        ));
+
+        drop(lock);
+        assert!(lock_exclusive(&path).await.is_ok());
+        assert!(lock_exclusive_blocking(&path).is_ok());
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_waiting_for_lock_does_not_block_runtime() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("issues.jsonl");
+        let lock = lock_exclusive(&path).await.unwrap();
+
+        // On a single-threaded runtime, the release can only happen while
+        // the waiter is sleeping if that sleep yields to the runtime
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(lock);
+        });
+        assert!(lock_exclusive(&path).await.is_ok());
+        release.await.unwrap();
+    }
+}
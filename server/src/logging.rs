@@ -0,0 +1,196 @@
+//! Log output.
+//!
+//! Logs are human-readable text by default. `--log-format json` writes one
+//! JSON object per line instead, for shipping to Loki, ELK, and the like.
+//! Either way, events logged while handling an API request carry the
+//! request's `route` and `project` (from the `path`, `repo_path`, or
+//! `project_path` query parameter), and subprocesses are logged at debug
+//! level with the `command` they run.
+//!
+//! Verbosity comes from `--log-level`, else `RUST_LOG` (full `EnvFilter`
+//! directives, e.g. `beads_server=debug,info`), else `info`, and can be
+//! changed while running with `PUT /api/v1/admin/log-level`.
+
+use axum::{
+    extract::{MatchedPath, Query, Request},
+    middleware::Next,
+    response::Response,
+};
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing::{debug, info_span, warn, Instrument, Level};
+use tracing_subscriber::{
+    filter::LevelFilter, fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter,
+    Registry,
+};
+
+/// Format of log output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Handle for swapping the filter of the global subscriber.
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Installs the global subscriber, logging at `level` if given.
+pub fn init(level: Option<Level>, format: LogFormat) {
+    let from_env = level.is_none().then(EnvFilter::try_from_default_env);
+    let filter = match (level, &from_env) {
+        (Some(level), _) => EnvFilter::default().add_directive(LevelFilter::from_level(level).into()),
+        (None, Some(Ok(filter))) => filter.clone(),
+        (None, _) => EnvFilter::new("info"),
+    };
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let (text, json) = match format {
+        LogFormat::Text => (Some(fmt::layer()), None),
+        LogFormat::Json => (
+            None,
+            Some(fmt::layer().json().with_current_span(true).with_span_list(false)),
+        ),
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(text)
+        .with(json)
+        .try_init()
+        .expect("Failed to set tracing subscriber");
+    let _ = FILTER.set(handle);
+
+    if let Some(Err(e)) = from_env {
+        if std::env::var_os(EnvFilter::DEFAULT_ENV).is_some() {
+            warn!("Ignoring invalid {}: {}", EnvFilter::DEFAULT_ENV, e);
+        }
+    }
+}
+
+/// The filter currently applied, as `EnvFilter` directives.
+pub fn current_filter() -> Option<String> {
+    FILTER.get()?.with_current(|filter| filter.to_string()).ok()
+}
+
+/// Replaces the filter with `directives` (a level such as `debug`, or
+/// `EnvFilter` directives), returning the new filter.
+pub fn set_filter(directives: &str) -> Result<String, String> {
+    let filter = parse_filter(directives)?;
+    let applied = filter.to_string();
+    FILTER
+        .get()
+        .ok_or("Logging is not initialized")?
+        .reload(filter)
+        .map_err(|e| format!("Failed to change the log filter: {}", e))?;
+    Ok(applied)
+}
+
+fn parse_filter(directives: &str) -> Result<EnvFilter, String> {
+    if directives.trim().is_empty() {
+        return Err("Log filter must not be empty".to_string());
+    }
+    EnvFilter::builder()
+        .parse(directives)
+        .map_err(|e| format!("Invalid log filter '{}': {}", directives, e))
+}
+
+/// Runs the request inside a `request` span with its method, route, and
+/// project.
+pub async fn request_span(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let project = project_param(&request).unwrap_or_default();
+    let span = info_span!(
+        "request",
+        method = %request.method(),
+        route = %route,
+        project = %project,
+    );
+    next.run(request).instrument(span).await
+}
+
+/// Returns the project a request is about, from its query string.
+fn project_param(request: &Request) -> Option<String> {
+    let Query(mut params) = Query::<HashMap<String, String>>::try_from_uri(request.uri()).ok()?;
+    ["path", "repo_path", "project_path"]
+        .into_iter()
+        .find_map(|name| params.remove(name))
+}
+
+/// Logs a subprocess before it runs.
+pub trait LogCommand {
+    /// Logs the command line and working directory at debug level.
+    fn logged(&mut self) -> &mut Self;
+}
+
+impl LogCommand for std::process::Command {
+    fn logged(&mut self) -> &mut Self {
+        log_command(self);
+        self
+    }
+}
+
+impl LogCommand for tokio::process::Command {
+    fn logged(&mut self) -> &mut Self {
+        log_command(self.as_std());
+        self
+    }
+}
+
+fn log_command(command: &std::process::Command) {
+    debug!(
+        command = %command_line(command),
+        cwd = %command.get_current_dir().map(|d| d.display().to_string()).unwrap_or_default(),
+        "Running subprocess"
+    );
+}
+
+/// The program and its arguments, space-separated.
+fn command_line(command: &std::process::Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_command_line() {
+        let mut command = std::process::Command::new("git");
+        command.args(["rebase", "origin/main"]);
+        assert_eq!(command_line(&command), "git rebase origin/main");
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(parse_filter("debug").unwrap().to_string(), "debug");
+        assert!(parse_filter("beads_server=trace,info").is_ok());
+        assert!(parse_filter("beads_server=loud").is_err());
+        assert!(parse_filter(" ").is_err());
+    }
+
+    #[test]
+    fn test_project_param() {
+        let request = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+        assert_eq!(
+            project_param(&request("/api/v1/beads?path=%2Fhome%2Fann%2Fapp")).as_deref(),
+            Some("/home/ann/app")
+        );
+        assert_eq!(
+            project_param(&request("/api/v1/git/pr-status?repo_path=/r&bead_id=b-1")).as_deref(),
+            Some("/r")
+        );
+        assert_eq!(project_param(&request("/api/v1/projects")), None);
+    }
+}
@@ -3,102 +3,74 @@
 //! An Axum-based HTTP server that serves the beads-kanban-ui frontend
 //! and provides API endpoints for backend functionality.
 
+mod args;
+#[cfg(feature = "frontend")]
+mod assets;
+mod auth;
 mod db;
+mod error;
+mod git_backend;
+mod limits;
+mod lock;
+mod logging;
+mod oidc;
 mod routes;
+mod shutdown;
+mod tls;
+mod update;
 
+use args::Cli;
 use axum::{
-    body::Body,
-    http::{header, Request, Response, StatusCode},
-    response::IntoResponse,
-    routing::{delete, get, post, put},
+    http::header,
+    middleware,
+    routing::{delete, get, patch, post, put},
     Router,
 };
-use rust_embed::Embed;
-use std::env;
+use clap::Parser;
 use std::sync::Arc;
+use tower_http::catch_panic::CatchPanicLayer;
 use tower_http::cors::{Any, CorsLayer};
-use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
-
-/// Embedded static files from the Next.js build output.
-#[derive(Embed)]
-#[folder = "../out/"]
-struct Assets;
-
-/// Serves embedded static files, with fallback to index.html for SPA routing.
-async fn serve_static(req: Request<Body>) -> impl IntoResponse {
-    let path = req.uri().path().trim_start_matches('/');
-
-    // Try the exact path first
-    if let Some(content) = Assets::get(path) {
-        let mime = mime_guess::from_path(path).first_or_octet_stream();
-        return Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, mime.as_ref())
-            .body(Body::from(content.data.into_owned()))
-            .unwrap();
-    }
-
-    // Try with .html extension (for Next.js static export)
-    let html_path = format!("{}.html", path);
-    if let Some(content) = Assets::get(&html_path) {
-        return Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "text/html")
-            .body(Body::from(content.data.into_owned()))
-            .unwrap();
-    }
+use tracing::info;
 
-    // Try index.html in subdirectory
-    let index_path = if path.is_empty() {
-        "index.html".to_string()
-    } else {
-        format!("{}/index.html", path)
-    };
-    if let Some(content) = Assets::get(&index_path) {
-        return Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "text/html")
-            .body(Body::from(content.data.into_owned()))
-            .unwrap();
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    if cli.update {
+        match update::run().await {
+            Ok(message) => println!("{}", message),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
     }
 
-    // Fallback to root index.html for SPA client-side routing
-    if let Some(content) = Assets::get("index.html") {
-        return Response::builder()
-            .status(StatusCode::OK)
-            .header(header::CONTENT_TYPE, "text/html")
-            .body(Body::from(content.data.into_owned()))
-            .unwrap();
-    }
+    let mut settings = cli.settings().unwrap_or_else(|e| {
+        eprintln!("error: {}", e);
+        std::process::exit(2);
+    });
 
-    // 404 if nothing found
-    Response::builder()
-        .status(StatusCode::NOT_FOUND)
-        .body(Body::from("Not Found"))
-        .unwrap()
-}
-
-#[tokio::main]
-async fn main() {
     // Initialize tracing subscriber for logging
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber)
-        .expect("Failed to set tracing subscriber");
-
-    // Parse port from environment variable, default to 3008
-    let port: u16 = env::var("PORT")
-        .ok()
-        .and_then(|p| p.parse().ok())
-        .unwrap_or(3008);
+    logging::init(settings.log_level, settings.log_format);
+    routes::diagnostics::mark_started();
 
     // Configure CORS for development
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([
+            header::ETAG,
+            header::LINK,
+            header::CONTENT_RANGE,
+            header::HeaderName::from_static("deprecation"),
+        ]);
+
+    #[cfg(feature = "frontend")]
+    if settings.frontend {
+        assets::init(settings.assets_dir.clone());
+    }
 
     // Initialize the database
     let database = Arc::new(
@@ -106,58 +78,428 @@ async fn main() {
     );
     info!("Database initialized");
 
-    // Build the router
-    let app = Router::new()
-        .route("/api/health", get(routes::health))
-        .nest("/api", routes::project_routes().with_state(database))
-        .route("/api/beads", get(routes::beads::read_beads))
-        .route("/api/beads/comment", post(routes::beads::add_comment))
-        .route("/api/fs/list", get(routes::fs::list_directory))
-        .route("/api/fs/exists", get(routes::fs::path_exists))
-        .route("/api/fs/read", get(routes::fs::read_file))
-        .route("/api/fs/open-external", post(routes::fs::open_external))
-        .route("/api/bd/command", post(routes::cli::bd_command))
-        .route("/api/git/branch-status", get(routes::git::branch_status))
+    // Keep the generated token across restarts so open boards stay signed in
+    if settings.token_generated {
+        if let Some(generated) = settings.token.take() {
+            settings.token = Some(database.keep_auth_token(&generated).unwrap_or_else(|e| {
+                tracing::warn!("Failed to keep the access token, using a new one: {}", e);
+                generated
+            }));
+        }
+    }
+
+    // Register the projects given on the command line; they, or all
+    // registered projects, make up the workspace
+    let mut workspace = Vec::new();
+    for path in &settings.projects {
+        match args::register_project(&database, path) {
+            Ok(project) => workspace.push(project),
+            Err(e) => tracing::warn!("Failed to register project: {}", e),
+        }
+    }
+    // A single project opens straight into its board
+    let mut page = String::new();
+    if let [project] = workspace.as_slice() {
+        if !settings.all_projects {
+            info!("Opening project {} ({})", project.name, project.path);
+            page = format!("/project?id={}", project.id);
+        }
+    }
+    if settings.all_projects {
+        match database.get_projects() {
+            Ok(projects) => workspace.extend(projects),
+            Err(e) => tracing::warn!("Failed to load projects: {}", e),
+        }
+    }
+    routes::watch::set_watch_mode(settings.watch_mode);
+    routes::workspace::init(&workspace);
+
+    // Stop cleanly on Ctrl-C or SIGTERM
+    tokio::spawn(shutdown::listen());
+
+    let background = [
+        // Materialize the next occurrence of recurring beads once they are closed
+        tokio::spawn(routes::beads::run_recurrence_scheduler(database.clone())),
+        // Refresh PR status for bead worktrees so cards don't each poll the provider
+        tokio::spawn(routes::worktree::run_pr_status_poller(database.clone())),
+        tokio::spawn(routes::worktree::run_fetch_scheduler(database.clone())),
+        // Push dirty/ahead/behind changes of watched projects' worktrees
+        tokio::spawn(routes::worktree::run_worktree_state_tracker()),
+        // Remove files that have been in a project's trash for 30 days
+        tokio::spawn(routes::trash::run_trash_purger(database.clone())),
+    ];
+
+    // Build the API router
+    let api = Router::new()
+        .route("/health", get(routes::health))
+        .route("/version", get(update::version))
+        .route(
+            "/health/deep",
+            get(routes::diagnostics::deep_health).with_state(database.clone()),
+        )
+        .route("/auth/me", get(auth::me))
+        .merge(routes::project_routes().with_state(database.clone()))
+        .route(
+            "/workspace",
+            get(routes::workspace::workspace_summary).with_state(database.clone()),
+        )
+        .route(
+            "/beads",
+            get(routes::beads::read_beads).post(routes::beads::create_bead),
+        )
+        .route(
+            "/beads/all",
+            get(routes::beads::read_all_beads).with_state(database.clone()),
+        )
+        .route("/beads/bulk", post(routes::beads::bulk_update_beads))
+        .route("/beads/comment", post(routes::beads::add_comment))
+        .route("/beads/undo", post(routes::beads::undo_last_mutation))
+        .route("/beads/repair", post(routes::beads::repair_beads))
+        .route("/beads/recompute-epics", post(routes::beads::recompute_epics))
+        .route("/beads/time-report", get(routes::beads::time_report))
+        .route("/beads/burndown", get(routes::beads::burndown))
+        .route("/beads/changes", get(routes::beads::bead_changes))
+        .route("/beads/integrity", get(routes::beads::integrity_report))
+        .route("/beads/ready", get(routes::beads::ready))
+        .route("/beads/owners", get(routes::beads::list_owners))
+        .route(
+            "/beads/:id",
+            patch(routes::beads::update_bead).delete(routes::beads::delete_bead),
+        )
+        .route("/beads/:id/assign", post(routes::beads::assign_bead))
+        .route("/beads/:id/children", post(routes::beads::create_child_bead))
+        .route("/beads/:id/rename", post(routes::beads::rename_bead))
+        .route("/beads/:id/attachments", get(routes::attachments::list_attachments))
+        .route(
+            "/beads/:id/attachments/:filename",
+            get(routes::attachments::download_attachment)
+                .delete(routes::attachments::delete_attachment),
+        )
+        .route(
+            "/beads/:id/labels",
+            post(routes::beads::add_labels).delete(routes::beads::remove_labels),
+        )
+        .route(
+            "/beads/:id/timer/start",
+            post(routes::beads::start_timer),
+        )
+        .route("/beads/:id/timer/stop", post(routes::beads::stop_timer))
+        .route(
+            "/beads/:id/status",
+            post(routes::beads::transition_bead_status),
+        )
+        .route("/designs", post(routes::beads::create_design))
+        .route("/designs/history", get(routes::git::design_history))
+        .route("/fs/list", get(routes::fs::list_directory))
+        .route("/fs/exists", get(routes::fs::path_exists))
+        .route("/fs/read", get(routes::fs::read_file))
+        .route("/fs/read-range", get(routes::fs::read_file_range))
+        .route("/fs/stat", get(routes::fs::stat_file))
+        .route("/fs/search", get(routes::fs::search_files))
+        .route("/fs/rename", post(routes::beads::rename_design))
+        .route("/fs/delete", delete(routes::beads::delete_design))
+        .route("/fs/trash", get(routes::trash::get_trash))
+        .route("/fs/trash/restore", post(routes::trash::restore_trash))
+        .route("/fs/open-external", post(routes::fs::open_external))
+        .route("/fs/reveal", post(routes::fs::reveal_path))
+        .route(
+            "/fs/open-terminal",
+            post(routes::fs::open_terminal).with_state(database.clone()),
+        )
+        .route("/bd/command", post(routes::cli::bd_command))
+        .route("/git/branch-status", get(routes::git::branch_status))
+        .route("/git/file-history", get(routes::git::file_history))
         // Worktree endpoints
-        .route("/api/git/worktree-status", get(routes::worktree::worktree_status))
-        .route("/api/git/worktree", post(routes::worktree::create_worktree))
-        .route("/api/git/worktree", delete(routes::worktree::delete_worktree))
-        .route("/api/git/worktrees", get(routes::worktree::list_worktrees))
+        .route("/git/worktree-status", get(routes::worktree::worktree_status))
+        .route("/git/worktree", delete(routes::worktree::delete_worktree))
+        .route("/git/worktree/unlock", post(routes::worktree::unlock_worktree))
+        .route("/git/worktrees", get(routes::worktree::list_worktrees))
+        .route("/git/worktree-hooks", get(routes::worktree::hook_status))
+        .route(
+            "/git/worktree-hooks/trust",
+            post(routes::worktree::trust_hooks).with_state(database.clone()),
+        )
+        .route(
+            "/git/tag",
+            post(routes::worktree::create_tag).with_state(database.clone()),
+        )
+        .route(
+            "/git/worktree-config",
+            get(routes::worktree::get_worktree_config).merge(
+                put(routes::worktree::update_worktree_config).with_state(database.clone()),
+            ),
+        )
         // PR endpoints
-        .route("/api/git/pr-status", get(routes::worktree::pr_status))
-        .route("/api/git/pr-files", get(routes::worktree::pr_files))
-        .route("/api/git/create-pr", post(routes::worktree::create_pr))
-        .route("/api/git/merge-pr", post(routes::worktree::merge_pr))
-        .route("/api/git/rebase-siblings", post(routes::worktree::rebase_siblings))
+        .route(
+            "/git/pr-status",
+            get(routes::worktree::pr_status).with_state(database.clone()),
+        )
+        .route(
+            "/git/pr-files",
+            get(routes::worktree::pr_files).with_state(database.clone()),
+        )
+        .route("/git/pr-preview", get(routes::worktree::pr_preview))
+        .route("/git/generate-pr-body", post(routes::worktree::generate_pr_body))
+        .route(
+            "/git/pr-ready",
+            post(routes::worktree::mark_pr_ready).with_state(database.clone()),
+        )
+        .route(
+            "/git/check-logs",
+            get(routes::worktree::check_logs).with_state(database.clone()),
+        )
+        .route("/git/rebase-check", get(routes::worktree::rebase_check))
+        .route("/git/conflicts", get(routes::worktree::list_conflicts))
+        .route("/git/conflicts/file", get(routes::worktree::conflict_file))
+        .route("/git/conflicts/resolve", post(routes::worktree::resolve_conflict))
+        .route("/git/conflicts/abort", post(routes::worktree::abort_operation))
+        // Admin endpoints
+        .route(
+            "/admin/log-level",
+            get(routes::admin::get_log_level).put(routes::admin::set_log_level),
+        )
         // Agent endpoints
-        .route("/api/agents", get(routes::agents::list_agents))
-        .route("/api/agents/:filename", put(routes::agents::update_agent))
+        .route("/agents", get(routes::agents::list_agents))
+        .route("/agents/:filename", put(routes::agents::update_agent))
+        // Custom field endpoints
+        .route(
+            "/fields",
+            get(routes::fields::list_fields).put(routes::fields::update_fields),
+        )
+        .route("/fields/:name", delete(routes::fields::delete_field))
+        // Markdown endpoints
+        .route("/markdown/render", post(routes::markdown::render))
         // Memory endpoints
         .route(
-            "/api/memory",
+            "/memory",
             get(routes::memory::list_memory)
                 .put(routes::memory::update_memory)
                 .delete(routes::memory::delete_memory),
         )
-        .route("/api/memory/stats", get(routes::memory::memory_stats))
-        .route("/api/watch/beads", get(routes::watch_beads))
-        .fallback(serve_static)
-        .layer(cors);
+        .route("/memory/stats", get(routes::memory::memory_stats))
+        // Milestone endpoints
+        .route(
+            "/milestones",
+            get(routes::milestones::list_milestones).post(routes::milestones::create_milestone),
+        )
+        .route(
+            "/milestones/:id",
+            patch(routes::milestones::update_milestone)
+                .delete(routes::milestones::delete_milestone),
+        )
+        .route(
+            "/milestones/:id/progress",
+            get(routes::milestones::milestone_progress),
+        )
+        // Notification endpoints
+        .route(
+            "/notifications",
+            get(routes::notifications::list_notifications),
+        )
+        .route("/events", get(routes::events).with_state(database.clone()))
+        .route("/watch/beads", get(routes::watch_beads))
+        .route("/ws", get(routes::watch_beads_ws));
 
-    let addr = format!("0.0.0.0:{}", port);
-    let listener = tokio::net::TcpListener::bind(&addr)
+    // Uploads and slow git operations have their own limits in place of the
+    // defaults, so they are added after those are applied
+    let api = limits::with_defaults(api)
+        .route(
+            "/beads/:id/attachments",
+            limits::upload(
+                post(routes::attachments::upload_attachments),
+                routes::attachments::MAX_ATTACHMENT_SIZE,
+            ),
+        )
+        .route(
+            "/fs/upload",
+            limits::upload(
+                post(routes::fs::upload_files),
+                routes::fs::MAX_UPLOAD_TOTAL_SIZE,
+            ),
+        )
+        .route(
+            "/import/jira",
+            limits::upload(
+                post(routes::import::import_jira),
+                routes::import::MAX_JIRA_CSV_SIZE,
+            ),
+        )
+        .route(
+            "/import/github",
+            limits::long_running(post(routes::import::import_github)),
+        )
+        .route("/git/fetch", limits::long_running(post(routes::worktree::fetch)))
+        .route(
+            "/git/worktree",
+            limits::long_running(
+                post(routes::worktree::create_worktree).with_state(database.clone()),
+            ),
+        )
+        .route(
+            "/git/commit",
+            limits::long_running(post(routes::worktree::commit_worktree)),
+        )
+        .route(
+            "/git/cherry-pick",
+            limits::long_running(post(routes::worktree::cherry_pick)),
+        )
+        .route(
+            "/git/create-pr",
+            limits::long_running(
+                post(routes::worktree::create_pr).with_state(database.clone()),
+            ),
+        )
+        .route(
+            "/git/merge-pr",
+            limits::long_running(
+                post(routes::worktree::merge_pr).with_state(database.clone()),
+            ),
+        )
+        .route(
+            "/git/auto-merge",
+            limits::long_running(
+                post(routes::worktree::auto_merge).with_state(database.clone()),
+            ),
+        )
+        .route(
+            "/git/complete-bead",
+            limits::long_running(
+                post(routes::worktree::complete_bead).with_state(database.clone()),
+            ),
+        )
+        .route(
+            "/git/rebase-siblings",
+            limits::long_running(post(routes::worktree::rebase_siblings)),
+        )
+        .route(
+            "/git/conflicts/continue",
+            limits::long_running(post(routes::worktree::continue_operation)),
+        )
+        .layer(CatchPanicLayer::custom(error::panic_response))
+        .layer(middleware::from_fn(logging::request_span));
+
+    // Serve the API under its version, keeping the unversioned paths from
+    // before versioning as deprecated aliases
+    let app = Router::new()
+        .nest(routes::version::API_PREFIX, api.clone())
+        .nest(
+            "/api",
+            api.layer(middleware::from_fn(routes::version::deprecated_alias)),
+        );
+
+    // Serve the frontend for everything else, unless running API-only
+    let app = match settings.frontend {
+        #[cfg(feature = "frontend")]
+        true => app.fallback(assets::serve_static),
+        _ => app.fallback(routes::not_found),
+    };
+
+    // Sign in with OpenID Connect, if configured
+    let oidc = settings.oidc.clone().map(|oidc| Arc::new(oidc::Oidc::new(oidc)));
+    let app = match &oidc {
+        Some(oidc) => app.merge(
+            Router::new()
+                .route("/auth/login", get(oidc::login))
+                .route("/auth/callback", get(oidc::callback))
+                .route("/auth/logout", post(oidc::logout))
+                .with_state(oidc.clone()),
+        ),
+        None => app,
+    };
+
+    // Require the access token or a signed-in session on API routes
+    let auth_state = auth::AuthState {
+        token: settings.token.as_deref().map(Arc::from),
+        oidc,
+    };
+    if auth_state.token.is_none() && auth_state.oidc.is_none() {
+        tracing::warn!("Authentication is disabled; anyone who can reach the server can use the API");
+    }
+    let app = app.layer(middleware::from_fn_with_state(auth_state, auth::require_auth));
+    let app = app.layer(cors);
+
+    let listener = tokio::net::TcpListener::bind((settings.host.as_str(), settings.port))
         .await
         .expect("Failed to bind to address");
 
-    info!("Server starting on http://localhost:{}", port);
+    let tls_config = match &settings.tls {
+        Some(tls) => match tls::rustls_config(tls, &settings.host).await {
+            Ok(config) => Some(config),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    // Browse to the bound host unless it is a wildcard address
+    let scheme = if tls_config.is_some() { "https" } else { "http" };
+    let url = match settings.host.as_str() {
+        "0.0.0.0" | "::" => format!("{}://localhost:{}", scheme, settings.port),
+        host if host.contains(':') => format!("{}://[{}]:{}", scheme, host, settings.port),
+        host => format!("{}://{}:{}", scheme, host, settings.port),
+    };
+    info!("Server starting on {}", url);
+
+    // The frontend picks the token up from the URL it is opened with
+    let mut launch_url = format!("{}{}", url, if page.is_empty() { "/" } else { &page });
+    if !settings.frontend {
+        info!("Serving the API only, under {}{}", url, routes::version::API_PREFIX);
+    } else if let Some(token) = &settings.token {
+        let separator = if launch_url.contains('?') { '&' } else { '?' };
+        launch_url = format!("{}{}token={}", launch_url, separator, token);
+        info!("Open {} to use the board", launch_url);
+    }
 
     // Open default browser
-    if let Err(e) = open::that(format!("http://localhost:{}", port)) {
-        tracing::warn!("Failed to open browser: {}", e);
+    if settings.open {
+        if let Err(e) = open::that(&launch_url) {
+            tracing::warn!("Failed to open browser: {}", e);
+        }
     }
 
-    // Start the server
-    axum::serve(listener, app)
-        .await
-        .expect("Server failed to start");
+    // Start the server; on shutdown it stops accepting connections and
+    // waits for in-flight requests
+    match tls_config {
+        Some(config) => {
+            let handle = axum_server::Handle::new();
+            tokio::spawn({
+                let handle = handle.clone();
+                async move {
+                    shutdown::signalled().await;
+                    handle.graceful_shutdown(None);
+                }
+            });
+            axum_server::from_tcp_rustls(
+                listener.into_std().expect("Failed to hand over listener"),
+                config,
+            )
+            .handle(handle)
+            .serve(app.into_make_service())
+            .await
+            .expect("Server failed to start")
+        }
+        None => axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown::signalled())
+            .await
+            .expect("Server failed to start"),
+    }
+
+    // Let background loops finish the pass they are in
+    for task in background {
+        if let Err(e) = task.await {
+            tracing::warn!("Background task failed: {}", e);
+        }
+    }
+
+    match Arc::try_unwrap(database) {
+        Ok(database) => {
+            if let Err(e) = database.close() {
+                tracing::warn!("Failed to close database: {}", e);
+            }
+        }
+        Err(_) => tracing::warn!("Database still in use at shutdown"),
+    }
+    info!("Server stopped");
 }
@@ -0,0 +1,583 @@
+//! OpenID Connect login for hosted deployments.
+//!
+//! Configured under `oidc` in the `--config` file:
+//!
+//! ```yaml
+//! oidc:
+//!   issuer: https://accounts.example.com
+//!   client_id: beads-kanban
+//!   client_secret: ...        # omit for public clients
+//!   redirect_url: https://beads.example.com/auth/callback
+//! ```
+//!
+//! `/auth/login` sends the browser to the issuer (authorization code flow
+//! with PKCE) and `/auth/callback` exchanges the code for an ID token,
+//! starts a session, and sets the session cookie. The ID token comes
+//! straight from the token endpoint over TLS, so its claims are checked
+//! (issuer, audience, expiry, nonce) but its signature is not.
+//!
+//! Sessions live in memory; restarting the server signs everyone out.
+
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Redirect, Response},
+    Json,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+
+/// Name of the session cookie.
+const SESSION_COOKIE: &str = "beads_session";
+
+/// How long a session lasts.
+const SESSION_TTL: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// How long a login may take between leaving for the issuer and returning.
+const LOGIN_TTL: Duration = Duration::from_secs(10 * 60);
+
+fn default_scopes() -> Vec<String> {
+    ["openid", "profile", "email"].map(String::from).to_vec()
+}
+
+/// Issuer and client registration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OidcSettings {
+    /// Issuer URL; `/.well-known/openid-configuration` is read from it.
+    pub issuer: String,
+    pub client_id: String,
+    /// Client secret, for confidential clients.
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// This server's `/auth/callback` URL as registered with the issuer.
+    pub redirect_url: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: Vec<String>,
+}
+
+impl OidcSettings {
+    /// Checks the URLs parse and the callback points at `/auth/callback`.
+    pub fn validate(&self) -> Result<(), String> {
+        reqwest::Url::parse(&self.issuer)
+            .map_err(|e| format!("Invalid OIDC issuer '{}': {}", self.issuer, e))?;
+        let redirect = reqwest::Url::parse(&self.redirect_url)
+            .map_err(|e| format!("Invalid OIDC redirect_url '{}': {}", self.redirect_url, e))?;
+        if redirect.path() != "/auth/callback" {
+            return Err("OIDC redirect_url must point at /auth/callback".to_string());
+        }
+        if self.client_id.trim().is_empty() {
+            return Err("OIDC client_id must not be empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The signed-in user.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Identity {
+    /// Subject identifier from the issuer
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+impl Identity {
+    /// Name shown as a comment author and in audit logs.
+    pub fn display_name(&self) -> &str {
+        self.name
+            .as_deref()
+            .or(self.email.as_deref())
+            .unwrap_or(&self.subject)
+    }
+}
+
+/// Endpoints from the issuer's discovery document.
+#[derive(Debug, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+}
+
+/// A login waiting for the issuer to redirect back.
+struct PendingLogin {
+    nonce: String,
+    verifier: String,
+    return_to: String,
+    started: Instant,
+}
+
+struct Session {
+    identity: Identity,
+    expires: Instant,
+}
+
+/// OIDC client state: discovery, logins in progress, and sessions.
+pub struct Oidc {
+    settings: OidcSettings,
+    client: reqwest::Client,
+    discovery: OnceCell<Discovery>,
+    pending: Mutex<HashMap<String, PendingLogin>>,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl Oidc {
+    pub fn new(settings: OidcSettings) -> Self {
+        Self {
+            settings,
+            client: reqwest::Client::new(),
+            discovery: OnceCell::new(),
+            pending: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the identity for the session cookie in `headers`, if the
+    /// session exists and has not expired.
+    pub fn identity(&self, headers: &HeaderMap) -> Option<Identity> {
+        let id = session_cookie(headers)?;
+        let mut sessions = self.sessions.lock().unwrap();
+        match sessions.get(&id) {
+            Some(session) if session.expires > Instant::now() => Some(session.identity.clone()),
+            Some(_) => {
+                sessions.remove(&id);
+                None
+            }
+            None => None,
+        }
+    }
+
+    async fn discovery(&self) -> Result<&Discovery, String> {
+        self.discovery
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.settings.issuer.trim_end_matches('/')
+                );
+                let response = self
+                    .client
+                    .get(&url)
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status())
+                    .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+                response
+                    .json::<Discovery>()
+                    .await
+                    .map_err(|e| format!("Invalid discovery document at {}: {}", url, e))
+            })
+            .await
+    }
+
+    /// Starts a login and returns the issuer's authorization URL.
+    async fn start_login(&self, return_to: String) -> Result<String, String> {
+        let discovery = self.discovery().await?;
+        let state = random_string();
+        let nonce = random_string();
+        // Two UUIDs give a 64-character verifier, within PKCE's 43..=128
+        let verifier = format!("{}{}", random_string(), random_string());
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+
+        let mut url = reqwest::Url::parse(&discovery.authorization_endpoint)
+            .map_err(|e| format!("Invalid authorization endpoint: {}", e))?;
+        url.query_pairs_mut()
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &self.settings.client_id)
+            .append_pair("redirect_uri", &self.settings.redirect_url)
+            .append_pair("scope", &self.settings.scopes.join(" "))
+            .append_pair("state", &state)
+            .append_pair("nonce", &nonce)
+            .append_pair("code_challenge", &challenge)
+            .append_pair("code_challenge_method", "S256");
+
+        let mut pending = self.pending.lock().unwrap();
+        pending.retain(|_, login| login.started.elapsed() < LOGIN_TTL);
+        pending.insert(
+            state,
+            PendingLogin {
+                nonce,
+                verifier,
+                return_to,
+                started: Instant::now(),
+            },
+        );
+        Ok(url.to_string())
+    }
+
+    /// Exchanges the authorization code, starting a session. Returns the
+    /// session ID and where to send the browser.
+    async fn finish_login(&self, code: &str, state: &str) -> Result<(String, String), String> {
+        let login = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(state)
+            .filter(|login| login.started.elapsed() < LOGIN_TTL)
+            .ok_or("Login expired or was not started here; try again")?;
+        let discovery = self.discovery().await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.settings.redirect_url.as_str()),
+            ("client_id", self.settings.client_id.as_str()),
+            ("code_verifier", login.verifier.as_str()),
+        ];
+        if let Some(secret) = &self.settings.client_secret {
+            form.push(("client_secret", secret.as_str()));
+        }
+        let response = self
+            .client
+            .post(&discovery.token_endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| format!("Token request failed: {}", e))?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Token request failed ({}): {}", status, body.trim()));
+        }
+        let tokens: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid token response: {}", e))?;
+        let id_token = tokens["id_token"]
+            .as_str()
+            .ok_or("Token response has no id_token")?;
+
+        let identity = verify_id_token(
+            id_token,
+            &discovery.issuer,
+            &self.settings.client_id,
+            &login.nonce,
+            unix_now(),
+        )?;
+        info!("{} signed in", identity.display_name());
+
+        let session_id = format!("{}{}", random_string(), random_string());
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| session.expires > Instant::now());
+        sessions.insert(
+            session_id.clone(),
+            Session {
+                identity,
+                expires: Instant::now() + SESSION_TTL,
+            },
+        );
+        Ok((session_id, login.return_to))
+    }
+
+    fn end_session(&self, headers: &HeaderMap) {
+        if let Some(id) = session_cookie(headers) {
+            self.sessions.lock().unwrap().remove(&id);
+        }
+    }
+
+    /// `Set-Cookie` value for a session (or clearing it when `None`).
+    fn cookie(&self, session_id: Option<&str>) -> String {
+        let secure = if self.settings.redirect_url.starts_with("https://") {
+            "; Secure"
+        } else {
+            ""
+        };
+        match session_id {
+            Some(id) => format!(
+                "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{}",
+                SESSION_COOKIE,
+                id,
+                SESSION_TTL.as_secs(),
+                secure
+            ),
+            None => format!(
+                "{}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0{}",
+                SESSION_COOKIE, secure
+            ),
+        }
+    }
+}
+
+fn random_string() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Reads the session ID from the `Cookie` header.
+fn session_cookie(headers: &HeaderMap) -> Option<String> {
+    read_cookie(headers, SESSION_COOKIE)
+}
+
+/// Reads a cookie from the `Cookie` header.
+pub(crate) fn read_cookie(headers: &HeaderMap, cookie: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == cookie)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Checks an ID token's claims and returns the identity in it.
+fn verify_id_token(
+    id_token: &str,
+    issuer: &str,
+    client_id: &str,
+    nonce: &str,
+    now: u64,
+) -> Result<Identity, String> {
+    let payload = id_token
+        .split('.')
+        .nth(1)
+        .ok_or("Malformed ID token")?;
+    let claims: serde_json::Value = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or("Malformed ID token")?;
+
+    if claims["iss"].as_str() != Some(issuer) {
+        return Err("ID token was issued by a different issuer".to_string());
+    }
+    let audience_ok = match &claims["aud"] {
+        serde_json::Value::String(aud) => aud == client_id,
+        serde_json::Value::Array(auds) => auds.iter().any(|a| a.as_str() == Some(client_id)),
+        _ => false,
+    };
+    if !audience_ok {
+        return Err("ID token is for a different client".to_string());
+    }
+    if claims["exp"].as_u64().is_none_or(|exp| exp <= now) {
+        return Err("ID token has expired".to_string());
+    }
+    if claims["nonce"].as_str() != Some(nonce) {
+        return Err("ID token nonce does not match the login".to_string());
+    }
+
+    let claim = |name: &str| claims[name].as_str().map(String::from);
+    Ok(Identity {
+        subject: claim("sub").ok_or("ID token has no subject")?,
+        name: claim("name").or_else(|| claim("preferred_username")),
+        email: claim("email"),
+    })
+}
+
+/// Only same-site paths are allowed as post-login destinations.
+fn safe_return_to(return_to: Option<String>) -> String {
+    return_to
+        .filter(|path| path.starts_with('/') && !path.starts_with("//") && !path.contains('\\'))
+        .unwrap_or_else(|| "/".to_string())
+}
+
+// ============================================================================
+// Login Endpoints
+// ============================================================================
+
+/// Query parameters for starting a login.
+#[derive(Deserialize)]
+pub struct LoginParams {
+    /// Path to return to after signing in (default: `/`).
+    #[serde(default)]
+    pub return_to: Option<String>,
+}
+
+/// Redirect to the issuer to sign in.
+///
+/// # Endpoint
+///
+/// `GET /auth/login?return_to=/project?id=...`
+pub async fn login(
+    State(oidc): State<Arc<Oidc>>,
+    Query(params): Query<LoginParams>,
+) -> Response {
+    match oidc.start_login(safe_return_to(params.return_to)).await {
+        Ok(url) => Redirect::to(&url).into_response(),
+        Err(e) => {
+            warn!("OIDC login failed: {}", e);
+            (StatusCode::BAD_GATEWAY, e).into_response()
+        }
+    }
+}
+
+/// Query parameters the issuer redirects back with.
+#[derive(Deserialize)]
+pub struct CallbackParams {
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub error_description: Option<String>,
+}
+
+/// Finish signing in: start a session and return to the board.
+///
+/// # Endpoint
+///
+/// `GET /auth/callback?code=...&state=...`
+pub async fn callback(
+    State(oidc): State<Arc<Oidc>>,
+    Query(params): Query<CallbackParams>,
+) -> Response {
+    let (code, state) = match (params.code, params.state, params.error) {
+        (_, _, Some(error)) => {
+            let description = params.error_description.unwrap_or_default();
+            return (
+                StatusCode::UNAUTHORIZED,
+                format!("Sign-in failed: {} {}", error, description),
+            )
+                .into_response();
+        }
+        (Some(code), Some(state), None) => (code, state),
+        _ => return (StatusCode::BAD_REQUEST, "Missing code or state").into_response(),
+    };
+
+    match oidc.finish_login(&code, &state).await {
+        Ok((session_id, return_to)) => (
+            [(header::SET_COOKIE, oidc.cookie(Some(&session_id)))],
+            Redirect::to(&return_to),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!("OIDC callback failed: {}", e);
+            (StatusCode::UNAUTHORIZED, format!("Sign-in failed: {}", e)).into_response()
+        }
+    }
+}
+
+/// Sign out, ending the session.
+///
+/// # Endpoint
+///
+/// `POST /auth/logout`
+pub async fn logout(State(oidc): State<Arc<Oidc>>, headers: HeaderMap) -> Response {
+    oidc.end_session(&headers);
+    (
+        [(header::SET_COOKIE, oidc.cookie(None))],
+        Json(serde_json::json!({ "success": true })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id_token(claims: serde_json::Value) -> String {
+        format!(
+            "eyJhbGciOiJSUzI1NiJ9.{}.c2lnbmF0dXJl",
+            URL_SAFE_NO_PAD.encode(claims.to_string())
+        )
+    }
+
+    #[test]
+    fn test_verify_id_token() {
+        let claims = serde_json::json!({
+            "iss": "https://id.example.com",
+            "aud": ["other", "beads"],
+            "exp": 2000,
+            "nonce": "n-1",
+            "sub": "user-1",
+            "preferred_username": "ann",
+            "email": "ann@example.com"
+        });
+        let verify = |token: &str, now| {
+            verify_id_token(token, "https://id.example.com", "beads", "n-1", now)
+        };
+
+        let identity = verify(&id_token(claims.clone()), 1000).unwrap();
+        assert_eq!(identity.subject, "user-1");
+        assert_eq!(identity.display_name(), "ann");
+        assert_eq!(identity.email.as_deref(), Some("ann@example.com"));
+
+        assert!(verify(&id_token(claims.clone()), 2000).is_err());
+        let mut wrong = claims.clone();
+        wrong["nonce"] = "n-2".into();
+        assert!(verify(&id_token(wrong), 1000).is_err());
+        let mut wrong = claims.clone();
+        wrong["aud"] = "other".into();
+        assert!(verify(&id_token(wrong), 1000).is_err());
+        let mut wrong = claims;
+        wrong["iss"] = "https://evil.example.com".into();
+        assert!(verify(&id_token(wrong), 1000).is_err());
+        assert!(verify("not-a-token", 1000).is_err());
+    }
+
+    #[test]
+    fn test_sessions() {
+        let oidc = Oidc::new(OidcSettings {
+            issuer: "https://id.example.com".to_string(),
+            client_id: "beads".to_string(),
+            client_secret: None,
+            redirect_url: "https://beads.example.com/auth/callback".to_string(),
+            scopes: default_scopes(),
+        });
+        oidc.sessions.lock().unwrap().insert(
+            "abc".to_string(),
+            Session {
+                identity: Identity {
+                    subject: "user-1".to_string(),
+                    name: None,
+                    email: Some("ann@example.com".to_string()),
+                },
+                expires: Instant::now() + SESSION_TTL,
+            },
+        );
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(oidc.identity(&headers), None);
+        headers.insert(header::COOKIE, "theme=dark; beads_session=abc".parse().unwrap());
+        assert_eq!(
+            oidc.identity(&headers).map(|i| i.display_name().to_string()),
+            Some("ann@example.com".to_string())
+        );
+        assert!(oidc.cookie(Some("abc")).ends_with("; Secure"));
+
+        oidc.end_session(&headers);
+        assert_eq!(oidc.identity(&headers), None);
+    }
+
+    #[test]
+    fn test_safe_return_to() {
+        assert_eq!(safe_return_to(None), "/");
+        assert_eq!(
+            safe_return_to(Some("/project?id=1".to_string())),
+            "/project?id=1"
+        );
+        assert_eq!(safe_return_to(Some("//evil.example.com".to_string())), "/");
+        assert_eq!(safe_return_to(Some("https://evil.example.com".to_string())), "/");
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        let settings = |redirect: &str| OidcSettings {
+            issuer: "https://id.example.com".to_string(),
+            client_id: "beads".to_string(),
+            client_secret: None,
+            redirect_url: redirect.to_string(),
+            scopes: default_scopes(),
+        };
+        assert!(settings("https://beads.example.com/auth/callback").validate().is_ok());
+        assert!(settings("https://beads.example.com/callback").validate().is_err());
+        assert!(settings("not a url").validate().is_err());
+    }
+}
@@ -0,0 +1,43 @@
+//! Server administration endpoints.
+
+use axum::{response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::error::AppError;
+use crate::logging;
+
+/// Current log filter.
+#[derive(Debug, Serialize)]
+pub struct LogLevelResponse {
+    /// `EnvFilter` directives, e.g. `info` or `beads_server=debug,info`
+    pub filter: Option<String>,
+}
+
+/// Request body for changing the log filter.
+#[derive(Debug, Deserialize)]
+pub struct SetLogLevelRequest {
+    /// A level (`debug`) or `EnvFilter` directives (`beads_server=trace,info`)
+    pub filter: String,
+}
+
+/// GET /api/v1/admin/log-level
+///
+/// Returns the log filter in effect.
+pub async fn get_log_level() -> impl IntoResponse {
+    Json(LogLevelResponse {
+        filter: logging::current_filter(),
+    })
+}
+
+/// PUT /api/v1/admin/log-level
+///
+/// Changes the log filter until the server restarts, e.g. to turn on
+/// debug logging while reproducing an issue.
+pub async fn set_log_level(
+    Json(payload): Json<SetLogLevelRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let filter = logging::set_filter(&payload.filter).map_err(AppError::BadRequest)?;
+    info!("Log filter changed to {}", filter);
+    Ok(Json(serde_json::json!({ "filter": filter })))
+}
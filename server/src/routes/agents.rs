@@ -6,7 +6,6 @@
 
 use axum::{
     extract::{Path as AxumPath, Query},
-    http::StatusCode,
     response::IntoResponse,
     Json,
 };
@@ -15,6 +14,7 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 use super::validate_path_security;
+use crate::error::AppError;
 
 // ---------------------------------------------------------------------------
 // Types
@@ -218,32 +218,20 @@ fn validate_agent_filename(filename: &str) -> Result<(), String> {
 ///
 /// Lists all agent files from `.claude/agents/` within the given project path.
 /// Parses YAML frontmatter and extracts agent metadata including nickname.
-pub async fn list_agents(Query(params): Query<AgentParams>) -> impl IntoResponse {
+pub async fn list_agents(Query(params): Query<AgentParams>) -> Result<impl IntoResponse, AppError> {
     let project_path = PathBuf::from(&params.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&project_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
 
     let dir = agents_dir(&project_path);
 
     if !dir.exists() {
-        return (StatusCode::OK, Json(serde_json::json!([])));
+        return Ok(Json(serde_json::json!([])));
     }
 
-    let entries = match std::fs::read_dir(&dir) {
-        Ok(e) => e,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": format!("Failed to read agents directory: {}", e) })),
-            );
-        }
-    };
+    let entries = std::fs::read_dir(&dir)
+        .map_err(|e| AppError::Internal(format!("Failed to read agents directory: {}", e)))?;
 
     let mut agents: Vec<AgentInfo> = Vec::new();
 
@@ -271,7 +259,7 @@ pub async fn list_agents(Query(params): Query<AgentParams>) -> impl IntoResponse
     // Sort by name for consistent ordering
     agents.sort_by(|a, b| a.name.cmp(&b.name));
 
-    (StatusCode::OK, Json(serde_json::json!(agents)))
+    Ok(Json(serde_json::json!(agents)))
 }
 
 /// PUT /api/agents/:filename
@@ -281,66 +269,34 @@ pub async fn list_agents(Query(params): Query<AgentParams>) -> impl IntoResponse
 pub async fn update_agent(
     AxumPath(filename): AxumPath<String>,
     Json(payload): Json<UpdateAgentBody>,
-) -> impl IntoResponse {
+) -> Result<impl IntoResponse, AppError> {
     // Validate filename
-    if let Err(e) = validate_agent_filename(&filename) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_agent_filename(&filename).map_err(AppError::BadRequest)?;
 
     let project_path = PathBuf::from(&payload.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&project_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
 
     let file_path = agents_dir(&project_path).join(&filename);
 
     if !file_path.exists() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": format!("Agent file '{}' not found", filename) })),
-        );
+        return Err(AppError::NotFound(format!(
+            "Agent file '{}' not found",
+            filename
+        )));
     }
 
-    let content = match std::fs::read_to_string(&file_path) {
-        Ok(c) => c,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": format!("Failed to read file: {}", e) })),
-            );
-        }
-    };
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| AppError::Internal(format!("Failed to read file: {}", e)))?;
 
-    let (yaml_str, body) = match split_frontmatter(&content) {
-        Ok(r) => r,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": e })),
-            );
-        }
-    };
+    let (yaml_str, body) = split_frontmatter(&content).map_err(AppError::Internal)?;
 
     let yaml_fixed = fix_bare_star(&yaml_str);
 
     // Parse as serde_yaml::Value so we can modify individual fields
-    let mut yaml_value: serde_yaml::Value = match serde_yaml::from_str(&yaml_fixed) {
-        Ok(v) => v,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": format!("Failed to parse YAML: {}", e) })),
-            );
-        }
-    };
+    let mut yaml_value: serde_yaml::Value = serde_yaml::from_str(&yaml_fixed)
+        .map_err(|e| AppError::Internal(format!("Failed to parse YAML: {}", e)))?;
 
     // Update model field
     if let serde_yaml::Value::Mapping(ref mut map) = yaml_value {
@@ -359,15 +315,8 @@ pub async fn update_agent(
     }
 
     // Serialize YAML back
-    let new_yaml = match serde_yaml::to_string(&yaml_value) {
-        Ok(y) => y,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": format!("Failed to serialize YAML: {}", e) })),
-            );
-        }
-    };
+    let new_yaml = serde_yaml::to_string(&yaml_value)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize YAML: {}", e)))?;
 
     // serde_yaml::to_string adds a trailing newline and no leading ---, so we
     // need to reassemble with proper delimiters.
@@ -378,21 +327,13 @@ pub async fn update_agent(
     // Reassemble the file: ---\n{yaml}\n---\n{body}
     let new_content = format!("---\n{}\n---{}", new_yaml_trimmed, body);
 
-    if let Err(e) = std::fs::write(&file_path, &new_content) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": format!("Failed to write file: {}", e) })),
-        );
-    }
+    std::fs::write(&file_path, &new_content)
+        .map_err(|e| AppError::Internal(format!("Failed to write file: {}", e)))?;
 
     // Re-parse the updated file and return agent info
-    match parse_agent_file(&file_path) {
-        Ok(agent) => (StatusCode::OK, Json(serde_json::json!(agent))),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": format!("Failed to re-read updated file: {}", e) })),
-        ),
-    }
+    let agent = parse_agent_file(&file_path)
+        .map_err(|e| AppError::Internal(format!("Failed to re-read updated file: {}", e)))?;
+    Ok(Json(serde_json::json!(agent)))
 }
 
 // ---------------------------------------------------------------------------
@@ -0,0 +1,326 @@
+//! Attachment API route handlers.
+//!
+//! Files attached to a bead are stored under
+//! `.beads/attachments/{bead_id}/{filename}` in the project.
+
+use axum::{
+    body::Body,
+    extract::{Multipart, Path as AxumPath, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::beads::{bead_exists, resolve_issues_path};
+use super::trash::move_to_trash;
+use super::validate_path_security;
+use crate::error::AppError;
+
+/// Maximum size of a single uploaded file (10MB).
+pub const MAX_ATTACHMENT_SIZE: usize = 10 * 1024 * 1024;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// Query parameters for attachment endpoints.
+#[derive(Debug, Deserialize)]
+pub struct AttachmentParams {
+    /// The project path containing .beads/
+    pub path: String,
+}
+
+/// Request body for the delete attachment endpoint.
+#[derive(Debug, Deserialize)]
+pub struct DeleteAttachmentRequest {
+    /// The project path containing .beads/
+    pub path: String,
+}
+
+/// Metadata for a stored attachment.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Attachment {
+    pub name: String,
+    pub size: u64,
+    /// Last modified time (RFC 3339), if available.
+    pub modified: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Validates a path segment (bead ID or filename) used to build a storage path.
+///
+/// Rejects empty names, hidden names, path separators, and `..`.
+pub(crate) fn validate_segment(segment: &str, what: &str) -> Result<(), String> {
+    let invalid = segment.is_empty()
+        || segment.starts_with('.')
+        || segment.contains('/')
+        || segment.contains('\\')
+        || segment.contains('\0')
+        || segment.contains("..");
+    if invalid {
+        Err(format!("Invalid {}: '{}'", what, segment))
+    } else {
+        Ok(())
+    }
+}
+
+/// Build the directory holding a bead's attachments.
+pub(crate) fn attachments_dir(project_path: &Path, bead_id: &str) -> PathBuf {
+    project_path
+        .join(".beads")
+        .join("attachments")
+        .join(bead_id)
+}
+
+/// Validates the project path and bead ID, returning the attachments directory.
+fn resolve_attachments_dir(project: &str, bead_id: &str) -> Result<PathBuf, AppError> {
+    let project_path = PathBuf::from(project);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+    validate_segment(bead_id, "bead id").map_err(AppError::BadRequest)?;
+
+    Ok(attachments_dir(&project_path, bead_id))
+}
+
+/// Resolves a single attachment file, validating every path component.
+fn resolve_attachment_file(
+    project: &str,
+    bead_id: &str,
+    filename: &str,
+) -> Result<PathBuf, AppError> {
+    let dir = resolve_attachments_dir(project, bead_id)?;
+    validate_segment(filename, "filename").map_err(AppError::BadRequest)?;
+
+    let file_path = dir.join(filename);
+    validate_path_security(&file_path).map_err(AppError::Forbidden)?;
+    Ok(file_path)
+}
+
+/// Lists attachments in a directory, sorted by name. Missing directories are empty.
+fn list_attachments_in(dir: &Path) -> Result<Vec<Attachment>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let read_dir =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    let mut attachments = Vec::new();
+    for entry in read_dir.flatten() {
+        let metadata = match entry.metadata() {
+            Ok(m) if m.is_file() => m,
+            _ => continue,
+        };
+        attachments.push(Attachment {
+            name: entry.file_name().to_string_lossy().to_string(),
+            size: metadata.len(),
+            modified: metadata
+                .modified()
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339()),
+        });
+    }
+
+    attachments.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(attachments)
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// POST /api/beads/:id/attachments?path={project_path}
+///
+/// Uploads one or more files (multipart form, any field name) and attaches
+/// them to a bead. Existing attachments with the same name are replaced.
+///
+/// # Security constraints:
+/// - Max file size: 10MB
+/// - Filenames must not contain path separators, `..`, or start with `.`
+/// - Path must be within allowed directories
+pub async fn upload_attachments(
+    AxumPath(bead_id): AxumPath<String>,
+    Query(params): Query<AttachmentParams>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let dir = resolve_attachments_dir(&params.path, &bead_id)?;
+
+    let issues_path = resolve_issues_path(Path::new(&params.path));
+    match bead_exists(&issues_path, &bead_id) {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                bead_id
+            )));
+        }
+        Err(e) => {
+            return Err(AppError::Internal(e));
+        }
+    }
+
+    let mut uploaded = Vec::new();
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(f)) => f,
+            Ok(None) => break,
+            Err(e) => {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid multipart body: {}",
+                    e
+                )));
+            }
+        };
+
+        let filename = match field.file_name() {
+            Some(name) => name.to_string(),
+            None => continue, // Not a file field
+        };
+        validate_segment(&filename, "filename").map_err(AppError::BadRequest)?;
+
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read upload: {}", e)))?;
+        if data.len() > MAX_ATTACHMENT_SIZE {
+            return Err(AppError::PayloadTooLarge(format!(
+                "File too large: {} bytes (max {} bytes)",
+                data.len(),
+                MAX_ATTACHMENT_SIZE
+            )));
+        }
+
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::Internal(format!("Failed to create directory: {}", e)))?;
+        std::fs::write(dir.join(&filename), &data)
+            .map_err(|e| AppError::Internal(format!("Failed to write file: {}", e)))?;
+        uploaded.push(filename);
+    }
+
+    if uploaded.is_empty() {
+        return Err(AppError::BadRequest("No files provided".to_string()));
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({ "success": true, "uploaded": uploaded })),
+    ))
+}
+
+/// GET /api/beads/:id/attachments?path={project_path}
+///
+/// Lists the attachments of a bead.
+pub async fn list_attachments(
+    AxumPath(bead_id): AxumPath<String>,
+    Query(params): Query<AttachmentParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let dir = resolve_attachments_dir(&params.path, &bead_id)?;
+    let attachments = list_attachments_in(&dir).map_err(AppError::Internal)?;
+    Ok(Json(serde_json::json!({ "attachments": attachments })))
+}
+
+/// GET /api/beads/:id/attachments/:filename?path={project_path}
+///
+/// Downloads an attachment.
+pub async fn download_attachment(
+    AxumPath((bead_id, filename)): AxumPath<(String, String)>,
+    Query(params): Query<AttachmentParams>,
+) -> Response {
+    let file_path = match resolve_attachment_file(&params.path, &bead_id, &filename) {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+
+    if !file_path.is_file() {
+        return AppError::NotFound("Attachment does not exist".to_string()).into_response();
+    }
+
+    match tokio::fs::read(&file_path).await {
+        Ok(data) => {
+            let mime = mime_guess::from_path(&file_path).first_or_octet_stream();
+            Response::builder()
+                .status(StatusCode::OK)
+                .header(header::CONTENT_TYPE, mime.as_ref())
+                .header(
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}\"", filename.replace('"', "")),
+                )
+                .body(Body::from(data))
+                .unwrap()
+        }
+        Err(e) => AppError::Internal(format!("Failed to read file: {}", e)).into_response(),
+    }
+}
+
+/// DELETE /api/beads/:id/attachments/:filename
+///
+/// Moves an attachment to the project's trash (see [`super::trash`]).
+/// Removes the bead's attachment directory once empty.
+pub async fn delete_attachment(
+    AxumPath((bead_id, filename)): AxumPath<(String, String)>,
+    Json(payload): Json<DeleteAttachmentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let file_path = resolve_attachment_file(&payload.path, &bead_id, &filename)?;
+
+    if !file_path.is_file() {
+        return Err(AppError::NotFound("Attachment does not exist".to_string()));
+    }
+
+    let entry =
+        move_to_trash(Path::new(&payload.path), &file_path).map_err(AppError::Internal)?;
+
+    // Best effort: only succeeds when the directory is empty
+    if let Some(dir) = file_path.parent() {
+        let _ = std::fs::remove_dir(dir);
+    }
+
+    Ok(Json(serde_json::json!({ "success": true, "trash_id": entry.id })))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_segment() {
+        assert!(validate_segment("screenshot.png", "filename").is_ok());
+        assert!(validate_segment("bd-abc.1", "bead id").is_ok());
+        assert!(validate_segment("", "filename").is_err());
+        assert!(validate_segment(".env", "filename").is_err());
+        assert!(validate_segment("../secret", "filename").is_err());
+        assert!(validate_segment("a/b.txt", "filename").is_err());
+        assert!(validate_segment("a\\b.txt", "filename").is_err());
+    }
+
+    #[test]
+    fn test_attachments_dir() {
+        let dir = attachments_dir(Path::new("/project"), "bd-1");
+        assert_eq!(dir, PathBuf::from("/project/.beads/attachments/bd-1"));
+    }
+
+    #[test]
+    fn test_list_attachments_in() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("bd-1");
+        assert!(list_attachments_in(&dir).unwrap().is_empty());
+
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("b.txt"), "hello").unwrap();
+        std::fs::write(dir.join("a.png"), "x").unwrap();
+
+        let attachments = list_attachments_in(&dir).unwrap();
+        let names: Vec<&str> = attachments.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["a.png", "b.txt"]);
+        assert_eq!(attachments[1].size, 5);
+    }
+}
@@ -3,18 +3,55 @@
 //! Provides endpoints for reading and modifying beads from .beads/issues.jsonl files.
 
 use axum::{
-    extract::Query,
-    http::StatusCode,
-    response::IntoResponse,
+    body::Body,
+    extract::{Extension, Path as AxumPath, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::io::Write;
+use std::hash::Hasher;
+use std::io::{BufRead, Seek, Write};
 use std::path::{Path, PathBuf};
+use uuid::Uuid;
 
+use super::projects::AppState;
 use super::validate_path_security;
+use crate::db::ProjectWithTags;
+use super::fields::{validate_custom_values, CustomValues};
+use super::milestones::milestone_exists;
+use super::notifications::notify_mentions;
+use crate::lock::lock_exclusive;
+use crate::oidc::Identity;
+
+mod burndown;
+mod changes;
+mod design;
+mod index;
+mod integrity;
+mod journal;
+mod owners;
+mod ready;
+mod recurrence;
+mod rename;
+mod repair;
+mod timer;
+
+use crate::error::AppError;
+pub use burndown::burndown;
+pub use changes::bead_changes;
+pub use design::{create_design, delete_design, rename_design};
+pub(crate) use index::refresh as refresh_index;
+pub use integrity::integrity_report;
+pub use owners::{assign_bead, list_owners};
+pub use ready::ready;
+pub use recurrence::run_recurrence_scheduler;
+pub use rename::rename_bead;
+pub use repair::repair_beads;
+pub use timer::{start_timer, stop_timer, time_report};
+use recurrence::validate_recurrence;
 
 /// Resolves the correct path to `issues.jsonl` for a project.
 ///
@@ -65,6 +102,140 @@ pub fn resolve_issues_path(project_path: &Path) -> PathBuf {
 pub struct BeadsParams {
     /// The project path containing .beads/issues.jsonl
     pub path: String,
+    /// Comma-separated statuses to include
+    pub status: Option<String>,
+    /// Comma-separated issue types to include
+    pub issue_type: Option<String>,
+    /// Comma-separated owners to include
+    pub owner: Option<String>,
+    /// Comma-separated priorities to include
+    pub priority: Option<String>,
+    /// Comma-separated labels; beads with any of them are included
+    pub labels: Option<String>,
+    /// Comma-separated milestone IDs to include
+    pub milestone: Option<String>,
+    /// Case-insensitive text matched against id, title, and description
+    pub q: Option<String>,
+    /// Sort field, prefixed with `-` for descending (e.g., `-updated_at`)
+    pub sort: Option<String>,
+    /// Maximum number of beads to return
+    pub limit: Option<usize>,
+    /// Number of matching beads to skip
+    pub offset: Option<usize>,
+    /// Response format: `json` (default) or `ndjson` to stream one bead per line
+    pub format: Option<String>,
+}
+
+/// Content type of the streaming (`format=ndjson`) beads response.
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Approximate size of the chunks a streaming response is sent in.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Fields accepted by the `sort` query parameter.
+const SORT_FIELDS: &[&str] = &["id", "title", "status", "priority", "created_at", "updated_at"];
+
+/// Checks whether a bead matches the filters in the query parameters.
+fn bead_matches(bead: &Bead, params: &BeadsParams, priorities: &[i32]) -> bool {
+    let statuses = split_csv_param(params.status.as_deref());
+    if !statuses.is_empty() && !statuses.contains(&bead.status.as_str()) {
+        return false;
+    }
+
+    let issue_types = split_csv_param(params.issue_type.as_deref());
+    if !issue_types.is_empty()
+        && !bead
+            .issue_type
+            .as_deref()
+            .is_some_and(|t| issue_types.contains(&t))
+    {
+        return false;
+    }
+
+    let owners = split_csv_param(params.owner.as_deref());
+    if !owners.is_empty() && !bead.owner.as_deref().is_some_and(|o| owners.contains(&o)) {
+        return false;
+    }
+
+    if !priorities.is_empty() && !bead.priority.is_some_and(|p| priorities.contains(&p)) {
+        return false;
+    }
+
+    let milestones = split_csv_param(params.milestone.as_deref());
+    if !milestones.is_empty()
+        && !bead
+            .milestone
+            .as_deref()
+            .is_some_and(|m| milestones.contains(&m))
+    {
+        return false;
+    }
+
+    let labels = split_csv_param(params.labels.as_deref());
+    if !labels.is_empty() && !bead.labels.iter().any(|l| labels.contains(&l.as_str())) {
+        return false;
+    }
+
+    if let Some(q) = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        let q = q.to_lowercase();
+        let matches = bead.id.to_lowercase().contains(&q)
+            || bead.title.to_lowercase().contains(&q)
+            || bead
+                .description
+                .as_deref()
+                .is_some_and(|d| d.to_lowercase().contains(&q));
+        if !matches {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Splits a `sort` parameter value into the field and whether it is descending.
+fn parse_sort(sort: &str) -> Result<(&str, bool), String> {
+    let (field, descending) = match sort.strip_prefix('-') {
+        Some(field) => (field, true),
+        None => (sort, false),
+    };
+
+    if !SORT_FIELDS.contains(&field) {
+        return Err(format!(
+            "Invalid sort field '{}'. Allowed fields: {:?}",
+            field, SORT_FIELDS
+        ));
+    }
+
+    Ok((field, descending))
+}
+
+/// Sorts beads by a `sort` parameter value such as `priority` or `-updated_at`.
+///
+/// Beads missing the sort field are placed last regardless of direction.
+fn sort_beads(beads: &mut [Bead], sort: &str) -> Result<(), String> {
+    let (field, descending) = parse_sort(sort)?;
+
+    let key = |bead: &Bead| -> Option<String> {
+        match field {
+            "id" => Some(bead.id.clone()),
+            "title" => Some(bead.title.to_lowercase()),
+            "status" => Some(bead.status.clone()),
+            // Zero-padded so string order matches numeric order
+            "priority" => bead.priority.map(|p| format!("{:010}", p)),
+            "created_at" => bead.created_at.clone(),
+            _ => bead.updated_at.clone(),
+        }
+    };
+
+    beads.sort_by(|a, b| match (key(a), key(b)) {
+        (Some(a), Some(b)) if descending => b.cmp(&a),
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    });
+
+    Ok(())
 }
 
 /// A dependency relationship in the JSONL file.
@@ -111,6 +282,31 @@ pub struct Bead {
     pub deps: Option<Vec<String>>,
     #[serde(default)]
     pub relates_to: Option<Vec<String>>,
+    /// Free-form labels stored in the JSONL (unlike project tags, which
+    /// live in the local database).
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Total seconds tracked with the timer endpoints.
+    #[serde(default)]
+    pub time_tracked_seconds: Option<u64>,
+    /// When the running timer was started, if one is running.
+    #[serde(default)]
+    pub timer_started_at: Option<String>,
+    /// ID of the milestone (sprint) this bead is assigned to.
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// Recurrence rule (RRULE subset) for chores that repeat once closed.
+    #[serde(default)]
+    pub recurrence: Option<String>,
+    /// ID of the previous occurrence this recurring bead was created from.
+    #[serde(default)]
+    pub recurrence_of: Option<String>,
+    /// Date (`YYYY-MM-DD`) a recurring occurrence is scheduled for.
+    #[serde(default)]
+    pub due_date: Option<String>,
+    /// Values of the project's custom fields (see `routes::fields`).
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub custom: CustomValues,
     #[serde(default, skip_serializing)]
     dependencies: Option<Vec<Dependency>>,
 }
@@ -125,352 +321,2042 @@ pub struct Comment {
     pub created_at: String,
 }
 
+/// A raw issue record as stored in `issues.jsonl`.
+///
+/// Mutations operate on the raw JSON object rather than on [`Bead`] so that
+/// fields the server does not model (dependencies, bd bookkeeping)
+/// survive a rewrite.
+pub(crate) type IssueRecord = serde_json::Map<String, serde_json::Value>;
+
+/// A single line of `issues.jsonl`.
+#[derive(Debug, Clone)]
+enum IssueLine {
+    /// A successfully parsed JSON object.
+    Record(IssueRecord),
+    /// A line that failed to parse; written back verbatim so a rewrite never
+    /// silently discards data.
+    Malformed(String),
+}
+
+/// Reads `issues.jsonl` into lines suitable for a lossless rewrite.
+fn read_issue_lines(issues_path: &Path) -> Result<Vec<IssueLine>, String> {
+    let contents = std::fs::read_to_string(issues_path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let mut lines = Vec::new();
+    for (line_num, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(serde_json::Value::Object(record)) => lines.push(IssueLine::Record(record)),
+            _ => {
+                tracing::warn!(
+                    "Preserving unparseable line {} in {:?}",
+                    line_num + 1,
+                    issues_path
+                );
+                lines.push(IssueLine::Malformed(line.to_string()));
+            }
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Writes lines back to `issues.jsonl`.
+///
+/// The new contents are written to a sibling temp file and renamed into place
+/// so a crash mid-write never leaves a truncated board behind.
+fn write_issue_lines(issues_path: &Path, lines: &[IssueLine]) -> Result<(), String> {
+    let tmp_path = issues_path.with_extension("jsonl.tmp");
+
+    {
+        let file = std::fs::File::create(&tmp_path)
+            .map_err(|e| format!("Failed to open file for writing: {}", e))?;
+
+        let mut writer = std::io::BufWriter::new(file);
+        for line in lines {
+            let json_line = match line {
+                IssueLine::Record(record) => serde_json::to_string(record)
+                    .map_err(|e| format!("Failed to serialize bead: {}", e))?,
+                IssueLine::Malformed(raw) => raw.clone(),
+            };
+            writeln!(writer, "{}", json_line)
+                .map_err(|e| format!("Failed to write to file: {}", e))?;
+        }
+        // Make sure the contents are on disk before the rename makes them
+        // the board, so a shutdown or crash can't leave it half-written
+        writer
+            .into_inner()
+            .map_err(|e| format!("Failed to flush file: {}", e.error()))?
+            .sync_all()
+            .map_err(|e| format!("Failed to flush file: {}", e))?;
+    }
+
+    std::fs::rename(&tmp_path, issues_path)
+        .map_err(|e| format!("Failed to replace issues file: {}", e))?;
+    index::invalidate(issues_path);
+    Ok(())
+}
+
+/// Iterates over the parsed records, skipping malformed lines.
+fn records(lines: &[IssueLine]) -> impl Iterator<Item = &IssueRecord> {
+    lines.iter().filter_map(|line| match line {
+        IssueLine::Record(record) => Some(record),
+        IssueLine::Malformed(_) => None,
+    })
+}
+
+/// Returns the ID of a raw record, if present.
+fn record_id(record: &IssueRecord) -> Option<&str> {
+    record.get("id").and_then(|v| v.as_str())
+}
+
+/// Finds the index of the record with the given bead ID.
+fn find_record_index(lines: &[IssueLine], bead_id: &str) -> Option<usize> {
+    lines.iter().position(|line| match line {
+        IssueLine::Record(record) => record_id(record) == Some(bead_id),
+        IssueLine::Malformed(_) => false,
+    })
+}
+
+/// Returns a mutable reference to the record at `index`.
+///
+/// Only call with an index obtained from [`find_record_index`].
+fn record_at_mut(lines: &mut [IssueLine], index: usize) -> &mut IssueRecord {
+    match &mut lines[index] {
+        IssueLine::Record(record) => record,
+        IssueLine::Malformed(_) => unreachable!("index does not point at a record"),
+    }
+}
+
+/// Converts a raw record into the API representation.
+fn record_to_bead(record: &IssueRecord) -> Result<Bead, String> {
+    serde_json::from_value(serde_json::Value::Object(record.clone()))
+        .map_err(|e| format!("Failed to parse bead: {}", e))
+}
+
+/// Computes the ETag for issues file contents.
+///
+/// The tag is a hash of the file contents, so it changes whenever the board
+/// is modified by this server or by the `bd` CLI.
+fn issues_etag(contents: &str) -> String {
+    let mut hasher = EtagHasher::default();
+    hasher.update(contents.as_bytes());
+    hasher.finish()
+}
+
+/// Computes the same tag as [`issues_etag`] from contents fed in pieces,
+/// e.g. line by line while reading the file.
+#[derive(Clone, Default)]
+struct EtagHasher(std::collections::hash_map::DefaultHasher);
+
+impl EtagHasher {
+    fn update(&mut self, bytes: &[u8]) {
+        self.0.write(bytes);
+    }
+
+    fn finish(&self) -> String {
+        format!("\"{:016x}\"", self.0.finish())
+    }
+}
+
+/// Checks whether an `If-Match` header value matches the current ETag.
+///
+/// Accepts `*`, comma-separated lists, and weak (`W/`) tags.
+fn if_match_satisfied(if_match: &str, etag: &str) -> bool {
+    if_match.split(',').map(str::trim).any(|tag| {
+        tag == "*" || tag.strip_prefix("W/").unwrap_or(tag) == etag
+    })
+}
+
+/// Validates the request's `If-Match` header against the issues file.
+///
+/// Must be called while holding the issues file lock. Requests without an
+/// `If-Match` header are allowed through so that clients that do not track
+/// ETags keep working. On mismatch, returns a 412 response whose `details`
+/// carry the current board state and ETag so the client can reconcile.
+fn check_if_match(headers: &HeaderMap, issues_path: &Path) -> Result<(), AppError> {
+    let if_match = match headers.get(header::IF_MATCH) {
+        Some(value) => value.to_str().unwrap_or_default(),
+        None => return Ok(()),
+    };
+
+    let contents = std::fs::read_to_string(issues_path)
+        .map_err(|e| AppError::Internal(format!("Failed to read file: {}", e)))?;
+
+    let etag = issues_etag(&contents);
+    if if_match_satisfied(if_match, &etag) {
+        return Ok(());
+    }
+
+    let mut beads = parse_beads(&contents);
+    link_bead_relationships(&mut beads);
+
+    Err(
+        AppError::PreconditionFailed("The board has changed since it was loaded".to_string())
+            .with_details(serde_json::json!({ "etag": etag, "beads": beads })),
+    )
+}
+
 /// GET /api/beads?path=/path/to/project
 ///
 /// Reads the .beads/issues.jsonl file from the specified project path
-/// and returns an array of beads.
-pub async fn read_beads(Query(params): Query<BeadsParams>) -> impl IntoResponse {
+/// and returns an array of beads. The response carries an `ETag` header
+/// that mutating endpoints accept via `If-Match`.
+///
+/// Optional `status`, `issue_type`, `owner`, `priority`, `labels`, `milestone`,
+/// and `q` parameters
+/// filter the result, `sort` orders it, and `limit`/`offset` page through it.
+/// `total` is the number of beads matching the filters before paging.
+///
+/// Queries are answered from the SQLite bead index (see `beads::index`),
+/// falling back to parsing the file if the index is unavailable.
+///
+/// With `format=ndjson`, the matching beads are streamed as one JSON object
+/// per line instead (without `total`, and without `sort`), so very large
+/// boards are never held in memory as a whole.
+pub async fn read_beads(Query(params): Query<BeadsParams>) -> Response {
+    let priorities = match split_csv_param(params.priority.as_deref())
+        .into_iter()
+        .map(|p| p.parse::<i32>())
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(p) => p,
+        Err(_) => {
+            return AppError::BadRequest(
+                "Invalid priority filter: expected comma-separated integers".to_string(),
+            )
+            .into_response();
+        }
+    };
+
     let project_path = PathBuf::from(&params.path);
 
     // Security: Validate path is within allowed directories
     if let Err(e) = validate_path_security(&project_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
+        return AppError::Forbidden(e).into_response();
     }
 
     let issues_path = resolve_issues_path(&project_path);
 
     // Check if the file exists
     if !issues_path.exists() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "No .beads/issues.jsonl found at the specified path" })),
-        );
+        return AppError::NotFound(
+            "No .beads/issues.jsonl found at the specified path".to_string(),
+        )
+        .into_response();
+    }
+
+    if let Some(ref sort) = params.sort {
+        if let Err(e) = parse_sort(sort) {
+            return AppError::BadRequest(e).into_response();
+        }
+    }
+
+    match params.format.as_deref() {
+        None | Some("json") => {}
+        Some("ndjson") => {
+            if params.sort.is_some() {
+                return AppError::BadRequest(
+                    "sort is not supported with format=ndjson".to_string(),
+                )
+                .into_response();
+            }
+            return match std::fs::File::open(&issues_path) {
+                Ok(file) => stream_beads(file, params, priorities).await,
+                Err(e) => AppError::Internal(format!("Failed to read file: {}", e)).into_response(),
+            };
+        }
+        Some(other) => {
+            return AppError::BadRequest(format!(
+                "Invalid format '{}'. Expected 'json' or 'ndjson'",
+                other
+            ))
+            .into_response();
+        }
+    }
+
+    // Serve from the index when possible; the file stays the source of truth
+    match index::query(&issues_path, &params, &priorities) {
+        Ok(page) => {
+            return (
+                StatusCode::OK,
+                [(header::ETAG, page.etag)],
+                Json(serde_json::json!({ "beads": page.beads, "total": page.total })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            tracing::warn!("Bead index unavailable for {:?}, parsing the file: {}", issues_path, e);
+        }
     }
 
     // Read the file contents
     let contents = match std::fs::read_to_string(&issues_path) {
         Ok(c) => c,
         Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": format!("Failed to read file: {}", e) })),
-            );
+            return AppError::Internal(format!("Failed to read file: {}", e)).into_response();
         }
     };
 
-    // Parse JSONL (each line is a JSON object)
-    let mut beads = Vec::new();
-    for (line_num, line) in contents.lines().enumerate() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+    let etag = issues_etag(&contents);
+    let mut beads = parse_beads(&contents);
+    // Link before filtering so parent/children reflect the whole board
+    link_bead_relationships(&mut beads);
+
+    beads.retain(|bead| bead_matches(bead, &params, &priorities));
+
+    if let Some(ref sort) = params.sort {
+        if let Err(e) = sort_beads(&mut beads, sort) {
+            return AppError::BadRequest(e).into_response();
         }
+    }
 
-        match serde_json::from_str::<Bead>(line) {
-            Ok(bead) => beads.push(bead),
-            Err(e) => {
-                tracing::warn!(
-                    "Failed to parse bead at line {}: {} - {}",
-                    line_num + 1,
-                    e,
-                    line
-                );
-                // Continue parsing other lines - graceful handling of malformed lines
-            }
+    let total = beads.len();
+    let beads: Vec<Bead> = beads
+        .into_iter()
+        .skip(params.offset.unwrap_or(0))
+        .take(params.limit.unwrap_or(usize::MAX))
+        .collect();
+
+    (
+        StatusCode::OK,
+        [(header::ETAG, etag)],
+        Json(serde_json::json!({ "beads": beads, "total": total })),
+    )
+        .into_response()
+}
+
+/// Reads `reader` line by line, passing each line (with its line ending)
+/// and its index to `f` until the input ends or `f` returns `false`.
+fn for_each_line(
+    reader: &mut impl BufRead,
+    mut f: impl FnMut(usize, &[u8]) -> bool,
+) -> std::io::Result<()> {
+    let mut line = Vec::new();
+    for line_num in 0.. {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 || !f(line_num, &line) {
+            break;
         }
     }
+    Ok(())
+}
 
-    // Post-process: Transform dependencies into parent_id and children
-    // Build a map of parent_id -> Vec<child_id>
-    let mut parent_to_children: std::collections::HashMap<String, Vec<String>> =
-        std::collections::HashMap::new();
+/// First pass of a streamed read: the ETag of the whole file and the
+/// hierarchy (IDs and parent links) of its beads.
+fn scan_beads(reader: &mut impl BufRead) -> std::io::Result<(String, Hierarchy)> {
+    let mut hasher = EtagHasher::default();
+    let mut nodes = Vec::new();
+    for_each_line(reader, |line_num, line| {
+        hasher.update(line);
+        if let Some(bead) = parse_bead_line(line_num, line) {
+            nodes.push(HierarchyNode::of(&bead));
+        }
+        true
+    })?;
+    Ok((hasher.finish(), bead_hierarchy(&nodes)))
+}
 
-    // First pass: Extract parent-child relationships from explicit dependencies
-    for bead in &mut beads {
-        if let Some(deps) = &bead.dependencies {
-            for dep in deps {
-                if dep.dep_type == "parent-child" {
-                    // Set parent_id on this bead
-                    bead.parent_id = Some(dep.depends_on_id.clone());
-                    // Record this bead as a child of the parent
-                    parent_to_children
-                        .entry(dep.depends_on_id.clone())
-                        .or_default()
-                        .push(bead.id.clone());
-                }
+/// Serializes the beads matching `params` as newline-delimited JSON,
+/// passing it to `send` in chunks of about [`STREAM_CHUNK_SIZE`] bytes.
+///
+/// Beads are read, parsed, linked, and serialized one line at a time, using
+/// the `hierarchy` from [`scan_beads`] over the same input. Stops early
+/// when `send` returns `false`.
+fn ndjson_chunks(
+    reader: &mut impl BufRead,
+    hierarchy: &Hierarchy,
+    params: &BeadsParams,
+    priorities: &[i32],
+    mut send: impl FnMut(String) -> bool,
+) -> std::io::Result<()> {
+    let mut parents = hierarchy.parents.iter();
+    let mut skip = params.offset.unwrap_or(0);
+    let mut take = params.limit.unwrap_or(usize::MAX);
+    let mut chunk = String::new();
+    let mut open = true;
+
+    for_each_line(reader, |line_num, line| {
+        if take == 0 {
+            return false;
+        }
+        let Some(mut bead) = parse_bead_line(line_num, line) else {
+            return true;
+        };
+        bead.parent_id = parents.next().cloned().flatten();
+        bead.children = hierarchy.children.get(&bead.id).cloned();
+        link_relates_to(&mut bead);
+        if !bead_matches(&bead, params, priorities) {
+            return true;
+        }
+        if skip > 0 {
+            skip -= 1;
+            return true;
+        }
+        take -= 1;
+
+        match serde_json::to_string(&bead) {
+            Ok(line) => {
+                chunk.push_str(&line);
+                chunk.push('\n');
             }
+            Err(e) => tracing::warn!("Failed to serialize bead {}: {}", bead.id, e),
+        }
+        if chunk.len() >= STREAM_CHUNK_SIZE {
+            open = send(std::mem::take(&mut chunk));
         }
+        open
+    })?;
+    if open && !chunk.is_empty() {
+        send(chunk);
     }
+    Ok(())
+}
 
-    // Second pass: Infer parent-child from ID patterns (e.g., "64n.1" -> parent "64n")
-    // This matches how the bd CLI infers relationships when parent_id is not set
-    // Collect existing bead IDs first to avoid borrow issues
-    let bead_ids: std::collections::HashSet<String> =
-        beads.iter().map(|b| b.id.clone()).collect();
-
-    // Collect inferred relationships: (child_id, parent_id)
-    let inferred: Vec<(String, String)> = beads
-        .iter()
-        .filter_map(|bead| {
-            // Only infer if parent_id is not already set
-            if bead.parent_id.is_some() {
-                return None;
+/// Builds the streaming `format=ndjson` response for `GET /api/beads`.
+///
+/// The file is read twice through one handle: once for the ETag and the
+/// hierarchy, which are needed before the first bead is sent, and once
+/// to stream the beads. Neither pass holds more than a line of it.
+async fn stream_beads(file: std::fs::File, params: BeadsParams, priorities: Vec<i32>) -> Response {
+    let (etag_tx, etag_rx) = tokio::sync::oneshot::channel();
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::convert::Infallible>>(4);
+
+    tokio::task::spawn_blocking(move || {
+        let mut reader = std::io::BufReader::new(file);
+        let hierarchy = match scan_beads(&mut reader) {
+            Ok((etag, hierarchy)) => {
+                if etag_tx.send(Ok(etag)).is_err() {
+                    return;
+                }
+                hierarchy
             }
-            // Check if ID contains a dot (indicating potential child)
-            let dot_pos = bead.id.rfind('.')?;
-            let potential_parent = &bead.id[..dot_pos];
-            // Only infer if the parent exists
-            if bead_ids.contains(potential_parent) {
-                Some((bead.id.clone(), potential_parent.to_string()))
-            } else {
-                None
+            Err(e) => {
+                let _ = etag_tx.send(Err(e));
+                return;
             }
-        })
-        .collect();
+        };
+
+        // A failed send means the client went away
+        let streamed = reader.rewind().and_then(|_| {
+            ndjson_chunks(&mut reader, &hierarchy, &params, &priorities, |chunk| {
+                tx.blocking_send(Ok(chunk)).is_ok()
+            })
+        });
+        if let Err(e) = streamed {
+            tracing::warn!("Failed to stream beads: {}", e);
+        }
+    });
 
-    // Apply inferred relationships
-    for (child_id, inferred_parent_id) in &inferred {
-        // Set parent_id on the child bead
-        if let Some(bead) = beads.iter_mut().find(|b| &b.id == child_id) {
-            bead.parent_id = Some(inferred_parent_id.clone());
+    let etag = match etag_rx.await {
+        Ok(Ok(etag)) => etag,
+        Ok(Err(e)) => {
+            return AppError::Internal(format!("Failed to read file: {}", e)).into_response()
         }
-        // Record in parent_to_children map
-        parent_to_children
-            .entry(inferred_parent_id.clone())
-            .or_default()
-            .push(child_id.clone());
+        Err(_) => return AppError::Internal("Failed to read file".to_string()).into_response(),
+    };
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, NDJSON_CONTENT_TYPE.to_string()),
+            (header::ETAG, etag),
+        ],
+        Body::from_stream(tokio_stream::wrappers::ReceiverStream::new(rx)),
+    )
+        .into_response()
+}
+
+/// Parses JSONL contents (one JSON object per line) into beads.
+///
+/// Malformed lines are skipped with a warning logged via `tracing`.
+pub(crate) fn parse_beads(contents: &str) -> Vec<Bead> {
+    iter_beads(contents).collect()
+}
+
+/// Like [`parse_beads`], but parses lazily, one line at a time.
+fn iter_beads(contents: &str) -> impl Iterator<Item = Bead> + '_ {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(line_num, line)| parse_bead_line(line_num, line.as_bytes()))
+}
+
+/// Parses line `line_num` (counting from 0) of the issues file, or `None`
+/// for a blank or malformed line.
+fn parse_bead_line(line_num: usize, line: &[u8]) -> Option<Bead> {
+    let line = line.trim_ascii();
+    if line.is_empty() {
+        return None;
     }
 
-    // Third pass: Set children on parent beads
-    for bead in &mut beads {
-        if let Some(children) = parent_to_children.get(&bead.id) {
-            bead.children = Some(children.clone());
+    match serde_json::from_slice::<Bead>(line) {
+        Ok(bead) => Some(bead),
+        Err(e) => {
+            tracing::warn!(
+                "Failed to parse bead at line {}: {} - {}",
+                line_num + 1,
+                e,
+                String::from_utf8_lossy(line)
+            );
+            // Continue parsing other lines - graceful handling of malformed lines
+            None
         }
     }
+}
 
-    // Fourth pass: Extract relates-to dependencies into relates_to field
-    for bead in &mut beads {
-        if let Some(deps) = &bead.dependencies {
-            let related: Vec<String> = deps
+/// The fields of a bead that place it in the parent/child hierarchy.
+#[derive(Debug)]
+struct HierarchyNode {
+    id: String,
+    /// Parent ID present in the JSON itself
+    parent_id: Option<String>,
+    /// Targets of the bead's parent-child dependencies, in order
+    parent_deps: Vec<String>,
+}
+
+impl HierarchyNode {
+    fn of(bead: &Bead) -> Self {
+        Self {
+            id: bead.id.clone(),
+            parent_id: bead.parent_id.clone(),
+            parent_deps: bead
+                .dependencies
                 .iter()
-                .filter(|dep| dep.dep_type == "relates-to")
+                .flatten()
+                .filter(|dep| dep.dep_type == "parent-child")
                 .map(|dep| dep.depends_on_id.clone())
-                .collect();
-            if !related.is_empty() {
-                bead.relates_to = Some(related);
-            }
+                .collect(),
         }
     }
-
-    (StatusCode::OK, Json(serde_json::json!({ "beads": beads })))
 }
 
-/// Request body for adding a comment to a bead.
-#[derive(Debug, Deserialize)]
-pub struct AddCommentRequest {
-    /// The project path containing .beads/issues.jsonl
-    pub path: String,
-    /// The ID of the bead to add a comment to
-    pub bead_id: String,
-    /// The comment text
-    pub text: String,
-    /// The author of the comment (e.g., email address)
-    pub author: String,
+/// The parent of each bead (by position) and the children of each parent ID.
+#[derive(Debug, Default)]
+struct Hierarchy {
+    parents: Vec<Option<String>>,
+    children: HashMap<String, Vec<String>>,
 }
 
-/// Response for the add comment endpoint.
-#[derive(Debug, Serialize)]
-pub struct AddCommentResponse {
-    pub success: bool,
-    pub bead: Option<Bead>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
-}
+/// Derives the hierarchy from explicit parent-child dependencies, plus links
+/// inferred from dotted IDs (e.g., "64n.1" -> parent "64n").
+fn bead_hierarchy(nodes: &[HierarchyNode]) -> Hierarchy {
+    let mut parents: Vec<Option<String>> = nodes.iter().map(|n| n.parent_id.clone()).collect();
+    // Build a map of parent_id -> Vec<child_id>
+    let mut parent_to_children: HashMap<String, Vec<String>> = HashMap::new();
 
-/// POST /api/beads/comment
-///
-/// Adds a comment to a specific bead in the .beads/issues.jsonl file.
-pub async fn add_comment(Json(payload): Json<AddCommentRequest>) -> impl IntoResponse {
-    let project_path = PathBuf::from(&payload.path);
+    // First pass: Extract parent-child relationships from explicit dependencies
+    for (node, parent) in nodes.iter().zip(parents.iter_mut()) {
+        for dep in &node.parent_deps {
+            // Set parent_id on this bead
+            *parent = Some(dep.clone());
+            // Record this bead as a child of the parent
+            parent_to_children
+                .entry(dep.clone())
+                .or_default()
+                .push(node.id.clone());
+        }
+    }
 
-    // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&project_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(AddCommentResponse {
-                success: false,
-                bead: None,
-                error: Some(e),
-            }),
-        );
+    // Second pass: Infer parent-child from ID patterns (e.g., "64n.1" -> parent "64n")
+    // This matches how the bd CLI infers relationships when parent_id is not set
+    let bead_ids: std::collections::HashSet<&str> = nodes.iter().map(|n| n.id.as_str()).collect();
+    for (node, parent) in nodes.iter().zip(parents.iter_mut()) {
+        // Only infer if parent_id is not already set
+        if parent.is_some() {
+            continue;
+        }
+        // Check if ID contains a dot (indicating potential child)
+        let Some(dot_pos) = node.id.rfind('.') else {
+            continue;
+        };
+        let potential_parent = &node.id[..dot_pos];
+        // Only infer if the parent exists
+        if bead_ids.contains(potential_parent) {
+            *parent = Some(potential_parent.to_string());
+            parent_to_children
+                .entry(potential_parent.to_string())
+                .or_default()
+                .push(node.id.clone());
+        }
     }
 
+    Hierarchy {
+        parents,
+        children: parent_to_children,
+    }
+}
+
+/// Populates the derived `parent_id`, `children`, and `relates_to` fields.
+///
+/// See [`bead_hierarchy`] for how parents are determined.
+pub(crate) fn link_bead_relationships(beads: &mut [Bead]) {
+    let nodes: Vec<HierarchyNode> = beads.iter().map(HierarchyNode::of).collect();
+    let hierarchy = bead_hierarchy(&nodes);
+
+    for (bead, parent) in beads.iter_mut().zip(hierarchy.parents) {
+        bead.parent_id = parent;
+        if let Some(children) = hierarchy.children.get(&bead.id) {
+            bead.children = Some(children.clone());
+        }
+        link_relates_to(bead);
+    }
+}
+
+/// Populates `relates_to` from a bead's own relates-to dependencies.
+fn link_relates_to(bead: &mut Bead) {
+    if let Some(deps) = &bead.dependencies {
+        let related: Vec<String> = deps
+            .iter()
+            .filter(|dep| dep.dep_type == "relates-to")
+            .map(|dep| dep.depends_on_id.clone())
+            .collect();
+        if !related.is_empty() {
+            bead.relates_to = Some(related);
+        }
+    }
+}
+
+/// Query parameters for the aggregate beads endpoint.
+#[derive(Debug, Deserialize)]
+pub struct AllBeadsParams {
+    /// Comma-separated tag IDs; only projects with at least one of these tags are included.
+    #[serde(default)]
+    pub tags: Option<String>,
+    /// Comma-separated project IDs; only these registered projects are included.
+    #[serde(default)]
+    pub projects: Option<String>,
+}
+
+/// A bead annotated with the registered project it was read from.
+#[derive(Debug, Serialize)]
+pub struct ProjectBead {
+    pub project_id: String,
+    pub project_name: String,
+    pub project_path: String,
+    #[serde(flatten)]
+    pub bead: Bead,
+}
+
+/// Per-project summary included in the aggregate beads response.
+#[derive(Debug, Serialize)]
+pub struct ProjectBeadsSummary {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    /// Number of beads read from this project.
+    pub count: usize,
+    /// Set when the project's issues file could not be read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Splits a comma-separated query value into trimmed, non-empty items.
+fn split_csv_param(value: Option<&str>) -> Vec<&str> {
+    value
+        .map(|v| v.split(',').map(str::trim).filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// Selects the registered projects matching the tag and project ID filters.
+///
+/// An empty filter matches everything; when both are given a project must
+/// satisfy both.
+fn select_projects<'a>(
+    projects: &'a [ProjectWithTags],
+    tag_ids: &[&str],
+    project_ids: &[&str],
+) -> Vec<&'a ProjectWithTags> {
+    projects
+        .iter()
+        .filter(|p| project_ids.is_empty() || project_ids.contains(&p.id.as_str()))
+        .filter(|p| tag_ids.is_empty() || p.tags.iter().any(|t| tag_ids.contains(&t.id.as_str())))
+        .collect()
+}
+
+/// GET /api/beads/all?tags=tag1,tag2&projects=id1,id2
+///
+/// Merges the beads of all selected registered projects into a single list,
+/// attributing each bead to its project. Issue files are read concurrently,
+/// and a project whose file is missing or unreadable is reported in the
+/// `projects` summary rather than failing the whole request.
+pub async fn read_all_beads(
+    State(db): State<AppState>,
+    Query(params): Query<AllBeadsParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let projects = db
+        .get_projects_with_tags()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let tag_ids = split_csv_param(params.tags.as_deref());
+    let project_ids = split_csv_param(params.projects.as_deref());
+    let selected = select_projects(&projects, &tag_ids, &project_ids);
+
+    // Batch the file reads so slow disks don't serialize the whole request
+    let reads = selected.iter().map(|project| {
+        let project_path = PathBuf::from(&project.path);
+        async move {
+            validate_path_security(&project_path)?;
+            let issues_path = resolve_issues_path(&project_path);
+            tokio::fs::read_to_string(&issues_path)
+                .await
+                .map_err(|e| format!("Failed to read file: {}", e))
+        }
+    });
+    let results = futures::future::join_all(reads).await;
+
+    let mut beads: Vec<ProjectBead> = Vec::new();
+    let mut summaries: Vec<ProjectBeadsSummary> = Vec::new();
+
+    for (project, result) in selected.into_iter().zip(results) {
+        let mut summary = ProjectBeadsSummary {
+            id: project.id.clone(),
+            name: project.name.clone(),
+            path: project.path.clone(),
+            count: 0,
+            error: None,
+        };
+
+        match result {
+            Ok(contents) => {
+                let mut project_beads = parse_beads(&contents);
+                link_bead_relationships(&mut project_beads);
+                summary.count = project_beads.len();
+                beads.extend(project_beads.into_iter().map(|bead| ProjectBead {
+                    project_id: project.id.clone(),
+                    project_name: project.name.clone(),
+                    project_path: project.path.clone(),
+                    bead,
+                }));
+            }
+            Err(e) => {
+                tracing::warn!("Skipping beads for project {}: {}", project.path, e);
+                summary.error = Some(e);
+            }
+        }
+
+        summaries.push(summary);
+    }
+
+    Ok(Json(
+        serde_json::json!({ "beads": beads, "projects": summaries }),
+    ))
+}
+
+/// Request body for adding a comment to a bead.
+#[derive(Debug, Deserialize)]
+pub struct AddCommentRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// The ID of the bead to add a comment to
+    pub bead_id: String,
+    /// The comment text
+    pub text: String,
+    /// The author of the comment (e.g., email address); replaced by the
+    /// signed-in user's name when OIDC login is enabled
+    pub author: String,
+}
+
+/// Response for the add comment endpoint.
+#[derive(Debug, Serialize)]
+pub struct AddCommentResponse {
+    pub success: bool,
+    pub bead: Option<Bead>,
+}
+
+/// POST /api/beads/comment
+///
+/// Adds a comment to a specific bead in the .beads/issues.jsonl file.
+pub async fn add_comment(
+    identity: Option<Extension<Identity>>,
+    Json(payload): Json<AddCommentRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let project_path = PathBuf::from(&payload.path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
     let issues_path = resolve_issues_path(&project_path);
 
     // Check if the file exists
     if !issues_path.exists() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(AddCommentResponse {
-                success: false,
-                bead: None,
-                error: Some("No .beads/issues.jsonl found at the specified path".to_string()),
-            }),
-        );
+        return Err(AppError::NotFound(
+            "No .beads/issues.jsonl found at the specified path".to_string(),
+        ));
     }
 
-    // Read the file contents
-    let contents = match std::fs::read_to_string(&issues_path) {
-        Ok(c) => c,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AddCommentResponse {
-                    success: false,
-                    bead: None,
-                    error: Some(format!("Failed to read file: {}", e)),
-                }),
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    // Track the maximum comment ID across all beads
+    let max_comment_id = records(&lines)
+        .filter_map(|record| record.get("comments").and_then(|c| c.as_array()))
+        .flatten()
+        .filter_map(|comment| comment.get("id").and_then(|id| id.as_i64()))
+        .max()
+        .unwrap_or(0);
+
+    // Check if the bead was found
+    let bead_index = match find_record_index(&lines, &payload.bead_id) {
+        Some(idx) => idx,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                payload.bead_id
+            )));
+        }
+    };
+
+    // Create the new comment
+    let new_comment = Comment {
+        id: max_comment_id + 1,
+        issue_id: payload.bead_id.clone(),
+        author: match identity {
+            Some(Extension(identity)) => identity.display_name().to_string(),
+            None => payload.author,
+        },
+        text: payload.text,
+        created_at: Utc::now().to_rfc3339(),
+    };
+
+    // Add the comment to the bead
+    let record = record_at_mut(&mut lines, bead_index);
+    let comment_value = serde_json::to_value(&new_comment).unwrap_or_default();
+    match record.get_mut("comments").and_then(|c| c.as_array_mut()) {
+        Some(comments) => comments.push(comment_value),
+        None => {
+            record.insert(
+                "comments".to_string(),
+                serde_json::Value::Array(vec![comment_value]),
             );
         }
+    }
+    let updated_bead = record_to_bead(record);
+
+    // Write the updated beads back to the file
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "comment", &before, &lines);
+
+    // Mentions are best-effort: the comment is saved even if notifying fails
+    if let Err(e) = notify_mentions(&issues_path, &new_comment).await {
+        tracing::warn!("Failed to record mention notifications: {}", e);
+    }
+
+    // Return the updated bead
+    Ok(Json(AddCommentResponse {
+        success: true,
+        bead: updated_bead.ok(),
+    }))
+}
+
+/// Statuses a bead can be in, in board column order.
+const BEAD_STATUSES: &[&str] = &["open", "in_progress", "inreview", "closed"];
+
+/// Validates a bead priority (bd uses 0 = critical through 4 = backlog).
+pub(crate) fn validate_priority(priority: i32) -> Result<(), String> {
+    if (0..=4).contains(&priority) {
+        Ok(())
+    } else {
+        Err(format!("Invalid priority {}: must be between 0 and 4", priority))
+    }
+}
+
+/// Validates a bead status against [`BEAD_STATUSES`].
+pub(crate) fn validate_status(status: &str) -> Result<(), String> {
+    if BEAD_STATUSES.contains(&status) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid status '{}'. Allowed statuses: {:?}",
+            status, BEAD_STATUSES
+        ))
+    }
+}
+
+/// Sets `status` on a record, maintaining `closed_at` and `updated_at`.
+///
+/// Closing a bead stamps `closed_at`; moving it out of `closed` clears
+/// `closed_at` and `close_reason`.
+fn apply_status(record: &mut IssueRecord, status: &str, now: &str) {
+    let was_closed = record.get("status").and_then(|s| s.as_str()) == Some("closed");
+    record.insert("status".to_string(), serde_json::json!(status));
+
+    if status == "closed" {
+        if !was_closed {
+            record.insert("closed_at".to_string(), serde_json::json!(now));
+        }
+    } else {
+        record.remove("closed_at");
+        record.remove("close_reason");
+    }
+
+    record.insert("updated_at".to_string(), serde_json::json!(now));
+}
+
+/// Reads the `issue-prefix` configured in `.beads/config.yaml`, if any.
+fn configured_issue_prefix(project_path: &Path) -> Option<String> {
+    let config_path = project_path.join(".beads").join("config.yaml");
+    let contents = std::fs::read_to_string(config_path).ok()?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(&contents).ok()?;
+    yaml.get("issue-prefix")
+        .and_then(|v| v.as_str())
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+}
+
+/// Determines the ID prefix for new beads.
+///
+/// Uses the configured `issue-prefix`, then the prefix of an existing
+/// top-level bead (everything before the last `-`), then the project
+/// directory name.
+fn issue_prefix(project_path: &Path, existing_ids: &[&str]) -> String {
+    if let Some(prefix) = configured_issue_prefix(project_path) {
+        return prefix;
+    }
+
+    let inferred = existing_ids
+        .iter()
+        .filter(|id| !id.contains('.'))
+        .find_map(|id| id.rfind('-').map(|pos| id[..pos].to_string()))
+        .filter(|p| !p.is_empty());
+    if let Some(prefix) = inferred {
+        return prefix;
+    }
+
+    project_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| "bd".to_string())
+}
+
+/// Generates a short base36 suffix, similar to the hash IDs `bd` produces.
+fn random_id_suffix(len: usize) -> String {
+    const ALPHABET: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut n = Uuid::new_v4().as_u128();
+    (0..len)
+        .map(|_| {
+            let c = ALPHABET[(n % 36) as usize] as char;
+            n /= 36;
+            c
+        })
+        .collect()
+}
+
+/// Allocates a new bead ID that does not collide with any existing ID.
+fn generate_bead_id(prefix: &str, existing_ids: &[&str]) -> String {
+    let mut len = 3;
+    loop {
+        // Try a handful of candidates at this length before growing the suffix
+        for _ in 0..8 {
+            let candidate = format!("{}-{}", prefix, random_id_suffix(len));
+            if !existing_ids.contains(&candidate.as_str()) {
+                return candidate;
+            }
+        }
+        len += 1;
+    }
+}
+
+/// Request body for creating a bead.
+#[derive(Debug, Deserialize)]
+pub struct CreateBeadRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Initial status (defaults to "open").
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub priority: Option<i32>,
+    #[serde(default)]
+    pub issue_type: Option<String>,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub design_doc: Option<String>,
+    /// The creator recorded in `created_by` (e.g., email address).
+    #[serde(default)]
+    pub created_by: Option<String>,
+    /// Milestone to assign the bead to.
+    #[serde(default)]
+    pub milestone: Option<String>,
+    /// Explicit ID for the new bead; generated when omitted.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Recurrence rule (e.g., `FREQ=WEEKLY;BYDAY=MO`); see `beads::recurrence`.
+    #[serde(default)]
+    pub recurrence: Option<String>,
+    /// Custom field values, validated against the project's field schema.
+    #[serde(default)]
+    pub custom: Option<CustomValues>,
+}
+
+/// Request body for updating a bead.
+///
+/// Only provided fields are changed. Passing an empty string for
+/// `description`, `owner`, `design_doc`, `milestone`, or `recurrence` clears
+/// that field.
+/// Custom values are merged into the existing ones.
+#[derive(Debug, Deserialize)]
+pub struct UpdateBeadRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub priority: Option<i32>,
+    #[serde(default)]
+    pub issue_type: Option<String>,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub design_doc: Option<String>,
+    #[serde(default)]
+    pub milestone: Option<String>,
+    #[serde(default)]
+    pub recurrence: Option<String>,
+    /// Custom field values to set; `null` clears a field.
+    #[serde(default)]
+    pub custom: Option<CustomValues>,
+}
+
+/// Request body for deleting a bead.
+#[derive(Debug, Deserialize)]
+pub struct DeleteBeadRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+}
+
+/// Sets an optional text field on a record, removing it when the value is empty.
+fn set_optional_text(record: &mut IssueRecord, key: &str, value: &str) {
+    if value.is_empty() {
+        record.remove(key);
+    } else {
+        record.insert(key.to_string(), serde_json::json!(value));
+    }
+}
+
+/// Merges custom field values into a record's `custom` object.
+///
+/// `null` values remove the field; the object is dropped when it ends up empty.
+fn merge_custom_values(record: &mut IssueRecord, values: &CustomValues) {
+    let mut custom = match record.remove("custom") {
+        Some(serde_json::Value::Object(existing)) => existing,
+        _ => CustomValues::new(),
+    };
+    for (name, value) in values {
+        if value.is_null() {
+            custom.remove(name);
+        } else {
+            custom.insert(name.clone(), value.clone());
+        }
+    }
+    if !custom.is_empty() {
+        record.insert("custom".to_string(), serde_json::Value::Object(custom));
+    }
+}
+
+/// Returns the key used for the design doc on this record.
+///
+/// bd writes `design`; older files may use `design_doc`. Whichever is already
+/// present is updated so the record never carries both.
+fn design_key(record: &IssueRecord) -> &'static str {
+    if record.contains_key("design_doc") {
+        "design_doc"
+    } else {
+        "design"
+    }
+}
+
+/// Applies an update request to a record.
+///
+/// A status change must be a transition [`validate_transition`] allows, as
+/// through `POST /api/beads/:id/status`; resending the current status
+/// leaves it alone. Nothing is changed when the transition is refused.
+fn apply_bead_update(
+    record: &mut IssueRecord,
+    update: &UpdateBeadRequest,
+    now: &str,
+) -> Result<(), String> {
+    let current_status = record
+        .get("status")
+        .and_then(|s| s.as_str())
+        .unwrap_or("open")
+        .to_string();
+    let new_status = update
+        .status
+        .as_deref()
+        .filter(|status| *status != current_status);
+    if let Some(status) = new_status {
+        validate_transition(&current_status, status)?;
+    }
+
+    if let Some(ref title) = update.title {
+        record.insert("title".to_string(), serde_json::json!(title));
+    }
+    if let Some(ref description) = update.description {
+        set_optional_text(record, "description", description);
+    }
+    if let Some(priority) = update.priority {
+        record.insert("priority".to_string(), serde_json::json!(priority));
+    }
+    if let Some(ref issue_type) = update.issue_type {
+        record.insert("issue_type".to_string(), serde_json::json!(issue_type));
+    }
+    if let Some(ref owner) = update.owner {
+        set_optional_text(record, "owner", owner);
+    }
+    if let Some(ref design_doc) = update.design_doc {
+        let key = design_key(record);
+        set_optional_text(record, key, design_doc);
+    }
+    if let Some(ref milestone) = update.milestone {
+        set_optional_text(record, "milestone", milestone);
+    }
+    if let Some(ref recurrence) = update.recurrence {
+        set_optional_text(record, "recurrence", recurrence.trim());
+    }
+    if let Some(ref custom) = update.custom {
+        merge_custom_values(record, custom);
+    }
+    match new_status {
+        Some(status) => apply_status(record, status, now),
+        None => {
+            record.insert("updated_at".to_string(), serde_json::json!(now));
+        }
+    }
+    Ok(())
+}
+
+/// Removes all dependency entries pointing at `bead_id`.
+///
+/// Returns the IDs of records that were changed.
+fn remove_dependency_references(lines: &mut [IssueLine], bead_id: &str) -> Vec<String> {
+    let mut updated = Vec::new();
+    for line in lines.iter_mut() {
+        let IssueLine::Record(record) = line else {
+            continue;
+        };
+        let Some(deps) = record.get_mut("dependencies").and_then(|d| d.as_array_mut()) else {
+            continue;
+        };
+        let before = deps.len();
+        deps.retain(|dep| dep.get("depends_on_id").and_then(|d| d.as_str()) != Some(bead_id));
+        if deps.len() != before {
+            if let Some(id) = record_id(record) {
+                updated.push(id.to_string());
+            }
+        }
+    }
+    updated
+}
+
+/// Checks that each non-empty milestone being assigned exists.
+fn validate_milestone_assignments<'a>(
+    issues_path: &Path,
+    milestones: impl IntoIterator<Item = Option<&'a str>>,
+) -> Result<(), AppError> {
+    for milestone in milestones.into_iter().flatten().filter(|m| !m.is_empty()) {
+        match milestone_exists(issues_path, milestone) {
+            Ok(true) => {}
+            Ok(false) => {
+                return Err(AppError::BadRequest(format!(
+                    "Milestone with id '{}' not found",
+                    milestone
+                )));
+            }
+            Err(e) => {
+                return Err(AppError::Internal(e));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether a bead exists in the issues file (false if the file is missing).
+pub(crate) fn bead_exists(issues_path: &Path, bead_id: &str) -> Result<bool, String> {
+    if !issues_path.exists() {
+        return Ok(false);
+    }
+    let lines = read_issue_lines(issues_path)?;
+    Ok(find_record_index(&lines, bead_id).is_some())
+}
+
+/// Validates the project path and resolves its issues file for a mutation.
+///
+/// Returns an error response when the path is rejected or no issues file exists.
+fn mutation_issues_path(project: &str) -> Result<PathBuf, AppError> {
+    let project_path = PathBuf::from(project);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    let issues_path = resolve_issues_path(&project_path);
+    if !issues_path.exists() {
+        return Err(AppError::NotFound(
+            "No .beads/issues.jsonl found at the specified path".to_string(),
+        ));
+    }
+
+    Ok(issues_path)
+}
+
+/// Validates the fields of a create request, returning the initial status.
+fn validate_create_request(payload: &CreateBeadRequest) -> Result<String, String> {
+    if payload.title.trim().is_empty() {
+        return Err("Title must not be empty".to_string());
+    }
+
+    let status = payload.status.clone().unwrap_or_else(|| "open".to_string());
+    validate_status(&status)?;
+    if let Some(priority) = payload.priority {
+        validate_priority(priority)?;
+    }
+    if let Some(ref recurrence) = payload.recurrence {
+        validate_recurrence(recurrence)?;
+    }
+    Ok(status)
+}
+
+/// Builds the record for a new bead from a create request.
+fn new_bead_record(id: &str, payload: &CreateBeadRequest, status: &str, now: &str) -> IssueRecord {
+    let mut record = IssueRecord::new();
+    record.insert("id".to_string(), serde_json::json!(id));
+    record.insert("title".to_string(), serde_json::json!(payload.title.trim()));
+    if let Some(ref description) = payload.description {
+        set_optional_text(&mut record, "description", description);
+    }
+    record.insert("status".to_string(), serde_json::json!(status));
+    record.insert("priority".to_string(), serde_json::json!(payload.priority.unwrap_or(2)));
+    record.insert(
+        "issue_type".to_string(),
+        serde_json::json!(payload.issue_type.as_deref().unwrap_or("task")),
+    );
+    if let Some(ref owner) = payload.owner {
+        set_optional_text(&mut record, "owner", owner);
+    }
+    if let Some(ref design_doc) = payload.design_doc {
+        set_optional_text(&mut record, "design", design_doc);
+    }
+    record.insert("created_at".to_string(), serde_json::json!(now));
+    if let Some(ref created_by) = payload.created_by {
+        set_optional_text(&mut record, "created_by", created_by);
+    }
+    if let Some(ref milestone) = payload.milestone {
+        set_optional_text(&mut record, "milestone", milestone);
+    }
+    if let Some(ref recurrence) = payload.recurrence {
+        set_optional_text(&mut record, "recurrence", recurrence.trim());
+    }
+    if let Some(ref custom) = payload.custom {
+        merge_custom_values(&mut record, custom);
+    }
+    record.insert("updated_at".to_string(), serde_json::json!(now));
+    if status == "closed" {
+        record.insert("closed_at".to_string(), serde_json::json!(now));
+    }
+    record
+}
+
+/// Returns the next free child ID (`{parent}.{n}`) for a parent bead.
+///
+/// `n` is one more than the highest existing direct child number, so IDs of
+/// deleted children are not reused.
+fn next_child_id(parent_id: &str, existing_ids: &[&str]) -> String {
+    let prefix = format!("{}.", parent_id);
+    let max = existing_ids
+        .iter()
+        .filter_map(|id| id.strip_prefix(&prefix))
+        .filter_map(|suffix| suffix.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0);
+    format!("{}{}", prefix, max + 1)
+}
+
+/// POST /api/beads
+///
+/// Creates a new bead in the .beads/issues.jsonl file. An ID is generated
+/// from the project's issue prefix unless one is provided.
+pub async fn create_bead(
+    Json(payload): Json<CreateBeadRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let status = validate_create_request(&payload).map_err(AppError::BadRequest)?;
+
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    validate_milestone_assignments(&issues_path, [payload.milestone.as_deref()])?;
+    if let Some(ref custom) = payload.custom {
+        validate_custom_values(&issues_path, custom)?;
+    }
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let existing_ids: Vec<&str> = records(&lines).filter_map(record_id).collect();
+    let id = new_bead_id(Path::new(&payload.path), payload.id.as_deref(), &existing_ids)?;
+
+    let record = new_bead_record(&id, &payload, &status, &Utc::now().to_rfc3339());
+    let bead = record_to_bead(&record);
+    lines.push(IssueLine::Record(record));
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "create", &before, &lines);
+
+    match bead {
+        Ok(bead) => Ok((
+            StatusCode::CREATED,
+            Json(serde_json::json!({ "success": true, "bead": bead })),
+        )),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// Picks the ID of a new bead: the requested one, trimmed, or else the next
+/// free ID with the project's prefix. A requested ID that is blank or taken
+/// is rejected.
+fn new_bead_id(
+    project_path: &Path,
+    requested: Option<&str>,
+    existing_ids: &[&str],
+) -> Result<String, AppError> {
+    let Some(requested) = requested else {
+        let prefix = issue_prefix(project_path, existing_ids);
+        return Ok(generate_bead_id(&prefix, existing_ids));
+    };
+
+    let id = requested.trim();
+    if existing_ids.contains(&id) {
+        return Err(AppError::Conflict(format!(
+            "Bead with id '{}' already exists",
+            id
+        )));
+    }
+    if id.is_empty() {
+        return Err(AppError::BadRequest("Bead id must not be empty".to_string()));
+    }
+    Ok(id.to_string())
+}
+
+/// POST /api/beads/:id/children
+///
+/// Creates a child of an existing bead. The child gets the next free
+/// `{parent}.{n}` ID and a `parent-child` dependency on the parent, so the
+/// relationship holds whether it is read from the ID or the dependency.
+/// Any `id` in the request body is ignored.
+pub async fn create_child_bead(
+    AxumPath(parent_id): AxumPath<String>,
+    Json(payload): Json<CreateBeadRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let status = validate_create_request(&payload).map_err(AppError::BadRequest)?;
+
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    validate_milestone_assignments(&issues_path, [payload.milestone.as_deref()])?;
+    if let Some(ref custom) = payload.custom {
+        validate_custom_values(&issues_path, custom)?;
+    }
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    if find_record_index(&lines, &parent_id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Bead with id '{}' not found",
+            parent_id
+        )));
+    }
+
+    let existing_ids: Vec<&str> = records(&lines).filter_map(record_id).collect();
+    let id = next_child_id(&parent_id, &existing_ids);
+
+    let now = Utc::now().to_rfc3339();
+    let mut record = new_bead_record(&id, &payload, &status, &now);
+    let mut dependency = serde_json::json!({
+        "issue_id": id,
+        "depends_on_id": parent_id,
+        "type": "parent-child",
+        "created_at": now
+    });
+    if let Some(created_by) = payload.created_by.as_deref().filter(|c| !c.is_empty()) {
+        dependency["created_by"] = serde_json::json!(created_by);
+    }
+    record.insert("dependencies".to_string(), serde_json::json!([dependency]));
+
+    let bead = record_to_bead(&record);
+    lines.push(IssueLine::Record(record));
+    let updated_epics = recompute_epic_lines(&mut lines);
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "create", &before, &lines);
+
+    match bead {
+        Ok(bead) => Ok((
+            StatusCode::CREATED,
+            Json(serde_json::json!({
+                "success": true,
+                "bead": bead,
+                "updated_epics": updated_epics
+            })),
+        )),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// PATCH /api/beads/:id
+///
+/// Updates the provided fields of a bead and stamps `updated_at`. A status
+/// change is checked and recomputes epics as `POST /api/beads/:id/status`
+/// does. Honors `If-Match` (see [`check_if_match`]).
+pub async fn update_bead(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<UpdateBeadRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if let Some(ref title) = payload.title {
+        if title.trim().is_empty() {
+            return Err(AppError::BadRequest("Title must not be empty".to_string()));
+        }
+    }
+    if let Some(ref status) = payload.status {
+        validate_status(status).map_err(AppError::BadRequest)?;
+    }
+    if let Some(priority) = payload.priority {
+        validate_priority(priority).map_err(AppError::BadRequest)?;
+    }
+    if let Some(ref recurrence) = payload.recurrence {
+        validate_recurrence(recurrence).map_err(AppError::BadRequest)?;
+    }
+
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    validate_milestone_assignments(&issues_path, [payload.milestone.as_deref()])?;
+    if let Some(ref custom) = payload.custom {
+        validate_custom_values(&issues_path, custom)?;
+    }
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let index = match find_record_index(&lines, &bead_id) {
+        Some(i) => i,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                bead_id
+            )));
+        }
+    };
+
+    let now = Utc::now().to_rfc3339();
+    let record = record_at_mut(&mut lines, index);
+    apply_bead_update(record, &payload, &now).map_err(AppError::Conflict)?;
+
+    // Epics follow their children's statuses
+    let updated_epics = if payload.status.is_some() {
+        recompute_epic_lines(&mut lines)
+    } else {
+        Vec::new()
+    };
+    let bead = match &lines[index] {
+        IssueLine::Record(record) => record_to_bead(record),
+        IssueLine::Malformed(_) => unreachable!("find_record_index only matches records"),
+    };
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "update", &before, &lines);
+
+    match bead {
+        Ok(bead) => Ok(Json(serde_json::json!({
+            "success": true,
+            "bead": bead,
+            "updated_epics": updated_epics
+        }))),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// DELETE /api/beads/:id
+///
+/// Removes a bead from the .beads/issues.jsonl file, along with any
+/// dependency entries on other beads that point at it.
+/// Honors `If-Match` (see [`check_if_match`]).
+pub async fn delete_bead(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<DeleteBeadRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let index = match find_record_index(&lines, &bead_id) {
+        Some(i) => i,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                bead_id
+            )));
+        }
+    };
+
+    lines.remove(index);
+    let updated_references = remove_dependency_references(&mut lines, &bead_id);
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "delete", &before, &lines);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "deleted": bead_id,
+        "updated_references": updated_references
+    })))
+}
+
+/// Request body for adding or removing bead labels.
+#[derive(Debug, Deserialize)]
+pub struct LabelsRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    pub labels: Vec<String>,
+}
+
+/// Normalizes requested labels: trims whitespace and drops empty entries.
+fn normalize_labels(labels: &[String]) -> Vec<String> {
+    labels
+        .iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// Adds labels to a record, skipping any it already has.
+fn add_record_labels(record: &mut IssueRecord, labels: &[String]) {
+    let current = record
+        .entry("labels")
+        .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    if !current.is_array() {
+        *current = serde_json::Value::Array(Vec::new());
+    }
+    if let Some(current) = current.as_array_mut() {
+        for label in labels {
+            if !current.iter().any(|l| l.as_str() == Some(label)) {
+                current.push(serde_json::json!(label));
+            }
+        }
+    }
+}
+
+/// Removes labels from a record, dropping the field when none remain.
+fn remove_record_labels(record: &mut IssueRecord, labels: &[String]) {
+    let now_empty = match record.get_mut("labels").and_then(|l| l.as_array_mut()) {
+        Some(current) => {
+            current.retain(|l| !l.as_str().is_some_and(|l| labels.iter().any(|r| r == l)));
+            current.is_empty()
+        }
+        None => false,
+    };
+    if now_empty {
+        record.remove("labels");
+    }
+}
+
+/// Shared read-modify-write cycle for the label endpoints.
+async fn modify_labels(
+    headers: &HeaderMap,
+    bead_id: &str,
+    payload: &LabelsRequest,
+    remove: bool,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let labels = normalize_labels(&payload.labels);
+    if labels.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one non-empty label must be provided".to_string(),
+        ));
+    }
+
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let index = match find_record_index(&lines, bead_id) {
+        Some(i) => i,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                bead_id
+            )));
+        }
+    };
+
+    let record = record_at_mut(&mut lines, index);
+    if remove {
+        remove_record_labels(record, &labels);
+    } else {
+        add_record_labels(record, &labels);
+    }
+    record.insert(
+        "updated_at".to_string(),
+        serde_json::json!(Utc::now().to_rfc3339()),
+    );
+    let bead = record_to_bead(record);
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "labels", &before, &lines);
+
+    match bead {
+        Ok(bead) => Ok(Json(serde_json::json!({ "success": true, "bead": bead }))),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// POST /api/beads/:id/labels
+///
+/// Adds labels to a bead. Labels already present are left unchanged.
+/// Honors `If-Match` (see [`check_if_match`]).
+pub async fn add_labels(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<LabelsRequest>,
+) -> impl IntoResponse {
+    modify_labels(&headers, &bead_id, &payload, false).await
+}
+
+/// DELETE /api/beads/:id/labels
+///
+/// Removes labels from a bead. Labels the bead does not have are ignored.
+/// Honors `If-Match` (see [`check_if_match`]).
+pub async fn remove_labels(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<LabelsRequest>,
+) -> impl IntoResponse {
+    modify_labels(&headers, &bead_id, &payload, true).await
+}
+
+/// Checks whether a bead may move from one status to another.
+///
+/// Beads advance one column at a time along [`BEAD_STATUSES`]
+/// (open -> in_progress -> inreview -> closed), and may be sent back to
+/// any earlier column (e.g., reopened or returned from review).
+fn validate_transition(from: &str, to: &str) -> Result<(), String> {
+    let position = |status: &str| BEAD_STATUSES.iter().position(|s| *s == status);
+
+    let to_pos = position(to).ok_or_else(|| {
+        format!(
+            "Invalid status '{}'. Allowed statuses: {:?}",
+            to, BEAD_STATUSES
+        )
+    })?;
+    let from_pos = position(from)
+        .ok_or_else(|| format!("Bead has unknown current status '{}'", from))?;
+
+    if to_pos == from_pos + 1 || to_pos < from_pos {
+        Ok(())
+    } else {
+        Err(format!("Cannot transition bead from '{}' to '{}'", from, to))
+    }
+}
+
+/// Request body for changing a bead's status.
+#[derive(Debug, Deserialize)]
+pub struct StatusTransitionRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// The target status
+    pub status: String,
+    /// Optional reason recorded when closing the bead
+    #[serde(default)]
+    pub close_reason: Option<String>,
+}
+
+/// POST /api/beads/:id/status
+///
+/// Moves a bead to a new status, then recomputes the statuses of any
+/// epics whose children changed as a result.
+/// Honors `If-Match` (see [`check_if_match`]).
+pub async fn transition_bead_status(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<StatusTransitionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    validate_status(&payload.status).map_err(AppError::BadRequest)?;
+
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let index = match find_record_index(&lines, &bead_id) {
+        Some(i) => i,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                bead_id
+            )));
+        }
+    };
+
+    let record = record_at_mut(&mut lines, index);
+    let current_status = record
+        .get("status")
+        .and_then(|s| s.as_str())
+        .unwrap_or("open")
+        .to_string();
+
+    validate_transition(&current_status, &payload.status).map_err(AppError::Conflict)?;
+
+    let now = Utc::now().to_rfc3339();
+    apply_status(record, &payload.status, &now);
+    if payload.status == "closed" {
+        if let Some(ref reason) = payload.close_reason {
+            set_optional_text(record, "close_reason", reason);
+        }
+    }
+
+    // Recompute epics in the same pass so the file is written once
+    let updated_epics = recompute_epic_lines(&mut lines);
+    let bead = match &lines[index] {
+        IssueLine::Record(record) => record_to_bead(record),
+        IssueLine::Malformed(_) => unreachable!("find_record_index only matches records"),
+    };
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "status", &before, &lines);
+
+    match bead {
+        Ok(bead) => Ok(Json(serde_json::json!({
+            "success": true,
+            "bead": bead,
+            "updated_epics": updated_epics
+        }))),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// A single bead patch within a bulk update.
+#[derive(Debug, Deserialize)]
+pub struct BulkBeadPatch {
+    pub id: String,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub priority: Option<i32>,
+    /// New owner; an empty string clears it.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Milestone ID; an empty string unassigns the bead.
+    #[serde(default)]
+    pub milestone: Option<String>,
+}
+
+/// Request body for the bulk update endpoint.
+#[derive(Debug, Deserialize)]
+pub struct BulkUpdateRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    pub updates: Vec<BulkBeadPatch>,
+}
+
+/// Validates every patch in a bulk update before any is applied.
+fn validate_bulk_patches(patches: &[BulkBeadPatch]) -> Result<(), String> {
+    if patches.is_empty() {
+        return Err("No updates provided".to_string());
+    }
+    for patch in patches {
+        if let Some(ref status) = patch.status {
+            validate_status(status).map_err(|e| format!("{}: {}", patch.id, e))?;
+        }
+        if let Some(priority) = patch.priority {
+            validate_priority(priority).map_err(|e| format!("{}: {}", patch.id, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Applies a bulk patch to a record.
+fn apply_bulk_patch(record: &mut IssueRecord, patch: &BulkBeadPatch, now: &str) {
+    if let Some(priority) = patch.priority {
+        record.insert("priority".to_string(), serde_json::json!(priority));
+    }
+    if let Some(ref owner) = patch.owner {
+        set_optional_text(record, "owner", owner);
+    }
+    if let Some(ref milestone) = patch.milestone {
+        set_optional_text(record, "milestone", milestone);
+    }
+    match patch.status {
+        Some(ref status) => apply_status(record, status, now),
+        None => {
+            record.insert("updated_at".to_string(), serde_json::json!(now));
+        }
+    }
+}
+
+/// POST /api/beads/bulk
+///
+/// Applies a list of `{id, status?, priority?, owner?, milestone?}` patches in a single
+/// read-modify-write pass, recomputing epic statuses once at the end.
+/// Either every patch is applied or none are.
+/// Honors `If-Match` (see [`check_if_match`]).
+pub async fn bulk_update_beads(
+    headers: HeaderMap,
+    Json(payload): Json<BulkUpdateRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    validate_bulk_patches(&payload.updates).map_err(AppError::BadRequest)?;
+
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    let milestones = payload.updates.iter().map(|p| p.milestone.as_deref());
+    validate_milestone_assignments(&issues_path, milestones)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    // Resolve every ID up front so a missing bead aborts before any change
+    let mut indices = Vec::with_capacity(payload.updates.len());
+    for patch in &payload.updates {
+        match find_record_index(&lines, &patch.id) {
+            Some(i) => indices.push(i),
+            None => {
+                return Err(AppError::NotFound(format!(
+                    "Bead with id '{}' not found",
+                    patch.id
+                )));
+            }
+        }
+    }
+
+    let now = Utc::now().to_rfc3339();
+    for (patch, &index) in payload.updates.iter().zip(&indices) {
+        apply_bulk_patch(record_at_mut(&mut lines, index), patch, &now);
+    }
+
+    let updated_epics = recompute_epic_lines(&mut lines);
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "bulk_update", &before, &lines);
+
+    let beads: Result<Vec<Bead>, String> = indices
+        .iter()
+        .map(|&index| match &lines[index] {
+            IssueLine::Record(record) => record_to_bead(record),
+            IssueLine::Malformed(_) => unreachable!("find_record_index only matches records"),
+        })
+        .collect();
+
+    match beads {
+        Ok(beads) => Ok(Json(serde_json::json!({
+            "success": true,
+            "beads": beads,
+            "updated_epics": updated_epics
+        }))),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// Request body for the undo endpoint.
+#[derive(Debug, Deserialize)]
+pub struct UndoRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+}
+
+/// POST /api/beads/undo
+///
+/// Reverses the most recent mutation this server applied to the project's
+/// board (status move, comment, create, update, delete, or bulk update),
+/// provided it falls within the undo window. Returns the restored beads.
+/// Honors `If-Match` (see [`check_if_match`]).
+pub async fn undo_last_mutation(
+    headers: HeaderMap,
+    Json(payload): Json<UndoRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let entry = match journal::pop_latest(&issues_path) {
+        Some(entry) => entry,
+        None => {
+            return Err(AppError::NotFound("Nothing to undo".to_string()));
+        }
     };
 
-    // Parse JSONL and find the target bead
-    let mut beads: Vec<Bead> = Vec::new();
-    let mut found_bead_index: Option<usize> = None;
-    let mut max_comment_id: i64 = 0;
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
 
-    for (line_num, line) in contents.lines().enumerate() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+    // The entry is dropped on conflict; it can no longer be applied cleanly
+    let outcome = journal::apply_undo(&entry, &mut lines).map_err(AppError::Conflict)?;
 
-        match serde_json::from_str::<Bead>(line) {
-            Ok(bead) => {
-                // Track the maximum comment ID across all beads
-                if let Some(comments) = &bead.comments {
-                    for comment in comments {
-                        if comment.id > max_comment_id {
-                            max_comment_id = comment.id;
-                        }
-                    }
-                }
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
 
-                if bead.id == payload.bead_id {
-                    found_bead_index = Some(beads.len());
-                }
-                beads.push(bead);
-            }
-            Err(e) => {
-                tracing::warn!(
-                    "Failed to parse bead at line {}: {} - {}",
-                    line_num + 1,
-                    e,
-                    line
-                );
-                // Continue parsing other lines - graceful handling of malformed lines
+    let beads: Result<Vec<Bead>, String> = outcome
+        .restored
+        .iter()
+        .filter_map(|id| find_record_index(&lines, id))
+        .map(|index| match &lines[index] {
+            IssueLine::Record(record) => record_to_bead(record),
+            IssueLine::Malformed(_) => unreachable!("find_record_index only matches records"),
+        })
+        .collect();
+
+    match beads {
+        Ok(beads) => Ok(Json(serde_json::json!({
+            "success": true,
+            "undone": outcome.kind,
+            "beads": beads,
+            "removed": outcome.removed
+        }))),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// Unassigns every bead assigned to a milestone (used when it is deleted).
+///
+/// Returns the IDs of beads that were unassigned.
+pub(crate) async fn clear_milestone_assignments(
+    issues_path: &Path,
+    milestone_id: &str,
+) -> Result<Vec<String>, String> {
+    let _lock = lock_exclusive(issues_path).await.map_err(|e| e.to_string())?;
+    let mut lines = read_issue_lines(issues_path)?;
+    let before = lines.clone();
+
+    let now = Utc::now().to_rfc3339();
+    let mut unassigned = Vec::new();
+    for line in lines.iter_mut() {
+        let IssueLine::Record(record) = line else {
+            continue;
+        };
+        if record.get("milestone").and_then(|m| m.as_str()) == Some(milestone_id) {
+            record.remove("milestone");
+            record.insert("updated_at".to_string(), serde_json::json!(now));
+            if let Some(id) = record_id(record) {
+                unassigned.push(id.to_string());
             }
         }
     }
 
-    // Check if the bead was found
-    let bead_index = match found_bead_index {
-        Some(idx) => idx,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(AddCommentResponse {
-                    success: false,
-                    bead: None,
-                    error: Some(format!("Bead with id '{}' not found", payload.bead_id)),
-                }),
-            );
-        }
-    };
+    if !unassigned.is_empty() {
+        write_issue_lines(issues_path, &lines)?;
+        journal::record(issues_path, "milestone", &before, &lines);
+    }
 
-    // Create the new comment
-    let new_comment = Comment {
-        id: max_comment_id + 1,
-        issue_id: payload.bead_id.clone(),
-        author: payload.author,
-        text: payload.text,
-        created_at: Utc::now().to_rfc3339(),
-    };
+    Ok(unassigned)
+}
 
-    // Add the comment to the bead
-    let bead = &mut beads[bead_index];
-    match &mut bead.comments {
-        Some(comments) => comments.push(new_comment),
-        None => bead.comments = Some(vec![new_comment]),
+/// Appends new records to a project's issues file, assigning each a fresh ID.
+///
+/// Used by importers. Records must not carry an `id`; missing `created_at`/
+/// `updated_at` are stamped with the current time. Returns the assigned IDs
+/// in the same order as `records`.
+pub(crate) async fn append_new_records(
+    project_path: &Path,
+    records: Vec<IssueRecord>,
+) -> Result<Vec<String>, String> {
+    let issues_path = resolve_issues_path(project_path);
+    if let Some(parent) = issues_path.parent() {
+        if !parent.exists() {
+            return Err("No .beads directory found at the specified path".to_string());
+        }
     }
 
-    // Write the updated beads back to the file
-    let file = match std::fs::File::create(&issues_path) {
-        Ok(f) => f,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(AddCommentResponse {
-                    success: false,
-                    bead: None,
-                    error: Some(format!("Failed to open file for writing: {}", e)),
-                }),
-            );
-        }
+    let _lock = lock_exclusive(&issues_path).await.map_err(|e| e.to_string())?;
+    let mut lines = if issues_path.exists() {
+        read_issue_lines(&issues_path)?
+    } else {
+        Vec::new()
     };
+    let before = lines.clone();
 
-    let mut writer = std::io::BufWriter::new(file);
-    for bead in &beads {
-        match serde_json::to_string(bead) {
-            Ok(json_line) => {
-                if let Err(e) = writeln!(writer, "{}", json_line) {
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(AddCommentResponse {
-                            success: false,
-                            bead: None,
-                            error: Some(format!("Failed to write to file: {}", e)),
-                        }),
-                    );
-                }
-            }
-            Err(e) => {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(AddCommentResponse {
-                        success: false,
-                        bead: None,
-                        error: Some(format!("Failed to serialize bead: {}", e)),
-                    }),
-                );
+    let mut existing_ids: Vec<String> = records_ids(&lines);
+    let prefix = {
+        let ids: Vec<&str> = existing_ids.iter().map(String::as_str).collect();
+        issue_prefix(project_path, &ids)
+    };
+
+    let now = Utc::now().to_rfc3339();
+    let mut assigned = Vec::with_capacity(records.len());
+    for mut record in records {
+        let id = {
+            let ids: Vec<&str> = existing_ids.iter().map(String::as_str).collect();
+            generate_bead_id(&prefix, &ids)
+        };
+        record.insert("id".to_string(), serde_json::json!(id));
+        for key in ["created_at", "updated_at"] {
+            if !record.contains_key(key) {
+                record.insert(key.to_string(), serde_json::json!(now));
             }
         }
+        lines.push(IssueLine::Record(record));
+        existing_ids.push(id.clone());
+        assigned.push(id);
     }
 
-    if let Err(e) = writer.flush() {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(AddCommentResponse {
-                success: false,
-                bead: None,
-                error: Some(format!("Failed to flush file: {}", e)),
-            }),
-        );
+    if !assigned.is_empty() {
+        write_issue_lines(&issues_path, &lines)?;
+        journal::record(&issues_path, "import", &before, &lines);
     }
 
-    // Return the updated bead
-    let updated_bead = beads.swap_remove(bead_index);
-    (
-        StatusCode::OK,
-        Json(AddCommentResponse {
-            success: true,
-            bead: Some(updated_bead),
-            error: None,
-        }),
-    )
+    Ok(assigned)
+}
+
+/// Collects the IDs of all records.
+fn records_ids(lines: &[IssueLine]) -> Vec<String> {
+    records(lines)
+        .filter_map(record_id)
+        .map(String::from)
+        .collect()
 }
 
 /// Computes the appropriate status for an epic based on its children's statuses.
@@ -511,9 +2397,9 @@ fn compute_epic_status_from_children(child_statuses: &[&str]) -> Option<&'static
 
 /// Recomputes and updates epic statuses based on their children's statuses.
 ///
-/// This function reads the issues.jsonl file, finds all epics with children,
-/// computes the appropriate status for each epic based on its children,
-/// and writes back the file if any epic status changed.
+/// This function locks and reads the issues.jsonl file, recomputes epic
+/// statuses via [`recompute_epic_lines`], and writes back the file if any
+/// epic status changed.
 ///
 /// # Arguments
 ///
@@ -523,31 +2409,68 @@ fn compute_epic_status_from_children(child_statuses: &[&str]) -> Option<&'static
 ///
 /// * `Ok(Vec<String>)` - List of epic IDs that were updated
 /// * `Err(String)` - Error message if something went wrong
-pub fn recompute_epic_statuses(issues_path: &Path) -> Result<Vec<String>, String> {
-    // Read the file contents
-    let contents = std::fs::read_to_string(issues_path)
-        .map_err(|e| format!("Failed to read file: {}", e))?;
+pub async fn recompute_epic_statuses(issues_path: &Path) -> Result<Vec<String>, String> {
+    let _lock = lock_exclusive(issues_path).await.map_err(|e| e.to_string())?;
+    let mut lines = read_issue_lines(issues_path)?;
 
-    // Parse JSONL into beads
-    let mut beads: Vec<Bead> = Vec::new();
-    for (line_num, line) in contents.lines().enumerate() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
-        }
+    let updated_epic_ids = recompute_epic_lines(&mut lines);
+
+    // Write back if any epic was updated
+    if !updated_epic_ids.is_empty() {
+        write_issue_lines(issues_path, &lines)?;
+    }
+
+    Ok(updated_epic_ids)
+}
+
+/// Request body for the recompute epics endpoint.
+#[derive(Debug, Deserialize)]
+pub struct RecomputeEpicsRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+}
+
+/// POST /api/beads/recompute-epics
+///
+/// Recomputes every epic's status from its children, e.g. after the issues
+/// file was edited outside the UI. Returns the IDs of epics that changed.
+pub async fn recompute_epics(
+    Json(payload): Json<RecomputeEpicsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let updated_epics = recompute_epic_lines(&mut lines);
+    if !updated_epics.is_empty() {
+        write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+        journal::record(&issues_path, "recompute_epics", &before, &lines);
+    }
+
+    Ok(Json(
+        serde_json::json!({ "success": true, "updated_epics": updated_epics }),
+    ))
+}
 
-        match serde_json::from_str::<Bead>(line) {
-            Ok(bead) => beads.push(bead),
+/// Updates epic statuses in already-loaded issue lines.
+///
+/// Finds all epics with children and sets each epic's status from its
+/// children's statuses. Returns the IDs of epics that were updated.
+fn recompute_epic_lines(lines: &mut [IssueLine]) -> Vec<String> {
+    // Parse records into beads for the status computation
+    let beads: Vec<Bead> = records(lines)
+        .filter_map(|record| match record_to_bead(record) {
+            Ok(bead) => Some(bead),
             Err(e) => {
-                tracing::warn!(
-                    "Failed to parse bead at line {}: {} - {}",
-                    line_num + 1,
-                    e,
-                    line
-                );
+                tracing::warn!("Skipping bead during epic recompute: {}", e);
+                None
             }
-        }
-    }
+        })
+        .collect();
 
     // Build parent-child relationships
     // parent_id -> Vec<child_id>
@@ -634,40 +2557,24 @@ pub fn recompute_epic_statuses(issues_path: &Path) -> Result<Vec<String>, String
     let mut updated_epic_ids: Vec<String> = Vec::new();
 
     for (epic_id, new_status) in &epic_updates {
-        for bead in &mut beads {
-            if &bead.id == epic_id {
-                tracing::info!(
-                    "Updating epic {} status from {} to {}",
-                    bead.id,
-                    bead.status,
-                    new_status
-                );
-                bead.status = new_status.clone();
-                bead.updated_at = Some(Utc::now().to_rfc3339());
-                updated_epic_ids.push(bead.id.clone());
-                break;
-            }
-        }
-    }
-
-    // Write back if any epic was updated
-    if !updated_epic_ids.is_empty() {
-        let file = std::fs::File::create(issues_path)
-            .map_err(|e| format!("Failed to open file for writing: {}", e))?;
-
-        let mut writer = std::io::BufWriter::new(file);
-        for bead in &beads {
-            let json_line = serde_json::to_string(bead)
-                .map_err(|e| format!("Failed to serialize bead: {}", e))?;
-            writeln!(writer, "{}", json_line)
-                .map_err(|e| format!("Failed to write to file: {}", e))?;
+        if let Some(index) = find_record_index(lines, epic_id) {
+            let record = record_at_mut(lines, index);
+            tracing::info!(
+                "Updating epic {} status from {} to {}",
+                epic_id,
+                record.get("status").and_then(|s| s.as_str()).unwrap_or(""),
+                new_status
+            );
+            record.insert("status".to_string(), serde_json::json!(new_status));
+            record.insert(
+                "updated_at".to_string(),
+                serde_json::json!(Utc::now().to_rfc3339()),
+            );
+            updated_epic_ids.push(epic_id.clone());
         }
-        writer
-            .flush()
-            .map_err(|e| format!("Failed to flush file: {}", e))?;
     }
 
-    Ok(updated_epic_ids)
+    updated_epic_ids
 }
 
 #[cfg(test)]
@@ -708,6 +2615,15 @@ mod tests {
         assert_eq!(bead.design_doc, Some("path/to/design2.md".to_string()));
     }
 
+    #[test]
+    fn test_next_child_id() {
+        assert_eq!(next_child_id("bd-1", &["bd-1", "bd-2"]), "bd-1.1");
+        assert_eq!(
+            next_child_id("bd-1", &["bd-1", "bd-1.1", "bd-1.3", "bd-1.3.1", "bd-10.7"]),
+            "bd-1.4"
+        );
+    }
+
     #[test]
     fn test_compute_epic_status_any_in_progress() {
         // Any child in_progress -> Epic in_progress
@@ -839,6 +2755,14 @@ mod tests {
             design_doc: None,
             deps: None,
             relates_to: None,
+            labels: vec![],
+            time_tracked_seconds: None,
+            timer_started_at: None,
+            milestone: None,
+            recurrence: None,
+            recurrence_of: None,
+            due_date: None,
+            custom: CustomValues::new(),
             dependencies: Some(vec![
                 Dependency {
                     depends_on_id: "bead-b".to_string(),
@@ -896,6 +2820,14 @@ mod tests {
             design_doc: None,
             deps: None,
             relates_to: None,
+            labels: vec![],
+            time_tracked_seconds: None,
+            timer_started_at: None,
+            milestone: None,
+            recurrence: None,
+            recurrence_of: None,
+            due_date: None,
+            custom: CustomValues::new(),
             dependencies: Some(vec![Dependency {
                 depends_on_id: "bead-parent".to_string(),
                 dep_type: "parent-child".to_string(),
@@ -941,6 +2873,14 @@ mod tests {
             design_doc: None,
             deps: None,
             relates_to: Some(vec!["bead-r1".to_string(), "bead-r2".to_string()]),
+            labels: vec![],
+            time_tracked_seconds: None,
+            timer_started_at: None,
+            milestone: None,
+            recurrence: None,
+            recurrence_of: None,
+            due_date: None,
+            custom: CustomValues::new(),
             dependencies: Some(vec![Dependency {
                 depends_on_id: "bead-r1".to_string(),
                 dep_type: "relates-to".to_string(),
@@ -959,6 +2899,82 @@ mod tests {
         assert!(!json.contains("depends_on_id"));
     }
 
+    #[test]
+    fn test_parse_beads_skips_malformed_lines() {
+        let contents = "{\"id\":\"a\",\"title\":\"A\",\"status\":\"open\"}\n\nnot json\n{\"id\":\"b\",\"title\":\"B\",\"status\":\"closed\"}\n";
+        let beads = parse_beads(contents);
+        assert_eq!(beads.len(), 2);
+        assert_eq!(beads[0].id, "a");
+        assert_eq!(beads[1].id, "b");
+    }
+
+    #[test]
+    fn test_link_bead_relationships_infers_children() {
+        let contents = "{\"id\":\"64n\",\"title\":\"Epic\",\"status\":\"open\",\"issue_type\":\"epic\"}\n{\"id\":\"64n.1\",\"title\":\"Child\",\"status\":\"open\"}\n";
+        let mut beads = parse_beads(contents);
+        link_bead_relationships(&mut beads);
+        assert_eq!(beads[0].children, Some(vec!["64n.1".to_string()]));
+        assert_eq!(beads[1].parent_id, Some("64n".to_string()));
+    }
+
+    #[test]
+    fn test_split_csv_param() {
+        assert_eq!(split_csv_param(Some("a, b,,c ")), vec!["a", "b", "c"]);
+        assert!(split_csv_param(Some("")).is_empty());
+        assert!(split_csv_param(None).is_empty());
+    }
+
+    #[test]
+    fn test_select_projects_by_tag_and_id() {
+        use crate::db::Tag;
+
+        let project = |id: &str, tag_ids: &[&str]| ProjectWithTags {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: format!("/{}", id),
+            tags: tag_ids
+                .iter()
+                .map(|t| Tag {
+                    id: t.to_string(),
+                    name: t.to_string(),
+                    color: "#000".to_string(),
+                })
+                .collect(),
+            last_opened: String::new(),
+            created_at: String::new(),
+        };
+        let projects = vec![project("p1", &["work"]), project("p2", &["home"]), project("p3", &[])];
+
+        let all = select_projects(&projects, &[], &[]);
+        assert_eq!(all.len(), 3);
+
+        let tagged = select_projects(&projects, &["work"], &[]);
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].id, "p1");
+
+        let by_id = select_projects(&projects, &[], &["p2", "p3"]);
+        assert_eq!(by_id.len(), 2);
+
+        let both = select_projects(&projects, &["work"], &["p2"]);
+        assert!(both.is_empty());
+    }
+
+    #[test]
+    fn test_project_bead_serialization_flattens_bead() {
+        let bead: Bead =
+            serde_json::from_str(r#"{"id":"x-1","title":"X","status":"open"}"#).unwrap();
+        let project_bead = ProjectBead {
+            project_id: "p1".to_string(),
+            project_name: "Project".to_string(),
+            project_path: "/p1".to_string(),
+            bead,
+        };
+        let json = serde_json::to_string(&project_bead).unwrap();
+        assert!(json.contains("\"project_id\":\"p1\""));
+        assert!(json.contains("\"id\":\"x-1\""));
+        assert!(!json.contains("\"bead\""));
+    }
+
     // ── resolve_issues_path tests ──────────────────────────────────────
 
     #[test]
@@ -1181,4 +3197,409 @@ mod tests {
         let result = resolve_issues_path(project);
         assert_eq!(result, project.join(".beads").join("issues.jsonl"));
     }
+
+    // ── issue file I/O and CRUD helper tests ───────────────────────────
+
+    #[test]
+    fn test_issue_lines_roundtrip_preserves_unknown_fields() {
+        let tmp = tempfile::tempdir().unwrap();
+        let issues_path = tmp.path().join("issues.jsonl");
+        let contents = concat!(
+            r#"{"id":"bd-1","title":"One","status":"open","labels":["x"],"dependencies":[{"depends_on_id":"bd-2","type":"blocks"}]}"#,
+            "\n",
+            "not json\n",
+        );
+        std::fs::write(&issues_path, contents).unwrap();
+
+        let lines = read_issue_lines(&issues_path).unwrap();
+        assert_eq!(lines.len(), 2);
+        write_issue_lines(&issues_path, &lines).unwrap();
+
+        let written = std::fs::read_to_string(&issues_path).unwrap();
+        assert!(written.contains(r#""labels":["x"]"#));
+        assert!(written.contains(r#""depends_on_id":"bd-2""#));
+        assert!(written.contains("not json"));
+        assert!(!issues_path.with_extension("jsonl.tmp").exists());
+    }
+
+    #[test]
+    fn test_issue_prefix_inferred_from_existing_ids() {
+        let tmp = tempfile::tempdir().unwrap();
+        let prefix = issue_prefix(tmp.path(), &["my-app-abc.1", "my-app-abc"]);
+        assert_eq!(prefix, "my-app");
+    }
+
+    #[test]
+    fn test_issue_prefix_from_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let beads_dir = tmp.path().join(".beads");
+        std::fs::create_dir_all(&beads_dir).unwrap();
+        std::fs::write(beads_dir.join("config.yaml"), "issue-prefix: kb\n").unwrap();
+
+        assert_eq!(issue_prefix(tmp.path(), &["other-123"]), "kb");
+    }
+
+    #[test]
+    fn test_generate_bead_id_avoids_existing() {
+        let id = generate_bead_id("bd", &["bd-abc"]);
+        assert!(id.starts_with("bd-"));
+        assert_ne!(id, "bd-abc");
+        assert_eq!(id.len(), "bd-".len() + 3);
+    }
+
+    #[test]
+    fn test_new_bead_id() {
+        let project = Path::new("/nonexistent/project");
+        let existing = ["BD-1"];
+
+        assert_eq!(new_bead_id(project, Some(" BD-2 "), &existing).unwrap(), "BD-2");
+        assert!(matches!(
+            new_bead_id(project, Some(" BD-1 "), &existing),
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            new_bead_id(project, Some("  "), &existing),
+            Err(AppError::BadRequest(_))
+        ));
+
+        let generated = new_bead_id(project, None, &existing).unwrap();
+        assert!(generated.starts_with("BD-"));
+        assert_ne!(generated, "BD-1");
+    }
+
+    #[test]
+    fn test_apply_status_sets_and_clears_closed_at() {
+        let mut record = IssueRecord::new();
+        record.insert("status".to_string(), serde_json::json!("open"));
+
+        apply_status(&mut record, "closed", "2026-01-01T00:00:00Z");
+        assert_eq!(record["closed_at"], "2026-01-01T00:00:00Z");
+
+        record.insert("close_reason".to_string(), serde_json::json!("done"));
+        apply_status(&mut record, "open", "2026-01-02T00:00:00Z");
+        assert!(!record.contains_key("closed_at"));
+        assert!(!record.contains_key("close_reason"));
+        assert_eq!(record["updated_at"], "2026-01-02T00:00:00Z");
+    }
+
+    #[test]
+    fn test_apply_bead_update_clears_empty_fields() {
+        let mut record: IssueRecord = serde_json::from_str(
+            r#"{"id":"bd-1","title":"Old","description":"desc","design_doc":".designs/a.md"}"#,
+        )
+        .unwrap();
+        let update = UpdateBeadRequest {
+            path: "/tmp".to_string(),
+            title: Some("New".to_string()),
+            description: Some(String::new()),
+            status: None,
+            priority: Some(1),
+            issue_type: None,
+            owner: None,
+            design_doc: Some(".designs/b.md".to_string()),
+            milestone: None,
+            recurrence: None,
+            custom: None,
+        };
+
+        apply_bead_update(&mut record, &update, "2026-01-01T00:00:00Z").unwrap();
+        assert_eq!(record["title"], "New");
+        assert!(!record.contains_key("description"));
+        assert_eq!(record["priority"], 1);
+        assert_eq!(record["design_doc"], ".designs/b.md");
+        assert!(!record.contains_key("design"));
+    }
+
+    #[test]
+    fn test_apply_bead_update_checks_status_transition() {
+        let mut record: IssueRecord =
+            serde_json::from_str(r#"{"id":"bd-1","title":"Old","status":"open"}"#).unwrap();
+        let update = |status: &str| UpdateBeadRequest {
+            path: "/tmp".to_string(),
+            title: Some("New".to_string()),
+            description: None,
+            status: Some(status.to_string()),
+            priority: None,
+            issue_type: None,
+            owner: None,
+            design_doc: None,
+            milestone: None,
+            recurrence: None,
+            custom: None,
+        };
+
+        // A PATCH can't skip columns any more than POST /status can
+        assert!(apply_bead_update(&mut record, &update("closed"), "2026-01-01T00:00:00Z").is_err());
+        assert_eq!(record["status"], "open");
+        assert_eq!(record["title"], "Old");
+
+        apply_bead_update(&mut record, &update("open"), "2026-01-01T00:00:00Z").unwrap();
+        assert_eq!(record["title"], "New");
+        apply_bead_update(&mut record, &update("in_progress"), "2026-01-01T00:00:00Z").unwrap();
+        assert_eq!(record["status"], "in_progress");
+    }
+
+    #[test]
+    fn test_merge_custom_values() {
+        let mut record: IssueRecord =
+            serde_json::from_str(r#"{"id":"bd-1","custom":{"severity":"low","customer":"acme"}}"#)
+                .unwrap();
+        let values: CustomValues =
+            serde_json::from_str(r#"{"severity":"high","customer":null}"#).unwrap();
+
+        merge_custom_values(&mut record, &values);
+        assert_eq!(record["custom"], serde_json::json!({ "severity": "high" }));
+
+        let clear: CustomValues = serde_json::from_str(r#"{"severity":null}"#).unwrap();
+        merge_custom_values(&mut record, &clear);
+        assert!(!record.contains_key("custom"));
+    }
+
+    fn params(query: serde_json::Value) -> BeadsParams {
+        serde_json::from_value(query).unwrap()
+    }
+
+    #[test]
+    fn test_bead_matches_filters() {
+        let beads = parse_beads(concat!(
+            r#"{"id":"bd-1","title":"Fix login","status":"open","priority":1,"issue_type":"bug","owner":"ann"}"#,
+            "\n",
+            r#"{"id":"bd-2","title":"Docs","description":"Login docs","status":"closed","priority":3,"issue_type":"task"}"#,
+        ));
+
+        let matching = |query: serde_json::Value| -> Vec<&str> {
+            let p = params(query);
+            let priorities: Vec<i32> = split_csv_param(p.priority.as_deref())
+                .into_iter()
+                .map(|v| v.parse().unwrap())
+                .collect();
+            beads
+                .iter()
+                .filter(|b| bead_matches(b, &p, &priorities))
+                .map(|b| b.id.as_str())
+                .collect()
+        };
+
+        assert_eq!(matching(serde_json::json!({ "path": "x" })), vec!["bd-1", "bd-2"]);
+        assert_eq!(matching(serde_json::json!({ "path": "x", "status": "open,inreview" })), vec!["bd-1"]);
+        assert_eq!(matching(serde_json::json!({ "path": "x", "issue_type": "task" })), vec!["bd-2"]);
+        assert_eq!(matching(serde_json::json!({ "path": "x", "owner": "ann" })), vec!["bd-1"]);
+        assert_eq!(matching(serde_json::json!({ "path": "x", "priority": "3" })), vec!["bd-2"]);
+        assert_eq!(matching(serde_json::json!({ "path": "x", "q": "LOGIN" })), vec!["bd-1", "bd-2"]);
+        assert_eq!(matching(serde_json::json!({ "path": "x", "q": "docs" })), vec!["bd-2"]);
+    }
+
+    #[test]
+    fn test_sort_beads() {
+        let mut beads = parse_beads(concat!(
+            r#"{"id":"bd-1","title":"A","status":"open","priority":2,"updated_at":"2026-01-02"}"#,
+            "\n",
+            r#"{"id":"bd-2","title":"B","status":"open","updated_at":"2026-01-03"}"#,
+            "\n",
+            r#"{"id":"bd-3","title":"C","status":"open","priority":10,"updated_at":"2026-01-01"}"#,
+        ));
+
+        sort_beads(&mut beads, "priority").unwrap();
+        let ids: Vec<&str> = beads.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["bd-1", "bd-3", "bd-2"]);
+
+        sort_beads(&mut beads, "-updated_at").unwrap();
+        let ids: Vec<&str> = beads.iter().map(|b| b.id.as_str()).collect();
+        assert_eq!(ids, vec!["bd-2", "bd-1", "bd-3"]);
+
+        assert!(sort_beads(&mut beads, "owner").is_err());
+    }
+
+    #[test]
+    fn test_ndjson_chunks() {
+        let contents = concat!(
+            r#"{"id":"bd-1","title":"Epic","status":"open"}"#,
+            "\n",
+            r#"{"id":"bd-1.1","title":"Child","status":"closed"}"#,
+            "\n",
+            "{not json\n",
+            r#"{"id":"bd-2","title":"Other","status":"open"}"#,
+        );
+
+        let mut reader = std::io::Cursor::new(contents.as_bytes());
+        let (etag, hierarchy) = scan_beads(&mut reader).unwrap();
+        assert_eq!(etag, issues_etag(contents));
+
+        let mut lines = |query: serde_json::Value| -> Vec<serde_json::Value> {
+            let mut out = String::new();
+            reader.rewind().unwrap();
+            ndjson_chunks(&mut reader, &hierarchy, &params(query), &[], |chunk| {
+                out.push_str(&chunk);
+                true
+            })
+            .unwrap();
+            out.lines().map(|l| serde_json::from_str(l).unwrap()).collect()
+        };
+
+        let all = lines(serde_json::json!({ "path": "x" }));
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0]["children"], serde_json::json!(["bd-1.1"]));
+        assert_eq!(all[1]["parent_id"], "bd-1");
+
+        let open = lines(serde_json::json!({ "path": "x", "status": "open", "offset": 1 }));
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0]["id"], "bd-2");
+
+        let first = lines(serde_json::json!({ "path": "x", "limit": 1 }));
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0]["id"], "bd-1");
+    }
+
+    #[test]
+    fn test_issues_etag_tracks_contents() {
+        let a = issues_etag("{\"id\":\"bd-1\"}\n");
+        assert_eq!(a, issues_etag("{\"id\":\"bd-1\"}\n"));
+        assert_ne!(a, issues_etag("{\"id\":\"bd-2\"}\n"));
+        assert!(a.starts_with('"') && a.ends_with('"'));
+    }
+
+    #[test]
+    fn test_if_match_satisfied() {
+        let etag = "\"abc\"";
+        assert!(if_match_satisfied("\"abc\"", etag));
+        assert!(if_match_satisfied("W/\"abc\"", etag));
+        assert!(if_match_satisfied("\"old\", \"abc\"", etag));
+        assert!(if_match_satisfied("*", etag));
+        assert!(!if_match_satisfied("\"old\"", etag));
+    }
+
+    #[test]
+    fn test_check_if_match_rejects_stale_tag() {
+        let tmp = tempfile::tempdir().unwrap();
+        let issues_path = tmp.path().join("issues.jsonl");
+        let contents = r#"{"id":"bd-1","title":"One","status":"open"}"#;
+        std::fs::write(&issues_path, contents).unwrap();
+
+        let mut headers = HeaderMap::new();
+        assert!(check_if_match(&headers, &issues_path).is_ok());
+
+        headers.insert(header::IF_MATCH, issues_etag(contents).parse().unwrap());
+        assert!(check_if_match(&headers, &issues_path).is_ok());
+
+        headers.insert(header::IF_MATCH, "\"stale\"".parse().unwrap());
+        let err = check_if_match(&headers, &issues_path).unwrap_err();
+        assert_eq!(err.status(), StatusCode::PRECONDITION_FAILED);
+        let body = err.body();
+        assert_eq!(body["details"]["beads"][0]["id"], "bd-1");
+        assert_eq!(body["details"]["etag"], issues_etag(contents));
+    }
+
+    #[test]
+    fn test_validate_bulk_patches() {
+        let patch = |id: &str, status: Option<&str>, priority: Option<i32>| BulkBeadPatch {
+            id: id.to_string(),
+            status: status.map(String::from),
+            priority,
+            owner: None,
+            milestone: None,
+        };
+
+        assert!(validate_bulk_patches(&[]).is_err());
+        assert!(validate_bulk_patches(&[patch("bd-1", Some("closed"), Some(0))]).is_ok());
+        let err = validate_bulk_patches(&[
+            patch("bd-1", Some("open"), None),
+            patch("bd-2", Some("done"), None),
+        ])
+        .unwrap_err();
+        assert!(err.starts_with("bd-2"));
+        assert!(validate_bulk_patches(&[patch("bd-1", None, Some(7))]).is_err());
+    }
+
+    #[test]
+    fn test_apply_bulk_patch() {
+        let mut record: IssueRecord =
+            serde_json::from_str(r#"{"id":"bd-1","status":"open","owner":"ann"}"#).unwrap();
+        let patch = BulkBeadPatch {
+            id: "bd-1".to_string(),
+            status: Some("in_progress".to_string()),
+            priority: Some(1),
+            owner: Some(String::new()),
+            milestone: Some("m1".to_string()),
+        };
+
+        apply_bulk_patch(&mut record, &patch, "2026-01-01T00:00:00Z");
+        assert_eq!(record["status"], "in_progress");
+        assert_eq!(record["priority"], 1);
+        assert!(!record.contains_key("owner"));
+        assert_eq!(record["milestone"], "m1");
+        assert_eq!(record["updated_at"], "2026-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_add_and_remove_record_labels() {
+        let mut record: IssueRecord =
+            serde_json::from_str(r#"{"id":"bd-1","labels":["ui"]}"#).unwrap();
+
+        add_record_labels(&mut record, &["ui".to_string(), "backend".to_string()]);
+        assert_eq!(record["labels"], serde_json::json!(["ui", "backend"]));
+
+        remove_record_labels(&mut record, &["ui".to_string(), "missing".to_string()]);
+        assert_eq!(record["labels"], serde_json::json!(["backend"]));
+
+        remove_record_labels(&mut record, &["backend".to_string()]);
+        assert!(!record.contains_key("labels"));
+    }
+
+    #[test]
+    fn test_normalize_labels() {
+        let labels = normalize_labels(&[" ui ".to_string(), "".to_string(), "  ".to_string()]);
+        assert_eq!(labels, vec!["ui".to_string()]);
+    }
+
+    #[test]
+    fn test_bead_matches_labels() {
+        let beads = parse_beads(concat!(
+            r#"{"id":"bd-1","title":"A","status":"open","labels":["ui","urgent"]}"#,
+            "\n",
+            r#"{"id":"bd-2","title":"B","status":"open"}"#,
+        ));
+        assert!(beads[1].labels.is_empty());
+
+        let p = params(serde_json::json!({ "path": "x", "labels": "urgent,other" }));
+        let ids: Vec<&str> = beads
+            .iter()
+            .filter(|b| bead_matches(b, &p, &[]))
+            .map(|b| b.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["bd-1"]);
+    }
+
+    #[test]
+    fn test_validate_transition() {
+        assert!(validate_transition("open", "in_progress").is_ok());
+        assert!(validate_transition("in_progress", "inreview").is_ok());
+        assert!(validate_transition("inreview", "closed").is_ok());
+        assert!(validate_transition("closed", "open").is_ok());
+        assert!(validate_transition("inreview", "in_progress").is_ok());
+        assert!(validate_transition("open", "closed").is_err());
+        assert!(validate_transition("open", "open").is_err());
+        assert!(validate_transition("open", "done").is_err());
+    }
+
+    #[test]
+    fn test_remove_dependency_references() {
+        let mut lines = vec![
+            IssueLine::Record(
+                serde_json::from_str(
+                    r#"{"id":"bd-2","dependencies":[{"depends_on_id":"bd-1","type":"blocks"},{"depends_on_id":"bd-3","type":"blocks"}]}"#,
+                )
+                .unwrap(),
+            ),
+            IssueLine::Record(serde_json::from_str(r#"{"id":"bd-3"}"#).unwrap()),
+        ];
+
+        let updated = remove_dependency_references(&mut lines, "bd-1");
+        assert_eq!(updated, vec!["bd-2".to_string()]);
+        let deps = records(&lines).next().unwrap()["dependencies"]
+            .as_array()
+            .unwrap()
+            .clone();
+        assert_eq!(deps.len(), 1);
+        assert_eq!(deps[0]["depends_on_id"], "bd-3");
+    }
 }
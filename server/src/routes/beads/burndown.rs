@@ -0,0 +1,209 @@
+//! Burndown / cumulative flow data.
+//!
+//! The issues file does not keep a status history, so each bead's status on
+//! a past day is reconstructed from its timestamps: a bead exists from
+//! `created_at`, is closed from `closed_at`, and is otherwise `open` until
+//! `updated_at`, after which it takes its current status.
+
+use axum::{extract::Query, response::IntoResponse, Json};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::{parse_beads, resolve_issues_path, split_csv_param, Bead, BEAD_STATUSES};
+use crate::error::AppError;
+use crate::routes::validate_path_security;
+
+/// Default number of days covered by the burndown.
+const DEFAULT_BURNDOWN_DAYS: u32 = 30;
+
+/// Maximum number of days covered by the burndown.
+const MAX_BURNDOWN_DAYS: u32 = 365;
+
+/// Query parameters for the burndown endpoint.
+#[derive(Debug, Deserialize)]
+pub struct BurndownParams {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// Number of days to cover, ending today (default 30, max 365)
+    #[serde(default)]
+    pub days: Option<u32>,
+    /// Only count beads in these milestones (comma-separated)
+    #[serde(default)]
+    pub milestone: Option<String>,
+}
+
+/// Bead counts per status at the end of one day.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct BurndownDay {
+    /// Day in YYYY-MM-DD form (UTC)
+    pub date: String,
+    pub open: usize,
+    pub in_progress: usize,
+    pub inreview: usize,
+    pub closed: usize,
+    /// All beads that existed on this day
+    pub total: usize,
+    /// Beads not yet closed (`total - closed`)
+    pub remaining: usize,
+}
+
+/// Parses an RFC 3339 timestamp (`None` if absent or unparseable).
+pub(super) fn parse_timestamp(value: Option<&str>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Reconstructs a bead's status as of `at` (`None` if it did not exist yet).
+fn status_at(bead: &Bead, at: DateTime<Utc>) -> Option<&str> {
+    let created_at = parse_timestamp(bead.created_at.as_deref())?;
+    if created_at > at {
+        return None;
+    }
+
+    if let Some(closed_at) = parse_timestamp(bead.closed_at.as_deref()) {
+        return Some(if closed_at <= at { "closed" } else { "open" });
+    }
+    if bead.status == "closed" {
+        // Closed without a closed_at: treat it as closed since it was created
+        return Some("closed");
+    }
+
+    match parse_timestamp(bead.updated_at.as_deref()) {
+        Some(updated_at) if updated_at > at => Some("open"),
+        _ => Some(bead.status.as_str()),
+    }
+}
+
+/// Builds day-by-day status counts for the `days` days ending on `today`.
+///
+/// Beads without a parseable `created_at` are left out.
+fn build_burndown(beads: &[Bead], days: u32, today: NaiveDate) -> Vec<BurndownDay> {
+    (0..days)
+        .rev()
+        .map(|offset| {
+            let date = today - Duration::days(offset as i64);
+            let end_of_day = date
+                .succ_opt()
+                .unwrap_or(date)
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+                .and_utc()
+                - Duration::nanoseconds(1);
+
+            let mut day = BurndownDay {
+                date: date.format("%Y-%m-%d").to_string(),
+                ..Default::default()
+            };
+            for status in beads.iter().filter_map(|bead| status_at(bead, end_of_day)) {
+                match status {
+                    "closed" => day.closed += 1,
+                    "in_progress" => day.in_progress += 1,
+                    "inreview" => day.inreview += 1,
+                    _ => day.open += 1,
+                }
+                day.total += 1;
+            }
+            day.remaining = day.total - day.closed;
+            day
+        })
+        .collect()
+}
+
+/// GET /api/beads/burndown?path=/path/to/project&days=30&milestone=m1
+///
+/// Returns bead counts per status for each day, oldest first, ending today
+/// (UTC). Statuses are reconstructed from bead timestamps.
+pub async fn burndown(Query(params): Query<BurndownParams>) -> Result<impl IntoResponse, AppError> {
+    let days = params.days.unwrap_or(DEFAULT_BURNDOWN_DAYS);
+    if days == 0 || days > MAX_BURNDOWN_DAYS {
+        return Err(AppError::BadRequest(format!(
+            "Invalid days {}. Must be between 1 and {}",
+            days, MAX_BURNDOWN_DAYS
+        )));
+    }
+
+    let project_path = PathBuf::from(&params.path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    let issues_path = resolve_issues_path(&project_path);
+    let contents = match std::fs::read_to_string(&issues_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::NotFound(
+                "No .beads/issues.jsonl found at the specified path".to_string(),
+            ));
+        }
+        Err(e) => {
+            return Err(AppError::Internal(format!("Failed to read file: {}", e)));
+        }
+    };
+
+    let mut beads = parse_beads(&contents);
+    let milestones = split_csv_param(params.milestone.as_deref());
+    if !milestones.is_empty() {
+        beads.retain(|bead| {
+            bead.milestone
+                .as_deref()
+                .is_some_and(|m| milestones.contains(&m))
+        });
+    }
+
+    let series = build_burndown(&beads, days, Utc::now().date_naive());
+    Ok(Json(serde_json::json!({
+        "statuses": BEAD_STATUSES,
+        "days": series
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_burndown_reconstructs_statuses() {
+        let beads = parse_beads(concat!(
+            r#"{"id":"bd-1","title":"Done","status":"closed","created_at":"2026-01-01T09:00:00Z","closed_at":"2026-01-02T15:00:00Z"}"#,
+            "\n",
+            r#"{"id":"bd-2","title":"Working","status":"in_progress","created_at":"2026-01-02T09:00:00Z","updated_at":"2026-01-03T09:00:00Z"}"#,
+            "\n",
+            r#"{"id":"bd-3","title":"Later","status":"open","created_at":"2026-01-05T09:00:00Z"}"#,
+            "\n",
+            r#"{"id":"bd-4","title":"Undated","status":"open"}"#,
+        ));
+        let today = NaiveDate::from_ymd_opt(2026, 1, 3).unwrap();
+
+        let series = build_burndown(&beads, 3, today);
+        assert_eq!(
+            series,
+            vec![
+                BurndownDay {
+                    date: "2026-01-01".to_string(),
+                    open: 1,
+                    total: 1,
+                    remaining: 1,
+                    ..Default::default()
+                },
+                BurndownDay {
+                    date: "2026-01-02".to_string(),
+                    open: 1,
+                    closed: 1,
+                    total: 2,
+                    remaining: 1,
+                    ..Default::default()
+                },
+                BurndownDay {
+                    date: "2026-01-03".to_string(),
+                    in_progress: 1,
+                    closed: 1,
+                    total: 2,
+                    remaining: 1,
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Incremental board refresh: the beads changed since a cursor.
+//!
+//! There is no change log, so a bead counts as changed when any of its
+//! timestamps (`created_at`, `updated_at`, `closed_at`, or a comment's
+//! `created_at`) is newer than the cursor. Deleted beads leave no trace in
+//! the file; clients drop the beads missing from `ids` instead.
+
+use axum::{
+    extract::Query,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use super::burndown::parse_timestamp;
+use super::{issues_etag, link_bead_relationships, parse_beads, resolve_issues_path, Bead};
+use crate::error::AppError;
+use crate::routes::validate_path_security;
+
+/// Query parameters for the changes endpoint.
+#[derive(Debug, Deserialize)]
+pub struct ChangesParams {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// RFC 3339 cursor; usually the `cursor` of the previous response
+    pub since: String,
+}
+
+/// The newest timestamp recorded on a bead or its comments.
+fn last_changed(bead: &Bead) -> Option<DateTime<Utc>> {
+    let comments = bead
+        .comments
+        .iter()
+        .flatten()
+        .map(|c| Some(c.created_at.as_str()));
+    [
+        bead.created_at.as_deref(),
+        bead.updated_at.as_deref(),
+        bead.closed_at.as_deref(),
+    ]
+    .into_iter()
+    .chain(comments)
+    .filter_map(parse_timestamp)
+    .max()
+}
+
+/// GET /api/beads/changes?path=/path/to/project&since=2026-01-01T00:00:00Z
+///
+/// Returns the beads changed after `since`, the IDs of every bead currently
+/// on the board, and a `cursor` to pass as `since` next time (the newest
+/// timestamp seen, so it does not depend on the server clock). Like
+/// `GET /api/beads`, the response carries an `ETag` header.
+pub async fn bead_changes(Query(params): Query<ChangesParams>) -> Response {
+    let since = match parse_timestamp(Some(&params.since)) {
+        Some(s) => s,
+        None => {
+            return AppError::BadRequest(
+                "Invalid since: expected an RFC 3339 timestamp".to_string(),
+            )
+            .into_response();
+        }
+    };
+
+    let project_path = PathBuf::from(&params.path);
+
+    // Security: Validate path is within allowed directories
+    if let Err(e) = validate_path_security(&project_path) {
+        return AppError::Forbidden(e).into_response();
+    }
+
+    let issues_path = resolve_issues_path(&project_path);
+    let contents = match std::fs::read_to_string(&issues_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return AppError::NotFound(
+                "No .beads/issues.jsonl found at the specified path".to_string(),
+            )
+            .into_response();
+        }
+        Err(e) => {
+            return AppError::Internal(format!("Failed to read file: {}", e)).into_response();
+        }
+    };
+
+    let mut beads = parse_beads(&contents);
+    link_bead_relationships(&mut beads);
+
+    let ids: Vec<String> = beads.iter().map(|b| b.id.clone()).collect();
+    let mut cursor = since;
+    let changed: Vec<Bead> = beads
+        .into_iter()
+        .filter(|bead| match last_changed(bead) {
+            Some(at) => {
+                cursor = cursor.max(at);
+                at > since
+            }
+            None => false,
+        })
+        .collect();
+
+    (
+        StatusCode::OK,
+        [(header::ETAG, issues_etag(&contents))],
+        Json(serde_json::json!({
+            "beads": changed,
+            "ids": ids,
+            "cursor": cursor.to_rfc3339()
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_changed_includes_comments() {
+        let beads = parse_beads(concat!(
+            r#"{"id":"bd-1","title":"A","status":"open","created_at":"2026-01-01T00:00:00Z","updated_at":"2026-01-02T00:00:00Z"}"#,
+            "\n",
+            r#"{"id":"bd-2","title":"B","status":"open","created_at":"2026-01-01T00:00:00Z","comments":[{"id":1,"issue_id":"bd-2","author":"ann","text":"hi","created_at":"2026-01-05T00:00:00Z"}]}"#,
+            "\n",
+            r#"{"id":"bd-3","title":"C","status":"open"}"#,
+        ));
+
+        let at = |s: &str| parse_timestamp(Some(s));
+        assert_eq!(last_changed(&beads[0]), at("2026-01-02T00:00:00Z"));
+        assert_eq!(last_changed(&beads[1]), at("2026-01-05T00:00:00Z"));
+        assert_eq!(last_changed(&beads[2]), None);
+    }
+}
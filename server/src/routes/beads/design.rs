@@ -0,0 +1,414 @@
+//! Creating a bead's design doc from a template.
+//!
+//! The doc is written to `.designs/{BEAD_ID}.md` and linked from the bead's
+//! design field. Projects can supply their own template at
+//! `.beads/design-template.md`; `{{id}}`, `{{title}}`, and `{{date}}` in it
+//! are filled in from the bead.
+//!
+//! Renaming or deleting a doc under `.designs/` goes through here too, so
+//! beads linking the doc are updated in the same write and links don't
+//! break.
+
+use axum::{http::HeaderMap, http::StatusCode, response::IntoResponse, Json};
+use chrono::Utc;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use super::{
+    check_if_match, design_key, find_record_index, journal, mutation_issues_path, read_issue_lines,
+    record_at_mut, record_id, record_to_bead, set_optional_text, write_issue_lines, IssueLine,
+};
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+use crate::routes::trash::move_to_trash;
+use crate::routes::validate_path_security;
+
+/// Template used when the project doesn't have one.
+const DEFAULT_TEMPLATE: &str = "---
+bead: {{id}}
+title: {{title}}
+created: {{date}}
+---
+
+# {{title}}
+
+## Context
+
+## Proposal
+
+## Open questions
+";
+
+/// Request body for the create design doc endpoint.
+#[derive(Debug, Deserialize)]
+pub struct CreateDesignRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// The bead the doc is for
+    pub bead_id: String,
+}
+
+/// Request body for the rename design doc endpoint.
+#[derive(Debug, Deserialize)]
+pub struct RenameDesignRequest {
+    /// The project path (absolute directory path)
+    pub project_path: String,
+    /// The doc's current path (relative, e.g., ".designs/epic.md")
+    pub from: String,
+    /// The doc's new path (relative, e.g., ".designs/search.md")
+    pub to: String,
+}
+
+/// Request body for the delete design doc endpoint.
+#[derive(Debug, Deserialize)]
+pub struct DeleteDesignRequest {
+    /// The project path (absolute directory path)
+    pub project_path: String,
+    /// The doc's path (relative, e.g., ".designs/epic.md")
+    pub path: String,
+}
+
+/// The project's design doc template, or the default.
+fn design_template(project_path: &Path) -> String {
+    std::fs::read_to_string(project_path.join(".beads").join("design-template.md"))
+        .unwrap_or_else(|_| DEFAULT_TEMPLATE.to_string())
+}
+
+/// Fills in the template placeholders.
+fn render_template(template: &str, bead_id: &str, title: &str, date: &str) -> String {
+    template
+        .replace("{{id}}", bead_id)
+        .replace("{{title}}", title)
+        .replace("{{date}}", date)
+}
+
+/// The design doc path for a bead, relative to the project.
+///
+/// Bead IDs become file names, so anything that could leave `.designs/` is
+/// rejected.
+fn design_path(bead_id: &str) -> Result<String, String> {
+    let valid = !bead_id.is_empty()
+        && !bead_id.starts_with('.')
+        && bead_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "Bead ID '{}' can't be used as a file name",
+            bead_id
+        ));
+    }
+    Ok(format!(".designs/{}.md", bead_id))
+}
+
+/// Checks that a path names a Markdown file inside `.designs/`.
+fn validate_design_file(path: &str) -> Result<(), String> {
+    let inside = path.strip_prefix(".designs/").is_some_and(|rest| {
+        !path.contains('\\')
+            && rest
+                .split('/')
+                .all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+    });
+    if !inside {
+        return Err(format!(
+            "Access denied: '{}' is not a path inside .designs/",
+            path
+        ));
+    }
+    if !path.ends_with(".md") {
+        return Err("Access denied: only .md files are allowed".to_string());
+    }
+    Ok(())
+}
+
+/// Points every bead linking the design doc `from` at `to`, or unlinks it
+/// when `to` is empty. Stamps `updated_at` on the changed beads and returns
+/// their IDs.
+fn relink_design(lines: &mut [IssueLine], from: &str, to: &str, now: &str) -> Vec<String> {
+    let mut updated = Vec::new();
+    for line in lines.iter_mut() {
+        let IssueLine::Record(record) = line else {
+            continue;
+        };
+        let key = design_key(record);
+        let linked = record
+            .get(key)
+            .and_then(|v| v.as_str())
+            .is_some_and(|v| v.strip_prefix("./").unwrap_or(v) == from);
+        if !linked {
+            continue;
+        }
+        set_optional_text(record, key, to);
+        record.insert("updated_at".to_string(), serde_json::json!(now));
+        if let Some(id) = record_id(record) {
+            updated.push(id.to_string());
+        }
+    }
+    updated
+}
+
+/// POST /api/fs/rename
+///
+/// Renames or moves a design doc within `.designs/` and points every bead
+/// linking it at the new path in the same write. Fails with 409 if the new
+/// path already exists. Honors `If-Match`.
+pub async fn rename_design(
+    headers: HeaderMap,
+    Json(payload): Json<RenameDesignRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    validate_design_file(&payload.from).map_err(AppError::Forbidden)?;
+    validate_design_file(&payload.to).map_err(AppError::Forbidden)?;
+    if payload.from == payload.to {
+        return Err(AppError::BadRequest(
+            "New path must differ from the current path".to_string(),
+        ));
+    }
+
+    let issues_path = mutation_issues_path(&payload.project_path)?;
+    let project_path = PathBuf::from(&payload.project_path);
+    let from = project_path.join(&payload.from);
+    let to = project_path.join(&payload.to);
+    // The new path may be in a directory that doesn't exist yet; the checks
+    // above already keep it inside .designs/
+    validate_path_security(&from).map_err(AppError::Forbidden)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    if !from.is_file() {
+        return Err(AppError::NotFound(format!(
+            "Design doc not found: {}",
+            payload.from
+        )));
+    }
+    if to.exists() {
+        return Err(AppError::Conflict(format!(
+            "{} already exists",
+            payload.to
+        )));
+    }
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+    let updated = relink_design(
+        &mut lines,
+        &payload.from,
+        &payload.to,
+        &Utc::now().to_rfc3339(),
+    );
+
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::Internal(format!("Failed to create directory: {}", e)))?;
+    }
+    std::fs::rename(&from, &to)
+        .map_err(|e| AppError::Internal(format!("Failed to rename design doc: {}", e)))?;
+    if let Err(e) = write_issue_lines(&issues_path, &lines) {
+        if let Err(e) = std::fs::rename(&to, &from) {
+            tracing::warn!("Failed to move {} back: {}", to.display(), e);
+        }
+        return Err(AppError::Internal(e));
+    }
+    journal::record(&issues_path, "design", &before, &lines);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "path": payload.to,
+        "updated": updated
+    })))
+}
+
+/// DELETE /api/fs/delete
+///
+/// Moves a design doc from `.designs/` to the project's trash (see
+/// [`crate::routes::trash`]) and clears the design field of every bead
+/// linking it. Honors `If-Match`.
+pub async fn delete_design(
+    headers: HeaderMap,
+    Json(payload): Json<DeleteDesignRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    validate_design_file(&payload.path).map_err(AppError::Forbidden)?;
+
+    let issues_path = mutation_issues_path(&payload.project_path)?;
+    let project_path = PathBuf::from(&payload.project_path);
+    let file_path = project_path.join(&payload.path);
+    validate_path_security(&file_path).map_err(AppError::Forbidden)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    if !file_path.is_file() {
+        return Err(AppError::NotFound(format!(
+            "Design doc not found: {}",
+            payload.path
+        )));
+    }
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+    let updated = relink_design(&mut lines, &payload.path, "", &Utc::now().to_rfc3339());
+
+    // Unlink first so a failed write never leaves beads pointing at a
+    // deleted doc; put the links back if the delete fails
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    let entry = match move_to_trash(&project_path, &file_path) {
+        Ok(entry) => entry,
+        Err(e) => {
+            if let Err(e) = write_issue_lines(&issues_path, &before) {
+                tracing::warn!("Failed to restore design links: {}", e);
+            }
+            return Err(AppError::Internal(e));
+        }
+    };
+    journal::record(&issues_path, "design", &before, &lines);
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "deleted": payload.path,
+        "trash_id": entry.id,
+        "updated": updated
+    })))
+}
+
+/// POST /api/designs
+///
+/// Creates `.designs/{BEAD_ID}.md` from the project's template and links it
+/// from the bead. An existing file is kept and linked as is. Fails with 409
+/// if the bead already links a different design doc. Honors `If-Match`.
+pub async fn create_design(
+    headers: HeaderMap,
+    Json(payload): Json<CreateDesignRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let doc = design_path(&payload.bead_id).map_err(AppError::BadRequest)?;
+    let project_path = PathBuf::from(&payload.path);
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let index = match find_record_index(&lines, &payload.bead_id) {
+        Some(i) => i,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                payload.bead_id
+            )));
+        }
+    };
+
+    let record = record_at_mut(&mut lines, index);
+    let key = design_key(record);
+    let linked = record
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.is_empty());
+    if let Some(linked) = linked.filter(|linked| *linked != doc) {
+        return Err(
+            AppError::Conflict(format!("Bead already has a design doc: {}", linked))
+                .with_details(serde_json::json!({ "design_doc": linked })),
+        );
+    }
+
+    let file_path = project_path.join(&doc);
+    let created = !file_path.exists();
+    if created {
+        let title = record.get("title").and_then(|v| v.as_str()).unwrap_or("");
+        let contents = render_template(
+            &design_template(&project_path),
+            &payload.bead_id,
+            title,
+            &Utc::now().format("%Y-%m-%d").to_string(),
+        );
+        std::fs::create_dir_all(project_path.join(".designs"))
+            .map_err(|e| AppError::Internal(format!("Failed to create .designs: {}", e)))?;
+        std::fs::write(&file_path, contents)
+            .map_err(|e| AppError::Internal(format!("Failed to write design doc: {}", e)))?;
+    }
+
+    set_optional_text(record, key, &doc);
+    record.insert(
+        "updated_at".to_string(),
+        serde_json::json!(Utc::now().to_rfc3339()),
+    );
+    let bead = record_to_bead(record).map_err(AppError::Internal)?;
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "design", &before, &lines);
+
+    Ok((
+        if created {
+            StatusCode::CREATED
+        } else {
+            StatusCode::OK
+        },
+        Json(serde_json::json!({
+            "success": true,
+            "bead": bead,
+            "path": doc,
+            "created": created
+        })),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let doc = render_template(DEFAULT_TEMPLATE, "bd-1", "Search", "2026-01-02");
+        assert!(doc.starts_with("---\nbead: bd-1\ntitle: Search\ncreated: 2026-01-02\n---\n"));
+        assert!(doc.contains("# Search\n"));
+    }
+
+    #[test]
+    fn test_validate_design_file() {
+        assert!(validate_design_file(".designs/bd-1.md").is_ok());
+        assert!(validate_design_file(".designs/api/v2.md").is_ok());
+        assert!(validate_design_file(".designs/../README.md").is_err());
+        assert!(validate_design_file(".designs//a.md").is_err());
+        assert!(validate_design_file(".designs/a.txt").is_err());
+        assert!(validate_design_file("docs/a.md").is_err());
+    }
+
+    #[test]
+    fn test_relink_design() {
+        let mut lines: Vec<IssueLine> = [
+            r#"{"id":"bd-1","title":"A","status":"open","design":".designs/a.md"}"#,
+            r#"{"id":"bd-2","title":"B","status":"open","design_doc":"./.designs/a.md"}"#,
+            r#"{"id":"bd-3","title":"C","status":"open","design":".designs/b.md"}"#,
+        ]
+        .iter()
+        .map(|l| IssueLine::Record(serde_json::from_str(l).unwrap()))
+        .collect();
+
+        let updated = relink_design(&mut lines, ".designs/a.md", ".designs/c.md", "now");
+        assert_eq!(updated, vec!["bd-1", "bd-2"]);
+        assert_eq!(record_at_mut(&mut lines, 0)["design"], ".designs/c.md");
+        assert_eq!(record_at_mut(&mut lines, 0)["updated_at"], "now");
+        assert_eq!(record_at_mut(&mut lines, 1)["design_doc"], ".designs/c.md");
+        assert_eq!(record_at_mut(&mut lines, 2)["design"], ".designs/b.md");
+
+        let updated = relink_design(&mut lines, ".designs/c.md", "", "later");
+        assert_eq!(updated, vec!["bd-1", "bd-2"]);
+        assert!(!record_at_mut(&mut lines, 0).contains_key("design"));
+        assert!(!record_at_mut(&mut lines, 1).contains_key("design_doc"));
+    }
+
+    #[test]
+    fn test_design_path() {
+        assert_eq!(design_path("bd-a1b2.3").unwrap(), ".designs/bd-a1b2.3.md");
+        assert!(design_path("../etc").is_err());
+        assert!(design_path("a/b").is_err());
+        assert!(design_path("").is_err());
+    }
+}
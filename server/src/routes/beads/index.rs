@@ -0,0 +1,857 @@
+//! SQLite read index for `GET /api/beads`.
+//!
+//! Each project's `issues.jsonl` is mirrored into an in-memory SQLite
+//! database so list, filter, search, and paging queries do not have to parse
+//! the whole file on every request. The file stays the source of truth: an
+//! index is updated whenever the file's size or modification time differs
+//! from the ones it was built from, whenever this server rewrites the file,
+//! and whenever the watcher reports a change. Since most `bd` writes append
+//! lines, a file that only grew is updated by reading and parsing just the
+//! new bytes.
+
+use rusqlite::{
+    params, params_from_iter, types::Value, Connection, OptionalExtension, Transaction,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
+
+use super::{
+    link_bead_relationships, link_relates_to, parse_beads, HierarchyNode, parse_sort,
+    split_csv_param, Bead, BeadsParams, EtagHasher,
+};
+
+/// A page of beads answered from the index.
+#[derive(Debug)]
+pub(super) struct IndexedBeads {
+    /// ETag of the file contents the index was built from.
+    pub etag: String,
+    /// Number of beads matching the filters before paging.
+    pub total: usize,
+    pub beads: Vec<Bead>,
+}
+
+/// `parent_kind` of a bead whose parent comes from a parent-child dependency.
+const EXPLICIT: &str = "explicit";
+
+/// `parent_kind` of a bead whose parent is inferred from its dotted ID.
+const INFERRED: &str = "inferred";
+
+/// Size and modification time of an issues file, used to detect changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FileStamp {
+    len: i64,
+    modified_ns: i64,
+}
+
+/// Bytes at the end of the indexed contents kept to check that a file that
+/// grew still holds them where they were.
+const TAIL_WINDOW: usize = 4096;
+
+/// The contents an index was built from, as far as needed to tell whether
+/// the file was only appended to since.
+#[derive(Clone)]
+struct IndexedSource {
+    stamp: FileStamp,
+    /// Hash of the indexed contents, resumed with appended bytes
+    hasher: EtagHasher,
+    /// The last [`TAIL_WINDOW`] bytes of the indexed contents
+    tail: Vec<u8>,
+}
+
+impl IndexedSource {
+    fn new(contents: &[u8], modified_ns: i64) -> Self {
+        let mut hasher = EtagHasher::default();
+        hasher.update(contents);
+        Self {
+            stamp: FileStamp {
+                len: contents.len() as i64,
+                modified_ns,
+            },
+            hasher,
+            tail: contents[contents.len().saturating_sub(TAIL_WINDOW)..].to_vec(),
+        }
+    }
+
+    /// The source once `appended` is added to the end of the contents.
+    fn grown(&self, appended: &[u8], modified_ns: i64) -> Self {
+        let mut hasher = self.hasher.clone();
+        hasher.update(appended);
+        let mut tail = self.tail.clone();
+        tail.extend_from_slice(appended);
+        tail.drain(..tail.len().saturating_sub(TAIL_WINDOW));
+        Self {
+            stamp: FileStamp {
+                len: self.stamp.len + appended.len() as i64,
+                modified_ns,
+            },
+            hasher,
+            tail,
+        }
+    }
+
+    fn etag(&self) -> String {
+        self.hasher.finish()
+    }
+}
+
+/// The index database and the file contents each project's beads came from.
+struct IndexState {
+    conn: Connection,
+    sources: HashMap<String, IndexedSource>,
+}
+
+/// The bead index database.
+struct BeadIndex {
+    state: Mutex<IndexState>,
+}
+
+fn index() -> Result<&'static BeadIndex, String> {
+    static INDEX: OnceLock<Result<BeadIndex, String>> = OnceLock::new();
+    INDEX
+        .get_or_init(BeadIndex::open)
+        .as_ref()
+        .map_err(Clone::clone)
+}
+
+fn sql_error(e: rusqlite::Error) -> String {
+    format!("Bead index error: {}", e)
+}
+
+/// Reads the size and modification time of a file.
+fn file_stamp(path: &Path) -> Result<FileStamp, String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("Failed to read file: {}", e))?;
+    let modified_ns = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos() as i64);
+    Ok(FileStamp {
+        len: metadata.len() as i64,
+        modified_ns,
+    })
+}
+
+/// Reads what was appended to a file since it was indexed as `indexed`, or
+/// `None` if it changed in some other way (as far as can be told from the
+/// indexed tail window still being in place).
+///
+/// Only the tail window and the appended bytes are read.
+fn read_appended(issues_path: &Path, indexed: &IndexedSource) -> Result<Option<String>, String> {
+    // A partial last line may have been completed since
+    if indexed.tail.last().is_some_and(|b| *b != b'\n') {
+        return Ok(None);
+    }
+    let read_error = |e: std::io::Error| format!("Failed to read file: {}", e);
+    let mut file = std::fs::File::open(issues_path).map_err(read_error)?;
+    let window_start = indexed.stamp.len as u64 - indexed.tail.len() as u64;
+    file.seek(SeekFrom::Start(window_start)).map_err(read_error)?;
+
+    let mut window = vec![0; indexed.tail.len()];
+    // Too short to hold the window means the file was truncated
+    if file.read_exact(&mut window).is_err() || window != indexed.tail {
+        return Ok(None);
+    }
+    let mut appended = Vec::new();
+    file.read_to_end(&mut appended).map_err(read_error)?;
+    if appended.is_empty() {
+        return Ok(None);
+    }
+    Ok(String::from_utf8(appended).ok())
+}
+
+/// Appends `column IN (?, ...)` for a non-empty list of values.
+fn push_any_of(clause: &mut String, values: &mut Vec<Value>, column: &str, items: Vec<Value>) {
+    if items.is_empty() {
+        return;
+    }
+    let placeholders = vec!["?"; items.len()].join(", ");
+    clause.push_str(&format!(" AND {} IN ({})", column, placeholders));
+    values.extend(items);
+}
+
+fn text_values(items: Vec<&str>) -> Vec<Value> {
+    items
+        .into_iter()
+        .map(|s| Value::Text(s.to_string()))
+        .collect()
+}
+
+/// Builds the WHERE clause and its parameters for the bead filters.
+///
+/// Mirrors `bead_matches`, which applies the same filters in memory.
+fn filter_clause(key: &str, params: &BeadsParams, priorities: &[i32]) -> (String, Vec<Value>) {
+    let mut clause = String::from("issues_path = ?");
+    let mut values = vec![Value::Text(key.to_string())];
+
+    let statuses = text_values(split_csv_param(params.status.as_deref()));
+    push_any_of(&mut clause, &mut values, "status", statuses);
+    let issue_types = text_values(split_csv_param(params.issue_type.as_deref()));
+    push_any_of(&mut clause, &mut values, "issue_type", issue_types);
+    let owners = text_values(split_csv_param(params.owner.as_deref()));
+    push_any_of(&mut clause, &mut values, "owner", owners);
+    let priorities = priorities
+        .iter()
+        .map(|p| Value::Integer(i64::from(*p)))
+        .collect();
+    push_any_of(&mut clause, &mut values, "priority", priorities);
+    let milestones = text_values(split_csv_param(params.milestone.as_deref()));
+    push_any_of(&mut clause, &mut values, "milestone", milestones);
+
+    let labels = text_values(split_csv_param(params.labels.as_deref()));
+    if !labels.is_empty() {
+        clause.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM bead_labels l WHERE l.issues_path = beads.issues_path \
+             AND l.position = beads.position AND l.label IN ({}))",
+            vec!["?"; labels.len()].join(", ")
+        ));
+        values.extend(labels);
+    }
+
+    if let Some(q) = params.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        clause.push_str(
+            " AND (instr(id_lower, ?) > 0 OR instr(title_lower, ?) > 0 \
+             OR instr(description_lower, ?) > 0)",
+        );
+        let q = q.to_lowercase();
+        values.extend(std::iter::repeat_n(Value::Text(q), 3));
+    }
+
+    (clause, values)
+}
+
+/// Builds the ORDER BY clause for a validated `sort` value.
+///
+/// Like `sort_beads`, missing values sort last in either direction and ties
+/// keep file order.
+fn order_clause(sort: Option<&str>) -> Result<String, String> {
+    let Some(sort) = sort else {
+        return Ok("position".to_string());
+    };
+    let (field, descending) = parse_sort(sort)?;
+    let column = match field {
+        "title" => "title_lower",
+        other => other,
+    };
+    Ok(format!(
+        "{column} IS NULL, {column} {}, position",
+        if descending { "DESC" } else { "ASC" }
+    ))
+}
+
+impl BeadIndex {
+    fn open() -> Result<Self, String> {
+        let conn = Connection::open_in_memory().map_err(sql_error)?;
+        conn.execute_batch(
+            r"
+            CREATE TABLE beads (
+                issues_path TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                id TEXT NOT NULL,
+                id_lower TEXT NOT NULL,
+                title_lower TEXT NOT NULL,
+                description_lower TEXT,
+                status TEXT NOT NULL,
+                priority INTEGER,
+                issue_type TEXT,
+                owner TEXT,
+                milestone TEXT,
+                created_at TEXT,
+                updated_at TEXT,
+                parent_id TEXT,
+                parent_kind TEXT,
+                children TEXT,
+                data TEXT NOT NULL,
+                PRIMARY KEY (issues_path, position)
+            );
+
+            CREATE TABLE bead_labels (
+                issues_path TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                label TEXT NOT NULL,
+                PRIMARY KEY (issues_path, position, label)
+            );
+
+            CREATE INDEX idx_beads_status ON beads(issues_path, status);
+            CREATE INDEX idx_beads_id ON beads(issues_path, id);
+            CREATE INDEX idx_beads_parent ON beads(issues_path, parent_id);
+            ",
+        )
+        .map_err(sql_error)?;
+
+        Ok(Self {
+            state: Mutex::new(IndexState {
+                conn,
+                sources: HashMap::new(),
+            }),
+        })
+    }
+
+    /// Returns the ETag of the indexed contents, bringing the index for the
+    /// file up to date first if it is missing or stale.
+    ///
+    /// When the file has only grown since it was indexed, just the appended
+    /// bytes are read and parsed, and the ETag is computed by resuming the
+    /// hash of the indexed contents. The last indexed bytes are checked to
+    /// still be in place first (bd also rewrites the file); anything else,
+    /// including truncation, falls back to a full rebuild.
+    fn ensure_fresh(state: &mut IndexState, issues_path: &Path) -> Result<String, String> {
+        let IndexState { conn, sources } = state;
+        let key = issues_path.to_string_lossy().to_string();
+        // Read the stamp before the file so a concurrent write is caught by
+        // the next freshness check
+        let stamp = file_stamp(issues_path)?;
+        let indexed = sources.get(&key);
+        if let Some(indexed) = indexed {
+            if indexed.stamp == stamp {
+                return Ok(indexed.etag());
+            }
+        }
+
+        let tx = conn.transaction().map_err(sql_error)?;
+        let mut source = None;
+        if let Some(indexed) = indexed.filter(|indexed| stamp.len > indexed.stamp.len) {
+            if let Some(tail) = read_appended(issues_path, indexed)? {
+                if Self::append(&tx, &key, &tail)? {
+                    source = Some(indexed.grown(tail.as_bytes(), stamp.modified_ns));
+                }
+            }
+        }
+        let source = match source {
+            Some(source) => source,
+            None => {
+                let contents = std::fs::read_to_string(issues_path)
+                    .map_err(|e| format!("Failed to read file: {}", e))?;
+                Self::rebuild(&tx, &key, &contents)?;
+                IndexedSource::new(contents.as_bytes(), stamp.modified_ns)
+            }
+        };
+        tx.commit().map_err(sql_error)?;
+
+        let etag = source.etag();
+        sources.insert(key, source);
+        Ok(etag)
+    }
+
+    /// Replaces the indexed beads for a file with its contents.
+    fn rebuild(tx: &Transaction, key: &str, contents: &str) -> Result<(), String> {
+        tx.execute("DELETE FROM beads WHERE issues_path = ?1", params![key])
+            .map_err(sql_error)?;
+        tx.execute(
+            "DELETE FROM bead_labels WHERE issues_path = ?1",
+            params![key],
+        )
+        .map_err(sql_error)?;
+
+        let mut beads = parse_beads(contents);
+        // Parent IDs present in the JSON are kept as-is and never listed as children
+        let kinds: Vec<Option<&str>> = beads
+            .iter()
+            .map(|bead| match HierarchyNode::of(bead).parent_deps.len() {
+                0 if bead.parent_id.is_some() => None,
+                0 => Some(INFERRED),
+                _ => Some(EXPLICIT),
+            })
+            .collect();
+        link_bead_relationships(&mut beads);
+
+        for (position, (bead, kind)) in beads.into_iter().zip(kinds).enumerate() {
+            let kind = bead.parent_id.as_ref().and(kind);
+            Self::insert(tx, key, position as i64, bead, kind)?;
+        }
+        Ok(())
+    }
+
+    /// Indexes beads appended to the end of a file.
+    ///
+    /// Returns `false` without changing anything if the new beads would
+    /// change the relationships of beads already indexed (a duplicate ID, a
+    /// bead that existing beads name as their parent, or a bead with several
+    /// parents); the caller then rebuilds the index instead.
+    fn append(tx: &Transaction, key: &str, tail: &str) -> Result<bool, String> {
+        let mut beads = parse_beads(tail);
+        let new_ids: HashSet<&str> = beads.iter().map(|b| b.id.as_str()).collect();
+        if new_ids.len() != beads.len() {
+            return Ok(false);
+        }
+
+        let exists = |sql: &str, id: &str| -> Result<bool, String> {
+            tx.query_row(sql, params![key, id], |_| Ok(()))
+                .optional()
+                .map(|row| row.is_some())
+                .map_err(sql_error)
+        };
+        for bead in &beads {
+            if HierarchyNode::of(bead).parent_deps.len() > 1
+                || exists(
+                    "SELECT 1 FROM beads WHERE issues_path = ?1 AND (id = ?2 OR parent_id = ?2 \
+                     OR (parent_id IS NULL AND substr(id, 1, length(?2) + 1) = ?2 || '.' \
+                     AND instr(substr(id, length(?2) + 2), '.') = 0)) LIMIT 1",
+                    &bead.id,
+                )?
+            {
+                return Ok(false);
+            }
+        }
+
+        // Link the new beads the same way `link_bead_relationships` would
+        let mut links: Vec<Option<(String, &str)>> = Vec::with_capacity(beads.len());
+        for bead in &beads {
+            let link = if let Some(parent) = HierarchyNode::of(bead).parent_deps.pop() {
+                Some((parent, EXPLICIT))
+            } else if bead.parent_id.is_some() {
+                None
+            } else {
+                match bead.id.rfind('.').map(|dot| &bead.id[..dot]) {
+                    Some(parent)
+                        if new_ids.contains(parent)
+                            || exists(
+                                "SELECT 1 FROM beads WHERE issues_path = ?1 AND id = ?2 LIMIT 1",
+                                parent,
+                            )? =>
+                    {
+                        Some((parent.to_string(), INFERRED))
+                    }
+                    _ => None,
+                }
+            };
+            links.push(link);
+        }
+
+        let first_position: i64 = tx
+            .query_row(
+                "SELECT COALESCE(MAX(position) + 1, 0) FROM beads WHERE issues_path = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .map_err(sql_error)?;
+
+        // Children gained by each parent, explicit ones first
+        let mut gained: BTreeMap<String, (Vec<String>, Vec<String>)> = BTreeMap::new();
+        for (bead, link) in beads.iter().zip(&links) {
+            if let Some((parent, kind)) = link {
+                let entry = gained.entry(parent.clone()).or_default();
+                let list = if *kind == EXPLICIT {
+                    &mut entry.0
+                } else {
+                    &mut entry.1
+                };
+                list.push(bead.id.clone());
+            }
+        }
+
+        for (offset, (mut bead, link)) in beads.drain(..).zip(links).enumerate() {
+            link_relates_to(&mut bead);
+            let kind = match link {
+                Some((parent, kind)) => {
+                    bead.parent_id = Some(parent);
+                    Some(kind)
+                }
+                None => None,
+            };
+            bead.children = None;
+            Self::insert(tx, key, first_position + offset as i64, bead, kind)?;
+        }
+
+        for (parent, (explicit, inferred)) in gained {
+            let current: Option<Option<String>> = tx
+                .query_row(
+                    "SELECT children FROM beads WHERE issues_path = ?1 AND id = ?2 LIMIT 1",
+                    params![key, parent],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(sql_error)?;
+            // A parent that is not on the board has no row to update
+            let Some(current) = current else {
+                continue;
+            };
+            let mut children: Vec<String> = current
+                .and_then(|c| serde_json::from_str(&c).ok())
+                .unwrap_or_default();
+            let existing_inferred: i64 = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM beads WHERE issues_path = ?1 AND parent_id = ?2 \
+                     AND parent_kind = ?3 AND position < ?4",
+                    params![key, parent, INFERRED, first_position],
+                    |row| row.get(0),
+                )
+                .map_err(sql_error)?;
+            let split = children.len().saturating_sub(existing_inferred as usize);
+            children.splice(split..split, explicit);
+            children.extend(inferred);
+
+            tx.execute(
+                "UPDATE beads SET children = ?3 WHERE issues_path = ?1 AND id = ?2",
+                params![
+                    key,
+                    parent,
+                    serde_json::to_string(&children).unwrap_or_default()
+                ],
+            )
+            .map_err(sql_error)?;
+        }
+
+        Ok(true)
+    }
+
+    /// Inserts one linked bead at `position`.
+    ///
+    /// `kind` records how the parent was determined (`None` if the bead has
+    /// no parent or the parent ID came from the JSON itself).
+    fn insert(
+        tx: &Transaction,
+        key: &str,
+        position: i64,
+        mut bead: Bead,
+        kind: Option<&str>,
+    ) -> Result<(), String> {
+        // Relationships live in their own columns
+        let parent_id = bead.parent_id.take();
+        let children = bead
+            .children
+            .take()
+            .map(|c| serde_json::to_string(&c).unwrap_or_default());
+        let data =
+            serde_json::to_string(&bead).map_err(|e| format!("Failed to serialize bead: {}", e))?;
+
+        tx.prepare_cached(
+            "INSERT INTO beads (issues_path, position, id, id_lower, title_lower,
+                description_lower, status, priority, issue_type, owner, milestone,
+                created_at, updated_at, parent_id, parent_kind, children, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        )
+        .and_then(|mut stmt| {
+            stmt.execute(params![
+                key,
+                position,
+                bead.id,
+                bead.id.to_lowercase(),
+                bead.title.to_lowercase(),
+                bead.description.as_deref().map(str::to_lowercase),
+                bead.status,
+                bead.priority,
+                bead.issue_type,
+                bead.owner,
+                bead.milestone,
+                bead.created_at,
+                bead.updated_at,
+                parent_id,
+                kind,
+                children,
+                data,
+            ])
+        })
+        .map_err(sql_error)?;
+
+        let mut insert_label = tx
+            .prepare_cached(
+                "INSERT OR IGNORE INTO bead_labels (issues_path, position, label) VALUES (?1, ?2, ?3)",
+            )
+            .map_err(sql_error)?;
+        for label in &bead.labels {
+            insert_label
+                .execute(params![key, position, label])
+                .map_err(sql_error)?;
+        }
+        Ok(())
+    }
+
+    fn query(
+        &self,
+        issues_path: &Path,
+        params: &BeadsParams,
+        priorities: &[i32],
+    ) -> Result<IndexedBeads, String> {
+        let order = order_clause(params.sort.as_deref())?;
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let etag = Self::ensure_fresh(&mut state, issues_path)?;
+        let conn = &state.conn;
+
+        let key = issues_path.to_string_lossy();
+        let (clause, mut values) = filter_clause(&key, params, priorities);
+
+        let total: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM beads WHERE {}", clause),
+                params_from_iter(values.iter()),
+                |row| row.get(0),
+            )
+            .map_err(sql_error)?;
+
+        // SQLite treats a negative LIMIT as "no limit"
+        values.push(Value::Integer(
+            params.limit.map_or(-1, |l| l.min(i64::MAX as usize) as i64),
+        ));
+        values.push(Value::Integer(
+            params.offset.unwrap_or(0).min(i64::MAX as usize) as i64,
+        ));
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT parent_id, children, data FROM beads WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+                clause, order
+            ))
+            .map_err(sql_error)?;
+        let rows = stmt
+            .query_map(params_from_iter(values.iter()), |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(sql_error)?;
+
+        let mut beads = Vec::new();
+        for row in rows {
+            let (parent_id, children, data) = row.map_err(sql_error)?;
+            let mut bead: Bead = serde_json::from_str(&data)
+                .map_err(|e| format!("Failed to parse indexed bead: {}", e))?;
+            bead.parent_id = parent_id;
+            bead.children = children.and_then(|c| serde_json::from_str(&c).ok());
+            beads.push(bead);
+        }
+
+        Ok(IndexedBeads {
+            etag,
+            total: total as usize,
+            beads,
+        })
+    }
+
+    /// Drops the freshness record for a file so the next query rebuilds it.
+    fn invalidate(&self, issues_path: &Path) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state
+            .sources
+            .remove(issues_path.to_string_lossy().as_ref());
+        Ok(())
+    }
+
+    /// Brings the index for a file up to date, or drops it if the file is gone.
+    fn refresh(&self, issues_path: &Path) -> Result<(), String> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if issues_path.exists() {
+            Self::ensure_fresh(&mut state, issues_path)?;
+            return Ok(());
+        }
+
+        let key = issues_path.to_string_lossy();
+        state.sources.remove(key.as_ref());
+        for table in ["beads", "bead_labels"] {
+            state.conn.execute(
+                &format!("DELETE FROM {} WHERE issues_path = ?1", table),
+                params![key],
+            )
+            .map_err(sql_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Answers a `GET /api/beads` query from the index.
+pub(super) fn query(
+    issues_path: &Path,
+    params: &BeadsParams,
+    priorities: &[i32],
+) -> Result<IndexedBeads, String> {
+    index()?.query(issues_path, params, priorities)
+}
+
+/// Marks the index for a file stale after this server rewrote it.
+pub(super) fn invalidate(issues_path: &Path) {
+    if let Err(e) = index().and_then(|index| index.invalidate(issues_path)) {
+        tracing::warn!(
+            "Failed to invalidate bead index for {:?}: {}",
+            issues_path,
+            e
+        );
+    }
+}
+
+/// Brings the index for a file up to date (called by the file watcher).
+pub fn refresh(issues_path: &Path) -> Result<(), String> {
+    index()?.refresh(issues_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::beads::issues_etag;
+    use tempfile::tempdir;
+
+    fn params(query: &str) -> BeadsParams {
+        let mut value = serde_json::json!({ "path": "/unused" });
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (k, v) = pair.split_once('=').unwrap();
+            value[k] = if k == "limit" || k == "offset" {
+                serde_json::json!(v.parse::<usize>().unwrap())
+            } else {
+                serde_json::json!(v)
+            };
+        }
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn ids(index: &BeadIndex, path: &Path, query: &str) -> (Vec<String>, usize) {
+        let page = index.query(path, &params(query), &[]).unwrap();
+        (page.beads.into_iter().map(|b| b.id).collect(), page.total)
+    }
+
+    const CONTENTS: &str = concat!(
+        r#"{"id":"bd-1","title":"Epic","status":"open","priority":1,"issue_type":"epic","labels":["ui"]}"#,
+        "\n",
+        r#"{"id":"bd-1.1","title":"Button","status":"in_progress","priority":2,"owner":"ann","description":"Fix the LOGIN button"}"#,
+        "\n",
+        r#"{"id":"bd-2","title":"api","status":"closed","labels":["backend","ui"],"updated_at":"2026-01-02T00:00:00Z"}"#,
+        "\n",
+        "{not json\n",
+    );
+
+    #[test]
+    fn test_query_filters_sorts_and_pages() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("issues.jsonl");
+        std::fs::write(&path, CONTENTS).unwrap();
+        let index = BeadIndex::open().unwrap();
+
+        assert_eq!(
+            ids(&index, &path, ""),
+            (vec!["bd-1".into(), "bd-1.1".into(), "bd-2".into()], 3)
+        );
+        assert_eq!(
+            ids(&index, &path, "status=open,closed"),
+            (vec!["bd-1".into(), "bd-2".into()], 2)
+        );
+        assert_eq!(
+            ids(&index, &path, "labels=ui"),
+            (vec!["bd-1".into(), "bd-2".into()], 2)
+        );
+        assert_eq!(ids(&index, &path, "q=login"), (vec!["bd-1.1".into()], 1));
+        assert_eq!(
+            ids(&index, &path, "sort=-priority"),
+            (vec!["bd-1.1".into(), "bd-1".into(), "bd-2".into()], 3)
+        );
+        assert_eq!(
+            ids(&index, &path, "sort=title&limit=1&offset=1"),
+            (vec!["bd-1.1".into()], 3)
+        );
+
+        let page = index.query(&path, &params(""), &[]).unwrap();
+        assert_eq!(page.etag, issues_etag(CONTENTS));
+        assert_eq!(page.beads[0].children, Some(vec!["bd-1.1".to_string()]));
+        assert_eq!(page.beads[1].parent_id.as_deref(), Some("bd-1"));
+    }
+
+    #[test]
+    fn test_query_picks_up_file_changes() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("issues.jsonl");
+        std::fs::write(&path, CONTENTS).unwrap();
+        let index = BeadIndex::open().unwrap();
+        assert_eq!(ids(&index, &path, "").1, 3);
+
+        let updated = format!(
+            "{}{}\n",
+            CONTENTS, r#"{"id":"bd-3","title":"New","status":"open"}"#
+        );
+        std::fs::write(&path, &updated).unwrap();
+        assert_eq!(
+            ids(&index, &path, "status=open"),
+            (vec!["bd-1".into(), "bd-3".into()], 2)
+        );
+
+        index.refresh(&path).unwrap();
+        std::fs::write(&path, CONTENTS.replace("Epic", "Big epic")).unwrap();
+        assert_eq!(ids(&index, &path, "q=big").0, vec!["bd-1".to_string()]);
+
+        std::fs::remove_file(&path).unwrap();
+        index.refresh(&path).unwrap();
+        assert!(index.query(&path, &params(""), &[]).is_err());
+    }
+
+    fn serialized(index: &BeadIndex, path: &Path) -> String {
+        serde_json::to_string(&index.query(path, &params(""), &[]).unwrap().beads).unwrap()
+    }
+
+    #[test]
+    fn test_read_appended() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("issues.jsonl");
+        let old = "{\"id\":\"bd-1\"}\n";
+        let indexed = IndexedSource::new(old.as_bytes(), 0);
+        let grown = format!("{}{{\"id\":\"bd-2\"}}\n", old);
+
+        std::fs::write(&path, &grown).unwrap();
+        assert_eq!(
+            read_appended(&path, &indexed).unwrap().as_deref(),
+            Some("{\"id\":\"bd-2\"}\n")
+        );
+        // Resuming the hash gives the ETag of the whole file
+        let appended = &grown.as_bytes()[old.len()..];
+        assert_eq!(indexed.grown(appended, 0).etag(), issues_etag(&grown));
+
+        std::fs::write(&path, old).unwrap();
+        assert_eq!(read_appended(&path, &indexed).unwrap(), None);
+        std::fs::write(&path, grown.replace("bd-1", "bd-9")).unwrap();
+        assert_eq!(read_appended(&path, &indexed).unwrap(), None);
+
+        // Bytes before the window are not read, so only the window must match
+        let large = format!("{}\n", "x".repeat(TAIL_WINDOW * 2));
+        let indexed = IndexedSource::new(large.as_bytes(), 0);
+        assert_eq!(indexed.tail.len(), TAIL_WINDOW);
+        let mut changed_early = large.replacen('x', "y", 1);
+        changed_early.push_str("{\"id\":\"bd-3\"}\n");
+        std::fs::write(&path, &changed_early).unwrap();
+        assert_eq!(
+            read_appended(&path, &indexed).unwrap().as_deref(),
+            Some("{\"id\":\"bd-3\"}\n")
+        );
+    }
+
+    #[test]
+    fn test_append_matches_rebuild() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("issues.jsonl");
+        std::fs::write(&path, CONTENTS).unwrap();
+        let index = BeadIndex::open().unwrap();
+        index.query(&path, &params(""), &[]).unwrap();
+
+        let appended = format!(
+            "{}{}\n{}\n{}\n",
+            CONTENTS,
+            r#"{"id":"bd-1.2","title":"Inferred child","status":"open"}"#,
+            r#"{"id":"bd-7","title":"Explicit child","status":"open","dependencies":[{"depends_on_id":"bd-1","type":"parent-child"}]}"#,
+            r#"{"id":"bd-8","title":"Top level","status":"open","labels":["ui"]}"#,
+        );
+        std::fs::write(&path, &appended).unwrap();
+        // Check the fast path applies (rolled back; the query below does it for real)
+        {
+            let mut state = index.state.lock().unwrap();
+            let tx = state.conn.transaction().unwrap();
+            let tail = &appended[CONTENTS.len()..];
+            assert!(BeadIndex::append(&tx, &path.to_string_lossy(), tail).unwrap());
+        }
+
+        let incremental = serialized(&index, &path);
+        let fresh = BeadIndex::open().unwrap();
+        assert_eq!(incremental, serialized(&fresh, &path));
+
+        let page = index.query(&path, &params("labels=ui"), &[]).unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(
+            index.query(&path, &params(""), &[]).unwrap().beads[0].children,
+            Some(vec!["bd-7".into(), "bd-1.1".into(), "bd-1.2".into()])
+        );
+
+        // New children of indexed beads are fine; a duplicate ID needs a rebuild
+        let mut state = index.state.lock().unwrap();
+        let tx = state.conn.transaction().unwrap();
+        let tail = r#"{"id":"bd-1.1.1","title":"x","status":"open"}"#;
+        assert!(BeadIndex::append(&tx, &path.to_string_lossy(), tail).unwrap());
+        let tail = r#"{"id":"bd-1","title":"dup","status":"open"}"#;
+        assert!(!BeadIndex::append(&tx, &path.to_string_lossy(), tail).unwrap());
+    }
+}
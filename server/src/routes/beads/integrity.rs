@@ -0,0 +1,262 @@
+//! Integrity report for `issues.jsonl`.
+//!
+//! Finds problems that survive parsing but confuse the board: beads sharing
+//! an ID, comments whose `issue_id` matches no bead, and children whose
+//! parent does not exist. Every problem carries its location (1-based line
+//! number in the file plus a JSON pointer into that line's record) and a
+//! `fix` the UI can offer:
+//!
+//! - `dedupe`: collapse the copies with `POST /api/beads/repair`
+//! - `reassign_comment`: set the comment's `issue_id` to the bead it is on
+//! - `remove_parent`: drop the dangling parent reference
+
+use axum::{
+    extract::Query,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+
+use super::{issues_etag, resolve_issues_path, IssueRecord};
+use crate::error::AppError;
+use crate::routes::validate_path_security;
+
+/// Query parameters for the integrity endpoint.
+#[derive(Debug, Deserialize)]
+pub struct IntegrityParams {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+}
+
+/// An ID used by more than one record.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DuplicateId {
+    pub id: String,
+    /// Lines holding a record with this ID
+    pub lines: Vec<usize>,
+    pub fix: &'static str,
+}
+
+/// A comment whose `issue_id` matches no bead.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct OrphanComment {
+    pub line: usize,
+    /// JSON pointer to the comment within the record
+    pub pointer: String,
+    /// The bead the comment is stored on
+    pub bead_id: String,
+    pub comment_id: Option<i64>,
+    pub issue_id: String,
+    pub fix: &'static str,
+}
+
+/// A reference from a child to a parent that does not exist.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct MissingParent {
+    pub line: usize,
+    /// JSON pointer to the reference within the record
+    pub pointer: String,
+    pub id: String,
+    pub parent_id: String,
+    pub fix: &'static str,
+}
+
+/// Problems found in an issues file.
+#[derive(Debug, Default, Serialize)]
+pub struct IntegrityReport {
+    pub duplicates: Vec<DuplicateId>,
+    pub orphan_comments: Vec<OrphanComment>,
+    pub missing_parents: Vec<MissingParent>,
+}
+
+/// Parses the records of an issues file with their 1-based line numbers.
+///
+/// Blank and unparseable lines are skipped; `POST /api/beads/repair` deals
+/// with the latter.
+fn numbered_records(contents: &str) -> Vec<(usize, IssueRecord)> {
+    contents
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let record = serde_json::from_str::<IssueRecord>(line.trim()).ok()?;
+            Some((index + 1, record))
+        })
+        .collect()
+}
+
+/// Checks an issues file for duplicate IDs, orphan comments, and missing
+/// parents.
+fn check_integrity(contents: &str) -> IntegrityReport {
+    let records = numbered_records(contents);
+    let text = |record: &IssueRecord, key: &str| {
+        record.get(key).and_then(|v| v.as_str()).map(String::from)
+    };
+
+    let mut lines_by_id: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (line, record) in &records {
+        if let Some(id) = text(record, "id") {
+            lines_by_id.entry(id).or_default().push(*line);
+        }
+    }
+    let ids: HashSet<&str> = lines_by_id.keys().map(String::as_str).collect();
+
+    let mut report = IntegrityReport::default();
+    for (line, record) in &records {
+        let Some(id) = text(record, "id") else {
+            continue;
+        };
+
+        let comments = record.get("comments").and_then(|c| c.as_array());
+        for (index, comment) in comments.into_iter().flatten().enumerate() {
+            let Some(issue_id) = comment.get("issue_id").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if !ids.contains(issue_id) {
+                report.orphan_comments.push(OrphanComment {
+                    line: *line,
+                    pointer: format!("/comments/{}/issue_id", index),
+                    bead_id: id.clone(),
+                    comment_id: comment.get("id").and_then(|v| v.as_i64()),
+                    issue_id: issue_id.to_string(),
+                    fix: "reassign_comment",
+                });
+            }
+        }
+
+        if let Some(parent_id) = text(record, "parent_id").filter(|p| !ids.contains(p.as_str())) {
+            report.missing_parents.push(MissingParent {
+                line: *line,
+                pointer: "/parent_id".to_string(),
+                id: id.clone(),
+                parent_id,
+                fix: "remove_parent",
+            });
+        }
+
+        let deps = record.get("dependencies").and_then(|d| d.as_array());
+        for (index, dep) in deps.into_iter().flatten().enumerate() {
+            if dep.get("type").and_then(|t| t.as_str()) != Some("parent-child") {
+                continue;
+            }
+            let Some(parent_id) = dep.get("depends_on_id").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if !ids.contains(parent_id) {
+                report.missing_parents.push(MissingParent {
+                    line: *line,
+                    pointer: format!("/dependencies/{}", index),
+                    id: id.clone(),
+                    parent_id: parent_id.to_string(),
+                    fix: "remove_parent",
+                });
+            }
+        }
+    }
+
+    report.duplicates = lines_by_id
+        .into_iter()
+        .filter(|(_, lines)| lines.len() > 1)
+        .map(|(id, lines)| DuplicateId {
+            id,
+            lines,
+            fix: "dedupe",
+        })
+        .collect();
+
+    report
+}
+
+/// GET /api/beads/integrity?path=/path/to/project
+///
+/// Reports duplicate bead IDs, comments with an unknown `issue_id`, and
+/// children pointing at missing parents, with their locations in the file.
+/// The response carries an `ETag` header for use as `If-Match` when fixing.
+pub async fn integrity_report(Query(params): Query<IntegrityParams>) -> Response {
+    let project_path = PathBuf::from(&params.path);
+
+    // Security: Validate path is within allowed directories
+    if let Err(e) = validate_path_security(&project_path) {
+        return AppError::Forbidden(e).into_response();
+    }
+
+    let issues_path = resolve_issues_path(&project_path);
+    let contents = match std::fs::read_to_string(&issues_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return AppError::NotFound(
+                "No .beads/issues.jsonl found at the specified path".to_string(),
+            )
+            .into_response();
+        }
+        Err(e) => {
+            return AppError::Internal(format!("Failed to read file: {}", e)).into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        [(header::ETAG, issues_etag(&contents))],
+        Json(check_integrity(&contents)),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_integrity() {
+        let report = check_integrity(concat!(
+            r#"{"id":"bd-1","title":"A","status":"open","comments":[{"id":1,"issue_id":"bd-1"},{"id":2,"issue_id":"bd-9"}]}"#,
+            "\n",
+            "\n",
+            r#"{"id":"bd-2","title":"B","status":"open","parent_id":"bd-8"}"#,
+            "\n",
+            "not json\n",
+            r#"{"id":"bd-1","title":"A again","status":"open","dependencies":[{"issue_id":"bd-1","depends_on_id":"bd-2","type":"blocks"},{"issue_id":"bd-1","depends_on_id":"bd-7","type":"parent-child"}]}"#,
+        ));
+
+        assert_eq!(
+            report.duplicates,
+            vec![DuplicateId {
+                id: "bd-1".to_string(),
+                lines: vec![1, 5],
+                fix: "dedupe",
+            }]
+        );
+        assert_eq!(
+            report.orphan_comments,
+            vec![OrphanComment {
+                line: 1,
+                pointer: "/comments/1/issue_id".to_string(),
+                bead_id: "bd-1".to_string(),
+                comment_id: Some(2),
+                issue_id: "bd-9".to_string(),
+                fix: "reassign_comment",
+            }]
+        );
+        assert_eq!(
+            report.missing_parents,
+            vec![
+                MissingParent {
+                    line: 3,
+                    pointer: "/parent_id".to_string(),
+                    id: "bd-2".to_string(),
+                    parent_id: "bd-8".to_string(),
+                    fix: "remove_parent",
+                },
+                MissingParent {
+                    line: 5,
+                    pointer: "/dependencies/1".to_string(),
+                    id: "bd-1".to_string(),
+                    parent_id: "bd-7".to_string(),
+                    fix: "remove_parent",
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! In-memory journal of board mutations applied by this server.
+//!
+//! Each mutation records the before/after state of every bead it touched
+//! (including epics updated by the status recompute), which lets
+//! `POST /api/beads/undo` reverse the most recent change. The journal lives
+//! in process memory: it is per server run, and entries expire after the
+//! undo window (`BEADS_UNDO_WINDOW_SECS`, default 300 seconds).
+
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::{record_id, IssueLine, IssueRecord};
+
+/// Maximum number of entries kept per issues file.
+const MAX_ENTRIES: usize = 50;
+
+/// Default undo window when `BEADS_UNDO_WINDOW_SECS` is not set.
+const DEFAULT_UNDO_WINDOW: Duration = Duration::from_secs(300);
+
+/// The change made to a single bead by a mutation.
+#[derive(Debug, Clone)]
+struct Change {
+    id: String,
+    /// Record before the mutation (`None` if the mutation created it).
+    before: Option<IssueRecord>,
+    /// Line index the record occupied before the mutation.
+    before_index: Option<usize>,
+    /// Record after the mutation (`None` if the mutation deleted it).
+    after: Option<IssueRecord>,
+}
+
+/// A journaled mutation.
+#[derive(Debug, Clone)]
+pub(super) struct JournalEntry {
+    /// What kind of mutation this was (e.g., "status", "comment", "bulk_update").
+    pub kind: String,
+    changes: Vec<Change>,
+    recorded_at: Instant,
+}
+
+/// Result of successfully reversing a journal entry.
+#[derive(Debug)]
+pub(super) struct UndoOutcome {
+    pub kind: String,
+    /// IDs of beads restored to their previous state.
+    pub restored: Vec<String>,
+    /// IDs of beads removed because the undone mutation created them.
+    pub removed: Vec<String>,
+}
+
+type Journal = HashMap<PathBuf, VecDeque<JournalEntry>>;
+
+fn journal() -> &'static Mutex<Journal> {
+    static JOURNAL: OnceLock<Mutex<Journal>> = OnceLock::new();
+    JOURNAL.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Returns the configured undo window.
+fn undo_window() -> Duration {
+    std::env::var("BEADS_UNDO_WINDOW_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_UNDO_WINDOW)
+}
+
+/// Indexes records by bead ID, keeping each record's line position.
+fn index_records(lines: &[IssueLine]) -> HashMap<&str, (usize, &IssueRecord)> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| match line {
+            IssueLine::Record(record) => record_id(record).map(|id| (id, (i, record))),
+            IssueLine::Malformed(_) => None,
+        })
+        .collect()
+}
+
+/// Computes the per-bead changes between two versions of the issues file.
+fn diff(before: &[IssueLine], after: &[IssueLine]) -> Vec<Change> {
+    let before_map = index_records(before);
+    let after_map = index_records(after);
+
+    let mut changes: Vec<Change> = before_map
+        .iter()
+        .filter(|(id, (_, record))| after_map.get(*id).map(|(_, r)| *r) != Some(*record))
+        .map(|(id, (index, record))| Change {
+            id: id.to_string(),
+            before: Some((*record).clone()),
+            before_index: Some(*index),
+            after: after_map.get(id).map(|(_, r)| (*r).clone()),
+        })
+        .collect();
+
+    changes.extend(
+        after_map
+            .iter()
+            .filter(|(id, _)| !before_map.contains_key(*id))
+            .map(|(id, (_, record))| Change {
+                id: id.to_string(),
+                before: None,
+                before_index: None,
+                after: Some((*record).clone()),
+            }),
+    );
+
+    changes.sort_by(|a, b| a.id.cmp(&b.id));
+    changes
+}
+
+/// Records a mutation that turned `before` into `after`.
+///
+/// Mutations that changed nothing are not recorded.
+pub(super) fn record(issues_path: &Path, kind: &str, before: &[IssueLine], after: &[IssueLine]) {
+    let changes = diff(before, after);
+    if changes.is_empty() {
+        return;
+    }
+
+    let mut journal = journal().lock().unwrap_or_else(|e| e.into_inner());
+    let entries = journal.entry(issues_path.to_path_buf()).or_default();
+    entries.push_back(JournalEntry {
+        kind: kind.to_string(),
+        changes,
+        recorded_at: Instant::now(),
+    });
+    while entries.len() > MAX_ENTRIES {
+        entries.pop_front();
+    }
+}
+
+/// Removes and returns the most recent entry still within the undo window.
+///
+/// Expired entries are discarded.
+pub(super) fn pop_latest(issues_path: &Path) -> Option<JournalEntry> {
+    let window = undo_window();
+    let mut journal = journal().lock().unwrap_or_else(|e| e.into_inner());
+    let entries = journal.get_mut(issues_path)?;
+    entries.retain(|entry| entry.recorded_at.elapsed() <= window);
+    entries.pop_back()
+}
+
+/// Reverses a journal entry against the current issues file contents.
+///
+/// Fails without modifying `lines` if any affected bead has changed since
+/// the mutation was applied (e.g., edited by the `bd` CLI).
+pub(super) fn apply_undo(
+    entry: &JournalEntry,
+    lines: &mut Vec<IssueLine>,
+) -> Result<UndoOutcome, String> {
+    {
+        let current = index_records(lines);
+        for change in &entry.changes {
+            if current.get(change.id.as_str()).map(|(_, r)| *r) != change.after.as_ref() {
+                return Err(format!(
+                    "Bead '{}' has changed since the last {} and cannot be undone",
+                    change.id, entry.kind
+                ));
+            }
+        }
+    }
+
+    let mut restored = Vec::new();
+    let mut removed = Vec::new();
+
+    // Replace and remove first; re-insert deleted records afterwards in their
+    // original order so positions are restored as closely as possible
+    for change in &entry.changes {
+        let position = lines.iter().position(|line| match line {
+            IssueLine::Record(record) => record_id(record) == Some(change.id.as_str()),
+            IssueLine::Malformed(_) => false,
+        });
+        match (position, &change.before) {
+            (Some(i), Some(before)) => {
+                lines[i] = IssueLine::Record(before.clone());
+                restored.push(change.id.clone());
+            }
+            (Some(i), None) => {
+                lines.remove(i);
+                removed.push(change.id.clone());
+            }
+            (None, _) => {}
+        }
+    }
+
+    let mut deleted: Vec<&Change> = entry
+        .changes
+        .iter()
+        .filter(|change| change.after.is_none())
+        .collect();
+    deleted.sort_by_key(|change| change.before_index);
+    for change in deleted {
+        if let Some(ref before) = change.before {
+            let index = change.before_index.unwrap_or(lines.len()).min(lines.len());
+            lines.insert(index, IssueLine::Record(before.clone()));
+            restored.push(change.id.clone());
+        }
+    }
+
+    Ok(UndoOutcome {
+        kind: entry.kind.clone(),
+        restored,
+        removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(json: &str) -> IssueLine {
+        IssueLine::Record(serde_json::from_str(json).unwrap())
+    }
+
+    fn ids(lines: &[IssueLine]) -> Vec<&str> {
+        index_records(lines).into_keys().collect()
+    }
+
+    #[test]
+    fn test_diff_detects_updates_creates_and_deletes() {
+        let before = vec![
+            line(r#"{"id":"bd-1","status":"open"}"#),
+            line(r#"{"id":"bd-2","status":"open"}"#),
+            line(r#"{"id":"bd-3","status":"open"}"#),
+        ];
+        let after = vec![
+            line(r#"{"id":"bd-1","status":"closed"}"#),
+            line(r#"{"id":"bd-2","status":"open"}"#),
+            line(r#"{"id":"bd-4","status":"open"}"#),
+        ];
+
+        let changes = diff(&before, &after);
+        let changed: Vec<&str> = changes.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(changed, vec!["bd-1", "bd-3", "bd-4"]);
+        assert!(changes[1].after.is_none());
+        assert!(changes[2].before.is_none());
+    }
+
+    #[test]
+    fn test_apply_undo_restores_previous_state() {
+        let before = vec![
+            line(r#"{"id":"bd-1","status":"open"}"#),
+            line(r#"{"id":"bd-2","status":"open"}"#),
+        ];
+        let mut lines = vec![
+            line(r#"{"id":"bd-1","status":"closed"}"#),
+            line(r#"{"id":"bd-3","status":"open"}"#),
+        ];
+        let entry = JournalEntry {
+            kind: "bulk_update".to_string(),
+            changes: diff(&before, &lines),
+            recorded_at: Instant::now(),
+        };
+
+        let outcome = apply_undo(&entry, &mut lines).unwrap();
+        assert_eq!(outcome.removed, vec!["bd-3".to_string()]);
+        assert_eq!(outcome.restored.len(), 2);
+
+        let mut restored_ids = ids(&lines);
+        restored_ids.sort();
+        assert_eq!(restored_ids, vec!["bd-1", "bd-2"]);
+        match &lines[0] {
+            IssueLine::Record(record) => assert_eq!(record["status"], "open"),
+            IssueLine::Malformed(_) => panic!("expected record"),
+        }
+    }
+
+    #[test]
+    fn test_apply_undo_rejects_external_changes() {
+        let before = vec![line(r#"{"id":"bd-1","status":"open"}"#)];
+        let after = vec![line(r#"{"id":"bd-1","status":"closed"}"#)];
+        let entry = JournalEntry {
+            kind: "status".to_string(),
+            changes: diff(&before, &after),
+            recorded_at: Instant::now(),
+        };
+
+        let mut lines = vec![line(r#"{"id":"bd-1","status":"inreview"}"#)];
+        assert!(apply_undo(&entry, &mut lines).is_err());
+    }
+
+    #[test]
+    fn test_record_and_pop_latest() {
+        let path = Path::new("/tmp/journal-test/issues.jsonl");
+        let before = vec![line(r#"{"id":"bd-1","status":"open"}"#)];
+        let after = vec![line(r#"{"id":"bd-1","status":"closed"}"#)];
+
+        record(path, "status", &before, &before);
+        assert!(pop_latest(path).is_none());
+
+        record(path, "status", &before, &after);
+        record(path, "comment", &after, &before);
+        assert_eq!(pop_latest(path).unwrap().kind, "comment");
+        assert_eq!(pop_latest(path).unwrap().kind, "status");
+        assert!(pop_latest(path).is_none());
+    }
+}
@@ -0,0 +1,181 @@
+//! Bead assignment and the assignee directory.
+//!
+//! Owners are not registered anywhere; the directory is aggregated from the
+//! `owner` field of the beads on the board.
+
+use axum::{
+    extract::{Path as AxumPath, Query},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::{
+    check_if_match, find_record_index, journal, mutation_issues_path, parse_beads,
+    read_issue_lines, record_at_mut, record_to_bead, resolve_issues_path, set_optional_text,
+    write_issue_lines, Bead,
+};
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+use crate::routes::validate_path_security;
+
+/// Request body for the assign endpoint.
+#[derive(Debug, Deserialize)]
+pub struct AssignRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// The new owner; `null` or an empty string unassigns the bead
+    #[serde(default)]
+    pub owner: Option<String>,
+}
+
+/// Query parameters for the owners endpoint.
+#[derive(Debug, Deserialize)]
+pub struct OwnersParams {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+}
+
+/// An owner in the assignee directory.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct OwnerSummary {
+    pub owner: String,
+    /// Beads owned that are not closed
+    pub open_count: usize,
+    /// All beads owned
+    pub total_count: usize,
+}
+
+/// Aggregates distinct owners with their bead counts, sorted by name.
+fn summarize_owners(beads: &[Bead]) -> Vec<OwnerSummary> {
+    let mut owners: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for bead in beads {
+        let Some(owner) = bead.owner.as_deref().map(str::trim).filter(|o| !o.is_empty()) else {
+            continue;
+        };
+        let counts = owners.entry(owner).or_default();
+        if bead.status != "closed" {
+            counts.0 += 1;
+        }
+        counts.1 += 1;
+    }
+
+    owners
+        .into_iter()
+        .map(|(owner, (open_count, total_count))| OwnerSummary {
+            owner: owner.to_string(),
+            open_count,
+            total_count,
+        })
+        .collect()
+}
+
+/// POST /api/beads/:id/assign
+///
+/// Sets or clears the owner of a bead. Honors `If-Match`.
+pub async fn assign_bead(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<AssignRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let index = match find_record_index(&lines, &bead_id) {
+        Some(i) => i,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                bead_id
+            )));
+        }
+    };
+
+    let record = record_at_mut(&mut lines, index);
+    set_optional_text(
+        record,
+        "owner",
+        payload.owner.as_deref().unwrap_or("").trim(),
+    );
+    record.insert(
+        "updated_at".to_string(),
+        serde_json::json!(Utc::now().to_rfc3339()),
+    );
+    let bead = record_to_bead(record);
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "assign", &before, &lines);
+
+    match bead {
+        Ok(bead) => Ok(Json(serde_json::json!({ "success": true, "bead": bead }))),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// GET /api/beads/owners?path=/path/to/project
+///
+/// Lists the distinct owners on the board with their open and total bead
+/// counts, for assignee pickers.
+pub async fn list_owners(
+    Query(params): Query<OwnersParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let project_path = PathBuf::from(&params.path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    let issues_path = resolve_issues_path(&project_path);
+    let contents = match std::fs::read_to_string(&issues_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::NotFound(
+                "No .beads/issues.jsonl found at the specified path".to_string(),
+            ));
+        }
+        Err(e) => {
+            return Err(AppError::Internal(format!("Failed to read file: {}", e)));
+        }
+    };
+
+    let owners = summarize_owners(&parse_beads(&contents));
+    Ok(Json(serde_json::json!({ "owners": owners })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_owners() {
+        let beads = parse_beads(concat!(
+            r#"{"id":"bd-1","title":"A","status":"open","owner":"bob"}"#,
+            "\n",
+            r#"{"id":"bd-2","title":"B","status":"closed","owner":"bob"}"#,
+            "\n",
+            r#"{"id":"bd-3","title":"C","status":"in_progress","owner":"ann"}"#,
+            "\n",
+            r#"{"id":"bd-4","title":"D","status":"open","owner":" "}"#,
+            "\n",
+            r#"{"id":"bd-5","title":"E","status":"open"}"#,
+        ));
+
+        assert_eq!(
+            summarize_owners(&beads),
+            vec![
+                OwnerSummary { owner: "ann".to_string(), open_count: 1, total_count: 1 },
+                OwnerSummary { owner: "bob".to_string(), open_count: 1, total_count: 2 },
+            ]
+        );
+    }
+}
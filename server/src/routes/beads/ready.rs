@@ -0,0 +1,148 @@
+//! Ready queue: open beads with no open blockers, mirroring `bd ready`.
+//!
+//! A bead is blocked while any bead it has a `blocks` dependency on is not
+//! closed. Blocking is inherited down the hierarchy: the children of a
+//! blocked bead are blocked too.
+
+use axum::{extract::Query, response::IntoResponse, Json};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use super::{link_bead_relationships, parse_beads, resolve_issues_path, Bead};
+use crate::error::AppError;
+use crate::routes::validate_path_security;
+
+/// Query parameters for the ready endpoint.
+#[derive(Debug, Deserialize)]
+pub struct ReadyParams {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// Only include beads owned by this owner
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// Maximum number of beads to return
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Returns the IDs of beads that are blocked, directly or through a parent.
+///
+/// Expects relationships to be linked (see [`link_bead_relationships`]).
+fn blocked_ids(beads: &[Bead]) -> HashSet<String> {
+    let status: HashMap<&str, &str> = beads
+        .iter()
+        .map(|b| (b.id.as_str(), b.status.as_str()))
+        .collect();
+
+    let mut blocked: HashSet<String> = beads
+        .iter()
+        .filter(|bead| {
+            bead.dependencies.iter().flatten().any(|dep| {
+                dep.dep_type == "blocks"
+                    && status
+                        .get(dep.depends_on_id.as_str())
+                        .is_some_and(|s| *s != "closed")
+            })
+        })
+        .map(|bead| bead.id.clone())
+        .collect();
+
+    // Propagate down the hierarchy until nothing changes
+    loop {
+        let inherited: Vec<String> = beads
+            .iter()
+            .filter(|bead| !blocked.contains(&bead.id))
+            .filter(|bead| bead.parent_id.as_ref().is_some_and(|p| blocked.contains(p)))
+            .map(|bead| bead.id.clone())
+            .collect();
+        if inherited.is_empty() {
+            return blocked;
+        }
+        blocked.extend(inherited);
+    }
+}
+
+/// Selects the ready beads, highest priority first, then oldest first.
+fn ready_beads(beads: Vec<Bead>, owner: Option<&str>) -> Vec<Bead> {
+    let blocked = blocked_ids(&beads);
+    let mut ready: Vec<Bead> = beads
+        .into_iter()
+        .filter(|bead| bead.status == "open" && !blocked.contains(&bead.id))
+        .filter(|bead| owner.is_none() || bead.owner.as_deref() == owner)
+        .collect();
+
+    ready.sort_by(|a, b| {
+        let priority = |bead: &Bead| bead.priority.unwrap_or(i32::MAX);
+        priority(a)
+            .cmp(&priority(b))
+            .then_with(|| a.created_at.cmp(&b.created_at))
+    });
+    ready
+}
+
+/// GET /api/beads/ready?path=/path/to/project&owner=ann&limit=10
+///
+/// Lists open beads whose blocking dependencies are all closed (the same
+/// "ready work" as `bd ready`), highest priority first.
+pub async fn ready(Query(params): Query<ReadyParams>) -> Result<impl IntoResponse, AppError> {
+    let project_path = PathBuf::from(&params.path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    let issues_path = resolve_issues_path(&project_path);
+    let contents = match std::fs::read_to_string(&issues_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::NotFound(
+                "No .beads/issues.jsonl found at the specified path".to_string(),
+            ));
+        }
+        Err(e) => {
+            return Err(AppError::Internal(format!("Failed to read file: {}", e)));
+        }
+    };
+
+    let mut beads = parse_beads(&contents);
+    link_bead_relationships(&mut beads);
+
+    let mut ready = ready_beads(beads, params.owner.as_deref());
+    let total = ready.len();
+    if let Some(limit) = params.limit {
+        ready.truncate(limit);
+    }
+
+    Ok(Json(serde_json::json!({ "beads": ready, "total": total })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ready_ids(contents: &str, owner: Option<&str>) -> Vec<String> {
+        let mut beads = parse_beads(contents);
+        link_bead_relationships(&mut beads);
+        ready_beads(beads, owner).into_iter().map(|b| b.id).collect()
+    }
+
+    #[test]
+    fn test_ready_beads_excludes_blocked_work() {
+        let contents = concat!(
+            r#"{"id":"bd-1","title":"Blocker","status":"in_progress","priority":1}"#,
+            "\n",
+            r#"{"id":"bd-2","title":"Blocked","status":"open","dependencies":[{"depends_on_id":"bd-1","type":"blocks"}]}"#,
+            "\n",
+            r#"{"id":"bd-2.1","title":"Child of blocked","status":"open"}"#,
+            "\n",
+            r#"{"id":"bd-3","title":"Unblocked","status":"open","priority":3,"dependencies":[{"depends_on_id":"bd-4","type":"blocks"}]}"#,
+            "\n",
+            r#"{"id":"bd-4","title":"Done","status":"closed"}"#,
+            "\n",
+            r#"{"id":"bd-5","title":"Urgent","status":"open","priority":0,"owner":"ann","dependencies":[{"depends_on_id":"bd-1","type":"related"}]}"#,
+        );
+
+        assert_eq!(ready_ids(contents, None), vec!["bd-5", "bd-3"]);
+        assert_eq!(ready_ids(contents, Some("ann")), vec!["bd-5"]);
+    }
+}
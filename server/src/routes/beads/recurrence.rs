@@ -0,0 +1,494 @@
+//! Recurring beads.
+//!
+//! A bead with a `recurrence` rule is a chore that comes back (e.g., weekly
+//! dependency updates). Rules are a subset of iCalendar RRULE: `FREQ`
+//! (`DAILY`, `WEEKLY`, `MONTHLY`, `YEARLY`), `INTERVAL`, `BYDAY` (weekly
+//! rules only), `COUNT`, and `UNTIL`.
+//!
+//! Once a recurring bead is closed, the scheduler appends its next occurrence
+//! as a new open bead. The new bead keeps the rule, records the bead it
+//! follows in `recurrence_of`, its number in the series in `occurrence`, and
+//! its date in `due_date`. The closed bead is stamped with `recurrence_next`
+//! so it is only materialized once, even if the new bead is later deleted.
+
+use chrono::{Datelike, Days, Months, NaiveDate, Utc, Weekday};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::burndown::parse_timestamp;
+use super::{
+    find_record_index, generate_bead_id, issue_prefix, journal, read_issue_lines, record_at_mut,
+    record_id, records, records_ids, resolve_issues_path, write_issue_lines, IssueLine,
+    IssueRecord,
+};
+use crate::db::Database;
+use crate::lock::lock_exclusive_blocking;
+use crate::shutdown;
+
+/// How often the scheduler looks for closed recurring beads.
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Upper bound on the dates stepped through when looking for the next
+/// occurrence, so a bead closed long after its due date cannot spin forever.
+const MAX_STEPS: usize = 100_000;
+
+/// Fields copied from a closed occurrence to the next one.
+const CARRIED_FIELDS: &[&str] = &[
+    "title",
+    "description",
+    "priority",
+    "issue_type",
+    "owner",
+    "design",
+    "design_doc",
+    "labels",
+    "custom",
+    "recurrence",
+];
+
+/// How often a rule repeats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+/// A parsed recurrence rule.
+#[derive(Debug, PartialEq)]
+pub(super) struct RecurrenceRule {
+    frequency: Frequency,
+    /// Repeat every `interval` days/weeks/months/years
+    interval: u32,
+    /// Weekdays a weekly rule falls on; empty means the anchor's weekday
+    by_day: Vec<Weekday>,
+    /// Total number of occurrences in the series
+    count: Option<u32>,
+    /// Last date an occurrence may fall on
+    until: Option<NaiveDate>,
+}
+
+impl RecurrenceRule {
+    /// Parses a rule such as `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH`.
+    ///
+    /// An optional `RRULE:` prefix is accepted.
+    pub(super) fn parse(rule: &str) -> Result<Self, String> {
+        let rule = rule.trim();
+        let rule = rule
+            .get(..6)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("RRULE:"))
+            .map_or(rule, |_| &rule[6..]);
+
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut by_day = Vec::new();
+        let mut count = None;
+        let mut until = None;
+
+        for part in rule.split(';').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid recurrence part '{}': expected KEY=VALUE", part))?;
+            let value = value.trim();
+            match key.trim().to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(format!("Unsupported recurrence frequency '{}'", value)),
+                    });
+                }
+                "INTERVAL" => interval = parse_positive(value, "INTERVAL")?,
+                "COUNT" => count = Some(parse_positive(value, "COUNT")?),
+                "UNTIL" => until = Some(parse_until(value)?),
+                "BYDAY" => {
+                    by_day = value
+                        .split(',')
+                        .map(|day| parse_weekday(day.trim()))
+                        .collect::<Result<_, _>>()?;
+                }
+                other => return Err(format!("Unsupported recurrence part '{}'", other)),
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| "Recurrence rule must include FREQ".to_string())?;
+        if !by_day.is_empty() && frequency != Frequency::Weekly {
+            return Err("BYDAY is only supported with FREQ=WEEKLY".to_string());
+        }
+
+        Ok(RecurrenceRule {
+            frequency,
+            interval,
+            by_day,
+            count,
+            until,
+        })
+    }
+
+    /// Returns the `k`th date after `anchor` for rules without `BYDAY`.
+    ///
+    /// Monthly and yearly dates are clamped to the end of shorter months.
+    fn nth(&self, anchor: NaiveDate, k: u32) -> Option<NaiveDate> {
+        let steps = k.checked_mul(self.interval)?;
+        match self.frequency {
+            Frequency::Daily => anchor.checked_add_days(Days::new(steps.into())),
+            Frequency::Weekly => anchor.checked_add_days(Days::new(u64::from(steps) * 7)),
+            Frequency::Monthly => anchor.checked_add_months(Months::new(steps)),
+            Frequency::Yearly => anchor.checked_add_months(Months::new(steps.checked_mul(12)?)),
+        }
+    }
+
+    /// Iterates the dates of the series anchored at `anchor`, excluding the
+    /// anchor itself.
+    fn occurrences(&self, anchor: NaiveDate) -> Box<dyn Iterator<Item = NaiveDate> + '_> {
+        if self.by_day.is_empty() {
+            return Box::new((1..).map_while(move |k| self.nth(anchor, k)));
+        }
+
+        let anchor_week = week_start(anchor);
+        Box::new(anchor.iter_days().skip(1).filter(move |date| {
+            let weeks = (week_start(*date) - anchor_week).num_days() / 7;
+            weeks % i64::from(self.interval) == 0 && self.by_day.contains(&date.weekday())
+        }))
+    }
+
+    /// Returns the first date of the series anchored at `anchor` that falls
+    /// after `after`, or `None` once the series has ended (`UNTIL`).
+    pub(super) fn next_after(&self, anchor: NaiveDate, after: NaiveDate) -> Option<NaiveDate> {
+        self.occurrences(anchor)
+            .take(MAX_STEPS)
+            .take_while(|date| self.until.is_none_or(|until| *date <= until))
+            .find(|date| *date > after)
+    }
+}
+
+/// Validates a recurrence rule from a request; an empty rule clears it.
+pub(super) fn validate_recurrence(rule: &str) -> Result<(), String> {
+    if rule.trim().is_empty() {
+        return Ok(());
+    }
+    RecurrenceRule::parse(rule).map(|_| ())
+}
+
+/// Parses a positive integer rule value.
+fn parse_positive(value: &str, key: &str) -> Result<u32, String> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or_else(|| format!("Invalid {} '{}': must be a positive integer", key, value))
+}
+
+/// Parses an `UNTIL` value (`YYYYMMDD`, `YYYYMMDDTHHMMSSZ`, or `YYYY-MM-DD`).
+fn parse_until(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .or_else(|| {
+            value
+                .get(..8)
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        })
+        .ok_or_else(|| format!("Invalid UNTIL '{}': expected a date like 20261231", value))
+}
+
+/// Parses a two-letter `BYDAY` weekday (`MO` through `SU`).
+fn parse_weekday(day: &str) -> Result<Weekday, String> {
+    match day.to_ascii_uppercase().as_str() {
+        "MO" => Ok(Weekday::Mon),
+        "TU" => Ok(Weekday::Tue),
+        "WE" => Ok(Weekday::Wed),
+        "TH" => Ok(Weekday::Thu),
+        "FR" => Ok(Weekday::Fri),
+        "SA" => Ok(Weekday::Sat),
+        "SU" => Ok(Weekday::Sun),
+        _ => Err(format!("Invalid BYDAY weekday '{}'", day)),
+    }
+}
+
+/// Returns the Monday of the week containing `date`.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday().into())
+}
+
+/// Builds the next occurrence of a closed recurring bead, if one is due.
+///
+/// The series is anchored at the bead's `due_date` (or the day it was closed)
+/// and the next occurrence is the first date after both the anchor and the
+/// closing day, so occurrences missed by closing late are skipped.
+fn next_occurrence(record: &IssueRecord, today: NaiveDate) -> Option<IssueRecord> {
+    let text = |key: &str| record.get(key).and_then(|v| v.as_str());
+    if text("status") != Some("closed") || record.contains_key("recurrence_next") {
+        return None;
+    }
+    let rule = RecurrenceRule::parse(text("recurrence")?).ok()?;
+
+    let occurrence = record
+        .get("occurrence")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(1);
+    if rule
+        .count
+        .is_some_and(|count| occurrence >= u64::from(count))
+    {
+        return None;
+    }
+
+    let closed_on = parse_timestamp(text("closed_at"))
+        .map(|closed_at| closed_at.date_naive())
+        .unwrap_or(today);
+    let anchor = text("due_date")
+        .and_then(|due| NaiveDate::parse_from_str(due, "%Y-%m-%d").ok())
+        .unwrap_or(closed_on);
+    let due_date = rule.next_after(anchor, closed_on)?;
+
+    let mut next = IssueRecord::new();
+    for key in CARRIED_FIELDS {
+        if let Some(value) = record.get(*key) {
+            next.insert(key.to_string(), value.clone());
+        }
+    }
+    next.insert("status".to_string(), serde_json::json!("open"));
+    next.insert(
+        "recurrence_of".to_string(),
+        serde_json::json!(record_id(record)?),
+    );
+    next.insert("occurrence".to_string(), serde_json::json!(occurrence + 1));
+    next.insert(
+        "due_date".to_string(),
+        serde_json::json!(due_date.format("%Y-%m-%d").to_string()),
+    );
+    Some(next)
+}
+
+/// Collects the next occurrences due for closed recurring beads, keyed by
+/// the ID of the bead each one follows.
+fn pending_occurrences(lines: &[IssueLine], today: NaiveDate) -> Vec<(String, IssueRecord)> {
+    records(lines)
+        .filter_map(|record| {
+            let next = next_occurrence(record, today)?;
+            Some((record_id(record)?.to_string(), next))
+        })
+        .collect()
+}
+
+/// Appends the next occurrence of every closed recurring bead in a project.
+///
+/// Returns the IDs of the beads that were created.
+pub(super) fn materialize(project_path: &Path, today: NaiveDate) -> Result<Vec<String>, String> {
+    let issues_path = resolve_issues_path(project_path);
+    if !issues_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    // Check without the lock first; most passes find nothing to do
+    if pending_occurrences(&read_issue_lines(&issues_path)?, today).is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let _lock = lock_exclusive_blocking(&issues_path).map_err(|e| e.to_string())?;
+    let mut lines = read_issue_lines(&issues_path)?;
+    let before = lines.clone();
+
+    let mut existing_ids = records_ids(&lines);
+    let prefix = {
+        let ids: Vec<&str> = existing_ids.iter().map(String::as_str).collect();
+        issue_prefix(project_path, &ids)
+    };
+
+    let now = Utc::now().to_rfc3339();
+    let mut created = Vec::new();
+    for (previous_id, mut record) in pending_occurrences(&lines, today) {
+        let id = {
+            let ids: Vec<&str> = existing_ids.iter().map(String::as_str).collect();
+            generate_bead_id(&prefix, &ids)
+        };
+        record.insert("id".to_string(), serde_json::json!(id));
+        record.insert("created_at".to_string(), serde_json::json!(now));
+        record.insert("updated_at".to_string(), serde_json::json!(now));
+
+        if let Some(index) = find_record_index(&lines, &previous_id) {
+            let previous = record_at_mut(&mut lines, index);
+            previous.insert("recurrence_next".to_string(), serde_json::json!(id));
+            previous.insert("updated_at".to_string(), serde_json::json!(now));
+        }
+
+        lines.push(IssueLine::Record(record));
+        existing_ids.push(id.clone());
+        created.push(id);
+    }
+
+    if !created.is_empty() {
+        write_issue_lines(&issues_path, &lines)?;
+        journal::record(&issues_path, "recur", &before, &lines);
+    }
+
+    Ok(created)
+}
+
+/// Periodically materializes the next occurrence of closed recurring beads
+/// in every registered project, until the server shuts down.
+pub async fn run_recurrence_scheduler(db: Arc<Database>) {
+    let mut ticker = tokio::time::interval(SCHEDULER_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown::signalled() => break,
+        }
+
+        let projects = match db.get_projects() {
+            Ok(projects) => projects,
+            Err(e) => {
+                warn!("Recurrence scheduler failed to list projects: {}", e);
+                continue;
+            }
+        };
+
+        let pass = tokio::task::spawn_blocking(move || {
+            let today = Utc::now().date_naive();
+            for project in projects {
+                match materialize(Path::new(&project.path), today) {
+                    Ok(created) if !created.is_empty() => {
+                        info!("Created recurring beads in {}: {:?}", project.path, created);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        warn!(
+                            "Failed to create recurring beads in {}: {}",
+                            project.path, e
+                        );
+                    }
+                }
+            }
+        });
+        if let Err(e) = pass.await {
+            warn!("Recurrence scheduler pass panicked: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_rule() {
+        let rule =
+            RecurrenceRule::parse("RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,th;COUNT=5").unwrap();
+        assert_eq!(
+            rule,
+            RecurrenceRule {
+                frequency: Frequency::Weekly,
+                interval: 2,
+                by_day: vec![Weekday::Mon, Weekday::Thu],
+                count: Some(5),
+                until: None,
+            }
+        );
+        assert_eq!(
+            RecurrenceRule::parse("FREQ=MONTHLY;UNTIL=20261231T000000Z")
+                .unwrap()
+                .until,
+            Some(date("2026-12-31"))
+        );
+
+        assert!(RecurrenceRule::parse("INTERVAL=2").is_err());
+        assert!(RecurrenceRule::parse("FREQ=HOURLY").is_err());
+        assert!(RecurrenceRule::parse("FREQ=DAILY;INTERVAL=0").is_err());
+        assert!(RecurrenceRule::parse("FREQ=DAILY;BYDAY=MO").is_err());
+        assert!(RecurrenceRule::parse("FREQ=DAILY;BYMONTH=1").is_err());
+        assert!(validate_recurrence("").is_ok());
+    }
+
+    #[test]
+    fn test_next_after() {
+        // 2026-01-05 is a Monday
+        let weekly = RecurrenceRule::parse("FREQ=WEEKLY").unwrap();
+        assert_eq!(
+            weekly.next_after(date("2026-01-05"), date("2026-01-05")),
+            Some(date("2026-01-12"))
+        );
+        // Closing late skips missed occurrences
+        assert_eq!(
+            weekly.next_after(date("2026-01-05"), date("2026-01-20")),
+            Some(date("2026-01-26"))
+        );
+
+        let by_day = RecurrenceRule::parse("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH").unwrap();
+        assert_eq!(
+            by_day.next_after(date("2026-01-05"), date("2026-01-05")),
+            Some(date("2026-01-08"))
+        );
+        assert_eq!(
+            by_day.next_after(date("2026-01-05"), date("2026-01-08")),
+            Some(date("2026-01-19"))
+        );
+
+        let monthly = RecurrenceRule::parse("FREQ=MONTHLY").unwrap();
+        assert_eq!(
+            monthly.next_after(date("2026-01-31"), date("2026-01-31")),
+            Some(date("2026-02-28"))
+        );
+
+        let until = RecurrenceRule::parse("FREQ=DAILY;UNTIL=2026-01-06").unwrap();
+        assert_eq!(
+            until.next_after(date("2026-01-05"), date("2026-01-05")),
+            Some(date("2026-01-06"))
+        );
+        assert_eq!(
+            until.next_after(date("2026-01-06"), date("2026-01-06")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_materialize() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".beads")).unwrap();
+        let issues_path = dir.path().join(".beads").join("issues.jsonl");
+        std::fs::write(
+            &issues_path,
+            concat!(
+                r#"{"id":"bd-1","title":"Update deps","status":"closed","priority":2,"labels":["chore"],"recurrence":"FREQ=WEEKLY","due_date":"2026-01-05","closed_at":"2026-01-06T10:00:00Z"}"#,
+                "\n",
+                r#"{"id":"bd-2","title":"Last one","status":"closed","recurrence":"FREQ=DAILY;COUNT=2","occurrence":2,"closed_at":"2026-01-06T10:00:00Z"}"#,
+                "\n",
+                r#"{"id":"bd-3","title":"Still open","status":"open","recurrence":"FREQ=DAILY"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+
+        let today = date("2026-01-07");
+        let created = materialize(dir.path(), today).unwrap();
+        assert_eq!(created.len(), 1);
+
+        let lines = read_issue_lines(&issues_path).unwrap();
+        let next = &lines[find_record_index(&lines, &created[0]).unwrap()];
+        let IssueLine::Record(next) = next else {
+            panic!("expected a record");
+        };
+        assert_eq!(next["title"], "Update deps");
+        assert_eq!(next["status"], "open");
+        assert_eq!(next["labels"], serde_json::json!(["chore"]));
+        assert_eq!(next["recurrence_of"], "bd-1");
+        assert_eq!(next["occurrence"], 2);
+        assert_eq!(next["due_date"], "2026-01-12");
+
+        let IssueLine::Record(previous) = &lines[0] else {
+            panic!("expected a record");
+        };
+        assert_eq!(previous["recurrence_next"], serde_json::json!(created[0]));
+
+        // A closed occurrence is only materialized once
+        assert!(materialize(dir.path(), today).unwrap().is_empty());
+    }
+}
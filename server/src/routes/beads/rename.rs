@@ -0,0 +1,385 @@
+//! Renaming beads.
+//!
+//! A bead's ID is referenced from many places: dependency entries on other
+//! beads, the dotted IDs of its children, the `issue_id` of its comments, and
+//! the `.designs/{id}.md` design doc and attachments directory named after
+//! it. A rename rewrites all of them together so the board never sees a
+//! half-renamed bead. Renamed beads keep their old IDs in `previous_ids`, so
+//! worktree branches created under an old ID (`bd-{id}`) can still be mapped
+//! back to them.
+
+use axum::{extract::Path as AxumPath, http::HeaderMap, response::IntoResponse, Json};
+use chrono::Utc;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::{
+    check_if_match, design_key, find_record_index, journal, mutation_issues_path, read_issue_lines,
+    record_at_mut, record_id, record_to_bead, records_ids, write_issue_lines, IssueLine,
+    IssueRecord,
+};
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+use crate::routes::attachments::attachments_dir;
+
+/// Record fields holding a single bead ID.
+const ID_FIELDS: &[&str] = &["id", "parent_id", "recurrence_of", "recurrence_next"];
+
+/// Request body for the rename endpoint.
+#[derive(Debug, Deserialize)]
+pub struct RenameRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// The ID the bead should have after the rename
+    pub new_id: String,
+}
+
+/// Validates a new bead ID.
+///
+/// IDs end up in branch names and file paths, so only letters, digits, `-`,
+/// `_`, and `.` are allowed.
+fn validate_new_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && !id.starts_with('.')
+        && !id.ends_with('.')
+        && !id.contains("..")
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid bead ID '{}': use letters, digits, '-', '_', and '.'",
+            id
+        ))
+    }
+}
+
+/// Maps the bead being renamed and its dotted descendants to their new IDs.
+fn rename_map(existing_ids: &[String], old_id: &str, new_id: &str) -> BTreeMap<String, String> {
+    let prefix = format!("{}.", old_id);
+    existing_ids
+        .iter()
+        .filter_map(|id| {
+            if id == old_id {
+                Some((id.clone(), new_id.to_string()))
+            } else {
+                id.strip_prefix(&prefix)
+                    .map(|suffix| (id.clone(), format!("{}.{}", new_id, suffix)))
+            }
+        })
+        .collect()
+}
+
+/// Returns the design doc path conventionally named after a bead.
+fn design_doc_path(bead_id: &str) -> String {
+    format!(".designs/{}.md", bead_id)
+}
+
+/// Replaces a string value that is a key of `renames`.
+fn rewrite_value(
+    value: Option<&mut serde_json::Value>,
+    renames: &BTreeMap<String, String>,
+) -> bool {
+    let Some(value) = value else {
+        return false;
+    };
+    match value.as_str().and_then(|v| renames.get(v)) {
+        Some(new) => {
+            *value = serde_json::json!(new);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Rewrites `keys` on each object in the array stored under `field`.
+fn rewrite_array(
+    record: &mut IssueRecord,
+    field: &str,
+    keys: &[&str],
+    renames: &BTreeMap<String, String>,
+) -> bool {
+    let Some(entries) = record.get_mut(field).and_then(|v| v.as_array_mut()) else {
+        return false;
+    };
+    let mut changed = false;
+    for entry in entries.iter_mut() {
+        for key in keys {
+            changed |= rewrite_value(entry.get_mut(*key), renames);
+        }
+    }
+    changed
+}
+
+/// Rewrites every reference to a renamed bead in a single record.
+///
+/// Returns whether the record changed.
+fn rewrite_record(record: &mut IssueRecord, renames: &BTreeMap<String, String>) -> bool {
+    let old_id = record_id(record).map(String::from);
+    let mut changed = false;
+
+    for field in ID_FIELDS {
+        changed |= rewrite_value(record.get_mut(*field), renames);
+    }
+    changed |= rewrite_array(
+        record,
+        "dependencies",
+        &["issue_id", "depends_on_id"],
+        renames,
+    );
+    changed |= rewrite_array(record, "comments", &["issue_id"], renames);
+
+    // Design docs named after the old ID move with the bead
+    let key = design_key(record);
+    let design = record.get(key).and_then(|v| v.as_str()).and_then(|path| {
+        renames
+            .iter()
+            .find(|(old, _)| path == design_doc_path(old))
+            .map(|(_, new)| design_doc_path(new))
+    });
+    if let Some(design) = design {
+        record.insert(key.to_string(), serde_json::json!(design));
+        changed = true;
+    }
+
+    if let Some(old_id) = old_id.filter(|id| renames.contains_key(id)) {
+        let previous = record
+            .entry("previous_ids")
+            .or_insert_with(|| serde_json::json!([]));
+        if let Some(previous) = previous.as_array_mut() {
+            if !previous.iter().any(|id| id.as_str() == Some(&old_id)) {
+                previous.push(serde_json::json!(old_id));
+            }
+        }
+    }
+
+    changed
+}
+
+/// Applies the renames to every record, stamping `updated_at` on the ones
+/// that changed. Returns the (new) IDs of the changed records.
+fn apply_renames(
+    lines: &mut [IssueLine],
+    renames: &BTreeMap<String, String>,
+    now: &str,
+) -> Vec<String> {
+    let mut updated = Vec::new();
+    for line in lines.iter_mut() {
+        let IssueLine::Record(record) = line else {
+            continue;
+        };
+        if rewrite_record(record, renames) {
+            record.insert("updated_at".to_string(), serde_json::json!(now));
+            if let Some(id) = record_id(record) {
+                updated.push(id.to_string());
+            }
+        }
+    }
+    updated
+}
+
+/// Lists the files and directories named after renamed beads that need to
+/// move: design docs and attachment directories.
+fn planned_moves(
+    project_path: &Path,
+    renames: &BTreeMap<String, String>,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let mut moves = Vec::new();
+    for (old, new) in renames {
+        let candidates = [
+            (
+                project_path.join(design_doc_path(old)),
+                project_path.join(design_doc_path(new)),
+            ),
+            (
+                attachments_dir(project_path, old),
+                attachments_dir(project_path, new),
+            ),
+        ];
+        for (from, to) in candidates {
+            if !from.exists() {
+                continue;
+            }
+            if to.exists() {
+                return Err(format!("Cannot rename: {} already exists", to.display()));
+            }
+            moves.push((from, to));
+        }
+    }
+    Ok(moves)
+}
+
+/// Moves files in order, undoing completed moves if one fails.
+fn move_files(moves: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+    for (done, (from, to)) in moves.iter().enumerate() {
+        if let Err(e) = std::fs::rename(from, to) {
+            undo_moves(&moves[..done]);
+            return Err(format!("Failed to move {}: {}", from.display(), e));
+        }
+    }
+    Ok(())
+}
+
+/// Reverses completed moves (best effort).
+fn undo_moves(moves: &[(PathBuf, PathBuf)]) {
+    for (from, to) in moves.iter().rev() {
+        if let Err(e) = std::fs::rename(to, from) {
+            tracing::warn!("Failed to move {} back: {}", to.display(), e);
+        }
+    }
+}
+
+/// POST /api/beads/:id/rename
+///
+/// Changes a bead's ID. Its dotted children are renamed with it, and every
+/// dependency, comment, and design doc reference is rewritten in the same
+/// write. Honors `If-Match`.
+pub async fn rename_bead(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<RenameRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let new_id = payload.new_id.trim();
+    validate_new_id(new_id).map_err(AppError::BadRequest)?;
+    if new_id == bead_id {
+        return Err(AppError::BadRequest(
+            "New ID must differ from the current ID".to_string(),
+        ));
+    }
+
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    if find_record_index(&lines, &bead_id).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Bead with id '{}' not found",
+            bead_id
+        )));
+    }
+
+    let existing_ids = records_ids(&lines);
+    let renames = rename_map(&existing_ids, &bead_id, new_id);
+    if let Some(taken) = renames
+        .values()
+        .find(|new| existing_ids.contains(new) && !renames.contains_key(*new))
+    {
+        return Err(AppError::Conflict(format!(
+            "Bead with id '{}' already exists",
+            taken
+        )));
+    }
+
+    let project_path = PathBuf::from(&payload.path);
+    let moves = planned_moves(&project_path, &renames).map_err(AppError::Conflict)?;
+
+    let updated = apply_renames(&mut lines, &renames, &Utc::now().to_rfc3339());
+    let bead = match find_record_index(&lines, new_id) {
+        Some(index) => record_to_bead(record_at_mut(&mut lines, index)),
+        None => Err(format!("Bead with id '{}' not found", new_id)),
+    };
+
+    move_files(&moves).map_err(AppError::Internal)?;
+    if let Err(e) = write_issue_lines(&issues_path, &lines) {
+        undo_moves(&moves);
+        return Err(AppError::Internal(e));
+    }
+    journal::record(&issues_path, "rename", &before, &lines);
+
+    match bead {
+        Ok(bead) => Ok(Json(serde_json::json!({
+            "success": true,
+            "bead": bead,
+            "renamed": renames,
+            "updated": updated
+        }))),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_new_id() {
+        assert!(validate_new_id("bd-a1b2").is_ok());
+        assert!(validate_new_id("proj_7.1").is_ok());
+        assert!(validate_new_id("").is_err());
+        assert!(validate_new_id("bd 1").is_err());
+        assert!(validate_new_id("../bd-1").is_err());
+        assert!(validate_new_id("bd-1.").is_err());
+    }
+
+    #[test]
+    fn test_apply_renames() {
+        let mut lines: Vec<IssueLine> = [
+            r#"{"id":"bd-1","title":"Epic","status":"open","design":".designs/bd-1.md","comments":[{"id":1,"issue_id":"bd-1","author":"a","text":"t","created_at":"x"}]}"#,
+            r#"{"id":"bd-1.1","title":"Child","status":"open","dependencies":[{"issue_id":"bd-1.1","depends_on_id":"bd-1","type":"parent-child"}]}"#,
+            r#"{"id":"bd-10","title":"Other","status":"open","dependencies":[{"issue_id":"bd-10","depends_on_id":"bd-1.1","type":"blocks"}]}"#,
+            r#"{"id":"bd-11","title":"Untouched","status":"open"}"#,
+        ]
+        .iter()
+        .map(|line| IssueLine::Record(serde_json::from_str(line).unwrap()))
+        .collect();
+
+        let ids = records_ids(&lines);
+        let renames = rename_map(&ids, "bd-1", "web-1");
+        assert_eq!(renames.len(), 2);
+        assert_eq!(renames["bd-1.1"], "web-1.1");
+
+        let updated = apply_renames(&mut lines, &renames, "2026-01-01T00:00:00Z");
+        assert_eq!(updated, vec!["web-1", "web-1.1", "bd-10"]);
+
+        let record = |i: usize| match &lines[i] {
+            IssueLine::Record(record) => record.clone(),
+            IssueLine::Malformed(_) => panic!("expected a record"),
+        };
+        let epic = record(0);
+        assert_eq!(epic["id"], "web-1");
+        assert_eq!(epic["design"], ".designs/web-1.md");
+        assert_eq!(epic["comments"][0]["issue_id"], "web-1");
+        assert_eq!(epic["previous_ids"], serde_json::json!(["bd-1"]));
+
+        let child = record(1);
+        assert_eq!(child["dependencies"][0]["issue_id"], "web-1.1");
+        assert_eq!(child["dependencies"][0]["depends_on_id"], "web-1");
+
+        let other = record(2);
+        assert_eq!(other["dependencies"][0]["depends_on_id"], "web-1.1");
+        assert!(!other.contains_key("previous_ids"));
+        assert!(!record(3).contains_key("updated_at"));
+    }
+
+    #[test]
+    fn test_planned_moves() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join(".designs")).unwrap();
+        std::fs::write(dir.path().join(".designs/bd-1.md"), "# Epic").unwrap();
+        std::fs::create_dir_all(attachments_dir(dir.path(), "bd-1.1")).unwrap();
+
+        let renames = BTreeMap::from([
+            ("bd-1".to_string(), "web-1".to_string()),
+            ("bd-1.1".to_string(), "web-1.1".to_string()),
+        ]);
+        let moves = planned_moves(dir.path(), &renames).unwrap();
+        assert_eq!(moves.len(), 2);
+
+        move_files(&moves).unwrap();
+        assert!(dir.path().join(".designs/web-1.md").exists());
+        assert!(attachments_dir(dir.path(), "web-1.1").exists());
+
+        // Existing targets block the rename
+        std::fs::write(dir.path().join(".designs/bd-1.md"), "# Again").unwrap();
+        assert!(planned_moves(dir.path(), &renames).is_err());
+    }
+}
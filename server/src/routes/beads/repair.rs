@@ -0,0 +1,421 @@
+//! Repair of corrupted `issues.jsonl` files.
+//!
+//! Unparseable lines (and records without an ID) are moved to
+//! `.beads/quarantine.jsonl`, duplicate IDs are collapsed to the most
+//! recently updated copy, and timestamps in non-RFC 3339 formats are
+//! rewritten. The original file is copied to a timestamped backup before
+//! anything is written.
+
+use axum::{http::HeaderMap, response::IntoResponse, Json};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::{
+    check_if_match, mutation_issues_path, read_issue_lines, record_id, write_issue_lines,
+    IssueLine, IssueRecord,
+};
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+
+/// Top-level timestamp fields checked during normalization.
+const TIMESTAMP_FIELDS: &[&str] = &["created_at", "updated_at", "closed_at", "timer_started_at"];
+
+/// Naive date-time formats accepted as timestamps (interpreted as UTC).
+const NAIVE_TIMESTAMP_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+];
+
+fn default_true() -> bool {
+    true
+}
+
+/// Request body for the repair endpoint. Every repair is enabled by default.
+#[derive(Debug, Deserialize)]
+pub struct RepairRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// Collapse records sharing an ID into the most recently updated one
+    #[serde(default = "default_true")]
+    pub dedupe_ids: bool,
+    /// Move unparseable lines to the quarantine file
+    #[serde(default = "default_true")]
+    pub quarantine_malformed: bool,
+    /// Rewrite timestamps in other formats as RFC 3339
+    #[serde(default = "default_true")]
+    pub normalize_timestamps: bool,
+    /// Report what would change without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Which repairs to apply.
+#[derive(Debug, Clone, Copy)]
+struct RepairOptions {
+    dedupe_ids: bool,
+    quarantine_malformed: bool,
+    normalize_timestamps: bool,
+}
+
+/// A duplicated bead ID and how many extra copies were removed.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct DuplicateRepair {
+    pub id: String,
+    pub removed: usize,
+}
+
+/// A timestamp that was rewritten, or could not be parsed.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TimestampRepair {
+    pub id: String,
+    /// Field name; comment timestamps are reported as "comments[i].created_at"
+    pub field: String,
+    pub from: String,
+    /// Normalized value (`None` if the timestamp could not be parsed)
+    pub to: Option<String>,
+}
+
+/// Summary of the changes made by a repair.
+#[derive(Debug, Default, Serialize)]
+pub struct RepairReport {
+    /// Number of lines moved to the quarantine file
+    pub quarantined_lines: usize,
+    pub duplicates: Vec<DuplicateRepair>,
+    pub timestamps: Vec<TimestampRepair>,
+    /// Timestamps left untouched because no known format matched
+    pub invalid_timestamps: Vec<TimestampRepair>,
+}
+
+impl RepairReport {
+    fn changed(&self) -> bool {
+        self.quarantined_lines > 0 || !self.duplicates.is_empty() || !self.timestamps.is_empty()
+    }
+}
+
+/// Parses a timestamp in RFC 3339 or one of the fallback formats.
+///
+/// Returns `Ok(None)` for valid RFC 3339 (nothing to do), `Ok(Some(_))`
+/// with the normalized value, or `Err(())` if no format matches.
+fn normalize_timestamp(value: &str) -> Result<Option<String>, ()> {
+    if DateTime::parse_from_rfc3339(value).is_ok() {
+        return Ok(None);
+    }
+    let trimmed = value.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(Some(dt.to_rfc3339()));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(trimmed, "%Y-%m-%d %H:%M:%S%.f %z") {
+        return Ok(Some(dt.to_rfc3339()));
+    }
+    if let Some(dt) = NAIVE_TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(trimmed, format).ok())
+    {
+        return Ok(Some(dt.and_utc().to_rfc3339()));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(trimmed, "%Y-%m-%d") {
+        return Ok(Some(
+            date.and_hms_opt(0, 0, 0).unwrap().and_utc().to_rfc3339(),
+        ));
+    }
+    if let Ok(secs) = trimmed.parse::<i64>() {
+        if let Some(dt) = DateTime::<Utc>::from_timestamp(secs, 0) {
+            return Ok(Some(dt.to_rfc3339()));
+        }
+    }
+    Err(())
+}
+
+/// Normalizes one timestamp value in place, recording the outcome.
+fn repair_timestamp(
+    value: &mut serde_json::Value,
+    id: &str,
+    field: String,
+    report: &mut RepairReport,
+) {
+    let original = match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) => n.to_string(),
+        _ => return,
+    };
+    match normalize_timestamp(&original) {
+        Ok(None) => {}
+        Ok(Some(normalized)) => {
+            *value = serde_json::json!(normalized);
+            report.timestamps.push(TimestampRepair {
+                id: id.to_string(),
+                field,
+                from: original,
+                to: Some(normalized),
+            });
+        }
+        Err(()) => report.invalid_timestamps.push(TimestampRepair {
+            id: id.to_string(),
+            field,
+            from: original,
+            to: None,
+        }),
+    }
+}
+
+/// Normalizes the timestamps of a record and its comments.
+fn repair_record_timestamps(record: &mut IssueRecord, report: &mut RepairReport) {
+    let id = record_id(record).unwrap_or_default().to_string();
+    for field in TIMESTAMP_FIELDS {
+        if let Some(value) = record.get_mut(*field) {
+            repair_timestamp(value, &id, field.to_string(), report);
+        }
+    }
+    if let Some(comments) = record.get_mut("comments").and_then(|c| c.as_array_mut()) {
+        for (i, comment) in comments.iter_mut().enumerate() {
+            if let Some(value) = comment.get_mut("created_at") {
+                repair_timestamp(value, &id, format!("comments[{}].created_at", i), report);
+            }
+        }
+    }
+}
+
+/// The `updated_at` of a record, for choosing between duplicates.
+fn updated_at(record: &IssueRecord) -> Option<DateTime<Utc>> {
+    record
+        .get("updated_at")
+        .and_then(|v| v.as_str())
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Applies the repairs to the lines of an issues file.
+///
+/// Returns the repaired lines and the raw lines to quarantine. Among
+/// records sharing an ID, the one with the latest `updated_at` is kept (the
+/// last one on a tie), in the position of the first copy.
+fn repair_lines(
+    lines: Vec<IssueLine>,
+    options: RepairOptions,
+) -> (Vec<IssueLine>, Vec<String>, RepairReport) {
+    let mut report = RepairReport::default();
+    let mut quarantined = Vec::new();
+    let mut kept: Vec<IssueLine> = Vec::with_capacity(lines.len());
+    // Bead ID -> index in `kept` of the copy currently kept
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut duplicate_counts: Vec<(String, usize)> = Vec::new();
+
+    for line in lines {
+        let mut record = match line {
+            IssueLine::Record(record) if record_id(&record).is_some() => record,
+            IssueLine::Record(record) if options.quarantine_malformed => {
+                quarantined.push(serde_json::to_string(&record).unwrap_or_default());
+                continue;
+            }
+            IssueLine::Malformed(raw) if options.quarantine_malformed => {
+                quarantined.push(raw);
+                continue;
+            }
+            other => {
+                kept.push(other);
+                continue;
+            }
+        };
+
+        if options.normalize_timestamps {
+            repair_record_timestamps(&mut record, &mut report);
+        }
+
+        let id = record_id(&record).unwrap_or_default().to_string();
+        let existing = if options.dedupe_ids {
+            positions.get(&id).copied()
+        } else {
+            None
+        };
+        match existing {
+            Some(index) => {
+                match duplicate_counts.iter_mut().find(|(d, _)| *d == id) {
+                    Some((_, count)) => *count += 1,
+                    None => duplicate_counts.push((id, 1)),
+                }
+                let keep_new = match &kept[index] {
+                    IssueLine::Record(current) => updated_at(&record) >= updated_at(current),
+                    IssueLine::Malformed(_) => unreachable!("positions only index records"),
+                };
+                let loser = if keep_new {
+                    std::mem::replace(&mut kept[index], IssueLine::Record(record))
+                } else {
+                    IssueLine::Record(record)
+                };
+                if let IssueLine::Record(loser) = loser {
+                    quarantined.push(serde_json::to_string(&loser).unwrap_or_default());
+                }
+            }
+            None => {
+                positions.insert(id, kept.len());
+                kept.push(IssueLine::Record(record));
+            }
+        }
+    }
+
+    report.quarantined_lines = quarantined.len();
+    report.duplicates = duplicate_counts
+        .into_iter()
+        .map(|(id, removed)| DuplicateRepair { id, removed })
+        .collect();
+    (kept, quarantined, report)
+}
+
+/// Returns the backup path for an issues file at `now`.
+fn backup_path(issues_path: &Path, now: DateTime<Utc>) -> PathBuf {
+    issues_path.with_extension(format!("jsonl.{}.bak", now.format("%Y%m%dT%H%M%SZ")))
+}
+
+/// Appends raw lines to the quarantine file.
+fn append_quarantine(path: &Path, lines: &[String]) -> Result<(), String> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open quarantine file: {}", e))?;
+    for line in lines {
+        writeln!(file, "{}", line)
+            .map_err(|e| format!("Failed to write quarantine file: {}", e))?;
+    }
+    Ok(())
+}
+
+/// POST /api/beads/repair
+///
+/// Repairs a corrupted issues file: quarantines unparseable lines,
+/// de-duplicates IDs, and normalizes timestamps. A backup copy of the
+/// original file is kept next to it. With `dry_run`, only reports what
+/// would change.
+pub async fn repair_beads(
+    headers: HeaderMap,
+    Json(payload): Json<RepairRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(&headers, &issues_path)?;
+
+    let lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+
+    let options = RepairOptions {
+        dedupe_ids: payload.dedupe_ids,
+        quarantine_malformed: payload.quarantine_malformed,
+        normalize_timestamps: payload.normalize_timestamps,
+    };
+    let (repaired, quarantined, report) = repair_lines(lines, options);
+
+    let mut backup = None;
+    let mut quarantine = None;
+    if report.changed() && !payload.dry_run {
+        let path = backup_path(&issues_path, Utc::now());
+        std::fs::copy(&issues_path, &path)
+            .map_err(|e| AppError::Internal(format!("Failed to create backup: {}", e)))?;
+        backup = Some(path);
+
+        if !quarantined.is_empty() {
+            let path = issues_path.with_file_name("quarantine.jsonl");
+            append_quarantine(&path, &quarantined).map_err(AppError::Internal)?;
+            quarantine = Some(path);
+        }
+
+        write_issue_lines(&issues_path, &repaired).map_err(AppError::Internal)?;
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "changed": report.changed(),
+        "dry_run": payload.dry_run,
+        "backup": backup,
+        "quarantine": quarantine,
+        "report": report
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: RepairOptions = RepairOptions {
+        dedupe_ids: true,
+        quarantine_malformed: true,
+        normalize_timestamps: true,
+    };
+
+    fn line(json: &str) -> IssueLine {
+        IssueLine::Record(serde_json::from_str(json).unwrap())
+    }
+
+    #[test]
+    fn test_normalize_timestamp() {
+        assert_eq!(normalize_timestamp("2026-01-01T10:00:00Z"), Ok(None));
+        assert_eq!(
+            normalize_timestamp("2026-01-01 10:00:00"),
+            Ok(Some("2026-01-01T10:00:00+00:00".to_string()))
+        );
+        assert_eq!(
+            normalize_timestamp("2026-01-01"),
+            Ok(Some("2026-01-01T00:00:00+00:00".to_string()))
+        );
+        assert_eq!(normalize_timestamp("yesterday"), Err(()));
+    }
+
+    #[test]
+    fn test_repair_lines() {
+        let lines = vec![
+            line(r#"{"id":"bd-1","title":"Old","updated_at":"2026-01-01T10:00:00Z"}"#),
+            IssueLine::Malformed("{not json".to_string()),
+            line(r#"{"id":"bd-2","title":"Two","created_at":"2026-01-02 09:30:00"}"#),
+            line(r#"{"id":"bd-1","title":"New","updated_at":"2026-01-03T10:00:00Z"}"#),
+            line(r#"{"title":"No id"}"#),
+        ];
+
+        let (repaired, quarantined, report) = repair_lines(lines, ALL);
+        assert_eq!(repaired.len(), 2);
+        let IssueLine::Record(ref first) = repaired[0] else {
+            panic!("expected record");
+        };
+        assert_eq!(first["title"], "New");
+        let IssueLine::Record(ref second) = repaired[1] else {
+            panic!("expected record");
+        };
+        assert_eq!(second["created_at"], "2026-01-02T09:30:00+00:00");
+
+        assert_eq!(quarantined.len(), 3);
+        assert!(quarantined.contains(&"{not json".to_string()));
+        assert_eq!(report.quarantined_lines, 3);
+        assert_eq!(
+            report.duplicates,
+            vec![DuplicateRepair {
+                id: "bd-1".to_string(),
+                removed: 1
+            }]
+        );
+        assert_eq!(report.timestamps.len(), 1);
+        assert!(report.changed());
+    }
+
+    #[test]
+    fn test_repair_lines_respects_options() {
+        let lines = vec![
+            line(r#"{"id":"bd-1","created_at":"2026-01-02 09:30:00"}"#),
+            IssueLine::Malformed("{not json".to_string()),
+            line(r#"{"id":"bd-1"}"#),
+        ];
+        let options = RepairOptions {
+            dedupe_ids: false,
+            quarantine_malformed: false,
+            normalize_timestamps: false,
+        };
+
+        let (repaired, quarantined, report) = repair_lines(lines, options);
+        assert_eq!(repaired.len(), 3);
+        assert!(quarantined.is_empty());
+        assert!(!report.changed());
+    }
+}
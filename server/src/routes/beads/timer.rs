@@ -0,0 +1,322 @@
+//! Time tracking on beads.
+//!
+//! A running timer is stored on the bead as `timer_started_at`; stopping it
+//! adds the elapsed time to `time_tracked_seconds`. Both fields live in the
+//! JSONL so tracked time travels with the repo.
+
+use axum::{
+    extract::{Path as AxumPath, Query},
+    http::HeaderMap,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use super::{
+    check_if_match, find_record_index, journal, link_bead_relationships, mutation_issues_path,
+    parse_beads, read_issue_lines, record_at_mut, record_to_bead, resolve_issues_path,
+    write_issue_lines, Bead, IssueRecord,
+};
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+use crate::routes::validate_path_security;
+
+/// Request body for the timer endpoints.
+#[derive(Debug, Deserialize)]
+pub struct TimerRequest {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+}
+
+/// Query parameters for the time report endpoint.
+#[derive(Debug, Deserialize)]
+pub struct TimeReportParams {
+    /// The project path containing .beads/issues.jsonl
+    pub path: String,
+    /// Grouping: "epic" (default) or "owner"
+    #[serde(default)]
+    pub group_by: Option<String>,
+}
+
+/// Tracked time for one group in the time report.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct TimeReportGroup {
+    /// Epic ID or owner; `None` for beads without an epic/owner.
+    pub key: Option<String>,
+    pub seconds: u64,
+    /// Number of beads in the group with tracked time.
+    pub bead_count: usize,
+}
+
+/// Seconds elapsed between an RFC 3339 timestamp and `now` (0 if unparseable).
+fn elapsed_seconds(started_at: &str, now: DateTime<Utc>) -> u64 {
+    DateTime::parse_from_rfc3339(started_at)
+        .map(|start| (now - start.with_timezone(&Utc)).num_seconds().max(0) as u64)
+        .unwrap_or(0)
+}
+
+/// Starts the timer on a record.
+fn start_record_timer(record: &mut IssueRecord, now: DateTime<Utc>) -> Result<(), String> {
+    if record.get("timer_started_at").is_some_and(|v| !v.is_null()) {
+        return Err("Timer is already running".to_string());
+    }
+    record.insert(
+        "timer_started_at".to_string(),
+        serde_json::json!(now.to_rfc3339()),
+    );
+    record.insert("updated_at".to_string(), serde_json::json!(now.to_rfc3339()));
+    Ok(())
+}
+
+/// Stops the timer on a record, adding the elapsed time to its total.
+///
+/// Returns the number of seconds added.
+fn stop_record_timer(record: &mut IssueRecord, now: DateTime<Utc>) -> Result<u64, String> {
+    let started_at = match record.remove("timer_started_at") {
+        Some(serde_json::Value::String(s)) => s,
+        _ => return Err("Timer is not running".to_string()),
+    };
+
+    let elapsed = elapsed_seconds(&started_at, now);
+    let total = record
+        .get("time_tracked_seconds")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+        + elapsed;
+    record.insert("time_tracked_seconds".to_string(), serde_json::json!(total));
+    record.insert("updated_at".to_string(), serde_json::json!(now.to_rfc3339()));
+    Ok(elapsed)
+}
+
+/// Total tracked seconds for a bead, including a running timer up to `now`.
+fn bead_tracked_seconds(bead: &Bead, now: DateTime<Utc>) -> u64 {
+    bead.time_tracked_seconds.unwrap_or(0)
+        + bead
+            .timer_started_at
+            .as_deref()
+            .map(|s| elapsed_seconds(s, now))
+            .unwrap_or(0)
+}
+
+/// Aggregates tracked time by epic or owner.
+///
+/// For epic grouping, an epic's own time counts toward itself and each
+/// child's time counts toward its parent. Beads with no tracked time are
+/// left out.
+fn build_time_report(beads: &[Bead], by_owner: bool, now: DateTime<Utc>) -> Vec<TimeReportGroup> {
+    let mut groups: BTreeMap<Option<String>, (u64, usize)> = BTreeMap::new();
+
+    for bead in beads {
+        let seconds = bead_tracked_seconds(bead, now);
+        if seconds == 0 {
+            continue;
+        }
+
+        let key = if by_owner {
+            bead.owner.clone()
+        } else if bead.issue_type.as_deref() == Some("epic") {
+            Some(bead.id.clone())
+        } else {
+            bead.parent_id.clone()
+        };
+
+        let group = groups.entry(key).or_default();
+        group.0 += seconds;
+        group.1 += 1;
+    }
+
+    groups
+        .into_iter()
+        .map(|(key, (seconds, bead_count))| TimeReportGroup {
+            key,
+            seconds,
+            bead_count,
+        })
+        .collect()
+}
+
+/// Shared read-modify-write cycle for the timer endpoints.
+async fn modify_timer(
+    headers: &HeaderMap,
+    bead_id: &str,
+    payload: &TimerRequest,
+    start: bool,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let issues_path = mutation_issues_path(&payload.path)?;
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&issues_path).await?;
+
+    check_if_match(headers, &issues_path)?;
+
+    let mut lines = read_issue_lines(&issues_path).map_err(AppError::Internal)?;
+    let before = lines.clone();
+
+    let index = match find_record_index(&lines, bead_id) {
+        Some(i) => i,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Bead with id '{}' not found",
+                bead_id
+            )));
+        }
+    };
+
+    let now = Utc::now();
+    let record = record_at_mut(&mut lines, index);
+    let result = if start {
+        start_record_timer(record, now).map(|_| 0)
+    } else {
+        stop_record_timer(record, now)
+    };
+    let elapsed = match result {
+        Ok(elapsed) => elapsed,
+        Err(e) => return Err(AppError::Conflict(e)),
+    };
+    let bead = record_to_bead(record);
+
+    write_issue_lines(&issues_path, &lines).map_err(AppError::Internal)?;
+    journal::record(&issues_path, "timer", &before, &lines);
+
+    match bead {
+        Ok(bead) if start => Ok(Json(serde_json::json!({ "success": true, "bead": bead }))),
+        Ok(bead) => Ok(Json(serde_json::json!({
+            "success": true,
+            "bead": bead,
+            "elapsed_seconds": elapsed
+        }))),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// POST /api/beads/:id/timer/start
+///
+/// Starts tracking time on a bead. Fails with 409 if a timer is already running.
+pub async fn start_timer(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<TimerRequest>,
+) -> impl IntoResponse {
+    modify_timer(&headers, &bead_id, &payload, true).await
+}
+
+/// POST /api/beads/:id/timer/stop
+///
+/// Stops the running timer on a bead and adds the elapsed time to
+/// `time_tracked_seconds`. Fails with 409 if no timer is running.
+pub async fn stop_timer(
+    headers: HeaderMap,
+    AxumPath(bead_id): AxumPath<String>,
+    Json(payload): Json<TimerRequest>,
+) -> impl IntoResponse {
+    modify_timer(&headers, &bead_id, &payload, false).await
+}
+
+/// GET /api/beads/time-report?path=/path/to/project&group_by=epic|owner
+///
+/// Aggregates tracked time per epic (default) or per owner. Running timers
+/// are counted up to the time of the request.
+pub async fn time_report(
+    Query(params): Query<TimeReportParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let by_owner = match params.group_by.as_deref() {
+        None | Some("epic") => false,
+        Some("owner") => true,
+        Some(other) => {
+            return Err(AppError::BadRequest(format!(
+                "Invalid group_by '{}'. Must be 'epic' or 'owner'",
+                other
+            )));
+        }
+    };
+
+    let project_path = PathBuf::from(&params.path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    let issues_path = resolve_issues_path(&project_path);
+    let contents = match std::fs::read_to_string(&issues_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::NotFound(
+                "No .beads/issues.jsonl found at the specified path".to_string(),
+            ));
+        }
+        Err(e) => {
+            return Err(AppError::Internal(format!("Failed to read file: {}", e)));
+        }
+    };
+
+    let mut beads = parse_beads(&contents);
+    link_bead_relationships(&mut beads);
+
+    let groups = build_time_report(&beads, by_owner, Utc::now());
+    let total_seconds: u64 = groups.iter().map(|g| g.seconds).sum();
+
+    Ok(Json(serde_json::json!({
+        "group_by": if by_owner { "owner" } else { "epic" },
+        "groups": groups,
+        "total_seconds": total_seconds
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_start_and_stop_record_timer() {
+        let mut record: IssueRecord =
+            serde_json::from_str(r#"{"id":"bd-1","time_tracked_seconds":60}"#).unwrap();
+
+        start_record_timer(&mut record, at("2026-01-01T10:00:00Z")).unwrap();
+        assert!(start_record_timer(&mut record, at("2026-01-01T10:00:05Z")).is_err());
+
+        let elapsed = stop_record_timer(&mut record, at("2026-01-01T10:01:30Z")).unwrap();
+        assert_eq!(elapsed, 90);
+        assert_eq!(record["time_tracked_seconds"], 150);
+        assert!(!record.contains_key("timer_started_at"));
+        assert!(stop_record_timer(&mut record, at("2026-01-01T10:02:00Z")).is_err());
+    }
+
+    #[test]
+    fn test_build_time_report_by_epic_and_owner() {
+        let mut beads = parse_beads(concat!(
+            r#"{"id":"bd-1","title":"Epic","status":"open","issue_type":"epic","time_tracked_seconds":10}"#,
+            "\n",
+            r#"{"id":"bd-1.1","title":"Child","status":"open","owner":"ann","time_tracked_seconds":20}"#,
+            "\n",
+            r#"{"id":"bd-2","title":"Loose","status":"open","owner":"ann","timer_started_at":"2026-01-01T10:00:00Z"}"#,
+            "\n",
+            r#"{"id":"bd-3","title":"Untracked","status":"open"}"#,
+        ));
+        link_bead_relationships(&mut beads);
+        let now = at("2026-01-01T10:00:30Z");
+
+        let by_epic = build_time_report(&beads, false, now);
+        assert_eq!(
+            by_epic,
+            vec![
+                TimeReportGroup { key: None, seconds: 30, bead_count: 1 },
+                TimeReportGroup { key: Some("bd-1".to_string()), seconds: 30, bead_count: 2 },
+            ]
+        );
+
+        let by_owner = build_time_report(&beads, true, now);
+        assert_eq!(
+            by_owner,
+            vec![
+                TimeReportGroup { key: None, seconds: 10, bead_count: 1 },
+                TimeReportGroup { key: Some("ann".to_string()), seconds: 50, bead_count: 2 },
+            ]
+        );
+    }
+}
@@ -2,12 +2,15 @@
 //!
 //! Provides a secure endpoint for executing whitelisted bd CLI commands.
 
-use axum::{http::StatusCode, response::IntoResponse, Json};
+use axum::{response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::time::Duration;
 use tokio::process::Command;
 
+use crate::error::AppError;
+use crate::logging::LogCommand;
+
 /// Whitelisted bd subcommands that are allowed to be executed.
 const ALLOWED_COMMANDS: &[&str] = &["list", "show", "comment", "update", "close", "create", "ready", "epic"];
 
@@ -45,49 +48,31 @@ pub struct BdCommandResponse {
 pub async fn bd_command(Json(req): Json<BdCommandRequest>) -> impl IntoResponse {
     // Validate that we have at least one argument (the subcommand)
     if req.args.is_empty() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": "No arguments provided. Expected a bd subcommand."
-            })),
+        return AppError::BadRequest(
+            "No arguments provided. Expected a bd subcommand.".to_string(),
         )
-            .into_response();
+        .into_response();
     }
 
     // Check if the subcommand is whitelisted
     let subcommand = &req.args[0];
     if !ALLOWED_COMMANDS.contains(&subcommand.as_str()) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({
-                "error": format!(
-                    "Command '{}' is not allowed. Allowed commands: {:?}",
-                    subcommand, ALLOWED_COMMANDS
-                )
-            })),
-        )
-            .into_response();
+        return AppError::Forbidden(format!(
+            "Command '{}' is not allowed. Allowed commands: {:?}",
+            subcommand, ALLOWED_COMMANDS
+        ))
+        .into_response();
     }
 
     // Validate and set working directory
     let cwd = if let Some(ref dir) = req.cwd {
         let path = Path::new(dir);
         if !path.exists() {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": format!("Working directory does not exist: {}", dir)
-                })),
-            )
+            return AppError::BadRequest(format!("Working directory does not exist: {}", dir))
                 .into_response();
         }
         if !path.is_dir() {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": format!("Path is not a directory: {}", dir)
-                })),
-            )
+            return AppError::BadRequest(format!("Path is not a directory: {}", dir))
                 .into_response();
         }
         path.to_path_buf()
@@ -99,7 +84,7 @@ pub async fn bd_command(Json(req): Json<BdCommandRequest>) -> impl IntoResponse
     let mut cmd = Command::new("bd");
     cmd.args(&req.args).current_dir(&cwd);
 
-    let result = tokio::time::timeout(Duration::from_secs(30), cmd.output()).await;
+    let result = tokio::time::timeout(Duration::from_secs(30), cmd.logged().output()).await;
 
     match result {
         Ok(Ok(output)) => {
@@ -110,20 +95,12 @@ pub async fn bd_command(Json(req): Json<BdCommandRequest>) -> impl IntoResponse
             };
             Json(response).into_response()
         }
-        Ok(Err(e)) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to execute command: {}", e)
-            })),
-        )
-            .into_response(),
-        Err(_) => (
-            StatusCode::GATEWAY_TIMEOUT,
-            Json(serde_json::json!({
-                "error": "Command timed out after 30 seconds"
-            })),
-        )
-            .into_response(),
+        Ok(Err(e)) => {
+            AppError::Internal(format!("Failed to execute command: {}", e)).into_response()
+        }
+        Err(_) => {
+            AppError::Timeout("Command timed out after 30 seconds".to_string()).into_response()
+        }
     }
 }
 
@@ -0,0 +1,283 @@
+//! Deep health check.
+//!
+//! `GET /api/v1/health` only says the server is up. `GET
+//! /api/v1/health/deep` also checks what the board depends on: the `git`,
+//! `bd`, and `gh` executables, the settings database, file watchers, and
+//! whether each registered project can be written to. Failed checks carry
+//! a hint the UI can show as a setup error.
+//!
+//! [`server_status`] is a cheaper summary, sent to event streams as their
+//! heartbeat.
+
+use axum::{extract::State, Json};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+use super::projects::AppState;
+use super::watch::{active_watchers, polling_watchers};
+use super::worktree::{fetches_in_progress, last_rate_limit, running_hooks};
+use crate::logging::LogCommand;
+
+/// How long to wait for `<tool> --version`.
+const TOOL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// When the server started.
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// Records the server's start, for its uptime.
+pub fn mark_started() {
+    STARTED.get_or_init(Instant::now);
+}
+
+/// Health of the file watchers.
+#[derive(Debug, Serialize)]
+pub struct WatcherHealth {
+    /// Watchers running
+    pub active: usize,
+    /// Of those, watchers polling rather than using native events
+    pub polling: usize,
+}
+
+/// A quick summary of the server, from state it already has.
+#[derive(Debug, Serialize)]
+pub struct ServerStatus {
+    pub uptime_seconds: u64,
+    /// Remaining GitHub API calls as of the last PR status check; absent
+    /// until one reports it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_remaining: Option<i32>,
+    pub watchers: WatcherHealth,
+    /// Worktree hook runs and repository fetches in progress
+    pub pending_jobs: usize,
+}
+
+/// Returns the server's current status.
+pub fn server_status() -> ServerStatus {
+    ServerStatus {
+        uptime_seconds: STARTED.get_or_init(Instant::now).elapsed().as_secs(),
+        rate_limit_remaining: last_rate_limit().map(|limit| limit.remaining),
+        watchers: WatcherHealth {
+            active: active_watchers(),
+            polling: polling_watchers(),
+        },
+        pending_jobs: running_hooks() + fetches_in_progress(),
+    }
+}
+
+/// Availability of an external executable.
+#[derive(Debug, Serialize)]
+pub struct ToolCheck {
+    pub name: &'static str,
+    pub available: bool,
+    /// First line of `<tool> --version`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<&'static str>,
+}
+
+/// Reachability of the settings database.
+#[derive(Debug, Serialize)]
+pub struct DatabaseCheck {
+    pub ok: bool,
+    /// Number of registered projects
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub projects: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// State of a registered project's directory.
+#[derive(Debug, Serialize)]
+pub struct ProjectCheck {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub exists: bool,
+    /// Whether `.beads/` exists
+    pub has_beads: bool,
+    /// Whether files can be created in `.beads/` (or the project, without it)
+    pub writable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for the deep health check.
+#[derive(Debug, Serialize)]
+pub struct DeepHealthResponse {
+    /// `ok`, or `degraded` if any check failed
+    pub status: &'static str,
+    pub version: &'static str,
+    pub tools: Vec<ToolCheck>,
+    pub database: DatabaseCheck,
+    /// File watchers running for open event streams
+    pub watchers: usize,
+    pub projects: Vec<ProjectCheck>,
+}
+
+/// GET /api/v1/health/deep
+///
+/// Checks external tools, the database, watchers, and registered projects.
+pub async fn deep_health(State(db): State<AppState>) -> Json<DeepHealthResponse> {
+    let (git, bd, gh) = tokio::join!(
+        check_tool("git", "Install git from https://git-scm.com/downloads"),
+        check_tool("bd", "Install the Beads CLI: brew install steveyegge/beads/bd"),
+        check_tool(
+            "gh",
+            "Install the GitHub CLI (https://cli.github.com) and run `gh auth login` for pull requests",
+        ),
+    );
+    let tools = vec![git, bd, gh];
+
+    let (database, projects) = match db.get_projects() {
+        Ok(projects) => (
+            DatabaseCheck {
+                ok: true,
+                projects: Some(projects.len()),
+                error: None,
+            },
+            projects,
+        ),
+        Err(e) => (
+            DatabaseCheck {
+                ok: false,
+                projects: None,
+                error: Some(e.to_string()),
+            },
+            Vec::new(),
+        ),
+    };
+    let projects = tokio::task::spawn_blocking(move || {
+        projects
+            .into_iter()
+            .map(|p| check_project(p.id, p.name, p.path))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    // gh is only needed for pull requests, so it doesn't degrade the server
+    let healthy = tools.iter().all(|t| t.available || t.name == "gh")
+        && database.ok
+        && projects.iter().all(|p| p.exists && p.writable);
+
+    Json(DeepHealthResponse {
+        status: if healthy { "ok" } else { "degraded" },
+        version: crate::update::VERSION,
+        tools,
+        database,
+        watchers: active_watchers(),
+        projects,
+    })
+}
+
+/// Runs `<name> --version`.
+async fn check_tool(name: &'static str, hint: &'static str) -> ToolCheck {
+    let output = Command::new(name)
+        .arg("--version")
+        .kill_on_drop(true)
+        .logged()
+        .output();
+    let error = match tokio::time::timeout(TOOL_TIMEOUT, output).await {
+        Ok(Ok(output)) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return ToolCheck {
+                name,
+                available: true,
+                version: stdout.lines().next().map(|l| l.trim().to_string()),
+                error: None,
+                hint: None,
+            };
+        }
+        Ok(Ok(output)) => String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+            format!("{} was not found on PATH", name)
+        }
+        Ok(Err(e)) => e.to_string(),
+        Err(_) => format!("{} --version timed out", name),
+    };
+    ToolCheck {
+        name,
+        available: false,
+        version: None,
+        error: Some(error),
+        hint: Some(hint),
+    }
+}
+
+/// Checks a project directory exists and can be written to.
+fn check_project(id: String, name: String, path: String) -> ProjectCheck {
+    let root = Path::new(&path);
+    let beads_dir = root.join(".beads");
+    let exists = root.is_dir();
+    let has_beads = beads_dir.is_dir();
+    let (writable, error) = if !exists {
+        (false, Some("Project directory does not exist".to_string()))
+    } else {
+        match probe_writable(if has_beads { &beads_dir } else { root }) {
+            Ok(()) => (true, None),
+            Err(e) => (false, Some(e)),
+        }
+    };
+    ProjectCheck {
+        id,
+        name,
+        path,
+        exists,
+        has_beads,
+        writable,
+        error,
+    }
+}
+
+/// Creates and removes a scratch file in `dir`.
+fn probe_writable(dir: &Path) -> Result<(), String> {
+    let probe = dir.join(format!(".health-check-{}", uuid::Uuid::new_v4().simple()));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .map_err(|e| format!("Cannot write to {}: {}", dir.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_string_lossy().to_string();
+
+        let check = check_project("p1".into(), "app".into(), path.clone());
+        assert!(check.exists && check.writable && !check.has_beads);
+
+        std::fs::create_dir(dir.path().join(".beads")).unwrap();
+        let check = check_project("p1".into(), "app".into(), path);
+        assert!(check.has_beads && check.writable);
+        assert_eq!(std::fs::read_dir(dir.path().join(".beads")).unwrap().count(), 0);
+
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+        let check = check_project("p2".into(), "gone".into(), missing);
+        assert!(!check.exists && !check.writable && check.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_check_tool() {
+        let missing = check_tool("beads-no-such-tool", "install it").await;
+        assert!(!missing.available);
+        assert_eq!(missing.hint, Some("install it"));
+
+        let git = check_tool("git", "install git").await;
+        assert!(git.available);
+        assert!(git.version.unwrap().starts_with("git version"));
+    }
+}
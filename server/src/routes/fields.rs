@@ -0,0 +1,365 @@
+//! Custom field API route handlers.
+//!
+//! A project's custom field schema is stored in `fields.json` next to its
+//! `issues.jsonl`. Beads carry their values in a `custom` object, which is
+//! validated against the schema whenever beads are created or updated.
+
+use axum::{
+    extract::{Path as AxumPath, Query},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, NaiveDate};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::beads::resolve_issues_path;
+use super::validate_path_security;
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+
+/// Supported custom field types.
+const FIELD_TYPES: &[&str] = &["text", "number", "boolean", "date", "select"];
+
+/// Custom field values on a bead, keyed by field name.
+pub type CustomValues = serde_json::Map<String, serde_json::Value>;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// The definition of one custom field.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FieldDefinition {
+    pub name: String,
+    /// One of "text", "number", "boolean", "date", or "select".
+    #[serde(rename = "type")]
+    pub field_type: String,
+    /// Allowed values for "select" fields.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// The contents of `fields.json`.
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct FieldsFile {
+    #[serde(default)]
+    fields: Vec<FieldDefinition>,
+}
+
+/// Query parameters for GET endpoints.
+#[derive(Debug, Deserialize)]
+pub struct FieldsParams {
+    pub path: String,
+}
+
+/// Request body for replacing the schema.
+#[derive(Debug, Deserialize)]
+pub struct UpdateFieldsRequest {
+    pub path: String,
+    pub fields: Vec<FieldDefinition>,
+}
+
+/// Request body for the delete field endpoint.
+#[derive(Debug, Deserialize)]
+pub struct DeleteFieldRequest {
+    pub path: String,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Matches valid field names (e.g., "severity", "customer_id").
+fn field_name_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[A-Za-z][A-Za-z0-9_-]{0,63}$").unwrap())
+}
+
+/// Build the path to the schema file from the issues file path.
+fn fields_path(issues_path: &Path) -> PathBuf {
+    issues_path.with_file_name("fields.json")
+}
+
+/// Read the schema. A missing file means no custom fields.
+fn read_fields(path: &Path) -> Result<Vec<FieldDefinition>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str::<FieldsFile>(&contents)
+            .map(|file| file.fields)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read file: {}", e)),
+    }
+}
+
+/// Write the schema back atomically (temp file + rename).
+fn write_fields(path: &Path, fields: &[FieldDefinition]) -> Result<(), String> {
+    let file = FieldsFile {
+        fields: fields.to_vec(),
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize fields: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json + "\n").map_err(|e| format!("Failed to write file: {}", e))?;
+    std::fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace file: {}", e))
+}
+
+/// Validate a full schema: unique valid names, known types, and options
+/// present exactly for select fields.
+fn validate_schema(fields: &[FieldDefinition]) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for field in fields {
+        if !field_name_regex().is_match(&field.name) {
+            return Err(format!(
+                "Invalid field name '{}'. Use letters, digits, '_' or '-', starting with a letter",
+                field.name
+            ));
+        }
+        if !names.insert(field.name.as_str()) {
+            return Err(format!("Duplicate field name '{}'", field.name));
+        }
+        if !FIELD_TYPES.contains(&field.field_type.as_str()) {
+            return Err(format!(
+                "Invalid type '{}' for field '{}'. Allowed types: {:?}",
+                field.field_type, field.name, FIELD_TYPES
+            ));
+        }
+        match (field.field_type.as_str(), field.options.is_empty()) {
+            ("select", true) => {
+                return Err(format!("Select field '{}' must have options", field.name));
+            }
+            ("select", false) => {}
+            (_, false) => {
+                return Err(format!(
+                    "Only select fields take options ('{}' is {})",
+                    field.name, field.field_type
+                ));
+            }
+            (_, true) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Check one value against its field definition.
+fn validate_value(field: &FieldDefinition, value: &serde_json::Value) -> Result<(), String> {
+    let valid = match field.field_type.as_str() {
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "date" => value.as_str().is_some_and(|s| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+                || DateTime::parse_from_rfc3339(s).is_ok()
+        }),
+        "select" => value
+            .as_str()
+            .is_some_and(|s| field.options.iter().any(|o| o == s)),
+        _ => value.is_string(),
+    };
+
+    if valid {
+        Ok(())
+    } else if field.field_type == "select" {
+        Err(format!(
+            "Invalid value {} for field '{}'. Allowed values: {:?}",
+            value, field.name, field.options
+        ))
+    } else {
+        Err(format!(
+            "Invalid value {} for {} field '{}'",
+            value, field.field_type, field.name
+        ))
+    }
+}
+
+/// Validate custom values against a schema. `null` values are allowed; they
+/// clear the field.
+fn check_custom_values(fields: &[FieldDefinition], values: &CustomValues) -> Result<(), String> {
+    for (name, value) in values {
+        let field = fields
+            .iter()
+            .find(|f| &f.name == name)
+            .ok_or_else(|| format!("Unknown custom field '{}'", name))?;
+        if !value.is_null() {
+            validate_value(field, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Validates custom values against the schema of the project owning `issues_path`.
+pub(crate) fn validate_custom_values(
+    issues_path: &Path,
+    values: &CustomValues,
+) -> Result<(), AppError> {
+    if values.is_empty() {
+        return Ok(());
+    }
+    let fields = read_fields(&fields_path(issues_path)).map_err(AppError::Internal)?;
+    check_custom_values(&fields, values).map_err(AppError::BadRequest)
+}
+
+/// Validate the project path and resolve its schema file.
+fn project_fields_path(project: &str) -> Result<PathBuf, AppError> {
+    let project_path = PathBuf::from(project);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    Ok(fields_path(&resolve_issues_path(&project_path)))
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /api/fields?path={project_path}
+///
+/// Returns the project's custom field schema.
+pub async fn list_fields(
+    Query(params): Query<FieldsParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let fpath = project_fields_path(&params.path)?;
+    let fields = read_fields(&fpath).map_err(AppError::Internal)?;
+    Ok(Json(serde_json::json!({ "fields": fields })))
+}
+
+/// PUT /api/fields
+///
+/// Replaces the project's custom field schema. Existing bead values are
+/// left as they are; only new writes are validated against the new schema.
+pub async fn update_fields(
+    Json(payload): Json<UpdateFieldsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    validate_schema(&payload.fields).map_err(AppError::BadRequest)?;
+
+    let fpath = project_fields_path(&payload.path)?;
+
+    // The .beads directory must exist; the schema belongs to a beads project
+    if !fpath.parent().is_some_and(|p| p.exists()) {
+        return Err(AppError::NotFound(
+            "No .beads directory found at the specified path".to_string(),
+        ));
+    }
+
+    let _lock = lock_exclusive(&fpath).await?;
+
+    write_fields(&fpath, &payload.fields).map_err(AppError::Internal)?;
+
+    Ok(Json(
+        serde_json::json!({ "success": true, "fields": payload.fields }),
+    ))
+}
+
+/// DELETE /api/fields/:name
+///
+/// Removes a field from the project's schema.
+pub async fn delete_field(
+    AxumPath(name): AxumPath<String>,
+    Json(payload): Json<DeleteFieldRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let fpath = project_fields_path(&payload.path)?;
+
+    let _lock = if fpath.exists() {
+        Some(lock_exclusive(&fpath).await?)
+    } else {
+        None
+    };
+
+    let mut fields = read_fields(&fpath).map_err(AppError::Internal)?;
+
+    let before = fields.len();
+    fields.retain(|f| f.name != name);
+    if fields.len() == before {
+        return Err(AppError::NotFound(format!("Field '{}' not found", name)));
+    }
+
+    write_fields(&fpath, &fields).map_err(AppError::Internal)?;
+
+    Ok(Json(
+        serde_json::json!({ "success": true, "deleted": name }),
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn field(name: &str, field_type: &str, options: &[&str]) -> FieldDefinition {
+        FieldDefinition {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            options: options.iter().map(|o| o.to_string()).collect(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        assert!(validate_schema(&[
+            field("severity", "select", &["low", "high"]),
+            field("customer", "text", &[]),
+        ])
+        .is_ok());
+        assert!(validate_schema(&[field("a", "text", &[]), field("a", "number", &[])]).is_err());
+        assert!(validate_schema(&[field("bad name", "text", &[])]).is_err());
+        assert!(validate_schema(&[field("x", "color", &[])]).is_err());
+        assert!(validate_schema(&[field("x", "select", &[])]).is_err());
+        assert!(validate_schema(&[field("x", "text", &["a"])]).is_err());
+    }
+
+    #[test]
+    fn test_check_custom_values() {
+        let fields = vec![
+            field("severity", "select", &["low", "high"]),
+            field("estimate", "number", &[]),
+            field("due", "date", &[]),
+        ];
+        let values =
+            |json: serde_json::Value| -> CustomValues { serde_json::from_value(json).unwrap() };
+
+        assert!(check_custom_values(
+            &fields,
+            &values(serde_json::json!({ "severity": "high", "estimate": 3, "due": "2026-02-01" }))
+        )
+        .is_ok());
+        assert!(
+            check_custom_values(&fields, &values(serde_json::json!({ "severity": null }))).is_ok()
+        );
+        assert!(check_custom_values(
+            &fields,
+            &values(serde_json::json!({ "severity": "urgent" }))
+        )
+        .is_err());
+        assert!(
+            check_custom_values(&fields, &values(serde_json::json!({ "estimate": "3" }))).is_err()
+        );
+        assert!(
+            check_custom_values(&fields, &values(serde_json::json!({ "due": "soon" }))).is_err()
+        );
+        assert!(
+            check_custom_values(&fields, &values(serde_json::json!({ "customer": "x" }))).is_err()
+        );
+    }
+
+    #[test]
+    fn test_fields_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("fields.json");
+        assert!(read_fields(&path).unwrap().is_empty());
+
+        let fields = vec![field("severity", "select", &["low", "high"])];
+        write_fields(&path, &fields).unwrap();
+        assert_eq!(read_fields(&path).unwrap(), fields);
+    }
+}
@@ -1,17 +1,26 @@
 //! Filesystem API route handlers.
 //!
-//! Provides endpoints for listing directories and checking path existence.
+//! Provides endpoints for listing directories, checking path existence,
+//! reading and describing files, uploading files into a project, searching a project's notes, and opening
+//! paths in other applications and terminals.
 
 use axum::{
-    extract::Query,
-    http::StatusCode,
-    response::IntoResponse,
+    body::Body,
+    extract::{Multipart, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use super::attachments::{validate_segment, MAX_ATTACHMENT_SIZE};
+use super::markdown::{extract_links, load_bead_ids};
+use super::projects::AppState;
+use super::trash::TRASH_DIR;
 use super::validate_path_security;
+use crate::error::AppError;
+use crate::logging::LogCommand;
 
 /// Query parameters for the list directory endpoint.
 #[derive(Debug, Deserialize)]
@@ -36,6 +45,127 @@ pub struct FsReadParams {
     pub project_path: String,
 }
 
+/// Query parameters for the ranged read endpoint.
+#[derive(Debug, Deserialize)]
+pub struct FsReadRangeParams {
+    /// The file path to read (relative, e.g., ".claude/logs/session.jsonl")
+    pub path: String,
+    /// The project path (absolute directory path)
+    pub project_path: String,
+    /// Byte offset to start reading at (default 0)
+    pub offset: Option<u64>,
+    /// Maximum number of bytes to read (default: to the end of the file)
+    pub limit: Option<u64>,
+}
+
+/// Project directories the ranged read endpoint serves files from.
+const READ_RANGE_DIRS: &[&str] = &[".claude/", ".beads/logs/"];
+
+/// Size of the chunks a ranged read is streamed in (64KB).
+const READ_RANGE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Query parameters for the stat endpoint.
+#[derive(Debug, Deserialize)]
+pub struct FsStatParams {
+    /// The path to describe (relative to the project, e.g., ".beads/attachments/bd-1/log.txt")
+    pub path: String,
+    /// The project path (absolute directory path)
+    pub project_path: String,
+}
+
+/// What the stat endpoint reports about a path.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FileStat {
+    /// The path, as requested
+    pub path: String,
+    #[serde(rename = "isDirectory")]
+    pub is_directory: bool,
+    /// Size in bytes
+    pub size: u64,
+    /// Last modification time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<chrono::DateTime<chrono::Utc>>,
+    /// MIME type guessed from the extension
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime: Option<String>,
+    /// Whether the content looks binary rather than text
+    pub binary: bool,
+    /// Number of lines, for text files up to 50MB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<u64>,
+}
+
+/// Bytes read from the start of a file to decide whether it is binary (8KB).
+const BINARY_SNIFF_SIZE: usize = 8192;
+
+/// Text files larger than this don't get a line count (50MB).
+const MAX_LINE_COUNT_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Query parameters for the upload endpoint.
+#[derive(Debug, Deserialize)]
+pub struct FsUploadParams {
+    /// Destination directory, relative to the project: `.designs/assets`,
+    /// `.beads/attachments`, or `.beads/attachments/{bead_id}`
+    pub dir: String,
+    /// The project path (absolute directory path)
+    pub project_path: String,
+}
+
+/// A file stored by the upload endpoint.
+#[derive(Debug, Serialize)]
+pub struct UploadedFile {
+    pub name: String,
+    /// Path relative to the project, for linking from markdown
+    pub path: String,
+    pub size: usize,
+}
+
+/// Maximum combined size of the files in one upload (25MB).
+pub const MAX_UPLOAD_TOTAL_SIZE: usize = 25 * 1024 * 1024;
+
+/// File extensions the upload endpoint accepts (compared lowercased).
+const UPLOAD_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "pdf", "txt", "log", "md", "json", "csv", "yaml", "yml",
+];
+
+/// Query parameters for the search endpoint.
+#[derive(Debug, Deserialize)]
+pub struct FsSearchParams {
+    /// The project path (absolute directory path)
+    pub project_path: String,
+    /// Text to find (case-insensitive)
+    pub q: String,
+    /// Maximum number of matches to return (default 100, max 500)
+    pub limit: Option<usize>,
+}
+
+/// A line matching a search.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct SearchMatch {
+    /// Path relative to the project
+    pub file: String,
+    /// 1-based line number
+    pub line: usize,
+    /// The matching line, trimmed and shortened around the match
+    pub snippet: String,
+}
+
+/// Project directories the search endpoint looks in.
+const SEARCH_DIRS: &[&str] = &[".designs", ".beads", ".claude"];
+
+/// Files larger than this are skipped by search (1MB).
+const MAX_SEARCH_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Most files a single search reads.
+const MAX_SEARCH_FILES: usize = 5000;
+
+/// Default and maximum number of search matches returned.
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+const MAX_SEARCH_LIMIT: usize = 500;
+
+/// Characters of context kept on each side of a match in a snippet.
+const SNIPPET_CONTEXT: usize = 80;
+
 /// Request body for opening a path in an external application.
 #[derive(Debug, Deserialize)]
 pub struct OpenExternalRequest {
@@ -45,6 +175,28 @@ pub struct OpenExternalRequest {
     pub target: String,
 }
 
+/// Request body for revealing a path in the system file manager.
+#[derive(Debug, Deserialize)]
+pub struct RevealRequest {
+    /// The file or directory to show
+    pub path: String,
+}
+
+/// Request body for opening a terminal.
+#[derive(Debug, Deserialize)]
+pub struct OpenTerminalRequest {
+    /// The directory to open the terminal in (a project or worktree)
+    pub path: String,
+}
+
+/// Terminal command used when none is configured.
+#[cfg(target_os = "macos")]
+const DEFAULT_TERMINAL_COMMAND: &str = "open -a Terminal {path}";
+#[cfg(windows)]
+const DEFAULT_TERMINAL_COMMAND: &str = "cmd /c start cmd";
+#[cfg(not(any(target_os = "macos", windows)))]
+const DEFAULT_TERMINAL_COMMAND: &str = "x-terminal-emulator";
+
 /// A single directory entry.
 #[derive(Debug, Serialize)]
 pub struct DirectoryEntry {
@@ -61,42 +213,26 @@ pub struct DirectoryEntry {
 ///
 /// Lists the contents of a directory, filtering out hidden files
 /// except for .beads directories.
-pub async fn list_directory(Query(params): Query<FsListParams>) -> impl IntoResponse {
+pub async fn list_directory(
+    Query(params): Query<FsListParams>,
+) -> Result<impl IntoResponse, AppError> {
     let dir_path = PathBuf::from(&params.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&dir_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&dir_path).map_err(AppError::Forbidden)?;
 
     // Check if path exists and is a directory
     if !dir_path.exists() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "Path does not exist" })),
-        );
+        return Err(AppError::NotFound("Path does not exist".to_string()));
     }
 
     if !dir_path.is_dir() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": "Path is not a directory" })),
-        );
+        return Err(AppError::BadRequest("Path is not a directory".to_string()));
     }
 
     // Read directory entries
-    let read_dir = match std::fs::read_dir(&dir_path) {
-        Ok(rd) => rd,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": format!("Failed to read directory: {}", e) })),
-            );
-        }
-    };
+    let read_dir = std::fs::read_dir(&dir_path)
+        .map_err(|e| AppError::Internal(format!("Failed to read directory: {}", e)))?;
 
     let mut entries: Vec<DirectoryEntry> = Vec::new();
 
@@ -127,54 +263,48 @@ pub async fn list_directory(Query(params): Query<FsListParams>) -> impl IntoResp
     }
 
     // Sort entries: directories first, then alphabetically
-    entries.sort_by(|a, b| {
-        match (a.is_directory, b.is_directory) {
-            (true, false) => std::cmp::Ordering::Less,
-            (false, true) => std::cmp::Ordering::Greater,
-            _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
-        }
+    entries.sort_by(|a, b| match (a.is_directory, b.is_directory) {
+        (true, false) => std::cmp::Ordering::Less,
+        (false, true) => std::cmp::Ordering::Greater,
+        _ => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
     });
 
-    (StatusCode::OK, Json(serde_json::json!({ "entries": entries })))
+    Ok(Json(serde_json::json!({ "entries": entries })))
 }
 
 /// GET /api/fs/exists?path=/some/path
 ///
 /// Checks if a path exists on the filesystem.
-pub async fn path_exists(Query(params): Query<FsExistsParams>) -> impl IntoResponse {
+pub async fn path_exists(
+    Query(params): Query<FsExistsParams>,
+) -> Result<impl IntoResponse, AppError> {
     let path = PathBuf::from(&params.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&path).map_err(AppError::Forbidden)?;
 
     let exists = path.exists();
 
-    (StatusCode::OK, Json(serde_json::json!({ "exists": exists })))
+    Ok(Json(serde_json::json!({ "exists": exists })))
 }
 
 /// GET /api/fs/read?path=.designs/{EPIC_ID}.md&project_path=/absolute/path
 ///
-/// Reads a design document file from the .designs directory.
+/// Reads a design document file from the .designs directory. Alongside the
+/// content, `links` lists the doc's references to the project's beads and
+/// to other design docs (see `markdown::extract_links`).
 ///
 /// # Security constraints:
 /// - Max file size: 100KB
 /// - Only .md extension allowed
 /// - Path must be within project directory
 /// - Path must start with ".designs/"
-pub async fn read_file(Query(params): Query<FsReadParams>) -> impl IntoResponse {
+pub async fn read_file(Query(params): Query<FsReadParams>) -> Result<impl IntoResponse, AppError> {
     // Security: Path must start with .designs/
     if !params.path.starts_with(".designs/") {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({
-                "error": "Access denied: path must start with .designs/"
-            })),
-        );
+        return Err(AppError::Forbidden(
+            "Access denied: path must start with .designs/".to_string(),
+        ));
     }
 
     // Parse relative path to validate extension
@@ -182,12 +312,9 @@ pub async fn read_file(Query(params): Query<FsReadParams>) -> impl IntoResponse
 
     // Security: Only .md extension allowed
     if relative_path.extension().and_then(|s| s.to_str()) != Some("md") {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({
-                "error": "Access denied: only .md files are allowed"
-            })),
-        );
+        return Err(AppError::Forbidden(
+            "Access denied: only .md files are allowed".to_string(),
+        ));
     }
 
     // Join project path with relative design doc path to get absolute path
@@ -195,72 +322,664 @@ pub async fn read_file(Query(params): Query<FsReadParams>) -> impl IntoResponse
     let file_path = project_root.join(&params.path);
 
     // Security: Validate absolute path is within allowed directories
-    if let Err(e) = validate_path_security(&file_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&file_path).map_err(AppError::Forbidden)?;
 
     // Check if file exists
     if !file_path.exists() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "File does not exist" })),
-        );
+        return Err(AppError::NotFound("File does not exist".to_string()));
     }
 
     // Check if path is a file (not a directory)
     if !file_path.is_file() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({ "error": "Path is not a file" })),
-        );
+        return Err(AppError::BadRequest("Path is not a file".to_string()));
     }
 
     // Security: Check file size (max 100KB)
-    let metadata = match std::fs::metadata(&file_path) {
-        Ok(m) => m,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to read file metadata: {}", e)
-                })),
-            );
-        }
-    };
+    let metadata = std::fs::metadata(&file_path)
+        .map_err(|e| AppError::Internal(format!("Failed to read file metadata: {}", e)))?;
 
     const MAX_FILE_SIZE: u64 = 100 * 1024; // 100KB
     if metadata.len() > MAX_FILE_SIZE {
-        return (
-            StatusCode::PAYLOAD_TOO_LARGE,
-            Json(serde_json::json!({
-                "error": format!("File too large: {} bytes (max {} bytes)", metadata.len(), MAX_FILE_SIZE)
-            })),
-        );
+        return Err(AppError::PayloadTooLarge(format!(
+            "File too large: {} bytes (max {} bytes)",
+            metadata.len(),
+            MAX_FILE_SIZE
+        )));
     }
 
     // Read file contents
-    let contents = match std::fs::read_to_string(&file_path) {
-        Ok(c) => c,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to read file: {}", e)
-                })),
-            );
+    let contents = std::fs::read_to_string(&file_path)
+        .map_err(|e| AppError::Internal(format!("Failed to read file: {}", e)))?;
+
+    let links = extract_links(&contents, &params.path, &load_bead_ids(&project_root));
+
+    Ok(Json(serde_json::json!({
+        "content": contents,
+        "path": params.path,
+        "links": links
+    })))
+}
+
+/// Resolves a `Range: bytes=...` header against a file of `size` bytes to
+/// an inclusive byte range.
+///
+/// Only a single range is supported; `None` means it can't be satisfied.
+fn parse_range(value: &str, size: u64) -> Option<(u64, u64)> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let last = size.checked_sub(1)?;
+    let (start, end) = match spec.split_once('-')? {
+        ("", suffix) => {
+            let len: u64 = suffix.trim().parse().ok()?;
+            if len == 0 {
+                return None;
+            }
+            (size.saturating_sub(len), last)
+        }
+        (start, "") => (start.trim().parse().ok()?, last),
+        (start, end) => (
+            start.trim().parse().ok()?,
+            end.trim().parse::<u64>().ok()?.min(last),
+        ),
+    };
+    (start <= end).then_some((start, end))
+}
+
+/// Resolves `offset` and `limit` against a file of `size` bytes to an
+/// inclusive byte range.
+fn offset_range(offset: Option<u64>, limit: Option<u64>, size: u64) -> Option<(u64, u64)> {
+    let start = offset.unwrap_or(0);
+    let len = limit.unwrap_or(u64::MAX).min(size.saturating_sub(start));
+    (start < size && len > 0).then(|| (start, start + len - 1))
+}
+
+/// Streams bytes `start..=end` of a file in chunks.
+fn stream_file_range(mut file: std::fs::File, start: u64, end: u64) -> Body {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<Vec<u8>, std::io::Error>>(4);
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = file.seek(SeekFrom::Start(start)) {
+            let _ = tx.blocking_send(Err(e));
+            return;
+        }
+        let mut remaining = end - start + 1;
+        while remaining > 0 {
+            let mut chunk = vec![0; remaining.min(READ_RANGE_CHUNK_SIZE as u64) as usize];
+            let result = file.read(&mut chunk).and_then(|n| match n {
+                0 => Err(std::io::ErrorKind::UnexpectedEof.into()),
+                n => Ok(n),
+            });
+            let message = result.map(|n| {
+                chunk.truncate(n);
+                remaining -= n as u64;
+                chunk
+            });
+            let failed = message.is_err();
+            // A failed send means the client went away
+            if tx.blocking_send(message).is_err() || failed {
+                return;
+            }
+        }
+    });
+
+    Body::from_stream(tokio_stream::wrappers::ReceiverStream::new(rx))
+}
+
+/// GET /api/fs/read-range?path=.claude/logs/session.jsonl&project_path=/absolute/path&offset=0&limit=65536
+///
+/// Streams part or all of a log or transcript file, with no size cap. The
+/// part is chosen with a `Range: bytes=...` header (single ranges only) or
+/// with `offset` and `limit`; either answers `206 Partial Content` with a
+/// `Content-Range` header, and a range past the end of the file answers
+/// 416 with the file's `size` in the error details. Without either, the
+/// whole file is returned.
+///
+/// # Security constraints:
+/// - Path must start with ".claude/" or ".beads/logs/"
+/// - Path must be within project directory
+pub async fn read_file_range(
+    headers: HeaderMap,
+    Query(params): Query<FsReadRangeParams>,
+) -> Result<Response, AppError> {
+    // Security: Path must be in a log directory and stay inside it
+    let relative_path = PathBuf::from(&params.path);
+    let allowed = READ_RANGE_DIRS.iter().any(|dir| params.path.starts_with(dir))
+        && !relative_path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir));
+    if !allowed {
+        return Err(AppError::Forbidden(format!(
+            "Access denied: path must start with {}",
+            READ_RANGE_DIRS.join(" or ")
+        )));
+    }
+
+    let file_path = PathBuf::from(&params.project_path).join(&relative_path);
+
+    // Security: Validate absolute path is within allowed directories
+    validate_path_security(&file_path).map_err(AppError::Forbidden)?;
+
+    if !file_path.exists() {
+        return Err(AppError::NotFound("File does not exist".to_string()));
+    }
+    if !file_path.is_file() {
+        return Err(AppError::BadRequest("Path is not a file".to_string()));
+    }
+
+    let file = std::fs::File::open(&file_path)
+        .map_err(|e| AppError::Internal(format!("Failed to open file: {}", e)))?;
+    let size = file
+        .metadata()
+        .map_err(|e| AppError::Internal(format!("Failed to read file metadata: {}", e)))?
+        .len();
+
+    let range_header = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
+    let partial = range_header.is_some() || params.offset.is_some() || params.limit.is_some();
+    let range = match range_header {
+        Some(value) => parse_range(value, size),
+        None if partial => offset_range(params.offset, params.limit, size),
+        None => size.checked_sub(1).map(|last| (0, last)),
+    };
+
+    let mime = mime_guess::from_path(&file_path)
+        .first()
+        .map(|m| m.essence_str().to_string())
+        .unwrap_or_else(|| "text/plain".to_string());
+    let content_type = if mime.starts_with("text/") {
+        format!("{}; charset=utf-8", mime)
+    } else {
+        mime
+    };
+
+    let Some((start, end)) = range else {
+        if partial {
+            return Err(AppError::RangeNotSatisfiable(format!(
+                "Requested range is outside the file ({} bytes)",
+                size
+            ))
+            .with_details(serde_json::json!({ "size": size })));
         }
+        // An empty file, read whole
+        return Ok((
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::ACCEPT_RANGES, "bytes".to_string()),
+            ],
+            Body::empty(),
+        )
+            .into_response());
     };
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "content": contents,
-            "path": params.path
-        })),
+    let mut response = (
+        if partial {
+            StatusCode::PARTIAL_CONTENT
+        } else {
+            StatusCode::OK
+        },
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CONTENT_LENGTH, (end - start + 1).to_string()),
+            (header::ACCEPT_RANGES, "bytes".to_string()),
+        ],
+        stream_file_range(file, start, end),
     )
+        .into_response();
+    if partial {
+        if let Ok(value) = format!("bytes {}-{}/{}", start, end, size).parse() {
+            response.headers_mut().insert(header::CONTENT_RANGE, value);
+        }
+    }
+    Ok(response)
+}
+
+/// Checks an upload destination, returning it as given if allowed.
+fn validate_upload_dir(dir: &str) -> Result<&str, String> {
+    let dir = dir.trim_end_matches('/');
+    if dir == ".designs/assets" || dir == ".beads/attachments" {
+        return Ok(dir);
+    }
+    if let Some(bead_id) = dir.strip_prefix(".beads/attachments/") {
+        validate_segment(bead_id, "bead id")?;
+        return Ok(dir);
+    }
+    Err(format!(
+        "Invalid upload directory '{}': must be .designs/assets or .beads/attachments",
+        dir
+    ))
+}
+
+/// Checks an uploaded file's name and extension.
+fn validate_upload_name(name: &str) -> Result<(), String> {
+    validate_segment(name, "filename")?;
+    let allowed = std::path::Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| UPLOAD_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+    if !allowed {
+        return Err(format!(
+            "File type not allowed: '{}' (allowed: {})",
+            name,
+            UPLOAD_EXTENSIONS.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// POST /api/fs/upload?dir=.designs/assets&project_path=/absolute/path
+///
+/// Uploads one or more files (multipart form, any field name) into
+/// `.designs/assets/` or `.beads/attachments/`, replacing files with the
+/// same name. Nothing is written unless every file is accepted.
+///
+/// # Security constraints:
+/// - Max file size: 10MB; max total size: 25MB
+/// - Only whitelisted extensions (images, PDFs, text, and logs)
+/// - Filenames must not contain path separators, `..`, or start with `.`
+/// - Path must be within allowed directories
+pub async fn upload_files(
+    Query(params): Query<FsUploadParams>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let dir = validate_upload_dir(&params.dir).map_err(AppError::BadRequest)?;
+    let project_root = PathBuf::from(&params.project_path);
+    validate_path_security(&project_root).map_err(AppError::Forbidden)?;
+    if !project_root.is_dir() {
+        return Err(AppError::NotFound(
+            "Project path does not exist".to_string(),
+        ));
+    }
+    let target_dir = project_root.join(dir);
+
+    let mut files = Vec::new();
+    let mut total = 0;
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(f)) => f,
+            Ok(None) => break,
+            Err(e) => {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid multipart body: {}",
+                    e
+                )));
+            }
+        };
+
+        let filename = match field.file_name() {
+            Some(name) => name.to_string(),
+            None => continue, // Not a file field
+        };
+        validate_upload_name(&filename).map_err(AppError::BadRequest)?;
+
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read upload: {}", e)))?;
+        if data.len() > MAX_ATTACHMENT_SIZE {
+            return Err(AppError::PayloadTooLarge(format!(
+                "File too large: {} ({} bytes, max {} bytes)",
+                filename,
+                data.len(),
+                MAX_ATTACHMENT_SIZE
+            )));
+        }
+        total += data.len();
+        if total > MAX_UPLOAD_TOTAL_SIZE {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Upload too large: over {} bytes in total",
+                MAX_UPLOAD_TOTAL_SIZE
+            )));
+        }
+        files.push((filename, data));
+    }
+
+    if files.is_empty() {
+        return Err(AppError::BadRequest("No files provided".to_string()));
+    }
+
+    std::fs::create_dir_all(&target_dir)
+        .map_err(|e| AppError::Internal(format!("Failed to create directory: {}", e)))?;
+    validate_path_security(&target_dir).map_err(AppError::Forbidden)?;
+
+    let mut uploaded = Vec::new();
+    for (name, data) in files {
+        std::fs::write(target_dir.join(&name), &data)
+            .map_err(|e| AppError::Internal(format!("Failed to write file: {}", e)))?;
+        uploaded.push(UploadedFile {
+            path: format!("{}/{}", dir, name),
+            name,
+            size: data.len(),
+        });
+    }
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({ "success": true, "uploaded": uploaded })),
+    ))
+}
+
+/// Shortens a matching line to the text around the match at byte `at`.
+fn snippet(line: &str, at: usize, len: usize) -> String {
+    let line_len = line.chars().count();
+    let start = line[..at].chars().count();
+    let end = start + line[at..at + len].chars().count();
+    let from = start.saturating_sub(SNIPPET_CONTEXT);
+    let to = (end + SNIPPET_CONTEXT).min(line_len);
+
+    let mut out: String = line.chars().skip(from).take(to - from).collect();
+    if from > 0 {
+        out.insert(0, '…');
+    }
+    if to < line_len {
+        out.push('…');
+    }
+    out.trim().to_string()
+}
+
+/// Whether the start of a file looks binary: it contains a NUL byte or
+/// isn't UTF-8 (a multi-byte character cut off at the end is allowed).
+fn looks_binary(data: &[u8]) -> bool {
+    let head = &data[..data.len().min(BINARY_SNIFF_SIZE)];
+    if head.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(head) {
+        Ok(_) => false,
+        Err(e) => e.error_len().is_some(),
+    }
+}
+
+/// Counts the lines in a file, including a last line without a newline.
+fn count_lines(path: &Path) -> std::io::Result<u64> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![0; READ_RANGE_CHUNK_SIZE];
+    let mut lines = 0;
+    let mut last = b'\n';
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        lines += buf[..n].iter().filter(|&&b| b == b'\n').count() as u64;
+        last = buf[n - 1];
+    }
+    if last != b'\n' {
+        lines += 1;
+    }
+    Ok(lines)
+}
+
+/// Describes the file or directory at `file_path`.
+fn stat_path(path: String, file_path: &Path) -> std::io::Result<FileStat> {
+    use std::io::Read;
+
+    let metadata = std::fs::metadata(file_path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .map(chrono::DateTime::<chrono::Utc>::from);
+    if metadata.is_dir() {
+        return Ok(FileStat {
+            path,
+            is_directory: true,
+            size: metadata.len(),
+            modified,
+            mime: None,
+            binary: false,
+            lines: None,
+        });
+    }
+
+    let mut head = Vec::with_capacity(BINARY_SNIFF_SIZE);
+    std::fs::File::open(file_path)?
+        .take(BINARY_SNIFF_SIZE as u64)
+        .read_to_end(&mut head)?;
+    let binary = looks_binary(&head);
+    let lines = if binary || metadata.len() > MAX_LINE_COUNT_SIZE {
+        None
+    } else {
+        Some(count_lines(file_path)?)
+    };
+
+    Ok(FileStat {
+        path,
+        is_directory: false,
+        size: metadata.len(),
+        modified,
+        mime: mime_guess::from_path(file_path)
+            .first()
+            .map(|m| m.essence_str().to_string()),
+        binary,
+        lines,
+    })
+}
+
+/// GET /api/fs/stat?path=.beads/attachments/bd-1/log.txt&project_path=/absolute/path
+///
+/// Describes a file in the project without reading it all: size,
+/// modification time, MIME type guessed from the extension, whether it
+/// looks binary, and (for text files up to 50MB) its line count. Enough
+/// for the UI to choose between showing a file inline, previewing an
+/// image, or offering a download.
+///
+/// # Security constraints:
+/// - Path must be within project directory
+pub async fn stat_file(Query(params): Query<FsStatParams>) -> Result<impl IntoResponse, AppError> {
+    // Security: Path must stay inside the project
+    let relative_path = PathBuf::from(&params.path);
+    if relative_path.is_absolute()
+        || relative_path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(AppError::Forbidden(
+            "Access denied: path must be inside the project".to_string(),
+        ));
+    }
+
+    let file_path = PathBuf::from(&params.project_path).join(&relative_path);
+
+    // Security: Validate absolute path is within allowed directories
+    validate_path_security(&file_path).map_err(AppError::Forbidden)?;
+
+    if !file_path.exists() {
+        return Err(AppError::NotFound("Path does not exist".to_string()));
+    }
+
+    let stat = tokio::task::spawn_blocking(move || stat_path(params.path, &file_path))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to read file: {}", e)))?
+        .map_err(|e| AppError::Internal(format!("Failed to read file: {}", e)))?;
+
+    Ok(Json(stat))
+}
+
+/// Searches one file, appending matches until `results` holds `limit`.
+///
+/// Binary files (see [`looks_binary`]) and files that aren't UTF-8 are
+/// skipped.
+fn search_file(
+    path: &Path,
+    file: &str,
+    needle: &str,
+    limit: usize,
+    results: &mut Vec<SearchMatch>,
+) {
+    let data = match std::fs::read(path) {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+    if looks_binary(&data) {
+        return;
+    }
+    let text = match std::str::from_utf8(&data) {
+        Ok(t) => t,
+        Err(_) => return,
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        if results.len() >= limit {
+            return;
+        }
+        if let Some((at, len)) = find_ignore_case(line, needle) {
+            results.push(SearchMatch {
+                file: file.to_string(),
+                line: i + 1,
+                snippet: snippet(line, at, len),
+            });
+        }
+    }
+}
+
+/// Finds lowercase `needle` in `line` ignoring case, returning the byte
+/// offset and length of the match in `line` itself.
+///
+/// Lowercasing can change a character's byte length, so offsets into the
+/// lowercased line are mapped back to the characters they came from.
+fn find_ignore_case(line: &str, needle: &str) -> Option<(usize, usize)> {
+    let mut lower = String::with_capacity(line.len());
+    // Byte range in `line` of the character each lowercased byte came from
+    let mut origin = Vec::with_capacity(line.len());
+    for (start, c) in line.char_indices() {
+        let end = start + c.len_utf8();
+        for l in c.to_lowercase() {
+            lower.push(l);
+            origin.extend(std::iter::repeat_n((start, end), l.len_utf8()));
+        }
+    }
+
+    let at = lower.find(needle)?;
+    if needle.is_empty() {
+        return Some((origin.get(at).map_or(line.len(), |o| o.0), 0));
+    }
+    let start = origin[at].0;
+    let end = origin[at + needle.len() - 1].1;
+    Some((start, end - start))
+}
+
+/// Walks `dir` in name order, searching each file. Symlinks are not
+/// followed, and the project's trash is skipped.
+///
+/// Returns false once the file or result budget runs out.
+fn search_dir(
+    root: &Path,
+    dir: &Path,
+    needle: &str,
+    limit: usize,
+    files_left: &mut usize,
+    results: &mut Vec<SearchMatch>,
+) -> bool {
+    let mut entries: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(read_dir) => read_dir.flatten().collect(),
+        Err(_) => return true,
+    };
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        if results.len() >= limit || *files_left == 0 {
+            return false;
+        }
+        let file_type = match entry.file_type() {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            if path == root.join(TRASH_DIR) {
+                continue;
+            }
+            if !search_dir(root, &path, needle, limit, files_left, results) {
+                return false;
+            }
+        } else if file_type.is_file() {
+            let too_large = entry
+                .metadata()
+                .map_or(true, |m| m.len() > MAX_SEARCH_FILE_SIZE);
+            if too_large {
+                continue;
+            }
+            *files_left -= 1;
+            let file = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            search_file(&path, &file, needle, limit, results);
+        }
+    }
+    true
+}
+
+/// Searches the project's `.designs/`, `.beads/`, and `.claude/` directories.
+///
+/// Returns the matches and whether the search stopped early.
+fn search_project(root: &Path, q: &str, limit: usize) -> (Vec<SearchMatch>, bool) {
+    let needle = q.to_lowercase();
+    let mut results = Vec::new();
+    let mut files_left = MAX_SEARCH_FILES;
+    let mut complete = true;
+    // One match past the limit tells whether there were more
+    for dir in SEARCH_DIRS {
+        complete = search_dir(
+            root,
+            &root.join(dir),
+            &needle,
+            limit + 1,
+            &mut files_left,
+            &mut results,
+        );
+        if !complete {
+            break;
+        }
+    }
+    let truncated = !complete || results.len() > limit;
+    results.truncate(limit);
+    (results, truncated)
+}
+
+/// GET /api/fs/search?project_path=/absolute/path&q=text&limit=100
+///
+/// Finds lines containing `q` (case-insensitive) in the project's
+/// `.designs/`, `.beads/`, and `.claude/` directories, returning each
+/// match's file, line number, and snippet. `truncated` is set when the
+/// result limit or the number of files searched ran out.
+///
+/// # Security constraints:
+/// - Path must be within allowed directories
+/// - Files over 1MB and binary files are skipped; symlinks are not followed
+pub async fn search_files(
+    Query(params): Query<FsSearchParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let q = params.q.trim().to_string();
+    if q.is_empty() {
+        return Err(AppError::BadRequest("Search text is required".to_string()));
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let project_root = PathBuf::from(&params.project_path);
+    validate_path_security(&project_root).map_err(AppError::Forbidden)?;
+    if !project_root.is_dir() {
+        return Err(AppError::NotFound(
+            "Project path does not exist".to_string(),
+        ));
+    }
+
+    let (results, truncated) =
+        tokio::task::spawn_blocking(move || search_project(&project_root, &q, limit))
+            .await
+            .map_err(|e| AppError::Internal(format!("Search failed: {}", e)))?;
+
+    Ok(Json(serde_json::json!({
+        "results": results,
+        "truncated": truncated
+    })))
 }
 
 /// POST /api/fs/open-external
@@ -270,30 +989,24 @@ pub async fn read_file(Query(params): Query<FsReadParams>) -> impl IntoResponse
 /// # Security constraints:
 /// - Path must be within user's home directory
 /// - Target must be one of: "vscode", "cursor", "finder"
-pub async fn open_external(Json(request): Json<OpenExternalRequest>) -> impl IntoResponse {
+pub async fn open_external(
+    Json(request): Json<OpenExternalRequest>,
+) -> Result<impl IntoResponse, AppError> {
     let path = PathBuf::from(&request.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&path).map_err(AppError::Forbidden)?;
 
     // Check if path exists
     if !path.exists() {
-        return (
-            StatusCode::NOT_FOUND,
-            Json(serde_json::json!({ "error": "Path does not exist" })),
-        );
+        return Err(AppError::NotFound("Path does not exist".to_string()));
     }
 
     // Execute the appropriate command based on target
     let result = match request.target.as_str() {
         "vscode" => {
             // Try "code" command first, fall back to macOS open command
-            let code_result = std::process::Command::new("code").arg(&path).spawn();
+            let code_result = std::process::Command::new("code").arg(&path).logged().spawn();
             if code_result.is_err() {
                 // Fallback for macOS: use open -a "Visual Studio Code"
                 #[cfg(target_os = "macos")]
@@ -301,6 +1014,7 @@ pub async fn open_external(Json(request): Json<OpenExternalRequest>) -> impl Int
                     std::process::Command::new("open")
                         .args(["-a", "Visual Studio Code"])
                         .arg(&path)
+                        .logged()
                         .spawn()
                 }
                 #[cfg(not(target_os = "macos"))]
@@ -313,7 +1027,7 @@ pub async fn open_external(Json(request): Json<OpenExternalRequest>) -> impl Int
         }
         "cursor" => {
             // Try "cursor" command first, fall back to macOS open command
-            let cursor_result = std::process::Command::new("cursor").arg(&path).spawn();
+            let cursor_result = std::process::Command::new("cursor").arg(&path).logged().spawn();
             if cursor_result.is_err() {
                 // Fallback for macOS: use open -a "Cursor"
                 #[cfg(target_os = "macos")]
@@ -321,6 +1035,7 @@ pub async fn open_external(Json(request): Json<OpenExternalRequest>) -> impl Int
                     std::process::Command::new("open")
                         .args(["-a", "Cursor"])
                         .arg(&path)
+                        .logged()
                         .spawn()
                 }
                 #[cfg(not(target_os = "macos"))]
@@ -336,42 +1051,181 @@ pub async fn open_external(Json(request): Json<OpenExternalRequest>) -> impl Int
             // On macOS: opens Finder, on Linux: file manager, on Windows: Explorer
             match open::that(&path) {
                 Ok(_) => {
-                    return (
-                        StatusCode::OK,
-                        Json(serde_json::json!({ "success": true })),
-                    );
+                    return Ok(Json(serde_json::json!({ "success": true })));
                 }
                 Err(e) => {
-                    return (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({
-                            "error": format!("Failed to open: {}", e)
-                        })),
-                    );
+                    return Err(AppError::Internal(format!("Failed to open: {}", e)));
                 }
             }
         }
         _ => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({
-                    "error": "Invalid target. Must be 'vscode', 'cursor', or 'finder'"
-                })),
-            );
+            return Err(AppError::BadRequest(
+                "Invalid target. Must be 'vscode', 'cursor', or 'finder'".to_string(),
+            ));
         }
     };
 
     match result {
-        Ok(_) => (
-            StatusCode::OK,
-            Json(serde_json::json!({ "success": true })),
-        ),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to open: {}. Make sure the application is installed.", e)
-            })),
-        ),
+        Ok(_) => Ok(Json(serde_json::json!({ "success": true }))),
+        Err(e) => Err(AppError::Internal(format!(
+            "Failed to open: {}. Make sure the application is installed.",
+            e
+        ))),
+    }
+}
+
+/// Encodes an absolute path as a `file://` URI.
+#[cfg_attr(any(target_os = "macos", windows), allow(dead_code))]
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// Opens the system file manager with `path` selected.
+#[cfg(target_os = "macos")]
+fn reveal(path: &Path) -> Result<(), String> {
+    std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .logged()
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Opens the system file manager with `path` selected.
+#[cfg(windows)]
+fn reveal(path: &Path) -> Result<(), String> {
+    let mut select = std::ffi::OsString::from("/select,");
+    select.push(path);
+    std::process::Command::new("explorer")
+        .arg(select)
+        .logged()
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Opens the system file manager with `path` selected.
+///
+/// File managers implementing the freedesktop `FileManager1` interface
+/// (Nautilus, Dolphin, Nemo, ...) select the item; otherwise the containing
+/// directory is opened with `xdg-open`.
+#[cfg(not(any(target_os = "macos", windows)))]
+fn reveal(path: &Path) -> Result<(), String> {
+    let shown = std::process::Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", file_uri(path)))
+        .arg("string:")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .logged()
+        .status()
+        .is_ok_and(|status| status.success());
+    if shown {
+        return Ok(());
+    }
+    open::that(path.parent().unwrap_or(path)).map_err(|e| e.to_string())
+}
+
+/// POST /api/fs/reveal
+///
+/// Opens the system file manager (Finder, Explorer, or the desktop's file
+/// manager) with a repo, worktree, or design doc path selected, rather than
+/// opening the path itself like `open-external` does.
+///
+/// # Security constraints:
+/// - Path must be within user's home directory
+pub async fn reveal_path(
+    Json(request): Json<RevealRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let path = PathBuf::from(&request.path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&path).map_err(AppError::Forbidden)?;
+
+    if !path.exists() {
+        return Err(AppError::NotFound("Path does not exist".to_string()));
+    }
+    let path = path
+        .canonicalize()
+        .map_err(|e| AppError::Internal(format!("Failed to resolve path: {}", e)))?;
+
+    tokio::task::spawn_blocking(move || reveal(&path))
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to reveal: {}", e)))?
+        .map_err(|e| AppError::Internal(format!("Failed to reveal: {}", e)))?;
+
+    Ok(Json(serde_json::json!({ "success": true })))
+}
+
+/// Builds the command that opens a terminal in `dir`.
+///
+/// The configured command is split on whitespace and `{path}` is replaced
+/// with the directory in each argument; the command also starts in `dir`,
+/// for terminals that open in their working directory.
+fn terminal_command(template: &str, dir: &Path) -> Option<std::process::Command> {
+    let dir_str = dir.to_string_lossy();
+    let mut parts = template
+        .split_whitespace()
+        .map(|part| part.replace("{path}", &dir_str));
+    let mut command = std::process::Command::new(parts.next()?);
+    command.args(parts).current_dir(dir);
+    Some(command)
+}
+
+/// POST /api/fs/open-terminal
+///
+/// Opens a terminal emulator in a project or worktree directory, using the
+/// `terminalCommand` setting (see `PUT /api/settings`) or the platform's
+/// default terminal.
+///
+/// # Security constraints:
+/// - Path must be within user's home directory
+/// - Path must be a directory
+pub async fn open_terminal(
+    State(db): State<AppState>,
+    Json(request): Json<OpenTerminalRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let path = PathBuf::from(&request.path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&path).map_err(AppError::Forbidden)?;
+
+    if !path.exists() {
+        return Err(AppError::NotFound("Path does not exist".to_string()));
+    }
+    if !path.is_dir() {
+        return Err(AppError::BadRequest("Path is not a directory".to_string()));
+    }
+
+    let template = db
+        .get_settings()?
+        .terminal_command
+        .unwrap_or_else(|| DEFAULT_TERMINAL_COMMAND.to_string());
+    let mut command = terminal_command(&template, &path)
+        .ok_or_else(|| AppError::BadRequest("Terminal command is empty".to_string()))?;
+
+    match command.logged().spawn() {
+        Ok(_) => Ok(Json(serde_json::json!({ "success": true }))),
+        Err(e) => Err(AppError::Internal(format!(
+            "Failed to open terminal with '{}': {}. Set the terminal command in settings.",
+            template, e
+        ))),
     }
 }
 
@@ -389,4 +1243,173 @@ mod tests {
         let json = serde_json::to_string(&entry).unwrap();
         assert!(json.contains("\"isDirectory\":true"));
     }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=900-", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-5000", 1000), Some((0, 999)));
+        assert_eq!(parse_range("bytes=500-5000", 1000), Some((500, 999)));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=0-1,5-9", 1000), None);
+        assert_eq!(parse_range("bytes=9-5", 1000), None);
+        assert_eq!(parse_range("lines=0-5", 1000), None);
+        assert_eq!(parse_range("bytes=0-", 0), None);
+    }
+
+    #[test]
+    fn test_offset_range() {
+        assert_eq!(offset_range(None, Some(10), 100), Some((0, 9)));
+        assert_eq!(offset_range(Some(90), Some(50), 100), Some((90, 99)));
+        assert_eq!(offset_range(Some(50), None, 100), Some((50, 99)));
+        assert_eq!(offset_range(Some(100), None, 100), None);
+        assert_eq!(offset_range(None, Some(0), 100), None);
+    }
+
+    #[tokio::test]
+    async fn test_stream_file_range() {
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(tmp.path(), "0123456789".repeat(20_000)).unwrap();
+        let file = std::fs::File::open(tmp.path()).unwrap();
+
+        let body = stream_file_range(file, 5, 100_004);
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(bytes.len(), 100_000);
+        assert!(bytes.starts_with(b"56789012"));
+    }
+
+    #[test]
+    fn test_looks_binary() {
+        assert!(!looks_binary(b"plain text\n"));
+        assert!(!looks_binary("caf\u{e9}".as_bytes()));
+        // A character cut off at the end of the sniffed bytes is still text
+        assert!(!looks_binary(&"\u{e9}".as_bytes()[..1]));
+        assert!(looks_binary(b"\x89PNG\r\n\x1a\n\0\0"));
+        assert!(looks_binary(b"\xff\xfe text"));
+    }
+
+    #[test]
+    fn test_stat_path() {
+        let tmp = tempfile::tempdir().unwrap();
+        let log = tmp.path().join("hook.log");
+        std::fs::write(&log, "one\ntwo\nthree").unwrap();
+        let image = tmp.path().join("shot.png");
+        std::fs::write(&image, b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+
+        let stat = stat_path("hook.log".to_string(), &log).unwrap();
+        assert_eq!(stat.size, 13);
+        assert!(!stat.binary);
+        assert_eq!(stat.lines, Some(3));
+        assert!(stat.modified.is_some());
+
+        let stat = stat_path("shot.png".to_string(), &image).unwrap();
+        assert!(stat.binary);
+        assert_eq!(stat.mime.as_deref(), Some("image/png"));
+        assert_eq!(stat.lines, None);
+
+        let stat = stat_path(".".to_string(), tmp.path()).unwrap();
+        assert!(stat.is_directory);
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("  find me  ", 2, 4), "find me");
+        let long = format!("{}needle{}", "a".repeat(100), "b".repeat(100));
+        let s = snippet(&long, 100, 6);
+        assert!(s.starts_with('…') && s.ends_with('…'));
+        assert_eq!(s.chars().count(), 80 + 6 + 80 + 2);
+    }
+
+    #[test]
+    fn test_find_ignore_case() {
+        assert_eq!(find_ignore_case("Use OAuth", "oauth"), Some((4, 5)));
+        assert_eq!(find_ignore_case("Use OAuth", "login"), None);
+        // 'ẞ' lowercases to a shorter 'ß' and 'İ' to a longer "i̇", so the
+        // lowercased line is the same length but its offsets don't line up
+        let line = "ẞİx";
+        assert_eq!(find_ignore_case(line, "i"), Some((3, 2)));
+        assert_eq!(snippet(line, 3, 2), line);
+        assert_eq!(find_ignore_case(line, "ßi\u{307}x"), Some((0, line.len())));
+        assert_eq!(find_ignore_case(line, "x"), Some((5, 1)));
+    }
+
+    #[test]
+    fn test_search_project() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        std::fs::create_dir_all(root.join(".designs")).unwrap();
+        std::fs::create_dir_all(root.join(".claude/agents")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join(".designs/bd-1.md"),
+            "# Login\n\nUse OAuth for login\n",
+        )
+        .unwrap();
+        std::fs::write(root.join(".claude/agents/dev.md"), "handles LOGIN bugs").unwrap();
+        std::fs::write(root.join(".designs/image.png"), b"login\0\x89PNG").unwrap();
+        std::fs::write(root.join("src/main.rs"), "// login").unwrap();
+
+        let (results, truncated) = search_project(root, "Login", 10);
+        assert!(!truncated);
+        let found: Vec<(&str, usize)> = results.iter().map(|m| (m.file.as_str(), m.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (".designs/bd-1.md", 1),
+                (".designs/bd-1.md", 3),
+                (".claude/agents/dev.md", 1)
+            ]
+        );
+        assert_eq!(results[1].snippet, "Use OAuth for login");
+
+        let (results, truncated) = search_project(root, "login", 2);
+        assert_eq!(results.len(), 2);
+        assert!(truncated);
+        let (_, truncated) = search_project(root, "login", 3);
+        assert!(!truncated);
+    }
+
+    #[test]
+    fn test_terminal_command() {
+        let dir = Path::new("/home/me/My Project");
+        let command = terminal_command("wezterm start --cwd {path}", dir).unwrap();
+        assert_eq!(command.get_program(), "wezterm");
+        let args: Vec<_> = command.get_args().collect();
+        assert_eq!(args, vec!["start", "--cwd", "/home/me/My Project"]);
+        assert_eq!(command.get_current_dir(), Some(dir));
+        assert!(terminal_command("  ", dir).is_none());
+    }
+
+    #[test]
+    fn test_file_uri() {
+        assert_eq!(
+            file_uri(Path::new("/home/me/My Project/.designs/a#1.md")),
+            "file:///home/me/My%20Project/.designs/a%231.md"
+        );
+    }
+
+    #[test]
+    fn test_validate_upload_dir() {
+        assert_eq!(
+            validate_upload_dir(".designs/assets/").unwrap(),
+            ".designs/assets"
+        );
+        assert!(validate_upload_dir(".beads/attachments").is_ok());
+        assert!(validate_upload_dir(".beads/attachments/bd-1").is_ok());
+        assert!(validate_upload_dir(".beads/attachments/..").is_err());
+        assert!(validate_upload_dir(".beads/attachments/a/b").is_err());
+        assert!(validate_upload_dir(".beads").is_err());
+        assert!(validate_upload_dir("src").is_err());
+    }
+
+    #[test]
+    fn test_validate_upload_name() {
+        assert!(validate_upload_name("screenshot.PNG").is_ok());
+        assert!(validate_upload_name("server.log").is_ok());
+        assert!(validate_upload_name("run.sh").is_err());
+        assert!(validate_upload_name("README").is_err());
+        assert!(validate_upload_name("../x.png").is_err());
+        assert!(validate_upload_name(".hidden.png").is_err());
+    }
 }
@@ -1,11 +1,19 @@
 //! Git route handlers for checking repository status.
 //!
-//! Provides endpoints for querying git branch status and repository state.
+//! Provides endpoints for querying git branch status, repository state,
+//! and the history and past revisions of design docs.
 
-use axum::{extract::Query, http::StatusCode, response::IntoResponse, Json};
+use axum::{extract::Query, response::IntoResponse, Json};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use tokio::process::Command;
+use std::path::{Path, PathBuf};
+
+use super::validate_path_security;
+use crate::error::AppError;
+use crate::git_backend;
+
+/// Default number of commits returned by the file history endpoint.
+const DEFAULT_HISTORY_LIMIT: usize = 50;
 
 /// Query parameters for the branch status endpoint.
 #[derive(Deserialize)]
@@ -43,22 +51,12 @@ pub async fn branch_status(Query(params): Query<GitStatusParams>) -> impl IntoRe
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Repository path does not exist: {}", params.path)
-            })),
-        )
+        return AppError::BadRequest(format!("Repository path does not exist: {}", params.path))
             .into_response();
     }
 
     if !repo_path.is_dir() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Path is not a directory: {}", params.path)
-            })),
-        )
+        return AppError::BadRequest(format!("Path is not a directory: {}", params.path))
             .into_response();
     }
 
@@ -92,60 +90,244 @@ pub async fn branch_status(Query(params): Query<GitStatusParams>) -> impl IntoRe
 
 /// Check if a branch exists in the repository.
 async fn check_branch_exists(repo_path: &str, branch: &str) -> bool {
-    let output = Command::new("git")
-        .args(["rev-parse", "--verify", branch])
-        .current_dir(repo_path)
-        .output()
-        .await;
-
-    matches!(output, Ok(o) if o.status.success())
+    let (repo_path, branch) = (PathBuf::from(repo_path), branch.to_string());
+    let result =
+        tokio::task::spawn_blocking(move || git_backend::revision_exists(&repo_path, &branch))
+            .await;
+    matches!(result, Ok(Ok(true)))
 }
 
 /// Get the number of commits ahead and behind relative to main.
 async fn get_ahead_behind(repo_path: &str, branch: &str) -> (i32, i32) {
+    let (repo_path, branch) = (PathBuf::from(repo_path), branch.to_string());
     // Try both 'main' and 'master' as the base branch
-    let base_branches = ["main", "master"];
-
-    for base in base_branches {
-        let output = Command::new("git")
-            .args([
-                "rev-list",
-                "--left-right",
-                "--count",
-                &format!("{}...{}", base, branch),
-            ])
-            .current_dir(repo_path)
-            .output()
-            .await;
+    let result = tokio::task::spawn_blocking(move || {
+        git_backend::ahead_behind(&repo_path, &branch, &["main", "master"])
+    })
+    .await;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let parts: Vec<&str> = stdout.trim().split('\t').collect();
-                if parts.len() == 2 {
-                    let behind = parts[0].parse().unwrap_or(0);
-                    let ahead = parts[1].parse().unwrap_or(0);
-                    return (ahead, behind);
-                }
-            }
-        }
+    match result {
+        Ok(Ok(Some((ahead, behind)))) => (ahead as i32, behind as i32),
+        _ => (0, 0),
     }
-
-    (0, 0)
 }
 
 /// Check if the repository has uncommitted changes.
 async fn check_dirty(repo_path: &str) -> bool {
-    let output = Command::new("git")
-        .args(["status", "--porcelain"])
-        .current_dir(repo_path)
-        .output()
-        .await;
-
-    match output {
-        Ok(o) => !o.stdout.is_empty(),
-        Err(_) => false,
+    let repo_path = PathBuf::from(repo_path);
+    let result = tokio::task::spawn_blocking(move || git_backend::is_dirty(&repo_path)).await;
+    matches!(result, Ok(Ok(true)))
+}
+
+/// Query parameters for the file history endpoint.
+#[derive(Deserialize)]
+pub struct FileHistoryParams {
+    /// The design doc path (relative, e.g., ".designs/epic.md").
+    pub path: String,
+    /// The project path (absolute directory path).
+    pub project_path: String,
+    /// Maximum number of commits to return (default: 50).
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// A commit that changed a file.
+#[derive(Serialize)]
+pub struct FileCommit {
+    /// Commit ID.
+    pub id: String,
+    /// First line of the commit message.
+    pub summary: String,
+    /// Author name.
+    pub author: String,
+    /// Commit time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// Response body for the file history endpoint.
+#[derive(Serialize)]
+pub struct FileHistoryResponse {
+    /// The design doc path, as requested.
+    pub path: String,
+    /// Commits that changed the file, newest first.
+    pub commits: Vec<FileCommit>,
+    /// Author of the most recent commit, if the file has been committed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_author: Option<String>,
+    /// Time of the most recent commit, if the file has been committed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+/// Checks a design doc path and project, returning the doc path relative to
+/// the project and the project root.
+fn design_doc_paths(path: &str, project_path: &str) -> Result<(PathBuf, PathBuf), AppError> {
+    // Security: Path must start with .designs/ and stay inside it
+    let relative_path = PathBuf::from(path);
+    if !path.starts_with(".designs/")
+        || relative_path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(AppError::Forbidden(
+            "Access denied: path must start with .designs/".to_string(),
+        ));
+    }
+
+    // Security: Only .md extension allowed
+    if relative_path.extension().and_then(|s| s.to_str()) != Some("md") {
+        return Err(AppError::Forbidden(
+            "Access denied: only .md files are allowed".to_string(),
+        ));
+    }
+
+    let project_root = PathBuf::from(project_path);
+    validate_path_security(&project_root).map_err(AppError::Forbidden)?;
+
+    if !project_root.is_dir() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            project_path
+        )));
     }
+
+    Ok((relative_path, project_root))
+}
+
+/// Loads the history of a design doc for the file history endpoints.
+async fn design_doc_history(
+    path: String,
+    project_path: &str,
+    limit: Option<usize>,
+) -> Result<FileHistoryResponse, AppError> {
+    let (relative_path, project_root) = design_doc_paths(&path, project_path)?;
+
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    let history = tokio::task::spawn_blocking(move || {
+        git_backend::file_history(&project_root, &relative_path, limit)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to read file history: {}", e)))?
+    .map_err(|e| AppError::Internal(format!("Failed to read file history: {}", e.message())))?;
+
+    let commits: Vec<FileCommit> = history
+        .into_iter()
+        .map(|c| FileCommit {
+            id: c.id,
+            summary: c.summary,
+            author: c.author,
+            time: DateTime::<Utc>::from_timestamp(c.time, 0),
+        })
+        .collect();
+    let last_author = commits.first().map(|c| c.author.clone());
+    let last_modified = commits.first().and_then(|c| c.time);
+
+    Ok(FileHistoryResponse {
+        path,
+        commits,
+        last_author,
+        last_modified,
+    })
+}
+
+/// Get the commit history of a design doc.
+///
+/// # Endpoint
+///
+/// `GET /api/git/file-history?project_path=...&path=.designs/epic.md&limit=50`
+///
+/// # Response
+///
+/// Returns the commits on HEAD that changed the file, newest first, and
+/// who last changed it and when. A file that was never committed has an
+/// empty history.
+///
+/// # Security constraints:
+/// - Only .md extension allowed
+/// - Path must be within project directory
+/// - Path must start with ".designs/"
+pub async fn file_history(
+    Query(params): Query<FileHistoryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let history = design_doc_history(params.path, &params.project_path, params.limit).await?;
+    Ok(Json(history))
+}
+
+/// Query parameters for the design doc history endpoint.
+#[derive(Deserialize)]
+pub struct DesignHistoryParams {
+    /// The design doc path (relative, e.g., ".designs/epic.md").
+    pub path: String,
+    /// The project path (absolute directory path).
+    pub project_path: String,
+    /// A commit to read the doc at; without it the history is listed.
+    #[serde(default)]
+    pub rev: Option<String>,
+    /// Maximum number of commits to return (default: 50).
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Response body for a design doc read at a past commit.
+#[derive(Serialize)]
+pub struct DesignRevisionResponse {
+    /// The design doc path, as requested.
+    pub path: String,
+    /// Full ID of the commit the content was read from.
+    pub rev: String,
+    /// The doc's content at that commit.
+    pub content: String,
+}
+
+/// Get the history of a design doc, or its content at one revision.
+///
+/// # Endpoint
+///
+/// `GET /api/designs/history?project_path=...&path=.designs/epic.md`
+/// `GET /api/designs/history?project_path=...&path=.designs/epic.md&rev=abc123`
+///
+/// # Response
+///
+/// Without `rev`, returns the same history as `/api/git/file-history`. With
+/// `rev` (a commit ID from that history, or any revision git understands),
+/// returns the doc's content as of that commit, or 404 if the doc didn't
+/// exist there.
+///
+/// # Security constraints:
+/// - Only .md extension allowed
+/// - Path must be within project directory
+/// - Path must start with ".designs/"
+pub async fn design_history(
+    Query(params): Query<DesignHistoryParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let Some(rev) = params.rev else {
+        let history = design_doc_history(params.path, &params.project_path, params.limit).await?;
+        return Ok(Json(history).into_response());
+    };
+
+    let (relative_path, project_root) = design_doc_paths(&params.path, &params.project_path)?;
+    let lookup = rev.clone();
+    let revision = tokio::task::spawn_blocking(move || {
+        git_backend::file_at_revision(&project_root, &lookup, &relative_path)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to read design doc: {}", e)))?
+    .map_err(|e| AppError::Internal(format!("Failed to read design doc: {}", e.message())))?;
+
+    let Some((id, content)) = revision else {
+        return Err(AppError::NotFound(format!(
+            "{} does not exist at revision '{}'",
+            params.path, rev
+        )));
+    };
+
+    Ok(Json(DesignRevisionResponse {
+        path: params.path,
+        rev: id,
+        content,
+    })
+    .into_response())
 }
 
 #[cfg(test)]
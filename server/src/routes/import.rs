@@ -0,0 +1,944 @@
+//! Import route handlers.
+//!
+//! Pulls issues from external trackers and appends them as beads. Imported
+//! items are recorded in `.beads/import-map.json` (external key -> bead ID)
+//! so re-running an import skips anything already imported.
+
+use axum::{
+    extract::{Multipart, Query},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, NaiveDateTime};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::process::Command;
+
+use super::beads::{
+    append_new_records, resolve_issues_path, validate_priority, validate_status, IssueRecord,
+};
+use super::validate_path_security;
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+use crate::logging::LogCommand;
+
+/// Default number of issues fetched per GitHub import.
+const DEFAULT_GITHUB_LIMIT: u32 = 100;
+
+/// Maximum number of issues fetched per GitHub import.
+const MAX_GITHUB_LIMIT: u32 = 1000;
+
+/// Fields requested from `gh issue list`.
+const GH_ISSUE_FIELDS: &str =
+    "number,title,body,state,labels,assignees,createdAt,updatedAt,closedAt,url";
+
+/// Maximum size of an uploaded Jira CSV export (10MB).
+pub const MAX_JIRA_CSV_SIZE: usize = 10 * 1024 * 1024;
+
+/// Default Jira issue type -> bead issue type mapping (keys lowercase).
+const DEFAULT_JIRA_ISSUE_TYPES: &[(&str, &str)] = &[
+    ("bug", "bug"),
+    ("story", "feature"),
+    ("new feature", "feature"),
+    ("improvement", "feature"),
+    ("epic", "epic"),
+    ("task", "task"),
+    ("sub-task", "task"),
+    ("subtask", "task"),
+];
+
+/// Default Jira status -> bead status mapping (keys lowercase).
+const DEFAULT_JIRA_STATUSES: &[(&str, &str)] = &[
+    ("open", "open"),
+    ("to do", "open"),
+    ("backlog", "open"),
+    ("selected for development", "open"),
+    ("reopened", "open"),
+    ("in progress", "in_progress"),
+    ("in review", "inreview"),
+    ("code review", "inreview"),
+    ("done", "closed"),
+    ("closed", "closed"),
+    ("resolved", "closed"),
+];
+
+/// Default Jira priority -> bead priority mapping (keys lowercase).
+const DEFAULT_JIRA_PRIORITIES: &[(&str, i32)] = &[
+    ("highest", 0),
+    ("blocker", 0),
+    ("high", 1),
+    ("critical", 1),
+    ("medium", 2),
+    ("major", 2),
+    ("low", 3),
+    ("minor", 3),
+    ("lowest", 4),
+    ("trivial", 4),
+];
+
+/// Date formats used by Jira CSV exports, tried after RFC 3339.
+const JIRA_DATE_FORMATS: &[&str] = &[
+    "%d/%b/%y %I:%M %p",
+    "%d/%b/%Y %I:%M %p",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%d %H:%M:%S",
+];
+
+/// Mapping of external item keys (e.g., "github:owner/repo#12") to bead IDs.
+type ImportMap = BTreeMap<String, String>;
+
+// -----------------------------------------------------------------------------
+// Types
+// -----------------------------------------------------------------------------
+
+/// Request body for the GitHub import endpoint.
+#[derive(Debug, Deserialize)]
+pub struct ImportGithubRequest {
+    /// The project path containing .beads/
+    pub path: String,
+    /// Repository in "owner/name" form
+    pub repo: String,
+    /// Only import issues with all of these labels
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Issue state to import: "open" (default), "closed", or "all"
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Maximum number of issues to fetch (default 100, max 1000)
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Query parameters for the Jira import endpoint.
+#[derive(Debug, Deserialize)]
+pub struct ImportJiraParams {
+    /// The project path containing .beads/
+    pub path: String,
+}
+
+/// Mapping of Jira values to the bead schema, sent as the `mapping` form field.
+///
+/// Keys are matched case-insensitively and override the built-in defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct JiraMapping {
+    /// Jira issue type -> bead issue type
+    pub issue_types: HashMap<String, String>,
+    /// Jira status -> bead status
+    pub statuses: HashMap<String, String>,
+    /// Jira priority -> bead priority (0-4)
+    pub priorities: HashMap<String, i32>,
+}
+
+/// Import result for one row of a Jira CSV.
+#[derive(Debug, Serialize)]
+pub struct JiraRowResult {
+    /// Row number in the CSV (the header is row 1)
+    pub row: usize,
+    /// Jira issue key, if the row has one
+    pub key: Option<String>,
+    /// "imported", "skipped", or "error"
+    pub result: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bead_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Outcome of importing one external item.
+#[derive(Debug, PartialEq)]
+enum ImportOutcome {
+    /// Appended as a new bead with this ID.
+    Imported(String),
+    /// Already imported earlier as the bead with this ID.
+    Skipped(String),
+}
+
+/// A GitHub issue as returned by `gh issue list --json`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhIssue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    state: String,
+    #[serde(default)]
+    labels: Vec<GhLabel>,
+    #[serde(default)]
+    assignees: Vec<GhUser>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    updated_at: Option<String>,
+    #[serde(default)]
+    closed_at: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhLabel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GhUser {
+    login: String,
+}
+
+// -----------------------------------------------------------------------------
+// Helpers
+// -----------------------------------------------------------------------------
+
+/// Matches "owner/name" repository slugs.
+fn repo_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^[A-Za-z0-9_.-]+/[A-Za-z0-9_.-]+$").unwrap())
+}
+
+/// Returns the path of the import mapping file for an issues file.
+fn import_map_path(issues_path: &Path) -> PathBuf {
+    issues_path.with_file_name("import-map.json")
+}
+
+/// Reads the import mapping file (empty if it does not exist).
+fn read_import_map(map_path: &Path) -> Result<ImportMap, String> {
+    match std::fs::read_to_string(map_path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", map_path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ImportMap::new()),
+        Err(e) => Err(format!("Failed to read import map: {}", e)),
+    }
+}
+
+/// Writes the import mapping file atomically.
+fn write_import_map(map_path: &Path, map: &ImportMap) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(map)
+        .map_err(|e| format!("Failed to serialize import map: {}", e))?;
+    let tmp_path = map_path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json + "\n")
+        .map_err(|e| format!("Failed to write import map: {}", e))?;
+    std::fs::rename(&tmp_path, map_path).map_err(|e| format!("Failed to write import map: {}", e))
+}
+
+/// Validates the project path of an import and checks it has a `.beads` directory.
+fn import_project_path(path: &str) -> Result<PathBuf, AppError> {
+    let project_path = PathBuf::from(path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    if !resolve_issues_path(&project_path)
+        .parent()
+        .is_some_and(|p| p.exists())
+    {
+        return Err(AppError::NotFound(
+            "No .beads directory found at the specified path".to_string(),
+        ));
+    }
+    Ok(project_path)
+}
+
+/// Appends the records whose keys are not yet in the import map, then
+/// records their bead IDs in the map.
+///
+/// Items whose key is already mapped, or repeated earlier in `items`, are
+/// skipped. Returns one outcome per item, in order.
+async fn import_keyed_records(
+    project_path: &Path,
+    items: Vec<(String, IssueRecord)>,
+) -> Result<Vec<ImportOutcome>, AppError> {
+    let map_path = import_map_path(&resolve_issues_path(project_path));
+
+    // Hold the mapping lock so concurrent imports cannot import an item twice
+    let _lock = lock_exclusive(&map_path).await?;
+    let mut map = read_import_map(&map_path).map_err(AppError::Internal)?;
+
+    let mut seen = HashSet::new();
+    let mut keys = Vec::with_capacity(items.len());
+    let mut new_keys = Vec::new();
+    let mut records = Vec::new();
+    for (key, record) in items {
+        if !map.contains_key(&key) && seen.insert(key.clone()) {
+            new_keys.push(key.clone());
+            records.push(record);
+        }
+        keys.push(key);
+    }
+
+    let bead_ids = append_new_records(project_path, records)
+        .await
+        .map_err(AppError::Internal)?;
+    if !bead_ids.is_empty() {
+        map.extend(new_keys.into_iter().zip(bead_ids));
+        write_import_map(&map_path, &map).map_err(AppError::Internal)?;
+    }
+
+    let mut imported = HashSet::new();
+    Ok(keys
+        .into_iter()
+        .map(|key| {
+            let bead_id = map.get(&key).cloned().unwrap_or_default();
+            if seen.contains(&key) && imported.insert(key) {
+                ImportOutcome::Imported(bead_id)
+            } else {
+                ImportOutcome::Skipped(bead_id)
+            }
+        })
+        .collect())
+}
+
+/// Mapping key for a GitHub issue.
+fn github_key(repo: &str, number: u64) -> String {
+    format!("github:{}#{}", repo.to_lowercase(), number)
+}
+
+/// Converts a GitHub issue to a new bead record.
+///
+/// Issues labelled "bug" become bugs, everything else a task; the first
+/// assignee becomes the owner and the issue URL is kept as `external_ref`.
+fn github_issue_to_record(issue: &GhIssue) -> IssueRecord {
+    let mut record = IssueRecord::new();
+    record.insert("title".to_string(), serde_json::json!(issue.title));
+
+    let body = issue.body.as_deref().unwrap_or("").trim();
+    let description = match (&issue.url, body.is_empty()) {
+        (Some(url), true) => format!("Imported from {}", url),
+        (Some(url), false) => format!("{}\n\nImported from {}", body, url),
+        (None, _) => body.to_string(),
+    };
+    if !description.is_empty() {
+        record.insert("description".to_string(), serde_json::json!(description));
+    }
+
+    let closed = issue.state.eq_ignore_ascii_case("closed");
+    record.insert(
+        "status".to_string(),
+        serde_json::json!(if closed { "closed" } else { "open" }),
+    );
+    record.insert("priority".to_string(), serde_json::json!(2));
+
+    let labels: Vec<&str> = issue.labels.iter().map(|l| l.name.as_str()).collect();
+    let is_bug = labels.iter().any(|l| l.eq_ignore_ascii_case("bug"));
+    record.insert(
+        "issue_type".to_string(),
+        serde_json::json!(if is_bug { "bug" } else { "task" }),
+    );
+    if !labels.is_empty() {
+        record.insert("labels".to_string(), serde_json::json!(labels));
+    }
+
+    if let Some(assignee) = issue.assignees.first() {
+        record.insert("owner".to_string(), serde_json::json!(assignee.login));
+    }
+    if let Some(ref url) = issue.url {
+        record.insert("external_ref".to_string(), serde_json::json!(url));
+    }
+    for (key, value) in [
+        ("created_at", &issue.created_at),
+        ("updated_at", &issue.updated_at),
+    ] {
+        if let Some(v) = value {
+            record.insert(key.to_string(), serde_json::json!(v));
+        }
+    }
+    if closed {
+        if let Some(ref closed_at) = issue.closed_at {
+            record.insert("closed_at".to_string(), serde_json::json!(closed_at));
+        }
+    }
+
+    record
+}
+
+/// Fetches issues from GitHub using the `gh` CLI.
+async fn fetch_github_issues(
+    repo: &str,
+    state: &str,
+    labels: &[String],
+    limit: u32,
+) -> Result<Vec<GhIssue>, String> {
+    let limit = limit.to_string();
+    let mut args = vec![
+        "issue",
+        "list",
+        "--repo",
+        repo,
+        "--state",
+        state,
+        "--limit",
+        &limit,
+        "--json",
+        GH_ISSUE_FIELDS,
+    ];
+    for label in labels {
+        args.push("--label");
+        args.push(label);
+    }
+
+    let output = Command::new("gh")
+        .args(&args)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "gh issue list failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Failed to parse gh output: {}", e))
+}
+
+/// Parses CSV text (RFC 4180: quoted fields, doubled quotes, embedded newlines).
+///
+/// Blank lines are skipped.
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("Invalid CSV: unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    rows.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    Ok(rows)
+}
+
+/// Lowercases mapping keys, fills in defaults, and validates the targets.
+fn resolve_jira_mapping(mapping: JiraMapping) -> Result<JiraMapping, String> {
+    fn merge<V: Clone>(defaults: &[(&str, V)], custom: HashMap<String, V>) -> HashMap<String, V> {
+        let mut merged: HashMap<String, V> = defaults
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        merged.extend(
+            custom
+                .into_iter()
+                .map(|(k, v)| (k.trim().to_lowercase(), v)),
+        );
+        merged
+    }
+
+    let resolved = JiraMapping {
+        issue_types: merge(
+            &DEFAULT_JIRA_ISSUE_TYPES
+                .iter()
+                .map(|(k, v)| (*k, v.to_string()))
+                .collect::<Vec<_>>(),
+            mapping.issue_types,
+        ),
+        statuses: merge(
+            &DEFAULT_JIRA_STATUSES
+                .iter()
+                .map(|(k, v)| (*k, v.to_string()))
+                .collect::<Vec<_>>(),
+            mapping.statuses,
+        ),
+        priorities: merge(DEFAULT_JIRA_PRIORITIES, mapping.priorities),
+    };
+
+    for issue_type in resolved.issue_types.values() {
+        if issue_type.trim().is_empty() {
+            return Err("Invalid mapping: issue types must not be empty".to_string());
+        }
+    }
+    for status in resolved.statuses.values() {
+        validate_status(status).map_err(|e| format!("Invalid mapping: {}", e))?;
+    }
+    for priority in resolved.priorities.values() {
+        validate_priority(*priority).map_err(|e| format!("Invalid mapping: {}", e))?;
+    }
+    Ok(resolved)
+}
+
+/// Parses a Jira export date into RFC 3339 (times without an offset are taken as UTC).
+fn parse_jira_date(value: &str) -> Option<String> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.to_rfc3339());
+    }
+    JIRA_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|dt| dt.and_utc().to_rfc3339())
+}
+
+/// A data row of a Jira CSV, addressed by column header.
+struct JiraRow<'a> {
+    headers: &'a [String],
+    values: &'a [String],
+}
+
+impl<'a> JiraRow<'a> {
+    /// All non-empty values in columns named `name` (Jira repeats columns
+    /// such as "Labels" once per value).
+    fn values(&self, name: &str) -> Vec<&'a str> {
+        self.headers
+            .iter()
+            .zip(self.values)
+            .filter(|(header, _)| header.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+            .filter(|value| !value.is_empty())
+            .collect()
+    }
+
+    /// The first non-empty value in columns named `name`.
+    fn value(&self, name: &str) -> Option<&'a str> {
+        self.values(name).into_iter().next()
+    }
+}
+
+/// Converts a Jira CSV row to a mapping key and new bead record.
+fn jira_row_to_record(
+    row: &JiraRow,
+    mapping: &JiraMapping,
+) -> Result<(String, IssueRecord), String> {
+    let key = row
+        .value("Issue key")
+        .ok_or("Missing 'Issue key'")?
+        .to_string();
+    let title = row.value("Summary").ok_or("Missing 'Summary'")?;
+
+    let lookup =
+        |column: &str, map_name: &str, map: &HashMap<String, String>, default: &str| match row
+            .value(column)
+        {
+            None => Ok(default.to_string()),
+            Some(value) => map
+                .get(&value.to_lowercase())
+                .cloned()
+                .ok_or_else(|| format!("No {} mapping for Jira {} '{}'", map_name, column, value)),
+        };
+    let issue_type = lookup("Issue Type", "issue type", &mapping.issue_types, "task")?;
+    let status = lookup("Status", "status", &mapping.statuses, "open")?;
+    let priority = match row.value("Priority") {
+        None => 2,
+        Some(value) => *mapping
+            .priorities
+            .get(&value.to_lowercase())
+            .ok_or_else(|| format!("No priority mapping for Jira Priority '{}'", value))?,
+    };
+
+    let mut record = IssueRecord::new();
+    record.insert("title".to_string(), serde_json::json!(title));
+    let description = match row.value("Description") {
+        Some(body) => format!("{}\n\nImported from Jira {}", body, key),
+        None => format!("Imported from Jira {}", key),
+    };
+    record.insert("description".to_string(), serde_json::json!(description));
+    record.insert("status".to_string(), serde_json::json!(status));
+    record.insert("priority".to_string(), serde_json::json!(priority));
+    record.insert("issue_type".to_string(), serde_json::json!(issue_type));
+    record.insert("external_ref".to_string(), serde_json::json!(key));
+
+    let labels = row.values("Labels");
+    if !labels.is_empty() {
+        record.insert("labels".to_string(), serde_json::json!(labels));
+    }
+    if let Some(assignee) = row.value("Assignee") {
+        record.insert("owner".to_string(), serde_json::json!(assignee));
+    }
+    for (key, column) in [("created_at", "Created"), ("updated_at", "Updated")] {
+        if let Some(date) = row.value(column).and_then(parse_jira_date) {
+            record.insert(key.to_string(), serde_json::json!(date));
+        }
+    }
+    if status == "closed" {
+        if let Some(date) = row.value("Resolved").and_then(parse_jira_date) {
+            record.insert("closed_at".to_string(), serde_json::json!(date));
+        }
+    }
+
+    Ok((format!("jira:{}", key.to_uppercase()), record))
+}
+
+// -----------------------------------------------------------------------------
+// Handlers
+// -----------------------------------------------------------------------------
+
+/// POST /api/import/github
+///
+/// Imports GitHub issues as beads using the `gh` CLI. Issues already listed
+/// in `.beads/import-map.json` are skipped, so re-running is safe.
+pub async fn import_github(
+    Json(payload): Json<ImportGithubRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let project_path = import_project_path(&payload.path)?;
+
+    if !repo_regex().is_match(&payload.repo) {
+        return Err(AppError::BadRequest(
+            "Invalid repo. Expected 'owner/name'".to_string(),
+        ));
+    }
+
+    let state = payload.state.as_deref().unwrap_or("open");
+    if !["open", "closed", "all"].contains(&state) {
+        return Err(AppError::BadRequest(format!(
+            "Invalid state '{}'. Must be 'open', 'closed', or 'all'",
+            state
+        )));
+    }
+
+    let limit = payload.limit.unwrap_or(DEFAULT_GITHUB_LIMIT);
+    if limit == 0 || limit > MAX_GITHUB_LIMIT {
+        return Err(AppError::BadRequest(format!(
+            "Invalid limit {}. Must be between 1 and {}",
+            limit, MAX_GITHUB_LIMIT
+        )));
+    }
+
+    let issues = fetch_github_issues(&payload.repo, state, &payload.labels, limit)
+        .await
+        .map_err(AppError::Upstream)?;
+
+    let items = issues
+        .iter()
+        .map(|issue| {
+            (
+                github_key(&payload.repo, issue.number),
+                github_issue_to_record(issue),
+            )
+        })
+        .collect();
+    let outcomes = import_keyed_records(&project_path, items).await?;
+
+    let mut imported = Vec::new();
+    let mut skipped = Vec::new();
+    for (issue, outcome) in issues.iter().zip(outcomes) {
+        match outcome {
+            ImportOutcome::Imported(bead_id) => {
+                imported.push(serde_json::json!({ "number": issue.number, "bead_id": bead_id }))
+            }
+            ImportOutcome::Skipped(_) => skipped.push(issue.number),
+        }
+    }
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "imported": imported,
+        "skipped": skipped
+    })))
+}
+
+/// POST /api/import/jira?path={project_path}
+///
+/// Imports a Jira CSV export as beads. The multipart body carries the CSV
+/// as a file field and, optionally, a `mapping` field with a JSON
+/// [`JiraMapping`] overriding how issue types, statuses, and priorities are
+/// translated. Rows already listed in `.beads/import-map.json` are skipped.
+/// Every row gets a result; rows that fail do not stop the others.
+///
+/// # Security constraints:
+/// - Max CSV size: 10MB
+/// - Path must be within allowed directories
+pub async fn import_jira(
+    Query(params): Query<ImportJiraParams>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, AppError> {
+    let project_path = import_project_path(&params.path)?;
+
+    let mut csv = None;
+    let mut mapping = JiraMapping::default();
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(f)) => f,
+            Ok(None) => break,
+            Err(e) => {
+                return Err(AppError::BadRequest(format!(
+                    "Invalid multipart body: {}",
+                    e
+                )));
+            }
+        };
+
+        let is_mapping = field.name() == Some("mapping");
+        let is_file = field.file_name().is_some();
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read upload: {}", e)))?;
+
+        if is_mapping {
+            mapping = match serde_json::from_slice(&data) {
+                Ok(m) => m,
+                Err(e) => {
+                    return Err(AppError::BadRequest(format!("Invalid mapping: {}", e)));
+                }
+            };
+        } else if is_file {
+            if data.len() > MAX_JIRA_CSV_SIZE {
+                return Err(AppError::PayloadTooLarge(format!(
+                    "File too large: {} bytes (max {} bytes)",
+                    data.len(),
+                    MAX_JIRA_CSV_SIZE
+                )));
+            }
+            csv = match String::from_utf8(data.to_vec()) {
+                Ok(text) => Some(text),
+                Err(_) => {
+                    return Err(AppError::BadRequest(
+                        "CSV must be UTF-8 encoded".to_string(),
+                    ));
+                }
+            };
+        }
+    }
+
+    let csv = match csv {
+        Some(c) => c,
+        None => {
+            return Err(AppError::BadRequest("No CSV file provided".to_string()));
+        }
+    };
+    let mapping = resolve_jira_mapping(mapping).map_err(AppError::BadRequest)?;
+    let rows = parse_csv(&csv).map_err(AppError::BadRequest)?;
+    let Some((headers, data_rows)) = rows.split_first() else {
+        return Err(AppError::BadRequest("CSV is empty".to_string()));
+    };
+
+    let mut results = Vec::with_capacity(data_rows.len());
+    let mut items = Vec::new();
+    for (i, values) in data_rows.iter().enumerate() {
+        let row = JiraRow { headers, values };
+        let mut result = JiraRowResult {
+            row: i + 2,
+            key: row.value("Issue key").map(String::from),
+            result: "error",
+            bead_id: None,
+            error: None,
+        };
+        match jira_row_to_record(&row, &mapping) {
+            Ok(item) => items.push(item),
+            Err(e) => result.error = Some(e),
+        }
+        results.push(result);
+    }
+
+    let outcomes = import_keyed_records(&project_path, items).await?;
+    let mut outcomes = outcomes.into_iter();
+    for result in results.iter_mut().filter(|r| r.error.is_none()) {
+        match outcomes.next() {
+            Some(ImportOutcome::Imported(bead_id)) => {
+                result.result = "imported";
+                result.bead_id = Some(bead_id);
+            }
+            Some(ImportOutcome::Skipped(bead_id)) => {
+                result.result = "skipped";
+                result.bead_id = Some(bead_id);
+            }
+            None => {}
+        }
+    }
+
+    let count = |kind: &str| results.iter().filter(|r| r.result == kind).count();
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "imported": count("imported"),
+        "skipped": count("skipped"),
+        "failed": count("error"),
+        "rows": results
+    })))
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_github_issue_to_record() {
+        let issues: Vec<GhIssue> = serde_json::from_str(
+            r#"[{
+                "number": 7,
+                "title": "Crash on save",
+                "body": "Steps to reproduce",
+                "state": "CLOSED",
+                "labels": [{"name": "bug"}, {"name": "ui"}],
+                "assignees": [{"login": "ann"}, {"login": "bob"}],
+                "createdAt": "2026-01-01T10:00:00Z",
+                "updatedAt": "2026-01-02T10:00:00Z",
+                "closedAt": "2026-01-02T10:00:00Z",
+                "url": "https://github.com/acme/app/issues/7"
+            }]"#,
+        )
+        .unwrap();
+
+        let record = github_issue_to_record(&issues[0]);
+        assert_eq!(record["title"], "Crash on save");
+        assert_eq!(
+            record["description"],
+            "Steps to reproduce\n\nImported from https://github.com/acme/app/issues/7"
+        );
+        assert_eq!(record["status"], "closed");
+        assert_eq!(record["closed_at"], "2026-01-02T10:00:00Z");
+        assert_eq!(record["issue_type"], "bug");
+        assert_eq!(record["labels"], serde_json::json!(["bug", "ui"]));
+        assert_eq!(record["owner"], "ann");
+        assert!(!record.contains_key("id"));
+    }
+
+    #[test]
+    fn test_import_map_round_trip() {
+        let dir = tempdir().unwrap();
+        let map_path = dir.path().join("import-map.json");
+        assert!(read_import_map(&map_path).unwrap().is_empty());
+
+        let mut map = ImportMap::new();
+        map.insert(github_key("Acme/App", 7), "bd-abc".to_string());
+        write_import_map(&map_path, &map).unwrap();
+
+        let loaded = read_import_map(&map_path).unwrap();
+        assert_eq!(
+            loaded.get("github:acme/app#7").map(String::as_str),
+            Some("bd-abc")
+        );
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let rows =
+            parse_csv("\u{feff}a,b,c\r\n1,\"x, \"\"y\"\"\",\"multi\nline\"\n\n2,,\n").unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["a", "b", "c"],
+                vec!["1", "x, \"y\"", "multi\nline"],
+                vec!["2", "", ""],
+            ]
+        );
+        assert!(parse_csv("a,\"b").is_err());
+    }
+
+    #[test]
+    fn test_jira_row_to_record() {
+        let mut custom = JiraMapping::default();
+        custom
+            .statuses
+            .insert("QA".to_string(), "inreview".to_string());
+        let mapping = resolve_jira_mapping(custom).unwrap();
+
+        let rows = parse_csv(concat!(
+            "Summary,Issue key,Issue Type,Status,Priority,Assignee,Created,Labels,Labels\n",
+            "Fix login,PROJ-1,Bug,qa,High,ann,12/Jan/26 10:15 AM,auth,web\n",
+            "Odd,PROJ-2,Task,Blocked,,,,,\n",
+        ))
+        .unwrap();
+
+        let row = JiraRow {
+            headers: &rows[0],
+            values: &rows[1],
+        };
+        let (key, record) = jira_row_to_record(&row, &mapping).unwrap();
+        assert_eq!(key, "jira:PROJ-1");
+        assert_eq!(record["issue_type"], "bug");
+        assert_eq!(record["status"], "inreview");
+        assert_eq!(record["priority"], 1);
+        assert_eq!(record["owner"], "ann");
+        assert_eq!(record["created_at"], "2026-01-12T10:15:00+00:00");
+        assert_eq!(record["labels"], serde_json::json!(["auth", "web"]));
+
+        let row = JiraRow {
+            headers: &rows[0],
+            values: &rows[2],
+        };
+        let err = jira_row_to_record(&row, &mapping).unwrap_err();
+        assert!(err.contains("Blocked"));
+    }
+
+    #[test]
+    fn test_resolve_jira_mapping_rejects_invalid_targets() {
+        let mut custom = JiraMapping::default();
+        custom.priorities.insert("urgent".to_string(), 9);
+        assert!(resolve_jira_mapping(custom).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_keyed_records_skips_mapped_items() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".beads")).unwrap();
+        let record = |title: &str| {
+            let mut r = IssueRecord::new();
+            r.insert("title".to_string(), serde_json::json!(title));
+            r
+        };
+
+        let first = import_keyed_records(
+            dir.path(),
+            vec![
+                ("jira:A-1".to_string(), record("One")),
+                ("jira:A-1".to_string(), record("One again")),
+            ],
+        )
+        .await
+        .unwrap();
+        let ImportOutcome::Imported(ref bead_id) = first[0] else {
+            panic!("expected import");
+        };
+        assert_eq!(first[1], ImportOutcome::Skipped(bead_id.clone()));
+
+        let second = import_keyed_records(
+            dir.path(),
+            vec![
+                ("jira:A-1".to_string(), record("One")),
+                ("jira:A-2".to_string(), record("Two")),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(second[0], ImportOutcome::Skipped(bead_id.clone()));
+        assert!(matches!(second[1], ImportOutcome::Imported(_)));
+
+        let contents = std::fs::read_to_string(resolve_issues_path(dir.path())).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_repo_regex() {
+        assert!(repo_regex().is_match("acme/app.rs"));
+        assert!(!repo_regex().is_match("acme"));
+        assert!(!repo_regex().is_match("acme/app --flag"));
+    }
+}
@@ -0,0 +1,420 @@
+//! Markdown rendering route handlers.
+//!
+//! Renders Markdown to sanitized HTML on the server so the frontend does not
+//! need its own renderer. References to beads are rewritten to in-app bead
+//! links (`<a href="#bead-{id}" class="bead-link" data-bead-id="{id}">`),
+//! which the frontend intercepts to open the bead. Design docs read through
+//! the fs API also get their bead and design doc references back as
+//! structured links (see [`extract_links`]).
+
+use axum::{response::IntoResponse, Json};
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use super::beads::{parse_beads, resolve_issues_path};
+use super::validate_path_security;
+use crate::error::AppError;
+
+/// Maximum Markdown input size accepted by the render endpoint (100KB).
+const MAX_MARKDOWN_SIZE: usize = 100 * 1024;
+
+/// Request body for the render endpoint.
+#[derive(Debug, Deserialize)]
+pub struct RenderMarkdownRequest {
+    /// The Markdown source to render
+    pub markdown: String,
+    /// Optional project path; when given, references to the project's bead
+    /// IDs are rewritten to bead links.
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// A reference found in a design doc, for the UI to turn into a link.
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocLink {
+    /// A known bead, mentioned bare or used as a link destination
+    Bead {
+        /// The bead's ID as stored, whatever case it was written in
+        id: String,
+        /// The text as it appears in the doc
+        text: String,
+        /// 1-based line number
+        line: usize,
+    },
+    /// A link to another design doc
+    Design {
+        /// Path relative to the project (e.g., ".designs/other.md")
+        path: String,
+        /// The link text
+        text: String,
+        /// 1-based line number
+        line: usize,
+    },
+}
+
+/// Matches tokens that could be bead IDs (e.g., "bd-a1b2" or "bd-a1b2.3").
+fn bead_token_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"[A-Za-z0-9][A-Za-z0-9_.-]*[A-Za-z0-9]").unwrap())
+}
+
+/// Builds the HTML anchor opening tag for a bead link.
+fn bead_link_open(bead_id: &str) -> String {
+    let id = html_escape(bead_id);
+    format!(
+        "<a href=\"#bead-{}\" class=\"bead-link\" data-bead-id=\"{}\">",
+        id, id
+    )
+}
+
+/// Escapes text for inclusion in HTML.
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Loads the IDs of all beads in a project (empty if none can be read).
+pub(crate) fn load_bead_ids(project_path: &Path) -> HashSet<String> {
+    std::fs::read_to_string(resolve_issues_path(project_path))
+        .map(|contents| parse_beads(&contents).into_iter().map(|b| b.id).collect())
+        .unwrap_or_default()
+}
+
+/// The Markdown extensions design docs are parsed with.
+fn markdown_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES
+}
+
+/// Splits a text event into text and bead-link events.
+fn link_bead_mentions<'a>(text: CowStr<'a>, bead_ids: &HashSet<String>) -> Vec<Event<'a>> {
+    let mut events = Vec::new();
+    let mut last = 0;
+
+    for m in bead_token_regex().find_iter(&text) {
+        if !bead_ids.contains(m.as_str()) {
+            continue;
+        }
+        if m.start() > last {
+            events.push(Event::Text(CowStr::from(text[last..m.start()].to_string())));
+        }
+        events.push(Event::InlineHtml(CowStr::from(bead_link_open(m.as_str()))));
+        events.push(Event::Text(CowStr::from(m.as_str().to_string())));
+        events.push(Event::InlineHtml(CowStr::from("</a>")));
+        last = m.end();
+    }
+
+    if last == 0 {
+        return vec![Event::Text(text)];
+    }
+    if last < text.len() {
+        events.push(Event::Text(CowStr::from(text[last..].to_string())));
+    }
+    events
+}
+
+/// Renders Markdown to sanitized HTML.
+///
+/// Links whose destination is a known bead ID, and bare mentions of known
+/// bead IDs outside of code and existing links, become bead links. Raw HTML
+/// in the source is sanitized away along with anything unsafe.
+pub fn render_markdown(markdown: &str, bead_ids: &HashSet<String>) -> String {
+    let mut events = Vec::new();
+    // One entry per open link: true if it was rewritten to a bead link
+    let mut link_stack: Vec<bool> = Vec::new();
+    let mut in_code_block = false;
+
+    for event in Parser::new_ext(markdown, markdown_options()) {
+        match event {
+            Event::Start(Tag::Link { ref dest_url, .. })
+                if bead_ids.contains(dest_url.as_ref()) =>
+            {
+                events.push(Event::InlineHtml(CowStr::from(bead_link_open(dest_url))));
+                link_stack.push(true);
+            }
+            Event::Start(Tag::Link { .. }) => {
+                events.push(event);
+                link_stack.push(false);
+            }
+            Event::End(TagEnd::Link) => {
+                if link_stack.pop() == Some(true) {
+                    events.push(Event::InlineHtml(CowStr::from("</a>")));
+                } else {
+                    events.push(event);
+                }
+            }
+            Event::Start(Tag::CodeBlock(_)) => {
+                in_code_block = true;
+                events.push(event);
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                in_code_block = false;
+                events.push(event);
+            }
+            Event::Text(text) if !in_code_block && link_stack.is_empty() => {
+                events.extend(link_bead_mentions(text, bead_ids));
+            }
+            other => events.push(other),
+        }
+    }
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, events.into_iter());
+
+    ammonia::Builder::default()
+        .add_tag_attributes("a", &["class", "data-bead-id"])
+        .add_tags(&["input"])
+        .add_tag_attributes("input", &["type", "checked", "disabled"])
+        .clean(&unsafe_html)
+        .to_string()
+}
+
+/// Resolves a link destination in the design doc at `doc_path` to another
+/// design doc, as a path relative to the project.
+///
+/// Only relative links to `.md` files that stay inside `.designs/` resolve.
+fn resolve_design_link(doc_path: &str, dest: &str) -> Option<String> {
+    if dest.contains(':') || dest.starts_with('/') {
+        return None;
+    }
+    let dest = dest.split(['#', '?']).next()?;
+    if !dest.ends_with(".md") {
+        return None;
+    }
+
+    let base = if dest.starts_with(".designs/") {
+        ""
+    } else {
+        doc_path.rsplit_once('/').map_or("", |(dir, _)| dir)
+    };
+    let mut parts: Vec<&str> = base.split('/').filter(|s| !s.is_empty()).collect();
+    for segment in dest.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            s => parts.push(s),
+        }
+    }
+
+    let path = parts.join("/");
+    path.starts_with(".designs/").then_some(path)
+}
+
+/// Finds the bead and design doc references in a design doc.
+///
+/// Bead IDs match case-insensitively (so `BD-12` finds `bd-12`) but only
+/// known IDs count. Links to other design docs are resolved relative to
+/// `doc_path`. Code is skipped, as in [`render_markdown`].
+pub fn extract_links(markdown: &str, doc_path: &str, bead_ids: &HashSet<String>) -> Vec<DocLink> {
+    let by_lower: HashMap<String, &String> =
+        bead_ids.iter().map(|id| (id.to_lowercase(), id)).collect();
+    let find_bead = |token: &str| by_lower.get(&token.to_lowercase()).map(|id| id.to_string());
+
+    let line_starts: Vec<usize> = markdown.match_indices('\n').map(|(i, _)| i).collect();
+    let line_at = |offset: usize| line_starts.partition_point(|&i| i < offset) + 1;
+
+    let mut links = Vec::new();
+    // One entry per open link: the reference it makes, if any, while its
+    // text is collected
+    let mut link_stack: Vec<Option<DocLink>> = Vec::new();
+    let mut in_code_block = false;
+
+    for (event, range) in Parser::new_ext(markdown, markdown_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                let line = line_at(range.start);
+                let link = match find_bead(&dest_url) {
+                    Some(id) => Some(DocLink::Bead {
+                        id,
+                        text: String::new(),
+                        line,
+                    }),
+                    None => resolve_design_link(doc_path, &dest_url).map(|path| {
+                        DocLink::Design {
+                            path,
+                            text: String::new(),
+                            line,
+                        }
+                    }),
+                };
+                link_stack.push(link);
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some(Some(link)) = link_stack.pop() {
+                    links.push(link);
+                }
+            }
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(text) | Event::Code(text) if !link_stack.is_empty() => {
+                if let Some(Some(DocLink::Bead { text: t, .. } | DocLink::Design { text: t, .. })) =
+                    link_stack.last_mut()
+                {
+                    t.push_str(&text);
+                }
+            }
+            Event::Text(text) if !in_code_block => {
+                let line = line_at(range.start);
+                for m in bead_token_regex().find_iter(&text) {
+                    if let Some(id) = find_bead(m.as_str()) {
+                        links.push(DocLink::Bead {
+                            id,
+                            text: m.as_str().to_string(),
+                            line,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    links
+}
+
+/// POST /api/markdown/render
+///
+/// Renders Markdown to sanitized HTML. When `path` is given, bead IDs of
+/// that project are rewritten to bead links.
+///
+/// # Security constraints:
+/// - Max input size: 100KB
+/// - Output is sanitized (scripts, event handlers, and unsafe URLs removed)
+pub async fn render(
+    Json(payload): Json<RenderMarkdownRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if payload.markdown.len() > MAX_MARKDOWN_SIZE {
+        return Err(AppError::PayloadTooLarge(format!(
+            "Markdown too large: {} bytes (max {} bytes)",
+            payload.markdown.len(),
+            MAX_MARKDOWN_SIZE
+        )));
+    }
+
+    let bead_ids = match payload.path {
+        Some(ref path) => {
+            let project_path = PathBuf::from(path);
+
+            // Security: Validate path is within allowed directories
+            validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+            load_bead_ids(&project_path)
+        }
+        None => HashSet::new(),
+    };
+
+    let html = render_markdown(&payload.markdown, &bead_ids);
+    Ok(Json(serde_json::json!({ "html": html })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> HashSet<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_render_basic_markdown() {
+        let html = render_markdown("# Title\n\nSome **bold** text.", &HashSet::new());
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<strong>bold</strong>"));
+    }
+
+    #[test]
+    fn test_render_sanitizes_html() {
+        let html = render_markdown(
+            "<script>alert(1)</script>\n\n[x](javascript:alert(1)) <img src=x onerror=alert(1)>",
+            &HashSet::new(),
+        );
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("onerror"));
+    }
+
+    #[test]
+    fn test_render_links_bead_mentions() {
+        let html = render_markdown("Blocked by bd-12.1 and bd-99.", &ids(&["bd-12.1"]));
+        assert!(html.contains(
+            "<a href=\"#bead-bd-12.1\" class=\"bead-link\" data-bead-id=\"bd-12.1\" rel=\"noopener noreferrer\">bd-12.1</a>"
+        ));
+        assert!(!html.contains("data-bead-id=\"bd-99\""));
+    }
+
+    #[test]
+    fn test_render_rewrites_bead_link_destinations() {
+        let html = render_markdown("See [the parent](bd-1).", &ids(&["bd-1"]));
+        assert!(html.contains("data-bead-id=\"bd-1\""));
+        assert!(html.contains(">the parent</a>"));
+    }
+
+    #[test]
+    fn test_resolve_design_link() {
+        let doc = ".designs/bd-1.md";
+        assert_eq!(
+            resolve_design_link(doc, "other.md#goals"),
+            Some(".designs/other.md".to_string())
+        );
+        assert_eq!(
+            resolve_design_link(doc, "./sub/../api.md"),
+            Some(".designs/api.md".to_string())
+        );
+        assert_eq!(
+            resolve_design_link(".designs/sub/a.md", ".designs/b.md"),
+            Some(".designs/b.md".to_string())
+        );
+        assert_eq!(resolve_design_link(doc, "../README.md"), None);
+        assert_eq!(resolve_design_link(doc, "../../../x.md"), None);
+        assert_eq!(resolve_design_link(doc, "https://example.com/a.md"), None);
+        assert_eq!(resolve_design_link(doc, "/etc/a.md"), None);
+        assert_eq!(resolve_design_link(doc, "image.png"), None);
+    }
+
+    #[test]
+    fn test_extract_links() {
+        let md = "# Plan\n\nBlocked by BD-12.1 and bd-99.\n\n\
+                  See [the parent](bd-1) and [API notes](api.md).\n\n\
+                  ```\nbd-1\n```\n";
+        let links = extract_links(md, ".designs/bd-1.md", &ids(&["bd-1", "bd-12.1"]));
+        assert_eq!(
+            links,
+            vec![
+                DocLink::Bead {
+                    id: "bd-12.1".to_string(),
+                    text: "BD-12.1".to_string(),
+                    line: 3
+                },
+                DocLink::Bead {
+                    id: "bd-1".to_string(),
+                    text: "the parent".to_string(),
+                    line: 5
+                },
+                DocLink::Design {
+                    path: ".designs/api.md".to_string(),
+                    text: "API notes".to_string(),
+                    line: 5
+                },
+            ]
+        );
+        let json = serde_json::to_value(&links[2]).unwrap();
+        assert_eq!(json["kind"], "design");
+    }
+
+    #[test]
+    fn test_render_skips_code() {
+        let html = render_markdown("`bd-1`\n\n```\nbd-1\n```", &ids(&["bd-1"]));
+        assert!(!html.contains("bead-link"));
+    }
+}
@@ -3,17 +3,14 @@
 //! Provides endpoints for reading, editing, and deleting knowledge base entries
 //! from `.beads/memory/knowledge.jsonl` files.
 
-use axum::{
-    extract::Query,
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
-};
+use axum::{extract::Query, response::IntoResponse, Json};
 use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use super::validate_path_security;
+use crate::error::AppError;
+use crate::lock::{lock_exclusive, FileLock, LockError};
 
 // ---------------------------------------------------------------------------
 // Types
@@ -94,6 +91,18 @@ fn archive_path(project_path: &Path) -> PathBuf {
         .join("knowledge.archive.jsonl")
 }
 
+/// Lock the knowledge file for a read-modify-write cycle.
+///
+/// Returns `None` when the file does not exist yet, since there is nothing
+/// to modify (and the memory directory may not exist to hold a lock file).
+async fn lock_knowledge(path: &Path) -> Result<Option<FileLock>, LockError> {
+    if path.exists() {
+        lock_exclusive(path).await.map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
 /// Parse a JSONL file into a list of `MemoryEntry` values.
 ///
 /// Missing files are treated as empty. Malformed lines are skipped with a
@@ -217,73 +226,51 @@ fn compute_stats(entries: &[MemoryEntry], archived: usize) -> MemoryStats {
 /// Reads all entries from the active knowledge file and returns them along
 /// with aggregate statistics. Entries are sorted by `ts` descending (newest
 /// first).
-pub async fn list_memory(Query(params): Query<MemoryParams>) -> impl IntoResponse {
+pub async fn list_memory(
+    Query(params): Query<MemoryParams>,
+) -> Result<impl IntoResponse, AppError> {
     let project_path = PathBuf::from(&params.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&project_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
 
     let kpath = knowledge_path(&project_path);
     let apath = archive_path(&project_path);
 
-    let mut entries = match read_entries(&kpath) {
-        Ok(e) => e,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": e })),
-            );
-        }
-    };
+    let mut entries = read_entries(&kpath).map_err(AppError::Internal)?;
 
     // Sort by ts descending (newest first)
-    entries.sort_by(|a, b| b.ts.cmp(&a.ts));
+    entries.sort_by_key(|e| std::cmp::Reverse(e.ts));
 
     let archived = count_entries(&apath);
     let stats = compute_stats(&entries, archived);
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!(MemoryListResponse { entries, stats })),
-    )
+    Ok(Json(serde_json::json!(MemoryListResponse {
+        entries,
+        stats
+    })))
 }
 
 /// GET /api/memory/stats?path={project_path}
 ///
 /// Lightweight endpoint returning only aggregate statistics (no entry content).
-pub async fn memory_stats(Query(params): Query<MemoryParams>) -> impl IntoResponse {
+pub async fn memory_stats(
+    Query(params): Query<MemoryParams>,
+) -> Result<impl IntoResponse, AppError> {
     let project_path = PathBuf::from(&params.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&project_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
 
     let kpath = knowledge_path(&project_path);
     let apath = archive_path(&project_path);
 
-    let entries = match read_entries(&kpath) {
-        Ok(e) => e,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": e })),
-            );
-        }
-    };
+    let entries = read_entries(&kpath).map_err(AppError::Internal)?;
 
     let archived = count_entries(&apath);
     let stats = compute_stats(&entries, archived);
 
-    (StatusCode::OK, Json(serde_json::json!(stats)))
+    Ok(Json(serde_json::json!(stats)))
 }
 
 /// PUT /api/memory
@@ -291,38 +278,27 @@ pub async fn memory_stats(Query(params): Query<MemoryParams>) -> impl IntoRespon
 /// Edit an existing entry by key. Updates `content` and/or `tags` fields.
 /// At least one of `content` or `tags` must be provided.
 /// The `ts` field is NOT updated (it represents original creation time).
-pub async fn update_memory(Json(payload): Json<UpdateMemoryRequest>) -> impl IntoResponse {
+pub async fn update_memory(
+    Json(payload): Json<UpdateMemoryRequest>,
+) -> Result<impl IntoResponse, AppError> {
     // Validate that at least one field is provided
     if payload.content.is_none() && payload.tags.is_none() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": "At least one of 'content' or 'tags' must be provided"
-            })),
-        );
+        return Err(AppError::BadRequest(
+            "At least one of 'content' or 'tags' must be provided".to_string(),
+        ));
     }
 
     let project_path = PathBuf::from(&payload.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&project_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
 
     let kpath = knowledge_path(&project_path);
 
-    let mut entries = match read_entries(&kpath) {
-        Ok(e) => e,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": e })),
-            );
-        }
-    };
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_knowledge(&kpath).await?;
+
+    let mut entries = read_entries(&kpath).map_err(AppError::Internal)?;
 
     // Find the entry with the matching key
     let entry_pos = entries.iter().position(|e| e.key == payload.key);
@@ -330,12 +306,10 @@ pub async fn update_memory(Json(payload): Json<UpdateMemoryRequest>) -> impl Int
     let idx = match entry_pos {
         Some(i) => i,
         None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "error": format!("Entry with key '{}' not found", payload.key)
-                })),
-            );
+            return Err(AppError::NotFound(format!(
+                "Entry with key '{}' not found",
+                payload.key
+            )));
         }
     };
 
@@ -348,21 +322,13 @@ pub async fn update_memory(Json(payload): Json<UpdateMemoryRequest>) -> impl Int
     }
 
     // Write back
-    if let Err(e) = write_entries(&kpath, &entries) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    write_entries(&kpath, &entries).map_err(AppError::Internal)?;
 
     let updated_entry = entries[idx].clone();
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "success": true,
-            "entry": updated_entry
-        })),
-    )
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "entry": updated_entry
+    })))
 }
 
 /// DELETE /api/memory
@@ -372,28 +338,20 @@ pub async fn update_memory(Json(payload): Json<UpdateMemoryRequest>) -> impl Int
 /// - `archive: true`  — Move entry to `knowledge.archive.jsonl`, then remove
 ///   from `knowledge.jsonl`.
 /// - `archive: false` — Permanently delete from `knowledge.jsonl`.
-pub async fn delete_memory(Json(payload): Json<DeleteMemoryRequest>) -> impl IntoResponse {
+pub async fn delete_memory(
+    Json(payload): Json<DeleteMemoryRequest>,
+) -> Result<impl IntoResponse, AppError> {
     let project_path = PathBuf::from(&payload.path);
 
     // Security: Validate path is within allowed directories
-    if let Err(e) = validate_path_security(&project_path) {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
 
     let kpath = knowledge_path(&project_path);
 
-    let mut entries = match read_entries(&kpath) {
-        Ok(e) => e,
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": e })),
-            );
-        }
-    };
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_knowledge(&kpath).await?;
+
+    let mut entries = read_entries(&kpath).map_err(AppError::Internal)?;
 
     // Find the entry with the matching key
     let entry_pos = entries.iter().position(|e| e.key == payload.key);
@@ -401,12 +359,10 @@ pub async fn delete_memory(Json(payload): Json<DeleteMemoryRequest>) -> impl Int
     let idx = match entry_pos {
         Some(i) => i,
         None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "error": format!("Entry with key '{}' not found", payload.key)
-                })),
-            );
+            return Err(AppError::NotFound(format!(
+                "Entry with key '{}' not found",
+                payload.key
+            )));
         }
     };
 
@@ -416,29 +372,16 @@ pub async fn delete_memory(Json(payload): Json<DeleteMemoryRequest>) -> impl Int
     // If archiving, append to archive file
     if payload.archive {
         let apath = archive_path(&project_path);
-        if let Err(e) = append_entry(&apath, &removed_entry) {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({ "error": e })),
-            );
-        }
+        append_entry(&apath, &removed_entry).map_err(AppError::Internal)?;
     }
 
     // Write back the remaining entries
-    if let Err(e) = write_entries(&kpath, &entries) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({ "error": e })),
-        );
-    }
+    write_entries(&kpath, &entries).map_err(AppError::Internal)?;
 
-    (
-        StatusCode::OK,
-        Json(serde_json::json!({
-            "success": true,
-            "archived": payload.archive
-        })),
-    )
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "archived": payload.archive
+    })))
 }
 
 // ---------------------------------------------------------------------------
@@ -0,0 +1,506 @@
+//! Milestone API route handlers.
+//!
+//! Milestones (sprints) are stored in `milestones.jsonl` next to the
+//! project's `issues.jsonl`, so they travel with the repo. Beads are
+//! assigned to a milestone through their `milestone` field.
+
+use axum::{
+    extract::{Path as AxumPath, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use super::beads::{clear_milestone_assignments, parse_beads, resolve_issues_path};
+use super::validate_path_security;
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// A milestone from the JSONL file.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Milestone {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Target date (e.g., "2026-03-31").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<String>,
+    /// "open" or "closed".
+    pub status: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Bead counts and completion for a milestone.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct MilestoneProgress {
+    pub open: usize,
+    pub in_progress: usize,
+    pub inreview: usize,
+    pub closed: usize,
+    pub total: usize,
+    /// Percentage of beads closed (0 when the milestone has no beads).
+    pub completion_percent: f64,
+}
+
+/// Query parameters for GET endpoints.
+#[derive(Debug, Deserialize)]
+pub struct MilestoneParams {
+    pub path: String,
+}
+
+/// Request body for the create milestone endpoint.
+#[derive(Debug, Deserialize)]
+pub struct CreateMilestoneRequest {
+    pub path: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub due_date: Option<String>,
+}
+
+/// Request body for the update milestone endpoint.
+///
+/// Passing an empty string for `description` or `due_date` clears it.
+#[derive(Debug, Deserialize)]
+pub struct UpdateMilestoneRequest {
+    pub path: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+/// Request body for the delete milestone endpoint.
+#[derive(Debug, Deserialize)]
+pub struct DeleteMilestoneRequest {
+    pub path: String,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// Build the path to the milestones file, alongside the issues file.
+fn milestones_path(project_path: &Path) -> PathBuf {
+    resolve_issues_path(project_path).with_file_name("milestones.jsonl")
+}
+
+/// Parse the milestones file. Missing files are treated as empty.
+fn read_milestones(path: &Path) -> Result<Vec<Milestone>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    let mut milestones = Vec::new();
+    for (line_num, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Milestone>(line) {
+            Ok(m) => milestones.push(m),
+            Err(e) => {
+                tracing::warn!("Failed to parse milestone line {}: {}", line_num + 1, e);
+            }
+        }
+    }
+
+    Ok(milestones)
+}
+
+/// Write milestones back atomically (temp file + rename).
+fn write_milestones(path: &Path, milestones: &[Milestone]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
+    }
+
+    let tmp_path = path.with_extension("jsonl.tmp");
+    let file = std::fs::File::create(&tmp_path)
+        .map_err(|e| format!("Failed to open file for writing: {}", e))?;
+
+    let mut writer = std::io::BufWriter::new(file);
+    for milestone in milestones {
+        let json_line = serde_json::to_string(milestone)
+            .map_err(|e| format!("Failed to serialize milestone: {}", e))?;
+        writeln!(writer, "{}", json_line)
+            .map_err(|e| format!("Failed to write to file: {}", e))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("Failed to flush file: {}", e))?;
+
+    std::fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace file: {}", e))
+}
+
+/// Checks whether a milestone exists for the project owning `issues_path`.
+pub(crate) fn milestone_exists(issues_path: &Path, milestone_id: &str) -> Result<bool, String> {
+    let path = issues_path.with_file_name("milestones.jsonl");
+    Ok(read_milestones(&path)?.iter().any(|m| m.id == milestone_id))
+}
+
+/// Validates a milestone status.
+fn validate_milestone_status(status: &str) -> Result<(), String> {
+    match status {
+        "open" | "closed" => Ok(()),
+        _ => Err(format!(
+            "Invalid status '{}'. Must be 'open' or 'closed'",
+            status
+        )),
+    }
+}
+
+/// Normalize an optional text field; empty values clear it.
+fn optional_text(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Compute progress from the statuses of a milestone's beads.
+fn compute_progress<'a>(statuses: impl IntoIterator<Item = &'a str>) -> MilestoneProgress {
+    let mut progress = MilestoneProgress {
+        open: 0,
+        in_progress: 0,
+        inreview: 0,
+        closed: 0,
+        total: 0,
+        completion_percent: 0.0,
+    };
+
+    for status in statuses {
+        match status {
+            "in_progress" => progress.in_progress += 1,
+            "inreview" => progress.inreview += 1,
+            "closed" => progress.closed += 1,
+            _ => progress.open += 1,
+        }
+        progress.total += 1;
+    }
+
+    if progress.total > 0 {
+        progress.completion_percent =
+            (progress.closed as f64 / progress.total as f64 * 1000.0).round() / 10.0;
+    }
+
+    progress
+}
+
+/// Validate the project path and resolve its milestones file.
+fn project_milestones_path(project: &str) -> Result<PathBuf, AppError> {
+    let project_path = PathBuf::from(project);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    Ok(milestones_path(&project_path))
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /api/milestones?path={project_path}
+///
+/// Lists all milestones for a project, ordered by due date (undated last).
+pub async fn list_milestones(
+    Query(params): Query<MilestoneParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let mpath = project_milestones_path(&params.path)?;
+
+    let mut milestones = read_milestones(&mpath).map_err(AppError::Internal)?;
+
+    milestones.sort_by(|a, b| match (&a.due_date, &b.due_date) {
+        (Some(x), Some(y)) => x.cmp(y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.created_at.cmp(&b.created_at),
+    });
+
+    Ok(Json(serde_json::json!({ "milestones": milestones })))
+}
+
+/// POST /api/milestones
+///
+/// Creates a new open milestone.
+pub async fn create_milestone(
+    Json(payload): Json<CreateMilestoneRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("Name must not be empty".to_string()));
+    }
+
+    let mpath = project_milestones_path(&payload.path)?;
+
+    // The .beads directory must exist; milestones belong to a beads project
+    if !mpath.parent().is_some_and(|p| p.exists()) {
+        return Err(AppError::NotFound(
+            "No .beads directory found at the specified path".to_string(),
+        ));
+    }
+
+    // Hold the lock across the whole read-modify-write cycle
+    let _lock = lock_exclusive(&mpath).await?;
+
+    let mut milestones = read_milestones(&mpath).map_err(AppError::Internal)?;
+
+    let now = Utc::now().to_rfc3339();
+    let milestone = Milestone {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        description: payload.description.as_deref().and_then(optional_text),
+        due_date: payload.due_date.as_deref().and_then(optional_text),
+        status: "open".to_string(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    milestones.push(milestone.clone());
+
+    write_milestones(&mpath, &milestones).map_err(AppError::Internal)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({ "success": true, "milestone": milestone })),
+    ))
+}
+
+/// PATCH /api/milestones/:id
+///
+/// Updates the provided fields of a milestone.
+pub async fn update_milestone(
+    AxumPath(id): AxumPath<String>,
+    Json(payload): Json<UpdateMilestoneRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    if let Some(ref status) = payload.status {
+        validate_milestone_status(status).map_err(AppError::BadRequest)?;
+    }
+    if payload.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::BadRequest("Name must not be empty".to_string()));
+    }
+
+    let mpath = project_milestones_path(&payload.path)?;
+
+    let _lock = if mpath.exists() {
+        Some(lock_exclusive(&mpath).await?)
+    } else {
+        None
+    };
+
+    let mut milestones = read_milestones(&mpath).map_err(AppError::Internal)?;
+
+    let milestone = match milestones.iter_mut().find(|m| m.id == id) {
+        Some(m) => m,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Milestone with id '{}' not found",
+                id
+            )));
+        }
+    };
+
+    if let Some(ref name) = payload.name {
+        milestone.name = name.trim().to_string();
+    }
+    if let Some(ref description) = payload.description {
+        milestone.description = optional_text(description);
+    }
+    if let Some(ref due_date) = payload.due_date {
+        milestone.due_date = optional_text(due_date);
+    }
+    if let Some(ref status) = payload.status {
+        milestone.status = status.clone();
+    }
+    milestone.updated_at = Utc::now().to_rfc3339();
+    let updated = milestone.clone();
+
+    write_milestones(&mpath, &milestones).map_err(AppError::Internal)?;
+
+    Ok(Json(
+        serde_json::json!({ "success": true, "milestone": updated }),
+    ))
+}
+
+/// DELETE /api/milestones/:id
+///
+/// Deletes a milestone and unassigns any beads assigned to it.
+pub async fn delete_milestone(
+    AxumPath(id): AxumPath<String>,
+    Json(payload): Json<DeleteMilestoneRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let mpath = project_milestones_path(&payload.path)?;
+
+    let _lock = if mpath.exists() {
+        Some(lock_exclusive(&mpath).await?)
+    } else {
+        None
+    };
+
+    let mut milestones = read_milestones(&mpath).map_err(AppError::Internal)?;
+
+    let before = milestones.len();
+    milestones.retain(|m| m.id != id);
+    if milestones.len() == before {
+        return Err(AppError::NotFound(format!(
+            "Milestone with id '{}' not found",
+            id
+        )));
+    }
+
+    write_milestones(&mpath, &milestones).map_err(AppError::Internal)?;
+
+    let issues_path = mpath.with_file_name("issues.jsonl");
+    let unassigned = if issues_path.exists() {
+        match clear_milestone_assignments(&issues_path, &id).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                return Err(AppError::Internal(e));
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "deleted": id,
+        "unassigned": unassigned
+    })))
+}
+
+/// GET /api/milestones/:id/progress?path={project_path}
+///
+/// Returns bead counts per status and the completion percentage for a
+/// milestone.
+pub async fn milestone_progress(
+    AxumPath(id): AxumPath<String>,
+    Query(params): Query<MilestoneParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let mpath = project_milestones_path(&params.path)?;
+
+    let milestone = match read_milestones(&mpath) {
+        Ok(m) => m.into_iter().find(|m| m.id == id),
+        Err(e) => {
+            return Err(AppError::Internal(e));
+        }
+    };
+    let milestone = match milestone {
+        Some(m) => m,
+        None => {
+            return Err(AppError::NotFound(format!(
+                "Milestone with id '{}' not found",
+                id
+            )));
+        }
+    };
+
+    let issues_path = mpath.with_file_name("issues.jsonl");
+    let contents = match std::fs::read_to_string(&issues_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(AppError::Internal(format!("Failed to read file: {}", e)));
+        }
+    };
+
+    let beads = parse_beads(&contents);
+    let progress = compute_progress(
+        beads
+            .iter()
+            .filter(|b| b.milestone.as_deref() == Some(id.as_str()))
+            .map(|b| b.status.as_str()),
+    );
+
+    Ok(Json(
+        serde_json::json!({ "milestone": milestone, "progress": progress }),
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn milestone(id: &str) -> Milestone {
+        Milestone {
+            id: id.to_string(),
+            name: format!("Sprint {}", id),
+            description: None,
+            due_date: Some("2026-03-31".to_string()),
+            status: "open".to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_milestones_path_default() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(
+            milestones_path(tmp.path()),
+            tmp.path().join(".beads").join("milestones.jsonl")
+        );
+    }
+
+    #[test]
+    fn test_write_and_read_milestones() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join(".beads").join("milestones.jsonl");
+        let milestones = vec![milestone("m1"), milestone("m2")];
+
+        write_milestones(&path, &milestones).unwrap();
+        assert_eq!(read_milestones(&path).unwrap(), milestones);
+
+        let issues_path = path.with_file_name("issues.jsonl");
+        assert!(milestone_exists(&issues_path, "m2").unwrap());
+        assert!(!milestone_exists(&issues_path, "m3").unwrap());
+    }
+
+    #[test]
+    fn test_read_milestones_missing_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("milestones.jsonl");
+        assert!(read_milestones(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_compute_progress() {
+        let progress = compute_progress(["open", "in_progress", "closed", "closed", "inreview", "closed"]);
+        assert_eq!(progress.open, 1);
+        assert_eq!(progress.in_progress, 1);
+        assert_eq!(progress.inreview, 1);
+        assert_eq!(progress.closed, 3);
+        assert_eq!(progress.total, 6);
+        assert_eq!(progress.completion_percent, 50.0);
+
+        assert_eq!(compute_progress(std::iter::empty()).completion_percent, 0.0);
+    }
+
+    #[test]
+    fn test_validate_milestone_status() {
+        assert!(validate_milestone_status("open").is_ok());
+        assert!(validate_milestone_status("closed").is_ok());
+        assert!(validate_milestone_status("done").is_err());
+    }
+}
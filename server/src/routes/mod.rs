@@ -3,19 +3,34 @@
 //! This module contains all HTTP route handlers.
 //! Additional handlers will be added as API endpoints are implemented.
 
+pub mod admin;
 pub mod agents;
+pub mod attachments;
 pub mod beads;
 pub mod cli;
+pub mod diagnostics;
+pub mod fields;
 pub mod fs;
 pub mod git;
+pub mod import;
+pub mod markdown;
 pub mod memory;
+pub mod milestones;
+pub mod notifications;
 pub mod projects;
+pub mod session;
+pub mod settings;
+pub mod trash;
+pub mod version;
+pub mod views;
 pub mod watch;
+pub mod workspace;
 pub mod worktree;
 
 pub use projects::project_routes;
-pub use watch::watch_beads;
+pub use watch::{events, watch_beads, watch_beads_ws};
 
+use crate::error::AppError;
 use axum::{response::IntoResponse, Json};
 use directories::UserDirs;
 use serde::Serialize;
@@ -34,6 +49,11 @@ pub async fn health() -> impl IntoResponse {
     Json(HealthResponse { status: "ok" })
 }
 
+/// Fallback handler when the frontend isn't served.
+pub async fn not_found() -> impl IntoResponse {
+    AppError::NotFound("Not found".to_string())
+}
+
 /// Validates that a path is within allowed directories (user home).
 ///
 /// # Security
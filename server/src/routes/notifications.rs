@@ -0,0 +1,278 @@
+//! Notification route handlers.
+//!
+//! Comments that mention someone with `@name` produce a notification record
+//! in `.beads/notifications.jsonl` (next to `issues.jsonl`). New
+//! notifications are also published in-process so the watch SSE stream can
+//! push them to connected boards as `notification` events.
+
+use axum::{extract::Query, response::IntoResponse, Json};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use super::beads::{resolve_issues_path, Comment};
+use super::validate_path_security;
+use crate::error::AppError;
+use crate::lock::lock_exclusive;
+
+/// Maximum length of the comment excerpt stored on a notification.
+const EXCERPT_LENGTH: usize = 200;
+
+/// Capacity of the in-process notification channel.
+const CHANNEL_CAPACITY: usize = 256;
+
+// -----------------------------------------------------------------------------
+// Types
+// -----------------------------------------------------------------------------
+
+/// A notification for one person.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Notification {
+    pub id: String,
+    /// Notification kind (currently always "mention")
+    pub kind: String,
+    /// The mentioned name, without the leading `@`
+    pub recipient: String,
+    pub bead_id: String,
+    pub comment_id: i64,
+    /// Author of the comment containing the mention
+    pub author: String,
+    /// Start of the comment text
+    pub excerpt: String,
+    pub created_at: String,
+}
+
+/// A notification published for a project, identified by its issues file.
+#[derive(Debug, Clone)]
+pub struct PublishedNotification {
+    pub issues_path: PathBuf,
+    pub notification: Notification,
+}
+
+/// Query parameters for the list notifications endpoint.
+#[derive(Debug, Deserialize)]
+pub struct NotificationParams {
+    /// The project path containing .beads/
+    pub path: String,
+    /// Only return notifications for this recipient (case-insensitive)
+    #[serde(default)]
+    pub recipient: Option<String>,
+}
+
+// -----------------------------------------------------------------------------
+// Helpers
+// -----------------------------------------------------------------------------
+
+/// Matches `@name` mentions not preceded by a word character (so email
+/// addresses are not treated as mentions).
+fn mention_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?:^|[^A-Za-z0-9_.@])@([A-Za-z0-9_][A-Za-z0-9_.-]*)").unwrap())
+}
+
+fn channel() -> &'static broadcast::Sender<PublishedNotification> {
+    static CHANNEL: OnceLock<broadcast::Sender<PublishedNotification>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Subscribes to notifications published by this server.
+pub fn subscribe() -> broadcast::Receiver<PublishedNotification> {
+    channel().subscribe()
+}
+
+/// Returns the path of the notifications file for an issues file.
+fn notifications_path(issues_path: &Path) -> PathBuf {
+    issues_path.with_file_name("notifications.jsonl")
+}
+
+/// Extracts the distinct names mentioned in a text, in order of appearance.
+///
+/// Trailing punctuation (e.g., "@ann.") is not part of the name.
+pub fn extract_mentions(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for captures in mention_regex().captures_iter(text) {
+        let name = captures[1].trim_end_matches(['.', '-']);
+        if !name.is_empty() && !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Whether a mentioned name refers to the comment author (the full author
+/// string, or the local part of an email address).
+fn is_author(name: &str, author: &str) -> bool {
+    author.eq_ignore_ascii_case(name)
+        || author
+            .split_once('@')
+            .is_some_and(|(local, _)| local.eq_ignore_ascii_case(name))
+}
+
+/// Builds mention notifications for a new comment, skipping self-mentions.
+fn mention_notifications(comment: &Comment) -> Vec<Notification> {
+    let excerpt: String = comment.text.chars().take(EXCERPT_LENGTH).collect();
+    extract_mentions(&comment.text)
+        .into_iter()
+        .filter(|name| !is_author(name, &comment.author))
+        .map(|recipient| Notification {
+            id: Uuid::new_v4().to_string(),
+            kind: "mention".to_string(),
+            recipient,
+            bead_id: comment.issue_id.clone(),
+            comment_id: comment.id,
+            author: comment.author.clone(),
+            excerpt: excerpt.clone(),
+            created_at: comment.created_at.clone(),
+        })
+        .collect()
+}
+
+/// Appends notifications to the notifications file.
+async fn append_notifications(path: &Path, notifications: &[Notification]) -> Result<(), String> {
+    let _lock = lock_exclusive(path).await.map_err(|e| e.to_string())?;
+
+    let mut contents = String::new();
+    for notification in notifications {
+        let line = serde_json::to_string(notification)
+            .map_err(|e| format!("Failed to serialize notification: {}", e))?;
+        contents.push_str(&line);
+        contents.push('\n');
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open notifications file: {}", e))?;
+    file.write_all(contents.as_bytes())
+        .map_err(|e| format!("Failed to write notifications file: {}", e))
+}
+
+/// Reads all notifications, skipping malformed lines.
+fn read_notifications(path: &Path) -> Result<Vec<Notification>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read notifications file: {}", e)),
+    }
+}
+
+/// Records and publishes a notification for each person mentioned in a new
+/// comment. Returns the recipients notified.
+pub(crate) async fn notify_mentions(
+    issues_path: &Path,
+    comment: &Comment,
+) -> Result<Vec<String>, String> {
+    let notifications = mention_notifications(comment);
+    if notifications.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    append_notifications(&notifications_path(issues_path), &notifications).await?;
+
+    let recipients = notifications.iter().map(|n| n.recipient.clone()).collect();
+    for notification in notifications {
+        // No subscribers is fine; the notification is still on disk
+        let _ = channel().send(PublishedNotification {
+            issues_path: issues_path.to_path_buf(),
+            notification,
+        });
+    }
+    Ok(recipients)
+}
+
+// -----------------------------------------------------------------------------
+// Handlers
+// -----------------------------------------------------------------------------
+
+/// GET /api/notifications?path=/path/to/project&recipient=ann
+///
+/// Lists a project's notifications, newest first.
+pub async fn list_notifications(
+    Query(params): Query<NotificationParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let project_path = PathBuf::from(&params.path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    let path = notifications_path(&resolve_issues_path(&project_path));
+    let mut notifications = read_notifications(&path).map_err(AppError::Internal)?;
+
+    if let Some(ref recipient) = params.recipient {
+        notifications.retain(|n| n.recipient.eq_ignore_ascii_case(recipient));
+    }
+    notifications.reverse();
+
+    Ok(Json(serde_json::json!({ "notifications": notifications })))
+}
+
+// -----------------------------------------------------------------------------
+// Tests
+// -----------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn comment(author: &str, text: &str) -> Comment {
+        Comment {
+            id: 3,
+            issue_id: "bd-1".to_string(),
+            author: author.to_string(),
+            text: text.to_string(),
+            created_at: "2026-01-01T10:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_extract_mentions() {
+        assert_eq!(
+            extract_mentions("@ann can you and @bob.smith look? Thanks @Ann."),
+            vec!["ann".to_string(), "bob.smith".to_string()]
+        );
+        assert!(extract_mentions("mail ann@example.com").is_empty());
+        assert!(extract_mentions("no mentions @ here").is_empty());
+    }
+
+    #[test]
+    fn test_mention_notifications_skip_author() {
+        let notifications = mention_notifications(&comment("ann@example.com", "@ann @bob ping"));
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0].recipient, "bob");
+        assert_eq!(notifications[0].bead_id, "bd-1");
+        assert_eq!(notifications[0].comment_id, 3);
+    }
+
+    #[tokio::test]
+    async fn test_notify_mentions_appends_and_publishes() {
+        let dir = tempdir().unwrap();
+        let issues_path = dir.path().join("issues.jsonl");
+        let mut rx = subscribe();
+
+        let recipients = notify_mentions(&issues_path, &comment("ann", "cc @bob"))
+            .await
+            .unwrap();
+        assert_eq!(recipients, vec!["bob".to_string()]);
+        notify_mentions(&issues_path, &comment("ann", "no mentions"))
+            .await
+            .unwrap();
+
+        let stored = read_notifications(&notifications_path(&issues_path)).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].recipient, "bob");
+
+        let published = rx.try_recv().unwrap();
+        assert_eq!(published.issues_path, issues_path);
+        assert_eq!(published.notification, stored[0]);
+    }
+}
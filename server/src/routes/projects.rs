@@ -10,62 +10,39 @@ use axum::{
 use serde::Serialize;
 use std::sync::Arc;
 
+use super::{session, settings, views};
 use crate::db::{
-    CreateProjectInput, CreateTagInput, Database, DbError, ProjectTagInput, ProjectWithTags, Tag,
-    UpdateProjectInput,
+    CreateProjectInput, CreateTagInput, Database, PrDefaults, PrSettings, ProjectTagInput, ProjectWithTags,
+    Tag, UpdateProjectInput,
 };
+use crate::error::AppError;
 
 /// Application state containing the database
 pub type AppState = Arc<Database>;
 
-/// Error response structure
-#[derive(Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
-}
-
 /// Success response structure for operations that don't return data
 #[derive(Serialize)]
 pub struct SuccessResponse {
     pub success: bool,
 }
 
-impl DbError {
-    fn status_code(&self) -> StatusCode {
-        match self {
-            DbError::ProjectNotFound(_) | DbError::TagNotFound(_) => StatusCode::NOT_FOUND,
-            DbError::Sqlite(_) | DbError::PathError => StatusCode::INTERNAL_SERVER_ERROR,
-        }
-    }
-}
-
-fn db_error_response(err: DbError) -> (StatusCode, Json<ErrorResponse>) {
-    let status = err.status_code();
-    (
-        status,
-        Json(ErrorResponse {
-            error: err.to_string(),
-        }),
-    )
-}
-
 // ===== Project Routes =====
 
 /// GET /api/projects - List all projects with their tags
 pub async fn list_projects(
     State(db): State<AppState>,
-) -> Result<Json<Vec<ProjectWithTags>>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<Vec<ProjectWithTags>>, AppError> {
     db.get_projects_with_tags()
         .map(Json)
-        .map_err(db_error_response)
+        .map_err(AppError::from)
 }
 
 /// POST /api/projects - Create a new project
 pub async fn create_project(
     State(db): State<AppState>,
     Json(input): Json<CreateProjectInput>,
-) -> Result<(StatusCode, Json<ProjectWithTags>), (StatusCode, Json<ErrorResponse>)> {
-    let project = db.create_project(input).map_err(db_error_response)?;
+) -> Result<(StatusCode, Json<ProjectWithTags>), AppError> {
+    let project = db.create_project(input)?;
 
     // Return project with empty tags array
     let project_with_tags = ProjectWithTags {
@@ -85,9 +62,9 @@ pub async fn update_project(
     State(db): State<AppState>,
     Path(id): Path<String>,
     Json(input): Json<UpdateProjectInput>,
-) -> Result<Json<ProjectWithTags>, (StatusCode, Json<ErrorResponse>)> {
-    let project = db.update_project(&id, input).map_err(db_error_response)?;
-    let tags = db.get_project_tags(&id).map_err(db_error_response)?;
+) -> Result<Json<ProjectWithTags>, AppError> {
+    let project = db.update_project(&id, input)?;
+    let tags = db.get_project_tags(&id)?;
 
     Ok(Json(ProjectWithTags {
         id: project.id,
@@ -103,26 +80,64 @@ pub async fn update_project(
 pub async fn delete_project(
     State(db): State<AppState>,
     Path(id): Path<String>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    db.delete_project(&id).map_err(db_error_response)?;
+) -> Result<StatusCode, AppError> {
+    db.delete_project(&id)?;
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// GET /api/projects/:id/pr-defaults - Get the reviewers, labels, and
+/// assignees applied to the project's new PRs
+pub async fn get_pr_defaults(
+    State(db): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<PrDefaults>, AppError> {
+    db.get_pr_defaults(&id).map(Json).map_err(AppError::from)
+}
+
+/// PUT /api/projects/:id/pr-defaults - Replace the project's PR defaults
+pub async fn set_pr_defaults(
+    State(db): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<PrDefaults>,
+) -> Result<Json<PrDefaults>, AppError> {
+    db.set_pr_defaults(&id, input)
+        .map(Json)
+        .map_err(AppError::from)
+}
+
+/// GET /api/projects/:id/pr-settings - Get where the project's PRs are
+/// hosted; the token itself is not returned
+pub async fn get_pr_settings(
+    State(db): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<PrSettings>, AppError> {
+    db.get_pr_settings(&id).map(Json).map_err(AppError::from)
+}
+
+/// PUT /api/projects/:id/pr-settings - Replace the project's PR settings
+pub async fn set_pr_settings(
+    State(db): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<PrSettings>,
+) -> Result<Json<PrSettings>, AppError> {
+    db.set_pr_settings(&id, input)
+        .map(Json)
+        .map_err(AppError::from)
+}
+
 // ===== Tag Routes =====
 
 /// GET /api/tags - List all tags
-pub async fn list_tags(
-    State(db): State<AppState>,
-) -> Result<Json<Vec<Tag>>, (StatusCode, Json<ErrorResponse>)> {
-    db.get_tags().map(Json).map_err(db_error_response)
+pub async fn list_tags(State(db): State<AppState>) -> Result<Json<Vec<Tag>>, AppError> {
+    db.get_tags().map(Json).map_err(AppError::from)
 }
 
 /// POST /api/tags - Create a new tag
 pub async fn create_tag(
     State(db): State<AppState>,
     Json(input): Json<CreateTagInput>,
-) -> Result<(StatusCode, Json<Tag>), (StatusCode, Json<ErrorResponse>)> {
-    let tag = db.create_tag(input).map_err(db_error_response)?;
+) -> Result<(StatusCode, Json<Tag>), AppError> {
+    let tag = db.create_tag(input)?;
     Ok((StatusCode::CREATED, Json(tag)))
 }
 
@@ -130,8 +145,8 @@ pub async fn create_tag(
 pub async fn delete_tag(
     State(db): State<AppState>,
     Path(id): Path<String>,
-) -> Result<StatusCode, (StatusCode, Json<ErrorResponse>)> {
-    db.delete_tag(&id).map_err(db_error_response)?;
+) -> Result<StatusCode, AppError> {
+    db.delete_tag(&id)?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -141,9 +156,8 @@ pub async fn delete_tag(
 pub async fn add_project_tag(
     State(db): State<AppState>,
     Json(input): Json<ProjectTagInput>,
-) -> Result<(StatusCode, Json<SuccessResponse>), (StatusCode, Json<ErrorResponse>)> {
-    db.add_tag_to_project(&input.project_id, &input.tag_id)
-        .map_err(db_error_response)?;
+) -> Result<(StatusCode, Json<SuccessResponse>), AppError> {
+    db.add_tag_to_project(&input.project_id, &input.tag_id)?;
     Ok((StatusCode::CREATED, Json(SuccessResponse { success: true })))
 }
 
@@ -151,9 +165,8 @@ pub async fn add_project_tag(
 pub async fn remove_project_tag(
     State(db): State<AppState>,
     Path((project_id, tag_id)): Path<(String, String)>,
-) -> Result<Json<SuccessResponse>, (StatusCode, Json<ErrorResponse>)> {
-    db.remove_tag_from_project(&project_id, &tag_id)
-        .map_err(db_error_response)?;
+) -> Result<Json<SuccessResponse>, AppError> {
+    db.remove_tag_from_project(&project_id, &tag_id)?;
     Ok(Json(SuccessResponse { success: true }))
 }
 
@@ -168,10 +181,38 @@ pub fn project_routes() -> axum::Router<AppState> {
             "/projects/:id",
             patch(update_project).delete(delete_project),
         )
+        .route(
+            "/projects/:id/pr-defaults",
+            get(get_pr_defaults).put(set_pr_defaults),
+        )
+        .route(
+            "/projects/:id/pr-settings",
+            get(get_pr_settings).put(set_pr_settings),
+        )
         // Tag routes
         .route("/tags", get(list_tags).post(create_tag))
         .route("/tags/:id", delete(delete_tag))
         // Project-tag relationship routes
         .route("/project-tags", post(add_project_tag))
         .route("/project-tags/:project_id/:tag_id", delete(remove_project_tag))
+        // Saved view routes
+        .route("/views", get(views::list_views).post(views::create_view))
+        .route(
+            "/views/:id",
+            patch(views::update_view).delete(views::delete_view),
+        )
+        // Per-device session routes
+        .route(
+            "/session",
+            get(session::get_session).put(session::rename_session),
+        )
+        .route(
+            "/session/prefs",
+            get(session::get_prefs).put(session::set_prefs),
+        )
+        // Server-wide settings
+        .route(
+            "/settings",
+            get(settings::get_settings).put(settings::set_settings),
+        )
 }
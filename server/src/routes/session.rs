@@ -0,0 +1,140 @@
+//! Per-device session REST API routes
+//!
+//! Each browser gets a long-lived `beads_device` cookie naming a session in
+//! the local database, so UI preferences (theme, last opened project,
+//! collapsed columns) follow the device rather than living in one tab's
+//! local storage. A missing or expired cookie starts a new session.
+
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+
+use super::projects::AppState;
+use crate::db::{Session, SessionPrefs, UpdateSessionInput};
+use crate::error::AppError;
+use crate::oidc::read_cookie;
+
+/// Name of the device cookie.
+const DEVICE_COOKIE: &str = "beads_device";
+
+/// How long the device cookie lasts; each request renews it (one year).
+const DEVICE_COOKIE_MAX_AGE: u64 = 365 * 24 * 60 * 60;
+
+/// Request body for renaming the session
+#[derive(Debug, Deserialize)]
+pub struct RenameSessionRequest {
+    /// New name; empty clears it
+    pub name: String,
+}
+
+/// `Set-Cookie` value for a device session.
+fn device_cookie(session_id: &str) -> String {
+    format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}",
+        DEVICE_COOKIE, session_id, DEVICE_COOKIE_MAX_AGE
+    )
+}
+
+/// Gets the request's session, starting one if needed.
+fn current_session(db: &AppState, headers: &HeaderMap) -> Result<Session, AppError> {
+    let id = read_cookie(headers, DEVICE_COOKIE);
+    Ok(db.touch_session(id.as_deref())?)
+}
+
+/// GET /api/session - The device's session (name and preferences)
+pub async fn get_session(
+    State(db): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let session = current_session(&db, &headers)?;
+    Ok((
+        [(header::SET_COOKIE, device_cookie(&session.id))],
+        Json(session),
+    ))
+}
+
+/// PUT /api/session - Name the device's session
+pub async fn rename_session(
+    State(db): State<AppState>,
+    headers: HeaderMap,
+    Json(input): Json<RenameSessionRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let session = current_session(&db, &headers)?;
+    let session = db.update_session(
+        &session.id,
+        UpdateSessionInput {
+            name: Some(input.name),
+            prefs: None,
+        },
+    )?;
+    Ok((
+        [(header::SET_COOKIE, device_cookie(&session.id))],
+        Json(session),
+    ))
+}
+
+/// GET /api/session/prefs - The device's UI preferences
+pub async fn get_prefs(
+    State(db): State<AppState>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AppError> {
+    let session = current_session(&db, &headers)?;
+    Ok((
+        [(header::SET_COOKIE, device_cookie(&session.id))],
+        Json(session.prefs),
+    ))
+}
+
+/// PUT /api/session/prefs - Replace the device's UI preferences
+pub async fn set_prefs(
+    State(db): State<AppState>,
+    headers: HeaderMap,
+    Json(prefs): Json<SessionPrefs>,
+) -> Result<impl IntoResponse, AppError> {
+    let session = current_session(&db, &headers)?;
+    let session = db.update_session(
+        &session.id,
+        UpdateSessionInput {
+            name: None,
+            prefs: Some(prefs),
+        },
+    )?;
+    Ok((
+        [(header::SET_COOKIE, device_cookie(&session.id))],
+        Json(session.prefs),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_prefs_follow_cookie() {
+        let db: AppState = Arc::new(crate::db::Database::new_in_memory().unwrap());
+
+        let prefs = SessionPrefs {
+            theme: Some("dark".to_string()),
+            ..SessionPrefs::default()
+        };
+        let response = set_prefs(State(db.clone()), HeaderMap::new(), Json(prefs.clone()))
+            .await
+            .unwrap()
+            .into_response();
+        let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        let id = cookie.split(';').next().unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, id.parse().unwrap());
+        assert_eq!(current_session(&db, &headers).unwrap().prefs, prefs);
+        assert_eq!(
+            current_session(&db, &HeaderMap::new()).unwrap().prefs,
+            SessionPrefs::default()
+        );
+    }
+}
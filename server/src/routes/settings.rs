@@ -0,0 +1,23 @@
+//! Server-wide settings REST API routes
+//!
+//! Settings that apply to the whole server rather than to one project or
+//! device, such as the command used to open a terminal.
+
+use axum::{extract::State, Json};
+
+use super::projects::AppState;
+use crate::db::Settings;
+use crate::error::AppError;
+
+/// GET /api/settings - Get the server-wide settings
+pub async fn get_settings(State(db): State<AppState>) -> Result<Json<Settings>, AppError> {
+    db.get_settings().map(Json).map_err(AppError::from)
+}
+
+/// PUT /api/settings - Replace the server-wide settings
+pub async fn set_settings(
+    State(db): State<AppState>,
+    Json(input): Json<Settings>,
+) -> Result<Json<Settings>, AppError> {
+    db.set_settings(input).map(Json).map_err(AppError::from)
+}
@@ -0,0 +1,316 @@
+//! Project trash.
+//!
+//! Files the server deletes (design docs, attachments) are moved into
+//! `.beads/.trash/` rather than unlinked, so a mistaken delete can be
+//! undone. Each trashed file lives in `.beads/.trash/{id}/` under its
+//! original name, next to a `{id}.json` record of where it came from. A
+//! background task purges entries older than 30 days from registered
+//! projects.
+
+use axum::{extract::Query, response::IntoResponse, Json};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::validate_path_security;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::shutdown;
+
+/// The trash directory, relative to the project.
+pub(crate) const TRASH_DIR: &str = ".beads/.trash";
+
+/// How long trashed files are kept before the purge removes them.
+const TRASH_RETENTION_DAYS: i64 = 30;
+
+/// How often the purge runs.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A file in the trash.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashEntry {
+    pub id: String,
+    /// Where the file was, relative to the project
+    pub original_path: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Query parameters for the list trash endpoint.
+#[derive(Debug, Deserialize)]
+pub struct TrashParams {
+    /// The project path (absolute directory path)
+    pub project_path: String,
+}
+
+/// Request body for the restore endpoint.
+#[derive(Debug, Deserialize)]
+pub struct RestoreRequest {
+    /// The project path (absolute directory path)
+    pub project_path: String,
+    /// The trash entry to restore
+    pub id: String,
+}
+
+/// Path of an entry's record.
+fn entry_record(trash: &Path, id: &str) -> PathBuf {
+    trash.join(format!("{}.json", id))
+}
+
+/// Moves a file of the project into the trash.
+///
+/// `file` must be inside `project_path`.
+pub(crate) fn move_to_trash(project_path: &Path, file: &Path) -> Result<TrashEntry, String> {
+    let original_path = file
+        .strip_prefix(project_path)
+        .map_err(|_| format!("{} is not inside the project", file.display()))?
+        .to_string_lossy()
+        .replace('\\', "/");
+    let name = file
+        .file_name()
+        .ok_or_else(|| format!("{} has no file name", file.display()))?;
+
+    let deleted_at = Utc::now();
+    let id = format!(
+        "{}-{}",
+        deleted_at.format("%Y%m%dT%H%M%S"),
+        &Uuid::new_v4().simple().to_string()[..8]
+    );
+    let trash = project_path.join(TRASH_DIR);
+    let dir = trash.join(&id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create trash: {}", e))?;
+
+    let entry = TrashEntry {
+        id,
+        original_path,
+        deleted_at,
+    };
+    let record = serde_json::to_string(&entry).map_err(|e| e.to_string())?;
+    std::fs::write(entry_record(&trash, &entry.id), record)
+        .map_err(|e| format!("Failed to write trash record: {}", e))?;
+
+    if let Err(e) = std::fs::rename(file, dir.join(name)) {
+        let _ = std::fs::remove_file(entry_record(&trash, &entry.id));
+        let _ = std::fs::remove_dir(&dir);
+        return Err(format!("Failed to move {} to trash: {}", file.display(), e));
+    }
+    Ok(entry)
+}
+
+/// Lists a project's trash, newest first. Entries with unreadable records
+/// are skipped.
+fn list_trash(project_path: &Path) -> Vec<TrashEntry> {
+    let Ok(read_dir) = std::fs::read_dir(project_path.join(TRASH_DIR)) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<TrashEntry> = read_dir
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|contents| serde_json::from_str(&contents).ok())
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    entries
+}
+
+/// Checks a trash entry ID before it is used in a path.
+fn validate_trash_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid trash entry '{}'", id))
+    }
+}
+
+/// Moves a trashed file back to where it was.
+///
+/// Fails without changing anything if a file now exists there.
+fn restore_entry(project_path: &Path, id: &str) -> Result<TrashEntry, AppError> {
+    validate_trash_id(id).map_err(AppError::BadRequest)?;
+    let trash = project_path.join(TRASH_DIR);
+    let entry: TrashEntry = std::fs::read_to_string(entry_record(&trash, id))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .ok_or_else(|| AppError::NotFound(format!("Trash entry '{}' not found", id)))?;
+
+    // The record is only read back from the trash, but it is still a path
+    // from disk, so keep it inside the project
+    let relative = Path::new(&entry.original_path);
+    if relative.is_absolute()
+        || relative
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(AppError::Forbidden(format!(
+            "Trash entry '{}' points outside the project",
+            id
+        )));
+    }
+    let target = project_path.join(relative);
+    if target.exists() {
+        return Err(AppError::Conflict(format!(
+            "{} already exists",
+            entry.original_path
+        )));
+    }
+    let name = target
+        .file_name()
+        .ok_or_else(|| AppError::Internal(format!("Trash entry '{}' has no file name", id)))?;
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| AppError::Internal(format!("Failed to create directory: {}", e)))?;
+    }
+    let dir = trash.join(id);
+    std::fs::rename(dir.join(name), &target)
+        .map_err(|e| AppError::Internal(format!("Failed to restore file: {}", e)))?;
+    let _ = std::fs::remove_file(entry_record(&trash, id));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    Ok(entry)
+}
+
+/// Removes trash entries deleted before `cutoff`. Returns how many.
+fn purge_trash(project_path: &Path, cutoff: DateTime<Utc>) -> usize {
+    let trash = project_path.join(TRASH_DIR);
+    let mut purged = 0;
+    for entry in list_trash(project_path) {
+        if entry.deleted_at >= cutoff {
+            continue;
+        }
+        match std::fs::remove_dir_all(trash.join(&entry.id)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                warn!("Failed to purge trash entry {}: {}", entry.id, e);
+                continue;
+            }
+        }
+        let _ = std::fs::remove_file(entry_record(&trash, &entry.id));
+        purged += 1;
+    }
+    purged
+}
+
+/// Periodically purges old trash from every registered project.
+pub async fn run_trash_purger(db: Arc<Database>) {
+    let mut ticker = tokio::time::interval(PURGE_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown::signalled() => break,
+        }
+
+        let projects = match db.get_projects() {
+            Ok(projects) => projects,
+            Err(e) => {
+                warn!("Trash purge failed to list projects: {}", e);
+                continue;
+            }
+        };
+
+        let pass = tokio::task::spawn_blocking(move || {
+            let cutoff = Utc::now() - chrono::Duration::days(TRASH_RETENTION_DAYS);
+            for project in projects {
+                let purged = purge_trash(Path::new(&project.path), cutoff);
+                if purged > 0 {
+                    info!("Purged {} trashed files from {}", purged, project.path);
+                }
+            }
+        });
+        if let Err(e) = pass.await {
+            warn!("Trash purge pass panicked: {}", e);
+        }
+    }
+}
+
+/// GET /api/fs/trash?project_path=/absolute/path
+///
+/// Lists the project's trashed files, newest first.
+pub async fn get_trash(Query(params): Query<TrashParams>) -> Result<impl IntoResponse, AppError> {
+    let project_path = PathBuf::from(&params.project_path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    Ok(Json(serde_json::json!({
+        "entries": list_trash(&project_path)
+    })))
+}
+
+/// POST /api/fs/trash/restore
+///
+/// Moves a trashed file back to its original path. Fails with 409 if a
+/// file exists there now. Restoring a design doc does not relink the beads
+/// it was unlinked from.
+pub async fn restore_trash(
+    Json(payload): Json<RestoreRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let project_path = PathBuf::from(&payload.project_path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&project_path).map_err(AppError::Forbidden)?;
+
+    let entry = restore_entry(&project_path, &payload.id)?;
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "path": entry.original_path
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trash_and_restore() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path();
+        std::fs::create_dir_all(project.join(".designs")).unwrap();
+        let doc = project.join(".designs/bd-1.md");
+        std::fs::write(&doc, "# Spec").unwrap();
+
+        let entry = move_to_trash(project, &doc).unwrap();
+        assert!(!doc.exists());
+        assert_eq!(entry.original_path, ".designs/bd-1.md");
+        assert_eq!(list_trash(project), vec![entry.clone()]);
+
+        std::fs::write(&doc, "# New").unwrap();
+        assert!(matches!(
+            restore_entry(project, &entry.id),
+            Err(AppError::Conflict(_))
+        ));
+        std::fs::remove_file(&doc).unwrap();
+
+        restore_entry(project, &entry.id).unwrap();
+        assert_eq!(std::fs::read_to_string(&doc).unwrap(), "# Spec");
+        assert!(list_trash(project).is_empty());
+        assert!(matches!(
+            restore_entry(project, &entry.id),
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            restore_entry(project, "../x"),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_purge_trash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let project = tmp.path();
+        let file = project.join("notes.txt");
+        std::fs::write(&file, "x").unwrap();
+        let entry = move_to_trash(project, &file).unwrap();
+
+        assert_eq!(purge_trash(project, entry.deleted_at), 0);
+        let later = entry.deleted_at + chrono::Duration::seconds(1);
+        assert_eq!(purge_trash(project, later), 1);
+        assert!(list_trash(project).is_empty());
+        assert!(!project.join(TRASH_DIR).join(&entry.id).exists());
+    }
+}
@@ -0,0 +1,62 @@
+//! API versioning.
+//!
+//! The API is served under [`API_PREFIX`] (`/api/v1`). Within a version,
+//! changes are additive only: new endpoints, new optional request fields,
+//! and new response fields. A breaking change (renaming or removing a
+//! field, changing a bead's schema) ships as a new version alongside the
+//! old one, which keeps its response shapes until it is removed.
+//!
+//! Deprecated paths keep working but mark their responses with
+//! `Deprecation: true` and a `Link: <...>; rel="successor-version"` header
+//! naming the replacement, so clients can warn before they break. The
+//! unversioned `/api/...` paths from before versioning are such aliases of
+//! `/api/v1`.
+
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+/// Prefix of the current API version.
+pub const API_PREFIX: &str = "/api/v1";
+
+/// Marks responses of an unversioned alias as deprecated in favor of the
+/// same path under [`API_PREFIX`].
+pub async fn deprecated_alias(request: Request, next: Next) -> Response {
+    // Nested routers see the path with the `/api` prefix stripped
+    let successor = format!("{}{}", API_PREFIX, request.uri().path());
+    let mut response = next.run(request).await;
+    mark_deprecated(&mut response, &successor);
+    response
+}
+
+/// Adds the deprecation headers pointing at `successor`.
+pub fn mark_deprecated(response: &mut Response, successor: &str) {
+    let headers = response.headers_mut();
+    headers.insert(
+        HeaderName::from_static("deprecation"),
+        HeaderValue::from_static("true"),
+    );
+    if let Ok(link) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor)) {
+        headers.insert(axum::http::header::LINK, link);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_mark_deprecated() {
+        let mut response = Response::new(Body::empty());
+        mark_deprecated(&mut response, "/api/v1/beads");
+        assert_eq!(response.headers()["deprecation"], "true");
+        assert_eq!(
+            response.headers()["link"],
+            "</api/v1/beads>; rel=\"successor-version\""
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Saved view REST API routes
+//!
+//! Provides CRUD endpoints for named board views (filters, grouping, sort)
+//! stored per project in the local database.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+
+use super::projects::AppState;
+use crate::db::{CreateViewInput, SavedView, UpdateViewInput};
+use crate::error::AppError;
+
+/// Query parameters for listing views
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewsParams {
+    /// Only return views of this project
+    pub project_id: Option<String>,
+}
+
+/// GET /api/views?projectId=... - List saved views
+pub async fn list_views(
+    State(db): State<AppState>,
+    Query(params): Query<ViewsParams>,
+) -> Result<Json<Vec<SavedView>>, AppError> {
+    db.get_views(params.project_id.as_deref())
+        .map(Json)
+        .map_err(AppError::from)
+}
+
+/// POST /api/views - Create a saved view
+pub async fn create_view(
+    State(db): State<AppState>,
+    Json(input): Json<CreateViewInput>,
+) -> Result<(StatusCode, Json<SavedView>), AppError> {
+    let view = db.create_view(input)?;
+    Ok((StatusCode::CREATED, Json(view)))
+}
+
+/// PATCH /api/views/:id - Update a saved view
+pub async fn update_view(
+    State(db): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<UpdateViewInput>,
+) -> Result<Json<SavedView>, AppError> {
+    db.update_view(&id, input).map(Json).map_err(AppError::from)
+}
+
+/// DELETE /api/views/:id - Delete a saved view
+pub async fn delete_view(
+    State(db): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    db.delete_view(&id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
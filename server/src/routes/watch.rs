@@ -1,24 +1,131 @@
-//! File watcher SSE endpoint for real-time file change notifications.
+//! File watcher endpoints for real-time file change notifications.
+//!
+//! Events for a project are delivered as Server-Sent Events
+//! (`GET /api/v1/events`, with each event named by its namespace; the older
+//! `GET /api/v1/watch/beads` sends bead changes with the default event
+//! type) or, for networks whose proxies buffer SSE, over a WebSocket
+//! (`GET /api/v1/ws`) as JSON text frames of the form
+//! `{"type": "<event>", "data": {...}}`. All carry the same events from
+//! the same watcher.
+//!
+//! Changes to the beads file are sent with the default event type, changes
+//! to memory files (`.beads/memory/*.jsonl`) and agent files
+//! (`.claude/agents/*.md`) as `memory` and `agents` events; each carries
+//! its `kind` (`beads`, `memory` or `agents`) too. Commits, checkouts and
+//! branch updates made outside the UI (`.git/HEAD` and refs), and worktrees
+//! added or removed, are sent as `git` events of kind `git`.
+//!
+//! Changes are detected with the platform's native file events, or by
+//! polling with `--watch-mode poll` for network filesystems and volumes
+//! that don't deliver them. In the default `auto` mode a project switches
+//! to polling once its issues file is seen changing without an event.
+//!
+//! Bead change events are only sent when the issues file's contents change,
+//! not for every write. They list the beads added, changed and removed since the
+//! previous one under `changes` (omitted past 100 beads), so clients can
+//! patch the board rather than refetch it.
+//!
+//! A client too slow to keep up with its stream gets a `resync_required`
+//! event and the stream is closed, rather than carrying on with events
+//! missing; the client should reload and reconnect.
+//!
+//! Every 30 seconds each stream gets a `heartbeat` event with the server's
+//! uptime, last known GitHub rate limit, watcher health and pending
+//! background jobs, which also keeps proxies from closing it while idle.
 //!
-//! Provides Server-Sent Events for monitoring changes to beads issue files.
 //! When the beads file changes, this module also recomputes epic statuses
-//! based on their children's statuses.
+//! based on their children's statuses and refreshes the bead index used by
+//! `GET /api/beads`. Mention notifications for the project are forwarded as
+//! `notification` events, PR status changes found by the background poller
+//! as `pr-status` events, progress of post-create worktree hooks as
+//! `worktree-hook` events, completed fetches of the repository as
+//! `git-fetch` events, and changes to whether its worktrees are dirty,
+//! ahead or behind as `worktree-state` events. Watchers stop when the
+//! server shuts down.
+//!
+//! Each project has at most one watcher, whose events are broadcast to all
+//! of the project's event streams. It starts with the first stream and stops
+//! when the last one goes away, except for projects in the workspace (see
+//! [`super::workspace`]), whose watchers run for the life of the server.
 
 use axum::{
-    extract::Query,
-    response::sse::{Event, Sse},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::{
+        sse::{Event, Sse},
+        Response,
+    },
 };
-use futures::stream::Stream;
+use futures::stream::{Stream, StreamExt};
+use clap::ValueEnum;
 use notify::{
-    event::ModifyKind, Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+    event::ModifyKind, Config, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher,
 };
 use serde::{Deserialize, Serialize};
-use std::{convert::Infallible, path::PathBuf, time::Duration};
-use tokio::sync::mpsc;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::{
+    convert::Infallible,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc, oneshot,
+};
 use tokio_stream::wrappers::ReceiverStream;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
+
+use super::beads::{parse_beads, recompute_epic_statuses, refresh_index, resolve_issues_path};
+use super::diagnostics::server_status;
+use super::notifications::subscribe;
+use super::worktree::WorktreeConfig;
+use super::worktree::{
+    subscribe_fetches, subscribe_hook_progress, subscribe_pr_status, subscribe_worktree_states,
+};
+use super::projects::AppState;
+use crate::db::Project;
+use crate::error::AppError;
+use crate::shutdown;
+
+/// Number of file watchers currently running.
+static ACTIVE_WATCHERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of file watchers currently running (one per project
+/// with an open event stream, or in the workspace).
+pub fn active_watchers() -> usize {
+    ACTIVE_WATCHERS.load(Ordering::Relaxed)
+}
+
+/// Number of file watchers currently polling.
+static POLLING_WATCHERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of file watchers polling rather than using native
+/// events.
+pub fn polling_watchers() -> usize {
+    POLLING_WATCHERS.load(Ordering::Relaxed)
+}
+
+/// Counts a watcher in one of the counters above while alive.
+struct CountedWatcher(&'static AtomicUsize);
+
+impl CountedWatcher {
+    fn start(counter: &'static AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Self(counter)
+    }
+}
 
-use super::beads::{recompute_epic_statuses, resolve_issues_path};
+impl Drop for CountedWatcher {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
 
 /// Query parameters for the watch endpoint.
 #[derive(Debug, Deserialize)]
@@ -27,6 +134,16 @@ pub struct WatchParams {
     pub path: String,
 }
 
+/// Query parameters for the multiplexed event stream; one of the two is
+/// required.
+#[derive(Debug, Deserialize)]
+pub struct EventsParams {
+    /// The project path to stream events for.
+    pub project: Option<String>,
+    /// Comma-separated registered project IDs, or `all`.
+    pub projects: Option<String>,
+}
+
 /// File change event sent to clients.
 #[derive(Debug, Serialize)]
 pub struct FileChangeEvent {
@@ -35,10 +152,453 @@ pub struct FileChangeEvent {
     /// The type of change (modified, created, removed).
     #[serde(rename = "type")]
     pub change_type: String,
+    /// What changed: `beads`, `memory`, `agents` or `git`.
+    pub kind: &'static str,
+    /// For bead changes, the beads that differ from the previous event's,
+    /// unless there were too many to list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<BeadChanges>,
+}
+
+/// Most beads a change event lists; past this clients refetch the board.
+const MAX_CHANGED_BEADS: usize = 100;
+
+/// Beads added, changed and removed by a change to the issues file.
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct BeadChanges {
+    /// New beads, in full
+    pub added: Vec<serde_json::Value>,
+    /// Beads that differ, in full
+    pub changed: Vec<serde_json::Value>,
+    /// IDs of beads no longer in the file
+    pub removed: Vec<String>,
+}
+
+/// Beads by ID, as served to clients.
+type BeadSnapshot = HashMap<String, serde_json::Value>;
+
+/// Reads the beads in the issues file; empty if it can't be read.
+fn load_snapshot(beads_file: &Path) -> BeadSnapshot {
+    let contents = std::fs::read_to_string(beads_file).unwrap_or_default();
+    parse_beads(&contents)
+        .into_iter()
+        .map(|bead| {
+            let id = bead.id.clone();
+            (id, serde_json::to_value(bead).unwrap_or_default())
+        })
+        .collect()
+}
+
+/// Hashes a file's contents; `None` if it can't be read.
+fn content_hash(path: &Path) -> Option<u64> {
+    let contents = std::fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+/// Compares two snapshots. `None` if more than [`MAX_CHANGED_BEADS`] beads
+/// differ.
+fn diff_snapshots(before: &BeadSnapshot, after: &BeadSnapshot) -> Option<BeadChanges> {
+    let mut changes = BeadChanges::default();
+    let mut ids: Vec<&String> = after.keys().collect();
+    ids.sort();
+    for id in ids {
+        let bead = &after[id];
+        match before.get(id) {
+            None => changes.added.push(bead.clone()),
+            Some(previous) if previous != bead => changes.changed.push(bead.clone()),
+            Some(_) => {}
+        }
+    }
+    changes.removed = before
+        .keys()
+        .filter(|id| !after.contains_key(*id))
+        .cloned()
+        .collect();
+    changes.removed.sort();
+
+    let total = changes.added.len() + changes.changed.len() + changes.removed.len();
+    (total <= MAX_CHANGED_BEADS).then_some(changes)
+}
+
+/// The kinds of project files watched for changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum WatchedKind {
+    /// The issues file
+    Beads,
+    /// `.beads/memory/*.jsonl`
+    Memory,
+    /// `.claude/agents/*.md`
+    Agents,
+    /// `.git/HEAD`, refs, and worktrees being added or removed
+    Git,
+}
+
+impl WatchedKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Beads => "beads",
+            Self::Memory => "memory",
+            Self::Agents => "agents",
+            Self::Git => "git",
+        }
+    }
+
+    /// Event name of changes of this kind. Bead changes keep the default
+    /// event type, which existing clients refresh the board on.
+    fn event_name(self) -> &'static str {
+        match self {
+            Self::Beads => CHANGE_EVENT,
+            kind => kind.as_str(),
+        }
+    }
+}
+
+/// Where a project's watched files are.
+struct WatchedPaths {
+    /// The issues file, which may live outside the project's `.beads`, see
+    /// [`resolve_issues_path`]
+    beads_file: PathBuf,
+    memory_dir: PathBuf,
+    agents_dir: PathBuf,
+    git_dir: PathBuf,
+    /// Where the project's worktrees are checked out
+    worktrees_dir: PathBuf,
+}
+
+impl WatchedPaths {
+    fn new(project_path: &Path, beads_file: PathBuf) -> Self {
+        Self {
+            beads_file,
+            memory_dir: project_path.join(".beads").join("memory"),
+            agents_dir: project_path.join(".claude").join("agents"),
+            git_dir: project_path.join(".git"),
+            worktrees_dir: WorktreeConfig::load(project_path).worktrees_dir(project_path),
+        }
+    }
+
+    /// Directories to watch besides the issues file's, which are optional:
+    /// each is only watched if it (or its parent) exists when the watcher
+    /// starts.
+    fn optional_targets(&self) -> Vec<(PathBuf, RecursiveMode)> {
+        let mut targets: Vec<(PathBuf, RecursiveMode)> = [&self.memory_dir, &self.agents_dir]
+            .into_iter()
+            .filter_map(|dir| watch_target(dir))
+            .map(|dir| (dir, RecursiveMode::Recursive))
+            .collect();
+        // Not the whole of `.git`, whose objects churn on every commit. A
+        // `.git` file rather than directory means the project is itself a
+        // worktree or submodule, which isn't followed.
+        if self.git_dir.is_dir() {
+            targets.push((self.git_dir.clone(), RecursiveMode::NonRecursive));
+            let refs = self.git_dir.join("refs");
+            if refs.is_dir() {
+                targets.push((refs, RecursiveMode::Recursive));
+            }
+        }
+        if self.worktrees_dir.is_dir() {
+            targets.push((self.worktrees_dir.clone(), RecursiveMode::NonRecursive));
+        }
+        targets
+    }
+
+    /// Returns what kind of watched file `path` is, if any.
+    fn classify(&self, path: &Path) -> Option<WatchedKind> {
+        let parent = path.parent();
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        if parent == Some(self.memory_dir.as_path()) && extension == Some("jsonl") {
+            return Some(WatchedKind::Memory);
+        }
+        if parent == Some(self.agents_dir.as_path()) && extension == Some("md") {
+            return Some(WatchedKind::Agents);
+        }
+        // Git writes `<ref>.lock` and renames it into place; the rename is
+        // reported on the ref itself
+        let is_git = path == self.git_dir.join("HEAD")
+            || path == self.git_dir.join("packed-refs")
+            || path.starts_with(self.git_dir.join("refs"))
+            || parent == Some(self.worktrees_dir.as_path());
+        if is_git && extension != Some("lock") {
+            return Some(WatchedKind::Git);
+        }
+        let is_beads = path.ends_with("issues.jsonl")
+            || path.ends_with(".beads")
+            || path == self.beads_file;
+        is_beads.then_some(WatchedKind::Beads)
+    }
+}
+
+/// The directory to watch for `dir`: itself, or its parent until it is
+/// created. `None` if neither exists.
+fn watch_target(dir: &Path) -> Option<PathBuf> {
+    if dir.exists() {
+        Some(dir.to_path_buf())
+    } else {
+        dir.parent().filter(|p| p.exists()).map(Path::to_path_buf)
+    }
+}
+
+/// Event name of file changes; the default SSE event type.
+const CHANGE_EVENT: &str = "message";
+
+/// Event name of the periodic server status.
+const HEARTBEAT_EVENT: &str = "heartbeat";
+
+/// Event name of the last event of a stream that fell behind: the client
+/// missed events, so should reload what it shows and reconnect.
+const RESYNC_EVENT: &str = "resync_required";
+
+/// How long a lagging client has to take the resync event.
+const RESYNC_SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Data of the resync event.
+#[derive(Debug, Serialize)]
+struct ResyncEvent {
+    /// Events the client missed
+    skipped: u64,
+}
+
+/// How often heartbeats are sent. They also keep proxies from closing idle
+/// connections.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// An event for a client, independent of transport.
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchMessage {
+    /// Event name: `message` for file changes, else e.g. `pr-status`
+    #[serde(rename = "type")]
+    pub event: &'static str,
+    pub data: serde_json::Value,
+}
+
+impl WatchMessage {
+    fn new(event: &'static str, data: &impl Serialize) -> Self {
+        Self {
+            event,
+            data: serde_json::to_value(data).unwrap_or_default(),
+        }
+    }
+
+    /// The event as sent on [`events`], named by its namespace: bead
+    /// changes as `beads` rather than the default type, and background
+    /// jobs as `jobs`.
+    fn namespaced(self) -> Self {
+        match self.event {
+            CHANGE_EVENT => Self {
+                event: "beads",
+                data: self.data,
+            },
+            "worktree-hook" | "git-fetch" => Self {
+                event: "jobs",
+                data: serde_json::json!({ "job": self.event, "data": self.data }),
+            },
+            _ => self,
+        }
+    }
+
+    /// Adds the ID of the project the event is about to its data.
+    fn tagged(mut self, project_id: &str) -> Self {
+        if let serde_json::Value::Object(data) = &mut self.data {
+            data.insert("project_id".to_string(), project_id.into());
+        }
+        self
+    }
+
+    fn into_sse(self) -> Event {
+        let event = Event::default().data(self.data.to_string());
+        if self.event == CHANGE_EVENT {
+            event
+        } else {
+            event.event(self.event)
+        }
+    }
+}
+
+/// A project's running watcher.
+struct ProjectWatcher {
+    /// Tells this watcher apart from later ones for the same project
+    id: u64,
+    events: broadcast::Sender<WatchMessage>,
+    /// Open event streams
+    subscribers: usize,
+    /// Kept running with no subscribers (workspace projects)
+    kept: bool,
+    /// Dropping this stops the watcher
+    _stop: oneshot::Sender<()>,
+}
+
+/// Running watchers, by project path.
+fn watchers() -> &'static Mutex<HashMap<PathBuf, ProjectWatcher>> {
+    static WATCHERS: OnceLock<Mutex<HashMap<PathBuf, ProjectWatcher>>> = OnceLock::new();
+    WATCHERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Returns the project's watcher, starting one if it has none.
+fn ensure_watcher<'a>(
+    watchers: &'a mut HashMap<PathBuf, ProjectWatcher>,
+    path: &str,
+) -> &'a mut ProjectWatcher {
+    watchers
+        .entry(PathBuf::from(path))
+        .or_insert_with(|| spawn_watcher(path))
+}
+
+/// Starts the watcher for the project at `path`. It runs until its
+/// registry entry is dropped or the server shuts down.
+fn spawn_watcher(path: &str) -> ProjectWatcher {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+    let project_path = PathBuf::from(path);
+    let beads_file = resolve_issues_path(&project_path);
+    info!("Starting file watcher for: {:?}", beads_file);
+
+    let (events, _) = broadcast::channel(100);
+    let (stop_tx, stop_rx) = oneshot::channel();
+    let tx = events.clone();
+    tokio::spawn(async move {
+        if let Err(e) = run_watcher(project_path.clone(), beads_file, tx, stop_rx).await {
+            error!("File watcher error: {}", e);
+        }
+        // The watcher stopped (or never started), which ends the streams
+        // sharing it; new ones start another
+        let mut watchers = watchers().lock().unwrap();
+        if watchers.get(&project_path).is_some_and(|w| w.id == id) {
+            watchers.remove(&project_path);
+        }
+    });
+
+    ProjectWatcher {
+        id,
+        events,
+        subscribers: 0,
+        kept: false,
+        _stop: stop_tx,
+    }
+}
+
+/// Returns the paths of the projects being watched.
+pub fn watched_projects() -> Vec<PathBuf> {
+    watchers().lock().unwrap().keys().cloned().collect()
+}
+
+/// Keeps a watcher running for the project at `path` until the server shuts
+/// down, whether or not any client is listening.
+pub fn keep_watching(path: &str) {
+    ensure_watcher(&mut watchers().lock().unwrap(), path).kept = true;
+}
+
+/// An event stream's hold on its project's watcher. The watcher stops when
+/// the last one is dropped, unless it is kept.
+struct Subscription {
+    path: PathBuf,
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut watchers = watchers().lock().unwrap();
+        let Some(watcher) = watchers.get_mut(&self.path).filter(|w| w.id == self.id) else {
+            return;
+        };
+        watcher.subscribers -= 1;
+        if watcher.subscribers == 0 && !watcher.kept {
+            info!("Last subscriber left, stopping watcher for {:?}", self.path);
+            watchers.remove(&self.path);
+        }
+    }
+}
+
+/// Returns the events for the project at `path` until the receiver is
+/// dropped or the server shuts down, starting with a `connected` event.
+/// All streams for a project share one watcher.
+fn start_watcher(path: &str) -> mpsc::Receiver<WatchMessage> {
+    let (subscription, mut events) = {
+        let mut watchers = watchers().lock().unwrap();
+        let watcher = ensure_watcher(&mut watchers, path);
+        watcher.subscribers += 1;
+        let subscription = Subscription {
+            path: PathBuf::from(path),
+            id: watcher.id,
+        };
+        (subscription, watcher.events.subscribe())
+    };
+
+    let (tx, rx) = mpsc::channel::<WatchMessage>(100);
+    let beads_file = resolve_issues_path(&PathBuf::from(path));
+    tokio::spawn(async move {
+        let _subscription = subscription;
+        let connected = WatchMessage::new(
+            CHANGE_EVENT,
+            &FileChangeEvent {
+                path: beads_file.to_string_lossy().to_string(),
+                change_type: "connected".to_string(),
+                kind: WatchedKind::Beads.as_str(),
+                changes: None,
+            },
+        );
+        if tx.send(connected).await.is_err() {
+            return;
+        }
+        loop {
+            let message = tokio::select! {
+                _ = shutdown::signalled() => break,
+                // Let go of the watcher as soon as the client leaves, not at
+                // the next event
+                _ = tx.closed() => break,
+                message = events.recv() => match message {
+                    Ok(message) => message,
+                    Err(RecvError::Lagged(skipped)) => {
+                        // Carrying on would leave the client with a board
+                        // missing changes it doesn't know about
+                        warn!(
+                            "Watch stream lagged, skipped {} events; asking the client to resync",
+                            skipped
+                        );
+                        let resync = WatchMessage::new(RESYNC_EVENT, &ResyncEvent { skipped });
+                        // Don't wait forever on a client that stopped reading
+                        let _ = tokio::time::timeout(RESYNC_SEND_TIMEOUT, tx.send(resync)).await;
+                        break;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            if tx.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Adds a `heartbeat` event with the server's status (see
+/// [`server_status`]) every [`HEARTBEAT_INTERVAL`] to `events`, until they
+/// end or one asks the client to resync. A stream merging several
+/// projects ends with the first of them to fall behind.
+fn with_heartbeats(
+    events: impl Stream<Item = WatchMessage> + Unpin,
+) -> impl Stream<Item = WatchMessage> {
+    let start = tokio::time::Instant::now() + HEARTBEAT_INTERVAL;
+    let heartbeat = tokio::time::interval_at(start, HEARTBEAT_INTERVAL);
+    let state = Some((events, heartbeat));
+    futures::stream::unfold(state, |state| async move {
+        let (mut events, mut heartbeat) = state?;
+        let message = tokio::select! {
+            message = events.next() => message?,
+            _ = heartbeat.tick() => WatchMessage::new(HEARTBEAT_EVENT, &server_status()),
+        };
+        if message.event == RESYNC_EVENT {
+            return Some((message, None));
+        }
+        Some((message, Some((events, heartbeat))))
+    })
 }
 
 /// SSE endpoint for watching beads file changes.
 ///
+/// Superseded by [`events`], which names every event by its namespace.
+///
 /// Monitors the `.beads/issues.jsonl` file in the specified project path
 /// and sends SSE events when changes are detected.
 ///
@@ -52,113 +612,387 @@ pub struct FileChangeEvent {
 pub async fn watch_beads(
     Query(params): Query<WatchParams>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
-    let project_path = PathBuf::from(&params.path);
-    let beads_file = resolve_issues_path(&project_path);
+    let stream = with_heartbeats(ReceiverStream::new(start_watcher(&params.path)))
+        .map(|message| Ok(message.into_sse()));
+    Sse::new(stream)
+}
 
-    info!("Starting file watcher for: {:?}", beads_file);
+/// Project event streams, with each event named by its namespace.
+///
+/// # Endpoint
+///
+/// `GET /api/v1/events?project=<path>`, or
+/// `GET /api/v1/events?projects=<id>,<id>` (`projects=all` for every
+/// registered project)
+///
+/// Carries the events of [`watch_beads`] on one connection, under the
+/// namespaces `beads`, `memory`, `agents`, `git`, `pr-status`,
+/// `worktree-state`, `notification` and `jobs` (worktree hook progress and repository
+/// fetches, as `{"job": "worktree-hook" | "git-fetch", "data": {...}}`).
+///
+/// With `projects`, the events of all the selected registered projects are
+/// merged and each carries its `project_id`. Projects registered after the
+/// stream opens aren't added to it.
+pub async fn events(
+    State(db): State<AppState>,
+    Query(params): Query<EventsParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    let streams: Vec<_> = match (&params.project, &params.projects) {
+        (Some(path), None) => vec![ReceiverStream::new(start_watcher(path))
+            .map(|message| message.namespaced())
+            .boxed()],
+        (None, Some(ids)) => {
+            let projects = db
+                .get_projects()
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            select_projects(projects, ids)
+                .into_iter()
+                .map(|project| {
+                    ReceiverStream::new(start_watcher(&project.path))
+                        .map(move |message| message.namespaced().tagged(&project.id))
+                        .boxed()
+                })
+                .collect()
+        }
+        _ => {
+            return Err(AppError::BadRequest(
+                "Pass either project or projects".to_string(),
+            ))
+        }
+    };
+    if streams.is_empty() {
+        return Err(AppError::NotFound("No matching projects".to_string()));
+    }
+    let stream = with_heartbeats(futures::stream::select_all(streams))
+        .map(|message| Ok(message.into_sse()));
+    Ok(Sse::new(stream))
+}
 
-    // Create channel for events with buffer for debouncing
-    let (tx, rx) = mpsc::channel::<Result<Event, Infallible>>(100);
+/// Selects the registered projects named by a `projects` parameter: a
+/// comma-separated list of IDs, or `all`. Unknown IDs are ignored.
+fn select_projects(projects: Vec<Project>, ids: &str) -> Vec<Project> {
+    if ids.trim() == "all" {
+        return projects;
+    }
+    let ids: Vec<&str> = ids.split(',').map(str::trim).collect();
+    projects
+        .into_iter()
+        .filter(|project| ids.contains(&project.id.as_str()))
+        .collect()
+}
 
-    // Spawn the watcher task
-    tokio::spawn(async move {
-        if let Err(e) = run_watcher(beads_file, tx).await {
-            error!("File watcher error: {}", e);
+/// WebSocket endpoint carrying the same events as [`watch_beads`].
+///
+/// # Endpoint
+///
+/// `GET /api/v1/ws?path=<project>` (upgraded to a WebSocket)
+pub async fn watch_beads_ws(
+    ws: WebSocketUpgrade,
+    Query(params): Query<WatchParams>,
+) -> Response {
+    let events = with_heartbeats(ReceiverStream::new(start_watcher(&params.path)));
+    ws.on_upgrade(move |socket| forward_to_socket(socket, Box::pin(events)))
+}
+
+/// Sends watcher events to the socket until either side goes away.
+async fn forward_to_socket(
+    mut socket: WebSocket,
+    mut events: impl Stream<Item = WatchMessage> + Unpin,
+) {
+    loop {
+        tokio::select! {
+            message = events.next() => {
+                let Some(message) = message else { break };
+                let text = serde_json::to_string(&message).unwrap_or_default();
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Clients don't send anything meaningful; pongs are automatic
+                Some(Ok(_)) => {}
+            },
         }
-    });
+    }
+    let _ = socket.send(Message::Close(None)).await;
+}
+
+/// How file changes are detected.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchMode {
+    /// Native events, switching a project to polling if its issues file
+    /// changes without one
+    #[default]
+    Auto,
+    /// Native events only (inotify, FSEvents, ...)
+    Native,
+    /// Polling, for network filesystems and volumes without native events
+    Poll,
+}
+
+/// The configured watch mode.
+static WATCH_MODE: OnceLock<WatchMode> = OnceLock::new();
 
-    let stream = ReceiverStream::new(rx);
-    Sse::new(stream).keep_alive(
-        axum::response::sse::KeepAlive::new()
-            .interval(Duration::from_secs(30))
-            .text("ping"),
-    )
+/// Sets how watchers started from now on detect changes.
+pub fn set_watch_mode(mode: WatchMode) {
+    let _ = WATCH_MODE.set(mode);
+}
+
+fn watch_mode() -> WatchMode {
+    WATCH_MODE.get().copied().unwrap_or_default()
+}
+
+/// How often polling watchers scan for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often auto mode checks for changes native events missed.
+const MISSED_CHANGE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long after a modification its event may still be on the way.
+const MISSED_CHANGE_GRACE: Duration = Duration::from_secs(2);
+
+/// Creates a native or polling watcher on `watch_paths`, sending its events
+/// to `notify_tx`.
+fn create_watcher(
+    poll: bool,
+    watch_paths: &[(PathBuf, RecursiveMode)],
+    notify_tx: mpsc::Sender<notify::Event>,
+) -> notify::Result<Box<dyn Watcher + Send>> {
+    let handler = move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res {
+            let _ = notify_tx.blocking_send(event);
+        }
+    };
+    let mut watcher: Box<dyn Watcher + Send> = if poll {
+        Box::new(PollWatcher::new(
+            handler,
+            Config::default().with_poll_interval(POLL_INTERVAL),
+        )?)
+    } else {
+        Box::new(RecommendedWatcher::new(handler, Config::default())?)
+    };
+    for (path, mode) in watch_paths {
+        watcher.watch(path, *mode)?;
+    }
+    Ok(watcher)
+}
+
+/// When a file was last modified; `None` if it can't be read.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Whether `path` was modified since `seen`, long enough ago that its event
+/// should have arrived.
+fn missed_change(path: &Path, seen: Option<SystemTime>) -> bool {
+    let Some(modified) = modified_time(path) else {
+        return false;
+    };
+    Some(modified) != seen && modified.elapsed().is_ok_and(|age| age >= MISSED_CHANGE_GRACE)
 }
 
 /// Runs the file watcher and sends events through the channel.
 async fn run_watcher(
+    project_path: PathBuf,
     beads_file: PathBuf,
-    tx: mpsc::Sender<Result<Event, Infallible>>,
+    tx: broadcast::Sender<WatchMessage>,
+    mut stop: oneshot::Receiver<()>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    // Create a channel for notify events
-    let (notify_tx, mut notify_rx) = mpsc::channel(100);
-
-    // Create the watcher
-    let mut watcher = RecommendedWatcher::new(
-        move |res: notify::Result<notify::Event>| {
-            if let Ok(event) = res {
-                // Only forward relevant events
-                let _ = notify_tx.blocking_send(event);
-            }
-        },
-        Config::default().with_poll_interval(Duration::from_millis(100)),
-    )?;
-
     // Watch the parent directory (.beads) since the file might not exist yet
     let watch_path = beads_file
         .parent()
         .map(|p| p.to_path_buf())
         .unwrap_or_else(|| beads_file.clone());
 
-    // Create the .beads directory if it doesn't exist
     if !watch_path.exists() {
         warn!(
             "Watch path does not exist, waiting for creation: {:?}",
             watch_path
         );
     }
-
-    // Try to watch the path, or watch parent if it doesn't exist
-    let actual_watch_path = if watch_path.exists() {
-        watch_path.clone()
-    } else if let Some(parent) = watch_path.parent() {
-        if parent.exists() {
-            parent.to_path_buf()
-        } else {
-            error!("Neither watch path nor parent exists: {:?}", watch_path);
-            return Ok(());
-        }
-    } else {
-        error!("No valid path to watch: {:?}", watch_path);
+    let Some(beads_watch_path) = watch_target(&watch_path) else {
+        error!("Neither watch path nor parent exists: {:?}", watch_path);
         return Ok(());
     };
 
-    watcher.watch(&actual_watch_path, RecursiveMode::Recursive)?;
-    info!("File watcher active on: {:?}", actual_watch_path);
+    let paths = WatchedPaths::new(&project_path, beads_file.clone());
+    let mut watch_paths = vec![(beads_watch_path, RecursiveMode::Recursive)];
+    for (path, mode) in paths.optional_targets() {
+        // A directory inside one already watched recursively is covered
+        // (and watching it again would double its events)
+        let covered = watch_paths.iter().any(|(watched, watched_mode)| {
+            *watched_mode == RecursiveMode::Recursive && path.starts_with(watched)
+        });
+        if !covered {
+            watch_paths.push((path, mode));
+        }
+    }
 
-    // Send initial connection event
-    let connect_event = Event::default().data(
-        serde_json::to_string(&FileChangeEvent {
-            path: beads_file.to_string_lossy().to_string(),
-            change_type: "connected".to_string(),
-        })
-        .unwrap_or_default(),
+    // Create a channel for notify events
+    let (notify_tx, mut notify_rx) = mpsc::channel(100);
+    let mode = watch_mode();
+    let mut polling = mode == WatchMode::Poll;
+    // Watches for as long as it is alive
+    let mut _watcher = create_watcher(polling, &watch_paths, notify_tx.clone())?;
+    // When the issues file was last modified, as of its last event; in auto
+    // mode a later modification with no event means native events are
+    // missing
+    let mut seen_modified = modified_time(&beads_file);
+    let mut missed_check = tokio::time::interval(MISSED_CHANGE_CHECK_INTERVAL);
+    let _active = CountedWatcher::start(&ACTIVE_WATCHERS);
+    let mut _polling = polling.then(|| CountedWatcher::start(&POLLING_WATCHERS));
+    // The beads as of the last change event, which the next one is a diff
+    // against
+    let mut snapshot = load_snapshot(&beads_file);
+    // Hash of the issues file as of the last change event; `None` while it
+    // doesn't exist
+    let mut served_hash = content_hash(&beads_file);
+    info!(
+        "File watcher active on: {:?}",
+        watch_paths.iter().map(|(path, _)| path).collect::<Vec<_>>()
     );
-    let _ = tx.send(Ok(connect_event)).await;
 
-    // Debounce state
-    let mut last_event_time = std::time::Instant::now();
+    // Debounce state, per kind of file so e.g. an agent edit doesn't hide
+    // a bead change
+    let mut last_event_times: HashMap<WatchedKind, std::time::Instant> = HashMap::new();
     let debounce_duration = Duration::from_millis(100);
 
+    // Mention notifications published by this server for the same project
+    let mut notifications = subscribe();
+    // PR status changes for worktrees of the project
+    let mut pr_statuses = subscribe_pr_status();
+    // Worktree hook progress for the project
+    let mut hook_progress = subscribe_hook_progress();
+    // Fetches of the project's repository
+    let mut fetches = subscribe_fetches();
+    // Dirty/ahead/behind changes of the project's worktrees
+    let mut worktree_states = subscribe_worktree_states();
+
     // Process events
-    while let Some(event) = notify_rx.recv().await {
-        // Check if the event is for our target file
-        let is_relevant = event.paths.iter().any(|p| {
-            p.ends_with("issues.jsonl")
-                || p.ends_with(".beads")
-                || p == &beads_file
-        });
+    loop {
+        let event = tokio::select! {
+            // Ending the stream lets the server's graceful shutdown finish
+            _ = shutdown::signalled() => break,
+            // The last subscriber left
+            _ = &mut stop => break,
+            event = notify_rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            _ = missed_check.tick(), if mode == WatchMode::Auto && !polling => {
+                if !missed_change(&beads_file, seen_modified) {
+                    continue;
+                }
+                warn!(
+                    "{:?} changed without a file event, switching to polling",
+                    beads_file
+                );
+                _watcher = create_watcher(true, &watch_paths, notify_tx.clone())?;
+                polling = true;
+                _polling = Some(CountedWatcher::start(&POLLING_WATCHERS));
+                // Deliver the missed change
+                notify::Event::new(EventKind::Modify(ModifyKind::Any)).add_path(beads_file.clone())
+            }
+            published = notifications.recv() => {
+                match published {
+                    Ok(published) if published.issues_path == beads_file => {
+                        let message = WatchMessage::new("notification", &published.notification);
+                        // No one listening is fine
+                        let _ = tx.send(message);
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Notification stream lagged, skipped {} notifications", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+                continue;
+            }
+            published = pr_statuses.recv() => {
+                match published {
+                    Ok(published) if published.repo_path == project_path => {
+                        let message = WatchMessage::new("pr-status", &published.event);
+                        // No one listening is fine
+                        let _ = tx.send(message);
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("PR status stream lagged, skipped {} updates", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+                continue;
+            }
+            published = hook_progress.recv() => {
+                match published {
+                    Ok(published) if published.repo_path == project_path => {
+                        let message = WatchMessage::new("worktree-hook", &published.event);
+                        // No one listening is fine
+                        let _ = tx.send(message);
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Worktree hook stream lagged, skipped {} updates", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+                continue;
+            }
+            published = fetches.recv() => {
+                match published {
+                    Ok(published) if published.repo_path == project_path => {
+                        let message = WatchMessage::new("git-fetch", &published.event);
+                        // No one listening is fine
+                        let _ = tx.send(message);
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Fetch stream lagged, skipped {} updates", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+                continue;
+            }
+            published = worktree_states.recv() => {
+                match published {
+                    Ok(published) if published.repo_path == project_path => {
+                        let message = WatchMessage::new("worktree-state", &published.event);
+                        // No one listening is fine
+                        let _ = tx.send(message);
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("Worktree state stream lagged, skipped {} updates", skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+                continue;
+            }
+        };
 
-        if !is_relevant {
+        let Some((kind, changed)) = event
+            .paths
+            .iter()
+            .find_map(|p| paths.classify(p).map(|kind| (kind, p)))
+        else {
             continue;
+        };
+
+        if kind == WatchedKind::Beads {
+            seen_modified = modified_time(&beads_file);
         }
 
         // Debounce rapid changes
         let now = std::time::Instant::now();
-        if now.duration_since(last_event_time) < debounce_duration {
+        if last_event_times
+            .get(&kind)
+            .is_some_and(|last| now.duration_since(*last) < debounce_duration)
+        {
             continue;
         }
-        last_event_time = now;
+        last_event_times.insert(kind, now);
 
         // Determine event type
         let change_type = match event.kind {
@@ -169,17 +1003,38 @@ async fn run_watcher(
             _ => continue, // Ignore other events
         };
 
-        let file_event = FileChangeEvent {
-            path: beads_file.to_string_lossy().to_string(),
+        // Bead events name the issues file, as there is only one
+        let path = match kind {
+            WatchedKind::Beads => &beads_file,
+            _ => changed,
+        };
+        let mut file_event = FileChangeEvent {
+            path: path.to_string_lossy().to_string(),
             change_type: change_type.to_string(),
+            kind: kind.as_str(),
+            changes: None,
         };
 
+        if kind != WatchedKind::Beads {
+            info!("File change detected: {:?}", file_event);
+            let _ = tx.send(WatchMessage::new(kind.event_name(), &file_event));
+            continue;
+        }
+
+        // Skip events that leave the contents as they were, like `bd`
+        // touching the file or the recompute below rewriting it unchanged
+        let hash = content_hash(&beads_file);
+        if hash == served_hash {
+            debug!("Issues file unchanged, skipping event: {:?}", event.kind);
+            continue;
+        }
+
         info!("File change detected: {:?}", file_event);
 
         // Recompute epic statuses when beads file is modified
         // This ensures epic status stays in sync with children
         if change_type == "modified" || change_type == "created" {
-            match recompute_epic_statuses(&beads_file) {
+            match recompute_epic_statuses(&beads_file).await {
                 Ok(updated_epics) => {
                     if !updated_epics.is_empty() {
                         info!("Updated epic statuses: {:?}", updated_epics);
@@ -191,14 +1046,21 @@ async fn run_watcher(
             }
         }
 
-        let sse_event = Event::default()
-            .data(serde_json::to_string(&file_event).unwrap_or_default());
-
-        // If send fails, client disconnected
-        if tx.send(Ok(sse_event)).await.is_err() {
-            info!("Client disconnected, stopping watcher");
-            break;
+        // Keep the bead index in step with the file
+        if let Err(e) = refresh_index(&beads_file) {
+            warn!("Failed to refresh bead index: {}", e);
         }
+
+        // The recompute may have written the file, whose event should then
+        // be skipped
+        served_hash = content_hash(&beads_file);
+        seen_modified = modified_time(&beads_file);
+        let next_snapshot = load_snapshot(&beads_file);
+        file_event.changes = diff_snapshots(&snapshot, &next_snapshot);
+        snapshot = next_snapshot;
+
+        // No one listening is fine; the watcher still keeps the index fresh
+        let _ = tx.send(WatchMessage::new(kind.event_name(), &file_event));
     }
 
     // Watcher is automatically dropped and cleaned up here
@@ -215,10 +1077,250 @@ mod tests {
         let event = FileChangeEvent {
             path: "/test/path".to_string(),
             change_type: "modified".to_string(),
+            kind: "beads",
+            changes: None,
         };
         let json = serde_json::to_string(&event).unwrap();
         assert!(json.contains("\"path\":\"/test/path\""));
         assert!(json.contains("\"type\":\"modified\""));
+        assert!(json.contains("\"kind\":\"beads\""));
+    }
+
+    #[test]
+    fn test_watch_message() {
+        let event = FileChangeEvent {
+            path: "/test/path".to_string(),
+            change_type: "modified".to_string(),
+            kind: "beads",
+            changes: None,
+        };
+        let message = WatchMessage::new(CHANGE_EVENT, &event);
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "type": "message",
+                "data": { "path": "/test/path", "type": "modified", "kind": "beads" }
+            })
+        );
+
+        let message = WatchMessage::new("pr-status", &serde_json::json!({ "bead_id": "b-1" }));
+        assert_eq!(message.event, "pr-status");
+    }
+
+    #[test]
+    fn test_classify() {
+        let project = Path::new("/p");
+        let paths = WatchedPaths::new(project, project.join(".beads/issues.jsonl"));
+        let kind = |path: &str| paths.classify(&project.join(path));
+
+        assert_eq!(kind(".beads/issues.jsonl"), Some(WatchedKind::Beads));
+        assert_eq!(kind(".beads"), Some(WatchedKind::Beads));
+        assert_eq!(kind(".beads/memory/knowledge.jsonl"), Some(WatchedKind::Memory));
+        assert_eq!(kind(".claude/agents/coder.md"), Some(WatchedKind::Agents));
+        assert_eq!(kind(".beads/memory/notes.txt"), None);
+        assert_eq!(kind(".claude/agents/nested/coder.md"), None);
+        assert_eq!(kind(".beads/config.yaml"), None);
+        assert_eq!(kind(".git/HEAD"), Some(WatchedKind::Git));
+        assert_eq!(kind(".git/refs/heads/main"), Some(WatchedKind::Git));
+        assert_eq!(kind(".git/refs/heads/main.lock"), None);
+        assert_eq!(kind(".git/index"), None);
+        assert_eq!(kind(".worktrees/bd-1"), Some(WatchedKind::Git));
+        assert_eq!(kind(".worktrees/bd-1/src/main.rs"), None);
+        assert_eq!(WatchedKind::Beads.event_name(), CHANGE_EVENT);
+        assert_eq!(WatchedKind::Agents.event_name(), "agents");
+    }
+
+    #[test]
+    fn test_missed_change() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("issues.jsonl");
+        assert!(!missed_change(&file, None));
+
+        std::fs::write(&file, "").unwrap();
+        let old = SystemTime::now() - Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&file)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        assert!(missed_change(&file, None));
+        assert!(!missed_change(&file, modified_time(&file)));
+
+        // Too recent to tell
+        std::fs::write(&file, "{}").unwrap();
+        assert!(!missed_change(&file, Some(old)));
+    }
+
+    #[test]
+    fn test_content_hash() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("issues.jsonl");
+        assert_eq!(content_hash(&file), None);
+
+        std::fs::write(&file, "{\"id\":\"b-1\"}\n").unwrap();
+        let hash = content_hash(&file);
+        assert!(hash.is_some());
+        std::fs::write(&file, "{\"id\":\"b-1\"}\n").unwrap();
+        assert_eq!(content_hash(&file), hash);
+        std::fs::write(&file, "{\"id\":\"b-2\"}\n").unwrap();
+        assert_ne!(content_hash(&file), hash);
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let snapshot = |beads: &[(&str, &str)]| -> BeadSnapshot {
+            beads
+                .iter()
+                .map(|(id, status)| {
+                    (id.to_string(), serde_json::json!({ "id": id, "status": status }))
+                })
+                .collect()
+        };
+        let before = snapshot(&[("b-1", "open"), ("b-2", "open"), ("b-3", "open")]);
+        let after = snapshot(&[("b-1", "open"), ("b-2", "closed"), ("b-4", "open")]);
+
+        let changes = diff_snapshots(&before, &after).unwrap();
+        assert_eq!(changes.added, vec![after["b-4"].clone()]);
+        assert_eq!(changes.changed, vec![after["b-2"].clone()]);
+        assert_eq!(changes.removed, vec!["b-3".to_string()]);
+        assert_eq!(diff_snapshots(&after, &after), Some(BeadChanges::default()));
+
+        let many: BeadSnapshot = (0..=MAX_CHANGED_BEADS)
+            .map(|i| (format!("b-{}", i), serde_json::json!({})))
+            .collect();
+        assert_eq!(diff_snapshots(&BeadSnapshot::new(), &many), None);
+    }
+
+    #[tokio::test]
+    async fn test_streams_share_watcher() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".beads")).unwrap();
+        let path = tmp.path().to_string_lossy().to_string();
+        let key = PathBuf::from(&path);
+        let subscribers = || {
+            watchers()
+                .lock()
+                .unwrap()
+                .get(&key)
+                .map(|w| w.subscribers)
+        };
+
+        let mut first = start_watcher(&path);
+        let mut second = start_watcher(&path);
+        assert_eq!(subscribers(), Some(2));
+        for rx in [&mut first, &mut second] {
+            let connected = rx.recv().await.unwrap();
+            assert_eq!(connected.data["type"], "connected");
+        }
+
+        drop(first);
+        drop(second);
+        for _ in 0..100 {
+            if subscribers().is_none() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(subscribers(), None);
+    }
+
+    #[test]
+    fn test_namespaced() {
+        let beads = WatchMessage::new(CHANGE_EVENT, &serde_json::json!({ "type": "modified" }));
+        assert_eq!(beads.namespaced().event, "beads");
+
+        let hook = WatchMessage::new("worktree-hook", &serde_json::json!({ "bead_id": "b-1" }));
+        let hook = hook.namespaced();
+        assert_eq!(hook.event, "jobs");
+        assert_eq!(
+            hook.data,
+            serde_json::json!({ "job": "worktree-hook", "data": { "bead_id": "b-1" } })
+        );
+
+        let git = WatchMessage::new("git", &serde_json::json!({}));
+        assert_eq!(git.namespaced().event, "git");
+    }
+
+    #[test]
+    fn test_select_projects() {
+        let project = |id: &str| Project {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: format!("/{}", id),
+            last_opened: String::new(),
+            created_at: String::new(),
+        };
+        let projects = vec![project("a"), project("b"), project("c")];
+        let ids = |selected: Vec<Project>| -> Vec<String> {
+            selected.into_iter().map(|p| p.id).collect()
+        };
+
+        assert_eq!(ids(select_projects(projects.clone(), "all")), ["a", "b", "c"]);
+        assert_eq!(ids(select_projects(projects.clone(), "c, a,x")), ["a", "c"]);
+        assert!(select_projects(projects, "").is_empty());
+    }
+
+    #[test]
+    fn test_tagged() {
+        let message = WatchMessage::new("git", &serde_json::json!({ "type": "modified" }));
+        assert_eq!(
+            message.tagged("p-1").data,
+            serde_json::json!({ "type": "modified", "project_id": "p-1" })
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_with_heartbeats() {
+        let (tx, rx) = mpsc::channel(1);
+        let mut events = Box::pin(with_heartbeats(ReceiverStream::new(rx)));
+
+        tx.send(WatchMessage::new("git", &serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(events.next().await.unwrap().event, "git");
+
+        let heartbeat = events.next().await.unwrap();
+        assert_eq!(heartbeat.event, HEARTBEAT_EVENT);
+        assert!(heartbeat.data["watchers"]["active"].is_number());
+
+        drop(tx);
+        assert!(events.next().await.is_none());
+
+        // A resync ends the stream
+        let (tx, rx) = mpsc::channel(2);
+        let mut events = Box::pin(with_heartbeats(ReceiverStream::new(rx)));
+        tx.send(WatchMessage::new(RESYNC_EVENT, &ResyncEvent { skipped: 1 }))
+            .await
+            .unwrap();
+        assert_eq!(events.next().await.unwrap().event, RESYNC_EVENT);
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lagging_stream_resyncs() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join(".beads")).unwrap();
+        let path = tmp.path().to_string_lossy().to_string();
+
+        let mut rx = start_watcher(&path);
+        let events = watchers()
+            .lock()
+            .unwrap()
+            .get(&PathBuf::from(&path))
+            .unwrap()
+            .events
+            .clone();
+        // Overflow the broadcast before the stream's task gets to run
+        for _ in 0..300 {
+            let _ = events.send(WatchMessage::new("git", &serde_json::json!({})));
+        }
+
+        assert_eq!(rx.recv().await.unwrap().data["type"], "connected");
+        let resync = rx.recv().await.unwrap();
+        assert_eq!(resync.event, RESYNC_EVENT);
+        assert!(resync.data["skipped"].as_u64().unwrap() > 0);
+        assert!(rx.recv().await.is_none());
     }
 
     #[test]
@@ -0,0 +1,132 @@
+//! Multi-project workspace.
+//!
+//! Launching with several `--project` paths, or with `--all-projects`, makes
+//! those projects the workspace. Each gets a file watcher that runs for the
+//! life of the server, keeping its bead index and epic statuses current and
+//! shared by every event stream for the project, so switching between
+//! projects in the UI doesn't tear anything down. `GET /api/v1/workspace`
+//! summarizes them with bead counts by status.
+
+use axum::{extract::State, response::IntoResponse, Json};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::info;
+
+use super::beads::{parse_beads, resolve_issues_path, Bead};
+use super::projects::AppState;
+use super::validate_path_security;
+use super::watch::keep_watching;
+use crate::db::Project;
+use crate::error::AppError;
+
+/// IDs of the workspace projects, in launch order.
+static WORKSPACE: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Makes `projects` the workspace and starts watching them.
+pub fn init(projects: &[Project]) {
+    let mut ids: Vec<String> = Vec::new();
+    for project in projects {
+        if ids.contains(&project.id) {
+            continue;
+        }
+        ids.push(project.id.clone());
+        keep_watching(&project.path);
+    }
+    if !ids.is_empty() {
+        info!("Watching {} workspace project(s)", ids.len());
+    }
+    let _ = WORKSPACE.set(ids);
+}
+
+/// Summary of a workspace project.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct WorkspaceProject {
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub open: usize,
+    pub in_progress: usize,
+    /// All beads, including closed ones
+    pub total: usize,
+    /// Set when the project's issues file could not be read.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl WorkspaceProject {
+    fn count(&mut self, beads: &[Bead]) {
+        self.total = beads.len();
+        self.open = beads.iter().filter(|b| b.status == "open").count();
+        self.in_progress = beads.iter().filter(|b| b.status == "in_progress").count();
+    }
+}
+
+/// GET /api/v1/workspace
+///
+/// Lists the workspace projects with their open and in-progress bead
+/// counts. Empty when the server was launched without a workspace.
+pub async fn workspace_summary(State(db): State<AppState>) -> Result<impl IntoResponse, AppError> {
+    let ids = WORKSPACE.get().map(Vec::as_slice).unwrap_or_default();
+    let projects = db
+        .get_projects()
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    // Projects removed since launch drop out
+    let selected: Vec<Project> = ids
+        .iter()
+        .filter_map(|id| projects.iter().find(|p| &p.id == id).cloned())
+        .collect();
+
+    let reads = selected.iter().map(|project| {
+        let project_path = PathBuf::from(&project.path);
+        async move {
+            validate_path_security(&project_path)?;
+            tokio::fs::read_to_string(resolve_issues_path(&project_path))
+                .await
+                .map_err(|e| format!("Failed to read file: {}", e))
+        }
+    });
+    let results = futures::future::join_all(reads).await;
+
+    let summaries: Vec<WorkspaceProject> = selected
+        .into_iter()
+        .zip(results)
+        .map(|(project, result)| {
+            let mut summary = WorkspaceProject {
+                id: project.id,
+                name: project.name,
+                path: project.path,
+                ..Default::default()
+            };
+            match result {
+                Ok(contents) => summary.count(&parse_beads(&contents)),
+                Err(e) => summary.error = Some(e),
+            }
+            summary
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({ "projects": summaries })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        let beads = parse_beads(concat!(
+            r#"{"id":"b-1","title":"One","status":"open"}"#,
+            "\n",
+            r#"{"id":"b-2","title":"Two","status":"in_progress"}"#,
+            "\n",
+            r#"{"id":"b-3","title":"Three","status":"closed"}"#,
+            "\n",
+            r#"{"id":"b-4","title":"Four","status":"open"}"#,
+            "\n",
+        ));
+        let mut summary = WorkspaceProject::default();
+        summary.count(&beads);
+        assert_eq!((summary.open, summary.in_progress, summary.total), (2, 1, 4));
+    }
+}
@@ -2,15 +2,63 @@
 //!
 //! Provides endpoints for:
 //! - Worktree CRUD operations (create, delete, list, status)
-//! - PR status checking and management via GitHub CLI
-
-use axum::{extract::Query, http::StatusCode, response::IntoResponse, Json};
+//! - Committing changes in a worktree
+//! - Resolving rebase and merge conflicts
+//! - PR status checking and management, with status refreshed in the
+//!   background (see `poller`)
+//! - Previewing a PR's commits and changed files before it is opened
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs::{self, OpenOptions};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::process::Command;
+use tokio::sync::Semaphore;
+
+use crate::db::Database;
+use crate::git_backend::{self, GitWorktree};
+use crate::logging::LogCommand;
+use crate::routes::beads::{parse_beads, resolve_issues_path, Bead};
+use crate::routes::projects::AppState;
+
+mod auto_merge;
+mod cherry_pick;
+mod complete;
+mod config;
+mod conflicts;
+mod fetch;
+mod hooks;
+mod poller;
+mod pr;
+mod state;
+mod tag;
+
+use crate::error::AppError;
+pub use auto_merge::auto_merge;
+pub use cherry_pick::cherry_pick;
+pub use complete::complete_bead;
+pub use config::{get_worktree_config, update_worktree_config};
+pub use conflicts::{
+    abort_operation, conflict_file, continue_operation, list_conflicts, resolve_conflict,
+};
+pub use fetch::{
+    fetch, in_progress as fetches_in_progress, run_fetch_scheduler, subscribe as subscribe_fetches,
+};
+pub use hooks::{
+    hook_status, running as running_hooks, subscribe as subscribe_hook_progress, trust_hooks,
+};
+pub use poller::{run_pr_status_poller, subscribe as subscribe_pr_status};
+pub use state::{run_worktree_state_tracker, subscribe as subscribe_worktree_states};
+pub use tag::create_tag;
+pub(crate) use config::WorktreeConfig;
+use pr::{PrMetadata, PrProvider};
 
 // ============================================================================
 // Worktree Status Endpoint
@@ -59,25 +107,25 @@ pub struct WorktreeStatusResponse {
 /// # Response
 ///
 /// Returns worktree existence, path, branch, ahead/behind counts, and dirty status.
-pub async fn worktree_status(Query(params): Query<WorktreeStatusParams>) -> impl IntoResponse {
+pub async fn worktree_status(
+    Query(params): Query<WorktreeStatusParams>,
+) -> Result<impl IntoResponse, AppError> {
     let repo_path = Path::new(&params.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Repository path does not exist: {}", params.repo_path)
-            })),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            params.repo_path
+        )));
     }
 
-    let branch_name = format!("bd-{}", params.bead_id);
-    let worktree_path = repo_path.join(".worktrees").join(&branch_name);
+    let config = WorktreeConfig::load(repo_path);
+    let branch_name = config.branch_name(&params.bead_id);
+    let worktree_path = config.worktree_path(repo_path, &params.bead_id);
 
     if !worktree_path.exists() {
-        return Json(WorktreeStatusResponse {
+        return Ok(Json(WorktreeStatusResponse {
             exists: false,
             worktree_path: None,
             branch: None,
@@ -85,8 +133,7 @@ pub async fn worktree_status(Query(params): Query<WorktreeStatusParams>) -> impl
             behind: None,
             dirty: None,
             last_modified: None,
-        })
-        .into_response();
+        }));
     }
 
     // Get ahead/behind counts relative to main
@@ -98,7 +145,7 @@ pub async fn worktree_status(Query(params): Query<WorktreeStatusParams>) -> impl
     // Get last modification time
     let last_modified = get_last_modified(&worktree_path);
 
-    Json(WorktreeStatusResponse {
+    Ok(Json(WorktreeStatusResponse {
         exists: true,
         worktree_path: Some(worktree_path.to_string_lossy().to_string()),
         branch: Some(branch_name),
@@ -106,8 +153,7 @@ pub async fn worktree_status(Query(params): Query<WorktreeStatusParams>) -> impl
         behind: Some(behind),
         dirty: Some(dirty),
         last_modified,
-    })
-    .into_response()
+    }))
 }
 
 // ============================================================================
@@ -141,10 +187,20 @@ pub struct CreateWorktreeResponse {
     pub branch: String,
     /// True if worktree already existed (idempotent response).
     pub already_existed: bool,
+    /// Whether post-create hooks started running; follow them with
+    /// `GET /api/git/worktree-hooks` or `worktree-hook` watch events.
+    pub hooks_started: bool,
+    /// `run` hooks that held back all hooks because the user has not
+    /// approved them; see `POST /api/git/worktree-hooks/trust`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub untrusted_hooks: Option<hooks::UntrustedHooks>,
 }
 
 /// Create a worktree for a bead. This operation is idempotent.
 ///
+/// A newly added worktree starts the project's post-create hooks (see
+/// `hooks`) in the background, once the user has approved their commands.
+///
 /// # Endpoint
 ///
 /// `POST /api/git/worktree`
@@ -162,49 +218,49 @@ pub struct CreateWorktreeResponse {
 /// # Response
 ///
 /// Returns the worktree path and whether it already existed.
-pub async fn create_worktree(Json(request): Json<CreateWorktreeRequest>) -> impl IntoResponse {
+pub async fn create_worktree(
+    State(db): State<AppState>,
+    Json(request): Json<CreateWorktreeRequest>,
+) -> Result<impl IntoResponse, AppError> {
     let repo_path = Path::new(&request.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() || !repo_path.is_dir() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Repository path does not exist: {}", request.repo_path)
-            })),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
     }
 
-    // Ensure .worktrees/ is in .gitignore
-    if let Err(e) = ensure_gitignore_entry(&request.repo_path) {
+    let config = WorktreeConfig::load(repo_path);
+
+    // Ensure the worktree directory is in .gitignore
+    if let Err(e) = ensure_gitignore_entry(&request.repo_path, &config.worktree_dir) {
         tracing::warn!("Failed to update .gitignore: {}", e);
     }
 
-    let branch_name = format!("bd-{}", request.bead_id);
-    let worktrees_dir = repo_path.join(".worktrees");
-    let worktree_path = worktrees_dir.join(&branch_name);
+    let branch_name = config.branch_name(&request.bead_id);
+    let worktrees_dir = config.worktrees_dir(repo_path);
+    let worktree_path = config.worktree_path(repo_path, &request.bead_id);
 
     // Check if worktree already exists (idempotent)
     if worktree_path.exists() {
-        return Json(CreateWorktreeResponse {
+        return Ok(Json(CreateWorktreeResponse {
             success: true,
             worktree_path: worktree_path.to_string_lossy().to_string(),
             branch: branch_name,
             already_existed: true,
-        })
-        .into_response();
+            hooks_started: false,
+            untrusted_hooks: None,
+        }));
     }
 
-    // Create .worktrees directory if it doesn't exist
+    // Create the worktree directory if it doesn't exist
     if let Err(e) = fs::create_dir_all(&worktrees_dir) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to create .worktrees directory: {}", e)
-            })),
-        )
-            .into_response();
+        return Err(AppError::Internal(format!(
+            "Failed to create {} directory: {}",
+            config.worktree_dir, e
+        )));
     }
 
     // Create the worktree with a new branch
@@ -218,17 +274,23 @@ pub async fn create_worktree(Json(request): Json<CreateWorktreeRequest>) -> impl
             &request.base_branch,
         ])
         .current_dir(&request.repo_path)
+        .logged()
         .output()
         .await;
 
     match output {
-        Ok(output) if output.status.success() => Json(CreateWorktreeResponse {
-            success: true,
-            worktree_path: worktree_path.to_string_lossy().to_string(),
-            branch: branch_name,
-            already_existed: false,
-        })
-        .into_response(),
+        Ok(output) if output.status.success() => {
+            let (hooks_started, untrusted_hooks) =
+                hooks::start(&db, repo_path, &request.bead_id, &worktree_path, config.hooks);
+            Ok(Json(CreateWorktreeResponse {
+                success: true,
+                worktree_path: worktree_path.to_string_lossy().to_string(),
+                branch: branch_name,
+                already_existed: false,
+                hooks_started,
+                untrusted_hooks,
+            }))
+        }
         Ok(output) => {
             let stderr = String::from_utf8_lossy(&output.stderr);
             // Check if branch already exists (perhaps worktree was removed but branch exists)
@@ -242,49 +304,39 @@ pub async fn create_worktree(Json(request): Json<CreateWorktreeRequest>) -> impl
                         &branch_name,
                     ])
                     .current_dir(&request.repo_path)
+                    .logged()
                     .output()
                     .await;
 
                 match retry_output {
-                    Ok(output) if output.status.success() => Json(CreateWorktreeResponse {
-                        success: true,
-                        worktree_path: worktree_path.to_string_lossy().to_string(),
-                        branch: branch_name,
-                        already_existed: true, // Branch existed even if worktree didn't
-                    })
-                    .into_response(),
-                    Ok(output) => (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({
-                            "error": format!("Failed to create worktree: {}", String::from_utf8_lossy(&output.stderr))
-                        })),
-                    )
-                        .into_response(),
-                    Err(e) => (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(serde_json::json!({
-                            "error": format!("Failed to run git command: {}", e)
-                        })),
-                    )
-                        .into_response(),
+                    Ok(output) if output.status.success() => {
+                        let (hooks_started, untrusted_hooks) = hooks::start(
+                            &db,
+                            repo_path,
+                            &request.bead_id,
+                            &worktree_path,
+                            config.hooks,
+                        );
+                        Ok(Json(CreateWorktreeResponse {
+                            success: true,
+                            worktree_path: worktree_path.to_string_lossy().to_string(),
+                            branch: branch_name,
+                            already_existed: true, // Branch existed even if worktree didn't
+                            hooks_started,
+                            untrusted_hooks,
+                        }))
+                    }
+                    Ok(output) => Err(AppError::Internal(format!(
+                        "Failed to create worktree: {}",
+                        String::from_utf8_lossy(&output.stderr)
+                    ))),
+                    Err(e) => Err(AppError::Internal(format!("Failed to run git command: {}", e))),
                 }
             } else {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": format!("Failed to create worktree: {}", stderr)
-                    })),
-                )
-                    .into_response()
+                Err(AppError::Internal(format!("Failed to create worktree: {}", stderr)))
             }
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to run git command: {}", e)
-            })),
-        )
-            .into_response(),
+        Err(e) => Err(AppError::Internal(format!("Failed to run git command: {}", e))),
     }
 }
 
@@ -322,102 +374,105 @@ pub struct DeleteWorktreeResponse {
 ///   "bead_id": "BD-001"
 /// }
 /// ```
-pub async fn delete_worktree(Json(request): Json<DeleteWorktreeRequest>) -> impl IntoResponse {
+pub async fn delete_worktree(
+    Json(request): Json<DeleteWorktreeRequest>,
+) -> Result<impl IntoResponse, AppError> {
     let repo_path = Path::new(&request.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Repository path does not exist: {}", request.repo_path)
-            })),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
+    }
+
+    match remove_worktree(repo_path, &request.bead_id).await {
+        Ok(()) => Ok(Json(DeleteWorktreeResponse { success: true })),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
+
+/// Remove a bead's worktree (forcibly if it has untracked or modified
+/// files), delete its local branch, and close the bead.
+async fn remove_worktree(repo_path: &Path, bead_id: &str) -> Result<(), String> {
+    if remove_worktree_checkout(repo_path, bead_id).await? {
+        // Close the bead (ignore errors - bead may not exist or already be closed)
+        let _ = close_bead(repo_path, bead_id).await;
     }
+    Ok(())
+}
 
-    let branch_name = format!("bd-{}", request.bead_id);
-    let worktree_path = repo_path.join(".worktrees").join(&branch_name);
+/// Remove a bead's worktree and delete its local branch, leaving the bead
+/// open. Returns whether there was a worktree to remove.
+async fn remove_worktree_checkout(repo_path: &Path, bead_id: &str) -> Result<bool, String> {
+    let config = WorktreeConfig::load(repo_path);
+    let branch_name = config.branch_name(bead_id);
+    let worktree_path = config.worktree_path(repo_path, bead_id);
 
     // Check if worktree exists
     if !worktree_path.exists() {
-        return Json(DeleteWorktreeResponse { success: true }).into_response();
+        return Ok(false);
     }
 
     // Remove the worktree
     let output = Command::new("git")
         .args(["worktree", "remove", &worktree_path.to_string_lossy()])
-        .current_dir(&request.repo_path)
+        .current_dir(repo_path)
+        .logged()
         .output()
-        .await;
-
-    let worktree_removed = match output {
-        Ok(output) if output.status.success() => true,
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            // Try force remove if there are untracked changes
-            if stderr.contains("contains modified or untracked files") {
-                let force_output = Command::new("git")
-                    .args([
-                        "worktree",
-                        "remove",
-                        "--force",
-                        &worktree_path.to_string_lossy(),
-                    ])
-                    .current_dir(&request.repo_path)
-                    .output()
-                    .await;
+        .await
+        .map_err(|e| format!("Failed to run git command: {}", e))?;
 
-                match force_output {
-                    Ok(output) if output.status.success() => true,
-                    _ => {
-                        return (
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            Json(serde_json::json!({
-                                "error": format!("Failed to remove worktree: {}", stderr)
-                            })),
-                        )
-                            .into_response();
-                    }
-                }
-            } else {
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": format!("Failed to remove worktree: {}", stderr)
-                    })),
-                )
-                    .into_response();
-            }
-        }
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to run git command: {}", e)
-                })),
-            )
-                .into_response();
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // Try force remove if there are untracked changes
+        if !stderr.contains("contains modified or untracked files") {
+            return Err(format!("Failed to remove worktree: {}", stderr));
         }
-    };
-
-    if worktree_removed {
-        // Delete local branch (ignore errors - branch may not exist or be already deleted)
-        let _ = Command::new("git")
-            .args(["branch", "-D", &branch_name])
-            .current_dir(&request.repo_path)
+        let force_output = Command::new("git")
+            .args([
+                "worktree",
+                "remove",
+                "--force",
+                &worktree_path.to_string_lossy(),
+            ])
+            .current_dir(repo_path)
+            .logged()
             .output()
             .await;
 
-        // Close the bead (ignore errors - bead may not exist or already be closed)
-        let _ = Command::new("bd")
-            .args(["close", &request.bead_id])
-            .current_dir(&request.repo_path)
-            .output()
-            .await;
+        if !matches!(force_output, Ok(ref output) if output.status.success()) {
+            return Err(format!("Failed to remove worktree: {}", stderr));
+        }
     }
 
-    Json(DeleteWorktreeResponse { success: true }).into_response()
+    // Delete local branch (ignore errors - branch may not exist or be already deleted)
+    let _ = Command::new("git")
+        .args(["branch", "-D", &branch_name])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await;
+
+    Ok(true)
+}
+
+/// Close a bead with `bd close`.
+async fn close_bead(repo_path: &Path, bead_id: &str) -> Result<(), String> {
+    let output = Command::new("bd")
+        .args(["close", bead_id])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run bd: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to close bead: {}", stderr.trim()));
+    }
+    Ok(())
 }
 
 // ============================================================================
@@ -438,9 +493,20 @@ pub struct WorktreeEntry {
     pub path: String,
     /// Branch name.
     pub branch: String,
-    /// Extracted bead ID (if it matches bd-{ID} pattern).
+    /// Extracted bead ID (if the branch matches the project's branch template).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bead_id: Option<String>,
+    /// Whether HEAD is detached; `branch` is then the branch the bead's
+    /// worktree would normally have checked out.
+    pub detached: bool,
+    /// Whether the worktree is locked, e.g. left behind by a crashed
+    /// `git worktree add`.
+    pub locked: bool,
+    /// Reason recorded with the lock, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    /// Whether the worktree directory is gone and git would prune it.
+    pub prunable: bool,
 }
 
 /// Response body for the list worktrees endpoint.
@@ -459,112 +525,101 @@ pub struct ListWorktreesResponse {
 /// # Response
 ///
 /// Returns a list of all worktrees with their paths, branches, and bead IDs.
-pub async fn list_worktrees(Query(params): Query<ListWorktreesParams>) -> impl IntoResponse {
+pub async fn list_worktrees(
+    Query(params): Query<ListWorktreesParams>,
+) -> Result<impl IntoResponse, AppError> {
     let repo_path = Path::new(&params.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Repository path does not exist: {}", params.repo_path)
-            })),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            params.repo_path
+        )));
     }
 
-    // List worktrees
-    let output = Command::new("git")
-        .args(["worktree", "list", "--porcelain"])
-        .current_dir(&params.repo_path)
-        .output()
-        .await;
-
-    match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let worktrees = parse_worktree_list(&stdout, &params.repo_path);
-            Json(ListWorktreesResponse { worktrees }).into_response()
-        }
-        Ok(output) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to list worktrees: {}", String::from_utf8_lossy(&output.stderr))
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to run git command: {}", e)
-            })),
-        )
-            .into_response(),
+    match get_worktree_entries(&params.repo_path).await {
+        Ok(worktrees) => Ok(Json(ListWorktreesResponse { worktrees })),
+        Err(e) => Err(AppError::Internal(e)),
     }
 }
 
-/// Parse the porcelain output of `git worktree list`.
-fn parse_worktree_list(output: &str, repo_path: &str) -> Vec<WorktreeEntry> {
+/// List the bead worktrees of a repository.
+async fn get_worktree_entries(repo_path: &str) -> Result<Vec<WorktreeEntry>, String> {
+    let repo = PathBuf::from(repo_path);
+    let config = WorktreeConfig::load(&repo);
+    let worktrees = tokio::task::spawn_blocking(move || git_backend::list_worktrees(&repo))
+        .await
+        .map_err(|e| format!("Failed to list worktrees: {}", e))?
+        .map_err(|e| format!("Failed to list worktrees: {}", e))?;
+    Ok(collect_worktree_entries(worktrees, repo_path, &config))
+}
+
+/// Build worktree entries from the worktrees registered with git.
+fn collect_worktree_entries(
+    git_worktrees: Vec<GitWorktree>,
+    repo_path: &str,
+    config: &WorktreeConfig,
+) -> Vec<WorktreeEntry> {
     let mut worktrees = Vec::new();
-    let mut current_path: Option<String> = None;
-    let mut current_branch: Option<String> = None;
-
-    for line in output.lines() {
-        if line.starts_with("worktree ") {
-            // Save previous entry if complete
-            if let (Some(path), Some(branch)) = (current_path.take(), current_branch.take()) {
-                // Only include worktrees in .worktrees directory
-                if path.contains(".worktrees/bd-") {
-                    let bead_id = extract_bead_id(&branch);
-                    worktrees.push(WorktreeEntry {
-                        path,
-                        branch,
-                        bead_id,
-                    });
-                }
-            }
-            current_path = Some(line.trim_start_matches("worktree ").to_string());
-        } else if line.starts_with("branch ") {
-            // Extract just the branch name from refs/heads/...
-            let full_ref = line.trim_start_matches("branch ");
-            current_branch = Some(
-                full_ref
-                    .trim_start_matches("refs/heads/")
-                    .to_string(),
-            );
-        }
-    }
 
-    // Don't forget the last entry
-    if let (Some(path), Some(branch)) = (current_path, current_branch) {
-        if path.contains(".worktrees/bd-") {
-            let bead_id = extract_bead_id(&branch);
-            worktrees.push(WorktreeEntry {
-                path,
-                branch,
-                bead_id,
-            });
+    for worktree in git_worktrees {
+        // Only include bead worktrees
+        if !config.is_bead_worktree(&worktree.path) {
+            continue;
         }
+        let path = worktree.path.to_string_lossy().to_string();
+        let detached = worktree.branch.is_none();
+        let (branch, bead_id) = match worktree.branch {
+            Some(branch) => {
+                let bead_id = config.bead_id_from_branch(&branch);
+                (branch, bead_id)
+            }
+            // Detached HEAD: identify the bead by its directory instead
+            None => {
+                let dir_name = worktree
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let Some(bead_id) = config.bead_id_from_dir(&dir_name) else {
+                    continue;
+                };
+                (config.branch_name(&bead_id), Some(bead_id))
+            }
+        };
+        worktrees.push(WorktreeEntry {
+            path,
+            branch,
+            bead_id,
+            detached,
+            locked: worktree.locked,
+            lock_reason: worktree.lock_reason,
+            prunable: worktree.prunable,
+        });
     }
 
-    // Also include worktrees from main repo .worktrees directory that may not be in git worktree list
+    // Also include worktrees from the worktree directory that may not be in git worktree list
     // (handles orphaned worktree directories)
-    let worktrees_dir = Path::new(repo_path).join(".worktrees");
+    let worktrees_dir = config.worktrees_dir(Path::new(repo_path));
     if worktrees_dir.exists() {
         if let Ok(entries) = fs::read_dir(&worktrees_dir) {
             for entry in entries.flatten() {
                 let entry_path = entry.path();
                 if entry_path.is_dir() {
                     let dir_name = entry.file_name().to_string_lossy().to_string();
-                    if dir_name.starts_with("bd-") {
+                    if let Some(bead_id) = config.bead_id_from_dir(&dir_name) {
                         // Check if already in list
                         let already_listed = worktrees.iter().any(|w| w.path.ends_with(&dir_name));
                         if !already_listed {
-                            let bead_id = extract_bead_id(&dir_name);
                             worktrees.push(WorktreeEntry {
                                 path: entry_path.to_string_lossy().to_string(),
-                                branch: dir_name,
-                                bead_id,
+                                branch: config.branch_name(&bead_id),
+                                bead_id: Some(bead_id),
+                                detached: false,
+                                locked: false,
+                                lock_reason: None,
+                                prunable: false,
                             });
                         }
                     }
@@ -576,15 +631,182 @@ fn parse_worktree_list(output: &str, repo_path: &str) -> Vec<WorktreeEntry> {
     worktrees
 }
 
-/// Extract bead ID from a branch name like "bd-BD-001".
-fn extract_bead_id(branch: &str) -> Option<String> {
-    if branch.starts_with("bd-") {
-        Some(branch.trim_start_matches("bd-").to_string())
-    } else {
-        None
+// ============================================================================
+// Unlock Worktree Endpoint
+// ============================================================================
+
+/// Request body for unlocking a worktree.
+#[derive(Deserialize)]
+pub struct UnlockWorktreeRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose worktree to unlock.
+    pub bead_id: String,
+}
+
+/// Unlock a bead's worktree so it can be removed or pruned again.
+///
+/// Worktrees stay locked when `git worktree add` is interrupted, e.g. by a
+/// crash, which blocks deleting them.
+///
+/// # Endpoint
+///
+/// `POST /api/git/worktree/unlock`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "bead_id": "BD-001"
+/// }
+/// ```
+pub async fn unlock_worktree(
+    Json(request): Json<UnlockWorktreeRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
+    }
+
+    let worktree_path = WorktreeConfig::load(repo_path).worktree_path(repo_path, &request.bead_id);
+    let repo = repo_path.to_path_buf();
+    let result = tokio::task::spawn_blocking(move || {
+        git_backend::unlock_worktree(&repo, &worktree_path)
+    })
+    .await;
+
+    match result {
+        Ok(Ok(true)) => Ok(Json(serde_json::json!({ "success": true }))),
+        Ok(Ok(false)) => {
+            Err(AppError::NotFound(format!("No worktree found for bead {}", request.bead_id)))
+        }
+        Ok(Err(e)) => Err(AppError::Internal(format!(
+            "Failed to unlock worktree: {}",
+            e.message()
+        ))),
+        Err(e) => Err(AppError::Internal(format!("Failed to unlock worktree: {}", e))),
+    }
+}
+
+// ============================================================================
+// Commit Endpoint
+// ============================================================================
+
+/// Request body for committing changes in a worktree.
+#[derive(Deserialize)]
+pub struct CommitRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose worktree to commit in.
+    pub bead_id: String,
+    /// Commit message.
+    pub message: String,
+    /// Paths relative to the worktree to stage (all changes when omitted).
+    #[serde(default)]
+    pub paths: Option<Vec<String>>,
+}
+
+/// Response body for the commit endpoint.
+#[derive(Serialize)]
+pub struct CommitResponse {
+    /// Whether the operation was successful.
+    pub success: bool,
+    /// ID of the new commit.
+    pub commit: String,
+}
+
+/// Stage changes in a bead's worktree and commit them.
+///
+/// # Endpoint
+///
+/// `POST /api/git/commit`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "bead_id": "BD-001",
+///   "message": "Fix badge contrast",
+///   "paths": ["src/components/badge.tsx"]
+/// }
+/// ```
+///
+/// # Response
+///
+/// Returns the new commit ID, or 409 when there is nothing to commit.
+pub async fn commit_worktree(
+    Json(request): Json<CommitRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
+    }
+
+    if request.message.trim().is_empty() {
+        return Err(AppError::BadRequest("Commit message must not be empty".to_string()));
+    }
+
+    // Only allow paths inside the worktree
+    if let Some(paths) = &request.paths {
+        if let Some(bad) = paths.iter().find(|p| !is_relative_subpath(p)) {
+            return Err(AppError::BadRequest(format!(
+                "Path must be relative to the worktree: {}",
+                bad
+            )));
+        }
+    }
+
+    let config = WorktreeConfig::load(repo_path);
+    let worktree_path = config.worktree_path(repo_path, &request.bead_id);
+    if !worktree_path.exists() {
+        return Err(AppError::NotFound(format!("No worktree found for bead {}", request.bead_id)));
+    }
+
+    let options = config.commit.options();
+    let result = tokio::task::spawn_blocking(move || {
+        git_backend::commit(
+            &worktree_path,
+            request.paths.as_deref(),
+            &request.message,
+            &options,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(Some(commit))) => Ok(Json(CommitResponse {
+            success: true,
+            commit,
+        })),
+        Ok(Ok(None)) => Err(AppError::Conflict("Nothing to commit".to_string())),
+        Ok(Err(e)) => {
+            Err(AppError::Internal(format!("Failed to commit: {}", e.message())))
+        }
+        Err(e) => Err(AppError::Internal(format!("Failed to commit: {}", e))),
     }
 }
 
+/// Whether a path is relative and stays inside the directory it is joined to.
+fn is_relative_subpath(path: &str) -> bool {
+    let path = Path::new(path);
+    !path.as_os_str().is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir))
+}
+
 // ============================================================================
 // PR Status Endpoint
 // ============================================================================
@@ -599,7 +821,7 @@ pub struct PrStatusParams {
 }
 
 /// CI check status.
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct ChecksStatus {
     /// Total number of checks.
     pub total: i32,
@@ -613,8 +835,33 @@ pub struct ChecksStatus {
     pub status: String,
 }
 
+impl ChecksStatus {
+    /// Summarize check counts. Any failure makes the overall status
+    /// "failure"; otherwise any pending check makes it "pending".
+    fn from_counts(passed: i32, failed: i32, pending: i32) -> Self {
+        let total = passed + failed + pending;
+        let status = if total == 0 {
+            "success".to_string() // No checks = success
+        } else if failed > 0 {
+            "failure".to_string()
+        } else if pending > 0 {
+            "pending".to_string()
+        } else {
+            "success".to_string()
+        };
+
+        ChecksStatus {
+            total,
+            passed,
+            failed,
+            pending,
+            status,
+        }
+    }
+}
+
 /// PR information.
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PrInfo {
     /// PR number.
     pub number: i32,
@@ -624,12 +871,39 @@ pub struct PrInfo {
     pub state: String,
     /// CI checks status.
     pub checks: ChecksStatus,
-    /// Whether the PR is mergeable.
+    /// Whether the PR is mergeable, including the base branch's required
+    /// checks and approvals.
     pub mergeable: bool,
+    /// Whether the PR is a draft.
+    pub draft: bool,
+    /// Protection rules of the base branch (GitHub only; absent when the
+    /// branch is unprotected or the rules can't be read).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protection: Option<BranchProtection>,
+}
+
+/// Merge requirements from the base branch's protection rules.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BranchProtection {
+    /// Checks that must pass before merging.
+    pub required_checks: Vec<String>,
+    /// Required checks that haven't passed yet.
+    pub missing_checks: Vec<String>,
+    /// Approving reviews required before merging.
+    pub required_approvals: i32,
+    /// Approving reviews the PR has.
+    pub approvals: i32,
+}
+
+impl BranchProtection {
+    /// Whether the PR meets the required checks and approvals.
+    pub fn satisfied(&self) -> bool {
+        self.missing_checks.is_empty() && self.approvals >= self.required_approvals
+    }
 }
 
 /// Rate limit information.
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct RateLimitInfo {
     /// Remaining API calls.
     pub remaining: i32,
@@ -639,22 +913,59 @@ pub struct RateLimitInfo {
     pub reset_at: String,
 }
 
+impl RateLimitInfo {
+    /// Rate limit for providers that do not report one.
+    fn unknown() -> Self {
+        RateLimitInfo {
+            remaining: -1, // Unknown
+            limit: 5000,
+            reset_at: String::new(),
+        }
+    }
+}
+
+/// The most recent known rate limit reported by a PR provider.
+fn last_rate_limit_slot() -> &'static std::sync::Mutex<Option<RateLimitInfo>> {
+    static LAST: std::sync::OnceLock<std::sync::Mutex<Option<RateLimitInfo>>> =
+        std::sync::OnceLock::new();
+    LAST.get_or_init(|| std::sync::Mutex::new(None))
+}
+
+/// Returns the rate limit from the last PR status check that reported one.
+pub fn last_rate_limit() -> Option<RateLimitInfo> {
+    last_rate_limit_slot().lock().unwrap().clone()
+}
+
 /// Response body for the PR status endpoint.
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PrStatusResponse {
     /// Whether the repo has a remote.
     pub has_remote: bool,
     /// Whether the branch has been pushed.
     pub branch_pushed: bool,
+    /// Commits the branch is ahead of main, from local git.
+    pub ahead: i32,
+    /// Commits the branch is behind main, from local git.
+    pub behind: i32,
+    /// PR provider queried: "github", "gh", "bitbucket", "gitea", or
+    /// "none" when PRs can't be queried.
+    pub provider: String,
+    /// Why PRs can't be queried, when `provider` is "none".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_reason: Option<String>,
     /// PR information (if exists).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pr: Option<PrInfo>,
     /// Rate limit information.
     pub rate_limit: RateLimitInfo,
+    /// Whether the PR is armed to merge once checks pass.
+    pub auto_merge: bool,
 }
 
 /// Get PR status for a bead's branch.
 ///
+/// Answered from the background poller's cache while it is fresh.
+///
 /// # Endpoint
 ///
 /// `GET /api/git/pr-status?repo_path=...&bead_id=...`
@@ -662,49 +973,91 @@ pub struct PrStatusResponse {
 /// # Response
 ///
 /// Returns PR information, CI checks, and rate limit info.
-pub async fn pr_status(Query(params): Query<PrStatusParams>) -> impl IntoResponse {
+pub async fn pr_status(
+    State(db): State<AppState>,
+    Query(params): Query<PrStatusParams>,
+) -> Result<impl IntoResponse, AppError> {
     let repo_path = Path::new(&params.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Repository path does not exist: {}", params.repo_path)
-            })),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            params.repo_path
+        )));
+    }
+
+    // Serve the poller's result while it is fresh
+    if let Some(status) = poller::cached(repo_path, &params.bead_id) {
+        return Ok(Json(status));
+    }
+
+    match load_pr_status(&db, &params.repo_path, &params.bead_id).await {
+        Ok(status) => {
+            poller::store(repo_path, &params.bead_id, status.clone());
+            Ok(Json(status))
+        }
+        Err(e) => Err(AppError::Internal(e)),
     }
+}
 
-    let branch_name = format!("bd-{}", params.bead_id);
+/// Query the PR provider for the status of a bead's branch.
+///
+/// If the provider can't be used (e.g. `gh` is missing or logged out), the
+/// status reports provider "none" with the reason, and only the data plain
+/// git provides.
+async fn load_pr_status(
+    db: &Database,
+    repo_path: &str,
+    bead_id: &str,
+) -> Result<PrStatusResponse, String> {
+    let branch_name = WorktreeConfig::load(Path::new(repo_path)).branch_name(bead_id);
+    let (provider, provider_reason) = match PrProvider::detect(db, repo_path).await {
+        Ok(provider) => match provider.unavailable_reason().await {
+            None => (Some(provider), None),
+            Some(reason) => (None, Some(reason)),
+        },
+        Err(e) => (None, Some(e)),
+    };
 
     // Check if repo has a remote
-    let has_remote = check_has_remote(&params.repo_path).await;
+    let has_remote = check_has_remote(repo_path).await;
 
     // Check if branch has been pushed
     let branch_pushed = if has_remote {
-        check_branch_pushed(&params.repo_path, &branch_name).await
+        check_branch_pushed(repo_path, &branch_name).await
     } else {
         false
     };
 
+    let (ahead, behind) = get_ahead_behind_worktree(repo_path, &branch_name).await;
+
     // Get rate limit info (before PR status to avoid using up calls)
-    let rate_limit = get_rate_limit(&params.repo_path).await;
+    let rate_limit = match &provider {
+        Some(provider) => provider.rate_limit().await,
+        None => RateLimitInfo::unknown(),
+    };
+    if rate_limit.remaining >= 0 {
+        *last_rate_limit_slot().lock().unwrap() = Some(rate_limit.clone());
+    }
 
     // Get PR info if branch is pushed
-    let pr = if branch_pushed {
-        get_pr_info(&params.repo_path, &branch_name).await
-    } else {
-        None
+    let pr = match &provider {
+        Some(provider) if branch_pushed => provider.pr_info(&branch_name).await,
+        _ => None,
     };
 
-    Json(PrStatusResponse {
+    Ok(PrStatusResponse {
         has_remote,
         branch_pushed,
+        ahead,
+        behind,
+        provider: provider.as_ref().map_or("none", PrProvider::name).to_string(),
+        provider_reason,
         pr,
         rate_limit,
+        auto_merge: auto_merge::is_armed(Path::new(repo_path), bead_id),
     })
-    .into_response()
 }
 
 // ============================================================================
@@ -718,10 +1071,28 @@ pub struct CreatePrRequest {
     pub repo_path: String,
     /// Bead ID for the PR.
     pub bead_id: String,
-    /// PR title.
+    /// PR title (generated when empty and `auto` is set).
+    #[serde(default)]
     pub title: String,
-    /// PR body.
+    /// PR body (generated when empty and `auto` is set).
+    #[serde(default)]
     pub body: String,
+    /// Generate the title and body from the bead and the branch's commits,
+    /// as `generate-pr-body` does.
+    #[serde(default)]
+    pub auto: bool,
+    /// Open the PR as a draft.
+    #[serde(default)]
+    pub draft: bool,
+    /// Reviewers to request; the project's PR defaults when omitted.
+    #[serde(default)]
+    pub reviewers: Option<Vec<String>>,
+    /// Labels to add; the project's PR defaults when omitted.
+    #[serde(default)]
+    pub labels: Option<Vec<String>>,
+    /// Users to assign; the project's PR defaults when omitted.
+    #[serde(default)]
+    pub assignees: Option<Vec<String>>,
 }
 
 /// Response body for the create PR endpoint.
@@ -733,11 +1104,10 @@ pub struct CreatePrResponse {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pr_number: Option<i32>,
     /// PR URL.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub pr_url: Option<String>,
-    /// Error message if failed.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+    pub pr_url: String,
+    /// Reviewers, labels, or assignees that could not be applied.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 /// Create a PR for a bead's branch.
@@ -753,134 +1123,92 @@ pub struct CreatePrResponse {
 ///   "repo_path": "/path/to/repo",
 ///   "bead_id": "BD-001",
 ///   "title": "Fix: Branch badge readability",
-///   "body": "Closes BD-001\n\n..."
+///   "body": "Closes BD-001\n\n...",
+///   "draft": false,
+///   "reviewers": ["ann", "acme/platform"],
+///   "labels": ["beads"],
+///   "assignees": ["bob"]
+/// }
+/// ```
+///
+/// Omitted `reviewers`, `labels`, and `assignees` fall back to the project's
+/// PR defaults (`/api/projects/:id/pr-defaults`); an empty array applies
+/// none. Ones the provider rejects are reported in `warnings`.
+///
+/// With `"auto": true`, an omitted title or body is generated from the bead
+/// and the branch's commits:
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "bead_id": "BD-001",
+///   "auto": true
 /// }
 /// ```
-pub async fn create_pr(Json(request): Json<CreatePrRequest>) -> impl IntoResponse {
+pub async fn create_pr(
+    State(db): State<AppState>,
+    Json(mut request): Json<CreatePrRequest>,
+) -> Result<Json<CreatePrResponse>, AppError> {
     let repo_path = Path::new(&request.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(CreatePrResponse {
-                success: false,
-                pr_number: None,
-                pr_url: None,
-                error: Some(format!("Repository path does not exist: {}", request.repo_path)),
-            }),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
     }
 
-    let branch_name = format!("bd-{}", request.bead_id);
-
-    // Check if a merged PR already exists for this branch
-    let check_output = Command::new("gh")
-        .args([
-            "pr",
-            "list",
-            "--head",
-            &branch_name,
-            "--state",
-            "merged",
-            "--json",
-            "number,title",
-        ])
-        .current_dir(&request.repo_path)
-        .output()
-        .await;
-
-    match check_output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let trimmed = stdout.trim();
-            // Parse JSON array - if non-empty, a merged PR exists
-            if !trimmed.is_empty() && trimmed != "[]" {
-                // Try to parse the JSON to get PR details
-                if let Ok(prs) = serde_json::from_str::<Vec<serde_json::Value>>(trimmed) {
-                    if let Some(pr) = prs.first() {
-                        let number = pr.get("number").and_then(|n| n.as_i64()).unwrap_or(0);
-                        let title = pr
-                            .get("title")
-                            .and_then(|t| t.as_str())
-                            .unwrap_or("Unknown");
-                        return (
-                            StatusCode::CONFLICT,
-                            Json(CreatePrResponse {
-                                success: false,
-                                pr_number: None,
-                                pr_url: None,
-                                error: Some(format!(
-                                    "A merged PR already exists for this branch: #{} \"{}\". Clean up the worktree first.",
-                                    number, title
-                                )),
-                            }),
-                        )
-                            .into_response();
-                    }
-                }
-            }
+    // Fill in whatever the client left out
+    if request.auto {
+        let (title, body) = generate_pr_text(repo_path, &request.bead_id).await;
+        if request.title.trim().is_empty() {
+            request.title = title;
         }
-        Ok(_) | Err(_) => {
-            // If the check fails, we'll proceed with PR creation anyway
-            // The gh pr create command will provide its own error if needed
+        if request.body.trim().is_empty() {
+            request.body = body;
         }
     }
+    if request.title.trim().is_empty() {
+        return Err(AppError::BadRequest(
+            "PR title must not be empty (or pass \"auto\": true)".to_string(),
+        ));
+    }
 
-    // Create PR using gh cli
-    let output = Command::new("gh")
-        .args([
-            "pr",
-            "create",
-            "--head",
-            &branch_name,
-            "--title",
-            &request.title,
-            "--body",
-            &request.body,
-        ])
-        .current_dir(&request.repo_path)
-        .output()
-        .await;
+    let branch_name = WorktreeConfig::load(repo_path).branch_name(&request.bead_id);
+    let provider = PrProvider::detect(&db, &request.repo_path)
+        .await
+        .map_err(AppError::Internal)?;
 
-    match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let pr_url = stdout.trim().to_string();
-            let pr_number = extract_pr_number_from_url(&pr_url);
-            Json(CreatePrResponse {
-                success: true,
-                pr_number,
-                pr_url: Some(pr_url),
-                error: None,
-            })
-            .into_response()
-        }
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(CreatePrResponse {
-                    success: false,
-                    pr_number: None,
-                    pr_url: None,
-                    error: Some(stderr.to_string()),
-                }),
-            )
-                .into_response()
-        }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(CreatePrResponse {
-                success: false,
-                pr_number: None,
-                pr_url: None,
-                error: Some(format!("Failed to run gh command: {}", e)),
-            }),
-        )
-            .into_response(),
+    // Check if a merged PR already exists for this branch
+    if let Some((number, title)) = provider.merged_pr(&branch_name).await {
+        return Err(AppError::Conflict(format!(
+            "A merged PR already exists for this branch: #{} \"{}\". Clean up the worktree first.",
+            number, title
+        )));
     }
+
+    let defaults = db
+        .get_pr_defaults_for_path(&request.repo_path)?
+        .unwrap_or_default();
+    let metadata = PrMetadata {
+        reviewers: request.reviewers.unwrap_or(defaults.reviewers),
+        labels: request.labels.unwrap_or(defaults.labels),
+        assignees: request.assignees.unwrap_or(defaults.assignees),
+    };
+
+    let result = provider
+        .create_pr(&branch_name, &request.title, &request.body, request.draft, &metadata)
+        .await;
+    poller::invalidate(repo_path, &request.bead_id);
+
+    let created = result.map_err(AppError::Upstream)?;
+    Ok(Json(CreatePrResponse {
+        success: true,
+        pr_number: created.number,
+        pr_url: created.url,
+        warnings: created.warnings,
+    }))
 }
 
 // ============================================================================
@@ -910,9 +1238,6 @@ pub struct MergePrResponse {
     pub success: bool,
     /// Whether the PR was merged.
     pub merged: bool,
-    /// Error message if failed.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
 }
 
 /// Merge a PR for a bead's branch.
@@ -930,69 +1255,101 @@ pub struct MergePrResponse {
 ///   "merge_method": "squash"
 /// }
 /// ```
-pub async fn merge_pr(Json(request): Json<MergePrRequest>) -> impl IntoResponse {
+pub async fn merge_pr(
+    State(db): State<AppState>,
+    Json(request): Json<MergePrRequest>,
+) -> Result<Json<MergePrResponse>, AppError> {
     let repo_path = Path::new(&request.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(MergePrResponse {
-                success: false,
-                merged: false,
-                error: Some(format!("Repository path does not exist: {}", request.repo_path)),
-            }),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
     }
 
-    let branch_name = format!("bd-{}", request.bead_id);
+    merge_bead_pr(&db, repo_path, &request.bead_id, &request.merge_method).await?;
+    Ok(Json(MergePrResponse {
+        success: true,
+        merged: true,
+    }))
+}
 
-    // Determine merge flag
-    let merge_flag = match request.merge_method.as_str() {
-        "merge" => "--merge",
-        "rebase" => "--rebase",
-        _ => "--squash", // Default to squash
-    };
+/// Merge the PR for a bead's branch. The provider refusing or failing to
+/// merge is an upstream error.
+async fn merge_bead_pr(
+    db: &Database,
+    repo_path: &Path,
+    bead_id: &str,
+    merge_method: &str,
+) -> Result<(), AppError> {
+    let branch_name = WorktreeConfig::load(repo_path).branch_name(bead_id);
+    let provider = PrProvider::detect(db, &repo_path.to_string_lossy())
+        .await
+        .map_err(AppError::Internal)?;
+    let result = provider.merge_pr(&branch_name, merge_method).await;
+    poller::invalidate(repo_path, bead_id);
+    result.map_err(AppError::Upstream)
+}
 
-    // Merge PR using gh cli
-    // Note: Don't use --delete-branch as it fails when branch is used by a worktree.
-    // The cleanup step (delete_worktree) handles branch deletion.
-    let output = Command::new("gh")
-        .args(["pr", "merge", &branch_name, merge_flag])
-        .current_dir(&request.repo_path)
-        .output()
-        .await;
+// ============================================================================
+// PR Ready Endpoint
+// ============================================================================
 
-    match output {
-        Ok(output) if output.status.success() => Json(MergePrResponse {
-            success: true,
-            merged: true,
-            error: None,
-        })
-        .into_response(),
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(MergePrResponse {
-                    success: false,
-                    merged: false,
-                    error: Some(stderr.to_string()),
-                }),
-            )
-                .into_response()
-        }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(MergePrResponse {
-                success: false,
-                merged: false,
-                error: Some(format!("Failed to run gh command: {}", e)),
-            }),
-        )
-            .into_response(),
+/// Request body for marking a draft PR ready for review.
+#[derive(Deserialize)]
+pub struct PrReadyRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose PR to mark ready.
+    pub bead_id: String,
+}
+
+/// Response body for the PR ready endpoint.
+#[derive(Serialize)]
+pub struct PrReadyResponse {
+    /// Whether the operation was successful.
+    pub success: bool,
+}
+
+/// Mark the draft PR for a bead's branch as ready for review.
+///
+/// # Endpoint
+///
+/// `POST /api/git/pr-ready`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "bead_id": "BD-001"
+/// }
+/// ```
+pub async fn mark_pr_ready(
+    State(db): State<AppState>,
+    Json(request): Json<PrReadyRequest>,
+) -> Result<Json<PrReadyResponse>, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
     }
+
+    let branch_name = WorktreeConfig::load(repo_path).branch_name(&request.bead_id);
+    let provider = PrProvider::detect(&db, &request.repo_path)
+        .await
+        .map_err(AppError::Internal)?;
+    let result = provider.mark_ready(&branch_name).await;
+    poller::invalidate(repo_path, &request.bead_id);
+
+    result.map_err(AppError::Upstream)?;
+    Ok(Json(PrReadyResponse { success: true }))
 }
 
 // ============================================================================
@@ -1045,162 +1402,396 @@ pub struct PrFilesResponse {
 /// # Response
 ///
 /// Returns the list of changed files with additions/deletions and totals.
-pub async fn pr_files(Query(params): Query<PrFilesParams>) -> impl IntoResponse {
+pub async fn pr_files(
+    State(db): State<AppState>,
+    Query(params): Query<PrFilesParams>,
+) -> Result<impl IntoResponse, AppError> {
     let repo_path = Path::new(&params.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Repository path does not exist: {}", params.repo_path)
-            })),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            params.repo_path
+        )));
     }
 
-    let branch_name = format!("bd-{}", params.bead_id);
-
-    // Step 1: Get the PR number via gh pr view
-    let pr_number = match get_pr_number(&params.repo_path, &branch_name).await {
-        Some(n) => n,
-        None => {
-            return (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "error": format!("No PR found for branch {}", branch_name)
-                })),
-            )
-                .into_response();
+    let branch_name = WorktreeConfig::load(repo_path).branch_name(&params.bead_id);
+    let provider = match PrProvider::detect(&db, &params.repo_path).await {
+        Ok(p) => p,
+        Err(e) => {
+            return Err(AppError::Internal(e));
         }
     };
 
-    // Step 2: Get the repo owner/name (nwo = name with owner)
-    let nwo = match get_repo_nwo(&params.repo_path).await {
-        Some(n) => n,
-        None => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": "Failed to determine repository owner/name"
-                })),
-            )
-                .into_response();
+    match provider.pr_files(&branch_name).await {
+        Ok(files) => {
+            let total_additions = files.iter().map(|f| f.additions).sum();
+            let total_deletions = files.iter().map(|f| f.deletions).sum();
+            let total_files = files.len() as i32;
+
+            Ok(Json(PrFilesResponse {
+                files,
+                total_additions,
+                total_deletions,
+                total_files,
+            }))
         }
-    };
+        Err((status, e)) => Err(AppError::with_status(status, e)),
+    }
+}
 
-    // Step 3: Fetch PR files using gh api
-    let api_path = format!("repos/{}/pulls/{}/files?per_page=100", nwo, pr_number);
-    let output = Command::new("gh")
-        .args(["api", &api_path])
-        .current_dir(&params.repo_path)
-        .output()
-        .await;
+// ============================================================================
+// Check Logs Endpoint
+// ============================================================================
 
-    match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            match serde_json::from_str::<Vec<serde_json::Value>>(&stdout) {
-                Ok(file_entries) => {
-                    let mut files = Vec::new();
-                    let mut total_additions: i32 = 0;
-                    let mut total_deletions: i32 = 0;
-
-                    for entry in &file_entries {
-                        let filename = entry["filename"].as_str().unwrap_or("").to_string();
-                        let status = entry["status"].as_str().unwrap_or("modified").to_string();
-                        let additions = entry["additions"].as_i64().unwrap_or(0) as i32;
-                        let deletions = entry["deletions"].as_i64().unwrap_or(0) as i32;
-                        let changes = entry["changes"].as_i64().unwrap_or(0) as i32;
-
-                        total_additions += additions;
-                        total_deletions += deletions;
-
-                        files.push(PrFileEntry {
-                            filename,
-                            status,
-                            additions,
-                            deletions,
-                            changes,
-                        });
-                    }
+/// Lines of log returned when the request does not say.
+const DEFAULT_LOG_LINES: usize = 200;
 
-                    let total_files = files.len() as i32;
+/// Most lines of log returned.
+const MAX_LOG_LINES: usize = 5000;
 
-                    Json(PrFilesResponse {
-                        files,
-                        total_additions,
-                        total_deletions,
-                        total_files,
-                    })
-                    .into_response()
-                }
-                Err(e) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(serde_json::json!({
-                        "error": format!("Failed to parse PR files response: {}", e)
-                    })),
-                )
-                    .into_response(),
-            }
+/// Query parameters for the check logs endpoint.
+#[derive(Deserialize)]
+pub struct CheckLogsParams {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose PR checks to look at.
+    pub bead_id: String,
+    /// Check name (defaults to the first failing check).
+    #[serde(default)]
+    pub check: Option<String>,
+    /// Number of lines from the end of the log to return.
+    #[serde(default)]
+    pub lines: Option<usize>,
+}
+
+/// Response body for the check logs endpoint.
+#[derive(Serialize)]
+pub struct CheckLogsResponse {
+    /// Check name.
+    pub check: String,
+    /// Check conclusion (e.g. "failure"), empty while running.
+    pub conclusion: String,
+    /// Web page of the check.
+    pub url: String,
+    /// Tail of the log.
+    pub log: String,
+    /// Whether earlier lines were left out.
+    pub truncated: bool,
+}
+
+/// Get the tail of a CI check's log for a bead's PR.
+///
+/// # Endpoint
+///
+/// `GET /api/git/check-logs?repo_path=...&bead_id=...&check=...&lines=...`
+///
+/// # Response
+///
+/// Returns the last `lines` lines (default 200) of the named check's log,
+/// or of the first failing check when `check` is omitted. Logs are only
+/// available for GitHub Actions jobs.
+pub async fn check_logs(
+    State(db): State<AppState>,
+    Query(params): Query<CheckLogsParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo_path = Path::new(&params.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            params.repo_path
+        )));
+    }
+
+    let branch_name = WorktreeConfig::load(repo_path).branch_name(&params.bead_id);
+    let provider = match PrProvider::detect(&db, &params.repo_path).await {
+        Ok(p) => p,
+        Err(e) => {
+            return Err(AppError::Internal(e));
         }
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to fetch PR files: {}", stderr)
-                })),
-            )
-                .into_response()
+    };
+
+    match provider
+        .check_log(&branch_name, params.check.as_deref())
+        .await
+    {
+        Ok(check_log) => {
+            let lines = params
+                .lines
+                .unwrap_or(DEFAULT_LOG_LINES)
+                .clamp(1, MAX_LOG_LINES);
+            let (log, truncated) = tail_lines(&check_log.log, lines);
+            Ok(Json(CheckLogsResponse {
+                check: check_log.name,
+                conclusion: check_log.conclusion,
+                url: check_log.url,
+                log,
+                truncated,
+            }))
         }
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to run gh command: {}", e)
-            })),
-        )
-            .into_response(),
+        Err((status, e)) => Err(AppError::with_status(status, e)),
     }
 }
 
-/// Get the PR number for a branch using gh pr view.
-async fn get_pr_number(repo_path: &str, branch: &str) -> Option<i32> {
-    let output = Command::new("gh")
-        .args(["pr", "view", branch, "--json", "number"])
-        .current_dir(repo_path)
-        .output()
-        .await
-        .ok()?;
+/// Keep the last `count` lines of a text, reporting whether any were cut.
+fn tail_lines(text: &str, count: usize) -> (String, bool) {
+    let lines: Vec<&str> = text.lines().collect();
+    let start = lines.len().saturating_sub(count);
+    (lines[start..].join("\n"), start > 0)
+}
 
-    if !output.status.success() {
-        return None;
+// ============================================================================
+// PR Preview Endpoint
+// ============================================================================
+
+/// Query parameters for the PR preview endpoint.
+#[derive(Deserialize)]
+pub struct PrPreviewParams {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID to preview a PR for.
+    pub bead_id: String,
+}
+
+/// A commit that would be included in the PR.
+#[derive(Serialize)]
+pub struct PrPreviewCommit {
+    /// Commit ID.
+    pub id: String,
+    /// First line of the commit message.
+    pub summary: String,
+    /// Author name.
+    pub author: String,
+    /// Commit time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// Response body for the PR preview endpoint.
+#[derive(Serialize)]
+pub struct PrPreviewResponse {
+    /// Branch the PR would be opened from.
+    pub branch: String,
+    /// Branch the changes were compared against.
+    pub base: String,
+    /// Commits ahead of the base, newest first.
+    pub commits: Vec<PrPreviewCommit>,
+    /// Changed files.
+    pub files: Vec<PrFileEntry>,
+    /// Total additions across all files.
+    pub total_additions: i32,
+    /// Total deletions across all files.
+    pub total_deletions: i32,
+    /// Total number of changed files.
+    pub total_files: i32,
+    /// Draft PR title.
+    pub title: String,
+    /// Draft PR body.
+    pub body: String,
+}
+
+/// Preview what a PR for a bead's branch would contain.
+///
+/// Works from the local branch, so it can be used before the branch is
+/// pushed or a PR exists.
+///
+/// # Endpoint
+///
+/// `GET /api/git/pr-preview?repo_path=...&bead_id=...`
+///
+/// # Response
+///
+/// Returns the commits and changed files ahead of main, with a title and
+/// body drafted from the bead and commits.
+pub async fn pr_preview(
+    Query(params): Query<PrPreviewParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo_path = Path::new(&params.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            params.repo_path
+        )));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let json: serde_json::Value = serde_json::from_str(&stdout).ok()?;
-    json["number"].as_i64().map(|n| n as i32)
+    let branch_name = WorktreeConfig::load(repo_path).branch_name(&params.bead_id);
+    let (repo, branch) = (repo_path.to_path_buf(), branch_name.clone());
+    let result = tokio::task::spawn_blocking(move || {
+        git_backend::branch_changes(&repo, &branch, &["main", "master"])
+    })
+    .await;
+
+    let changes = match result {
+        Ok(Ok(Some(changes))) => changes,
+        Ok(Ok(None)) => {
+            return Err(AppError::NotFound(format!(
+                "Branch {} or its base branch not found",
+                branch_name
+            )));
+        }
+        Ok(Err(e)) => {
+            return Err(AppError::Internal(format!("Failed to compare branches: {}", e.message())));
+        }
+        Err(e) => {
+            return Err(AppError::Internal(format!("Failed to compare branches: {}", e)));
+        }
+    };
+
+    let bead = read_bead(repo_path, &params.bead_id);
+    let (title, body) = compose_pr_text(&params.bead_id, bead.as_ref(), &changes.commits);
+
+    let commits = changes
+        .commits
+        .into_iter()
+        .map(|c| PrPreviewCommit {
+            id: c.id,
+            summary: c.summary,
+            author: c.author,
+            time: DateTime::<Utc>::from_timestamp(c.time, 0),
+        })
+        .collect();
+    let files: Vec<PrFileEntry> = changes
+        .files
+        .into_iter()
+        .map(|f| PrFileEntry {
+            filename: f.path,
+            status: f.status.to_string(),
+            additions: f.additions as i32,
+            deletions: f.deletions as i32,
+            changes: (f.additions + f.deletions) as i32,
+        })
+        .collect();
+
+    Ok(Json(PrPreviewResponse {
+        branch: branch_name,
+        base: changes.base,
+        commits,
+        total_additions: files.iter().map(|f| f.additions).sum(),
+        total_deletions: files.iter().map(|f| f.deletions).sum(),
+        total_files: files.len() as i32,
+        files,
+        title,
+        body,
+    }))
 }
 
-/// Get the repository name with owner (e.g. "owner/repo") using gh repo view.
-async fn get_repo_nwo(repo_path: &str) -> Option<String> {
-    let output = Command::new("gh")
-        .args(["repo", "view", "--json", "nameWithOwner", "--jq", ".nameWithOwner"])
-        .current_dir(repo_path)
-        .output()
-        .await
-        .ok()?;
+/// Read a single bead from the project's issues file.
+fn read_bead(repo_path: &Path, bead_id: &str) -> Option<Bead> {
+    let contents = fs::read_to_string(resolve_issues_path(repo_path)).ok()?;
+    parse_beads(&contents).into_iter().find(|b| b.id == bead_id)
+}
 
-    if !output.status.success() {
-        return None;
+/// Compose a PR title and body for a bead's branch from the bead and the
+/// branch's commits.
+async fn generate_pr_text(repo_path: &Path, bead_id: &str) -> (String, String) {
+    let branch_name = WorktreeConfig::load(repo_path).branch_name(bead_id);
+    let repo = repo_path.to_path_buf();
+    let commits = tokio::task::spawn_blocking(move || {
+        git_backend::branch_changes(&repo, &branch_name, &["main", "master"])
+    })
+    .await
+    .ok()
+    .and_then(|result| result.ok().flatten())
+    .map(|changes| changes.commits)
+    .unwrap_or_default();
+
+    let bead = read_bead(repo_path, bead_id);
+    compose_pr_text(bead_id, bead.as_ref(), &commits)
+}
+
+/// Compose a PR title and body: the bead title (or the only commit's
+/// summary), then a closing reference, the description, the design doc,
+/// and the commits oldest first.
+fn compose_pr_text(
+    bead_id: &str,
+    bead: Option<&Bead>,
+    commits: &[git_backend::CommitSummary],
+) -> (String, String) {
+    let title = match (bead, commits) {
+        (Some(bead), _) => bead.title.clone(),
+        (None, [commit]) => commit.summary.clone(),
+        (None, _) => bead_id.to_string(),
+    };
+
+    let mut sections = vec![format!("Closes {}", bead_id)];
+    if let Some(description) = bead.and_then(|b| b.description.as_deref()) {
+        if !description.trim().is_empty() {
+            sections.push(description.trim().to_string());
+        }
+    }
+    if let Some(design_doc) = bead.and_then(|b| b.design_doc.as_deref()) {
+        sections.push(format!("Design doc: `{}`", design_doc));
+    }
+    if !commits.is_empty() {
+        let list: Vec<String> = commits
+            .iter()
+            .rev()
+            .map(|c| format!("- {} ({})", c.summary, &c.id[..c.id.len().min(7)]))
+            .collect();
+        sections.push(format!("## Commits\n\n{}", list.join("\n")));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if stdout.is_empty() {
-        None
-    } else {
-        Some(stdout)
+    (title, sections.join("\n\n"))
+}
+
+// ============================================================================
+// Generate PR Body Endpoint
+// ============================================================================
+
+/// Request body for generating PR text.
+#[derive(Deserialize)]
+pub struct GeneratePrBodyRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID to generate PR text for.
+    pub bead_id: String,
+}
+
+/// Response body for the generate PR body endpoint.
+#[derive(Serialize)]
+pub struct GeneratePrBodyResponse {
+    /// Generated PR title.
+    pub title: String,
+    /// Generated PR body (markdown).
+    pub body: String,
+}
+
+/// Generate a PR title and body from a bead and its branch's commits.
+///
+/// The same text is used by `create-pr` when called with `"auto": true`.
+///
+/// # Endpoint
+///
+/// `POST /api/git/generate-pr-body`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "bead_id": "BD-001"
+/// }
+/// ```
+pub async fn generate_pr_body(
+    Json(request): Json<GeneratePrBodyRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
     }
+
+    let (title, body) = generate_pr_text(repo_path, &request.bead_id).await;
+    Ok(Json(GeneratePrBodyResponse { title, body }))
 }
 
 // ============================================================================
@@ -1209,48 +1800,23 @@ async fn get_repo_nwo(repo_path: &str) -> Option<String> {
 
 /// Get the number of commits ahead and behind for a worktree branch.
 async fn get_ahead_behind_worktree(repo_path: &str, branch: &str) -> (i32, i32) {
-    let base_branches = ["main", "master"];
-
-    for base in base_branches {
-        let output = Command::new("git")
-            .args([
-                "rev-list",
-                "--left-right",
-                "--count",
-                &format!("{}...{}", base, branch),
-            ])
-            .current_dir(repo_path)
-            .output()
-            .await;
+    let (repo_path, branch) = (PathBuf::from(repo_path), branch.to_string());
+    let result = tokio::task::spawn_blocking(move || {
+        git_backend::ahead_behind(&repo_path, &branch, &["main", "master"])
+    })
+    .await;
 
-        if let Ok(output) = output {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let parts: Vec<&str> = stdout.trim().split('\t').collect();
-                if parts.len() == 2 {
-                    let behind = parts[0].parse().unwrap_or(0);
-                    let ahead = parts[1].parse().unwrap_or(0);
-                    return (ahead, behind);
-                }
-            }
-        }
+    match result {
+        Ok(Ok(Some((ahead, behind)))) => (ahead as i32, behind as i32),
+        _ => (0, 0),
     }
-
-    (0, 0)
 }
 
 /// Check if a worktree has uncommitted changes.
 async fn check_worktree_dirty(worktree_path: &str) -> bool {
-    let output = Command::new("git")
-        .args(["status", "--porcelain"])
-        .current_dir(worktree_path)
-        .output()
-        .await;
-
-    match output {
-        Ok(o) => !o.stdout.is_empty(),
-        Err(_) => false,
-    }
+    let worktree_path = PathBuf::from(worktree_path);
+    let result = tokio::task::spawn_blocking(move || git_backend::is_dirty(&worktree_path)).await;
+    matches!(result, Ok(Ok(true)))
 }
 
 /// Get the last modification time of a directory.
@@ -1263,16 +1829,9 @@ fn get_last_modified(path: &Path) -> Option<DateTime<Utc>> {
 
 /// Check if repository has a remote.
 async fn check_has_remote(repo_path: &str) -> bool {
-    let output = Command::new("git")
-        .args(["remote"])
-        .current_dir(repo_path)
-        .output()
-        .await;
-
-    match output {
-        Ok(o) => !o.stdout.is_empty(),
-        Err(_) => false,
-    }
+    let repo_path = PathBuf::from(repo_path);
+    let result = tokio::task::spawn_blocking(move || git_backend::has_remote(&repo_path)).await;
+    matches!(result, Ok(Ok(true)))
 }
 
 /// Check if a branch has been pushed to remote.
@@ -1280,6 +1839,7 @@ async fn check_branch_pushed(repo_path: &str, branch: &str) -> bool {
     let output = Command::new("git")
         .args(["ls-remote", "--heads", "origin", branch])
         .current_dir(repo_path)
+        .logged()
         .output()
         .await;
 
@@ -1289,154 +1849,96 @@ async fn check_branch_pushed(repo_path: &str, branch: &str) -> bool {
     }
 }
 
-/// Get rate limit information from GitHub API.
-async fn get_rate_limit(repo_path: &str) -> RateLimitInfo {
-    let output = Command::new("gh")
-        .args(["api", "rate_limit", "--jq", ".rate"])
-        .current_dir(repo_path)
-        .output()
-        .await;
-
-    match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&stdout) {
-                let remaining = json["remaining"].as_i64().unwrap_or(0) as i32;
-                let limit = json["limit"].as_i64().unwrap_or(5000) as i32;
-                let reset = json["reset"].as_i64().unwrap_or(0);
-                let reset_at =
-                    DateTime::<Utc>::from_timestamp(reset, 0).map(|dt| dt.to_rfc3339()).unwrap_or_default();
-                return RateLimitInfo {
-                    remaining,
-                    limit,
-                    reset_at,
-                };
-            }
-        }
-        _ => {}
-    }
+/// Ensure the worktree directory is in the repository's .gitignore.
+fn ensure_gitignore_entry(repo_path: &str, worktree_dir: &str) -> Result<(), std::io::Error> {
+    let gitignore_path = format!("{}/.gitignore", repo_path);
+    let content = fs::read_to_string(&gitignore_path).unwrap_or_default();
 
-    // Default fallback
-    RateLimitInfo {
-        remaining: -1, // Unknown
-        limit: 5000,
-        reset_at: String::new(),
+    if !content.contains(worktree_dir) {
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&gitignore_path)?;
+        writeln!(file, "\n# Git worktrees\n{}/", worktree_dir)?;
     }
+    Ok(())
 }
 
-/// Get PR information for a branch.
-async fn get_pr_info(repo_path: &str, branch: &str) -> Option<PrInfo> {
-    // Try to get PR info
-    let output = Command::new("gh")
-        .args([
-            "pr",
-            "view",
-            branch,
-            "--json",
-            "number,url,state,mergeable,statusCheckRollup",
-        ])
-        .current_dir(repo_path)
-        .output()
-        .await;
-
-    match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&stdout) {
-                let number = json["number"].as_i64().unwrap_or(0) as i32;
-                let url = json["url"].as_str().unwrap_or("").to_string();
-                let state = json["state"].as_str().unwrap_or("UNKNOWN").to_lowercase();
-                let mergeable_str = json["mergeable"].as_str().unwrap_or("UNKNOWN");
-                let mergeable = mergeable_str == "MERGEABLE";
-
-                // Parse status checks
-                let checks = parse_status_checks(&json["statusCheckRollup"]);
-
-                return Some(PrInfo {
-                    number,
-                    url,
-                    state,
-                    checks,
-                    mergeable,
-                });
-            }
-        }
-        _ => {}
-    }
+// ============================================================================
+// Rebase Check Endpoint
+// ============================================================================
 
-    None
+/// Query parameters for the rebase check endpoint.
+#[derive(Deserialize)]
+pub struct RebaseCheckParams {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose branch to check.
+    pub bead_id: String,
 }
 
-/// Parse status check rollup from gh pr view output.
-/// GitHub Actions returns:
-/// - status: "QUEUED" | "IN_PROGRESS" | "COMPLETED"
-/// - conclusion: "" | "SUCCESS" | "FAILURE" | "CANCELLED" (only set when COMPLETED)
-fn parse_status_checks(rollup: &serde_json::Value) -> ChecksStatus {
-    let mut total = 0;
-    let mut passed = 0;
-    let mut failed = 0;
-    let mut pending = 0;
-
-    if let Some(checks) = rollup.as_array() {
-        for check in checks {
-            total += 1;
-            let status = check["status"].as_str().unwrap_or("");
-            let conclusion = check["conclusion"].as_str().unwrap_or("");
-
-            match status {
-                "QUEUED" | "IN_PROGRESS" => pending += 1,
-                "COMPLETED" => match conclusion {
-                    "SUCCESS" => passed += 1,
-                    "FAILURE" | "CANCELLED" | "TIMED_OUT" | "ACTION_REQUIRED" => failed += 1,
-                    _ => pending += 1, // Unknown conclusion treated as pending
-                },
-                // Legacy status check API uses state/conclusion differently
-                _ => match conclusion {
-                    "SUCCESS" => passed += 1,
-                    "FAILURE" | "ERROR" => failed += 1,
-                    _ => pending += 1,
-                },
-            }
-        }
-    }
+/// Response body for the rebase check endpoint.
+#[derive(Serialize)]
+pub struct RebaseCheckResponse {
+    /// Branch that was checked.
+    pub branch: String,
+    /// Base branch the branch was merged against.
+    pub base: String,
+    /// Whether the branch would rebase cleanly.
+    pub clean: bool,
+    /// Files that would conflict.
+    pub conflicts: Vec<String>,
+}
 
-    let status = if total == 0 {
-        "success".to_string() // No checks = success
-    } else if failed > 0 {
-        "failure".to_string()
-    } else if pending > 0 {
-        "pending".to_string()
-    } else {
-        "success".to_string()
-    };
+/// Predict whether a bead's branch would rebase cleanly onto main.
+///
+/// Merges the branch into the base in memory, so neither the worktree nor
+/// the index is touched. The base is the last fetched `origin/main` (the
+/// target `rebase-siblings` uses), falling back to local `main` or `master`.
+///
+/// # Endpoint
+///
+/// `GET /api/git/rebase-check?repo_path=...&bead_id=...`
+///
+/// # Response
+///
+/// Returns whether the branch is clean and the paths that would conflict.
+pub async fn rebase_check(
+    Query(params): Query<RebaseCheckParams>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo_path = Path::new(&params.repo_path);
 
-    ChecksStatus {
-        total,
-        passed,
-        failed,
-        pending,
-        status,
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            params.repo_path
+        )));
     }
-}
-
-/// Extract PR number from a GitHub PR URL.
-fn extract_pr_number_from_url(url: &str) -> Option<i32> {
-    url.rsplit('/').next().and_then(|s| s.parse().ok())
-}
 
-/// Ensure .worktrees/ is in the repository's .gitignore.
-fn ensure_gitignore_entry(repo_path: &str) -> Result<(), std::io::Error> {
-    let gitignore_path = format!("{}/.gitignore", repo_path);
-    let content = fs::read_to_string(&gitignore_path).unwrap_or_default();
+    let branch_name = WorktreeConfig::load(repo_path).branch_name(&params.bead_id);
+    let (repo, branch) = (repo_path.to_path_buf(), branch_name.clone());
+    let result = tokio::task::spawn_blocking(move || {
+        git_backend::merge_conflicts(&repo, &branch, &["origin/main", "main", "master"])
+    })
+    .await;
 
-    if !content.contains(".worktrees/") && !content.contains(".worktrees") {
-        let mut file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&gitignore_path)?;
-        writeln!(file, "\n# Git worktrees\n.worktrees/")?;
+    match result {
+        Ok(Ok(Some((base, conflicts)))) => Ok(Json(RebaseCheckResponse {
+            branch: branch_name,
+            base,
+            clean: conflicts.is_empty(),
+            conflicts,
+        })),
+        Ok(Ok(None)) => Err(AppError::NotFound(format!(
+            "Branch {} or its base branch not found",
+            branch_name
+        ))),
+        Ok(Err(e)) => {
+            Err(AppError::Internal(format!("Failed to check rebase: {}", e.message())))
+        }
+        Err(e) => Err(AppError::Internal(format!("Failed to check rebase: {}", e))),
     }
-    Ok(())
 }
 
 // ============================================================================
@@ -1450,6 +1952,18 @@ pub struct RebaseSiblingsRequest {
     pub repo_path: String,
     /// Bead ID to exclude from rebasing (the one just merged).
     pub exclude_bead_id: String,
+    /// Leave a conflicting rebase in progress for resolution through the
+    /// conflict endpoints instead of aborting it.
+    #[serde(default)]
+    pub keep_conflicts: bool,
+    /// Worktrees rebased at once; defaults to the project's
+    /// `rebase_concurrency` setting.
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    /// Branch on origin to rebase onto (e.g. "develop"); defaults to the
+    /// remote's default branch.
+    #[serde(default)]
+    pub base_branch: Option<String>,
 }
 
 /// Result for a single sibling rebase operation.
@@ -1462,11 +1976,18 @@ pub struct RebaseSiblingResult {
     /// Error message if rebase failed.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Whether uncommitted changes were stashed around the rebase.
+    pub stashed: bool,
+    /// Whether restoring the stashed changes conflicted. The changes are
+    /// then kept in the stash and the worktree has conflict markers.
+    pub stash_conflict: bool,
 }
 
 /// Response body for the rebase siblings endpoint.
 #[derive(Serialize)]
 pub struct RebaseSiblingsResponse {
+    /// Remote-tracking branch the worktrees were rebased onto.
+    pub base: String,
     /// Results for each sibling worktree.
     pub results: Vec<RebaseSiblingResult>,
     /// Bead IDs that were skipped (not in 'inreview' status).
@@ -1505,7 +2026,7 @@ fn get_bead_status(repo_path: &Path, bead_id: &str) -> Option<String> {
     None
 }
 
-/// Rebase all sibling worktrees onto latest origin/main.
+/// Rebase all sibling worktrees onto the latest base branch on origin.
 ///
 /// # Endpoint
 ///
@@ -1516,98 +2037,103 @@ fn get_bead_status(repo_path: &Path, bead_id: &str) -> Option<String> {
 /// ```json
 /// {
 ///   "repo_path": "/path/to/repo",
-///   "exclude_bead_id": "BD-001"
+///   "exclude_bead_id": "BD-001",
+///   "keep_conflicts": false,
+///   "concurrency": 4,
+///   "base_branch": "develop"
 /// }
 /// ```
 ///
 /// # Response
 ///
 /// Returns results for each sibling worktree rebase attempt.
-pub async fn rebase_siblings(Json(request): Json<RebaseSiblingsRequest>) -> impl IntoResponse {
+pub async fn rebase_siblings(
+    Json(request): Json<RebaseSiblingsRequest>,
+) -> Result<impl IntoResponse, AppError> {
     let repo_path = Path::new(&request.repo_path);
 
     // Validate repository path exists
     if !repo_path.exists() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(serde_json::json!({
-                "error": format!("Repository path does not exist: {}", request.repo_path)
-            })),
-        )
-            .into_response();
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
     }
 
-    // List all worktrees using git worktree list
-    let output = Command::new("git")
-        .args(["worktree", "list", "--porcelain"])
-        .current_dir(&request.repo_path)
-        .output()
-        .await;
+    match rebase_sibling_worktrees(
+        repo_path,
+        &request.exclude_bead_id,
+        request.keep_conflicts,
+        request.concurrency,
+        request.base_branch.as_deref(),
+    )
+    .await
+    {
+        Ok(response) => Ok(Json(response)),
+        Err(e) => Err(AppError::Internal(e)),
+    }
+}
 
-    let worktrees = match output {
-        Ok(output) if output.status.success() => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            parse_worktree_list(&stdout, &request.repo_path)
-        }
-        Ok(output) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to list worktrees: {}", String::from_utf8_lossy(&output.stderr))
-                })),
-            )
-                .into_response();
-        }
-        Err(e) => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({
-                    "error": format!("Failed to run git command: {}", e)
-                })),
-            )
-                .into_response();
-        }
-    };
+/// Rebase the worktrees of beads in review, other than `exclude_bead_id`,
+/// onto the latest `base_branch` on origin (default: origin's default
+/// branch). Up to `concurrency` worktrees (default: the project's
+/// `rebase_concurrency`) are rebased at once.
+async fn rebase_sibling_worktrees(
+    repo_path: &Path,
+    exclude_bead_id: &str,
+    keep_conflicts: bool,
+    concurrency: Option<usize>,
+    base_branch: Option<&str>,
+) -> Result<RebaseSiblingsResponse, String> {
+    // List all worktrees
+    let worktrees = get_worktree_entries(&repo_path.to_string_lossy()).await?;
 
     // Filter out the excluded bead and main worktree
     let siblings: Vec<_> = worktrees
         .into_iter()
         .filter(|w| {
             if let Some(ref bead_id) = w.bead_id {
-                bead_id != &request.exclude_bead_id
+                bead_id != exclude_bead_id
             } else {
                 false // No bead_id means it's main worktree
             }
         })
         .collect();
 
-    let mut results = Vec::new();
     let mut skipped = Vec::new();
 
     // Fetch latest from origin once (in main repo)
-    let fetch_output = Command::new("git")
+    Command::new("git")
         .args(["fetch", "origin"])
-        .current_dir(&request.repo_path)
+        .current_dir(repo_path)
+        .logged()
         .output()
-        .await;
-
-    if let Err(e) = fetch_output {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("Failed to fetch from origin: {}", e)
-            })),
-        )
-            .into_response();
-    }
-
-    // Rebase each sibling that is in 'inreview' status
+        .await
+        .map_err(|e| format!("Failed to fetch from origin: {}", e))?;
+
+    let base = resolve_rebase_base(repo_path, base_branch).await?;
+    let config = WorktreeConfig::load(repo_path);
+    let git_config = Arc::new(config.commit.git_args());
+    let concurrency = concurrency
+        .unwrap_or(config.rebase_concurrency)
+        .clamp(1, config::MAX_REBASE_CONCURRENCY);
+    let permits = Arc::new(Semaphore::new(concurrency));
+    let mut tasks = Vec::new();
+
+    // Rebase each sibling that is in 'inreview' status, a few at a time
     for sibling in siblings {
         let bead_id = match sibling.bead_id {
             Some(id) => id,
             None => continue,
         };
 
+        // A worktree whose directory is gone has nothing to rebase
+        if sibling.prunable {
+            tracing::info!("Skipping rebase for bead {} (worktree missing)", bead_id);
+            skipped.push(bead_id);
+            continue;
+        }
+
         // Only rebase beads that are in 'inreview' status
         // Skip beads that are in_progress, open, or have unknown status
         let status = get_bead_status(repo_path, &bead_id);
@@ -1621,92 +2147,236 @@ pub async fn rebase_siblings(Json(request): Json<RebaseSiblingsRequest>) -> impl
             continue;
         }
 
-        let result = rebase_single_worktree(&sibling.path, &bead_id).await;
-        results.push(result);
+        let (permits, base, git_config) = (permits.clone(), base.clone(), git_config.clone());
+        let task = tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            rebase_single_worktree(
+                &sibling.path,
+                &bead_id,
+                &sibling.branch,
+                &base,
+                keep_conflicts,
+                &git_config,
+            )
+            .await
+        });
+        tasks.push(task);
     }
 
-    Json(RebaseSiblingsResponse { results, skipped }).into_response()
-}
+    // Results keep the order of the worktree list
+    let mut results = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(result) => results.push(result),
+            Err(e) => tracing::error!("Sibling rebase task failed: {}", e),
+        }
+    }
 
-/// Rebase a single worktree onto origin/main.
-async fn rebase_single_worktree(worktree_path: &str, bead_id: &str) -> RebaseSiblingResult {
-    // Fetch in the worktree to update refs
-    let fetch_result = Command::new("git")
-        .args(["fetch", "origin"])
-        .current_dir(worktree_path)
-        .output()
-        .await;
+    Ok(RebaseSiblingsResponse {
+        base,
+        results,
+        skipped,
+    })
+}
 
-    if let Err(e) = fetch_result {
-        return RebaseSiblingResult {
-            bead_id: bead_id.to_string(),
-            success: false,
-            error: Some(format!("Failed to fetch: {}", e)),
+/// Resolves the remote-tracking branch to rebase onto: `origin/<branch>`
+/// for the requested branch, or for origin's default branch if none was
+/// requested.
+async fn resolve_rebase_base(repo_path: &Path, base_branch: Option<&str>) -> Result<String, String> {
+    let repo = repo_path.to_path_buf();
+    let requested = base_branch
+        .map(|b| b.trim().trim_start_matches("origin/").to_string())
+        .filter(|b| !b.is_empty());
+
+    tokio::task::spawn_blocking(move || {
+        let branch = match requested {
+            Some(branch) => branch,
+            None => git_backend::default_branch(&repo, "origin")
+                .map_err(|e| format!("Failed to detect the default branch: {}", e))?
+                .ok_or("Could not detect the default branch of origin; specify base_branch")?,
         };
+        let base = format!("origin/{}", branch);
+        match git_backend::revision_exists(&repo, &base) {
+            Ok(true) => Ok(base),
+            Ok(false) => Err(format!("Base branch not found: {}", base)),
+            Err(e) => Err(format!("Failed to resolve {}: {}", base, e)),
+        }
+    })
+    .await
+    .map_err(|e| format!("Failed to resolve base branch: {}", e))?
+}
+
+/// Rebase a single worktree onto `base` (a remote-tracking branch).
+///
+/// Uncommitted changes are stashed first and restored afterwards. A
+/// conflicting rebase is aborted unless `keep_conflicts` is set.
+/// `git_config` holds `-c` options applying the project's commit settings
+/// to the rewritten commits.
+async fn rebase_single_worktree(
+    worktree_path: &str,
+    bead_id: &str,
+    branch_name: &str,
+    base: &str,
+    keep_conflicts: bool,
+    git_config: &[String],
+) -> RebaseSiblingResult {
+    let mut result = RebaseSiblingResult {
+        bead_id: bead_id.to_string(),
+        success: false,
+        error: None,
+        stashed: false,
+        stash_conflict: false,
+    };
+
+    // origin was fetched once for all siblings by the caller; worktrees
+    // share refs with the main repository
+
+    // Stash uncommitted changes so they don't block the rebase
+    let mut stash = None;
+    if check_worktree_dirty(worktree_path).await {
+        let _guard = stash_lock().lock().await;
+        let stash_output = Command::new("git")
+            .args([
+                "stash",
+                "push",
+                "--include-untracked",
+                "-m",
+                "beads-kanban: auto-stash before rebase",
+            ])
+            .current_dir(worktree_path)
+            .logged()
+            .output()
+            .await;
+
+        match stash_output {
+            Ok(output) if output.status.success() => {
+                result.stashed = true;
+                stash = git_output(worktree_path, &["rev-parse", "refs/stash"]).await;
+            }
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                result.error = Some(format!("Failed to stash changes: {}", stderr));
+                return result;
+            }
+            Err(e) => {
+                result.error = Some(format!("Stash command failed: {}", e));
+                return result;
+            }
+        }
     }
 
-    // Try to rebase onto origin/main
+    // Try to rebase onto the base branch
     let rebase_output = Command::new("git")
-        .args(["rebase", "origin/main"])
+        .args(git_config)
+        .args(["rebase", base])
         .current_dir(worktree_path)
+        .logged()
         .output()
         .await;
 
+    let mut rebase_in_progress = false;
     match rebase_output {
         Ok(output) if output.status.success() => {
             // Rebase succeeded, force push with explicit branch name
             // (branch may not have upstream tracking configured)
-            let branch_name = format!("bd-{}", bead_id);
             let push_output = Command::new("git")
-                .args(["push", "origin", &branch_name, "--force-with-lease"])
+                .args(["push", "origin", branch_name, "--force-with-lease"])
                 .current_dir(worktree_path)
+                .logged()
                 .output()
                 .await;
 
             match push_output {
-                Ok(output) if output.status.success() => RebaseSiblingResult {
-                    bead_id: bead_id.to_string(),
-                    success: true,
-                    error: None,
-                },
+                Ok(output) if output.status.success() => result.success = true,
                 Ok(output) => {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    RebaseSiblingResult {
-                        bead_id: bead_id.to_string(),
-                        success: false,
-                        error: Some(format!("Push failed: {}", stderr)),
-                    }
+                    result.error = Some(format!("Push failed: {}", stderr));
                 }
-                Err(e) => RebaseSiblingResult {
-                    bead_id: bead_id.to_string(),
-                    success: false,
-                    error: Some(format!("Push command failed: {}", e)),
-                },
+                Err(e) => result.error = Some(format!("Push command failed: {}", e)),
             }
         }
         Ok(output) => {
-            // Rebase failed (likely conflict), abort it
+            // Rebase failed (likely conflict)
             let stderr = String::from_utf8_lossy(&output.stderr);
 
-            // Abort the rebase
-            let _ = Command::new("git")
-                .args(["rebase", "--abort"])
-                .current_dir(worktree_path)
-                .output()
-                .await;
+            if keep_conflicts {
+                rebase_in_progress = true;
+                result.error = Some(format!("Rebase conflict (left in progress): {}", stderr));
+            } else {
+                // Abort the rebase
+                let _ = Command::new("git")
+                    .args(["rebase", "--abort"])
+                    .current_dir(worktree_path)
+                    .logged()
+                    .output()
+                    .await;
 
-            RebaseSiblingResult {
-                bead_id: bead_id.to_string(),
-                success: false,
-                error: Some(format!("Rebase conflict: {}", stderr)),
+                result.error = Some(format!("Rebase conflict: {}", stderr));
             }
         }
-        Err(e) => RebaseSiblingResult {
-            bead_id: bead_id.to_string(),
-            success: false,
-            error: Some(format!("Rebase command failed: {}", e)),
-        },
+        Err(e) => result.error = Some(format!("Rebase command failed: {}", e)),
+    }
+
+    // Restore stashed changes; a stopped rebase has to finish first
+    if result.stashed {
+        if rebase_in_progress {
+            if let Some(error) = result.error.as_mut() {
+                error.push_str("\nUncommitted changes remain in the stash.");
+            }
+        } else {
+            result.stash_conflict = !pop_stash(worktree_path, stash.as_deref()).await;
+        }
     }
+
+    result
+}
+
+/// Serializes stash pushes and pops: worktrees share one stash, and
+/// siblings are rebased concurrently.
+fn stash_lock() -> &'static tokio::sync::Mutex<()> {
+    static LOCK: std::sync::OnceLock<tokio::sync::Mutex<()>> = std::sync::OnceLock::new();
+    LOCK.get_or_init(|| tokio::sync::Mutex::new(()))
+}
+
+/// Pop the stash entry with commit `stash` (the most recent entry if
+/// unknown). Returns false if it could not be applied cleanly, in which case
+/// git keeps the stash entry.
+async fn pop_stash(worktree_path: &str, stash: Option<&str>) -> bool {
+    let _guard = stash_lock().lock().await;
+
+    // Other worktrees may have stashed since; find this worktree's entry
+    let mut entry = "stash@{0}".to_string();
+    if let Some(stash) = stash {
+        let list = git_output(worktree_path, &["stash", "list", "--format=%H"])
+            .await
+            .unwrap_or_default();
+        match list.lines().position(|hash| hash == stash) {
+            Some(index) => entry = format!("stash@{{{}}}", index),
+            None => return false,
+        }
+    }
+
+    let output = Command::new("git")
+        .args(["stash", "pop", &entry])
+        .current_dir(worktree_path)
+        .logged()
+        .output()
+        .await;
+
+    matches!(output, Ok(o) if o.status.success())
+}
+
+/// Run a git command in `dir`, returning its trimmed stdout on success.
+async fn git_output(dir: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .logged()
+        .output()
+        .await
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
 #[cfg(test)]
@@ -1715,6 +2385,7 @@ mod tests {
 
     #[test]
     fn test_extract_bead_id() {
+        let extract_bead_id = |branch| WorktreeConfig::default().bead_id_from_branch(branch);
         assert_eq!(extract_bead_id("bd-BD-001"), Some("BD-001".to_string()));
         assert_eq!(extract_bead_id("bd-EPIC-001.1"), Some("EPIC-001.1".to_string()));
         assert_eq!(extract_bead_id("main"), None);
@@ -1722,16 +2393,84 @@ mod tests {
     }
 
     #[test]
-    fn test_extract_pr_number_from_url() {
-        assert_eq!(
-            extract_pr_number_from_url("https://github.com/user/repo/pull/142"),
-            Some(142)
-        );
-        assert_eq!(
-            extract_pr_number_from_url("https://github.com/user/repo/pull/1"),
-            Some(1)
+    fn test_is_relative_subpath() {
+        assert!(is_relative_subpath("src/main.rs"));
+        assert!(is_relative_subpath("./README.md"));
+        assert!(!is_relative_subpath(""));
+        assert!(!is_relative_subpath("/etc/passwd"));
+        assert!(!is_relative_subpath("src/../../secret"));
+    }
+
+    #[tokio::test]
+    async fn test_pop_stash_finds_own_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |cwd: &Path, args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(cwd)
+                .logged()
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join("file.txt"), "base\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-qm", "base"]);
+        let other = dir.path().join("other");
+        git(&repo, &["worktree", "add", "-q", "-b", "other", other.to_str().unwrap()]);
+
+        // Stash in the repo, then in the other worktree on top of it
+        std::fs::write(repo.join("file.txt"), "mine\n").unwrap();
+        git(&repo, &["stash", "push", "-q"]);
+        let repo_str = repo.to_str().unwrap();
+        let mine = git_output(repo_str, &["rev-parse", "refs/stash"]).await;
+        std::fs::write(other.join("file.txt"), "theirs\n").unwrap();
+        git(&other, &["stash", "push", "-q"]);
+
+        assert!(pop_stash(repo_str, mine.as_deref()).await);
+        assert_eq!(std::fs::read_to_string(repo.join("file.txt")).unwrap(), "mine\n");
+        assert!(pop_stash(other.to_str().unwrap(), None).await);
+        assert_eq!(std::fs::read_to_string(other.join("file.txt")).unwrap(), "theirs\n");
+    }
+
+    #[test]
+    fn test_collect_worktree_entries_attributes() {
+        let config = WorktreeConfig::default();
+        let worktree = |dir: &str, branch: Option<&str>| GitWorktree {
+            path: PathBuf::from("/nonexistent-repo").join(&config.worktree_dir).join(dir),
+            branch: branch.map(String::from),
+            locked: false,
+            lock_reason: None,
+            prunable: false,
+        };
+        let locked = GitWorktree {
+            locked: true,
+            lock_reason: Some("crashed".to_string()),
+            ..worktree(&config.branch_name("BD-001"), Some(&config.branch_name("BD-001")))
+        };
+        let detached = worktree(&config.branch_name("BD-002"), None);
+        let prunable = GitWorktree {
+            prunable: true,
+            ..worktree(&config.branch_name("BD-003"), Some(&config.branch_name("BD-003")))
+        };
+
+        let entries = collect_worktree_entries(
+            vec![locked, detached, prunable, worktree("scratch", None)],
+            "/nonexistent-repo",
+            &config,
         );
-        assert_eq!(extract_pr_number_from_url("invalid-url"), None);
+        assert_eq!(entries.len(), 3);
+        assert!(entries[0].locked);
+        assert_eq!(entries[0].lock_reason.as_deref(), Some("crashed"));
+        assert!(entries[1].detached);
+        assert_eq!(entries[1].bead_id.as_deref(), Some("BD-002"));
+        assert_eq!(entries[1].branch, config.branch_name("BD-002"));
+        assert!(entries[2].prunable);
+        assert!(!entries[2].detached);
     }
 
     #[test]
@@ -1758,10 +2497,13 @@ mod tests {
             worktree_path: "/repo/.worktrees/bd-BD-001".to_string(),
             branch: "bd-BD-001".to_string(),
             already_existed: false,
+            hooks_started: false,
+            untrusted_hooks: None,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("\"success\":true"));
         assert!(json.contains("\"already_existed\":false"));
+        assert!(!json.contains("untrusted_hooks"));
     }
 
     #[test]
@@ -1769,6 +2511,10 @@ mod tests {
         let response = PrStatusResponse {
             has_remote: true,
             branch_pushed: true,
+            ahead: 2,
+            behind: 0,
+            provider: "gh".to_string(),
+            provider_reason: None,
             pr: Some(PrInfo {
                 number: 142,
                 url: "https://github.com/user/repo/pull/142".to_string(),
@@ -1781,86 +2527,22 @@ mod tests {
                     status: "pending".to_string(),
                 },
                 mergeable: true,
+                draft: false,
+                protection: None,
             }),
             rate_limit: RateLimitInfo {
                 remaining: 4823,
                 limit: 5000,
                 reset_at: "2024-01-22T15:00:00Z".to_string(),
             },
+            auto_merge: false,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("\"has_remote\":true"));
         assert!(json.contains("\"number\":142"));
         assert!(json.contains("\"remaining\":4823"));
-    }
-
-    #[test]
-    fn test_parse_status_checks_empty() {
-        let empty = serde_json::json!([]);
-        let checks = parse_status_checks(&empty);
-        assert_eq!(checks.total, 0);
-        assert_eq!(checks.status, "success");
-    }
-
-    #[test]
-    fn test_parse_status_checks_queued() {
-        // QUEUED status should count as pending
-        let rollup = serde_json::json!([
-            {"status": "QUEUED", "conclusion": ""},
-            {"status": "IN_PROGRESS", "conclusion": ""}
-        ]);
-        let checks = parse_status_checks(&rollup);
-        assert_eq!(checks.total, 2);
-        assert_eq!(checks.pending, 2);
-        assert_eq!(checks.passed, 0);
-        assert_eq!(checks.failed, 0);
-        assert_eq!(checks.status, "pending");
-    }
-
-    #[test]
-    fn test_parse_status_checks_completed() {
-        // COMPLETED status uses conclusion field
-        let rollup = serde_json::json!([
-            {"status": "COMPLETED", "conclusion": "SUCCESS"},
-            {"status": "COMPLETED", "conclusion": "FAILURE"},
-            {"status": "COMPLETED", "conclusion": "CANCELLED"}
-        ]);
-        let checks = parse_status_checks(&rollup);
-        assert_eq!(checks.total, 3);
-        assert_eq!(checks.passed, 1);
-        assert_eq!(checks.failed, 2); // FAILURE + CANCELLED
-        assert_eq!(checks.pending, 0);
-        assert_eq!(checks.status, "failure");
-    }
-
-    #[test]
-    fn test_parse_status_checks_mixed() {
-        // Mix of queued, in-progress, and completed checks
-        let rollup = serde_json::json!([
-            {"status": "COMPLETED", "conclusion": "SUCCESS"},
-            {"status": "QUEUED", "conclusion": ""},
-            {"status": "COMPLETED", "conclusion": "FAILURE"}
-        ]);
-        let checks = parse_status_checks(&rollup);
-        assert_eq!(checks.total, 3);
-        assert_eq!(checks.passed, 1);
-        assert_eq!(checks.pending, 1);
-        assert_eq!(checks.failed, 1);
-        assert_eq!(checks.status, "failure");
-    }
-
-    #[test]
-    fn test_parse_status_checks_all_success() {
-        let rollup = serde_json::json!([
-            {"status": "COMPLETED", "conclusion": "SUCCESS"},
-            {"status": "COMPLETED", "conclusion": "SUCCESS"}
-        ]);
-        let checks = parse_status_checks(&rollup);
-        assert_eq!(checks.total, 2);
-        assert_eq!(checks.passed, 2);
-        assert_eq!(checks.pending, 0);
-        assert_eq!(checks.failed, 0);
-        assert_eq!(checks.status, "success");
+        assert!(json.contains("\"provider\":\"gh\""));
+        assert!(!json.contains("provider_reason"));
     }
 
     #[test]
@@ -1909,21 +2591,28 @@ mod tests {
     #[test]
     fn test_rebase_siblings_response_with_skipped() {
         let response = RebaseSiblingsResponse {
+            base: "origin/develop".to_string(),
             results: vec![RebaseSiblingResult {
                 bead_id: "BD-001".to_string(),
                 success: true,
                 error: None,
+                stashed: true,
+                stash_conflict: false,
             }],
             skipped: vec!["BD-002".to_string(), "BD-003".to_string()],
         };
         let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"base\":\"origin/develop\""));
         assert!(json.contains("\"skipped\":[\"BD-002\",\"BD-003\"]"));
         assert!(json.contains("\"bead_id\":\"BD-001\""));
+        assert!(json.contains("\"stashed\":true"));
+        assert!(json.contains("\"stash_conflict\":false"));
     }
 
     #[test]
     fn test_rebase_siblings_response_empty_skipped() {
         let response = RebaseSiblingsResponse {
+            base: "origin/main".to_string(),
             results: vec![],
             skipped: vec![],
         };
@@ -1977,6 +2666,47 @@ mod tests {
         assert!(json.contains("\"total_files\":0"));
     }
 
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), ("b\nc".to_string(), true));
+        assert_eq!(tail_lines("a\nb", 5), ("a\nb".to_string(), false));
+        assert_eq!(tail_lines("", 5), (String::new(), false));
+    }
+
+    #[test]
+    fn test_compose_pr_text() {
+        let bead: Bead = serde_json::from_str(
+            r#"{"id": "BD-001", "title": "Fix badge", "status": "open", "description": "Contrast is too low", "design_doc": ".designs/BD-001.md"}"#,
+        )
+        .unwrap();
+        let commit = |id: &str, summary: &str| git_backend::CommitSummary {
+            id: id.to_string(),
+            summary: summary.to_string(),
+            author: "Test".to_string(),
+            time: 0,
+        };
+        // Newest first, as the branch walk returns them
+        let commits = vec![
+            commit("bbbbbbbbbb", "Darken badge text"),
+            commit("aaaaaaaaaa", "Add contrast token"),
+        ];
+
+        let (title, body) = compose_pr_text("BD-001", Some(&bead), &commits);
+        assert_eq!(title, "Fix badge");
+        assert_eq!(
+            body,
+            "Closes BD-001\n\nContrast is too low\n\nDesign doc: `.designs/BD-001.md`\n\n## Commits\n\n- Add contrast token (aaaaaaa)\n- Darken badge text (bbbbbbb)"
+        );
+
+        let (title, body) = compose_pr_text("BD-002", None, &commits[..1]);
+        assert_eq!(title, "Darken badge text");
+        assert_eq!(body, "Closes BD-002\n\n## Commits\n\n- Darken badge text (bbbbbbb)");
+
+        let (title, body) = compose_pr_text("BD-003", None, &[]);
+        assert_eq!(title, "BD-003");
+        assert_eq!(body, "Closes BD-003");
+    }
+
     #[test]
     fn test_pr_file_entry_all_statuses() {
         // Verify all GitHub file status values serialize correctly
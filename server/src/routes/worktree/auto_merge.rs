@@ -0,0 +1,293 @@
+//! Auto-merge: PRs armed to merge as soon as they are ready.
+//!
+//! Arming a bead's PR records how to merge it. Whenever the background
+//! poller refreshes the status of an armed PR that is open, not a draft,
+//! mergeable, and has passing checks, the PR is merged as `merge-pr` would
+//! merge it. Afterwards the sibling worktrees are rebased and the bead's
+//! worktree is cleaned up, as the board does after a manual merge. Armed
+//! PRs are kept in memory, so a restart disarms them.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::{info, warn};
+
+use super::{
+    default_merge_method, load_pr_status, merge_bead_pr, poller, rebase_sibling_worktrees,
+    remove_worktree, PrStatusResponse,
+};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::routes::projects::AppState;
+
+/// How an armed PR gets merged.
+#[derive(Clone)]
+struct AutoMerge {
+    merge_method: String,
+    rebase_siblings: bool,
+    cleanup: bool,
+}
+
+/// What to do with an armed PR given its latest status.
+#[derive(Debug, PartialEq)]
+enum Action {
+    /// Not ready yet (no PR, draft, checks pending or failing, not mergeable).
+    Wait,
+    /// Ready to merge.
+    Merge,
+    /// The PR was merged or closed some other way.
+    Disarm,
+}
+
+fn armed() -> &'static Mutex<HashMap<(PathBuf, String), AutoMerge>> {
+    static ARMED: OnceLock<Mutex<HashMap<(PathBuf, String), AutoMerge>>> = OnceLock::new();
+    ARMED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Whether a bead's PR is armed to merge automatically.
+pub fn is_armed(repo_path: &Path, bead_id: &str) -> bool {
+    armed()
+        .lock()
+        .unwrap()
+        .contains_key(&(repo_path.to_path_buf(), bead_id.to_string()))
+}
+
+/// Decides what to do with an armed PR.
+fn next_action(status: &PrStatusResponse) -> Action {
+    match &status.pr {
+        None => Action::Wait,
+        Some(pr) if pr.state != "open" => Action::Disarm,
+        Some(pr) if !pr.draft && pr.mergeable && pr.checks.status == "success" => Action::Merge,
+        Some(_) => Action::Wait,
+    }
+}
+
+/// Acts on a freshly fetched status if the bead's PR is armed: merges it
+/// in the background once it is ready, or disarms it once it is closed.
+pub fn on_status(db: &Arc<Database>, repo_path: &Path, bead_id: &str, status: &PrStatusResponse) {
+    let key = (repo_path.to_path_buf(), bead_id.to_string());
+    let mut armed = armed().lock().unwrap();
+    if !armed.contains_key(&key) {
+        return;
+    }
+
+    match next_action(status) {
+        Action::Wait => {}
+        Action::Disarm => {
+            armed.remove(&key);
+        }
+        Action::Merge => {
+            // Disarm first so the next refresh doesn't merge again
+            if let Some(settings) = armed.remove(&key) {
+                tokio::spawn(merge(db.clone(), key.0, key.1, settings));
+            }
+        }
+    }
+}
+
+/// Merges an armed PR, then rebases siblings and cleans up its worktree.
+async fn merge(db: Arc<Database>, repo_path: PathBuf, bead_id: String, settings: AutoMerge) {
+    if let Err(e) = merge_bead_pr(&db, &repo_path, &bead_id, &settings.merge_method).await {
+        warn!("Auto-merge of {} in {} failed: {}", bead_id, repo_path.display(), e);
+        return;
+    }
+    info!("Auto-merged PR for {} in {}", bead_id, repo_path.display());
+
+    if settings.rebase_siblings {
+        match rebase_sibling_worktrees(&repo_path, &bead_id, false, None, None).await {
+            Ok(response) => {
+                for result in response.results.iter().filter(|r| !r.success) {
+                    warn!(
+                        "Rebase of {} after auto-merging {} failed: {}",
+                        result.bead_id,
+                        bead_id,
+                        result.error.as_deref().unwrap_or("unknown error")
+                    );
+                }
+            }
+            Err(e) => warn!("Failed to rebase siblings after auto-merging {}: {}", bead_id, e),
+        }
+    }
+
+    if settings.cleanup {
+        if let Err(e) = remove_worktree(&repo_path, &bead_id).await {
+            warn!("Failed to clean up {} after auto-merge: {}", bead_id, e);
+        }
+    }
+}
+
+/// Request body for arming or disarming auto-merge.
+#[derive(Deserialize)]
+pub struct AutoMergeRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose PR to merge.
+    pub bead_id: String,
+    /// Arm (true, the default) or disarm (false) auto-merge.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Merge method: "merge", "squash", or "rebase".
+    #[serde(default = "default_merge_method")]
+    pub merge_method: String,
+    /// Rebase sibling worktrees after merging.
+    #[serde(default = "default_true")]
+    pub rebase_siblings: bool,
+    /// Remove the bead's worktree and close the bead after merging.
+    #[serde(default = "default_true")]
+    pub cleanup: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Response body for the auto-merge endpoint.
+#[derive(Serialize)]
+pub struct AutoMergeResponse {
+    /// Whether the operation was successful.
+    pub success: bool,
+    /// Whether the PR is now armed to merge automatically.
+    pub armed: bool,
+}
+
+/// Arm or disarm automatic merging of a bead's PR.
+///
+/// A PR that is already ready starts merging right away.
+///
+/// # Endpoint
+///
+/// `POST /api/git/auto-merge`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "bead_id": "BD-001",
+///   "enabled": true,
+///   "merge_method": "squash",
+///   "rebase_siblings": true,
+///   "cleanup": true
+/// }
+/// ```
+pub async fn auto_merge(
+    State(db): State<AppState>,
+    Json(request): Json<AutoMergeRequest>,
+) -> Result<Json<AutoMergeResponse>, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+    let key = (repo_path.to_path_buf(), request.bead_id.clone());
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
+    }
+
+    if !request.enabled {
+        armed().lock().unwrap().remove(&key);
+        poller::invalidate(repo_path, &request.bead_id);
+        return Ok(Json(AutoMergeResponse {
+            success: true,
+            armed: false,
+        }));
+    }
+
+    let status = load_pr_status(&db, &request.repo_path, &request.bead_id)
+        .await
+        .map_err(AppError::Internal)?;
+    if status.pr.as_ref().map(|pr| pr.state.as_str()) != Some("open") {
+        return Err(AppError::NotFound(format!(
+            "No open PR found for {}",
+            request.bead_id
+        )));
+    }
+
+    armed().lock().unwrap().insert(
+        key,
+        AutoMerge {
+            merge_method: request.merge_method,
+            rebase_siblings: request.rebase_siblings,
+            cleanup: request.cleanup,
+        },
+    );
+    poller::invalidate(repo_path, &request.bead_id);
+    on_status(&db, repo_path, &request.bead_id, &status);
+
+    Ok(Json(AutoMergeResponse {
+        success: true,
+        armed: is_armed(repo_path, &request.bead_id),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::worktree::{ChecksStatus, PrInfo, RateLimitInfo};
+
+    fn status(state: &str, checks: &str, mergeable: bool, draft: bool) -> PrStatusResponse {
+        PrStatusResponse {
+            has_remote: true,
+            branch_pushed: true,
+            ahead: 1,
+            behind: 0,
+            provider: "github".to_string(),
+            provider_reason: None,
+            pr: Some(PrInfo {
+                number: 1,
+                url: "https://github.com/user/repo/pull/1".to_string(),
+                state: state.to_string(),
+                checks: ChecksStatus {
+                    total: 1,
+                    passed: 0,
+                    failed: 0,
+                    pending: 0,
+                    status: checks.to_string(),
+                },
+                mergeable,
+                draft,
+                protection: None,
+            }),
+            rate_limit: RateLimitInfo::unknown(),
+            auto_merge: true,
+        }
+    }
+
+    #[test]
+    fn test_next_action() {
+        assert_eq!(next_action(&status("open", "success", true, false)), Action::Merge);
+        assert_eq!(next_action(&status("open", "pending", true, false)), Action::Wait);
+        assert_eq!(next_action(&status("open", "failure", true, false)), Action::Wait);
+        assert_eq!(next_action(&status("open", "success", false, false)), Action::Wait);
+        assert_eq!(next_action(&status("open", "success", true, true)), Action::Wait);
+        assert_eq!(next_action(&status("merged", "success", true, false)), Action::Disarm);
+
+        let mut no_pr = status("open", "success", true, false);
+        no_pr.pr = None;
+        assert_eq!(next_action(&no_pr), Action::Wait);
+    }
+
+    #[test]
+    fn test_closed_pr_disarms() {
+        let repo_path = Path::new("/tmp/auto-merge-test-repo");
+        armed().lock().unwrap().insert(
+            (repo_path.to_path_buf(), "BD-001".to_string()),
+            AutoMerge {
+                merge_method: "squash".to_string(),
+                rebase_siblings: true,
+                cleanup: true,
+            },
+        );
+        assert!(is_armed(repo_path, "BD-001"));
+
+        let db = Arc::new(Database::new_in_memory().unwrap());
+        on_status(&db, repo_path, "BD-001", &status("open", "pending", true, false));
+        assert!(is_armed(repo_path, "BD-001"));
+
+        on_status(&db, repo_path, "BD-001", &status("closed", "success", false, false));
+        assert!(!is_armed(repo_path, "BD-001"));
+    }
+}
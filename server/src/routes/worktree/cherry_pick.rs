@@ -0,0 +1,269 @@
+//! Cherry-picking commits from one bead's branch into another bead's
+//! worktree, e.g. when a fix was committed on the wrong card.
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use super::WorktreeConfig;
+use crate::error::AppError;
+use crate::git_backend;
+use crate::logging::LogCommand;
+
+/// Request body for cherry-picking commits.
+#[derive(Deserialize)]
+pub struct CherryPickRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead whose branch the commits are on.
+    pub source_bead_id: String,
+    /// Bead whose worktree receives the commits.
+    pub target_bead_id: String,
+    /// Commit IDs (full or abbreviated) to apply. They are applied in the
+    /// order they appear on the source branch.
+    pub commits: Vec<String>,
+    /// Leave a conflicting cherry-pick in progress for resolution through
+    /// the conflict endpoints instead of aborting it.
+    #[serde(default)]
+    pub keep_conflicts: bool,
+}
+
+/// Response body for the cherry-pick endpoint; also the `details` of the
+/// 409 when the commits conflict.
+#[derive(Serialize)]
+pub struct CherryPickResponse {
+    /// Whether every commit was applied.
+    pub success: bool,
+    /// Full IDs of the source commits, in the order they were applied.
+    pub commits: Vec<String>,
+    /// Whether the cherry-pick conflicted and was left in progress.
+    pub conflict: bool,
+}
+
+/// Apply commits from one bead's branch onto another bead's worktree.
+///
+/// The target worktree must be clean. A conflicting cherry-pick is aborted,
+/// leaving the worktree as it was, unless `keep_conflicts` is set.
+///
+/// # Endpoint
+///
+/// `POST /api/git/cherry-pick`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "source_bead_id": "BD-001",
+///   "target_bead_id": "BD-002",
+///   "commits": ["3f2c9a1"],
+///   "keep_conflicts": false
+/// }
+/// ```
+///
+/// # Response
+///
+/// Returns the applied commits, or 409 when the commits did not apply
+/// cleanly, with the commits and whether the conflict was kept in
+/// `details`.
+pub async fn cherry_pick(
+    Json(request): Json<CherryPickRequest>,
+) -> Result<Json<CherryPickResponse>, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
+    }
+    if request.commits.is_empty() {
+        return Err(AppError::BadRequest("No commits to cherry-pick".to_string()));
+    }
+    if request.source_bead_id == request.target_bead_id {
+        return Err(AppError::BadRequest(
+            "Source and target bead must differ".to_string(),
+        ));
+    }
+
+    let config = WorktreeConfig::load(repo_path);
+    let source_branch = config.branch_name(&request.source_bead_id);
+    let target_branch = config.branch_name(&request.target_bead_id);
+    let worktree_path = config.worktree_path(repo_path, &request.target_bead_id);
+    if !worktree_path.exists() {
+        return Err(AppError::NotFound(format!(
+            "No worktree found for bead {}",
+            request.target_bead_id
+        )));
+    }
+
+    let commits = select_commits(
+        repo_path.to_path_buf(),
+        worktree_path.clone(),
+        source_branch,
+        target_branch,
+        request.commits,
+    )
+    .await?;
+
+    let output = Command::new("git")
+        .args(config.commit.git_args())
+        .arg("cherry-pick")
+        // Record where each commit came from in its message
+        .arg("-x")
+        .args(&commits)
+        .current_dir(&worktree_path)
+        .logged()
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => Ok(Json(CherryPickResponse {
+            success: true,
+            commits,
+            conflict: false,
+        })),
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            if !request.keep_conflicts {
+                let _ = Command::new("git")
+                    .args(["cherry-pick", "--abort"])
+                    .current_dir(&worktree_path)
+                    .logged()
+                    .output()
+                    .await;
+            }
+            let response = CherryPickResponse {
+                success: false,
+                commits,
+                conflict: request.keep_conflicts,
+            };
+            Err(AppError::Conflict(format!("Cherry-pick failed: {}", stderr))
+                .with_details(serde_json::to_value(&response).unwrap_or_default()))
+        }
+        Err(e) => Err(AppError::Internal(format!(
+            "Failed to run git cherry-pick: {}",
+            e
+        ))),
+    }
+}
+
+/// Checks the target worktree can take commits and that each requested
+/// commit is on the source branch but not the target, returning their full
+/// IDs in source branch order.
+async fn select_commits(
+    repo_path: PathBuf,
+    worktree_path: PathBuf,
+    source_branch: String,
+    target_branch: String,
+    requested: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    let internal =
+        |e: git2::Error| AppError::Internal(format!("Failed to read commits: {}", e.message()));
+
+    tokio::task::spawn_blocking(move || {
+        if let Some(operation) = git_backend::operation_in_progress(&worktree_path).map_err(internal)? {
+            return Err(AppError::Conflict(format!(
+                "A {} is in progress in the target worktree",
+                operation
+            )));
+        }
+        if git_backend::is_dirty(&worktree_path).map_err(internal)? {
+            return Err(AppError::Conflict(
+                "The target worktree has uncommitted changes".to_string(),
+            ));
+        }
+        if !git_backend::revision_exists(&repo_path, &source_branch).map_err(internal)? {
+            return Err(AppError::NotFound(format!(
+                "Branch not found: {}",
+                source_branch
+            )));
+        }
+
+        let candidates = git_backend::commits_between(&repo_path, &target_branch, &source_branch)
+            .map_err(internal)?;
+        let mut wanted = Vec::new();
+        for rev in &requested {
+            match git_backend::resolve_commit(&repo_path, rev).map_err(internal)? {
+                Some(id) if candidates.contains(&id) => wanted.push(id),
+                _ => {
+                    return Err(AppError::BadRequest(format!(
+                        "Commit {} is not on {} or is already on {}",
+                        rev, source_branch, target_branch
+                    )))
+                }
+            }
+        }
+        Ok(candidates.into_iter().filter(|id| wanted.contains(id)).collect())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Failed to read commits: {}", e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use git2::{Repository, Signature, WorktreeAddOptions};
+
+    /// Writes `file` and commits it on HEAD, returning the commit ID.
+    fn commit(repo: &Repository, dir: &Path, file: &str, contents: &str) -> String {
+        std::fs::write(dir.join(file), contents).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new(file)).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &signature, &signature, contents, &tree, &parents)
+            .unwrap()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_select_commits() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.set_head("refs/heads/main").unwrap();
+        let base = commit(&repo, dir.path(), "a.txt", "base");
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.branch("bd-BD-002", &head, false).unwrap();
+        repo.branch("bd-BD-001", &head, false).unwrap();
+        repo.set_head("refs/heads/bd-BD-001").unwrap();
+        let first = commit(&repo, dir.path(), "b.txt", "first");
+        let second = commit(&repo, dir.path(), "c.txt", "second");
+
+        let target = repo.find_branch("bd-BD-002", git2::BranchType::Local).unwrap();
+        let worktree_path = dir.path().join(".worktrees").join("bd-BD-002");
+        std::fs::create_dir_all(worktree_path.parent().unwrap()).unwrap();
+        let mut options = WorktreeAddOptions::new();
+        options.reference(Some(target.get()));
+        repo.worktree("bd-BD-002", &worktree_path, Some(&options)).unwrap();
+
+        let select = |requested: Vec<String>| {
+            select_commits(
+                dir.path().to_path_buf(),
+                worktree_path.clone(),
+                "bd-BD-001".to_string(),
+                "bd-BD-002".to_string(),
+                requested,
+            )
+        };
+
+        // Applied in branch order, whatever order they were asked for
+        let selected = select(vec![second.clone(), first[..8].to_string()]).await.unwrap();
+        assert_eq!(selected, vec![first.clone(), second.clone()]);
+
+        // Already on the target branch
+        let error = select(vec![base]).await.unwrap_err();
+        assert_eq!(error.status(), StatusCode::BAD_REQUEST);
+
+        std::fs::write(worktree_path.join("a.txt"), "dirty").unwrap();
+        let error = select(vec![first]).await.unwrap_err();
+        assert_eq!(error.status(), StatusCode::CONFLICT);
+    }
+}
@@ -0,0 +1,403 @@
+//! Completing a bead in one request: merge its PR, remove its worktree,
+//! close the bead, and rebase the sibling worktrees.
+//!
+//! Each step is reported separately. The sequence stops at the first step
+//! that fails, and calling the endpoint again resumes it: steps that already
+//! succeeded are skipped, whether this server recorded them or their effect
+//! is visible (a merged PR, a missing worktree, a closed bead). Progress is
+//! kept in memory, so a restart falls back to the visible effects alone.
+//! A failed attempt answers with the failing step's error, carrying the
+//! step results in its `details`.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use super::{
+    close_bead, config::WorktreeConfig, default_merge_method, get_bead_status, merge_bead_pr,
+    poller, pr::PrProvider, rebase_sibling_worktrees, remove_worktree_checkout,
+    RebaseSiblingsResponse,
+};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::routes::projects::AppState;
+
+/// Progress key: repository path and bead ID.
+type Key = (PathBuf, String);
+
+/// A step of completing a bead, in the order they run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    Merge,
+    RemoveWorktree,
+    CloseBead,
+    RebaseSiblings,
+}
+
+const STEPS: [Step; 4] = [
+    Step::Merge,
+    Step::RemoveWorktree,
+    Step::CloseBead,
+    Step::RebaseSiblings,
+];
+
+/// How a step went.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    /// The step ran and succeeded.
+    Done,
+    /// Nothing to do: already done or not requested.
+    Skipped,
+    /// The step failed; later steps did not run.
+    Failed,
+    /// Not attempted because an earlier step failed.
+    Pending,
+}
+
+/// Result of a single step.
+#[derive(Serialize)]
+pub struct StepResult {
+    pub step: Step,
+    pub status: StepStatus,
+    /// Why the step was skipped, what it did, or why it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Steps that succeeded in an earlier, unfinished attempt.
+fn progress() -> &'static Mutex<HashMap<Key, Vec<Step>>> {
+    static PROGRESS: OnceLock<Mutex<HashMap<Key, Vec<Step>>>> = OnceLock::new();
+    PROGRESS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Beads currently being completed.
+fn running() -> &'static Mutex<HashSet<Key>> {
+    static RUNNING: OnceLock<Mutex<HashSet<Key>>> = OnceLock::new();
+    RUNNING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Marks a bead as being completed until dropped.
+struct RunningGuard(Key);
+
+impl RunningGuard {
+    /// Returns `None` if the bead is already being completed.
+    fn acquire(key: Key) -> Option<Self> {
+        let inserted = running().lock().unwrap().insert(key.clone());
+        inserted.then(|| RunningGuard(key))
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        running().lock().unwrap().remove(&self.0);
+    }
+}
+
+/// Request body for completing a bead.
+#[derive(Deserialize)]
+pub struct CompleteBeadRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID to complete.
+    pub bead_id: String,
+    /// Merge method: "merge", "squash", or "rebase".
+    #[serde(default = "default_merge_method")]
+    pub merge_method: String,
+    /// Rebase sibling worktrees after merging.
+    #[serde(default = "default_true")]
+    pub rebase_siblings: bool,
+    /// Branch on origin to rebase siblings onto; defaults to the remote's
+    /// default branch.
+    #[serde(default)]
+    pub base_branch: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Response body for the complete bead endpoint; also the `details` of
+/// the error when a step fails.
+#[derive(Serialize)]
+pub struct CompleteBeadResponse {
+    /// Whether every step succeeded or was skipped.
+    pub success: bool,
+    /// Result of each step, in order.
+    pub steps: Vec<StepResult>,
+    /// Per-worktree results of the sibling rebase, if it ran.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rebase: Option<RebaseSiblingsResponse>,
+}
+
+/// Merge a bead's PR, remove its worktree, close it, and rebase the
+/// sibling worktrees.
+///
+/// Stops at the first failing step. Calling it again resumes from there.
+///
+/// # Endpoint
+///
+/// `POST /api/git/complete-bead`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "bead_id": "BD-001",
+///   "merge_method": "squash",
+///   "rebase_siblings": true,
+///   "base_branch": "main"
+/// }
+/// ```
+///
+/// # Response
+///
+/// Returns the result of each step (`done`, `skipped`, `failed`, or
+/// `pending`), with 409 if the bead is already being completed. When a step
+/// fails, the error is that step's, with the step results in `details`.
+pub async fn complete_bead(
+    State(db): State<AppState>,
+    Json(request): Json<CompleteBeadRequest>,
+) -> Result<Json<CompleteBeadResponse>, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
+    }
+
+    let key = (repo_path.to_path_buf(), request.bead_id.clone());
+    let Some(_guard) = RunningGuard::acquire(key) else {
+        return Err(AppError::Conflict(format!(
+            "{} is already being completed",
+            request.bead_id
+        )));
+    };
+
+    complete(&db, repo_path, &request).await.map(Json)
+}
+
+/// Runs the steps in order, skipping those recorded as done.
+async fn complete(
+    db: &Database,
+    repo_path: &Path,
+    request: &CompleteBeadRequest,
+) -> Result<CompleteBeadResponse, AppError> {
+    let key = (repo_path.to_path_buf(), request.bead_id.clone());
+    let done = progress().lock().unwrap().get(&key).cloned().unwrap_or_default();
+
+    let mut steps = Vec::new();
+    let mut rebase = None;
+    let mut error = None;
+
+    for step in STEPS {
+        if error.is_some() {
+            steps.push(StepResult {
+                step,
+                status: StepStatus::Pending,
+                detail: None,
+            });
+            continue;
+        }
+        if done.contains(&step) {
+            steps.push(StepResult {
+                step,
+                status: StepStatus::Skipped,
+                detail: Some("Done in an earlier attempt".to_string()),
+            });
+            continue;
+        }
+
+        match run_step(db, step, repo_path, request, &mut rebase).await {
+            Ok((status, detail)) => {
+                progress().lock().unwrap().entry(key.clone()).or_default().push(step);
+                steps.push(StepResult { step, status, detail });
+            }
+            Err(e) => {
+                steps.push(StepResult {
+                    step,
+                    status: StepStatus::Failed,
+                    detail: Some(e.message().to_string()),
+                });
+                error = Some(e);
+            }
+        }
+    }
+
+    let response = CompleteBeadResponse {
+        success: error.is_none(),
+        steps,
+        rebase,
+    };
+    if let Some(error) = error {
+        let details = serde_json::to_value(&response).unwrap_or_default();
+        return Err(error.with_details(details));
+    }
+
+    // Nothing left to resume
+    progress().lock().unwrap().remove(&key);
+    Ok(response)
+}
+
+/// Runs one step, returning whether it was done or skipped and why.
+async fn run_step(
+    db: &Database,
+    step: Step,
+    repo_path: &Path,
+    request: &CompleteBeadRequest,
+    rebase: &mut Option<RebaseSiblingsResponse>,
+) -> Result<(StepStatus, Option<String>), AppError> {
+    let bead_id = &request.bead_id;
+    match step {
+        Step::Merge => {
+            let branch_name = WorktreeConfig::load(repo_path).branch_name(bead_id);
+            let provider = PrProvider::detect(db, &repo_path.to_string_lossy())
+                .await
+                .map_err(AppError::Internal)?;
+            if let Some((number, _)) = provider.merged_pr(&branch_name).await {
+                poller::invalidate(repo_path, bead_id);
+                return Ok((StepStatus::Skipped, Some(format!("PR #{} is already merged", number))));
+            }
+            merge_bead_pr(db, repo_path, bead_id, &request.merge_method).await?;
+            Ok((StepStatus::Done, None))
+        }
+        Step::RemoveWorktree => match remove_worktree_checkout(repo_path, bead_id)
+            .await
+            .map_err(AppError::Internal)?
+        {
+            true => Ok((StepStatus::Done, None)),
+            false => Ok((StepStatus::Skipped, Some("No worktree to remove".to_string()))),
+        },
+        Step::CloseBead => {
+            if get_bead_status(repo_path, bead_id).as_deref() == Some("closed") {
+                return Ok((StepStatus::Skipped, Some("Bead is already closed".to_string())));
+            }
+            close_bead(repo_path, bead_id)
+                .await
+                .map_err(AppError::Internal)?;
+            Ok((StepStatus::Done, None))
+        }
+        Step::RebaseSiblings => {
+            if !request.rebase_siblings {
+                return Ok((StepStatus::Skipped, Some("Not requested".to_string())));
+            }
+            let response = rebase_sibling_worktrees(
+                repo_path,
+                bead_id,
+                false,
+                None,
+                request.base_branch.as_deref(),
+            )
+            .await
+            .map_err(AppError::Internal)?;
+            let failed = response.results.iter().filter(|r| !r.success).count();
+            let detail = format!(
+                "Rebased {} of {} worktrees onto {}",
+                response.results.len() - failed,
+                response.results.len(),
+                response.base
+            );
+            *rebase = Some(response);
+            Ok((StepStatus::Done, Some(detail)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(repo_path: &Path) -> CompleteBeadRequest {
+        CompleteBeadRequest {
+            repo_path: repo_path.to_string_lossy().to_string(),
+            bead_id: "BD-001".to_string(),
+            merge_method: default_merge_method(),
+            rebase_siblings: false,
+            base_branch: None,
+        }
+    }
+
+    fn statuses(response: &CompleteBeadResponse) -> Vec<StepStatus> {
+        response.steps.iter().map(|s| s.status).collect()
+    }
+
+    #[tokio::test]
+    async fn test_complete_stops_at_failed_step() {
+        let dir = tempfile::tempdir().unwrap();
+        // Bitbucket without credentials makes the merge step fail without network
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        repo.remote("origin", "git@bitbucket.org:team/repo.git").unwrap();
+        std::env::remove_var("BITBUCKET_USERNAME");
+
+        let db = Database::new_in_memory().unwrap();
+        let error = complete(&db, dir.path(), &request(dir.path()))
+            .await
+            .err()
+            .unwrap();
+        assert!(error.message().contains("Bitbucket credentials not configured"));
+        let body = error.body();
+        assert_eq!(body["details"]["success"], false);
+        assert_eq!(
+            body["details"]["steps"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|s| s["status"].as_str().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["failed", "pending", "pending", "pending"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_complete_resumes_after_recorded_steps() {
+        let dir = tempfile::tempdir().unwrap();
+        let beads_dir = dir.path().join(".beads");
+        std::fs::create_dir_all(&beads_dir).unwrap();
+        std::fs::write(
+            beads_dir.join("issues.jsonl"),
+            r#"{"id":"BD-001","status":"closed"}"#,
+        )
+        .unwrap();
+        let key = (dir.path().to_path_buf(), "BD-001".to_string());
+        progress().lock().unwrap().insert(key.clone(), vec![Step::Merge]);
+
+        let db = Database::new_in_memory().unwrap();
+        let response = complete(&db, dir.path(), &request(dir.path())).await.unwrap();
+        assert!(response.success);
+        assert_eq!(statuses(&response), vec![StepStatus::Skipped; 4]);
+        assert_eq!(
+            response.steps[0].detail.as_deref(),
+            Some("Done in an earlier attempt")
+        );
+        // Finished, so nothing is left to resume
+        assert!(!progress().lock().unwrap().contains_key(&key));
+    }
+
+    #[test]
+    fn test_running_guard() {
+        let key = (PathBuf::from("/tmp/complete-test-repo"), "BD-001".to_string());
+        let guard = RunningGuard::acquire(key.clone()).unwrap();
+        assert!(RunningGuard::acquire(key.clone()).is_none());
+        drop(guard);
+        assert!(RunningGuard::acquire(key).is_some());
+    }
+
+    #[test]
+    fn test_step_result_serialization() {
+        let result = StepResult {
+            step: Step::RemoveWorktree,
+            status: StepStatus::Skipped,
+            detail: Some("No worktree to remove".to_string()),
+        };
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"step\":\"remove_worktree\""));
+        assert!(json.contains("\"status\":\"skipped\""));
+    }
+}
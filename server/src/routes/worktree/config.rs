@@ -0,0 +1,576 @@
+//! Per-project worktree settings.
+//!
+//! Stored under the `worktree` key of `.beads/config.json` in the repository:
+//!
+//! ```json
+//! { "worktree": { "branch_template": "bd-{id}", "worktree_dir": ".worktrees" } }
+//! ```
+//!
+//! Projects without the file (or the key) get the defaults shown above, no
+//! post-create hooks (see `hooks`), and commits made with the identity and
+//! signing settings of the user's git config (see `CommitSettings`).
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use directories::UserDirs;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+use super::hooks::{self, WorktreeHook};
+use crate::error::AppError;
+use crate::git_backend::{CommitOptions, Signing};
+use crate::lock::lock_exclusive;
+use crate::routes::projects::AppState;
+use crate::routes::validate_path_security;
+
+/// Placeholder replaced with the bead ID in branch templates.
+const ID_PLACEHOLDER: &str = "{id}";
+
+/// Upper bound on concurrent sibling rebases.
+pub(super) const MAX_REBASE_CONCURRENCY: usize = 32;
+
+/// Shortest allowed interval between background fetches.
+const MIN_FETCH_INTERVAL_SECS: u64 = 60;
+
+/// Branch naming and worktree location for a project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorktreeConfig {
+    /// Branch name for a bead, with `{id}` standing for the bead ID.
+    #[serde(default = "default_branch_template")]
+    pub branch_template: String,
+    /// Directory holding worktrees, relative to the repository root.
+    #[serde(default = "default_worktree_dir")]
+    pub worktree_dir: String,
+    /// Steps run after a worktree is created.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<WorktreeHook>,
+    /// Sibling worktrees rebased at once after a merge.
+    #[serde(default = "default_rebase_concurrency")]
+    pub rebase_concurrency: usize,
+    /// Seconds between background `git fetch --prune` runs (0 disables).
+    #[serde(default = "default_fetch_interval_secs")]
+    pub fetch_interval_secs: u64,
+    /// Identity and signing for commits the server makes.
+    #[serde(default, skip_serializing_if = "CommitSettings::is_empty")]
+    pub commit: CommitSettings,
+}
+
+/// How commits are signed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SigningFormat {
+    Gpg,
+    Ssh,
+}
+
+/// Committer identity and signing for commits made through the server
+/// (commits, merges, rebases, and cherry-picks), overriding the git config.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommitSettings {
+    /// Author and committer name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Author and committer email.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Sign commits with GPG or SSH; unsigned when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing: Option<SigningFormat>,
+    /// GPG key ID (default: gpg's default key), or the path of the SSH key
+    /// to sign with (required for SSH).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+}
+
+impl CommitSettings {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Checks that name and email are set together and usable in a
+    /// signature, and that SSH signing has a key.
+    pub fn validate(&self) -> Result<(), String> {
+        match (&self.name, &self.email) {
+            (Some(name), Some(email)) => {
+                let usable = |s: &str| {
+                    !s.trim().is_empty() && !s.contains(['<', '>', '\n'])
+                };
+                if !usable(name) {
+                    return Err(format!("Invalid committer name: '{}'", name));
+                }
+                if !usable(email) || !email.contains('@') {
+                    return Err(format!("Invalid committer email: '{}'", email));
+                }
+            }
+            (None, None) => {}
+            _ => return Err("Committer name and email must be set together".to_string()),
+        }
+        let key = self.signing_key.as_deref().filter(|k| !k.trim().is_empty());
+        if self.signing == Some(SigningFormat::Ssh) && key.is_none() {
+            return Err("SSH signing requires a signing key".to_string());
+        }
+        Ok(())
+    }
+
+    /// Options for commits made with libgit2.
+    pub fn options(&self) -> CommitOptions {
+        let identity = self.name.clone().zip(self.email.clone());
+        let signing = self.signing.map(|format| match format {
+            SigningFormat::Gpg => Signing::Gpg(self.signing_key.clone()),
+            SigningFormat::Ssh => Signing::Ssh(expand_home(
+                self.signing_key.as_deref().unwrap_or_default(),
+            )),
+        });
+        CommitOptions { identity, signing }
+    }
+
+    /// `-c` options that apply the settings to a `git` command.
+    pub fn git_args(&self) -> Vec<String> {
+        let mut config = Vec::new();
+        if let Some((name, email)) = self.name.as_ref().zip(self.email.as_ref()) {
+            config.push(format!("user.name={}", name));
+            config.push(format!("user.email={}", email));
+        }
+        if let Some(format) = self.signing {
+            config.push("commit.gpgSign=true".to_string());
+            config.push(format!(
+                "gpg.format={}",
+                match format {
+                    SigningFormat::Gpg => "openpgp",
+                    SigningFormat::Ssh => "ssh",
+                }
+            ));
+            if let Some(key) = &self.signing_key {
+                config.push(format!("user.signingKey={}", key));
+            }
+        }
+        config
+            .into_iter()
+            .flat_map(|c| ["-c".to_string(), c])
+            .collect()
+    }
+}
+
+fn default_branch_template() -> String {
+    "bd-{id}".to_string()
+}
+
+fn default_worktree_dir() -> String {
+    ".worktrees".to_string()
+}
+
+fn default_rebase_concurrency() -> usize {
+    4
+}
+
+fn default_fetch_interval_secs() -> u64 {
+    300
+}
+
+impl Default for WorktreeConfig {
+    fn default() -> Self {
+        Self {
+            branch_template: default_branch_template(),
+            worktree_dir: default_worktree_dir(),
+            hooks: Vec::new(),
+            rebase_concurrency: default_rebase_concurrency(),
+            fetch_interval_secs: default_fetch_interval_secs(),
+            commit: CommitSettings::default(),
+        }
+    }
+}
+
+impl WorktreeConfig {
+    /// Loads the configuration for a repository.
+    ///
+    /// Falls back to the defaults when the file is missing, malformed, or
+    /// holds settings that fail validation.
+    pub fn load(repo_path: &Path) -> Self {
+        let path = config_path(repo_path);
+        read_config(&path).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring worktree settings in {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// Checks that the template yields valid branch names and the directory
+    /// (and any paths hooks copy or symlink) stays inside the repository.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.branch_template.matches(ID_PLACEHOLDER).count() != 1 {
+            return Err(format!(
+                "Branch template '{}' must contain {} exactly once",
+                self.branch_template, ID_PLACEHOLDER
+            ));
+        }
+        if !git2::Branch::name_is_valid(&self.branch_name("BD-001")).unwrap_or(false) {
+            return Err(format!(
+                "Branch template '{}' does not produce a valid branch name",
+                self.branch_template
+            ));
+        }
+
+        let dir = Path::new(&self.worktree_dir);
+        let inside_repo = dir
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if self.worktree_dir.trim().is_empty() || !inside_repo {
+            return Err(format!(
+                "Worktree directory '{}' must be a relative path inside the repository",
+                self.worktree_dir
+            ));
+        }
+        if !(1..=MAX_REBASE_CONCURRENCY).contains(&self.rebase_concurrency) {
+            return Err(format!(
+                "Rebase concurrency must be between 1 and {}",
+                MAX_REBASE_CONCURRENCY
+            ));
+        }
+        if (1..MIN_FETCH_INTERVAL_SECS).contains(&self.fetch_interval_secs) {
+            return Err(format!(
+                "Fetch interval must be 0 (disabled) or at least {} seconds",
+                MIN_FETCH_INTERVAL_SECS
+            ));
+        }
+        self.commit.validate()?;
+        self.hooks.iter().try_for_each(WorktreeHook::validate)
+    }
+
+    /// Returns the branch name for a bead.
+    pub fn branch_name(&self, bead_id: &str) -> String {
+        self.branch_template.replace(ID_PLACEHOLDER, bead_id)
+    }
+
+    /// Extracts the bead ID from a branch name produced by the template.
+    pub fn bead_id_from_branch(&self, branch: &str) -> Option<String> {
+        extract_id(&self.branch_template, branch)
+    }
+
+    /// Returns the directory holding the repository's worktrees.
+    pub fn worktrees_dir(&self, repo_path: &Path) -> PathBuf {
+        repo_path.join(&self.worktree_dir)
+    }
+
+    /// Returns the worktree path for a bead.
+    ///
+    /// The directory is named after the branch, with `/` replaced by `-` so
+    /// templates like `feature/{id}` do not nest directories.
+    pub fn worktree_path(&self, repo_path: &Path, bead_id: &str) -> PathBuf {
+        self.worktrees_dir(repo_path)
+            .join(self.branch_name(bead_id).replace('/', "-"))
+    }
+
+    /// Extracts the bead ID from a worktree directory name.
+    pub fn bead_id_from_dir(&self, dir_name: &str) -> Option<String> {
+        extract_id(&self.branch_template.replace('/', "-"), dir_name)
+    }
+
+    /// Returns whether `path` is a worktree directory for some bead.
+    pub fn is_bead_worktree(&self, path: &Path) -> bool {
+        let in_worktree_dir = path
+            .parent()
+            .is_some_and(|p| p.ends_with(&self.worktree_dir));
+        let dir_name = path.file_name().map(|n| n.to_string_lossy().to_string());
+        in_worktree_dir && dir_name.is_some_and(|n| self.bead_id_from_dir(&n).is_some())
+    }
+}
+
+/// Expands a leading `~/` to the home directory, as git does for
+/// `user.signingKey`.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), UserDirs::new()) {
+        (Some(rest), Some(dirs)) => dirs.home_dir().join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Matches `name` against a template and returns the text standing in for
+/// `{id}`.
+fn extract_id(template: &str, name: &str) -> Option<String> {
+    let (prefix, suffix) = template.split_once(ID_PLACEHOLDER)?;
+    name.strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix(suffix))
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+}
+
+/// Returns the path of a repository's `.beads/config.json`.
+pub(super) fn config_path(repo_path: &Path) -> PathBuf {
+    repo_path.join(".beads").join("config.json")
+}
+
+/// Reads `config.json` as a JSON object. A missing file is an empty object.
+pub(super) fn read_config_file(
+    path: &Path,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(serde_json::Map::new()),
+        Err(e) => Err(format!("Failed to read file: {}", e)),
+    }
+}
+
+/// Reads and validates the settings under the `worktree` key.
+fn read_config(path: &Path) -> Result<WorktreeConfig, String> {
+    let config = match read_config_file(path)?.remove("worktree") {
+        Some(value) => serde_json::from_value::<WorktreeConfig>(value)
+            .map_err(|e| format!("Failed to parse worktree settings: {}", e))?,
+        None => WorktreeConfig::default(),
+    };
+    config.validate()?;
+    Ok(config)
+}
+
+/// Stores the settings under the `worktree` key, keeping any other keys in
+/// the file, and writes it back atomically (temp file + rename).
+fn write_config(path: &Path, config: &WorktreeConfig) -> Result<(), String> {
+    let mut file = read_config_file(path)?;
+    let value =
+        serde_json::to_value(config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    file.insert("worktree".to_string(), value);
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json + "\n").map_err(|e| format!("Failed to write file: {}", e))?;
+    std::fs::rename(&tmp_path, path).map_err(|e| format!("Failed to replace file: {}", e))
+}
+
+// ============================================================================
+// Worktree Config Endpoints
+// ============================================================================
+
+/// Query parameters for the worktree config endpoint.
+#[derive(Deserialize)]
+pub struct WorktreeConfigParams {
+    /// Path to the git repository.
+    pub repo_path: String,
+}
+
+/// Request body for updating the worktree config.
+#[derive(Deserialize)]
+pub struct UpdateWorktreeConfigRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// New settings.
+    #[serde(flatten)]
+    pub config: WorktreeConfig,
+}
+
+/// Get the branch template, worktree directory, and post-create hooks for a
+/// repository.
+///
+/// # Endpoint
+///
+/// `GET /api/git/worktree-config?repo_path=...`
+pub async fn get_worktree_config(Query(params): Query<WorktreeConfigParams>) -> impl IntoResponse {
+    let repo_path = PathBuf::from(&params.repo_path);
+
+    // Security: Validate path is within allowed directories
+    if let Err(e) = validate_path_security(&repo_path) {
+        return AppError::Forbidden(e).into_response();
+    }
+
+    Json(WorktreeConfig::load(&repo_path)).into_response()
+}
+
+/// Update the branch template, worktree directory, and post-create hooks
+/// for a repository.
+///
+/// Existing worktrees are left where they are; only worktrees created after
+/// the change use the new settings. Saving `run` hooks here approves them.
+///
+/// # Endpoint
+///
+/// `PUT /api/git/worktree-config`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "branch_template": "feature/{id}",
+///   "worktree_dir": ".worktrees"
+/// }
+/// ```
+pub async fn update_worktree_config(
+    State(db): State<AppState>,
+    Json(request): Json<UpdateWorktreeConfigRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo_path = PathBuf::from(&request.repo_path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&repo_path).map_err(AppError::Forbidden)?;
+
+    request.config.validate().map_err(AppError::BadRequest)?;
+
+    let path = config_path(&repo_path);
+    if !path.parent().is_some_and(|p| p.exists()) {
+        return Err(AppError::NotFound(
+            "No .beads directory found at the specified path".to_string(),
+        ));
+    }
+
+    let _lock = lock_exclusive(&path).await?;
+
+    write_config(&path, &request.config).map_err(AppError::Internal)?;
+    // The user wrote these hooks, so they may run
+    hooks::trust(&db, &repo_path, &request.config.hooks)?;
+
+    Ok(Json(
+        serde_json::json!({ "success": true, "config": request.config }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_branch_names() {
+        let config = WorktreeConfig {
+            branch_template: "feature/{id}-work".to_string(),
+            worktree_dir: "tmp/trees".to_string(),
+            ..WorktreeConfig::default()
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(config.branch_name("BD-1"), "feature/BD-1-work");
+        assert_eq!(
+            config.bead_id_from_branch("feature/BD-1-work"),
+            Some("BD-1".to_string())
+        );
+        assert_eq!(config.bead_id_from_branch("main"), None);
+
+        let path = config.worktree_path(Path::new("/repo"), "BD-1");
+        assert_eq!(path, PathBuf::from("/repo/tmp/trees/feature-BD-1-work"));
+        assert!(config.is_bead_worktree(&path));
+        assert!(!config.is_bead_worktree(Path::new("/repo/tmp/trees/other")));
+        assert_eq!(
+            config.bead_id_from_dir("feature-BD-1-work"),
+            Some("BD-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let with = |template: &str, dir: &str| WorktreeConfig {
+            branch_template: template.to_string(),
+            worktree_dir: dir.to_string(),
+            ..WorktreeConfig::default()
+        };
+        assert!(WorktreeConfig::default().validate().is_ok());
+        assert!(with("bd-", ".worktrees").validate().is_err());
+        assert!(with("{id}-{id}", ".worktrees").validate().is_err());
+        assert!(with("bd {id}", ".worktrees").validate().is_err());
+        assert!(with("bd-{id}", "../elsewhere").validate().is_err());
+        assert!(with("bd-{id}", "/tmp").validate().is_err());
+        assert!(with("bd-{id}", "").validate().is_err());
+
+        let config = WorktreeConfig {
+            hooks: vec![WorktreeHook::Copy("../.env".to_string())],
+            ..WorktreeConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = WorktreeConfig {
+            rebase_concurrency: 0,
+            ..WorktreeConfig::default()
+        };
+        assert!(config.validate().is_err());
+
+        let fetch_every = |secs: u64| WorktreeConfig {
+            fetch_interval_secs: secs,
+            ..WorktreeConfig::default()
+        };
+        assert!(fetch_every(0).validate().is_ok());
+        assert!(fetch_every(10).validate().is_err());
+        assert!(fetch_every(MIN_FETCH_INTERVAL_SECS).validate().is_ok());
+
+        let commit = |name: Option<&str>, email: Option<&str>, signing| WorktreeConfig {
+            commit: CommitSettings {
+                name: name.map(String::from),
+                email: email.map(String::from),
+                signing,
+                signing_key: None,
+            },
+            ..WorktreeConfig::default()
+        };
+        assert!(commit(Some("Bot"), Some("bot@example.com"), None).validate().is_ok());
+        assert!(commit(Some("Bot"), None, None).validate().is_err());
+        assert!(commit(Some("Bot"), Some("not-an-email"), None).validate().is_err());
+        assert!(commit(Some("<Bot>"), Some("bot@example.com"), None).validate().is_err());
+        assert!(commit(None, None, Some(SigningFormat::Gpg)).validate().is_ok());
+        assert!(commit(None, None, Some(SigningFormat::Ssh)).validate().is_err());
+    }
+
+    #[test]
+    fn test_commit_settings() {
+        assert!(CommitSettings::default().git_args().is_empty());
+        assert_eq!(CommitSettings::default().options(), CommitOptions::default());
+
+        let settings: CommitSettings = serde_json::from_str(
+            r#"{"name":"Bot","email":"bot@example.com","signing":"ssh","signing_key":"~/.ssh/id_ed25519.pub"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            settings.git_args(),
+            [
+                "-c",
+                "user.name=Bot",
+                "-c",
+                "user.email=bot@example.com",
+                "-c",
+                "commit.gpgSign=true",
+                "-c",
+                "gpg.format=ssh",
+                "-c",
+                "user.signingKey=~/.ssh/id_ed25519.pub",
+            ]
+        );
+        let options = settings.options();
+        assert_eq!(
+            options.identity,
+            Some(("Bot".to_string(), "bot@example.com".to_string()))
+        );
+        let Some(Signing::Ssh(key)) = options.signing else {
+            panic!("expected SSH signing");
+        };
+        assert!(key.ends_with(".ssh/id_ed25519.pub"));
+        assert!(!key.starts_with("~"));
+    }
+
+    #[test]
+    fn test_load_and_write() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(WorktreeConfig::load(dir.path()), WorktreeConfig::default());
+
+        std::fs::create_dir(dir.path().join(".beads")).unwrap();
+        let path = config_path(dir.path());
+        std::fs::write(
+            &path,
+            r#"{"other":1,"worktree":{"branch_template":"wip/{id}"}}"#,
+        )
+        .unwrap();
+        let config = WorktreeConfig::load(dir.path());
+        assert_eq!(config.branch_template, "wip/{id}");
+        assert_eq!(config.worktree_dir, ".worktrees");
+
+        let updated = WorktreeConfig {
+            worktree_dir: "trees".to_string(),
+            ..config
+        };
+        write_config(&path, &updated).unwrap();
+        assert_eq!(WorktreeConfig::load(dir.path()), updated);
+        assert_eq!(read_config_file(&path).unwrap()["other"], 1);
+
+        std::fs::write(
+            &path,
+            r#"{"worktree":{"branch_template":"no-placeholder"}}"#,
+        )
+        .unwrap();
+        assert_eq!(WorktreeConfig::load(dir.path()), WorktreeConfig::default());
+    }
+}
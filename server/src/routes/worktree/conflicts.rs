@@ -0,0 +1,356 @@
+//! Conflict resolution for worktrees stuck mid-rebase or mid-merge.
+//!
+//! Lists conflicted files, shows both sides of each, resolves a file by
+//! taking one side, and continues or aborts the operation. "Ours" and
+//! "theirs" follow git: during a rebase "ours" is the upstream being
+//! rebased onto and "theirs" is the bead's commit being replayed.
+
+use axum::{extract::Query, response::IntoResponse, Json};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+use super::{is_relative_subpath, WorktreeConfig};
+use crate::error::AppError;
+use crate::git_backend::{self, ConflictSides};
+use crate::logging::LogCommand;
+
+/// Query parameters for listing conflicts.
+#[derive(Deserialize)]
+pub struct ConflictsParams {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose worktree to inspect.
+    pub bead_id: String,
+}
+
+/// Response body for listing conflicts (also returned after continuing).
+#[derive(Serialize)]
+pub struct ConflictsResponse {
+    /// Operation in progress: "rebase", "merge", "cherry-pick", or "revert".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    /// Paths that still have conflicts.
+    pub files: Vec<String>,
+}
+
+/// Query parameters for a single conflicted file.
+#[derive(Deserialize)]
+pub struct ConflictFileParams {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose worktree to inspect.
+    pub bead_id: String,
+    /// File path relative to the worktree.
+    pub path: String,
+}
+
+/// Response body for a single conflicted file.
+#[derive(Serialize)]
+pub struct ConflictFileResponse {
+    /// File path relative to the worktree.
+    pub path: String,
+    /// Common ancestor version (absent if the file did not exist).
+    pub ancestor: Option<String>,
+    /// Version on the branch being merged into.
+    pub ours: Option<String>,
+    /// Version being merged in.
+    pub theirs: Option<String>,
+    /// Working tree contents, with conflict markers around each hunk.
+    pub merged: Option<String>,
+}
+
+/// Request body for resolving a conflicted file.
+#[derive(Deserialize)]
+pub struct ResolveConflictRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose worktree to resolve in.
+    pub bead_id: String,
+    /// File path relative to the worktree.
+    pub path: String,
+    /// Side to keep: "ours" or "theirs".
+    pub side: String,
+}
+
+/// Request body for continuing or aborting the operation in progress.
+#[derive(Deserialize)]
+pub struct ConflictOperationRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose worktree to act on.
+    pub bead_id: String,
+}
+
+/// Response body for continuing or aborting.
+#[derive(Serialize)]
+pub struct ConflictOperationResponse {
+    /// Whether git completed the step.
+    pub success: bool,
+    /// Operation still in progress afterwards (e.g. the rebase stopped at
+    /// the next conflicting commit).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    /// Paths that have conflicts afterwards.
+    pub conflicts: Vec<String>,
+    /// Error message if failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Resolve a bead's worktree path, checking it exists.
+fn bead_worktree(repo_path: &str, bead_id: &str) -> Result<PathBuf, AppError> {
+    let repo_path = Path::new(repo_path);
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            repo_path.display()
+        )));
+    }
+
+    let worktree_path = WorktreeConfig::load(repo_path).worktree_path(repo_path, bead_id);
+    if !worktree_path.exists() {
+        return Err(AppError::NotFound(format!(
+            "No worktree found for bead {}",
+            bead_id
+        )));
+    }
+    Ok(worktree_path)
+}
+
+/// Read the operation in progress and the remaining conflicts.
+async fn conflict_state(worktree_path: &Path) -> Result<(Option<String>, Vec<String>), String> {
+    let path = worktree_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let operation = git_backend::operation_in_progress(&path)?.map(String::from);
+        let files = git_backend::conflicted_files(&path)?;
+        Ok::<_, git2::Error>((operation, files))
+    })
+    .await
+    .map_err(|e| format!("Failed to read conflicts: {}", e))?
+    .map_err(|e| format!("Failed to read conflicts: {}", e.message()))
+}
+
+/// List the conflicted files in a bead's worktree.
+///
+/// # Endpoint
+///
+/// `GET /api/git/conflicts?repo_path=...&bead_id=...`
+///
+/// # Response
+///
+/// Returns the operation in progress (if any) and the conflicted paths.
+pub async fn list_conflicts(Query(params): Query<ConflictsParams>) -> impl IntoResponse {
+    let worktree_path = match bead_worktree(&params.repo_path, &params.bead_id) {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+
+    match conflict_state(&worktree_path).await {
+        Ok((operation, files)) => Json(ConflictsResponse { operation, files }).into_response(),
+        Err(e) => AppError::Internal(e).into_response(),
+    }
+}
+
+/// Get both sides and the merged contents of a conflicted file.
+///
+/// # Endpoint
+///
+/// `GET /api/git/conflicts/file?repo_path=...&bead_id=...&path=...`
+pub async fn conflict_file(Query(params): Query<ConflictFileParams>) -> impl IntoResponse {
+    let worktree_path = match bead_worktree(&params.repo_path, &params.bead_id) {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+    if !is_relative_subpath(&params.path) {
+        return AppError::BadRequest(format!(
+            "Path must be relative to the worktree: {}",
+            params.path
+        ))
+        .into_response();
+    }
+
+    let (path, file) = (worktree_path.clone(), params.path.clone());
+    let result =
+        tokio::task::spawn_blocking(move || git_backend::conflict_sides(&path, &file)).await;
+
+    match result {
+        Ok(Ok(Some(ConflictSides {
+            ancestor,
+            ours,
+            theirs,
+        }))) => {
+            let merged = std::fs::read(worktree_path.join(&params.path))
+                .ok()
+                .map(|bytes| String::from_utf8_lossy(&bytes).to_string());
+            Json(ConflictFileResponse {
+                path: params.path,
+                ancestor,
+                ours,
+                theirs,
+                merged,
+            })
+            .into_response()
+        }
+        Ok(Ok(None)) => {
+            AppError::NotFound(format!("File is not conflicted: {}", params.path)).into_response()
+        }
+        Ok(Err(e)) => {
+            AppError::Internal(format!("Failed to read conflict: {}", e.message())).into_response()
+        }
+        Err(e) => AppError::Internal(format!("Failed to read conflict: {}", e)).into_response(),
+    }
+}
+
+/// Resolve a conflicted file by keeping one side, and stage it.
+///
+/// # Endpoint
+///
+/// `POST /api/git/conflicts/resolve`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "bead_id": "BD-001",
+///   "path": "src/app.tsx",
+///   "side": "theirs"
+/// }
+/// ```
+///
+/// # Response
+///
+/// Returns the conflicts that remain.
+pub async fn resolve_conflict(Json(request): Json<ResolveConflictRequest>) -> impl IntoResponse {
+    let worktree_path = match bead_worktree(&request.repo_path, &request.bead_id) {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+    if !is_relative_subpath(&request.path) {
+        return AppError::BadRequest(format!(
+            "Path must be relative to the worktree: {}",
+            request.path
+        ))
+        .into_response();
+    }
+    let ours = match request.side.as_str() {
+        "ours" => true,
+        "theirs" => false,
+        other => {
+            return AppError::BadRequest(format!(
+                "Side must be \"ours\" or \"theirs\", got \"{}\"",
+                other
+            ))
+            .into_response();
+        }
+    };
+
+    let (path, file) = (worktree_path.clone(), request.path.clone());
+    let result =
+        tokio::task::spawn_blocking(move || git_backend::resolve_conflict(&path, &file, ours))
+            .await;
+
+    match result {
+        Ok(Ok(true)) => {}
+        Ok(Ok(false)) => {
+            return AppError::NotFound(format!("File is not conflicted: {}", request.path))
+                .into_response();
+        }
+        Ok(Err(e)) => {
+            return AppError::Internal(format!("Failed to resolve conflict: {}", e.message()))
+                .into_response();
+        }
+        Err(e) => {
+            return AppError::Internal(format!("Failed to resolve conflict: {}", e))
+                .into_response();
+        }
+    }
+
+    match conflict_state(&worktree_path).await {
+        Ok((operation, files)) => Json(ConflictsResponse { operation, files }).into_response(),
+        Err(e) => AppError::Internal(e).into_response(),
+    }
+}
+
+/// Continue the rebase (or merge) in progress once conflicts are resolved.
+///
+/// # Endpoint
+///
+/// `POST /api/git/conflicts/continue`
+///
+/// # Response
+///
+/// A rebase may stop again at a later commit; the response then lists the
+/// new conflicts with the rebase still in progress.
+pub async fn continue_operation(
+    Json(request): Json<ConflictOperationRequest>,
+) -> impl IntoResponse {
+    run_operation_step(&request, "--continue").await
+}
+
+/// Abort the rebase (or merge) in progress, restoring the branch.
+///
+/// # Endpoint
+///
+/// `POST /api/git/conflicts/abort`
+pub async fn abort_operation(Json(request): Json<ConflictOperationRequest>) -> impl IntoResponse {
+    run_operation_step(&request, "--abort").await
+}
+
+/// Run `git <operation> <flag>` in a bead's worktree.
+async fn run_operation_step(
+    request: &ConflictOperationRequest,
+    flag: &str,
+) -> axum::response::Response {
+    let worktree_path = match bead_worktree(&request.repo_path, &request.bead_id) {
+        Ok(p) => p,
+        Err(e) => return e.into_response(),
+    };
+
+    let operation = match conflict_state(&worktree_path).await {
+        Ok((Some(operation), _)) => operation,
+        Ok((None, _)) => {
+            return AppError::Conflict("No rebase or merge in progress".to_string())
+                .into_response();
+        }
+        Err(e) => {
+            return AppError::Internal(e).into_response();
+        }
+    };
+
+    // Keep the prepared commit messages instead of opening an editor
+    let git_config = WorktreeConfig::load(Path::new(&request.repo_path))
+        .commit
+        .git_args();
+    let output = Command::new("git")
+        .args(git_config)
+        .args([operation.as_str(), flag])
+        .env("GIT_EDITOR", "true")
+        .current_dir(&worktree_path)
+        .logged()
+        .output()
+        .await;
+
+    let error = match output {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(format!(
+            "git {} {} failed: {}",
+            operation,
+            flag,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(e) => Some(format!("Failed to run git command: {}", e)),
+    };
+
+    match conflict_state(&worktree_path).await {
+        Ok((operation, conflicts)) => Json(ConflictOperationResponse {
+            success: error.is_none(),
+            operation,
+            conflicts,
+            error,
+        })
+        .into_response(),
+        Err(e) => AppError::Internal(error.unwrap_or(e)).into_response(),
+    }
+}
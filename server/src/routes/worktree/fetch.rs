@@ -0,0 +1,243 @@
+//! Background `git fetch`.
+//!
+//! Ahead/behind counts and pushed-branch checks compare against remote
+//! tracking refs, which only move when something fetches. A server task
+//! runs `git fetch --prune` for each registered project every
+//! `worktree.fetch_interval_secs` (0 disables it), and `POST /api/git/fetch`
+//! triggers a fetch on demand. After each fetch the cached PR statuses of
+//! the project are dropped and a `git-fetch` event is published so boards
+//! can refresh their branch statuses.
+
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use super::{check_has_remote, config::WorktreeConfig, poller};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::logging::LogCommand;
+use crate::shutdown;
+
+/// How often the scheduler looks for projects that are due.
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Capacity of the in-process fetch channel.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// A completed fetch, as sent to SSE clients.
+#[derive(Clone, Serialize)]
+pub struct FetchEvent {
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// A completed fetch published for a repository.
+#[derive(Clone)]
+pub struct PublishedFetch {
+    pub repo_path: PathBuf,
+    pub event: FetchEvent,
+}
+
+/// When each repository was last fetched (successfully or not).
+fn last_fetch() -> &'static Mutex<HashMap<PathBuf, Instant>> {
+    static LAST_FETCH: OnceLock<Mutex<HashMap<PathBuf, Instant>>> = OnceLock::new();
+    LAST_FETCH.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Repositories with a fetch in progress.
+fn in_flight() -> &'static Mutex<HashSet<PathBuf>> {
+    static IN_FLIGHT: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
+    IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+fn channel() -> &'static broadcast::Sender<PublishedFetch> {
+    static CHANNEL: OnceLock<broadcast::Sender<PublishedFetch>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Subscribes to fetches completed by this server.
+pub fn subscribe() -> broadcast::Receiver<PublishedFetch> {
+    channel().subscribe()
+}
+
+/// Number of fetches in progress.
+pub fn in_progress() -> usize {
+    in_flight().lock().unwrap().len()
+}
+
+/// Whether a repository is due for a background fetch.
+fn is_due(repo_path: &Path, interval: Duration) -> bool {
+    !interval.is_zero()
+        && last_fetch()
+            .lock()
+            .unwrap()
+            .get(repo_path)
+            .is_none_or(|at| at.elapsed() >= interval)
+}
+
+/// Runs `git fetch --prune` in a repository, then drops its cached PR
+/// statuses and publishes the fetch.
+///
+/// Returns `Ok(None)` if the repository has no remote to fetch from.
+async fn fetch_repo(repo_path: &Path) -> Result<Option<DateTime<Utc>>, String> {
+    if !in_flight().lock().unwrap().insert(repo_path.to_path_buf()) {
+        return Err("A fetch is already running for this repository".to_string());
+    }
+    let result = run_fetch(repo_path).await;
+    in_flight().lock().unwrap().remove(repo_path);
+    last_fetch()
+        .lock()
+        .unwrap()
+        .insert(repo_path.to_path_buf(), Instant::now());
+
+    let fetched_at = result?;
+    if let Some(fetched_at) = fetched_at {
+        poller::invalidate_repo(repo_path);
+        let _ = channel().send(PublishedFetch {
+            repo_path: repo_path.to_path_buf(),
+            event: FetchEvent { fetched_at },
+        });
+    }
+    Ok(fetched_at)
+}
+
+async fn run_fetch(repo_path: &Path) -> Result<Option<DateTime<Utc>>, String> {
+    if !check_has_remote(&repo_path.to_string_lossy()).await {
+        return Ok(None);
+    }
+
+    let output = Command::new("git")
+        .args(["fetch", "--prune"])
+        // Never block on a credentials prompt
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git fetch: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("git fetch failed: {}", stderr.trim()));
+    }
+    Ok(Some(Utc::now()))
+}
+
+/// Fetches registered projects as their fetch interval elapses. Runs until
+/// the server shuts down.
+pub async fn run_fetch_scheduler(db: Arc<Database>) {
+    let mut ticker = tokio::time::interval(TICK_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown::signalled() => break,
+        }
+
+        let projects = match db.get_projects() {
+            Ok(projects) => projects,
+            Err(e) => {
+                warn!("Fetch scheduler failed to list projects: {}", e);
+                continue;
+            }
+        };
+
+        for project in projects {
+            let repo_path = PathBuf::from(&project.path);
+            if !repo_path.exists() {
+                continue;
+            }
+            let interval = Duration::from_secs(WorktreeConfig::load(&repo_path).fetch_interval_secs);
+            if !is_due(&repo_path, interval) {
+                continue;
+            }
+            if let Err(e) = fetch_repo(&repo_path).await {
+                warn!("Background fetch of {} failed: {}", project.path, e);
+            }
+        }
+    }
+}
+
+/// Request body for the fetch endpoint.
+#[derive(Deserialize)]
+pub struct FetchRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+}
+
+/// Response body for the fetch endpoint.
+#[derive(Serialize)]
+pub struct FetchResponse {
+    /// Whether the operation was successful.
+    pub success: bool,
+    /// When the fetch finished; absent if the repository has no remote.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+/// Fetch a repository now instead of waiting for the background fetch.
+///
+/// # Endpoint
+///
+/// `POST /api/git/fetch`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo"
+/// }
+/// ```
+pub async fn fetch(Json(request): Json<FetchRequest>) -> Result<Json<FetchResponse>, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
+    }
+
+    let fetched_at = fetch_repo(repo_path).await.map_err(AppError::Upstream)?;
+    Ok(Json(FetchResponse {
+        success: true,
+        fetched_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_due() {
+        let repo_path = Path::new("/tmp/fetch-test-repo");
+        assert!(!is_due(repo_path, Duration::ZERO));
+        assert!(is_due(repo_path, Duration::from_secs(60)));
+
+        last_fetch()
+            .lock()
+            .unwrap()
+            .insert(repo_path.to_path_buf(), Instant::now());
+        assert!(!is_due(repo_path, Duration::from_secs(60)));
+        assert!(is_due(repo_path, Duration::from_nanos(1)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_repo_without_remote() {
+        let dir = tempfile::tempdir().unwrap();
+        git2::Repository::init(dir.path()).unwrap();
+        let mut events = subscribe();
+
+        assert_eq!(fetch_repo(dir.path()).await, Ok(None));
+        assert!(!is_due(dir.path(), Duration::from_secs(60)));
+        // Nothing was fetched, so nothing is published
+        assert!(events.try_recv().is_err());
+    }
+}
@@ -0,0 +1,687 @@
+//! Post-create worktree hooks.
+//!
+//! Hooks are configured per project under `worktree.hooks` in
+//! `.beads/config.json` and run in order, in the background, after
+//! `create_worktree` adds a new worktree:
+//!
+//! ```json
+//! { "worktree": { "hooks": [
+//!     { "copy": ".env" },
+//!     { "symlink": "node_modules" },
+//!     { "run": "npm install" }
+//! ] } }
+//! ```
+//!
+//! `copy` and `symlink` take a path relative to the repository root and
+//! recreate it at the same place in the worktree; `run` runs a shell
+//! command in the worktree. The first failing hook stops the run.
+//!
+//! `.beads/` is usually committed, so a cloned repository could ship `run`
+//! hooks. None of a project's hooks run until the user has approved its
+//! `run` commands, which is recorded per repository against a hash of the
+//! commands; changing them needs a new approval. Hooks saved through the
+//! config endpoint are approved by saving them. Progress
+//! is published in-process so the watch SSE stream can push it to boards as
+//! `worktree-hook` events, and the latest run per bead is kept for the
+//! status endpoint.
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Command;
+use tokio::sync::broadcast;
+
+use super::config::WorktreeConfig;
+use super::is_relative_subpath;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::logging::LogCommand;
+use crate::routes::projects::AppState;
+use crate::routes::validate_path_security;
+
+/// Output lines kept per hook for the status endpoint.
+const MAX_OUTPUT_LINES: usize = 200;
+
+/// Capacity of the in-process progress channel.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// A step run after a worktree is created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeHook {
+    /// Run a shell command in the worktree.
+    Run(String),
+    /// Copy a file or directory from the repository into the worktree.
+    Copy(String),
+    /// Symlink a file or directory of the repository into the worktree.
+    Symlink(String),
+}
+
+impl WorktreeHook {
+    /// Checks that copied and symlinked paths stay inside the repository.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            WorktreeHook::Run(command) if command.trim().is_empty() => {
+                Err("Hook command must not be empty".to_string())
+            }
+            WorktreeHook::Copy(path) | WorktreeHook::Symlink(path)
+                if !is_relative_subpath(path) =>
+            {
+                Err(format!(
+                    "Hook path '{}' must be a relative path inside the repository",
+                    path
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `run` hooks held back until the user approves them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UntrustedHooks {
+    /// Hash of the commands, to send back when approving them.
+    pub hash: String,
+    pub commands: Vec<String>,
+}
+
+/// The `run` commands of a list of hooks, with their hash; `None` when
+/// there are none, as copying and symlinking need no approval.
+fn run_commands(hooks: &[WorktreeHook]) -> Option<UntrustedHooks> {
+    let commands: Vec<String> = hooks
+        .iter()
+        .filter_map(|hook| match hook {
+            WorktreeHook::Run(command) => Some(command.clone()),
+            _ => None,
+        })
+        .collect();
+    if commands.is_empty() {
+        return None;
+    }
+
+    let mut hasher = Sha256::new();
+    for command in &commands {
+        hasher.update(command.as_bytes());
+        hasher.update([0]);
+    }
+    let hash = hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Some(UntrustedHooks { hash, commands })
+}
+
+/// Returns the `run` commands of `hooks` unless the user approved them for
+/// the repository.
+fn untrusted(db: &Database, repo_path: &Path, hooks: &[WorktreeHook]) -> Option<UntrustedHooks> {
+    let commands = run_commands(hooks)?;
+    let trusted = db
+        .get_trusted_hooks(&repo_path.to_string_lossy())
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to read approved hooks: {}", e);
+            None
+        });
+    (trusted.as_deref() != Some(commands.hash.as_str())).then_some(commands)
+}
+
+/// Approves the `run` commands of `hooks` for the repository.
+pub fn trust(db: &Database, repo_path: &Path, hooks: &[WorktreeHook]) -> Result<(), AppError> {
+    match run_commands(hooks) {
+        Some(commands) => db
+            .trust_hooks(&repo_path.to_string_lossy(), &commands.hash)
+            .map_err(AppError::from),
+        None => Ok(()),
+    }
+}
+
+/// Progress of one hook.
+#[derive(Clone, Serialize)]
+pub struct HookStep {
+    pub hook: WorktreeHook,
+    /// "pending", "running", "succeeded", "failed", or "skipped".
+    pub status: String,
+    /// Last lines of output.
+    pub output: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The hooks run for a bead's worktree.
+#[derive(Clone, Serialize)]
+pub struct HookRun {
+    /// "running", "succeeded", or "failed".
+    pub status: String,
+    pub steps: Vec<HookStep>,
+    pub started_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+}
+
+/// A progress update, as sent to SSE clients.
+#[derive(Clone, Serialize)]
+pub struct HookProgressEvent {
+    pub bead_id: String,
+    /// Index of the hook the update is about.
+    pub step: usize,
+    /// Status of the hook.
+    pub status: String,
+    /// A line of output, for output updates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<String>,
+    /// Status of the whole run.
+    pub run_status: String,
+}
+
+/// A progress update published for a repository.
+#[derive(Clone)]
+pub struct PublishedHookProgress {
+    pub repo_path: PathBuf,
+    pub event: HookProgressEvent,
+}
+
+fn runs() -> &'static Mutex<HashMap<(PathBuf, String), HookRun>> {
+    static RUNS: OnceLock<Mutex<HashMap<(PathBuf, String), HookRun>>> = OnceLock::new();
+    RUNS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn channel() -> &'static broadcast::Sender<PublishedHookProgress> {
+    static CHANNEL: OnceLock<broadcast::Sender<PublishedHookProgress>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Subscribes to hook progress published by this server.
+pub fn subscribe() -> broadcast::Receiver<PublishedHookProgress> {
+    channel().subscribe()
+}
+
+/// Number of hook runs still in progress.
+pub fn running() -> usize {
+    runs()
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|run| run.status == "running")
+        .count()
+}
+
+/// Starts running hooks for a new worktree in the background. Returns
+/// whether there were any to run, and the `run` commands that held them
+/// back if the user has not approved them.
+pub fn start(
+    db: &Database,
+    repo_path: &Path,
+    bead_id: &str,
+    worktree_path: &Path,
+    hooks: Vec<WorktreeHook>,
+) -> (bool, Option<UntrustedHooks>) {
+    if hooks.is_empty() {
+        return (false, None);
+    }
+    if let Some(untrusted) = untrusted(db, repo_path, &hooks) {
+        tracing::warn!(
+            "Not running worktree hooks for {} in {}: their commands are not approved",
+            bead_id,
+            repo_path.display()
+        );
+        return (false, Some(untrusted));
+    }
+
+    runs()
+        .lock()
+        .unwrap()
+        .insert((repo_path.to_path_buf(), bead_id.to_string()), new_run(&hooks));
+
+    let runner = Runner {
+        repo_path: repo_path.to_path_buf(),
+        bead_id: bead_id.to_string(),
+    };
+    let worktree_path = worktree_path.to_path_buf();
+    tokio::spawn(async move { runner.run(&worktree_path, hooks).await });
+    (true, None)
+}
+
+/// A run that has yet to start its hooks.
+fn new_run(hooks: &[WorktreeHook]) -> HookRun {
+    HookRun {
+        status: "running".to_string(),
+        steps: hooks
+            .iter()
+            .map(|hook| HookStep {
+                hook: hook.clone(),
+                status: "pending".to_string(),
+                output: Vec::new(),
+                error: None,
+            })
+            .collect(),
+        started_at: Utc::now().to_rfc3339(),
+        finished_at: None,
+    }
+}
+
+/// Runs the hooks of one worktree, recording and publishing progress.
+struct Runner {
+    repo_path: PathBuf,
+    bead_id: String,
+}
+
+impl Runner {
+    async fn run(&self, worktree_path: &Path, hooks: Vec<WorktreeHook>) {
+        for (step, hook) in hooks.iter().enumerate() {
+            self.update(step, |s| s.status = "running".to_string(), None);
+
+            let result = match hook {
+                WorktreeHook::Run(command) => self.run_command(step, worktree_path, command).await,
+                WorktreeHook::Copy(path) => {
+                    copy_path(&self.repo_path.join(path), &worktree_path.join(path))
+                }
+                WorktreeHook::Symlink(path) => {
+                    symlink_path(&self.repo_path.join(path), &worktree_path.join(path))
+                }
+            };
+
+            if let Err(e) = result {
+                tracing::warn!("Worktree hook for {} failed: {}", self.bead_id, e);
+                self.update(
+                    step,
+                    |s| {
+                        s.status = "failed".to_string();
+                        s.error = Some(e);
+                    },
+                    None,
+                );
+                self.finish("failed");
+                return;
+            }
+            self.update(step, |s| s.status = "succeeded".to_string(), None);
+        }
+        self.finish("succeeded");
+    }
+
+    /// Runs a shell command, publishing its output line by line.
+    async fn run_command(&self, step: usize, worktree_path: &Path, command: &str) -> Result<(), String> {
+        let mut child = shell(command)
+            .current_dir(worktree_path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .logged()
+            .spawn()
+            .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        tokio::join!(self.forward(step, stdout), self.forward(step, stderr));
+
+        let status = child
+            .wait()
+            .await
+            .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("'{}' exited with {}", command, status))
+        }
+    }
+
+    /// Records and publishes each line read from a command's output.
+    async fn forward(&self, step: usize, output: Option<impl AsyncRead + Unpin>) {
+        let Some(output) = output else {
+            return;
+        };
+        let mut lines = BufReader::new(output).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            self.update(
+                step,
+                |s| {
+                    if s.output.len() == MAX_OUTPUT_LINES {
+                        s.output.remove(0);
+                    }
+                    s.output.push(line.clone());
+                },
+                Some(line.clone()),
+            );
+        }
+    }
+
+    /// Applies a change to a step and publishes its new status.
+    fn update(&self, step: usize, change: impl FnOnce(&mut HookStep), line: Option<String>) {
+        let mut runs = runs().lock().unwrap();
+        let Some(run) = runs.get_mut(&(self.repo_path.clone(), self.bead_id.clone())) else {
+            return;
+        };
+        let Some(hook_step) = run.steps.get_mut(step) else {
+            return;
+        };
+        change(hook_step);
+
+        let event = HookProgressEvent {
+            bead_id: self.bead_id.clone(),
+            step,
+            status: hook_step.status.clone(),
+            line,
+            run_status: run.status.clone(),
+        };
+        self.publish(event);
+    }
+
+    /// Marks the run finished, skipping hooks that never ran.
+    fn finish(&self, status: &str) {
+        let mut runs = runs().lock().unwrap();
+        let Some(run) = runs.get_mut(&(self.repo_path.clone(), self.bead_id.clone())) else {
+            return;
+        };
+        for step in run.steps.iter_mut().filter(|s| s.status == "pending") {
+            step.status = "skipped".to_string();
+        }
+        run.status = status.to_string();
+        run.finished_at = Some(Utc::now().to_rfc3339());
+
+        let last = run.steps.len().saturating_sub(1);
+        let step = run
+            .steps
+            .iter()
+            .position(|s| s.status == "failed")
+            .unwrap_or(last);
+        let event = HookProgressEvent {
+            bead_id: self.bead_id.clone(),
+            step,
+            status: run.steps[step].status.clone(),
+            line: None,
+            run_status: run.status.clone(),
+        };
+        self.publish(event);
+    }
+
+    fn publish(&self, event: HookProgressEvent) {
+        let _ = channel().send(PublishedHookProgress {
+            repo_path: self.repo_path.clone(),
+            event,
+        });
+    }
+}
+
+/// Builds a command that runs `command` through the platform shell.
+fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Copies a file or directory tree, creating parent directories.
+fn copy_path(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    if from.is_dir() {
+        std::fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+        let entries = std::fs::read_dir(from)
+            .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(from, to)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+    }
+}
+
+/// Symlinks `to` to `from`, creating parent directories.
+fn symlink_path(from: &Path, to: &Path) -> Result<(), String> {
+    if !from.exists() {
+        return Err(format!("{} does not exist", from.display()));
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    #[cfg(unix)]
+    let result = std::os::unix::fs::symlink(from, to);
+    #[cfg(windows)]
+    let result = if from.is_dir() {
+        std::os::windows::fs::symlink_dir(from, to)
+    } else {
+        std::os::windows::fs::symlink_file(from, to)
+    };
+    result.map_err(|e| format!("Failed to symlink {}: {}", to.display(), e))
+}
+
+// ============================================================================
+// Worktree Hooks Status Endpoint
+// ============================================================================
+
+/// Query parameters for the hook status endpoint.
+#[derive(Deserialize)]
+pub struct HookStatusParams {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Bead ID whose worktree hooks to report.
+    pub bead_id: String,
+}
+
+/// Get the progress of the hooks run for a bead's worktree.
+///
+/// # Endpoint
+///
+/// `GET /api/git/worktree-hooks?repo_path=...&bead_id=...`
+///
+/// # Response
+///
+/// The latest run, with each hook's status and last lines of output. 404
+/// when no hooks have run for the bead since the server started.
+pub async fn hook_status(Query(params): Query<HookStatusParams>) -> impl IntoResponse {
+    let key = (PathBuf::from(&params.repo_path), params.bead_id.clone());
+    match runs().lock().unwrap().get(&key) {
+        Some(run) => Json(run.clone()).into_response(),
+        None => AppError::NotFound(format!("No worktree hooks have run for {}", params.bead_id))
+            .into_response(),
+    }
+}
+
+/// Request body for approving a repository's hook commands.
+#[derive(Deserialize)]
+pub struct TrustHooksRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Hash of the commands being approved, as reported by
+    /// `create_worktree`.
+    pub hash: String,
+    /// Bead whose new worktree should run its held-back hooks now.
+    #[serde(default)]
+    pub bead_id: Option<String>,
+}
+
+/// Approve the `run` hooks of a repository so they run for new worktrees.
+///
+/// # Endpoint
+///
+/// `POST /api/git/worktree-hooks/trust`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "hash": "3f2a...",
+///   "bead_id": "BD-001"
+/// }
+/// ```
+///
+/// # Response
+///
+/// 409 if the configured commands no longer match `hash`, so only commands
+/// the user has seen get approved. With `bead_id`, the hooks start for that
+/// bead's existing worktree.
+pub async fn trust_hooks(
+    State(db): State<AppState>,
+    Json(request): Json<TrustHooksRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    let repo_path = PathBuf::from(&request.repo_path);
+
+    // Security: Validate path is within allowed directories
+    validate_path_security(&repo_path).map_err(AppError::Forbidden)?;
+
+    let config = WorktreeConfig::load(&repo_path);
+    if run_commands(&config.hooks).map(|c| c.hash) != Some(request.hash) {
+        return Err(AppError::Conflict(
+            "The worktree hook commands have changed; review them again".to_string(),
+        ));
+    }
+    trust(&db, &repo_path, &config.hooks)?;
+
+    let hooks_started = match &request.bead_id {
+        Some(bead_id) => {
+            let worktree_path = config.worktree_path(&repo_path, bead_id);
+            worktree_path.exists() && start(&db, &repo_path, bead_id, &worktree_path, config.hooks).0
+        }
+        None => false,
+    };
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "hooks_started": hooks_started
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_serialization_and_validation() {
+        let hooks: Vec<WorktreeHook> = serde_json::from_str(
+            r#"[{"copy": ".env"}, {"symlink": "node_modules"}, {"run": "npm install"}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            hooks,
+            vec![
+                WorktreeHook::Copy(".env".to_string()),
+                WorktreeHook::Symlink("node_modules".to_string()),
+                WorktreeHook::Run("npm install".to_string()),
+            ]
+        );
+        assert!(hooks.iter().all(|h| h.validate().is_ok()));
+
+        assert!(WorktreeHook::Copy("../secrets".to_string()).validate().is_err());
+        assert!(WorktreeHook::Symlink("/etc".to_string()).validate().is_err());
+        assert!(WorktreeHook::Run(" ".to_string()).validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hooks_run_in_order_and_stop_on_failure() {
+        let repo = tempfile::tempdir().unwrap();
+        let worktree = tempfile::tempdir().unwrap();
+        std::fs::write(repo.path().join(".env"), "KEY=1").unwrap();
+        std::fs::create_dir_all(repo.path().join("node_modules/pkg")).unwrap();
+
+        let mut events = subscribe();
+        let hooks = vec![
+            WorktreeHook::Copy(".env".to_string()),
+            WorktreeHook::Symlink("node_modules".to_string()),
+            WorktreeHook::Run("echo installed && exit 3".to_string()),
+            WorktreeHook::Run("echo never".to_string()),
+        ];
+        let runner = Runner {
+            repo_path: repo.path().to_path_buf(),
+            bead_id: "BD-001".to_string(),
+        };
+        let db = Database::new_in_memory().unwrap();
+        assert_eq!(start(&db, repo.path(), "BD-001", worktree.path(), Vec::new()), (false, None));
+        runs().lock().unwrap().insert(
+            (repo.path().to_path_buf(), "BD-001".to_string()),
+            new_run(&hooks),
+        );
+        runner.run(worktree.path(), hooks).await;
+
+        assert_eq!(
+            std::fs::read_to_string(worktree.path().join(".env")).unwrap(),
+            "KEY=1"
+        );
+        assert!(worktree.path().join("node_modules/pkg").is_dir());
+
+        let run = runs()
+            .lock()
+            .unwrap()
+            .get(&(repo.path().to_path_buf(), "BD-001".to_string()))
+            .cloned()
+            .unwrap();
+        assert_eq!(run.status, "failed");
+        let statuses: Vec<_> = run.steps.iter().map(|s| s.status.as_str()).collect();
+        assert_eq!(statuses, vec!["succeeded", "succeeded", "failed", "skipped"]);
+        assert_eq!(run.steps[2].output, vec!["installed"]);
+        assert!(run.finished_at.is_some());
+
+        let mut saw_output = false;
+        while let Ok(published) = events.try_recv() {
+            if published.repo_path == repo.path() && published.event.line.is_some() {
+                assert_eq!(published.event.line.as_deref(), Some("installed"));
+                saw_output = true;
+            }
+        }
+        assert!(saw_output);
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_hooks_do_not_run() {
+        let repo = tempfile::tempdir().unwrap();
+        let worktree = tempfile::tempdir().unwrap();
+        let db = Database::new_in_memory().unwrap();
+        let key = (repo.path().to_path_buf(), "BD-002".to_string());
+        let hooks = vec![
+            WorktreeHook::Copy(".env".to_string()),
+            WorktreeHook::Run("echo ran > ran.txt".to_string()),
+        ];
+
+        let (started, held_back) = start(&db, repo.path(), "BD-002", worktree.path(), hooks.clone());
+        assert!(!started);
+        let held_back = held_back.unwrap();
+        assert_eq!(held_back.commands, vec!["echo ran > ran.txt"]);
+        assert!(!runs().lock().unwrap().contains_key(&key));
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!worktree.path().join("ran.txt").exists());
+
+        // Approving other commands doesn't approve these
+        trust(&db, repo.path(), &[WorktreeHook::Run("true".to_string())]).unwrap();
+        assert_eq!(untrusted(&db, repo.path(), &hooks), Some(held_back.clone()));
+
+        // Copying and symlinking need no approval
+        assert_eq!(untrusted(&db, repo.path(), &hooks[..1]), None);
+
+        trust(&db, repo.path(), &hooks).unwrap();
+        assert_eq!(untrusted(&db, repo.path(), &hooks), None);
+        std::fs::write(repo.path().join(".env"), "KEY=1").unwrap();
+        let (started, held_back) = start(&db, repo.path(), "BD-002", worktree.path(), hooks);
+        assert!(started);
+        assert_eq!(held_back, None);
+        for _ in 0..100 {
+            if runs().lock().unwrap()[&key].status != "running" {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(runs().lock().unwrap()[&key].status, "succeeded");
+        assert!(worktree.path().join("ran.txt").exists());
+    }
+}
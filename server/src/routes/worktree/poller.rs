@@ -0,0 +1,305 @@
+//! Background PR status polling.
+//!
+//! Instead of every card polling `/api/git/pr-status` (and each poll
+//! spending provider rate limit), a server task refreshes the PR status of
+//! every bead worktree in the registered projects and caches it. The
+//! interval adapts per worktree: short while checks are running, backing
+//! off while nothing changes, and long once the PR is closed or the rate
+//! limit runs low. Changes are published in-process so the watch SSE stream
+//! can push them to boards as `pr-status` events. Each refresh also drives
+//! auto-merge for armed PRs.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use super::{auto_merge, get_worktree_entries, load_pr_status, PrStatusResponse};
+use crate::db::Database;
+use crate::shutdown;
+
+/// How often the poller looks for worktrees that are due.
+const TICK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Interval while a PR's checks are pending.
+const MIN_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Interval after a status change (and for new worktrees).
+const BASE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Longest interval, used for closed PRs and when the rate limit runs low.
+const MAX_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Remaining rate limit below which polling slows to `MAX_INTERVAL`.
+const LOW_RATE_LIMIT: i32 = 100;
+
+/// Capacity of the in-process PR status channel.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A cached status with when it was fetched and how long it stays fresh.
+struct CacheEntry {
+    status: PrStatusResponse,
+    fetched_at: Instant,
+    interval: Duration,
+}
+
+/// Cache key: repository path and bead ID.
+type CacheKey = (PathBuf, String);
+
+/// A PR status change, as sent to SSE clients.
+#[derive(Clone, Serialize)]
+pub struct PrStatusEvent {
+    pub bead_id: String,
+    #[serde(flatten)]
+    pub status: PrStatusResponse,
+}
+
+/// A PR status change published for a repository.
+#[derive(Clone)]
+pub struct PublishedPrStatus {
+    pub repo_path: PathBuf,
+    pub event: PrStatusEvent,
+}
+
+fn cache() -> &'static Mutex<HashMap<CacheKey, CacheEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<CacheKey, CacheEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn channel() -> &'static broadcast::Sender<PublishedPrStatus> {
+    static CHANNEL: OnceLock<broadcast::Sender<PublishedPrStatus>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Subscribes to PR status changes found by this server.
+pub fn subscribe() -> broadcast::Receiver<PublishedPrStatus> {
+    channel().subscribe()
+}
+
+/// Returns the cached status for a bead if it is still fresh.
+pub fn cached(repo_path: &Path, bead_id: &str) -> Option<PrStatusResponse> {
+    let cache = cache().lock().unwrap();
+    let entry = cache.get(&(repo_path.to_path_buf(), bead_id.to_string()))?;
+    (entry.fetched_at.elapsed() < entry.interval).then(|| entry.status.clone())
+}
+
+/// Drops the cached status for a bead, e.g. after creating or merging its
+/// PR, so the next request fetches it again.
+pub fn invalidate(repo_path: &Path, bead_id: &str) {
+    cache()
+        .lock()
+        .unwrap()
+        .remove(&(repo_path.to_path_buf(), bead_id.to_string()));
+}
+
+/// Drops the cached statuses of every bead in a repository, e.g. after
+/// fetching, so they reflect the new remote state.
+pub fn invalidate_repo(repo_path: &Path) {
+    cache().lock().unwrap().retain(|(path, _), _| path != repo_path);
+}
+
+/// Caches a freshly fetched status, publishing it if it differs from the
+/// previous one.
+pub fn store(repo_path: &Path, bead_id: &str, status: PrStatusResponse) {
+    let key = (repo_path.to_path_buf(), bead_id.to_string());
+    let mut cache = cache().lock().unwrap();
+    let previous = cache.get(&key);
+
+    let changed = previous.is_none_or(|p| !same_status(&p.status, &status));
+    let interval = next_interval(previous.map(|p| p.interval), &status, changed);
+    // Only changes to a known status are news; the first fetch is what the
+    // client asked for
+    if changed && previous.is_some() {
+        let _ = channel().send(PublishedPrStatus {
+            repo_path: key.0.clone(),
+            event: PrStatusEvent {
+                bead_id: bead_id.to_string(),
+                status: status.clone(),
+            },
+        });
+    }
+
+    cache.insert(
+        key,
+        CacheEntry {
+            status,
+            fetched_at: Instant::now(),
+            interval,
+        },
+    );
+}
+
+/// Whether two statuses differ only in rate limit information.
+fn same_status(a: &PrStatusResponse, b: &PrStatusResponse) -> bool {
+    a.has_remote == b.has_remote
+        && a.branch_pushed == b.branch_pushed
+        && (a.ahead, a.behind) == (b.ahead, b.behind)
+        && a.provider == b.provider
+        && a.provider_reason == b.provider_reason
+        && a.auto_merge == b.auto_merge
+        && serde_json::to_value(&a.pr).ok() == serde_json::to_value(&b.pr).ok()
+}
+
+/// Picks how long a status stays fresh.
+fn next_interval(
+    previous: Option<Duration>,
+    status: &PrStatusResponse,
+    changed: bool,
+) -> Duration {
+    let remaining = status.rate_limit.remaining;
+    if (0..LOW_RATE_LIMIT).contains(&remaining) {
+        return MAX_INTERVAL;
+    }
+
+    match &status.pr {
+        Some(pr) if pr.state != "open" => MAX_INTERVAL,
+        Some(pr) if pr.checks.status == "pending" => MIN_INTERVAL,
+        _ => match previous {
+            Some(previous) if !changed => (previous * 2).clamp(BASE_INTERVAL, MAX_INTERVAL),
+            _ => BASE_INTERVAL,
+        },
+    }
+}
+
+/// Refreshes the PR status of bead worktrees as it goes stale. Runs until
+/// the server shuts down.
+pub async fn run_pr_status_poller(db: Arc<Database>) {
+    let mut ticker = tokio::time::interval(TICK_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown::signalled() => break,
+        }
+
+        let projects = match db.get_projects() {
+            Ok(projects) => projects,
+            Err(e) => {
+                warn!("PR status poller failed to list projects: {}", e);
+                continue;
+            }
+        };
+
+        for project in projects {
+            let repo_path = PathBuf::from(&project.path);
+            if !repo_path.exists() {
+                continue;
+            }
+            let worktrees = match get_worktree_entries(&project.path).await {
+                Ok(worktrees) => worktrees,
+                Err(e) => {
+                    warn!("PR status poller failed to list worktrees in {}: {}", project.path, e);
+                    continue;
+                }
+            };
+            let bead_ids: Vec<String> = worktrees.into_iter().filter_map(|w| w.bead_id).collect();
+
+            // Forget worktrees that have been removed
+            cache()
+                .lock()
+                .unwrap()
+                .retain(|(path, bead_id), _| path != &repo_path || bead_ids.contains(bead_id));
+
+            for bead_id in bead_ids {
+                // Still fresh
+                if cached(&repo_path, &bead_id).is_some() {
+                    continue;
+                }
+                match load_pr_status(&db, &project.path, &bead_id).await {
+                    Ok(status) => {
+                        auto_merge::on_status(&db, &repo_path, &bead_id, &status);
+                        store(&repo_path, &bead_id, status);
+                    }
+                    Err(e) => {
+                        warn!("Failed to refresh PR status for {} in {}: {}", bead_id, project.path, e);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::routes::worktree::{ChecksStatus, PrInfo, RateLimitInfo};
+
+    fn status(state: &str, checks: &str, remaining: i32) -> PrStatusResponse {
+        PrStatusResponse {
+            has_remote: true,
+            branch_pushed: true,
+            ahead: 1,
+            behind: 0,
+            provider: "github".to_string(),
+            provider_reason: None,
+            pr: Some(PrInfo {
+                number: 1,
+                url: "https://github.com/user/repo/pull/1".to_string(),
+                state: state.to_string(),
+                checks: ChecksStatus {
+                    total: 1,
+                    passed: 0,
+                    failed: 0,
+                    pending: 0,
+                    status: checks.to_string(),
+                },
+                mergeable: true,
+                draft: false,
+                protection: None,
+            }),
+            rate_limit: RateLimitInfo {
+                remaining,
+                limit: 5000,
+                reset_at: String::new(),
+            },
+            auto_merge: false,
+        }
+    }
+
+    #[test]
+    fn test_next_interval() {
+        let open = status("open", "success", 4000);
+        assert_eq!(next_interval(None, &open, true), BASE_INTERVAL);
+        assert_eq!(
+            next_interval(Some(BASE_INTERVAL), &open, false),
+            BASE_INTERVAL * 2
+        );
+        assert_eq!(next_interval(Some(MAX_INTERVAL), &open, false), MAX_INTERVAL);
+        assert_eq!(next_interval(Some(MIN_INTERVAL), &open, true), BASE_INTERVAL);
+
+        let pending = status("open", "pending", 4000);
+        assert_eq!(next_interval(Some(MAX_INTERVAL), &pending, false), MIN_INTERVAL);
+        assert_eq!(next_interval(None, &status("merged", "success", 4000), true), MAX_INTERVAL);
+        assert_eq!(next_interval(None, &status("open", "pending", 50), true), MAX_INTERVAL);
+        // Unknown rate limits (-1) don't slow polling
+        assert_eq!(next_interval(None, &status("open", "pending", -1), true), MIN_INTERVAL);
+    }
+
+    #[test]
+    fn test_store_publishes_changes() {
+        let repo_path = Path::new("/tmp/poller-test-repo");
+        let mut events = subscribe();
+
+        store(repo_path, "BD-001", status("open", "pending", 4000));
+        assert!(cached(repo_path, "BD-001").is_some());
+
+        // Same status (only the rate limit moved): nothing published
+        store(repo_path, "BD-001", status("open", "pending", 3999));
+        store(repo_path, "BD-001", status("open", "success", 3998));
+
+        let published = events.try_recv().unwrap();
+        assert_eq!(published.repo_path, repo_path);
+        assert_eq!(published.event.bead_id, "BD-001");
+        assert_eq!(published.event.status.pr.unwrap().checks.status, "success");
+        assert!(events.try_recv().is_err());
+
+        invalidate(repo_path, "BD-001");
+        assert!(cached(repo_path, "BD-001").is_none());
+
+        store(repo_path, "BD-002", status("open", "pending", 4000));
+        invalidate_repo(repo_path);
+        assert!(cached(repo_path, "BD-002").is_none());
+    }
+}
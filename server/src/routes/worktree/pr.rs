@@ -0,0 +1,643 @@
+//! Pull request providers.
+//!
+//! The PR endpoints work against whichever service hosts the repository's
+//! `origin` remote: GitHub, Bitbucket Cloud, Gitea, or Forgejo, each through
+//! its REST API. GitHub falls back to the `gh` CLI when no token is
+//! configured. The provider is detected from the remote URL; it can be
+//! forced, and the server of a self-hosted instance set, in the project's PR
+//! settings (`/api/projects/:id/pr-settings`). Those live in the user's
+//! database and are never read from the repository, so a cloned repository
+//! cannot send the user's tokens to a server of its choosing.
+
+use axum::http::StatusCode;
+use std::path::PathBuf;
+
+use super::{BranchProtection, PrFileEntry, PrInfo, RateLimitInfo};
+use crate::db::Database;
+use crate::git_backend;
+
+mod bitbucket;
+mod gh_cli;
+mod gitea;
+mod github_api;
+
+use bitbucket::Bitbucket;
+use gitea::Gitea;
+use github_api::GitHubApi;
+
+/// A newly created pull request.
+pub struct CreatedPr {
+    pub number: Option<i32>,
+    pub url: String,
+    /// Reviewers, labels, or assignees that could not be applied; the PR
+    /// itself was created.
+    pub warnings: Vec<String>,
+}
+
+/// Reviewers, labels, and assignees to apply to a new pull request.
+#[derive(Debug, Default)]
+pub struct PrMetadata {
+    /// User names; on GitHub, "org/team" requests a team review.
+    pub reviewers: Vec<String>,
+    pub labels: Vec<String>,
+    pub assignees: Vec<String>,
+}
+
+/// The log of a CI check on a PR.
+pub struct CheckLog {
+    /// Name of the check.
+    pub name: String,
+    /// Conclusion of the check (e.g. "failure"), empty while running.
+    pub conclusion: String,
+    /// Web page of the check.
+    pub url: String,
+    /// Full log text.
+    pub log: String,
+}
+
+/// The owner and name of a hosted repository, parsed from a remote URL.
+#[derive(Debug, PartialEq)]
+pub struct RemoteRepo {
+    pub host: String,
+    pub owner: String,
+    pub name: String,
+}
+
+/// The service hosting a repository's pull requests.
+pub enum PrProvider {
+    GitHub(GitHubApi),
+    /// GitHub through the `gh` CLI, run in the repository.
+    GitHubCli {
+        repo_path: String,
+    },
+    Bitbucket(Bitbucket),
+    Gitea(Gitea),
+}
+
+impl PrProvider {
+    /// Picks the provider for a repository from its configuration and
+    /// `origin` remote. Repositories without a recognized remote use GitHub.
+    pub async fn detect(db: &Database, repo_path: &str) -> Result<Self, String> {
+        let settings = db
+            .get_pr_settings_for_path(repo_path)
+            .map_err(|e| format!("Failed to read PR settings: {}", e))?;
+        let path = PathBuf::from(repo_path);
+        let origin = tokio::task::spawn_blocking(move || git_backend::remote_url(&path, "origin"))
+            .await
+            .map_err(|e| format!("Failed to read remote: {}", e))?
+            .ok()
+            .flatten()
+            .and_then(|url| parse_remote_url(&url));
+
+        let provider = match settings.provider.as_deref() {
+            Some(provider) => provider.to_lowercase(),
+            None => match &origin {
+                Some(remote) if remote.host == "bitbucket.org" => "bitbucket".to_string(),
+                Some(remote) if remote.host == "codeberg.org" => "gitea".to_string(),
+                _ => "github".to_string(),
+            },
+        };
+
+        match provider.as_str() {
+            "github" => {
+                let token = ["GITHUB_TOKEN", "GH_TOKEN"]
+                    .iter()
+                    .find_map(|name| std::env::var(name).ok())
+                    .filter(|t| !t.is_empty())
+                    .or(settings.token);
+                match (token, origin) {
+                    (Some(token), Some(remote)) => Ok(PrProvider::GitHub(GitHubApi::new(
+                        &remote,
+                        token,
+                        settings.base_url.as_deref(),
+                    ))),
+                    // Without a token (or a parseable remote) gh does the work
+                    _ => Ok(PrProvider::GitHubCli {
+                        repo_path: repo_path.to_string(),
+                    }),
+                }
+            }
+            "bitbucket" => {
+                let remote = origin.ok_or_else(|| {
+                    "Cannot determine the Bitbucket repository from the origin remote".to_string()
+                })?;
+                Ok(PrProvider::Bitbucket(Bitbucket::from_env(&remote)?))
+            }
+            "gitea" | "forgejo" => {
+                let remote = origin.ok_or_else(|| {
+                    "Cannot determine the Gitea repository from the origin remote".to_string()
+                })?;
+                let client = Gitea::from_env(&remote, settings.base_url.as_deref())?;
+                Ok(PrProvider::Gitea(client))
+            }
+            other => Err(format!(
+                "Unknown PR provider '{}'. Supported providers: github, bitbucket, gitea",
+                other
+            )),
+        }
+    }
+
+    /// Short name of the provider, as reported in PR status responses.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PrProvider::GitHub(_) => "github",
+            PrProvider::GitHubCli { .. } => "gh",
+            PrProvider::Bitbucket(_) => "bitbucket",
+            PrProvider::Gitea(_) => "gitea",
+        }
+    }
+
+    /// Returns why PRs can't be queried, if they can't. Only the `gh` CLI
+    /// can be missing or logged out; the API clients are built with a token.
+    pub async fn unavailable_reason(&self) -> Option<String> {
+        match self {
+            PrProvider::GitHubCli { .. } => gh_cli::unavailable_reason().await,
+            PrProvider::GitHub(_) | PrProvider::Bitbucket(_) | PrProvider::Gitea(_) => None,
+        }
+    }
+
+    /// Get API rate limit information.
+    pub async fn rate_limit(&self) -> RateLimitInfo {
+        match self {
+            PrProvider::GitHub(client) => client.rate_limit().await,
+            PrProvider::GitHubCli { repo_path } => gh_cli::rate_limit(repo_path).await,
+            PrProvider::Bitbucket(_) | PrProvider::Gitea(_) => RateLimitInfo::unknown(),
+        }
+    }
+
+    /// Get the PR for a branch, if one exists.
+    pub async fn pr_info(&self, branch: &str) -> Option<PrInfo> {
+        match self {
+            PrProvider::GitHub(client) => client.pr_info(branch).await,
+            PrProvider::GitHubCli { repo_path } => gh_cli::pr_info(repo_path, branch).await,
+            PrProvider::Bitbucket(client) => client.pr_info(branch).await,
+            PrProvider::Gitea(client) => client.pr_info(branch).await,
+        }
+    }
+
+    /// Find a merged PR for a branch, returning its number and title.
+    pub async fn merged_pr(&self, branch: &str) -> Option<(i64, String)> {
+        match self {
+            PrProvider::GitHub(client) => client.merged_pr(branch).await,
+            PrProvider::GitHubCli { repo_path } => gh_cli::merged_pr(repo_path, branch).await,
+            PrProvider::Bitbucket(client) => client.merged_pr(branch).await,
+            PrProvider::Gitea(client) => client.merged_pr(branch).await,
+        }
+    }
+
+    /// Open a PR from a branch into the default branch, as a draft if
+    /// `draft` is set, with the given reviewers, labels, and assignees.
+    pub async fn create_pr(
+        &self,
+        branch: &str,
+        title: &str,
+        body: &str,
+        draft: bool,
+        metadata: &PrMetadata,
+    ) -> Result<CreatedPr, String> {
+        match self {
+            PrProvider::GitHub(client) => {
+                client.create_pr(branch, title, body, draft, metadata).await
+            }
+            PrProvider::GitHubCli { repo_path } => {
+                gh_cli::create_pr(repo_path, branch, title, body, draft, metadata).await
+            }
+            PrProvider::Bitbucket(client) => {
+                client.create_pr(branch, title, body, draft, metadata).await
+            }
+            PrProvider::Gitea(client) => {
+                client.create_pr(branch, title, body, draft, metadata).await
+            }
+        }
+    }
+
+    /// Mark the draft PR for a branch as ready for review.
+    pub async fn mark_ready(&self, branch: &str) -> Result<(), String> {
+        match self {
+            PrProvider::GitHub(client) => client.mark_ready(branch).await,
+            PrProvider::GitHubCli { repo_path } => gh_cli::mark_ready(repo_path, branch).await,
+            PrProvider::Bitbucket(client) => client.mark_ready(branch).await,
+            PrProvider::Gitea(client) => client.mark_ready(branch).await,
+        }
+    }
+
+    /// Publish a release for an existing tag, returning its web URL.
+    /// Bitbucket has no releases.
+    pub async fn create_release(&self, tag: &str, name: &str, notes: &str) -> Result<String, String> {
+        match self {
+            PrProvider::GitHub(client) => client.create_release(tag, name, notes).await,
+            PrProvider::GitHubCli { repo_path } => {
+                gh_cli::create_release(repo_path, tag, name, notes).await
+            }
+            PrProvider::Bitbucket(_) => Err("Bitbucket does not support releases".to_string()),
+            PrProvider::Gitea(client) => client.create_release(tag, name, notes).await,
+        }
+    }
+
+    /// Merge the PR for a branch with "merge", "squash", or "rebase".
+    pub async fn merge_pr(&self, branch: &str, merge_method: &str) -> Result<(), String> {
+        match self {
+            PrProvider::GitHub(client) => client.merge_pr(branch, merge_method).await,
+            PrProvider::GitHubCli { repo_path } => {
+                gh_cli::merge_pr(repo_path, branch, merge_method).await
+            }
+            PrProvider::Bitbucket(client) => client.merge_pr(branch, merge_method).await,
+            PrProvider::Gitea(client) => client.merge_pr(branch, merge_method).await,
+        }
+    }
+
+    /// Get the log of a CI check on the PR for a branch: the named check, or
+    /// the first failing one when `check` is unset. Only GitHub Actions
+    /// jobs have retrievable logs.
+    pub async fn check_log(
+        &self,
+        branch: &str,
+        check: Option<&str>,
+    ) -> Result<CheckLog, (StatusCode, String)> {
+        match self {
+            PrProvider::GitHub(client) => client.check_log(branch, check).await,
+            PrProvider::GitHubCli { repo_path } => {
+                gh_cli::check_log(repo_path, branch, check).await
+            }
+            PrProvider::Bitbucket(_) | PrProvider::Gitea(_) => Err((
+                StatusCode::NOT_IMPLEMENTED,
+                "Check logs are only available for GitHub repositories".to_string(),
+            )),
+        }
+    }
+
+    /// Get the changed files of the PR for a branch.
+    pub async fn pr_files(&self, branch: &str) -> Result<Vec<PrFileEntry>, (StatusCode, String)> {
+        match self {
+            PrProvider::GitHub(client) => client.pr_files(branch).await,
+            PrProvider::GitHubCli { repo_path } => gh_cli::pr_files(repo_path, branch).await,
+            PrProvider::Bitbucket(client) => client.pr_files(branch).await,
+            PrProvider::Gitea(client) => client.pr_files(branch).await,
+        }
+    }
+}
+
+/// Picks the check named `wanted`, or the first check `is_failed` accepts
+/// when no name is given. Checks are matched on their `name` field.
+fn select_check<'a>(
+    checks: &'a [serde_json::Value],
+    wanted: Option<&str>,
+    is_failed: impl Fn(&serde_json::Value) -> bool,
+) -> Result<&'a serde_json::Value, (StatusCode, String)> {
+    match wanted {
+        Some(name) => checks
+            .iter()
+            .find(|c| c["name"].as_str() == Some(name))
+            .ok_or_else(|| (StatusCode::NOT_FOUND, format!("No check named {}", name))),
+        None => checks
+            .iter()
+            .find(|c| is_failed(c))
+            .ok_or_else(|| (StatusCode::NOT_FOUND, "No failing checks".to_string())),
+    }
+}
+
+/// Extracts the GitHub Actions job ID from a check's web URL
+/// (`.../actions/runs/{run}/job/{job}`).
+fn actions_job_id(url: &str) -> Option<u64> {
+    let (_, rest) = url.split_once("/actions/runs/")?;
+    let (_, job) = rest.split_once("/job/")?;
+    job.split(['/', '?', '#']).next()?.parse().ok()
+}
+
+/// Reads the required checks and approvals of a GitHub branch from its
+/// classic protection (`/branches/{branch}/protection`, admins only) and
+/// its rulesets (`/rules/branches/{branch}`). Either may be null. Returns
+/// None when neither requires anything.
+fn parse_protection(
+    classic: &serde_json::Value,
+    rules: &serde_json::Value,
+) -> Option<BranchProtection> {
+    let mut checks: Vec<String> = Vec::new();
+    let mut approvals = 0;
+
+    let classic_checks = &classic["required_status_checks"];
+    let contexts = classic_checks["contexts"].as_array().into_iter().flatten();
+    let named = classic_checks["checks"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|c| &c["context"]);
+    checks.extend(contexts.chain(named).filter_map(|c| c.as_str()).map(String::from));
+    approvals = approvals.max(
+        classic["required_pull_request_reviews"]["required_approving_review_count"]
+            .as_i64()
+            .unwrap_or(0),
+    );
+
+    for rule in rules.as_array().into_iter().flatten() {
+        let parameters = &rule["parameters"];
+        match rule["type"].as_str() {
+            Some("pull_request") => {
+                approvals = approvals.max(
+                    parameters["required_approving_review_count"]
+                        .as_i64()
+                        .unwrap_or(0),
+                );
+            }
+            Some("required_status_checks") => checks.extend(
+                parameters["required_status_checks"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|c| c["context"].as_str())
+                    .map(String::from),
+            ),
+            _ => {}
+        }
+    }
+
+    checks.sort();
+    checks.dedup();
+    if checks.is_empty() && approvals == 0 {
+        return None;
+    }
+    Some(BranchProtection {
+        required_checks: checks,
+        missing_checks: Vec::new(),
+        required_approvals: approvals as i32,
+        approvals: 0,
+    })
+}
+
+/// Fills in which required checks haven't passed, from a `gh`-style status
+/// rollup (check runs by `name`, commit statuses by `context`), and how
+/// many approvals the PR has.
+fn evaluate_protection(
+    protection: &mut BranchProtection,
+    rollup: &serde_json::Value,
+    reviews: &serde_json::Value,
+) {
+    let checks = rollup.as_array().cloned().unwrap_or_default();
+    protection.missing_checks = protection
+        .required_checks
+        .iter()
+        .filter(|required| {
+            !checks.iter().any(|check| {
+                let name = check["name"].as_str().or_else(|| check["context"].as_str());
+                let result = check["conclusion"]
+                    .as_str()
+                    .filter(|c| !c.is_empty())
+                    .or_else(|| check["state"].as_str())
+                    .unwrap_or("");
+                name == Some(required.as_str())
+                    && matches!(result, "SUCCESS" | "NEUTRAL" | "SKIPPED")
+            })
+        })
+        .cloned()
+        .collect();
+    protection.approvals = count_approvals(reviews);
+}
+
+/// Counts reviewers whose latest review approves. Takes reviews from the
+/// REST API (`user.login`) or `gh` (`author.login`), oldest first.
+fn count_approvals(reviews: &serde_json::Value) -> i32 {
+    let mut latest: Vec<(&str, &str)> = Vec::new();
+    for review in reviews.as_array().into_iter().flatten() {
+        let author = review["user"]["login"]
+            .as_str()
+            .or_else(|| review["author"]["login"].as_str())
+            .unwrap_or("");
+        let state = review["state"].as_str().unwrap_or("");
+        // Comments don't change a reviewer's verdict
+        if state == "COMMENTED" {
+            continue;
+        }
+        match latest.iter_mut().find(|(a, _)| *a == author) {
+            Some(entry) => entry.1 = state,
+            None => latest.push((author, state)),
+        }
+    }
+    latest.iter().filter(|(_, state)| *state == "APPROVED").count() as i32
+}
+
+/// Convert an entry of a GitHub-style PR files API response (GitHub and
+/// Gitea share the format).
+fn parse_file_entry(entry: &serde_json::Value) -> PrFileEntry {
+    PrFileEntry {
+        filename: entry["filename"].as_str().unwrap_or("").to_string(),
+        status: entry["status"].as_str().unwrap_or("modified").to_string(),
+        additions: entry["additions"].as_i64().unwrap_or(0) as i32,
+        deletions: entry["deletions"].as_i64().unwrap_or(0) as i32,
+        changes: entry["changes"].as_i64().unwrap_or(0) as i32,
+    }
+}
+
+/// Parses the host, owner, and repository name from a remote URL.
+///
+/// Handles `https://[user@]host[:port]/owner/name[.git]`,
+/// `ssh://git@host[:port]/owner/name[.git]`, and scp-like
+/// `git@host:owner/name[.git]` URLs.
+pub fn parse_remote_url(url: &str) -> Option<RemoteRepo> {
+    let url = url.trim();
+    let (authority, path) = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?,
+        None => url.split_once(':')?,
+    };
+
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.to_lowercase();
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, name) = path.rsplit_once('/')?;
+    if host.is_empty() || owner.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    Some(RemoteRepo {
+        host,
+        owner: owner.to_string(),
+        name: name.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{CreateProjectInput, PrSettings};
+
+    #[test]
+    fn test_parse_protection() {
+        assert_eq!(
+            parse_protection(&serde_json::Value::Null, &serde_json::json!([])),
+            None
+        );
+
+        let classic = serde_json::json!({
+            "required_status_checks": {
+                "contexts": ["ci/build"],
+                "checks": [{"context": "ci/build"}, {"context": "lint"}]
+            },
+            "required_pull_request_reviews": {"required_approving_review_count": 1}
+        });
+        let rules = serde_json::json!([
+            {"type": "pull_request", "parameters": {"required_approving_review_count": 2}},
+            {"type": "required_status_checks", "parameters": {
+                "required_status_checks": [{"context": "test"}]
+            }},
+            {"type": "deletion"}
+        ]);
+        let protection = parse_protection(&classic, &rules).unwrap();
+        assert_eq!(protection.required_checks, vec!["ci/build", "lint", "test"]);
+        assert_eq!(protection.required_approvals, 2);
+    }
+
+    #[test]
+    fn test_evaluate_protection() {
+        let mut protection = BranchProtection {
+            required_checks: vec!["build".to_string(), "ci/legacy".to_string(), "lint".to_string()],
+            required_approvals: 2,
+            ..Default::default()
+        };
+        let rollup = serde_json::json!([
+            {"name": "build", "status": "COMPLETED", "conclusion": "SUCCESS"},
+            {"name": "lint", "status": "COMPLETED", "conclusion": "FAILURE"},
+            {"context": "ci/legacy", "state": "SUCCESS"}
+        ]);
+        let reviews = serde_json::json!([
+            {"user": {"login": "ann"}, "state": "CHANGES_REQUESTED"},
+            {"user": {"login": "bob"}, "state": "APPROVED"},
+            {"user": {"login": "ann"}, "state": "APPROVED"},
+            {"user": {"login": "bob"}, "state": "COMMENTED"}
+        ]);
+        evaluate_protection(&mut protection, &rollup, &reviews);
+        assert_eq!(protection.missing_checks, vec!["lint"]);
+        assert_eq!(protection.approvals, 2);
+        assert!(!protection.satisfied());
+
+        protection.missing_checks.clear();
+        assert!(protection.satisfied());
+    }
+
+    #[test]
+    fn test_count_approvals_from_gh() {
+        let reviews = serde_json::json!([
+            {"author": {"login": "ann"}, "state": "APPROVED"},
+            {"author": {"login": "bob"}, "state": "DISMISSED"}
+        ]);
+        assert_eq!(count_approvals(&reviews), 1);
+    }
+
+    #[test]
+    fn test_actions_job_id() {
+        assert_eq!(
+            actions_job_id("https://github.com/team/repo/actions/runs/123/job/456"),
+            Some(456)
+        );
+        assert_eq!(
+            actions_job_id("https://github.com/team/repo/actions/runs/123/job/456?pr=7"),
+            Some(456)
+        );
+        assert_eq!(actions_job_id("https://ci.example.com/build/1"), None);
+    }
+
+    #[test]
+    fn test_select_check() {
+        let checks = vec![
+            serde_json::json!({"name": "lint", "bucket": "pass"}),
+            serde_json::json!({"name": "test", "bucket": "fail"}),
+        ];
+        let failed = |c: &serde_json::Value| c["bucket"] == "fail";
+        assert_eq!(select_check(&checks, None, failed).unwrap()["name"], "test");
+        assert_eq!(select_check(&checks, Some("lint"), failed).unwrap()["name"], "lint");
+        let (status, _) = select_check(&checks, Some("build"), failed).unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = select_check(&checks[..1], None, failed).unwrap_err();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        let expected = |host: &str| RemoteRepo {
+            host: host.to_string(),
+            owner: "team".to_string(),
+            name: "repo".to_string(),
+        };
+        assert_eq!(
+            parse_remote_url("git@bitbucket.org:team/repo.git"),
+            Some(expected("bitbucket.org"))
+        );
+        assert_eq!(
+            parse_remote_url("https://user@bitbucket.org/team/repo.git"),
+            Some(expected("bitbucket.org"))
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@GitHub.com:22/team/repo"),
+            Some(expected("github.com"))
+        );
+        assert_eq!(
+            parse_remote_url("https://git.example.com/team/repo/"),
+            Some(expected("git.example.com"))
+        );
+        assert_eq!(parse_remote_url("/local/path/repo"), None);
+        assert_eq!(parse_remote_url("https://example.com/repo"), None);
+    }
+
+    #[tokio::test]
+    async fn test_detect_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo_path = dir.path().to_string_lossy().to_string();
+        let repo = git2::Repository::init(dir.path()).unwrap();
+        let db = Database::new_in_memory().unwrap();
+        let project = db
+            .create_project(CreateProjectInput {
+                name: "repo".to_string(),
+                path: repo_path.clone(),
+            })
+            .unwrap();
+
+        // No remote: the gh CLI (which resolves the repository itself)
+        let provider = PrProvider::detect(&db, &repo_path).await.unwrap();
+        assert!(matches!(provider, PrProvider::GitHubCli { .. }));
+
+        repo.remote("origin", "git@bitbucket.org:team/repo.git")
+            .unwrap();
+        std::env::remove_var("BITBUCKET_USERNAME");
+        assert!(PrProvider::detect(&db, &repo_path).await.is_err());
+
+        std::fs::create_dir(dir.path().join(".beads")).unwrap();
+        let config = dir.path().join(".beads").join("config.json");
+        std::fs::write(
+            &config,
+            r#"{"pr":{"provider":"gitea","base_url":"https://attacker.example","token":"x"}}"#,
+        )
+        .unwrap();
+        // PR settings in the repository are ignored
+        std::env::set_var("GITEA_TOKEN", "secret");
+        assert!(PrProvider::detect(&db, &repo_path).await.is_err());
+
+        // A token in the user's settings is used
+        std::env::remove_var("GITHUB_TOKEN");
+        std::env::remove_var("GH_TOKEN");
+        let set = |provider: &str, base_url: Option<&str>, token: Option<&str>| {
+            db.set_pr_settings(
+                &project.id,
+                PrSettings {
+                    provider: Some(provider.to_string()),
+                    base_url: base_url.map(str::to_string),
+                    token: token.map(str::to_string),
+                    has_token: false,
+                },
+            )
+            .unwrap();
+        };
+        set("github", None, None);
+        let provider = PrProvider::detect(&db, &repo_path).await.unwrap();
+        assert!(matches!(provider, PrProvider::GitHubCli { .. }));
+        set("github", None, Some("secret"));
+        let provider = PrProvider::detect(&db, &repo_path).await.unwrap();
+        assert!(matches!(provider, PrProvider::GitHub(_)));
+
+        set("forgejo", Some("https://git.example.com"), None);
+        let provider = PrProvider::detect(&db, &repo_path).await.unwrap();
+        assert!(matches!(provider, PrProvider::Gitea(_)));
+
+        std::env::remove_var("GITEA_TOKEN");
+        assert!(PrProvider::detect(&db, &repo_path).await.is_err());
+    }
+}
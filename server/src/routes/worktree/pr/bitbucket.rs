@@ -0,0 +1,372 @@
+//! Bitbucket Cloud pull requests through the REST API.
+//!
+//! Authenticates with an app password taken from the `BITBUCKET_USERNAME`
+//! and `BITBUCKET_APP_PASSWORD` environment variables. The app password
+//! needs the "Pull requests: Write" permission.
+
+use axum::http::StatusCode;
+
+use super::{CreatedPr, PrMetadata, RemoteRepo};
+use crate::routes::worktree::{ChecksStatus, PrFileEntry, PrInfo};
+
+const API_BASE: &str = "https://api.bitbucket.org/2.0";
+
+/// A client for one Bitbucket repository.
+pub struct Bitbucket {
+    workspace: String,
+    repo_slug: String,
+    username: String,
+    app_password: String,
+    client: reqwest::Client,
+}
+
+impl Bitbucket {
+    /// Creates a client for the repository, reading credentials from the
+    /// environment.
+    pub fn from_env(remote: &RemoteRepo) -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let (Some(username), Some(app_password)) =
+            (var("BITBUCKET_USERNAME"), var("BITBUCKET_APP_PASSWORD"))
+        else {
+            return Err(
+                "Bitbucket credentials not configured: set BITBUCKET_USERNAME and BITBUCKET_APP_PASSWORD"
+                    .to_string(),
+            );
+        };
+
+        Ok(Bitbucket {
+            workspace: remote.owner.clone(),
+            repo_slug: remote.name.clone(),
+            username,
+            app_password,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Builds an API URL under the repository.
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/repositories/{}/{}{}",
+            API_BASE, self.workspace, self.repo_slug, path
+        )
+    }
+
+    /// Sends a request and returns the JSON response body.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+        let response = request
+            .basic_auth(&self.username, Some(&self.app_password))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Bitbucket: {}", e))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        if status.is_success() {
+            Ok(body)
+        } else {
+            let message = body["error"]["message"]
+                .as_str()
+                .unwrap_or("request failed");
+            Err(format!("Bitbucket API error ({}): {}", status, message))
+        }
+    }
+
+    /// Finds the most recently updated PR from a branch in one of `states`.
+    async fn find_pr(
+        &self,
+        branch: &str,
+        states: &[&str],
+    ) -> Result<Option<serde_json::Value>, String> {
+        let mut query = vec![
+            ("q", format!("source.branch.name=\"{}\"", branch)),
+            ("sort", "-updated_on".to_string()),
+            ("pagelen", "1".to_string()),
+        ];
+        query.extend(states.iter().map(|s| ("state", s.to_string())));
+
+        let body = self
+            .send(self.client.get(self.url("/pullrequests")).query(&query))
+            .await?;
+        Ok(body["values"].as_array().and_then(|v| v.first()).cloned())
+    }
+
+    /// Get the PR for a branch, with its build results.
+    pub async fn pr_info(&self, branch: &str) -> Option<PrInfo> {
+        let pr = self
+            .find_pr(branch, &["OPEN", "MERGED", "DECLINED", "SUPERSEDED"])
+            .await
+            .ok()??;
+        let id = pr["id"].as_i64()?;
+
+        let statuses = self
+            .send(
+                self.client
+                    .get(self.url(&format!("/pullrequests/{}/statuses", id)))
+                    .query(&[("pagelen", "100")]),
+            )
+            .await
+            .unwrap_or_default();
+        let checks = parse_build_statuses(&statuses["values"]);
+
+        let state = pr_state(pr["state"].as_str().unwrap_or(""));
+        let draft = pr["draft"].as_bool().unwrap_or(false);
+        // Bitbucket has no mergeability flag; an open PR without failed
+        // builds is the closest equivalent
+        let mergeable = state == "open" && checks.failed == 0;
+
+        Some(PrInfo {
+            number: id as i32,
+            url: pr["links"]["html"]["href"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            state,
+            checks,
+            mergeable,
+            draft,
+            protection: None,
+        })
+    }
+
+    /// Find a merged PR for a branch, returning its number and title.
+    pub async fn merged_pr(&self, branch: &str) -> Option<(i64, String)> {
+        let pr = self.find_pr(branch, &["MERGED"]).await.ok()??;
+        let title = pr["title"].as_str().unwrap_or("Unknown").to_string();
+        Some((pr["id"].as_i64().unwrap_or(0), title))
+    }
+
+    /// Open a PR from a branch into the repository's main branch.
+    ///
+    /// Reviewers are account IDs or `{uuid}`s. Bitbucket PRs have no labels
+    /// or assignees, so those come back as warnings.
+    pub async fn create_pr(
+        &self,
+        branch: &str,
+        title: &str,
+        body: &str,
+        draft: bool,
+        metadata: &PrMetadata,
+    ) -> Result<CreatedPr, String> {
+        let reviewers: Vec<serde_json::Value> =
+            metadata.reviewers.iter().map(|r| reviewer_ref(r)).collect();
+        let payload = serde_json::json!({
+            "title": title,
+            "description": body,
+            "source": { "branch": { "name": branch } },
+            "draft": draft,
+            "reviewers": reviewers,
+        });
+        let pr = self
+            .send(self.client.post(self.url("/pullrequests")).json(&payload))
+            .await?;
+
+        let mut warnings = Vec::new();
+        if !metadata.labels.is_empty() {
+            warnings.push("Bitbucket PRs don't support labels".to_string());
+        }
+        if !metadata.assignees.is_empty() {
+            warnings.push("Bitbucket PRs don't support assignees".to_string());
+        }
+
+        Ok(CreatedPr {
+            number: pr["id"].as_i64().map(|n| n as i32),
+            url: pr["links"]["html"]["href"]
+                .as_str()
+                .unwrap_or("")
+                .to_string(),
+            warnings,
+        })
+    }
+
+    /// Merge the open PR for a branch.
+    pub async fn merge_pr(&self, branch: &str, merge_method: &str) -> Result<(), String> {
+        let pr = self
+            .find_pr(branch, &["OPEN"])
+            .await?
+            .ok_or_else(|| format!("No open PR found for branch {}", branch))?;
+        let id = pr["id"].as_i64().unwrap_or(0);
+
+        // The source branch is kept; delete_worktree removes it locally
+        let payload = serde_json::json!({
+            "merge_strategy": merge_strategy(merge_method),
+            "close_source_branch": false,
+        });
+        self.send(
+            self.client
+                .post(self.url(&format!("/pullrequests/{}/merge", id)))
+                .json(&payload),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Mark the open draft PR for a branch as ready for review.
+    pub async fn mark_ready(&self, branch: &str) -> Result<(), String> {
+        let pr = self
+            .find_pr(branch, &["OPEN"])
+            .await?
+            .ok_or_else(|| format!("No open PR found for branch {}", branch))?;
+        let id = pr["id"].as_i64().unwrap_or(0);
+
+        self.send(
+            self.client
+                .put(self.url(&format!("/pullrequests/{}", id)))
+                .json(&serde_json::json!({ "draft": false })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Get the changed files of the PR for a branch.
+    pub async fn pr_files(&self, branch: &str) -> Result<Vec<PrFileEntry>, (StatusCode, String)> {
+        let pr = self
+            .find_pr(branch, &["OPEN", "MERGED", "DECLINED", "SUPERSEDED"])
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("No PR found for branch {}", branch),
+                )
+            })?;
+        let id = pr["id"].as_i64().unwrap_or(0);
+
+        let diffstat = self
+            .send(
+                self.client
+                    .get(self.url(&format!("/pullrequests/{}/diffstat", id)))
+                    .query(&[("pagelen", "100")]),
+            )
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+        let entries = diffstat["values"].as_array().cloned().unwrap_or_default();
+        Ok(entries.iter().map(parse_diffstat_entry).collect())
+    }
+}
+
+/// Maps a Bitbucket PR state to "open", "merged", or "closed".
+fn pr_state(state: &str) -> String {
+    match state {
+        "OPEN" => "open",
+        "MERGED" => "merged",
+        _ => "closed", // DECLINED or SUPERSEDED
+    }
+    .to_string()
+}
+
+/// Maps a merge method to a Bitbucket merge strategy.
+fn merge_strategy(merge_method: &str) -> &'static str {
+    match merge_method {
+        "merge" => "merge_commit",
+        "rebase" => "fast_forward",
+        _ => "squash", // Default to squash
+    }
+}
+
+/// Refers to a reviewer by `{uuid}` or account ID.
+fn reviewer_ref(reviewer: &str) -> serde_json::Value {
+    if reviewer.starts_with('{') {
+        serde_json::json!({ "uuid": reviewer })
+    } else {
+        serde_json::json!({ "account_id": reviewer })
+    }
+}
+
+/// Summarize build statuses. Bitbucket reports `state` as "SUCCESSFUL",
+/// "FAILED", "STOPPED", or "INPROGRESS".
+fn parse_build_statuses(statuses: &serde_json::Value) -> ChecksStatus {
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut pending = 0;
+
+    for status in statuses.as_array().into_iter().flatten() {
+        match status["state"].as_str().unwrap_or("") {
+            "SUCCESSFUL" => passed += 1,
+            "FAILED" | "STOPPED" => failed += 1,
+            _ => pending += 1,
+        }
+    }
+
+    ChecksStatus::from_counts(passed, failed, pending)
+}
+
+/// Convert a diffstat entry to a PR file entry.
+fn parse_diffstat_entry(entry: &serde_json::Value) -> PrFileEntry {
+    // Removed files only have an old path
+    let filename = entry["new"]["path"]
+        .as_str()
+        .or_else(|| entry["old"]["path"].as_str())
+        .unwrap_or("")
+        .to_string();
+    let additions = entry["lines_added"].as_i64().unwrap_or(0) as i32;
+    let deletions = entry["lines_removed"].as_i64().unwrap_or(0) as i32;
+
+    PrFileEntry {
+        filename,
+        status: entry["status"].as_str().unwrap_or("modified").to_string(),
+        additions,
+        deletions,
+        changes: additions + deletions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_build_statuses() {
+        let statuses = serde_json::json!([
+            {"state": "SUCCESSFUL"},
+            {"state": "INPROGRESS"},
+            {"state": "STOPPED"}
+        ]);
+        let checks = parse_build_statuses(&statuses);
+        assert_eq!(checks.total, 3);
+        assert_eq!(checks.passed, 1);
+        assert_eq!(checks.pending, 1);
+        assert_eq!(checks.failed, 1);
+        assert_eq!(checks.status, "failure");
+
+        let checks = parse_build_statuses(&serde_json::Value::Null);
+        assert_eq!(checks.total, 0);
+        assert_eq!(checks.status, "success");
+    }
+
+    #[test]
+    fn test_reviewer_ref() {
+        assert_eq!(
+            reviewer_ref("{0b1c2d3e-aaaa}"),
+            serde_json::json!({"uuid": "{0b1c2d3e-aaaa}"})
+        );
+        assert_eq!(
+            reviewer_ref("557058:abcd"),
+            serde_json::json!({"account_id": "557058:abcd"})
+        );
+    }
+
+    #[test]
+    fn test_parse_diffstat_entry() {
+        let entry = parse_diffstat_entry(&serde_json::json!({
+            "status": "removed",
+            "lines_added": 0,
+            "lines_removed": 12,
+            "old": {"path": "src/old.rs"},
+            "new": null
+        }));
+        assert_eq!(entry.filename, "src/old.rs");
+        assert_eq!(entry.status, "removed");
+        assert_eq!(entry.changes, 12);
+    }
+
+    #[test]
+    fn test_state_and_strategy_mapping() {
+        assert_eq!(pr_state("OPEN"), "open");
+        assert_eq!(pr_state("MERGED"), "merged");
+        assert_eq!(pr_state("DECLINED"), "closed");
+        assert_eq!(merge_strategy("merge"), "merge_commit");
+        assert_eq!(merge_strategy("rebase"), "fast_forward");
+        assert_eq!(merge_strategy("squash"), "squash");
+    }
+}
@@ -0,0 +1,656 @@
+//! GitHub pull requests through the `gh` CLI.
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+use super::{
+    actions_job_id, evaluate_protection, parse_file_entry, parse_protection, select_check,
+    CheckLog, CreatedPr, PrMetadata,
+};
+use crate::logging::LogCommand;
+use crate::routes::worktree::{BranchProtection, ChecksStatus, PrFileEntry, PrInfo, RateLimitInfo};
+
+/// How long a `gh` availability check is reused.
+const AVAILABILITY_TTL: Duration = Duration::from_secs(60);
+
+/// When `gh` availability was last checked, and why it was unavailable.
+type AvailabilityCheck = (Instant, Option<String>);
+
+/// Returns why `gh` can't be used (not installed or not logged in), if it
+/// can't. The answer is cached briefly since every PR status query asks.
+pub async fn unavailable_reason() -> Option<String> {
+    static CHECKED: OnceLock<Mutex<Option<AvailabilityCheck>>> = OnceLock::new();
+    let checked = CHECKED.get_or_init(|| Mutex::new(None));
+    if let Some((at, reason)) = checked.lock().unwrap().as_ref() {
+        if at.elapsed() < AVAILABILITY_TTL {
+            return reason.clone();
+        }
+    }
+
+    // `gh auth token` only reads local credentials, unlike `gh auth status`
+    let output = Command::new("gh").args(["auth", "token"]).logged().output().await;
+    let reason = auth_failure(output.map(|o| o.status.success()));
+    *checked.lock().unwrap() = Some((Instant::now(), reason.clone()));
+    reason
+}
+
+/// Explains the outcome of `gh auth token` (whether it succeeded, or the
+/// error running it) as a reason `gh` can't be used.
+fn auth_failure(result: std::io::Result<bool>) -> Option<String> {
+    match result {
+        Ok(true) => None,
+        Ok(false) => Some(
+            "The gh CLI is not logged in; run `gh auth login` or set GITHUB_TOKEN".to_string(),
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            Some("The gh CLI is not installed; install it or set GITHUB_TOKEN".to_string())
+        }
+        Err(e) => Some(format!("Failed to run the gh CLI: {}", e)),
+    }
+}
+
+/// Get rate limit information from GitHub API.
+pub async fn rate_limit(repo_path: &str) -> RateLimitInfo {
+    let output = Command::new("gh")
+        .args(["api", "rate_limit", "--jq", ".rate"])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&stdout) {
+                let remaining = json["remaining"].as_i64().unwrap_or(0) as i32;
+                let limit = json["limit"].as_i64().unwrap_or(5000) as i32;
+                let reset = json["reset"].as_i64().unwrap_or(0);
+                let reset_at = DateTime::<Utc>::from_timestamp(reset, 0)
+                    .map(|dt| dt.to_rfc3339())
+                    .unwrap_or_default();
+                return RateLimitInfo {
+                    remaining,
+                    limit,
+                    reset_at,
+                };
+            }
+        }
+        _ => {}
+    }
+
+    // Default fallback
+    RateLimitInfo::unknown()
+}
+
+/// Get PR information for a branch.
+pub async fn pr_info(repo_path: &str, branch: &str) -> Option<PrInfo> {
+    // Try to get PR info
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "view",
+            branch,
+            "--json",
+            "number,url,state,isDraft,mergeable,statusCheckRollup,baseRefName,latestReviews",
+        ])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&stdout) {
+                let number = json["number"].as_i64().unwrap_or(0) as i32;
+                let url = json["url"].as_str().unwrap_or("").to_string();
+                let state = json["state"].as_str().unwrap_or("UNKNOWN").to_lowercase();
+                let mergeable_str = json["mergeable"].as_str().unwrap_or("UNKNOWN");
+                let mergeable = mergeable_str == "MERGEABLE";
+                let draft = json["isDraft"].as_bool().unwrap_or(false);
+
+                // Parse status checks
+                let checks = parse_status_checks(&json["statusCheckRollup"]);
+
+                // Protection only matters while the PR can still be merged
+                let protection = match json["baseRefName"].as_str() {
+                    Some(base) if state == "open" => {
+                        protection(repo_path, base, &json["statusCheckRollup"], &json["latestReviews"])
+                            .await
+                    }
+                    _ => None,
+                };
+                let mergeable = mergeable
+                    && protection.as_ref().is_none_or(BranchProtection::satisfied);
+
+                return Some(PrInfo {
+                    number,
+                    url,
+                    state,
+                    checks,
+                    mergeable,
+                    draft,
+                    protection,
+                });
+            }
+        }
+        _ => {}
+    }
+
+    None
+}
+
+/// Reads the protection rules of a PR's base branch with gh api and checks
+/// the PR against them.
+async fn protection(
+    repo_path: &str,
+    base: &str,
+    rollup: &serde_json::Value,
+    reviews: &serde_json::Value,
+) -> Option<BranchProtection> {
+    let classic = gh_api(
+        repo_path,
+        &format!("repos/{{owner}}/{{repo}}/branches/{}/protection", base),
+    )
+    .await;
+    let rules = gh_api(repo_path, &format!("repos/{{owner}}/{{repo}}/rules/branches/{}", base)).await;
+
+    let mut protection = parse_protection(&classic, &rules)?;
+    evaluate_protection(&mut protection, rollup, reviews);
+    Some(protection)
+}
+
+/// Runs `gh api` on a path, returning null if it fails.
+async fn gh_api(repo_path: &str, path: &str) -> serde_json::Value {
+    let output = Command::new("gh")
+        .args(["api", path])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await;
+
+    match output {
+        Ok(output) if output.status.success() => {
+            serde_json::from_slice(&output.stdout).unwrap_or_default()
+        }
+        _ => serde_json::Value::Null,
+    }
+}
+
+/// Parse status check rollup from gh pr view output.
+/// GitHub Actions returns:
+/// - status: "QUEUED" | "IN_PROGRESS" | "COMPLETED"
+/// - conclusion: "" | "SUCCESS" | "FAILURE" | "CANCELLED" (only set when COMPLETED)
+pub fn parse_status_checks(rollup: &serde_json::Value) -> ChecksStatus {
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut pending = 0;
+
+    if let Some(checks) = rollup.as_array() {
+        for check in checks {
+            let status = check["status"].as_str().unwrap_or("");
+            let conclusion = check["conclusion"].as_str().unwrap_or("");
+
+            match status {
+                "QUEUED" | "IN_PROGRESS" => pending += 1,
+                "COMPLETED" => match conclusion {
+                    "SUCCESS" => passed += 1,
+                    "FAILURE" | "CANCELLED" | "TIMED_OUT" | "ACTION_REQUIRED" => failed += 1,
+                    _ => pending += 1, // Unknown conclusion treated as pending
+                },
+                // Legacy status check API uses state/conclusion differently
+                _ => match conclusion {
+                    "SUCCESS" => passed += 1,
+                    "FAILURE" | "ERROR" => failed += 1,
+                    _ => pending += 1,
+                },
+            }
+        }
+    }
+
+    ChecksStatus::from_counts(passed, failed, pending)
+}
+
+/// Find a merged PR for a branch, returning its number and title.
+pub async fn merged_pr(repo_path: &str, branch: &str) -> Option<(i64, String)> {
+    let output = Command::new("gh")
+        .args([
+            "pr",
+            "list",
+            "--head",
+            branch,
+            "--state",
+            "merged",
+            "--json",
+            "number,title",
+        ])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .ok()?;
+
+    // If the check fails, PR creation proceeds anyway; gh pr create
+    // reports its own error if needed
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let prs = serde_json::from_str::<Vec<serde_json::Value>>(stdout.trim()).ok()?;
+    let pr = prs.first()?;
+    let number = pr.get("number").and_then(|n| n.as_i64()).unwrap_or(0);
+    let title = pr
+        .get("title")
+        .and_then(|t| t.as_str())
+        .unwrap_or("Unknown");
+    Some((number, title.to_string()))
+}
+
+/// Create a PR for a branch using gh pr create. gh fails the whole command
+/// if a reviewer, label, or assignee doesn't exist.
+pub async fn create_pr(
+    repo_path: &str,
+    branch: &str,
+    title: &str,
+    body: &str,
+    draft: bool,
+    metadata: &PrMetadata,
+) -> Result<CreatedPr, String> {
+    let mut args = vec![
+        "pr", "create", "--head", branch, "--title", title, "--body", body,
+    ];
+    if draft {
+        args.push("--draft");
+    }
+    for reviewer in &metadata.reviewers {
+        args.extend(["--reviewer", reviewer]);
+    }
+    for label in &metadata.labels {
+        args.extend(["--label", label]);
+    }
+    for assignee in &metadata.assignees {
+        args.extend(["--assignee", assignee]);
+    }
+    let output = Command::new("gh")
+        .args(args)
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let url = stdout.trim().to_string();
+    Ok(CreatedPr {
+        number: extract_pr_number_from_url(&url),
+        url,
+        warnings: Vec::new(),
+    })
+}
+
+/// Publish a release for a tag already on the remote using gh release
+/// create, returning its URL.
+pub async fn create_release(
+    repo_path: &str,
+    tag: &str,
+    name: &str,
+    notes: &str,
+) -> Result<String, String> {
+    let output = Command::new("gh")
+        .args([
+            "release",
+            "create",
+            tag,
+            "--verify-tag",
+            "--title",
+            name,
+            "--notes",
+            notes,
+        ])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh command: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Merge the PR for a branch using gh pr merge.
+pub async fn merge_pr(repo_path: &str, branch: &str, merge_method: &str) -> Result<(), String> {
+    // Determine merge flag
+    let merge_flag = match merge_method {
+        "merge" => "--merge",
+        "rebase" => "--rebase",
+        _ => "--squash", // Default to squash
+    };
+
+    // Note: Don't use --delete-branch as it fails when branch is used by a worktree.
+    // The cleanup step (delete_worktree) handles branch deletion.
+    let output = Command::new("gh")
+        .args(["pr", "merge", branch, merge_flag])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh command: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+/// Mark the draft PR for a branch as ready for review using gh pr ready.
+pub async fn mark_ready(repo_path: &str, branch: &str) -> Result<(), String> {
+    let output = Command::new("gh")
+        .args(["pr", "ready", branch])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh command: {}", e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    }
+}
+
+/// Get the changed files of the PR for a branch.
+pub async fn pr_files(
+    repo_path: &str,
+    branch: &str,
+) -> Result<Vec<PrFileEntry>, (StatusCode, String)> {
+    // Step 1: Get the PR number via gh pr view
+    let pr_number = get_pr_number(repo_path, branch).await.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            format!("No PR found for branch {}", branch),
+        )
+    })?;
+
+    // Step 2: Get the repo owner/name (nwo = name with owner)
+    let nwo = get_repo_nwo(repo_path).await.ok_or_else(|| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to determine repository owner/name".to_string(),
+        )
+    })?;
+
+    // Step 3: Fetch PR files using gh api
+    let api_path = format!("repos/{}/pulls/{}/files?per_page=100", nwo, pr_number);
+    let output = Command::new("gh")
+        .args(["api", &api_path])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to run gh command: {}", e),
+            )
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to fetch PR files: {}", stderr),
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let file_entries = serde_json::from_str::<Vec<serde_json::Value>>(&stdout).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to parse PR files response: {}", e),
+        )
+    })?;
+
+    Ok(file_entries.iter().map(parse_file_entry).collect())
+}
+
+/// Get the log of a check on the PR for a branch using gh pr checks and
+/// gh run view.
+pub async fn check_log(
+    repo_path: &str,
+    branch: &str,
+    check: Option<&str>,
+) -> Result<CheckLog, (StatusCode, String)> {
+    let output = Command::new("gh")
+        .args(["pr", "checks", branch, "--json", "name,state,bucket,link"])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to run gh command: {}", e),
+            )
+        })?;
+
+    // gh pr checks exits non-zero when checks fail or are pending, so go by
+    // whether it printed the checks
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let checks = serde_json::from_str::<Vec<serde_json::Value>>(&stdout).map_err(|_| {
+        (
+            StatusCode::NOT_FOUND,
+            format!(
+                "Failed to get checks for branch {}: {}",
+                branch,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        )
+    })?;
+
+    let chosen = select_check(&checks, check, |c| c["bucket"] == "fail")?;
+    let name = chosen["name"].as_str().unwrap_or("").to_string();
+    let url = chosen["link"].as_str().unwrap_or("").to_string();
+    let job_id = actions_job_id(&url).ok_or_else(|| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Check {} is not a GitHub Actions job; see {}", name, url),
+        )
+    })?;
+
+    // Prefer the failed steps; fall back to the whole job log
+    let mut log = String::new();
+    for flag in ["--log-failed", "--log"] {
+        let output = Command::new("gh")
+            .args(["run", "view", "--job", &job_id.to_string(), flag])
+            .current_dir(repo_path)
+            .logged()
+            .output()
+            .await
+            .map_err(|e| {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to run gh command: {}", e),
+                )
+            })?;
+        if !output.status.success() {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!(
+                    "Failed to fetch check log: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+        log = String::from_utf8_lossy(&output.stdout).to_string();
+        if !log.trim().is_empty() {
+            break;
+        }
+    }
+
+    Ok(CheckLog {
+        name,
+        conclusion: chosen["state"].as_str().unwrap_or("").to_lowercase(),
+        url,
+        log,
+    })
+}
+
+/// Get the PR number for a branch using gh pr view.
+async fn get_pr_number(repo_path: &str, branch: &str) -> Option<i32> {
+    let output = Command::new("gh")
+        .args(["pr", "view", branch, "--json", "number"])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).ok()?;
+    json["number"].as_i64().map(|n| n as i32)
+}
+
+/// Get the repository name with owner (e.g. "owner/repo") using gh repo view.
+async fn get_repo_nwo(repo_path: &str) -> Option<String> {
+    let output = Command::new("gh")
+        .args([
+            "repo",
+            "view",
+            "--json",
+            "nameWithOwner",
+            "--jq",
+            ".nameWithOwner",
+        ])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if stdout.is_empty() {
+        None
+    } else {
+        Some(stdout)
+    }
+}
+
+/// Extract PR number from a GitHub PR URL.
+fn extract_pr_number_from_url(url: &str) -> Option<i32> {
+    url.rsplit('/').next().and_then(|s| s.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_pr_number_from_url() {
+        assert_eq!(
+            extract_pr_number_from_url("https://github.com/user/repo/pull/142"),
+            Some(142)
+        );
+        assert_eq!(
+            extract_pr_number_from_url("https://github.com/user/repo/pull/1"),
+            Some(1)
+        );
+        assert_eq!(extract_pr_number_from_url("invalid-url"), None);
+    }
+
+    #[test]
+    fn test_parse_status_checks_empty() {
+        let empty = serde_json::json!([]);
+        let checks = parse_status_checks(&empty);
+        assert_eq!(checks.total, 0);
+        assert_eq!(checks.status, "success");
+    }
+
+    #[test]
+    fn test_parse_status_checks_queued() {
+        // QUEUED status should count as pending
+        let rollup = serde_json::json!([
+            {"status": "QUEUED", "conclusion": ""},
+            {"status": "IN_PROGRESS", "conclusion": ""}
+        ]);
+        let checks = parse_status_checks(&rollup);
+        assert_eq!(checks.total, 2);
+        assert_eq!(checks.pending, 2);
+        assert_eq!(checks.passed, 0);
+        assert_eq!(checks.failed, 0);
+        assert_eq!(checks.status, "pending");
+    }
+
+    #[test]
+    fn test_parse_status_checks_completed() {
+        // COMPLETED status uses conclusion field
+        let rollup = serde_json::json!([
+            {"status": "COMPLETED", "conclusion": "SUCCESS"},
+            {"status": "COMPLETED", "conclusion": "FAILURE"},
+            {"status": "COMPLETED", "conclusion": "CANCELLED"}
+        ]);
+        let checks = parse_status_checks(&rollup);
+        assert_eq!(checks.total, 3);
+        assert_eq!(checks.passed, 1);
+        assert_eq!(checks.failed, 2); // FAILURE + CANCELLED
+        assert_eq!(checks.pending, 0);
+        assert_eq!(checks.status, "failure");
+    }
+
+    #[test]
+    fn test_parse_status_checks_mixed() {
+        // Mix of queued, in-progress, and completed checks
+        let rollup = serde_json::json!([
+            {"status": "COMPLETED", "conclusion": "SUCCESS"},
+            {"status": "QUEUED", "conclusion": ""},
+            {"status": "COMPLETED", "conclusion": "FAILURE"}
+        ]);
+        let checks = parse_status_checks(&rollup);
+        assert_eq!(checks.total, 3);
+        assert_eq!(checks.passed, 1);
+        assert_eq!(checks.pending, 1);
+        assert_eq!(checks.failed, 1);
+        assert_eq!(checks.status, "failure");
+    }
+
+    #[test]
+    fn test_parse_status_checks_all_success() {
+        let rollup = serde_json::json!([
+            {"status": "COMPLETED", "conclusion": "SUCCESS"},
+            {"status": "COMPLETED", "conclusion": "SUCCESS"}
+        ]);
+        let checks = parse_status_checks(&rollup);
+        assert_eq!(checks.total, 2);
+        assert_eq!(checks.passed, 2);
+        assert_eq!(checks.pending, 0);
+        assert_eq!(checks.failed, 0);
+        assert_eq!(checks.status, "success");
+    }
+
+    #[test]
+    fn test_auth_failure() {
+        assert_eq!(auth_failure(Ok(true)), None);
+        assert!(auth_failure(Ok(false)).unwrap().contains("gh auth login"));
+        let missing = std::io::Error::from(std::io::ErrorKind::NotFound);
+        assert!(auth_failure(Err(missing)).unwrap().contains("not installed"));
+    }
+}
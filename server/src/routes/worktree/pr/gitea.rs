@@ -0,0 +1,456 @@
+//! Gitea and Forgejo pull requests through the REST API.
+//!
+//! The server is the base URL of the project's PR settings, defaulting to
+//! `https://{host}` of the origin remote. Requests authenticate with an
+//! access token from the `GITEA_TOKEN` environment variable; the token
+//! needs read and write access to the repository.
+
+use axum::http::StatusCode;
+
+use super::{parse_file_entry, CreatedPr, PrMetadata, RemoteRepo};
+use crate::routes::worktree::{ChecksStatus, PrFileEntry, PrInfo};
+
+/// Page size for list requests (Gitea's default maximum).
+const PAGE_LIMIT: usize = 50;
+
+/// Pages of PRs searched for a branch before giving up.
+const MAX_PAGES: usize = 10;
+
+/// Title prefixes that mark a PR as work in progress (Gitea's defaults);
+/// Gitea has no separate draft flag.
+const WIP_PREFIXES: [&str; 2] = ["WIP:", "[WIP]"];
+
+/// A client for one Gitea repository.
+pub struct Gitea {
+    api_base: String,
+    owner: String,
+    repo: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+impl Gitea {
+    /// Creates a client for the repository, reading the token from the
+    /// environment.
+    pub fn from_env(remote: &RemoteRepo, base_url: Option<&str>) -> Result<Self, String> {
+        let token = std::env::var("GITEA_TOKEN")
+            .ok()
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| "Gitea token not configured: set GITEA_TOKEN".to_string())?;
+        let base_url = base_url
+            .map(|url| url.trim_end_matches('/').to_string())
+            .unwrap_or_else(|| format!("https://{}", remote.host));
+
+        Ok(Gitea {
+            api_base: format!("{}/api/v1", base_url),
+            owner: remote.owner.clone(),
+            repo: remote.name.clone(),
+            token,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// Builds an API URL under the repository.
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/repos/{}/{}{}",
+            self.api_base, self.owner, self.repo, path
+        )
+    }
+
+    /// Sends a request and returns the JSON response body.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+        let response = request
+            .header("Authorization", format!("token {}", self.token))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Gitea: {}", e))?;
+
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        if status.is_success() {
+            Ok(body)
+        } else {
+            let message = body["message"].as_str().unwrap_or("request failed");
+            Err(format!("Gitea API error ({}): {}", status, message))
+        }
+    }
+
+    /// Finds the most recently updated PR from a branch.
+    ///
+    /// The list API cannot filter by head branch, so PRs are paged through
+    /// newest first.
+    async fn find_pr(&self, branch: &str) -> Result<Option<serde_json::Value>, String> {
+        for page in 1..=MAX_PAGES {
+            let prs = self
+                .send(self.client.get(self.url("/pulls")).query(&[
+                    ("state", "all".to_string()),
+                    ("sort", "recentupdate".to_string()),
+                    ("limit", PAGE_LIMIT.to_string()),
+                    ("page", page.to_string()),
+                ]))
+                .await?;
+            let prs = prs.as_array().cloned().unwrap_or_default();
+
+            if let Some(pr) = prs.iter().find(|pr| pr["head"]["ref"] == branch) {
+                return Ok(Some(pr.clone()));
+            }
+            if prs.len() < PAGE_LIMIT {
+                break;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the PR for a branch, with the commit statuses of its head.
+    pub async fn pr_info(&self, branch: &str) -> Option<PrInfo> {
+        let pr = self.find_pr(branch).await.ok()??;
+        let number = pr["number"].as_i64()?;
+
+        let checks = match pr["head"]["sha"].as_str() {
+            Some(sha) => {
+                let combined = self
+                    .send(
+                        self.client
+                            .get(self.url(&format!("/commits/{}/status", sha))),
+                    )
+                    .await
+                    .unwrap_or_default();
+                parse_commit_statuses(&combined["statuses"])
+            }
+            None => ChecksStatus::from_counts(0, 0, 0),
+        };
+
+        Some(PrInfo {
+            number: number as i32,
+            url: pr["html_url"].as_str().unwrap_or("").to_string(),
+            state: pr_state(&pr),
+            checks,
+            mergeable: pr["mergeable"].as_bool().unwrap_or(false),
+            draft: pr["draft"].as_bool() == Some(true)
+                || wip_prefix_len(pr["title"].as_str().unwrap_or("")).is_some(),
+            protection: None,
+        })
+    }
+
+    /// Find a merged PR for a branch, returning its number and title.
+    pub async fn merged_pr(&self, branch: &str) -> Option<(i64, String)> {
+        let pr = self.find_pr(branch).await.ok()??;
+        if pr["merged"].as_bool() != Some(true) {
+            return None;
+        }
+        let title = pr["title"].as_str().unwrap_or("Unknown").to_string();
+        Some((pr["number"].as_i64().unwrap_or(0), title))
+    }
+
+    /// Open a PR from a branch into the repository's default branch. Drafts
+    /// get a "WIP:" title prefix.
+    ///
+    /// Labels are matched by name against the repository's labels; unknown
+    /// labels and rejected reviewers come back as warnings.
+    pub async fn create_pr(
+        &self,
+        branch: &str,
+        title: &str,
+        body: &str,
+        draft: bool,
+        metadata: &PrMetadata,
+    ) -> Result<CreatedPr, String> {
+        let repo = self.send(self.client.get(self.url(""))).await?;
+        let base = repo["default_branch"].as_str().unwrap_or("main");
+        let title = if draft && wip_prefix_len(title).is_none() {
+            format!("{} {}", WIP_PREFIXES[0], title)
+        } else {
+            title.to_string()
+        };
+
+        let mut warnings = Vec::new();
+        let mut label_ids = Vec::new();
+        if !metadata.labels.is_empty() {
+            match self
+                .send(
+                    self.client
+                        .get(self.url("/labels"))
+                        .query(&[("limit", PAGE_LIMIT)]),
+                )
+                .await
+            {
+                Ok(labels) => {
+                    let (ids, missing) = resolve_label_ids(&labels, &metadata.labels);
+                    label_ids = ids;
+                    if !missing.is_empty() {
+                        warnings.push(format!("Unknown labels: {}", missing.join(", ")));
+                    }
+                }
+                Err(e) => warnings.push(format!("Failed to look up labels: {}", e)),
+            }
+        }
+
+        let payload = serde_json::json!({
+            "title": title,
+            "body": body,
+            "head": branch,
+            "base": base,
+            "assignees": metadata.assignees,
+            "labels": label_ids,
+        });
+        let pr = self
+            .send(self.client.post(self.url("/pulls")).json(&payload))
+            .await?;
+        let number = pr["number"].as_i64();
+
+        if let (Some(number), false) = (number, metadata.reviewers.is_empty()) {
+            if let Err(e) = self
+                .send(
+                    self.client
+                        .post(self.url(&format!("/pulls/{}/requested_reviewers", number)))
+                        .json(&serde_json::json!({ "reviewers": metadata.reviewers })),
+                )
+                .await
+            {
+                warnings.push(format!("Failed to request reviewers: {}", e));
+            }
+        }
+
+        Ok(CreatedPr {
+            number: number.map(|n| n as i32),
+            url: pr["html_url"].as_str().unwrap_or("").to_string(),
+            warnings,
+        })
+    }
+
+    /// Merge the open PR for a branch.
+    pub async fn merge_pr(&self, branch: &str, merge_method: &str) -> Result<(), String> {
+        let pr = self
+            .find_pr(branch)
+            .await?
+            .filter(|pr| pr["state"] == "open")
+            .ok_or_else(|| format!("No open PR found for branch {}", branch))?;
+        let number = pr["number"].as_i64().unwrap_or(0);
+
+        // The branch is kept; delete_worktree removes it locally
+        let payload = serde_json::json!({
+            "Do": merge_style(merge_method),
+            "delete_branch_after_merge": false,
+        });
+        self.send(
+            self.client
+                .post(self.url(&format!("/pulls/{}/merge", number)))
+                .json(&payload),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Publish a release for an existing tag, returning its URL.
+    pub async fn create_release(&self, tag: &str, name: &str, notes: &str) -> Result<String, String> {
+        let payload = serde_json::json!({
+            "tag_name": tag,
+            "name": name,
+            "body": notes,
+        });
+        let release = self
+            .send(self.client.post(self.url("/releases")).json(&payload))
+            .await?;
+        Ok(release["html_url"].as_str().unwrap_or("").to_string())
+    }
+
+    /// Mark the open draft PR for a branch as ready for review by removing
+    /// its WIP title prefix.
+    pub async fn mark_ready(&self, branch: &str) -> Result<(), String> {
+        let pr = self
+            .find_pr(branch)
+            .await?
+            .filter(|pr| pr["state"] == "open")
+            .ok_or_else(|| format!("No open PR found for branch {}", branch))?;
+        let number = pr["number"].as_i64().unwrap_or(0);
+        let title = pr["title"].as_str().unwrap_or("");
+        let Some(prefix_len) = wip_prefix_len(title) else {
+            return Ok(()); // Already ready
+        };
+
+        self.send(
+            self.client
+                .patch(self.url(&format!("/pulls/{}", number)))
+                .json(&serde_json::json!({ "title": title[prefix_len..].trim_start() })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Get the changed files of the PR for a branch.
+    pub async fn pr_files(&self, branch: &str) -> Result<Vec<PrFileEntry>, (StatusCode, String)> {
+        let pr = self
+            .find_pr(branch)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("No PR found for branch {}", branch),
+                )
+            })?;
+        let number = pr["number"].as_i64().unwrap_or(0);
+
+        let files = self
+            .send(
+                self.client
+                    .get(self.url(&format!("/pulls/{}/files", number)))
+                    .query(&[("limit", PAGE_LIMIT)]),
+            )
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+        let entries = files.as_array().cloned().unwrap_or_default();
+        Ok(entries.iter().map(parse_file_entry).collect())
+    }
+}
+
+/// Maps a Gitea PR to "open", "merged", or "closed".
+fn pr_state(pr: &serde_json::Value) -> String {
+    if pr["merged"].as_bool() == Some(true) {
+        "merged".to_string()
+    } else if pr["state"] == "open" {
+        "open".to_string()
+    } else {
+        "closed".to_string()
+    }
+}
+
+/// Length of the WIP prefix a title starts with (case-insensitive), if any.
+fn wip_prefix_len(title: &str) -> Option<usize> {
+    WIP_PREFIXES
+        .iter()
+        .find(|prefix| {
+            title
+                .get(..prefix.len())
+                .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        })
+        .map(|prefix| prefix.len())
+}
+
+/// Looks up label IDs by name (case-insensitive), returning the IDs found
+/// and the names that matched no label.
+fn resolve_label_ids(labels: &serde_json::Value, names: &[String]) -> (Vec<i64>, Vec<String>) {
+    let labels = labels.as_array().cloned().unwrap_or_default();
+    let mut ids = Vec::new();
+    let mut missing = Vec::new();
+
+    for name in names {
+        let id = labels
+            .iter()
+            .find(|label| {
+                label["name"]
+                    .as_str()
+                    .is_some_and(|n| n.eq_ignore_ascii_case(name))
+            })
+            .and_then(|label| label["id"].as_i64());
+        match id {
+            Some(id) => ids.push(id),
+            None => missing.push(name.clone()),
+        }
+    }
+
+    (ids, missing)
+}
+
+/// Maps a merge method to a Gitea merge style.
+fn merge_style(merge_method: &str) -> &'static str {
+    match merge_method {
+        "merge" => "merge",
+        "rebase" => "rebase",
+        _ => "squash", // Default to squash
+    }
+}
+
+/// Summarize commit statuses. Gitea reports `status` as "success",
+/// "pending", "failure", "error", or "warning".
+fn parse_commit_statuses(statuses: &serde_json::Value) -> ChecksStatus {
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut pending = 0;
+
+    for status in statuses.as_array().into_iter().flatten() {
+        match status["status"].as_str().unwrap_or("") {
+            "success" | "warning" => passed += 1,
+            "failure" | "error" => failed += 1,
+            _ => pending += 1,
+        }
+    }
+
+    ChecksStatus::from_counts(passed, failed, pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commit_statuses() {
+        let statuses = serde_json::json!([
+            {"status": "success"},
+            {"status": "warning"},
+            {"status": "pending"}
+        ]);
+        let checks = parse_commit_statuses(&statuses);
+        assert_eq!(checks.total, 3);
+        assert_eq!(checks.passed, 2);
+        assert_eq!(checks.pending, 1);
+        assert_eq!(checks.status, "pending");
+
+        let checks = parse_commit_statuses(&serde_json::json!([{"status": "error"}]));
+        assert_eq!(checks.failed, 1);
+        assert_eq!(checks.status, "failure");
+    }
+
+    #[test]
+    fn test_pr_state_and_merge_style() {
+        let pr = |state: &str, merged: bool| serde_json::json!({"state": state, "merged": merged});
+        assert_eq!(pr_state(&pr("open", false)), "open");
+        assert_eq!(pr_state(&pr("closed", true)), "merged");
+        assert_eq!(pr_state(&pr("closed", false)), "closed");
+        assert_eq!(merge_style("merge"), "merge");
+        assert_eq!(merge_style("rebase"), "rebase");
+        assert_eq!(merge_style("anything"), "squash");
+    }
+
+    #[test]
+    fn test_wip_prefix_len() {
+        assert_eq!(wip_prefix_len("WIP: Fix badge"), Some(4));
+        assert_eq!(wip_prefix_len("[wip] Fix badge"), Some(5));
+        assert_eq!(wip_prefix_len("Fix WIP: badge"), None);
+        assert_eq!(wip_prefix_len("WI"), None);
+    }
+
+    #[test]
+    fn test_resolve_label_ids() {
+        let labels = serde_json::json!([
+            {"id": 3, "name": "Beads"},
+            {"id": 7, "name": "needs-review"}
+        ]);
+        let names = vec!["beads".to_string(), "urgent".to_string(), "needs-review".to_string()];
+        let (ids, missing) = resolve_label_ids(&labels, &names);
+        assert_eq!(ids, vec![3, 7]);
+        assert_eq!(missing, vec!["urgent"]);
+    }
+
+    #[test]
+    fn test_base_url() {
+        std::env::set_var("GITEA_TOKEN", "secret");
+        let remote = RemoteRepo {
+            host: "codeberg.org".to_string(),
+            owner: "team".to_string(),
+            name: "repo".to_string(),
+        };
+        let client = Gitea::from_env(&remote, None).unwrap();
+        assert_eq!(
+            client.url("/pulls"),
+            "https://codeberg.org/api/v1/repos/team/repo/pulls"
+        );
+        let client = Gitea::from_env(&remote, Some("http://localhost:3000/")).unwrap();
+        assert_eq!(
+            client.url("/pulls"),
+            "http://localhost:3000/api/v1/repos/team/repo/pulls"
+        );
+    }
+}
@@ -0,0 +1,583 @@
+//! GitHub pull requests through the REST API.
+//!
+//! Used instead of the `gh` CLI when a token is available, so the PR flow
+//! works in containers and CI where `gh` is not installed or logged in.
+//! GitHub Enterprise servers are reached through the project's PR settings.
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+
+use super::gh_cli::parse_status_checks;
+use super::{
+    actions_job_id, evaluate_protection, parse_file_entry, parse_protection, select_check,
+    CheckLog, CreatedPr, PrMetadata, RemoteRepo,
+};
+use crate::routes::worktree::{BranchProtection, PrFileEntry, PrInfo, RateLimitInfo};
+
+const API_BASE: &str = "https://api.github.com";
+
+const GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// Page size for list requests (GitHub's maximum).
+const PER_PAGE: &str = "100";
+
+/// A client for one GitHub repository.
+pub struct GitHubApi {
+    api_base: String,
+    /// GraphQL endpoint, for the few operations REST does not cover
+    graphql_url: String,
+    owner: String,
+    repo: String,
+    token: String,
+    client: reqwest::Client,
+}
+
+impl GitHubApi {
+    /// Creates a client for the repository.
+    ///
+    /// `base_url` is the web URL of a GitHub Enterprise server; github.com
+    /// is used when it is unset.
+    pub fn new(remote: &RemoteRepo, token: String, base_url: Option<&str>) -> Self {
+        let (api_base, graphql_url) = match base_url {
+            Some(url) => {
+                let url = url.trim_end_matches('/');
+                (format!("{}/api/v3", url), format!("{}/api/graphql", url))
+            }
+            None => (API_BASE.to_string(), GRAPHQL_URL.to_string()),
+        };
+
+        GitHubApi {
+            api_base,
+            graphql_url,
+            owner: remote.owner.clone(),
+            repo: remote.name.clone(),
+            token,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Builds an API URL under the repository.
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/repos/{}/{}{}",
+            self.api_base, self.owner, self.repo, path
+        )
+    }
+
+    /// Sends an authenticated request, failing on error statuses.
+    async fn send_raw(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, String> {
+        let response = request
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "beads-kanban-ui")
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(response)
+        } else {
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let message = body["message"].as_str().unwrap_or("request failed");
+            Err(format!("GitHub API error ({}): {}", status, message))
+        }
+    }
+
+    /// Sends a request and returns the JSON response body.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+        let response = self.send_raw(request).await?;
+        Ok(response.json().await.unwrap_or_default())
+    }
+
+    /// Lists PRs from a branch in `state` ("open", "closed", or "all"),
+    /// most recently updated first.
+    async fn list_prs(&self, branch: &str, state: &str) -> Result<Vec<serde_json::Value>, String> {
+        let head = format!("{}:{}", self.owner, branch);
+        let prs = self
+            .send(self.client.get(self.url("/pulls")).query(&[
+                ("head", head.as_str()),
+                ("state", state),
+                ("sort", "updated"),
+                ("direction", "desc"),
+                ("per_page", PER_PAGE),
+            ]))
+            .await?;
+        Ok(prs.as_array().cloned().unwrap_or_default())
+    }
+
+    /// Finds the number of the most recently updated PR from a branch.
+    async fn find_pr_number(&self, branch: &str) -> Result<Option<i64>, String> {
+        let prs = self.list_prs(branch, "all").await?;
+        Ok(prs.first().and_then(|pr| pr["number"].as_i64()))
+    }
+
+    /// Get rate limit information.
+    pub async fn rate_limit(&self) -> RateLimitInfo {
+        let url = format!("{}/rate_limit", self.api_base);
+        let Ok(json) = self.send(self.client.get(url)).await else {
+            return RateLimitInfo::unknown();
+        };
+
+        let rate = &json["rate"];
+        let reset = rate["reset"].as_i64().unwrap_or(0);
+        RateLimitInfo {
+            remaining: rate["remaining"].as_i64().unwrap_or(0) as i32,
+            limit: rate["limit"].as_i64().unwrap_or(5000) as i32,
+            reset_at: DateTime::<Utc>::from_timestamp(reset, 0)
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Get the PR for a branch, with the checks on its head commit.
+    pub async fn pr_info(&self, branch: &str) -> Option<PrInfo> {
+        let number = self.find_pr_number(branch).await.ok()??;
+        // The list endpoint omits mergeability; fetch the PR itself
+        let pr = self
+            .send(self.client.get(self.url(&format!("/pulls/{}", number))))
+            .await
+            .ok()?;
+
+        let rollup = match pr["head"]["sha"].as_str() {
+            Some(sha) => self.status_rollup(sha).await,
+            None => serde_json::json!([]),
+        };
+        let state = pr_state(&pr);
+
+        // Protection only matters while the PR can still be merged
+        let protection = match pr["base"]["ref"].as_str() {
+            Some(base) if state == "open" => self.protection(base, number, &rollup).await,
+            _ => None,
+        };
+        let mergeable = pr["mergeable"].as_bool().unwrap_or(false)
+            && protection.as_ref().is_none_or(BranchProtection::satisfied);
+
+        Some(PrInfo {
+            number: number as i32,
+            url: pr["html_url"].as_str().unwrap_or("").to_string(),
+            state,
+            checks: parse_status_checks(&rollup),
+            mergeable,
+            draft: pr["draft"].as_bool().unwrap_or(false),
+            protection,
+        })
+    }
+
+    /// Reads the protection rules of a PR's base branch and checks the PR
+    /// against them. Classic protection needs admin access; rulesets are
+    /// readable by anyone who can read the repository.
+    async fn protection(
+        &self,
+        base: &str,
+        number: i64,
+        rollup: &serde_json::Value,
+    ) -> Option<BranchProtection> {
+        let classic = self
+            .send(
+                self.client
+                    .get(self.url(&format!("/branches/{}/protection", base))),
+            )
+            .await
+            .unwrap_or_default();
+        let rules = self
+            .send(self.client.get(self.url(&format!("/rules/branches/{}", base))))
+            .await
+            .unwrap_or_default();
+        let mut protection = parse_protection(&classic, &rules)?;
+
+        let reviews = if protection.required_approvals > 0 {
+            self.send(
+                self.client
+                    .get(self.url(&format!("/pulls/{}/reviews", number)))
+                    .query(&[("per_page", PER_PAGE)]),
+            )
+            .await
+            .unwrap_or_default()
+        } else {
+            serde_json::Value::Null
+        };
+        evaluate_protection(&mut protection, rollup, &reviews);
+        Some(protection)
+    }
+
+    /// Collects check runs and legacy commit statuses for a commit in the
+    /// shape of `gh pr view --json statusCheckRollup`.
+    async fn status_rollup(&self, sha: &str) -> serde_json::Value {
+        let check_runs = self
+            .send(
+                self.client
+                    .get(self.url(&format!("/commits/{}/check-runs", sha)))
+                    .query(&[("per_page", PER_PAGE)]),
+            )
+            .await
+            .unwrap_or_default();
+        let statuses = self
+            .send(
+                self.client
+                    .get(self.url(&format!("/commits/{}/status", sha))),
+            )
+            .await
+            .unwrap_or_default();
+
+        to_status_rollup(&check_runs["check_runs"], &statuses["statuses"])
+    }
+
+    /// Find a merged PR for a branch, returning its number and title.
+    pub async fn merged_pr(&self, branch: &str) -> Option<(i64, String)> {
+        let prs = self.list_prs(branch, "closed").await.ok()?;
+        let pr = prs.iter().find(|pr| !pr["merged_at"].is_null())?;
+        let title = pr["title"].as_str().unwrap_or("Unknown").to_string();
+        Some((pr["number"].as_i64().unwrap_or(0), title))
+    }
+
+    /// Open a PR from a branch into the repository's default branch.
+    ///
+    /// Reviewers, labels, and assignees are added once the PR exists; any
+    /// that are rejected come back as warnings.
+    pub async fn create_pr(
+        &self,
+        branch: &str,
+        title: &str,
+        body: &str,
+        draft: bool,
+        metadata: &PrMetadata,
+    ) -> Result<CreatedPr, String> {
+        let repo = self.send(self.client.get(self.url(""))).await?;
+        let base = repo["default_branch"].as_str().unwrap_or("main");
+
+        let payload = serde_json::json!({
+            "title": title,
+            "body": body,
+            "head": branch,
+            "base": base,
+            "draft": draft,
+        });
+        let pr = self
+            .send(self.client.post(self.url("/pulls")).json(&payload))
+            .await?;
+
+        let number = pr["number"].as_i64();
+        let warnings = match number {
+            Some(number) => self.apply_metadata(number, metadata).await,
+            None => Vec::new(),
+        };
+
+        Ok(CreatedPr {
+            number: number.map(|n| n as i32),
+            url: pr["html_url"].as_str().unwrap_or("").to_string(),
+            warnings,
+        })
+    }
+
+    /// Request reviews and add labels and assignees to a PR, returning what
+    /// failed.
+    async fn apply_metadata(&self, number: i64, metadata: &PrMetadata) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        if !metadata.reviewers.is_empty() {
+            let (reviewers, team_reviewers) = split_reviewers(&metadata.reviewers);
+            let payload = serde_json::json!({
+                "reviewers": reviewers,
+                "team_reviewers": team_reviewers,
+            });
+            if let Err(e) = self
+                .send(
+                    self.client
+                        .post(self.url(&format!("/pulls/{}/requested_reviewers", number)))
+                        .json(&payload),
+                )
+                .await
+            {
+                warnings.push(format!("Failed to request reviewers: {}", e));
+            }
+        }
+        if !metadata.labels.is_empty() {
+            if let Err(e) = self
+                .send(
+                    self.client
+                        .post(self.url(&format!("/issues/{}/labels", number)))
+                        .json(&serde_json::json!({ "labels": metadata.labels })),
+                )
+                .await
+            {
+                warnings.push(format!("Failed to add labels: {}", e));
+            }
+        }
+        if !metadata.assignees.is_empty() {
+            if let Err(e) = self
+                .send(
+                    self.client
+                        .post(self.url(&format!("/issues/{}/assignees", number)))
+                        .json(&serde_json::json!({ "assignees": metadata.assignees })),
+                )
+                .await
+            {
+                warnings.push(format!("Failed to add assignees: {}", e));
+            }
+        }
+
+        warnings
+    }
+
+    /// Merge the open PR for a branch.
+    pub async fn merge_pr(&self, branch: &str, merge_method: &str) -> Result<(), String> {
+        let prs = self.list_prs(branch, "open").await?;
+        let number = prs
+            .first()
+            .and_then(|pr| pr["number"].as_i64())
+            .ok_or_else(|| format!("No open PR found for branch {}", branch))?;
+
+        let merge_method = match merge_method {
+            "merge" | "rebase" => merge_method,
+            _ => "squash", // Default to squash
+        };
+        self.send(
+            self.client
+                .put(self.url(&format!("/pulls/{}/merge", number)))
+                .json(&serde_json::json!({ "merge_method": merge_method })),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Publish a release for an existing tag, returning its URL.
+    pub async fn create_release(&self, tag: &str, name: &str, notes: &str) -> Result<String, String> {
+        let payload = serde_json::json!({
+            "tag_name": tag,
+            "name": name,
+            "body": notes,
+        });
+        let release = self
+            .send(self.client.post(self.url("/releases")).json(&payload))
+            .await?;
+        Ok(release["html_url"].as_str().unwrap_or("").to_string())
+    }
+
+    /// Mark the open draft PR for a branch as ready for review.
+    ///
+    /// REST cannot change draft state, so this goes through GraphQL.
+    pub async fn mark_ready(&self, branch: &str) -> Result<(), String> {
+        let prs = self.list_prs(branch, "open").await?;
+        let node_id = prs
+            .first()
+            .and_then(|pr| pr["node_id"].as_str())
+            .ok_or_else(|| format!("No open PR found for branch {}", branch))?;
+
+        let payload = serde_json::json!({
+            "query": "mutation($id: ID!) { markPullRequestReadyForReview(input: {pullRequestId: $id}) { pullRequest { isDraft } } }",
+            "variables": { "id": node_id },
+        });
+        let response = self
+            .send(self.client.post(&self.graphql_url).json(&payload))
+            .await?;
+        // GraphQL reports failures in the body with a 200 status
+        match response["errors"][0]["message"].as_str() {
+            Some(message) => Err(format!("GitHub API error: {}", message)),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the log of a check run on the head commit of the PR for a branch.
+    pub async fn check_log(
+        &self,
+        branch: &str,
+        check: Option<&str>,
+    ) -> Result<CheckLog, (StatusCode, String)> {
+        let internal = |e: String| (StatusCode::INTERNAL_SERVER_ERROR, e);
+        let number = self
+            .find_pr_number(branch)
+            .await
+            .map_err(internal)?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("No PR found for branch {}", branch),
+                )
+            })?;
+        let pr = self
+            .send(self.client.get(self.url(&format!("/pulls/{}", number))))
+            .await
+            .map_err(internal)?;
+        let sha = pr["head"]["sha"].as_str().unwrap_or("");
+
+        let runs = self
+            .send(
+                self.client
+                    .get(self.url(&format!("/commits/{}/check-runs", sha)))
+                    .query(&[("per_page", PER_PAGE)]),
+            )
+            .await
+            .map_err(internal)?;
+        let runs = runs["check_runs"].as_array().cloned().unwrap_or_default();
+        let run = select_check(&runs, check, |r| {
+            matches!(
+                r["conclusion"].as_str(),
+                Some("failure" | "timed_out" | "cancelled" | "action_required")
+            )
+        })?;
+
+        let name = run["name"].as_str().unwrap_or("").to_string();
+        let url = run["html_url"].as_str().unwrap_or("").to_string();
+        let job_id = actions_job_id(&url).ok_or_else(|| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Check {} is not a GitHub Actions job; see {}", name, url),
+            )
+        })?;
+
+        // Redirects to a short-lived download URL
+        let log = self
+            .send_raw(
+                self.client
+                    .get(self.url(&format!("/actions/jobs/{}/logs", job_id))),
+            )
+            .await
+            .map_err(internal)?
+            .text()
+            .await
+            .map_err(|e| internal(format!("Failed to read check log: {}", e)))?;
+
+        Ok(CheckLog {
+            name,
+            conclusion: run["conclusion"].as_str().unwrap_or("").to_string(),
+            url,
+            log,
+        })
+    }
+
+    /// Get the changed files of the PR for a branch.
+    pub async fn pr_files(&self, branch: &str) -> Result<Vec<PrFileEntry>, (StatusCode, String)> {
+        let number = self
+            .find_pr_number(branch)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("No PR found for branch {}", branch),
+                )
+            })?;
+
+        let files = self
+            .send(
+                self.client
+                    .get(self.url(&format!("/pulls/{}/files", number)))
+                    .query(&[("per_page", PER_PAGE)]),
+            )
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+        let entries = files.as_array().cloned().unwrap_or_default();
+        Ok(entries.iter().map(parse_file_entry).collect())
+    }
+}
+
+/// Maps a GitHub PR to "open", "merged", or "closed".
+fn pr_state(pr: &serde_json::Value) -> String {
+    if !pr["merged_at"].is_null() || pr["merged"].as_bool() == Some(true) {
+        "merged".to_string()
+    } else {
+        pr["state"].as_str().unwrap_or("unknown").to_string()
+    }
+}
+
+/// Splits reviewers into user logins and the slugs of "org/team" entries.
+fn split_reviewers(reviewers: &[String]) -> (Vec<&str>, Vec<&str>) {
+    let mut users = Vec::new();
+    let mut teams = Vec::new();
+    for reviewer in reviewers {
+        match reviewer.split_once('/') {
+            Some((_, team)) => teams.push(team),
+            None => users.push(reviewer.as_str()),
+        }
+    }
+    (users, teams)
+}
+
+/// Converts check runs (`status`/`conclusion`) and legacy commit statuses
+/// (`state`) to the upper-case rollup format the `gh` CLI reports.
+fn to_status_rollup(
+    check_runs: &serde_json::Value,
+    statuses: &serde_json::Value,
+) -> serde_json::Value {
+    let upper = |v: &serde_json::Value| v.as_str().unwrap_or("").to_uppercase();
+
+    let runs = check_runs.as_array().into_iter().flatten().map(|run| {
+        serde_json::json!({
+            "name": run["name"],
+            "status": upper(&run["status"]),
+            "conclusion": upper(&run["conclusion"]),
+        })
+    });
+    let contexts = statuses.as_array().into_iter().flatten().map(|status| {
+        serde_json::json!({
+            "context": status["context"],
+            "status": "",
+            "conclusion": upper(&status["state"]),
+        })
+    });
+
+    serde_json::Value::Array(runs.chain(contexts).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_reviewers() {
+        let reviewers = vec!["ann".to_string(), "acme/platform".to_string(), "bob".to_string()];
+        let (users, teams) = split_reviewers(&reviewers);
+        assert_eq!(users, vec!["ann", "bob"]);
+        assert_eq!(teams, vec!["platform"]);
+    }
+
+    #[test]
+    fn test_to_status_rollup() {
+        let check_runs = serde_json::json!([
+            {"status": "completed", "conclusion": "success"},
+            {"status": "in_progress", "conclusion": null}
+        ]);
+        let statuses = serde_json::json!([{"state": "error"}, {"state": "pending"}]);
+
+        let checks = parse_status_checks(&to_status_rollup(&check_runs, &statuses));
+        assert_eq!(checks.total, 4);
+        assert_eq!(checks.passed, 1);
+        assert_eq!(checks.failed, 1);
+        assert_eq!(checks.pending, 2);
+        assert_eq!(checks.status, "failure");
+    }
+
+    #[test]
+    fn test_pr_state_and_api_base() {
+        let pr = |state: &str, merged_at: Option<&str>| serde_json::json!({"state": state, "merged_at": merged_at});
+        assert_eq!(pr_state(&pr("open", None)), "open");
+        assert_eq!(
+            pr_state(&pr("closed", Some("2024-01-01T00:00:00Z"))),
+            "merged"
+        );
+        assert_eq!(pr_state(&pr("closed", None)), "closed");
+
+        let remote = RemoteRepo {
+            host: "github.com".to_string(),
+            owner: "team".to_string(),
+            name: "repo".to_string(),
+        };
+        let client = GitHubApi::new(&remote, "token".to_string(), None);
+        assert_eq!(
+            client.url("/pulls"),
+            "https://api.github.com/repos/team/repo/pulls"
+        );
+        let client = GitHubApi::new(
+            &remote,
+            "token".to_string(),
+            Some("https://ghe.example.com/"),
+        );
+        assert_eq!(
+            client.url("/pulls"),
+            "https://ghe.example.com/api/v3/repos/team/repo/pulls"
+        );
+        assert_eq!(client.graphql_url, "https://ghe.example.com/api/graphql");
+    }
+}
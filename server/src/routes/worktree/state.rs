@@ -0,0 +1,148 @@
+//! Worktree state tracking.
+//!
+//! Cards show whether a bead's worktree has uncommitted changes and how far
+//! its branch is ahead of or behind main. Rather than each card polling
+//! `/api/git/worktree-status`, a server task checks the worktrees of the
+//! projects with open event streams every few seconds and publishes changes
+//! in-process, which the streams send as `worktree-state` events.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use super::{check_worktree_dirty, get_ahead_behind_worktree, get_worktree_entries};
+use crate::routes::watch::watched_projects;
+use crate::shutdown;
+
+/// How often worktrees are checked.
+const TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Capacity of the in-process worktree state channel.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// A worktree's state, as sent to SSE clients when it changes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorktreeStateEvent {
+    pub bead_id: String,
+    /// Whether there are uncommitted changes
+    pub dirty: bool,
+    /// Commits ahead of main
+    pub ahead: i32,
+    /// Commits behind main
+    pub behind: i32,
+}
+
+/// A worktree state change published for a repository.
+#[derive(Clone)]
+pub struct PublishedWorktreeState {
+    pub repo_path: PathBuf,
+    pub event: WorktreeStateEvent,
+}
+
+/// Last seen state of each worktree, by repository path and bead ID.
+type States = HashMap<(PathBuf, String), WorktreeStateEvent>;
+
+fn channel() -> &'static broadcast::Sender<PublishedWorktreeState> {
+    static CHANNEL: OnceLock<broadcast::Sender<PublishedWorktreeState>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}
+
+/// Subscribes to worktree state changes found by this server.
+pub fn subscribe() -> broadcast::Receiver<PublishedWorktreeState> {
+    channel().subscribe()
+}
+
+/// Records a worktree's state, returning whether it changed. The first
+/// state seen for a worktree isn't a change: clients load it with the board.
+fn record(states: &mut States, repo_path: &Path, state: WorktreeStateEvent) -> bool {
+    let key = (repo_path.to_path_buf(), state.bead_id.clone());
+    match states.insert(key, state.clone()) {
+        Some(previous) => previous != state,
+        None => false,
+    }
+}
+
+/// Checks the bead worktrees of watched projects, publishing state changes,
+/// until the server shuts down.
+pub async fn run_worktree_state_tracker() {
+    let mut states = States::new();
+    let mut ticker = tokio::time::interval(TICK_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = shutdown::signalled() => break,
+        }
+
+        let projects = watched_projects();
+        // Forget projects no one is watching any more
+        states.retain(|(path, _), _| projects.contains(path));
+
+        for repo_path in projects {
+            let repo = repo_path.to_string_lossy().to_string();
+            let worktrees = match get_worktree_entries(&repo).await {
+                Ok(worktrees) => worktrees,
+                // Not every watched project is a git repository
+                Err(e) => {
+                    debug!("Skipping worktree states of {}: {}", repo, e);
+                    continue;
+                }
+            };
+            let worktrees: Vec<_> = worktrees
+                .into_iter()
+                .filter(|w| w.bead_id.is_some() && !w.prunable)
+                .collect();
+
+            // Forget worktrees that have been removed
+            states.retain(|(path, bead_id), _| {
+                path != &repo_path || worktrees.iter().any(|w| w.bead_id.as_ref() == Some(bead_id))
+            });
+
+            for worktree in worktrees {
+                let Some(bead_id) = worktree.bead_id else {
+                    continue;
+                };
+                let (ahead, behind) = get_ahead_behind_worktree(&repo, &worktree.branch).await;
+                let dirty = check_worktree_dirty(&worktree.path).await;
+                let state = WorktreeStateEvent {
+                    bead_id,
+                    dirty,
+                    ahead,
+                    behind,
+                };
+                if record(&mut states, &repo_path, state.clone()) {
+                    let _ = channel().send(PublishedWorktreeState {
+                        repo_path: repo_path.clone(),
+                        event: state,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut states = States::new();
+        let repo = Path::new("/repo");
+        let state = |dirty: bool, ahead: i32| WorktreeStateEvent {
+            bead_id: "bd-1".to_string(),
+            dirty,
+            ahead,
+            behind: 0,
+        };
+
+        assert!(!record(&mut states, repo, state(false, 0)));
+        assert!(!record(&mut states, repo, state(false, 0)));
+        assert!(record(&mut states, repo, state(true, 0)));
+        assert!(record(&mut states, repo, state(true, 1)));
+        assert!(!record(&mut states, Path::new("/other"), state(false, 0)));
+    }
+}
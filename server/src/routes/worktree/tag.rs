@@ -0,0 +1,311 @@
+//! Tagging releases, optionally for a completed epic.
+//!
+//! Creates an annotated tag, pushes it, and can publish a release on the
+//! hosting provider. When an epic is given, it must be closed, and the
+//! tag message and release notes list the closed beads under it.
+
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::process::Command;
+
+use super::pr::PrProvider;
+use crate::error::AppError;
+use crate::git_backend;
+use crate::logging::LogCommand;
+use crate::routes::projects::AppState;
+use crate::routes::beads::{link_bead_relationships, parse_beads, resolve_issues_path, Bead};
+
+/// Request body for creating a tag.
+#[derive(Deserialize)]
+pub struct TagRequest {
+    /// Path to the git repository.
+    pub repo_path: String,
+    /// Tag name, e.g. "v1.4.0".
+    pub tag: String,
+    /// Revision to tag (default: HEAD).
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Tag message; defaults to the release notes, or the tag name.
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Closed epic whose closed beads make up the release notes.
+    #[serde(default)]
+    pub epic_id: Option<String>,
+    /// Push the tag to origin.
+    #[serde(default = "default_true")]
+    pub push: bool,
+    /// Publish a release for the tag (requires `push`).
+    #[serde(default)]
+    pub release: bool,
+    /// Release title (default: the tag name).
+    #[serde(default)]
+    pub release_name: Option<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Response body for the tag endpoint; also the `details` of the error
+/// when a step after creating the tag fails.
+#[derive(Serialize)]
+pub struct TagResponse {
+    /// Whether every requested step succeeded.
+    pub success: bool,
+    /// Whether the tag was created locally.
+    pub created: bool,
+    /// Whether the tag was pushed to origin.
+    pub pushed: bool,
+    /// ID of the tagged commit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Notes generated from the epic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// URL of the published release.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_url: Option<String>,
+}
+
+impl TagResponse {
+    /// The error for a failed step, with what was done so far.
+    fn failed(mut self, error: AppError) -> AppError {
+        self.success = false;
+        error.with_details(serde_json::to_value(&self).unwrap_or_default())
+    }
+}
+
+/// Create an annotated tag, push it, and optionally publish a release.
+///
+/// # Endpoint
+///
+/// `POST /api/git/tag`
+///
+/// # Request Body
+///
+/// ```json
+/// {
+///   "repo_path": "/path/to/repo",
+///   "tag": "v1.4.0",
+///   "epic_id": "BD-010",
+///   "push": true,
+///   "release": true
+/// }
+/// ```
+///
+/// # Response
+///
+/// Reports which steps completed. A failure after the tag was created
+/// leaves the tag in place, and the error's `details` say how far it got.
+pub async fn create_tag(
+    State(db): State<AppState>,
+    Json(request): Json<TagRequest>,
+) -> Result<Json<TagResponse>, AppError> {
+    let repo_path = Path::new(&request.repo_path);
+
+    // Validate repository path exists
+    if !repo_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "Repository path does not exist: {}",
+            request.repo_path
+        )));
+    }
+    let tag = request.tag.trim().to_string();
+    if !git2::Reference::is_valid_name(&format!("refs/tags/{}", tag)) {
+        return Err(AppError::BadRequest(format!("Invalid tag name: '{}'", tag)));
+    }
+    if request.release && !request.push {
+        return Err(AppError::BadRequest(
+            "Publishing a release requires pushing the tag".to_string(),
+        ));
+    }
+
+    // Find out whether a release can be published before tagging
+    let provider = if request.release {
+        let provider = PrProvider::detect(&db, &request.repo_path)
+            .await
+            .map_err(AppError::Internal)?;
+        if matches!(provider, PrProvider::Bitbucket(_)) {
+            return Err(AppError::NotImplemented(
+                "Bitbucket does not support releases".to_string(),
+            ));
+        }
+        Some(provider)
+    } else {
+        None
+    };
+
+    let notes = match &request.epic_id {
+        Some(epic_id) => Some(epic_notes(repo_path, epic_id)?),
+        None => None,
+    };
+    let message = request
+        .message
+        .clone()
+        .filter(|m| !m.trim().is_empty())
+        .or_else(|| notes.clone())
+        .unwrap_or_else(|| tag.clone());
+
+    let (repo, name, target) = (
+        repo_path.to_path_buf(),
+        tag.clone(),
+        request.target.clone().unwrap_or_else(|| "HEAD".to_string()),
+    );
+    let created = tokio::task::spawn_blocking(move || {
+        git_backend::create_tag(&repo, &name, &target, &message)
+    })
+    .await;
+    let commit = match created {
+        Ok(Ok(commit)) => commit,
+        Ok(Err(e)) if e.code() == git2::ErrorCode::Exists => {
+            return Err(AppError::Conflict(format!("Tag already exists: {}", tag)));
+        }
+        Ok(Err(e)) => {
+            return Err(AppError::Internal(format!(
+                "Failed to create tag: {}",
+                e.message()
+            )));
+        }
+        Err(e) => return Err(AppError::Internal(format!("Failed to create tag: {}", e))),
+    };
+
+    let mut response = TagResponse {
+        success: true,
+        created: true,
+        pushed: false,
+        commit: Some(commit),
+        notes,
+        release_url: None,
+    };
+
+    if request.push {
+        if let Err(e) = push_tag(repo_path, &tag).await {
+            return Err(response.failed(AppError::Internal(e)));
+        }
+        response.pushed = true;
+    }
+
+    if let Some(provider) = provider {
+        let name = request.release_name.clone().unwrap_or_else(|| tag.clone());
+        let notes = response.notes.clone().unwrap_or_default();
+        match provider.create_release(&tag, &name, &notes).await {
+            Ok(url) => response.release_url = Some(url),
+            Err(e) => {
+                let error = AppError::Upstream(format!("Failed to publish release: {}", e));
+                return Err(response.failed(error));
+            }
+        }
+    }
+
+    Ok(Json(response))
+}
+
+/// Push a tag to origin.
+async fn push_tag(repo_path: &Path, tag: &str) -> Result<(), String> {
+    let output = Command::new("git")
+        .args(["push", "origin", &format!("refs/tags/{}", tag)])
+        .current_dir(repo_path)
+        .logged()
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git push: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Failed to push tag: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+/// Reads the project's beads and builds release notes for a closed epic.
+fn epic_notes(repo_path: &Path, epic_id: &str) -> Result<String, AppError> {
+    let contents = std::fs::read_to_string(resolve_issues_path(repo_path))
+        .map_err(|e| AppError::Internal(format!("Failed to read beads: {}", e)))?;
+    let mut beads = parse_beads(&contents);
+    link_bead_relationships(&mut beads);
+
+    let epic = beads
+        .iter()
+        .find(|b| b.id == epic_id)
+        .ok_or_else(|| AppError::NotFound(format!("Bead with id '{}' not found", epic_id)))?;
+    if epic.status != "closed" {
+        return Err(AppError::Conflict(format!("Epic {} is not closed", epic_id)));
+    }
+    Ok(release_notes(epic, &beads))
+}
+
+/// Lists the closed beads under an epic (at any depth), in hierarchy order.
+fn release_notes(epic: &Bead, beads: &[Bead]) -> String {
+    let by_id: HashMap<&str, &Bead> = beads.iter().map(|b| (b.id.as_str(), b)).collect();
+    let mut notes = format!("## {}\n", epic.title);
+
+    let mut stack: Vec<&str> = epic
+        .children
+        .iter()
+        .flatten()
+        .rev()
+        .map(String::as_str)
+        .collect();
+    let mut listed = 0;
+    while let Some(id) = stack.pop() {
+        let Some(bead) = by_id.get(id) else {
+            continue;
+        };
+        if bead.status == "closed" {
+            notes.push_str(&format!("\n- {} ({})", bead.title, bead.id));
+            listed += 1;
+        }
+        stack.extend(bead.children.iter().flatten().rev().map(String::as_str));
+    }
+
+    if listed == 0 {
+        notes.push_str("\nNo closed beads.");
+    }
+    notes.push('\n');
+    notes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn test_release_notes() {
+        let contents = [
+            r#"{"id":"BD-010","title":"Checkout","status":"closed","issue_type":"epic"}"#,
+            r#"{"id":"BD-010.1","title":"Cart page","status":"closed"}"#,
+            r#"{"id":"BD-010.2","title":"Payments","status":"closed"}"#,
+            r#"{"id":"BD-010.2.1","title":"Refunds","status":"closed"}"#,
+            r#"{"id":"BD-010.3","title":"Gift cards","status":"open"}"#,
+            r#"{"id":"BD-011","title":"Unrelated","status":"closed"}"#,
+        ]
+        .join("\n");
+        let mut beads = parse_beads(&contents);
+        link_bead_relationships(&mut beads);
+
+        let notes = release_notes(&beads[0], &beads);
+        assert_eq!(
+            notes,
+            "## Checkout\n\n- Cart page (BD-010.1)\n- Payments (BD-010.2)\n- Refunds (BD-010.2.1)\n"
+        );
+    }
+
+    #[test]
+    fn test_epic_notes_requires_closed_epic() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".beads")).unwrap();
+        std::fs::write(
+            dir.path().join(".beads").join("issues.jsonl"),
+            r#"{"id":"BD-010","title":"Checkout","status":"open","issue_type":"epic"}"#,
+        )
+        .unwrap();
+
+        let error = epic_notes(dir.path(), "BD-010").unwrap_err();
+        assert_eq!(error.status(), StatusCode::CONFLICT);
+        let error = epic_notes(dir.path(), "BD-999").unwrap_err();
+        assert_eq!(error.status(), StatusCode::NOT_FOUND);
+    }
+}
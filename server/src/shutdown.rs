@@ -0,0 +1,55 @@
+//! Graceful shutdown on Ctrl-C (SIGINT) or SIGTERM.
+//!
+//! [`listen`] waits for the signal and flips a process-wide flag. The
+//! server stops accepting connections and lets in-flight requests finish,
+//! background loops and file watchers wait on [`signalled`] and stop at the
+//! end of their current pass, and `main` then closes the database. A second
+//! signal exits immediately.
+
+use std::sync::OnceLock;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+fn channel() -> &'static watch::Sender<bool> {
+    static SHUTDOWN: OnceLock<watch::Sender<bool>> = OnceLock::new();
+    SHUTDOWN.get_or_init(|| watch::channel(false).0)
+}
+
+/// Starts shutting down.
+pub fn trigger() {
+    channel().send_replace(true);
+}
+
+/// Completes once shutdown has started.
+pub async fn signalled() {
+    let mut rx = channel().subscribe();
+    // The sender lives in a static, so this only fails if it never fires
+    let _ = rx.wait_for(|&stopping| stopping).await;
+}
+
+/// Waits for SIGINT or SIGTERM and starts shutting down.
+pub async fn listen() {
+    signal().await;
+    info!("Shutting down; press Ctrl-C again to exit immediately");
+    trigger();
+
+    signal().await;
+    warn!("Exiting without waiting for in-flight work");
+    std::process::exit(130);
+}
+
+#[cfg(unix)]
+async fn signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
@@ -0,0 +1,125 @@
+//! HTTPS for servers reachable from other machines.
+//!
+//! Either a certificate and key (PEM) are configured, or a self-signed
+//! certificate is generated on first use and kept in the app data directory
+//! next to the database. Browsers warn about a self-signed certificate until
+//! it is trusted; delete the files to generate a new one (e.g. after
+//! changing `--host`).
+
+use axum_server::tls_rustls::RustlsConfig;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Where the server's certificate comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TlsSettings {
+    /// PEM certificate chain and private key files
+    Files { cert: PathBuf, key: PathBuf },
+    /// A generated self-signed certificate
+    SelfSigned,
+}
+
+/// Builds the rustls configuration for the server.
+///
+/// `host` is the address the server binds to; a self-signed certificate is
+/// also valid for it unless it is a wildcard address.
+pub async fn rustls_config(settings: &TlsSettings, host: &str) -> Result<RustlsConfig, String> {
+    // reqwest and axum-server share rustls; pick its crypto provider once
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let (cert, key) = match settings {
+        TlsSettings::Files { cert, key } => (cert.clone(), key.clone()),
+        TlsSettings::SelfSigned => {
+            let dir = directories::ProjectDirs::from("com", "beads", "kanban-ui")
+                .ok_or("Could not determine the app data directory")?
+                .data_dir()
+                .join("tls");
+            self_signed(&dir, host)?
+        }
+    };
+
+    RustlsConfig::from_pem_file(&cert, &key).await.map_err(|e| {
+        format!(
+            "Failed to load TLS certificate {} and key {}: {}",
+            cert.display(),
+            key.display(),
+            e
+        )
+    })
+}
+
+/// Returns the self-signed certificate and key in `dir`, generating them
+/// if they do not exist yet.
+fn self_signed(dir: &Path, host: &str) -> Result<(PathBuf, PathBuf), String> {
+    let (cert_path, key_path) = (dir.join("cert.pem"), dir.join("key.pem"));
+    if cert_path.exists() && key_path.exists() {
+        return Ok((cert_path, key_path));
+    }
+
+    let mut names = vec![
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+        "::1".to_string(),
+    ];
+    if !matches!(host, "0.0.0.0" | "::") && !names.iter().any(|n| n == host) {
+        names.push(host.to_string());
+    }
+    let generated = rcgen::generate_simple_self_signed(names.clone())
+        .map_err(|e| format!("Failed to generate a self-signed certificate: {}", e))?;
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    std::fs::write(&cert_path, generated.cert.pem())
+        .map_err(|e| format!("Failed to write {}: {}", cert_path.display(), e))?;
+    write_private(&key_path, &generated.key_pair.serialize_pem())?;
+    info!(
+        "Generated a self-signed certificate for {} in {}",
+        names.join(", "),
+        dir.display()
+    );
+    Ok((cert_path, key_path))
+}
+
+/// Writes a file readable only by the current user.
+fn write_private(path: &Path, contents: &str) -> Result<(), String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_self_signed() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls_dir = dir.path().join("tls");
+
+        let (cert, key) = self_signed(&tls_dir, "192.168.1.20").unwrap();
+        let pem = std::fs::read_to_string(&cert).unwrap();
+        assert!(pem.starts_with("-----BEGIN CERTIFICATE-----"));
+
+        // Reused rather than regenerated
+        assert_eq!(self_signed(&tls_dir, "0.0.0.0").unwrap(), (cert.clone(), key.clone()));
+        assert_eq!(std::fs::read_to_string(&cert).unwrap(), pem);
+
+        let settings = TlsSettings::Files { cert, key };
+        assert!(rustls_config(&settings, "0.0.0.0").await.is_ok());
+
+        let missing = TlsSettings::Files {
+            cert: dir.path().join("missing.pem"),
+            key: dir.path().join("missing-key.pem"),
+        };
+        assert!(rustls_config(&missing, "0.0.0.0").await.is_err());
+    }
+}
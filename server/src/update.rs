@@ -0,0 +1,328 @@
+//! Self-update from GitHub Releases.
+//!
+//! Most installs are a standalone binary (directly, or fetched by the npm
+//! package), which never hears about fixes. `beads-server --update`
+//! downloads the latest release's binary for this platform and replaces the
+//! running one, and `GET /api/v1/version` reports whether a newer release
+//! is out so the UI can say so. The download is checked against the
+//! release's `SHA256SUMS` before anything is replaced.
+
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Version of this build. Release builds take it from the tag through
+/// `BEADS_VERSION`, since the crate version isn't bumped on release.
+pub const VERSION: &str = match option_env!("BEADS_VERSION") {
+    Some(version) => version,
+    None => env!("CARGO_PKG_VERSION"),
+};
+
+/// Repository whose releases carry the server binaries.
+const RELEASES_URL: &str = "https://api.github.com/repos/AvivK5498/beads-kanban-ui/releases/latest";
+
+/// Release asset listing the SHA-256 of each binary, in `sha256sum` format.
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+/// How long a release check is reused, to stay well inside GitHub's
+/// unauthenticated rate limit.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A published release.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// A file attached to a release.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+/// Name of the release asset for this platform, as built by the release
+/// workflow.
+fn asset_name() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "aarch64") => Some("beads-server-darwin-arm64"),
+        ("macos", "x86_64") => Some("beads-server-darwin-x64"),
+        ("linux", "x86_64") => Some("beads-server-linux-x64"),
+        ("windows", "x86_64") => Some("beads-server-win32-x64.exe"),
+        _ => None,
+    }
+}
+
+/// Parses `v1.2.3` or `1.2.3` (ignoring any pre-release suffix) for
+/// comparison.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+    let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+    Some((major, minor, patch))
+}
+
+/// Whether `latest` is a newer version than `current`.
+fn is_newer(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// Fetches the latest release.
+async fn fetch_latest_release() -> Result<Release, String> {
+    let response = reqwest::Client::new()
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "beads-kanban-ui")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach GitHub: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("GitHub API error ({}) checking for releases", status));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid release from GitHub: {}", e))
+}
+
+/// Finds the checksum listed for `name` in a `sha256sum` output.
+fn expected_checksum(sums: &str, name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (hash, file) = line.trim().split_once(char::is_whitespace)?;
+        // Binary mode marks the name with a leading '*'
+        let file = file.trim_start().trim_start_matches('*');
+        (file == name).then(|| hash.to_lowercase())
+    })
+}
+
+/// Checks a download against its expected SHA-256.
+fn verify_checksum(binary: &[u8], expected: &str) -> Result<(), String> {
+    let actual: String = Sha256::digest(binary)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "Checksum mismatch: expected {}, downloaded {}",
+            expected, actual
+        ))
+    }
+}
+
+/// Downloads a release asset.
+async fn download(asset: &ReleaseAsset) -> Result<Vec<u8>, String> {
+    let response = reqwest::Client::new()
+        .get(&asset.browser_download_url)
+        .header("User-Agent", "beads-kanban-ui")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download {}: {}", asset.name, e))?;
+    response
+        .bytes()
+        .await
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| format!("Failed to download {}: {}", asset.name, e))
+}
+
+/// Returns the latest release, checking GitHub at most once per
+/// [`CHECK_INTERVAL`].
+async fn latest_release() -> Result<Release, String> {
+    static CHECKED: Mutex<Option<(Instant, Release)>> = Mutex::new(None);
+    if let Some((at, release)) = CHECKED.lock().unwrap().as_ref() {
+        if at.elapsed() < CHECK_INTERVAL {
+            return Ok(release.clone());
+        }
+    }
+    let release = fetch_latest_release().await?;
+    *CHECKED.lock().unwrap() = Some((Instant::now(), release.clone()));
+    Ok(release)
+}
+
+/// Response for the version endpoint.
+#[derive(Debug, Serialize)]
+pub struct VersionResponse {
+    pub version: &'static str,
+    /// Version of the latest release, if the check succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest: Option<String>,
+    pub update_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_url: Option<String>,
+    /// Why the latest release is unknown
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// GET /api/v1/version
+///
+/// Returns the running version and whether a newer release is available.
+pub async fn version() -> Json<VersionResponse> {
+    let response = match latest_release().await {
+        Ok(release) => VersionResponse {
+            version: VERSION,
+            update_available: is_newer(&release.tag_name, VERSION),
+            latest: Some(release.tag_name.trim_start_matches('v').to_string()),
+            release_url: Some(release.html_url),
+            error: None,
+        },
+        Err(e) => VersionResponse {
+            version: VERSION,
+            latest: None,
+            update_available: false,
+            release_url: None,
+            error: Some(e),
+        },
+    };
+    Json(response)
+}
+
+/// Replaces this executable with the latest release, if it is newer.
+///
+/// Returns a message for the user.
+pub async fn run() -> Result<String, String> {
+    let release = fetch_latest_release().await?;
+    if !is_newer(&release.tag_name, VERSION) {
+        return Ok(format!("beads-server {} is up to date", VERSION));
+    }
+    let name = asset_name().ok_or_else(|| {
+        format!(
+            "No release binary for {}/{}; build from source instead",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        )
+    })?;
+    let find_asset = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| format!("Release {} has no {}", release.tag_name, name))
+    };
+    let asset = find_asset(name)?;
+    // Without checksums there is nothing to verify the binary against
+    let checksums = find_asset(CHECKSUMS_ASSET)?;
+
+    let sums = String::from_utf8(download(checksums).await?)
+        .map_err(|_| format!("{} is not text", CHECKSUMS_ASSET))?;
+    let expected = expected_checksum(&sums, name)
+        .ok_or_else(|| format!("{} does not list {}", CHECKSUMS_ASSET, name))?;
+
+    println!("Downloading {} from {}", release.tag_name, asset.browser_download_url);
+    let binary = download(asset).await?;
+    verify_checksum(&binary, &expected).map_err(|e| format!("Not installing {}: {}", name, e))?;
+
+    let current = std::env::current_exe()
+        .and_then(|path| path.canonicalize())
+        .map_err(|e| format!("Failed to locate the running binary: {}", e))?;
+    tokio::task::spawn_blocking(move || replace_binary(&current, &binary))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    Ok(format!(
+        "Updated beads-server {} -> {}",
+        VERSION,
+        release.tag_name.trim_start_matches('v')
+    ))
+}
+
+/// Swaps `binary` in for the executable at `current`.
+///
+/// The new file is written next to the old one and renamed over it, so an
+/// interrupted update leaves the old binary in place.
+fn replace_binary(current: &Path, binary: &[u8]) -> Result<(), String> {
+    let staged = current.with_extension("new");
+    std::fs::write(&staged, binary)
+        .map_err(|e| format!("Failed to write {}: {}", staged.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", staged.display(), e))?;
+    }
+
+    // Windows can't replace a running executable, but it can rename one
+    #[cfg(windows)]
+    let old = {
+        let old = current.with_extension("old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(current, &old).map_err(|e| {
+            let _ = std::fs::remove_file(&staged);
+            format!("Failed to move {} aside: {}", current.display(), e)
+        })?;
+        old
+    };
+
+    std::fs::rename(&staged, current).map_err(|e| {
+        let _ = std::fs::remove_file(&staged);
+        // Put the running binary back rather than leave nothing installed
+        #[cfg(windows)]
+        let _ = std::fs::rename(&old, current);
+        format!("Failed to replace {}: {}", current.display(), e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.4.0", "0.3.1"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("v0.3.1", "0.3.1"));
+        assert!(!is_newer("v0.3.0", "0.3.1"));
+        assert!(!is_newer("v0.3.1-beta.1", "0.3.1"));
+        assert!(!is_newer("nightly", "0.3.1"));
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let sums = "\
+2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824  beads-server-linux-x64
+ABC123 *beads-server-win32-x64.exe
+";
+        let expected = expected_checksum(sums, "beads-server-linux-x64").unwrap();
+        assert!(verify_checksum(b"hello", &expected).is_ok());
+        assert!(verify_checksum(b"tampered", &expected).is_err());
+        assert_eq!(
+            expected_checksum(sums, "beads-server-win32-x64.exe").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(expected_checksum(sums, "beads-server-darwin-arm64"), None);
+        assert_eq!(expected_checksum(sums, "linux-x64"), None);
+    }
+
+    #[test]
+    fn test_replace_binary() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join("beads-server");
+        std::fs::write(&current, "old").unwrap();
+
+        replace_binary(&current, b"new").unwrap();
+        assert_eq!(std::fs::read(&current).unwrap(), b"new");
+        assert!(!current.with_extension("new").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&current).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o755);
+        }
+    }
+}
@@ -429,14 +429,10 @@ export function BeadDetail({
 
     try {
       const prBody = `Closes ${bead.id}\n\n${bead.description ?? ""}`;
-      const result = await api.git.createPR(projectPath, bead.id, bead.title, prBody);
+      await api.git.createPR(projectPath, bead.id, bead.title, prBody);
 
-      if (!result.success && result.error) {
-        setActionError(result.error);
-      } else {
-        // Refresh PR status to show the new PR
-        await refreshPRStatus();
-      }
+      // Refresh PR status to show the new PR
+      await refreshPRStatus();
     } catch (err) {
       const error = err instanceof Error ? err.message : "Failed to create PR";
       setActionError(error);
@@ -455,46 +451,42 @@ export function BeadDetail({
     setActionError(null);
 
     try {
-      const result = await api.git.mergePR(projectPath, bead.id, "squash");
+      await api.git.mergePR(projectPath, bead.id, "squash");
 
-      if (!result.success && result.error) {
-        setActionError(result.error);
-      } else {
-        // Refresh PR status to show merged state
-        await refreshPRStatus();
+      // Refresh PR status to show merged state
+      await refreshPRStatus();
 
-        // Rebase sibling branches after successful merge
-        setIsRebasingSiblings(true);
-        try {
-          const rebaseResult = await api.git.rebaseSiblings(projectPath, bead.id);
-          const successCount = rebaseResult.results.filter(r => r.success).length;
-          const failedResults = rebaseResult.results.filter(r => !r.success);
-
-          if (rebaseResult.results.length === 0) {
-            // No siblings to rebase - don't show toast
-          } else if (failedResults.length === 0) {
-            toast({
-              title: "Branches rebased",
-              description: `Successfully rebased ${successCount} sibling branch${successCount !== 1 ? "es" : ""} onto main.`,
-            });
-          } else {
-            toast({
-              variant: "destructive",
-              title: "Some rebases failed",
-              description: `${successCount} succeeded, ${failedResults.length} failed: ${failedResults.map(r => r.bead_id).join(", ")}`,
-            });
-          }
-        } catch (rebaseErr) {
-          // Log but don't block - rebase is a nice-to-have
-          console.error("Failed to rebase siblings:", rebaseErr);
+      // Rebase sibling branches after successful merge
+      setIsRebasingSiblings(true);
+      try {
+        const rebaseResult = await api.git.rebaseSiblings(projectPath, bead.id);
+        const successCount = rebaseResult.results.filter(r => r.success).length;
+        const failedResults = rebaseResult.results.filter(r => !r.success);
+
+        if (rebaseResult.results.length === 0) {
+          // No siblings to rebase - don't show toast
+        } else if (failedResults.length === 0) {
+          toast({
+            title: "Branches rebased",
+            description: `Successfully rebased ${successCount} sibling branch${successCount !== 1 ? "es" : ""} onto main.`,
+          });
+        } else {
           toast({
             variant: "destructive",
-            title: "Rebase failed",
-            description: rebaseErr instanceof Error ? rebaseErr.message : "Failed to rebase sibling branches",
+            title: "Some rebases failed",
+            description: `${successCount} succeeded, ${failedResults.length} failed: ${failedResults.map(r => r.bead_id).join(", ")}`,
           });
-        } finally {
-          setIsRebasingSiblings(false);
         }
+      } catch (rebaseErr) {
+        // Log but don't block - rebase is a nice-to-have
+        console.error("Failed to rebase siblings:", rebaseErr);
+        toast({
+          variant: "destructive",
+          title: "Rebase failed",
+          description: rebaseErr instanceof Error ? rebaseErr.message : "Failed to rebase sibling branches",
+        });
+      } finally {
+        setIsRebasingSiblings(false);
       }
     } catch (err) {
       const error = err instanceof Error ? err.message : "Failed to merge PR";
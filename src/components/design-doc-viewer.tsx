@@ -18,6 +18,7 @@ import {
   MorphingDialogDescription,
 } from "@/components/ui/morphing-dialog";
 import { ScrollArea } from "@/components/ui/scroll-area";
+import { authHeaders } from "@/lib/api";
 import { cn } from "@/lib/utils";
 import "highlight.js/styles/github-dark.css";
 
@@ -43,7 +44,8 @@ async function fetchDesignDoc(path: string, projectPath: string): Promise<string
   const encodedPath = encodeURIComponent(path);
   const encodedProjectPath = encodeURIComponent(projectPath);
   const response = await fetch(
-    `${API_BASE}/api/fs/read?path=${encodedPath}&project_path=${encodedProjectPath}`
+    `${API_BASE}/api/v1/fs/read?path=${encodedPath}&project_path=${encodedProjectPath}`,
+    { headers: authHeaders() }
   );
   if (!response.ok) {
     throw new Error('Failed to fetch design doc: ' + response.statusText);
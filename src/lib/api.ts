@@ -56,6 +56,61 @@ export interface BdCommandResult {
 export interface WatchEvent {
   path: string;
   type: string;
+  /** What changed; absent from servers that only watch beads */
+  kind?: 'beads' | 'memory' | 'agents' | 'git';
+  /** For bead changes: what differs from the previous event, unless too much did */
+  changes?: {
+    added: Bead[];
+    changed: Bead[];
+    removed: string[];
+  };
+}
+
+const AUTH_TOKEN_KEY = 'beads-auth-token';
+
+/**
+ * Access token for the API. The server opens the board with `?token=...`;
+ * the token is kept in localStorage and removed from the address bar.
+ */
+export function getAuthToken(): string | null {
+  if (typeof window === 'undefined') return null;
+  const url = new URL(window.location.href);
+  const fromUrl = url.searchParams.get('token');
+  if (fromUrl) {
+    localStorage.setItem(AUTH_TOKEN_KEY, fromUrl);
+    url.searchParams.delete('token');
+    window.history.replaceState(window.history.state, '', url.toString());
+  }
+  return localStorage.getItem(AUTH_TOKEN_KEY);
+}
+
+/**
+ * Shown when the server rejects the stored token
+ */
+const TOKEN_REJECTED_MESSAGE =
+  'The access token was not accepted. Open the board with the "Open ...?token=..." URL from the server log to sign in again.';
+
+/**
+ * Authorization header for requests made outside `fetchApi`
+ */
+export function authHeaders(): Record<string, string> {
+  const token = getAuthToken();
+  return token ? { Authorization: `Bearer ${token}` } : {};
+}
+
+/**
+ * An error response from the API
+ */
+export class ApiError extends Error {
+  constructor(
+    public status: number,
+    public code: string,
+    message: string,
+    public details?: unknown,
+  ) {
+    super(message);
+    this.name = 'ApiError';
+  }
 }
 
 /**
@@ -65,55 +120,239 @@ async function fetchApi<T>(path: string, options?: RequestInit): Promise<T> {
   const res = await fetch(`${API_BASE}${path}`, {
     ...options,
     headers: {
-      'Content-Type': 'application/json',
+      // The browser sets the multipart boundary for form data itself
+      ...(options?.body instanceof FormData ? {} : { 'Content-Type': 'application/json' }),
+      ...authHeaders(),
       ...options?.headers,
     },
   });
   if (!res.ok) {
-    throw new Error(`API error: ${res.status} ${res.statusText}`);
+    // Errors carry a JSON envelope: { code, message, details? }
+    const body = await res.json().catch(() => null);
+    // With OIDC login enabled the server says where to sign in
+    const loginUrl = res.status === 401 ? body?.details?.login_url : undefined;
+    if (loginUrl && typeof window !== 'undefined') {
+      const returnTo = window.location.pathname + window.location.search;
+      window.location.href = `${API_BASE}${loginUrl}?return_to=${encodeURIComponent(returnTo)}`;
+    }
+    // Otherwise the stored token was rejected, e.g. the server now has a
+    // different one; drop it so it isn't sent again
+    if (res.status === 401 && !loginUrl && typeof window !== 'undefined') {
+      localStorage.removeItem(AUTH_TOKEN_KEY);
+      throw new ApiError(401, body?.code ?? 'unauthorized', TOKEN_REJECTED_MESSAGE, body?.details);
+    }
+    throw new ApiError(
+      res.status,
+      body?.code ?? 'unknown',
+      body?.message ?? `API error: ${res.status} ${res.statusText}`,
+      body?.details,
+    );
   }
   return res.json();
 }
 
+/**
+ * User signed in with OIDC login
+ */
+export interface AuthUser {
+  subject: string;
+  name?: string;
+  email?: string;
+}
+
+/**
+ * Auth API
+ */
+export const auth = {
+  /** The signed-in user, or null when signed in with a token (or not at all) */
+  me: () => fetchApi<{ user: AuthUser | null }>('/api/v1/auth/me'),
+
+  logout: async () => {
+    await fetch(`${API_BASE}/auth/logout`, { method: 'POST' });
+  },
+};
+
+/**
+ * Deep health check: external tools, database, watchers, and projects
+ */
+export interface DeepHealthResponse {
+  status: 'ok' | 'degraded';
+  version: string;
+  tools: Array<{
+    name: 'git' | 'bd' | 'gh';
+    available: boolean;
+    version?: string;
+    error?: string;
+    /** How to fix it, for showing as a setup error */
+    hint?: string;
+  }>;
+  database: { ok: boolean; projects?: number; error?: string };
+  watchers: number;
+  projects: Array<{
+    id: string;
+    name: string;
+    path: string;
+    exists: boolean;
+    has_beads: boolean;
+    writable: boolean;
+    error?: string;
+  }>;
+}
+
+/**
+ * Health API
+ */
+export const health = {
+  deep: () => fetchApi<DeepHealthResponse>('/api/v1/health/deep'),
+};
+
+/**
+ * Running server version and the latest release
+ */
+export interface VersionResponse {
+  version: string;
+  latest?: string;
+  update_available: boolean;
+  release_url?: string;
+  /** Why the latest release couldn't be checked */
+  error?: string;
+}
+
+export const version = {
+  get: () => fetchApi<VersionResponse>('/api/v1/version'),
+};
+
+/**
+ * A project kept active by a multi-project launch, with bead counts
+ */
+export interface WorkspaceProject {
+  id: string;
+  name: string;
+  path: string;
+  open: number;
+  in_progress: number;
+  total: number;
+  error?: string;
+}
+
+/**
+ * Workspace API
+ */
+export const workspace = {
+  summary: () => fetchApi<{ projects: WorkspaceProject[] }>('/api/v1/workspace'),
+};
+
+/**
+ * UI preferences stored server-side for this browser
+ */
+export interface SessionPrefs {
+  theme?: 'light' | 'dark' | 'system';
+  lastProjectId?: string;
+  collapsedColumns: string[];
+}
+
+/**
+ * This browser's session, identified by a cookie
+ */
+export interface DeviceSession {
+  name?: string;
+  prefs: SessionPrefs;
+  createdAt: string;
+  lastSeen: string;
+}
+
+/**
+ * Session API
+ */
+export const session = {
+  get: () => fetchApi<DeviceSession>('/api/v1/session'),
+
+  rename: (name: string) => fetchApi<DeviceSession>('/api/v1/session', {
+    method: 'PUT',
+    body: JSON.stringify({ name }),
+  }),
+
+  getPrefs: () => fetchApi<SessionPrefs>('/api/v1/session/prefs'),
+
+  setPrefs: (prefs: SessionPrefs) => fetchApi<SessionPrefs>('/api/v1/session/prefs', {
+    method: 'PUT',
+    body: JSON.stringify(prefs),
+  }),
+};
+
+/**
+ * Server-wide settings
+ */
+export interface Settings {
+  /** Command that opens a terminal; `{path}` is replaced with the directory */
+  terminalCommand?: string;
+}
+
+/**
+ * Settings API
+ */
+export const settings = {
+  get: () => fetchApi<Settings>('/api/v1/settings'),
+
+  set: (data: Settings) => fetchApi<Settings>('/api/v1/settings', {
+    method: 'PUT',
+    body: JSON.stringify(data),
+  }),
+};
+
 /**
  * Projects API
  */
 export const projects = {
-  list: () => fetchApi<Project[]>('/api/projects'),
+  list: () => fetchApi<Project[]>('/api/v1/projects'),
 
-  create: (data: CreateProjectInput) => fetchApi<Project>('/api/projects', {
+  create: (data: CreateProjectInput) => fetchApi<Project>('/api/v1/projects', {
     method: 'POST',
     body: JSON.stringify(data),
   }),
 
-  update: (id: string, data: Partial<Project>) => fetchApi<Project>(`/api/projects/${id}`, {
+  update: (id: string, data: Partial<Project>) => fetchApi<Project>(`/api/v1/projects/${id}`, {
     method: 'PATCH',
     body: JSON.stringify(data),
   }),
 
-  delete: (id: string) => fetchApi<void>(`/api/projects/${id}`, { method: 'DELETE' }),
+  delete: (id: string) => fetchApi<void>(`/api/v1/projects/${id}`, { method: 'DELETE' }),
+
+  getPRDefaults: (id: string) => fetchApi<PRDefaults>(`/api/v1/projects/${id}/pr-defaults`),
+
+  setPRDefaults: (id: string, data: PRDefaults) => fetchApi<PRDefaults>(`/api/v1/projects/${id}/pr-defaults`, {
+    method: 'PUT',
+    body: JSON.stringify(data),
+  }),
+
+  getPRSettings: (id: string) => fetchApi<PRSettings>(`/api/v1/projects/${id}/pr-settings`),
+
+  setPRSettings: (id: string, data: PRSettingsInput) => fetchApi<PRSettings>(`/api/v1/projects/${id}/pr-settings`, {
+    method: 'PUT',
+    body: JSON.stringify(data),
+  }),
 };
 
 /**
  * Tags API
  */
 export const tags = {
-  list: () => fetchApi<Tag[]>('/api/tags'),
+  list: () => fetchApi<Tag[]>('/api/v1/tags'),
 
-  create: (data: CreateTagInput) => fetchApi<Tag>('/api/tags', {
+  create: (data: CreateTagInput) => fetchApi<Tag>('/api/v1/tags', {
     method: 'POST',
     body: JSON.stringify(data),
   }),
 
-  delete: (id: string) => fetchApi<void>(`/api/tags/${id}`, { method: 'DELETE' }),
+  delete: (id: string) => fetchApi<void>(`/api/v1/tags/${id}`, { method: 'DELETE' }),
 
-  addToProject: (projectId: string, tagId: string) => fetchApi<void>('/api/project-tags', {
+  addToProject: (projectId: string, tagId: string) => fetchApi<void>('/api/v1/project-tags', {
     method: 'POST',
     body: JSON.stringify({ projectId, tagId }),
   }),
 
   removeFromProject: (projectId: string, tagId: string) => fetchApi<void>(
-    `/api/project-tags/${projectId}/${tagId}`,
+    `/api/v1/project-tags/${projectId}/${tagId}`,
     { method: 'DELETE' }
   ),
 };
@@ -123,21 +362,31 @@ export const tags = {
  */
 export const beads = {
   read: (path: string) => fetchApi<{ beads: Bead[] }>(
-    `/api/beads?path=${encodeURIComponent(path)}`
+    `/api/v1/beads?path=${encodeURIComponent(path)}`
   ),
 
   addComment: (path: string, beadId: string, text: string, author: string) =>
-    fetchApi<Bead>('/api/beads/comment', {
+    fetchApi<Bead>('/api/v1/beads/comment', {
       method: 'POST',
       body: JSON.stringify({ path, bead_id: beadId, text, author }),
     }),
+
+  /** Create `.designs/{beadId}.md` from the project's template and link it */
+  createDesign: (path: string, beadId: string) =>
+    fetchApi<{ success: boolean; bead: Bead; path: string; created: boolean }>(
+      '/api/v1/designs',
+      {
+        method: 'POST',
+        body: JSON.stringify({ path, bead_id: beadId }),
+      }
+    ),
 };
 
 /**
  * BD CLI API
  */
 export const bd = {
-  command: (args: string[], cwd?: string) => fetchApi<BdCommandResult>('/api/bd/command', {
+  command: (args: string[], cwd?: string) => fetchApi<BdCommandResult>('/api/v1/bd/command', {
     method: 'POST',
     body: JSON.stringify({ args, cwd }),
   }),
@@ -151,6 +400,30 @@ export interface CreateWorktreeResponse {
   worktree_path: string;
   branch: string;
   already_existed: boolean;
+  /** Whether post-create hooks started running */
+  hooks_started: boolean;
+  /** `run` hooks the user must approve before any hooks run */
+  untrusted_hooks?: { hash: string; commands: string[] };
+}
+
+/**
+ * A step run after a worktree is created
+ */
+export type WorktreeHook = { run: string } | { copy: string } | { symlink: string };
+
+/**
+ * Progress of post-create worktree hooks
+ */
+export interface WorktreeHookRun {
+  status: 'running' | 'succeeded' | 'failed';
+  steps: {
+    hook: WorktreeHook;
+    status: 'pending' | 'running' | 'succeeded' | 'failed' | 'skipped';
+    output: string[];
+    error?: string;
+  }[];
+  started_at: string;
+  finished_at?: string;
 }
 
 /**
@@ -173,8 +446,37 @@ export interface ListWorktreesResponse {
 export interface CreatePRResponse {
   success: boolean;
   pr_number?: number;
-  pr_url?: string;
-  error?: string;
+  pr_url: string;
+  warnings?: string[];
+}
+
+/**
+ * Reviewers, labels, and assignees applied to a project's new PRs
+ */
+export interface PRDefaults {
+  reviewers: string[];
+  labels: string[];
+  assignees: string[];
+}
+
+/**
+ * Where a project's PRs are hosted, stored per user rather than in the repo
+ */
+export interface PRSettings {
+  /** Detected from the origin remote when unset */
+  provider?: 'github' | 'bitbucket' | 'gitea' | 'forgejo' | null;
+  /** Gitea or GitHub Enterprise server; unset uses the remote's host or github.com */
+  baseUrl?: string | null;
+  hasToken: boolean;
+}
+
+/**
+ * PR settings update; omit `token` to keep the stored one, send '' to clear it
+ */
+export interface PRSettingsInput {
+  provider?: PRSettings['provider'];
+  baseUrl?: string | null;
+  token?: string;
 }
 
 /**
@@ -183,7 +485,6 @@ export interface CreatePRResponse {
 export interface MergePRResponse {
   success: boolean;
   merged: boolean;
-  error?: string;
 }
 
 /**
@@ -193,12 +494,17 @@ export interface RebaseSiblingResult {
   bead_id: string;
   success: boolean;
   error?: string;
+  /** Uncommitted changes were stashed around the rebase */
+  stashed: boolean;
+  /** Restoring the stash conflicted; the changes are still in the stash */
+  stash_conflict: boolean;
 }
 
 /**
  * Rebase siblings response
  */
 export interface RebaseSiblingsResponse {
+  base: string;
   results: RebaseSiblingResult[];
 }
 
@@ -207,6 +513,51 @@ export interface RebaseSiblingsResponse {
  */
 export type MergeMethod = 'merge' | 'squash' | 'rebase';
 
+/**
+ * A step of completing a bead and how it went
+ */
+export interface CompleteBeadStep {
+  step: 'merge' | 'remove_worktree' | 'close_bead' | 'rebase_siblings';
+  status: 'done' | 'skipped' | 'failed' | 'pending';
+  detail?: string;
+}
+
+/**
+ * Complete bead response
+ */
+export interface CompleteBeadResponse {
+  success: boolean;
+  steps: CompleteBeadStep[];
+  rebase?: RebaseSiblingsResponse;
+}
+
+/**
+ * Tag creation response
+ */
+export interface TagResponse {
+  success: boolean;
+  created: boolean;
+  pushed: boolean;
+  commit?: string;
+  notes?: string;
+  release_url?: string;
+}
+
+/**
+ * Design doc history response
+ */
+export interface FileHistoryResponse {
+  path: string;
+  commits: Array<{
+    id: string;
+    summary: string;
+    author: string;
+    time?: string;
+  }>;
+  last_author?: string;
+  last_modified?: string;
+}
+
 /**
  * GitHub status response
  */
@@ -224,62 +575,174 @@ export const git = {
    * Get GitHub status for a repository
    */
   githubStatus: (repoPath: string) => fetchApi<GitHubStatusResponse>(
-    `/api/git/github-status?repo_path=${encodeURIComponent(repoPath)}`
+    `/api/v1/git/github-status?repo_path=${encodeURIComponent(repoPath)}`
   ),
   /**
    * Get branch status relative to main
    * @deprecated Use `worktreeStatus()` instead. Branch-based workflow is deprecated in favor of worktrees.
    */
   branchStatus: (path: string, branch: string) => fetchApi<BranchStatus>(
-    `/api/git/branch-status?path=${encodeURIComponent(path)}&branch=${encodeURIComponent(branch)}`
+    `/api/v1/git/branch-status?path=${encodeURIComponent(path)}&branch=${encodeURIComponent(branch)}`
   ),
 
+  /**
+   * Get the commit history of a design doc, newest first
+   */
+  fileHistory: (projectPath: string, path: string, limit?: number) => fetchApi<FileHistoryResponse>(
+    `/api/v1/git/file-history?project_path=${encodeURIComponent(projectPath)}&path=${encodeURIComponent(path)}${limit !== undefined ? `&limit=${limit}` : ''}`
+  ),
+
+  /**
+   * Get a design doc's content as of a commit from its history
+   */
+  designAtRevision: (projectPath: string, path: string, rev: string) => fetchApi<{
+    path: string;
+    rev: string;
+    content: string;
+  }>(
+    `/api/v1/designs/history?project_path=${encodeURIComponent(projectPath)}&path=${encodeURIComponent(path)}&rev=${encodeURIComponent(rev)}`
+  ),
+
+  fetch: (repoPath: string) =>
+    fetchApi<{ success: boolean; fetched_at?: string }>('/api/v1/git/fetch', {
+      method: 'POST',
+      body: JSON.stringify({ repo_path: repoPath }),
+    }),
+
   // Worktree endpoints
   worktreeStatus: (repoPath: string, beadId: string) => fetchApi<WorktreeStatus>(
-    `/api/git/worktree-status?repo_path=${encodeURIComponent(repoPath)}&bead_id=${encodeURIComponent(beadId)}`
+    `/api/v1/git/worktree-status?repo_path=${encodeURIComponent(repoPath)}&bead_id=${encodeURIComponent(beadId)}`
   ),
 
   createWorktree: (repoPath: string, beadId: string, baseBranch = 'main') =>
-    fetchApi<CreateWorktreeResponse>('/api/git/worktree', {
+    fetchApi<CreateWorktreeResponse>('/api/v1/git/worktree', {
       method: 'POST',
       body: JSON.stringify({ repo_path: repoPath, bead_id: beadId, base_branch: baseBranch }),
     }),
 
+  worktreeHooks: (repoPath: string, beadId: string) =>
+    fetchApi<WorktreeHookRun>(
+      `/api/v1/git/worktree-hooks?repo_path=${encodeURIComponent(repoPath)}&bead_id=${encodeURIComponent(beadId)}`
+    ),
+
+  trustWorktreeHooks: (repoPath: string, hash: string, beadId?: string) =>
+    fetchApi<{ success: boolean; hooks_started: boolean }>('/api/v1/git/worktree-hooks/trust', {
+      method: 'POST',
+      body: JSON.stringify({ repo_path: repoPath, hash, bead_id: beadId }),
+    }),
+
   deleteWorktree: (repoPath: string, beadId: string) =>
-    fetchApi<DeleteWorktreeResponse>('/api/git/worktree', {
+    fetchApi<DeleteWorktreeResponse>('/api/v1/git/worktree', {
       method: 'DELETE',
       body: JSON.stringify({ repo_path: repoPath, bead_id: beadId }),
     }),
 
+  unlockWorktree: (repoPath: string, beadId: string) =>
+    fetchApi<{ success: boolean }>('/api/v1/git/worktree/unlock', {
+      method: 'POST',
+      body: JSON.stringify({ repo_path: repoPath, bead_id: beadId }),
+    }),
+
   listWorktrees: (repoPath: string) => fetchApi<ListWorktreesResponse>(
-    `/api/git/worktrees?repo_path=${encodeURIComponent(repoPath)}`
+    `/api/v1/git/worktrees?repo_path=${encodeURIComponent(repoPath)}`
   ),
 
+  cherryPick: (repoPath: string, sourceBeadId: string, targetBeadId: string, commits: string[], keepConflicts = false) =>
+    fetchApi<{ success: boolean; commits: string[]; conflict: boolean }>('/api/v1/git/cherry-pick', {
+      method: 'POST',
+      body: JSON.stringify({
+        repo_path: repoPath,
+        source_bead_id: sourceBeadId,
+        target_bead_id: targetBeadId,
+        commits,
+        keep_conflicts: keepConflicts,
+      }),
+    }),
+
+  createTag: (
+    repoPath: string,
+    tag: string,
+    options: { epicId?: string; target?: string; message?: string; push?: boolean; release?: boolean; releaseName?: string } = {}
+  ) =>
+    fetchApi<TagResponse>('/api/v1/git/tag', {
+      method: 'POST',
+      body: JSON.stringify({
+        repo_path: repoPath,
+        tag,
+        epic_id: options.epicId,
+        target: options.target,
+        message: options.message,
+        push: options.push,
+        release: options.release,
+        release_name: options.releaseName,
+      }),
+    }),
+
   // PR endpoints
   prStatus: (repoPath: string, beadId: string) => fetchApi<PRStatus>(
-    `/api/git/pr-status?repo_path=${encodeURIComponent(repoPath)}&bead_id=${encodeURIComponent(beadId)}`
+    `/api/v1/git/pr-status?repo_path=${encodeURIComponent(repoPath)}&bead_id=${encodeURIComponent(beadId)}`
   ),
 
   prFiles: (repoPath: string, beadId: string) => fetchApi<PRFilesResponse>(
-    `/api/git/pr-files?repo_path=${encodeURIComponent(repoPath)}&bead_id=${encodeURIComponent(beadId)}`
+    `/api/v1/git/pr-files?repo_path=${encodeURIComponent(repoPath)}&bead_id=${encodeURIComponent(beadId)}`
   ),
 
-  createPR: (repoPath: string, beadId: string, title: string, body: string) =>
-    fetchApi<CreatePRResponse>('/api/git/create-pr', {
+  createPR: (
+    repoPath: string,
+    beadId: string,
+    title: string,
+    body: string,
+    draft = false,
+    metadata: Partial<PRDefaults> = {},
+  ) =>
+    fetchApi<CreatePRResponse>('/api/v1/git/create-pr', {
       method: 'POST',
-      body: JSON.stringify({ repo_path: repoPath, bead_id: beadId, title, body }),
+      body: JSON.stringify({ repo_path: repoPath, bead_id: beadId, title, body, draft, ...metadata }),
+    }),
+
+  generatePRBody: (repoPath: string, beadId: string) =>
+    fetchApi<{ title: string; body: string }>('/api/v1/git/generate-pr-body', {
+      method: 'POST',
+      body: JSON.stringify({ repo_path: repoPath, bead_id: beadId }),
+    }),
+
+  setAutoMerge: (repoPath: string, beadId: string, enabled: boolean, mergeMethod: MergeMethod = 'squash') =>
+    fetchApi<{ success: boolean; armed: boolean }>('/api/v1/git/auto-merge', {
+      method: 'POST',
+      body: JSON.stringify({ repo_path: repoPath, bead_id: beadId, enabled, merge_method: mergeMethod }),
+    }),
+
+  markPRReady: (repoPath: string, beadId: string) =>
+    fetchApi<{ success: boolean }>('/api/v1/git/pr-ready', {
+      method: 'POST',
+      body: JSON.stringify({ repo_path: repoPath, bead_id: beadId }),
     }),
 
   mergePR: (repoPath: string, beadId: string, mergeMethod: MergeMethod = 'squash') =>
-    fetchApi<MergePRResponse>('/api/git/merge-pr', {
+    fetchApi<MergePRResponse>('/api/v1/git/merge-pr', {
       method: 'POST',
       body: JSON.stringify({ repo_path: repoPath, bead_id: beadId, merge_method: mergeMethod }),
     }),
 
-  rebaseSiblings: (repoPath: string, excludeBeadId: string) =>
-    fetchApi<RebaseSiblingsResponse>('/api/git/rebase-siblings', {
+  completeBead: (repoPath: string, beadId: string, mergeMethod: MergeMethod = 'squash', baseBranch?: string) =>
+    fetchApi<CompleteBeadResponse>('/api/v1/git/complete-bead', {
       method: 'POST',
-      body: JSON.stringify({ repo_path: repoPath, exclude_bead_id: excludeBeadId }),
+      body: JSON.stringify({
+        repo_path: repoPath,
+        bead_id: beadId,
+        merge_method: mergeMethod,
+        base_branch: baseBranch,
+      }),
+    }),
+
+  rebaseSiblings: (repoPath: string, excludeBeadId: string, baseBranch?: string) =>
+    fetchApi<RebaseSiblingsResponse>('/api/v1/git/rebase-siblings', {
+      method: 'POST',
+      body: JSON.stringify({
+        repo_path: repoPath,
+        exclude_bead_id: excludeBeadId,
+        base_branch: baseBranch,
+      }),
     }),
 };
 
@@ -288,18 +751,109 @@ export const git = {
  */
 export const fs = {
   list: (path: string) => fetchApi<{ entries: FsEntry[] }>(
-    `/api/fs/list?path=${encodeURIComponent(path)}`
+    `/api/v1/fs/list?path=${encodeURIComponent(path)}`
   ),
 
   exists: (path: string) => fetchApi<{ exists: boolean }>(
-    `/api/fs/exists?path=${encodeURIComponent(path)}`
+    `/api/v1/fs/exists?path=${encodeURIComponent(path)}`
+  ),
+
+  /** Describe a project file: size, mtime, MIME type, binary or text, line count */
+  stat: (projectPath: string, path: string) => fetchApi<{
+    path: string;
+    isDirectory: boolean;
+    size: number;
+    modified?: string;
+    mime?: string;
+    binary: boolean;
+    lines?: number;
+  }>(
+    `/api/v1/fs/stat?path=${encodeURIComponent(path)}&project_path=${encodeURIComponent(projectPath)}`
+  ),
+
+  /**
+   * Read part of a log or transcript under `.claude/` or `.beads/logs/`.
+   * Resolves to `null` once `offset` is past the end of the file.
+   */
+  readRange: async (projectPath: string, path: string, offset = 0, limit = 256 * 1024) => {
+    const res = await fetch(
+      `${API_BASE}/api/v1/fs/read-range?path=${encodeURIComponent(path)}&project_path=${encodeURIComponent(projectPath)}&offset=${offset}&limit=${limit}`,
+      { headers: authHeaders() }
+    );
+    if (res.status === 416) return null;
+    if (!res.ok) throw new Error(`Failed to read ${path}: ${res.statusText}`);
+    // Content-Range: bytes start-end/size
+    const size = Number(res.headers.get('Content-Range')?.split('/')[1] ?? 0);
+    const bytes = await res.arrayBuffer();
+    return { text: new TextDecoder().decode(bytes), nextOffset: offset + bytes.byteLength, size };
+  },
+
+  /** Upload files into `.designs/assets` or `.beads/attachments[/{beadId}]` */
+  upload: (projectPath: string, dir: string, files: File[]) => {
+    const form = new FormData();
+    files.forEach((file) => form.append('file', file));
+    return fetchApi<{
+      success: boolean;
+      uploaded: { name: string; path: string; size: number }[];
+    }>(
+      `/api/v1/fs/upload?dir=${encodeURIComponent(dir)}&project_path=${encodeURIComponent(projectPath)}`,
+      { method: 'POST', body: form }
+    );
+  },
+
+  /** Rename a design doc within `.designs/`, relinking beads that point at it */
+  renameDesign: (projectPath: string, from: string, to: string) =>
+    fetchApi<{ success: boolean; path: string; updated: string[] }>('/api/v1/fs/rename', {
+      method: 'POST',
+      body: JSON.stringify({ project_path: projectPath, from, to }),
+    }),
+
+  /** Move a design doc to the trash, unlinking beads that point at it */
+  deleteDesign: (projectPath: string, path: string) =>
+    fetchApi<{ success: boolean; deleted: string; trash_id: string; updated: string[] }>('/api/v1/fs/delete', {
+      method: 'DELETE',
+      body: JSON.stringify({ project_path: projectPath, path }),
+    }),
+
+  /** Files deleted through the API, newest first; kept for 30 days */
+  listTrash: (projectPath: string) => fetchApi<{
+    entries: { id: string; original_path: string; deleted_at: string }[];
+  }>(`/api/v1/fs/trash?project_path=${encodeURIComponent(projectPath)}`),
+
+  /** Move a trashed file back to where it was */
+  restoreTrash: (projectPath: string, id: string) =>
+    fetchApi<{ success: boolean; path: string }>('/api/v1/fs/trash/restore', {
+      method: 'POST',
+      body: JSON.stringify({ project_path: projectPath, id }),
+    }),
+
+  /** Search `.designs/`, `.beads/`, and `.claude/` for lines containing `q` */
+  search: (projectPath: string, q: string, limit?: number) => fetchApi<{
+    results: { file: string; line: number; snippet: string }[];
+    truncated: boolean;
+  }>(
+    `/api/v1/fs/search?project_path=${encodeURIComponent(projectPath)}&q=${encodeURIComponent(q)}${limit ? `&limit=${limit}` : ''}`
   ),
 
   openExternal: (path: string, target: 'vscode' | 'cursor' | 'finder') =>
-    fetchApi<{ success: boolean }>('/api/fs/open-external', {
+    fetchApi<{ success: boolean }>('/api/v1/fs/open-external', {
       method: 'POST',
       body: JSON.stringify({ path, target }),
     }),
+
+  /** Open the configured terminal in a project or worktree directory */
+  openTerminal: (path: string) =>
+    fetchApi<{ success: boolean }>('/api/v1/fs/open-terminal', {
+      method: 'POST',
+      body: JSON.stringify({ path }),
+    }),
+
+  /** Show a path selected in Finder, Explorer, or the system file manager */
+  reveal: (path: string) =>
+    fetchApi<{ success: boolean }>('/api/v1/fs/reveal', {
+      method: 'POST',
+      body: JSON.stringify({ path }),
+    }),
 };
 
 /**
@@ -308,24 +862,24 @@ export const fs = {
 export const memory = {
   /** Fetch all memory entries and stats */
   list: (path: string) => fetchApi<MemoryResponse>(
-    `/api/memory?path=${encodeURIComponent(path)}`
+    `/api/v1/memory?path=${encodeURIComponent(path)}`
   ),
 
   /** Fetch memory stats only (lightweight) */
   stats: (path: string) => fetchApi<MemoryStats>(
-    `/api/memory/stats?path=${encodeURIComponent(path)}`
+    `/api/v1/memory/stats?path=${encodeURIComponent(path)}`
   ),
 
   /** Update an entry's content and/or tags */
   update: (path: string, key: string, content?: string, tags?: string[]) =>
-    fetchApi<{ success: boolean; entry: MemoryEntry }>('/api/memory', {
+    fetchApi<{ success: boolean; entry: MemoryEntry }>('/api/v1/memory', {
       method: 'PUT',
       body: JSON.stringify({ path, key, content, tags }),
     }),
 
   /** Delete or archive an entry */
   remove: (path: string, key: string, archive: boolean) =>
-    fetchApi<{ success: boolean; archived: boolean }>('/api/memory', {
+    fetchApi<{ success: boolean; archived: boolean }>('/api/v1/memory', {
       method: 'DELETE',
       body: JSON.stringify({ path, key, archive }),
     }),
@@ -337,26 +891,137 @@ export const memory = {
 export const agents = {
   /** List all agents for a project */
   list: (path: string) =>
-    fetchApi<Agent[]>(`/api/agents?path=${encodeURIComponent(path)}`),
+    fetchApi<Agent[]>(`/api/v1/agents?path=${encodeURIComponent(path)}`),
 
   /** Update an agent's model or tools configuration */
   update: (filename: string, path: string, data: { model: AgentModel; all_tools: boolean }) =>
-    fetchApi<Agent>(`/api/agents/${encodeURIComponent(filename)}`, {
+    fetchApi<Agent>(`/api/v1/agents/${encodeURIComponent(filename)}`, {
       method: 'PUT',
       body: JSON.stringify({ path, ...data }),
     }),
 };
 
+const EVENT_NAMESPACES = [
+  'beads',
+  'memory',
+  'agents',
+  'git',
+  'pr-status',
+  'worktree-state',
+  'notification',
+  'jobs',
+  'heartbeat',
+  'resync_required',
+] as const;
+
+/** Sent before the server closes a stream the client fell behind on */
+const RESYNC_EVENT = 'resync_required';
+
+/** Data of `heartbeat` events, sent every 30 seconds */
+export interface ServerStatus {
+  uptime_seconds: number;
+  /** Remaining GitHub API calls, once a PR status check has reported them */
+  rate_limit_remaining?: number;
+  watchers: { active: number; polling: number };
+  pending_jobs: number;
+}
+
+/** Namespaces of the events on `GET /api/v1/events` */
+export type EventNamespace = (typeof EVENT_NAMESPACES)[number];
+
 /**
- * File Watcher (Server-Sent Events)
+ * File Watcher (Server-Sent Events, or a WebSocket with
+ * NEXT_PUBLIC_EVENT_TRANSPORT=ws for proxies that buffer SSE)
  */
 export const watch = {
-  beads: (path: string, onEvent: (event: WatchEvent) => void) => {
-    const eventSource = new EventSource(
-      `${API_BASE}/api/watch/beads?path=${encodeURIComponent(path)}`
-    );
-    eventSource.onmessage = (e) => onEvent(JSON.parse(e.data));
-    eventSource.onerror = () => eventSource.close();
-    return () => eventSource.close();
+  /**
+   * Bead changes go to `onEvent`; memory, agent file and git changes to
+   * `onFileEvent`, if given
+   */
+  beads: (
+    path: string,
+    onEvent: (event: WatchEvent) => void,
+    onFileEvent?: (event: WatchEvent) => void
+  ): (() => void) => {
+    // Neither EventSource nor WebSocket can send headers, so the token goes in the query
+    const token = getAuthToken();
+    const query =
+      `?path=${encodeURIComponent(path)}` +
+      (token ? `&token=${encodeURIComponent(token)}` : '');
+
+    // After falling behind the stream is closed; reload the board
+    const resync = () => onEvent({ path, type: RESYNC_EVENT });
+
+    if (process.env.NEXT_PUBLIC_EVENT_TRANSPORT === 'ws') {
+      let socket: WebSocket;
+      const connect = () => {
+        socket = new WebSocket(`${API_BASE.replace(/^http/, 'ws')}/api/v1/ws${query}`);
+        socket.onmessage = (e) => {
+          const message = JSON.parse(e.data) as { type: string; data: unknown };
+          if (message.type === 'message') onEvent(message.data as WatchEvent);
+          else if (['memory', 'agents', 'git'].includes(message.type)) {
+            onFileEvent?.(message.data as WatchEvent);
+          } else if (message.type === RESYNC_EVENT) {
+            socket.close();
+            resync();
+            connect();
+          }
+        };
+        socket.onerror = () => socket.close();
+      };
+      connect();
+      return () => socket.close();
+    }
+
+    return watch.events(path, (namespace, data) => {
+      if (namespace === 'beads') onEvent(data as WatchEvent);
+      else if (['memory', 'agents', 'git'].includes(namespace)) {
+        onFileEvent?.(data as WatchEvent);
+      } else if (namespace === RESYNC_EVENT) resync();
+    });
   },
+
+  /** All of a project's events on one connection, by namespace */
+  events: (path: string, onEvent: (namespace: EventNamespace, data: unknown) => void) =>
+    openEventStream(`project=${encodeURIComponent(path)}`, onEvent),
+
+  /**
+   * Events of several registered projects (or 'all') on one connection;
+   * each event's data carries its `project_id`
+   */
+  projects: (
+    projectIds: string[] | 'all',
+    onEvent: (namespace: EventNamespace, data: { project_id: string }) => void
+  ) =>
+    openEventStream(
+      `projects=${encodeURIComponent(projectIds === 'all' ? 'all' : projectIds.join(','))}`,
+      (namespace, data) => onEvent(namespace, data as { project_id: string })
+    ),
 };
+
+function openEventStream(
+  params: string,
+  onEvent: (namespace: EventNamespace, data: unknown) => void
+): () => void {
+  const token = getAuthToken();
+  const query = `?${params}` + (token ? `&token=${encodeURIComponent(token)}` : '');
+
+  let eventSource: EventSource;
+  const connect = () => {
+    eventSource = new EventSource(`${API_BASE}/api/v1/events${query}`);
+    for (const namespace of EVENT_NAMESPACES) {
+      eventSource.addEventListener(namespace, (e) => {
+        onEvent(namespace, JSON.parse((e as MessageEvent).data));
+        // The server closes a stream that fell behind once it has said so;
+        // the caller reloads, and a new stream picks up from here
+        if (namespace === RESYNC_EVENT) {
+          eventSource.close();
+          connect();
+        }
+      });
+    }
+    eventSource.onerror = () => eventSource.close();
+  };
+  connect();
+  return () => eventSource.close();
+}
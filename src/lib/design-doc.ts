@@ -3,6 +3,8 @@
  * Shared functions for fetching and processing design documents
  */
 
+import { authHeaders } from '@/lib/api';
+
 const API_BASE = process.env.NEXT_PUBLIC_BACKEND_URL || 'http://localhost:3008';
 
 /**
@@ -12,7 +14,8 @@ export async function fetchDesignDoc(path: string, projectPath: string): Promise
   const encodedPath = encodeURIComponent(path);
   const encodedProjectPath = encodeURIComponent(projectPath);
   const response = await fetch(
-    `${API_BASE}/api/fs/read?path=${encodedPath}&project_path=${encodedProjectPath}`
+    `${API_BASE}/api/v1/fs/read?path=${encodedPath}&project_path=${encodedProjectPath}`,
+    { headers: authHeaders() }
   );
   if (!response.ok) {
     throw new Error('Failed to fetch design doc: ' + response.statusText);
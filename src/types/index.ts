@@ -102,6 +102,13 @@ export interface Bead {
   deps?: string[];            // Dependency IDs (blocking this task)
   blockers?: string[];        // COMPUTED: Tasks this blocks (derived from deps relationships)
   relates_to?: string[];      // Bead IDs with relates-to links (bidirectional "see also")
+  labels?: string[];          // Free-form labels stored in issues.jsonl
+  milestone?: string;         // ID of the assigned milestone (sprint)
+  recurrence?: string;        // Recurrence rule (RRULE subset, e.g. "FREQ=WEEKLY;BYDAY=MO")
+  recurrence_of?: string;     // ID of the previous occurrence of a recurring bead
+  due_date?: string;          // Date (YYYY-MM-DD) a recurring occurrence is scheduled for
+  previous_ids?: string[];    // IDs the bead had before being renamed
+  custom?: Record<string, unknown>; // Custom field values, keyed by field name (see .beads/fields.json)
   // Status mapping fields (set by beads-parser when raw status differs from column)
   _originalStatus?: string;   // The raw status from the backend before mapping
   _statusBadge?: StatusBadgeInfo; // Badge info if the bead was mapped to a different column
@@ -191,6 +198,14 @@ export interface WorktreeEntry {
   branch: string;
   /** Extracted bead ID (if matches bd-{ID} pattern) */
   bead_id?: string;
+  /** Whether HEAD is detached (branch is then the expected branch) */
+  detached: boolean;
+  /** Whether the worktree is locked, e.g. after a crash */
+  locked: boolean;
+  /** Reason recorded with the lock */
+  lock_reason?: string;
+  /** Whether the worktree directory is gone and git would prune it */
+  prunable: boolean;
 }
 
 // ============================================================================
@@ -242,8 +257,23 @@ export interface PRInfo {
   state: PRState;
   /** CI checks status */
   checks: PRChecks;
-  /** Whether the PR is mergeable */
+  /** Whether the PR is mergeable, including required checks and approvals */
   mergeable: boolean;
+  /** Whether the PR is a draft */
+  draft: boolean;
+  /** Base branch protection rules (absent when unprotected or unknown) */
+  protection?: BranchProtection;
+}
+
+/**
+ * Merge requirements from a base branch's protection rules
+ */
+export interface BranchProtection {
+  required_checks: string[];
+  /** Required checks that haven't passed yet */
+  missing_checks: string[];
+  required_approvals: number;
+  approvals: number;
 }
 
 /**
@@ -254,10 +284,20 @@ export interface PRStatus {
   has_remote: boolean;
   /** Whether the branch has been pushed */
   branch_pushed: boolean;
+  /** Commits ahead of main, from local git */
+  ahead: number;
+  /** Commits behind main, from local git */
+  behind: number;
+  /** PR provider queried, or "none" when PRs can't be queried */
+  provider: 'github' | 'gh' | 'bitbucket' | 'gitea' | 'none';
+  /** Why PRs can't be queried, when provider is "none" */
+  provider_reason?: string;
   /** PR information (null if no PR exists) */
   pr: PRInfo | null;
   /** Rate limit information */
   rate_limit: RateLimit;
+  /** Whether the PR is armed to merge once checks pass */
+  auto_merge: boolean;
 }
 
 // ============================================================================